@@ -56,7 +56,7 @@ async fn main() -> Result<()> {
                 "    Core: {:.1}°C ({:.1}°F) [from {}]",
                 core,
                 celsius_to_fahrenheit(core),
-                sel.core_sensor_name()
+                sel.core().map_or("?", |s| s.name())
             );
         }
         if let Some(surface) = virtual_temps.surface {
@@ -64,7 +64,7 @@ async fn main() -> Result<()> {
                 "    Surface: {:.1}°C ({:.1}°F) [from {}]",
                 surface,
                 celsius_to_fahrenheit(surface),
-                sel.surface_sensor_name()
+                sel.surface().map_or("?", |s| s.name())
             );
         }
         if let Some(ambient) = virtual_temps.ambient {
@@ -72,7 +72,7 @@ async fn main() -> Result<()> {
                 "    Ambient: {:.1}°C ({:.1}°F) [from {}]",
                 ambient,
                 celsius_to_fahrenheit(ambient),
-                sel.ambient_sensor_name()
+                sel.ambient().map_or("?", |s| s.name())
             );
         }
     });
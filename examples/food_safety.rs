@@ -13,8 +13,8 @@
 //!   cargo run --example food_safety -- --serial 1001192D
 
 use combustion_rust_ble::{
-    celsius_to_fahrenheit, DeviceManager, Error, FoodSafeConfig, FoodSafeMode,
-    FoodSafeState, IntegratedProduct, Result, Serving, SimplifiedProduct,
+    celsius_to_fahrenheit, DeviceManager, Error, FoodSafeConfig, FoodSafeMode, FoodSafeState,
+    IntegratedProduct, Result, Serving, SimplifiedProduct,
 };
 use std::io::Write;
 use std::time::Duration;
@@ -73,7 +73,10 @@ async fn main() -> Result<()> {
         1 => {
             println!("Using SIMPLIFIED mode: Chicken (Any Poultry)");
             (
-                FoodSafeConfig::simplified(SimplifiedProduct::AnyPoultry, Serving::ServedImmediately),
+                FoodSafeConfig::simplified(
+                    SimplifiedProduct::AnyPoultry,
+                    Serving::ServedImmediately,
+                ),
                 "Simplified - Any Poultry",
             )
         }
@@ -87,14 +90,14 @@ async fn main() -> Result<()> {
         _ => {
             println!("Using CUSTOM INTEGRATED mode");
             (
-                FoodSafeConfig::custom(
+                FoodSafeConfig::try_custom(
                     54.4, // ~130°F threshold
                     5.5,  // Z-value
                     70.0, // Reference temp
                     1.0,  // D-value
                     7.0,  // Target log reduction
                     Serving::ServedImmediately,
-                ),
+                )?,
                 "Custom Integrated",
             )
         }
@@ -102,10 +105,22 @@ async fn main() -> Result<()> {
 
     println!("\nConfiguration:");
     println!("  Mode: {:?}", config.mode);
-    println!("  Threshold: {:.1}°C ({:.1}°F)", config.threshold_temperature, celsius_to_fahrenheit(config.threshold_temperature));
+    println!(
+        "  Threshold: {:.1}°C ({:.1}°F)",
+        config.threshold_temperature,
+        celsius_to_fahrenheit(config.threshold_temperature)
+    );
     if config.mode == FoodSafeMode::Integrated {
-        println!("  Z-value: {:.1}°C ({:.1}°F)", config.z_value, celsius_to_fahrenheit(config.z_value));
-        println!("  Reference Temp: {:.1}°C ({:.1}°F)", config.reference_temperature, celsius_to_fahrenheit(config.reference_temperature));
+        println!(
+            "  Z-value: {:.1}°C ({:.1}°F)",
+            config.z_value,
+            celsius_to_fahrenheit(config.z_value)
+        );
+        println!(
+            "  Reference Temp: {:.1}°C ({:.1}°F)",
+            config.reference_temperature,
+            celsius_to_fahrenheit(config.reference_temperature)
+        );
         println!("  D-value at RT: {:.1}", config.d_value_at_reference);
         println!("  Target Log Reduction: {:.1}", config.target_log_reduction);
     }
@@ -128,9 +143,9 @@ async fn main() -> Result<()> {
     let probe = if let Some(ref serial) = target_serial {
         // Look for probe with matching serial number
         let probes = manager.probes();
-        let found = probes.iter().find(|(_, p)| {
-            p.serial_number_string().to_uppercase() == *serial
-        });
+        let found = probes
+            .iter()
+            .find(|(_, p)| p.serial_number_string().to_uppercase() == *serial);
         found
             .map(|(_, p)| p.clone())
             .ok_or_else(|| Error::ProbeNotFound {
@@ -151,7 +166,9 @@ async fn main() -> Result<()> {
 
     // Configure food safety with the chosen config
     println!("Configuring food safety ({})...", mode_name);
-    probe.configure_food_safe_with_config(config.clone()).await?;
+    probe
+        .configure_food_safe_with_config(config.clone())
+        .await?;
 
     println!("Food safety monitoring active!\n");
     println!("Insert probe into food and begin cooking.");
@@ -227,21 +244,20 @@ fn display_food_safety_status(
     println!("├─────────────────────────────────────────────────────────────┤");
 
     if let Some(core) = vt.core {
-        let above = if config.mode == FoodSafeMode::Integrated {
-            core >= config.threshold_temperature
-        } else {
-            core >= config.threshold_temperature
-        };
+        let above = core >= config.threshold_temperature;
         let status_char = if above { "▲" } else { "▼" };
         println!(
             "│  Core:    {:6.1}°C ({:6.1}°F) [{}] {}                     │",
             core,
             celsius_to_fahrenheit(core),
-            sel.core_sensor_name(),
+            sel.core().map_or("?", |s| s.name()),
             status_char
         );
     } else {
-        println!("│  Core:    --°C (--°F) [{}]                               │", sel.core_sensor_name());
+        println!(
+            "│  Core:    --°C (--°F) [{}]                               │",
+            sel.core().map_or("?", |s| s.name())
+        );
     }
 
     if let Some(surface) = vt.surface {
@@ -249,7 +265,7 @@ fn display_food_safety_status(
             "│  Surface: {:6.1}°C ({:6.1}°F) [{}]                         │",
             surface,
             celsius_to_fahrenheit(surface),
-            sel.surface_sensor_name()
+            sel.surface().map_or("?", |s| s.name())
         );
     }
 
@@ -258,7 +274,7 @@ fn display_food_safety_status(
             "│  Ambient: {:6.1}°C ({:6.1}°F) [{}]                         │",
             ambient,
             celsius_to_fahrenheit(ambient),
-            sel.ambient_sensor_name()
+            sel.ambient().map_or("?", |s| s.name())
         );
     }
     println!("└─────────────────────────────────────────────────────────────┘\n");
@@ -277,8 +293,12 @@ fn display_food_safety_status(
             FoodSafeState::NotSafe => "⏳",
             FoodSafeState::Safe => "✅",
             FoodSafeState::SafetyImpossible => "❌",
+            _ => "❓",
         };
-        println!("│  State: {} {:?}                                        │", state_icon, state);
+        println!(
+            "│  State: {} {:?}                                        │",
+            state_icon, state
+        );
 
         if config.mode == FoodSafeMode::Integrated {
             // Progress bar for integrated mode
@@ -290,14 +310,16 @@ fn display_food_safety_status(
 
             println!(
                 "│  Log Reduction: {:.2} / {:.1} ({:.1}%)                      │",
-                data.log_reduction, target_reduction, progress
+                data.log_reduction(),
+                target_reduction,
+                progress
             );
             println!("│  Progress: {}                          │", bar);
         }
 
         println!(
             "│  Time at Temp: {} seconds                                 │",
-            data.seconds_above_threshold
+            data.seconds_above_threshold()
         );
 
         // Safety messaging
@@ -333,7 +355,9 @@ fn display_food_safety_status(
                                 celsius_to_fahrenheit(diff)
                             );
                         } else {
-                            println!("│  Temperature reached! Waiting for safety confirmation...  │");
+                            println!(
+                                "│  Temperature reached! Waiting for safety confirmation...  │"
+                            );
                         }
                     } else if core < config.threshold_temperature {
                         println!(
@@ -344,6 +368,10 @@ fn display_food_safety_status(
                     }
                 }
             }
+            _ => {
+                println!("│                                                             │");
+                println!("│  ❓ UNKNOWN STATE - Reported by newer probe firmware         │");
+            }
         }
     } else {
         println!("│  Waiting for food safety data...                           │");
@@ -355,18 +383,36 @@ fn display_food_safety_status(
     println!("┌─────────────────────────────────────────────────────────────┐");
     println!("│ Configuration                                               │");
     println!("├─────────────────────────────────────────────────────────────┤");
-    println!("│  Mode: {:?}                                           │", config.mode);
+    println!(
+        "│  Mode: {:?}                                           │",
+        config.mode
+    );
     println!(
         "│  Threshold: {:.1}°C ({:.1}°F)                              │",
         config.threshold_temperature,
         celsius_to_fahrenheit(config.threshold_temperature)
     );
     if config.mode == FoodSafeMode::Integrated {
-        println!("│  Z-value: {:.1}°C ({:.1}°F)                                  │", config.z_value, celsius_to_fahrenheit(config.z_value));
-        println!("│  D-value @ {:.0}°C ({:.0}°F): {:.1}s                           │", config.reference_temperature, celsius_to_fahrenheit(config.reference_temperature), config.d_value_at_reference);
-        println!("│  Target: {:.1} log reduction                                │", config.target_log_reduction);
+        println!(
+            "│  Z-value: {:.1}°C ({:.1}°F)                                  │",
+            config.z_value,
+            celsius_to_fahrenheit(config.z_value)
+        );
+        println!(
+            "│  D-value @ {:.0}°C ({:.0}°F): {:.1}s                           │",
+            config.reference_temperature,
+            celsius_to_fahrenheit(config.reference_temperature),
+            config.d_value_at_reference
+        );
+        println!(
+            "│  Target: {:.1} log reduction                                │",
+            config.target_log_reduction
+        );
     }
-    println!("│  Serving: {:?}                                   │", config.serving);
+    println!(
+        "│  Serving: {:?}                                   │",
+        config.serving
+    );
     println!("└─────────────────────────────────────────────────────────────┘\n");
 
     println!("Press Ctrl+C to exit");
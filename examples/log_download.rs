@@ -1,6 +1,9 @@
 //! Download temperature logs from a probe
 //!
 //! Run with: cargo run --example log_download
+//!
+//! To also chart the log to a PNG, enable the `charts` feature and name the
+//! file: cargo run --example log_download --features charts -- chart log.png
 
 use combustion_rust_ble::{DeviceManager, Error, Result};
 use std::io::Write;
@@ -140,6 +143,17 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Chart the log
+    #[cfg(feature = "charts")]
+    if let Some(path) = chart_path() {
+        let chart = combustion_rust_ble::charts::SessionChart::new(&log)
+            .with_title(format!("Probe {}", probe.serial_number_string()));
+        match chart.save_png(&path) {
+            Ok(()) => println!("Chart saved to: {}", path),
+            Err(e) => println!("Failed to save chart: {}", e),
+        }
+    }
+
     probe.disconnect().await?;
     manager.shutdown().await?;
 
@@ -147,3 +161,13 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Get the chart file named on the command line as `chart <path>`.
+#[cfg(feature = "charts")]
+fn chart_path() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("chart") => Some(args.next().unwrap_or_else(|| "log.png".to_string())),
+        _ => None,
+    }
+}
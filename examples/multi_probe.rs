@@ -1,6 +1,9 @@
 //! Managing multiple probes simultaneously
 //!
 //! Run with: cargo run --example multi_probe
+//!
+//! Pass `--json` to print one newline-delimited JSON snapshot per probe
+//! update instead (needs `--features serde`).
 
 use combustion_rust_ble::{celsius_to_fahrenheit, DeviceManager, Probe, Result};
 use std::io::Write;
@@ -10,11 +13,31 @@ use std::time::Duration;
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
-    tracing_subscriber::fmt().with_env_filter("warn").init();
+    tracing_subscriber::fmt()
+        .with_env_filter("warn")
+        .with_writer(std::io::stderr)
+        .init();
+
+    let json = std::env::args().any(|arg| arg == "--json");
+    if json && !cfg!(feature = "serde") {
+        eprintln!("--json needs the serde feature: add --features serde");
+        return Ok(());
+    }
+
+    // In JSON mode stdout carries only records, so messages go to stderr
+    macro_rules! say {
+        ($($arg:tt)*) => {
+            if json {
+                eprintln!($($arg)*);
+            } else {
+                println!($($arg)*);
+            }
+        };
+    }
 
-    println!("Multi-Probe Manager");
-    println!("===================\n");
-    println!("Supports up to 8 probes simultaneously.\n");
+    say!("Multi-Probe Manager");
+    say!("===================\n");
+    say!("Supports up to 8 probes simultaneously.\n");
 
     let manager = DeviceManager::new().await?;
 
@@ -24,7 +47,7 @@ async fn main() -> Result<()> {
 
     let _handle = manager.on_probe_discovered(move |probe| {
         let count = probes_found_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
-        println!(
+        eprintln!(
             "Found probe #{}: {} (ID: {}, Color: {:?})",
             count,
             probe.serial_number_string(),
@@ -35,24 +58,24 @@ async fn main() -> Result<()> {
 
     manager.start_scanning().await?;
 
-    println!("Scanning for probes... (waiting 10 seconds)\n");
+    say!("Scanning for probes... (waiting 10 seconds)\n");
     tokio::time::sleep(Duration::from_secs(10)).await;
 
     let probes: Vec<Arc<Probe>> = manager.probes().values().cloned().collect();
 
     if probes.is_empty() {
-        println!("No probes found!");
+        say!("No probes found!");
         manager.shutdown().await?;
         return Ok(());
     }
 
-    println!("\nFound {} probes. Connecting to all...\n", probes.len());
+    say!("\nFound {} probes. Connecting to all...\n", probes.len());
 
     // Connect to all probes
     for probe in &probes {
         match probe.connect().await {
-            Ok(_) => println!("  ✓ Connected to {}", probe.serial_number_string()),
-            Err(e) => println!(
+            Ok(_) => say!("  ✓ Connected to {}", probe.serial_number_string()),
+            Err(e) => say!(
                 "  ✗ Failed to connect to {}: {}",
                 probe.serial_number_string(),
                 e
@@ -60,30 +83,70 @@ async fn main() -> Result<()> {
         }
     }
 
-    println!("\nMonitoring temperatures... Press Ctrl+C to exit.\n");
-
-    // Display loop
-    loop {
-        tokio::select! {
-            _ = tokio::signal::ctrl_c() => {
-                println!("\n\nExiting...");
-                break;
-            }
-            _ = tokio::time::sleep(Duration::from_secs(2)) => {
-                display_probe_table(&probes);
+    say!("\nMonitoring temperatures... Press Ctrl+C to exit.\n");
+
+    if json {
+        stream_json(&probes).await?;
+    } else {
+        // Display loop
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    println!("\n\nExiting...");
+                    break;
+                }
+                _ = tokio::time::sleep(Duration::from_secs(2)) => {
+                    display_probe_table(&probes);
+                }
             }
         }
     }
 
     // Disconnect all
-    println!("\nDisconnecting from all probes...");
+    say!("\nDisconnecting from all probes...");
     for probe in &probes {
         let _ = probe.disconnect().await;
     }
 
     manager.shutdown().await?;
-    println!("Done!");
+    say!("Done!");
+
+    Ok(())
+}
+
+/// Print a JSON snapshot of each probe as it updates until Ctrl+C.
+#[cfg(feature = "serde")]
+async fn stream_json(probes: &[Arc<Probe>]) -> Result<()> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    for probe in probes {
+        let mut updates = probe.subscribe_temperatures();
+        let probe = probe.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            while let Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) =
+                updates.recv().await
+            {
+                if tx.send(probe.clone()).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            probe = rx.recv() => match probe {
+                Some(probe) => println!("{}", probe.snapshot().to_json_line()?),
+                None => return Ok(()),
+            },
+        }
+    }
+}
 
+#[cfg(not(feature = "serde"))]
+async fn stream_json(_probes: &[Arc<Probe>]) -> Result<()> {
     Ok(())
 }
 
@@ -123,9 +186,9 @@ fn display_probe_table(probes: &[Arc<Probe>]) {
         // Sensor sources
         let sensors_str = format!(
             "{}/{}/{}",
-            sel.core_sensor_name(),
-            sel.surface_sensor_name(),
-            sel.ambient_sensor_name()
+            sel.core().map_or("?", |s| s.name()),
+            sel.surface().map_or("?", |s| s.name()),
+            sel.ambient().map_or("?", |s| s.name())
         );
 
         let status = if probe.is_stale() {
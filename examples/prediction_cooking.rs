@@ -2,7 +2,9 @@
 //!
 //! Run with: cargo run --example prediction_cooking
 
-use combustion_rust_ble::{celsius_to_fahrenheit, DeviceManager, Error, PredictionMode, Result};
+use combustion_rust_ble::{
+    celsius_to_fahrenheit, CookSpec, DeviceManager, Error, FoodSafeProduct, PredictionMode, Result,
+};
 use std::io::Write;
 use std::time::Duration;
 
@@ -47,9 +49,10 @@ async fn main() -> Result<()> {
         celsius_to_fahrenheit(target_celsius)
     );
 
-    probe
-        .set_prediction(PredictionMode::TimeToRemoval, target_celsius)
-        .await?;
+    let spec = CookSpec::new(FoodSafeProduct::BeefSteak, target_celsius)
+        .with_prediction_mode(PredictionMode::TimeToRemoval)
+        .without_food_safe();
+    probe.start_cook(spec).await?;
 
     println!("Prediction set! Monitoring...\n");
     println!("Press Ctrl+C to exit.\n");
@@ -67,7 +70,7 @@ async fn main() -> Result<()> {
                 let vt = probe.virtual_temperatures();
                 let sel = &vt.sensor_selection;
                 let core_temp = vt.core.unwrap_or(0.0);
-                let core_sensor = sel.core_sensor_name();
+                let core_sensor = sel.core().map_or("?", |s| s.name());
 
                 if let Some(info) = probe.prediction_info() {
                     match info.state {
@@ -131,9 +134,9 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Cancel prediction and cleanup
-    println!("\nCancelling prediction...");
-    let _ = probe.cancel_prediction().await;
+    // End the cook and cleanup
+    println!("\nEnding cook...");
+    let _ = probe.end_cook().await;
 
     probe.disconnect().await?;
     manager.shutdown().await?;
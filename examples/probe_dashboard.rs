@@ -16,6 +16,9 @@
 //! | Key | Action |
 //! |-----|--------|
 //! | `Up/Down` | Navigate probe list |
+//! | `Left/Right` | Navigate probe tiles (grid view) |
+//! | `G` | Toggle grid view of all probes |
+//! | `:` | Open command prompt, e.g. `predict 3 63C`, `alarm all core-high 95`, `export all` |
 //! | `Enter` | Connect/disconnect selected probe |
 //! | `P` | Set prediction target |
 //! | `C` | Cancel prediction |
@@ -34,11 +37,12 @@
 //! | `?` | Show help |
 //! | `Q/Esc` | Quit |
 
+use combustion_rust_ble::data::catalog;
 use combustion_rust_ble::{
-    celsius_to_fahrenheit, BatteryStatus, ConnectionState, DeviceManager, FoodSafeConfig,
-    FoodSafeMode, FoodSafeServingState, FoodSafeState, IntegratedProduct, PowerMode,
-    PredictionMode, PredictionState, PredictionType, Probe, ProbeColor, ProbeMode, Result,
-    Serving, SimplifiedProduct,
+    format_temperature, format_temperature_dual, parse_temperature, BatteryStatus, CatalogProduct,
+    Command, CommandOutput, ConnectionState, DeviceManager, FoodSafeConfig, FoodSafeMode,
+    FoodSafeState, IntegratedProduct, PowerMode, PredictionMode, PredictionState, PredictionType,
+    Probe, ProbeColor, ProbeSnapshot, Result, Serving, SimplifiedProduct, TemperatureUnit,
 };
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
@@ -53,33 +57,6 @@ use std::io::{self, stdout, Stdout};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-/// Temperature unit preference
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum TemperatureUnit {
-    Celsius,
-    Fahrenheit,
-}
-
-impl TemperatureUnit {
-    fn format(&self, celsius: f64) -> String {
-        match self {
-            TemperatureUnit::Celsius => format!("{:.1}°C", celsius),
-            TemperatureUnit::Fahrenheit => format!("{:.1}°F", celsius_to_fahrenheit(celsius)),
-        }
-    }
-
-    fn format_dual(&self, celsius: f64) -> String {
-        match self {
-            TemperatureUnit::Celsius => {
-                format!("{:.1}°C ({:.1}°F)", celsius, celsius_to_fahrenheit(celsius))
-            }
-            TemperatureUnit::Fahrenheit => {
-                format!("{:.1}°F ({:.1}°C)", celsius_to_fahrenheit(celsius), celsius)
-            }
-        }
-    }
-}
-
 /// Log severity level
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -117,6 +94,15 @@ struct LogEntry {
     message: String,
 }
 
+/// Layout of the main content area
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ViewMode {
+    /// Probe list with the selected probe's details
+    Detail,
+    /// Compact tiles for all probes
+    Grid,
+}
+
 /// Input dialog type
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -127,6 +113,7 @@ enum DialogType {
     SetProbeColor,
     SetAlarm,
     ConfirmReset,
+    Command,
     Help,
 }
 
@@ -174,6 +161,7 @@ struct App {
     device_manager: DeviceManager,
     probes: Vec<Arc<Probe>>,
     selected_probe_index: usize,
+    view: ViewMode,
     temperature_unit: TemperatureUnit,
     event_log: Vec<LogEntry>,
     max_log_entries: usize,
@@ -191,6 +179,7 @@ impl App {
             device_manager,
             probes: Vec::new(),
             selected_probe_index: 0,
+            view: ViewMode::Detail,
             temperature_unit: TemperatureUnit::Celsius,
             event_log: Vec::new(),
             max_log_entries: 100,
@@ -281,6 +270,13 @@ impl App {
         }
     }
 
+    fn toggle_view(&mut self) {
+        self.view = match self.view {
+            ViewMode::Detail => ViewMode::Grid,
+            ViewMode::Grid => ViewMode::Detail,
+        };
+    }
+
     async fn toggle_connection(&mut self) -> Result<()> {
         if let Some(probe) = self.selected_probe().cloned() {
             match probe.connection_state() {
@@ -309,19 +305,10 @@ impl App {
     }
 
     fn toggle_unit(&mut self) {
-        self.temperature_unit = match self.temperature_unit {
-            TemperatureUnit::Celsius => TemperatureUnit::Fahrenheit,
-            TemperatureUnit::Fahrenheit => TemperatureUnit::Celsius,
-        };
+        self.temperature_unit = self.temperature_unit.toggled();
         self.log(
             LogLevel::Info,
-            format!(
-                "Switched to {}",
-                match self.temperature_unit {
-                    TemperatureUnit::Celsius => "Celsius",
-                    TemperatureUnit::Fahrenheit => "Fahrenheit",
-                }
-            ),
+            format!("Switched to {}", self.temperature_unit.name()),
         );
     }
 
@@ -345,7 +332,7 @@ impl App {
         if let Some(dialog) = self.dialog.take() {
             match dialog.dialog_type {
                 DialogType::SetPrediction => {
-                    if let Ok(temp) = dialog.input.parse::<f64>() {
+                    if let Ok(temp) = parse_temperature(&dialog.input, TemperatureUnit::Celsius) {
                         if let Some(probe) = self.selected_probe().cloned() {
                             let mode = match dialog.selected_mode {
                                 0 => PredictionMode::TimeToRemoval,
@@ -355,7 +342,11 @@ impl App {
                             probe.set_prediction(mode, temp).await?;
                             self.log(
                                 LogLevel::Info,
-                                format!("Set prediction: {:.1}°C, mode: {:?}", temp, mode),
+                                format!(
+                                    "Set prediction: {}, mode: {:?}",
+                                    format_temperature(temp, self.temperature_unit),
+                                    mode
+                                ),
                             );
                         }
                     } else {
@@ -390,7 +381,9 @@ impl App {
                     };
 
                     if let Some(probe) = self.selected_probe().cloned() {
-                        probe.configure_food_safe_with_config(config.clone()).await?;
+                        probe
+                            .configure_food_safe_with_config(config.clone())
+                            .await?;
                         let mode_str = if fs.selected_mode == 0 {
                             "Simplified"
                         } else {
@@ -399,14 +392,13 @@ impl App {
                         let serving_str = match serving {
                             Serving::ServedImmediately => "Served Immediately",
                             Serving::CookedAndChilled => "Cooked and Chilled",
+                            _ => "Unknown",
                         };
                         self.log(
                             LogLevel::Info,
                             format!(
                                 "Configured food safety: {} mode, product #{}, {}",
-                                mode_str,
-                                fs.selected_product,
-                                serving_str
+                                mode_str, fs.selected_product, serving_str
                             ),
                         );
                     }
@@ -451,45 +443,65 @@ impl App {
                         match alarm.selected_alarm_type {
                             0 => {
                                 // Core High
-                                if let Ok(temp) = alarm.temp_input.parse::<f64>() {
+                                if let Ok(temp) =
+                                    parse_temperature(&alarm.temp_input, TemperatureUnit::Celsius)
+                                {
                                     probe.set_core_high_alarm(temp).await?;
                                     self.log(
                                         LogLevel::Info,
-                                        format!("Set core HIGH alarm to {:.1}°C", temp),
+                                        format!(
+                                            "Set core HIGH alarm to {}",
+                                            format_temperature(temp, self.temperature_unit)
+                                        ),
                                     );
                                 }
                             }
                             1 => {
                                 // Core Low
-                                if let Ok(temp) = alarm.temp_input.parse::<f64>() {
+                                if let Ok(temp) =
+                                    parse_temperature(&alarm.temp_input, TemperatureUnit::Celsius)
+                                {
                                     probe.set_core_low_alarm(temp).await?;
                                     self.log(
                                         LogLevel::Info,
-                                        format!("Set core LOW alarm to {:.1}°C", temp),
+                                        format!(
+                                            "Set core LOW alarm to {}",
+                                            format_temperature(temp, self.temperature_unit)
+                                        ),
                                     );
                                 }
                             }
                             2 => {
                                 // Surface High
-                                if let Ok(temp) = alarm.temp_input.parse::<f64>() {
+                                if let Ok(temp) =
+                                    parse_temperature(&alarm.temp_input, TemperatureUnit::Celsius)
+                                {
                                     let mut config = probe.alarm_config().unwrap_or_default();
                                     config.set_surface_high_alarm(temp, true);
                                     probe.set_alarms(&config).await?;
                                     self.log(
                                         LogLevel::Info,
-                                        format!("Set surface HIGH alarm to {:.1}°C", temp),
+                                        format!(
+                                            "Set surface HIGH alarm to {}",
+                                            format_temperature(temp, self.temperature_unit)
+                                        ),
                                     );
                                 }
                             }
                             3 => {
                                 // Ambient Low
-                                if let Ok(temp) = alarm.temp_input.parse::<f64>() {
+                                if let Ok(temp) =
+                                    parse_temperature(&alarm.temp_input, TemperatureUnit::Celsius)
+                                {
                                     let mut config = probe.alarm_config().unwrap_or_default();
                                     config.set_ambient_low_alarm(temp, true);
                                     probe.set_alarms(&config).await?;
                                     self.log(
                                         LogLevel::Info,
-                                        format!("Set ambient LOW alarm to {:.1}°C", temp),
+                                        format!(
+                                            "Set ambient LOW alarm to {}",
+                                            format_temperature(temp, self.temperature_unit)
+                                        ),
                                     );
                                 }
                             }
@@ -508,12 +520,52 @@ impl App {
                         self.log(LogLevel::Warn, "Thermometer reset to factory defaults");
                     }
                 }
+                DialogType::Command => {
+                    self.run_command(dialog.input.trim()).await;
+                }
                 DialogType::Help => {}
             }
         }
         Ok(())
     }
 
+    async fn run_command(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let command = match Command::parse_with_unit(line, self.temperature_unit) {
+            Ok(command) => command,
+            Err(e) => {
+                self.log(LogLevel::Error, format!("{}: {}", line, e));
+                return;
+            }
+        };
+        let outcomes = match self.device_manager.execute(&command).await {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                self.log(LogLevel::Error, format!("{}: {}", command, e));
+                return;
+            }
+        };
+
+        for outcome in outcomes {
+            let serial = outcome.probe.serial_number_string();
+            match outcome.result {
+                Ok(CommandOutput::Done) => {
+                    self.log(LogLevel::Info, format!("{}: {}", serial, command));
+                }
+                Ok(CommandOutput::Log(log)) => {
+                    let filename = format!("probe_{}_log.csv", serial);
+                    match std::fs::write(&filename, log.to_csv()) {
+                        Ok(_) => self.log(LogLevel::Info, format!("Exported logs to {}", filename)),
+                        Err(e) => self.log(LogLevel::Error, format!("Failed to export: {}", e)),
+                    }
+                }
+                Err(e) => self.log(LogLevel::Error, format!("{}: {}: {}", serial, command, e)),
+            }
+        }
+    }
+
     async fn cancel_prediction(&mut self) -> Result<()> {
         if let Some(probe) = self.selected_probe().cloned() {
             probe.cancel_prediction().await?;
@@ -543,10 +595,13 @@ impl App {
             let current = probe.power_mode().unwrap_or(PowerMode::Normal);
             let new_mode = match current {
                 PowerMode::Normal => PowerMode::AlwaysOn,
-                PowerMode::AlwaysOn => PowerMode::Normal,
+                _ => PowerMode::Normal,
             };
             probe.set_power_mode(new_mode).await?;
-            self.log(LogLevel::Info, format!("Power mode set to {}", new_mode.name()));
+            self.log(
+                LogLevel::Info,
+                format!("Power mode set to {}", new_mode.name()),
+            );
         }
         Ok(())
     }
@@ -595,112 +650,46 @@ fn restore_terminal(terminal: &mut Terminal) -> io::Result<()> {
 }
 
 /// Get list of simplified mode products with display names
-fn get_simplified_products() -> Vec<(SimplifiedProduct, &'static str)> {
-    vec![
-        (SimplifiedProduct::AnyPoultry, "Any Poultry (165°F/74°C)"),
-        (SimplifiedProduct::BeefCuts, "Beef Cuts (145°F/63°C)"),
-        (SimplifiedProduct::PorkCuts, "Pork Cuts (145°F/63°C)"),
-        (SimplifiedProduct::VealCuts, "Veal Cuts (145°F/63°C)"),
-        (SimplifiedProduct::LambCuts, "Lamb Cuts (145°F/63°C)"),
-        (SimplifiedProduct::GroundMeats, "Ground Meats (160°F/71°C)"),
-        (
-            SimplifiedProduct::HamFreshOrSmoked,
-            "Ham Fresh/Smoked (145°F/63°C)",
-        ),
-        (
-            SimplifiedProduct::HamCookedAndReheated,
-            "Ham Reheated (165°F/74°C)",
-        ),
-        (SimplifiedProduct::Eggs, "Eggs (160°F/71°C)"),
-        (
-            SimplifiedProduct::FishAndShellfish,
-            "Fish & Shellfish (145°F/63°C)",
-        ),
-        (SimplifiedProduct::Leftovers, "Leftovers (165°F/74°C)"),
-        (SimplifiedProduct::Casseroles, "Casseroles (165°F/74°C)"),
-    ]
+fn get_simplified_products() -> Vec<(SimplifiedProduct, String)> {
+    catalog::products(FoodSafeMode::Simplified)
+        .filter_map(|entry| match entry.product {
+            CatalogProduct::Simplified(SimplifiedProduct::Default) => None,
+            CatalogProduct::Simplified(product) => {
+                let celsius = entry.threshold_temperature();
+                let fahrenheit = celsius * 9.0 / 5.0 + 32.0;
+                Some((
+                    product,
+                    format!("{} ({:.0}°F/{:.0}°C)", entry.name, fahrenheit, celsius),
+                ))
+            }
+            CatalogProduct::Integrated(_) => None,
+        })
+        .collect()
 }
 
 /// Get list of integrated mode products with display names
-fn get_integrated_products() -> Vec<(IntegratedProduct, &'static str)> {
-    vec![
-        (IntegratedProduct::Poultry, "Poultry (7.0 log reduction)"),
-        (IntegratedProduct::Meats, "Meats - whole muscle (5.0 log)"),
-        (
-            IntegratedProduct::MeatsGroundChoppedOrStuffed,
-            "Meats - ground/stuffed (6.5 log)",
-        ),
-        (
-            IntegratedProduct::PoultryGroundChoppedOrStuffed,
-            "Poultry - ground/stuffed (7.0 log)",
-        ),
-        (IntegratedProduct::Seafood, "Seafood - whole (6.0 log)"),
-        (
-            IntegratedProduct::SeafoodGroundOrChopped,
-            "Seafood - ground (6.0 log)",
-        ),
-        (IntegratedProduct::SeafoodStuffed, "Seafood - stuffed (6.0 log)"),
-        (IntegratedProduct::Eggs, "Eggs (5.0 log)"),
-        (IntegratedProduct::EggsYolk, "Egg Yolk (5.0 log)"),
-        (IntegratedProduct::EggsWhite, "Egg White (5.0 log)"),
-        (IntegratedProduct::DairyMilk, "Dairy - Milk (5.0 log)"),
-        (IntegratedProduct::DairyCreams, "Dairy - Creams (5.0 log)"),
-        (
-            IntegratedProduct::DairyIceCreamMixEggnog,
-            "Dairy - Ice Cream/Eggnog (5.0 log)",
-        ),
-        (IntegratedProduct::Other, "Other (6.5 log)"),
-    ]
+fn get_integrated_products() -> Vec<(IntegratedProduct, String)> {
+    catalog::products(FoodSafeMode::Integrated)
+        .filter_map(|entry| match entry.product {
+            CatalogProduct::Integrated(IntegratedProduct::Custom) => None,
+            CatalogProduct::Integrated(product) => Some((
+                product,
+                format!(
+                    "{} ({:.1} log)",
+                    entry.name,
+                    product.default_target_log_reduction()
+                ),
+            )),
+            CatalogProduct::Simplified(_) => None,
+        })
+        .collect()
 }
 
 /// Get product display name from product code and mode
 fn get_product_name(product_code: u16, mode: FoodSafeMode) -> String {
-    match mode {
-        FoodSafeMode::Simplified => {
-            if let Some(product) = SimplifiedProduct::from_raw(product_code) {
-                match product {
-                    SimplifiedProduct::Default => "Default".to_string(),
-                    SimplifiedProduct::AnyPoultry => "Any Poultry".to_string(),
-                    SimplifiedProduct::BeefCuts => "Beef Cuts".to_string(),
-                    SimplifiedProduct::PorkCuts => "Pork Cuts".to_string(),
-                    SimplifiedProduct::VealCuts => "Veal Cuts".to_string(),
-                    SimplifiedProduct::LambCuts => "Lamb Cuts".to_string(),
-                    SimplifiedProduct::GroundMeats => "Ground Meats".to_string(),
-                    SimplifiedProduct::HamFreshOrSmoked => "Ham Fresh/Smoked".to_string(),
-                    SimplifiedProduct::HamCookedAndReheated => "Ham Reheated".to_string(),
-                    SimplifiedProduct::Eggs => "Eggs".to_string(),
-                    SimplifiedProduct::FishAndShellfish => "Fish & Shellfish".to_string(),
-                    SimplifiedProduct::Leftovers => "Leftovers".to_string(),
-                    SimplifiedProduct::Casseroles => "Casseroles".to_string(),
-                }
-            } else {
-                format!("Unknown ({})", product_code)
-            }
-        }
-        FoodSafeMode::Integrated => {
-            if let Some(product) = IntegratedProduct::from_raw(product_code) {
-                match product {
-                    IntegratedProduct::Poultry => "Poultry".to_string(),
-                    IntegratedProduct::Meats => "Meats".to_string(),
-                    IntegratedProduct::MeatsGroundChoppedOrStuffed => "Ground Meats".to_string(),
-                    IntegratedProduct::PoultryGroundChoppedOrStuffed => "Ground Poultry".to_string(),
-                    IntegratedProduct::Seafood => "Seafood".to_string(),
-                    IntegratedProduct::SeafoodGroundOrChopped => "Ground Seafood".to_string(),
-                    IntegratedProduct::SeafoodStuffed => "Stuffed Seafood".to_string(),
-                    IntegratedProduct::Eggs => "Eggs".to_string(),
-                    IntegratedProduct::EggsYolk => "Egg Yolk".to_string(),
-                    IntegratedProduct::EggsWhite => "Egg White".to_string(),
-                    IntegratedProduct::DairyMilk => "Dairy - Milk".to_string(),
-                    IntegratedProduct::DairyCreams => "Dairy - Creams".to_string(),
-                    IntegratedProduct::DairyIceCreamMixEggnog => "Ice Cream/Eggnog".to_string(),
-                    IntegratedProduct::Other => "Other".to_string(),
-                    IntegratedProduct::Custom => "Custom".to_string(),
-                }
-            } else {
-                format!("Unknown ({})", product_code)
-            }
-        }
-    }
+    catalog::lookup(mode, product_code)
+        .map(|entry| entry.name.to_string())
+        .unwrap_or_else(|| format!("Unknown ({})", product_code))
 }
 
 fn render_ui(frame: &mut Frame, app: &App) {
@@ -757,6 +746,11 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn render_content(frame: &mut Frame, area: Rect, app: &App) {
+    if app.view == ViewMode::Grid {
+        render_grid(frame, area, app);
+        return;
+    }
+
     // Split into left (probes list + temps) and right (details + actions)
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -789,6 +783,154 @@ fn render_content(frame: &mut Frame, area: Rect, app: &App) {
     render_food_safety_and_logs(frame, right_chunks[3], app);
 }
 
+/// Number of tile columns in the grid view (two rows fit all 8 probes)
+const GRID_COLUMNS: usize = 4;
+
+fn render_grid(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" All Probes ")
+        .title(
+            Title::from(" [G] Detail view ")
+                .alignment(Alignment::Right)
+                .position(block::Position::Top),
+        );
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.probes.is_empty() {
+        let waiting = Paragraph::new("Waiting for probes...")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(waiting, inner);
+        return;
+    }
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .split(inner);
+    let cells: Vec<Rect> = rows
+        .iter()
+        .flat_map(|row| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Ratio(1, GRID_COLUMNS as u32); GRID_COLUMNS])
+                .split(*row)
+                .to_vec()
+        })
+        .collect();
+
+    for (i, (probe, cell)) in app.probes.iter().zip(cells).enumerate() {
+        render_probe_tile(
+            frame,
+            cell,
+            &probe.snapshot(),
+            probe.is_stale(),
+            i == app.selected_probe_index,
+            app,
+        );
+    }
+}
+
+fn render_probe_tile(
+    frame: &mut Frame,
+    area: Rect,
+    snapshot: &ProbeSnapshot,
+    stale: bool,
+    selected: bool,
+    app: &App,
+) {
+    let connected = snapshot.connection_state == ConnectionState::Connected;
+    let (status, status_style) = if stale {
+        ("Stale", Style::default().fg(Color::Red))
+    } else if connected {
+        ("Connected", Style::default().fg(Color::Green))
+    } else {
+        ("Advertising", Style::default().fg(Color::Yellow))
+    };
+
+    let core = snapshot
+        .virtual_temperatures
+        .core
+        .map(|c| format_temperature(c, app.temperature_unit))
+        .unwrap_or_else(|| "--".to_string());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            core,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::raw("State: "),
+            Span::styled(status, status_style),
+        ]),
+    ];
+
+    match &snapshot.prediction {
+        Some(info) if info.set_point_temperature > 0.0 => {
+            lines.push(Line::from(vec![
+                Span::raw("Target: "),
+                Span::styled(
+                    format_temperature(info.set_point_temperature, app.temperature_unit),
+                    Style::default().fg(Color::Cyan),
+                ),
+            ]));
+            let eta = if info.state.is_predicting() {
+                let secs = info.prediction_value_seconds;
+                format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+            } else {
+                format!("{:?}", info.state)
+            };
+            lines.push(Line::from(vec![
+                Span::raw("ETA: "),
+                Span::styled(eta, Style::default().fg(Color::Green)),
+            ]));
+        }
+        _ => {
+            lines.push(Line::from(Span::styled(
+                "No prediction",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    if snapshot
+        .food_safe_data
+        .as_ref()
+        .is_some_and(|data| data.is_safe())
+    {
+        lines.push(Line::from(Span::styled(
+            "Food safe",
+            Style::default().fg(Color::Green),
+        )));
+    }
+
+    let border_style = if selected {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let title = format!(
+        " {} {} [ID:{}] ",
+        color_emoji(snapshot.color),
+        snapshot.serial_number_string(),
+        snapshot.probe_id
+    );
+    let tile = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title),
+    );
+
+    frame.render_widget(tile, area);
+}
+
 fn render_probe_list(frame: &mut Frame, area: Rect, app: &App) {
     let items: Vec<ListItem> = app
         .probes
@@ -862,13 +1004,13 @@ fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
         // Core
         if let Some(core) = vt.core {
             rows.push(Row::new(vec![
-                Cell::from(format!("Core [{}]:", sel.core_sensor_name())),
-                Cell::from(app.temperature_unit.format_dual(core)),
+                Cell::from(format!("Core [{}]:", sel.core().map_or("?", |s| s.name()))),
+                Cell::from(format_temperature_dual(core, app.temperature_unit)),
                 Cell::from("✓").style(Style::default().fg(Color::Green)),
             ]));
         } else {
             rows.push(Row::new(vec![
-                Cell::from(format!("Core [{}]:", sel.core_sensor_name())),
+                Cell::from(format!("Core [{}]:", sel.core().map_or("?", |s| s.name()))),
                 Cell::from("--"),
                 Cell::from(""),
             ]));
@@ -877,13 +1019,19 @@ fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
         // Surface
         if let Some(surface) = vt.surface {
             rows.push(Row::new(vec![
-                Cell::from(format!("Surface [{}]:", sel.surface_sensor_name())),
-                Cell::from(app.temperature_unit.format_dual(surface)),
+                Cell::from(format!(
+                    "Surface [{}]:",
+                    sel.surface().map_or("?", |s| s.name())
+                )),
+                Cell::from(format_temperature_dual(surface, app.temperature_unit)),
                 Cell::from("✓").style(Style::default().fg(Color::Green)),
             ]));
         } else {
             rows.push(Row::new(vec![
-                Cell::from(format!("Surface [{}]:", sel.surface_sensor_name())),
+                Cell::from(format!(
+                    "Surface [{}]:",
+                    sel.surface().map_or("?", |s| s.name())
+                )),
                 Cell::from("--"),
                 Cell::from(""),
             ]));
@@ -892,13 +1040,19 @@ fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
         // Ambient
         if let Some(ambient) = vt.ambient {
             rows.push(Row::new(vec![
-                Cell::from(format!("Ambient [{}]:", sel.ambient_sensor_name())),
-                Cell::from(app.temperature_unit.format_dual(ambient)),
+                Cell::from(format!(
+                    "Ambient [{}]:",
+                    sel.ambient().map_or("?", |s| s.name())
+                )),
+                Cell::from(format_temperature_dual(ambient, app.temperature_unit)),
                 Cell::from("✓").style(Style::default().fg(Color::Green)),
             ]));
         } else {
             rows.push(Row::new(vec![
-                Cell::from(format!("Ambient [{}]:", sel.ambient_sensor_name())),
+                Cell::from(format!(
+                    "Ambient [{}]:",
+                    sel.ambient().map_or("?", |s| s.name())
+                )),
                 Cell::from("--"),
                 Cell::from(""),
             ]));
@@ -913,7 +1067,6 @@ fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
 
         let temps = probe.current_temperatures();
         let celsius_temps = temps.to_celsius();
-        let is_instant_read = probe.mode() == ProbeMode::InstantRead;
         let overheating = probe.overheating();
 
         let sensor_names = [
@@ -930,7 +1083,7 @@ fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
         for (i, celsius) in celsius_temps.iter().enumerate() {
             let is_overheating = overheating.is_sensor_overheating(i);
 
-            if is_instant_read && i > 0 {
+            if !temps.is_active(i) {
                 rows.push(Row::new(vec![
                     Cell::from(format!("{}:", sensor_names[i])),
                     Cell::from("N/A (Instant Read)").style(Style::default().fg(Color::DarkGray)),
@@ -951,7 +1104,7 @@ fn render_temperatures(frame: &mut Frame, area: Rect, app: &App) {
 
                 rows.push(Row::new(vec![
                     Cell::from(format!("{}:", sensor_names[i])),
-                    Cell::from(app.temperature_unit.format_dual(*c)).style(temp_style),
+                    Cell::from(format_temperature_dual(*c, app.temperature_unit)).style(temp_style),
                     status,
                 ]));
             } else {
@@ -1067,6 +1220,7 @@ fn render_probe_details(frame: &mut Frame, area: Rect, app: &App) {
         let power_style = match power_mode {
             PowerMode::Normal => Style::default().fg(Color::Green),
             PowerMode::AlwaysOn => Style::default().fg(Color::Yellow),
+            _ => Style::default().fg(Color::DarkGray),
         };
         lines.push(Line::from(vec![
             Span::raw("Power: "),
@@ -1208,7 +1362,7 @@ fn render_prediction(frame: &mut Frame, area: Rect, app: &App) {
             lines.push(Line::from(vec![
                 Span::raw("Setpoint: "),
                 Span::styled(
-                    app.temperature_unit.format_dual(info.set_point_temperature),
+                    format_temperature_dual(info.set_point_temperature, app.temperature_unit),
                     Style::default().fg(Color::Cyan),
                 ),
             ]));
@@ -1217,7 +1371,7 @@ fn render_prediction(frame: &mut Frame, area: Rect, app: &App) {
             lines.push(Line::from(vec![
                 Span::raw("Heat Start: "),
                 Span::styled(
-                    app.temperature_unit.format(info.heat_start_temperature),
+                    format_temperature(info.heat_start_temperature, app.temperature_unit),
                     Style::default().fg(Color::Magenta),
                 ),
             ]));
@@ -1226,7 +1380,7 @@ fn render_prediction(frame: &mut Frame, area: Rect, app: &App) {
             lines.push(Line::from(vec![
                 Span::raw("Est. Core: "),
                 Span::styled(
-                    app.temperature_unit.format(info.estimated_core_temperature),
+                    format_temperature(info.estimated_core_temperature, app.temperature_unit),
                     Style::default().fg(Color::Yellow),
                 ),
             ]));
@@ -1311,6 +1465,7 @@ fn render_food_safety_and_logs(frame: &mut Frame, area: Rect, app: &App) {
                 FoodSafeState::NotSafe => ("⏳", Style::default().fg(Color::Yellow)),
                 FoodSafeState::Safe => ("✓", Style::default().fg(Color::Green)),
                 FoodSafeState::SafetyImpossible => ("✗", Style::default().fg(Color::Red)),
+                _ => ("?", Style::default().fg(Color::DarkGray)),
             };
 
             food_lines.push(Line::from(vec![
@@ -1323,6 +1478,7 @@ fn render_food_safety_and_logs(frame: &mut Frame, area: Rect, app: &App) {
                 let mode_str = match config.mode {
                     FoodSafeMode::Simplified => "Simplified",
                     FoodSafeMode::Integrated => "Integrated",
+                    _ => "Unknown",
                 };
                 food_lines.push(Line::from(vec![
                     Span::raw("Mode: "),
@@ -1348,16 +1504,23 @@ fn render_food_safety_and_logs(frame: &mut Frame, area: Rect, app: &App) {
                     food_lines.push(Line::from(vec![
                         Span::raw("Log Red: "),
                         Span::styled(
-                            format!("{:.2}/{:.1}", data.log_reduction, config.target_log_reduction),
+                            format!(
+                                "{:.2}/{:.1}",
+                                data.log_reduction(),
+                                config.target_log_reduction
+                            ),
                             Style::default().fg(Color::Yellow),
                         ),
                     ]));
-                    food_lines.push(Line::from(Span::styled(bar, Style::default().fg(Color::Green))));
+                    food_lines.push(Line::from(Span::styled(
+                        bar,
+                        Style::default().fg(Color::Green),
+                    )));
                 } else {
                     food_lines.push(Line::from(vec![
                         Span::raw("Target: "),
                         Span::styled(
-                            app.temperature_unit.format(config.threshold_temperature),
+                            format_temperature(config.threshold_temperature, app.temperature_unit),
                             Style::default().fg(Color::Yellow),
                         ),
                     ]));
@@ -1366,13 +1529,14 @@ fn render_food_safety_and_logs(frame: &mut Frame, area: Rect, app: &App) {
                 // Time at temp
                 food_lines.push(Line::from(vec![
                     Span::raw("Time@Temp: "),
-                    Span::raw(format!("{}s", data.seconds_above_threshold)),
+                    Span::raw(format!("{}s", data.seconds_above_threshold())),
                 ]));
 
                 // Serving mode
                 let serving_str = match config.serving {
                     Serving::ServedImmediately => "Immediate",
                     Serving::CookedAndChilled => "Chilled",
+                    _ => "Unknown",
                 };
                 food_lines.push(Line::from(vec![
                     Span::raw("Serving: "),
@@ -1380,14 +1544,15 @@ fn render_food_safety_and_logs(frame: &mut Frame, area: Rect, app: &App) {
                 ]));
             } else {
                 // Legacy display without config
-                let status_style = match data.serving_state {
-                    FoodSafeServingState::SafeToServe => Style::default().fg(Color::Green),
-                    FoodSafeServingState::NotSafe => Style::default().fg(Color::Red),
+                let status_style = if data.is_safe() {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default().fg(Color::Red)
                 };
 
                 food_lines.push(Line::from(vec![
                     Span::raw("Serving: "),
-                    Span::styled(format!("{:?}", data.serving_state), status_style),
+                    Span::styled(format!("{:?}", data.state()), status_style),
                 ]));
 
                 food_lines.push(Line::from(format!(
@@ -1397,7 +1562,7 @@ fn render_food_safety_and_logs(frame: &mut Frame, area: Rect, app: &App) {
 
                 food_lines.push(Line::from(format!(
                     "Time@Temp: {}s",
-                    data.seconds_above_threshold
+                    data.seconds_above_threshold()
                 )));
             }
         } else {
@@ -1476,10 +1641,7 @@ fn render_event_log(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
-    let unit_str = match app.temperature_unit {
-        TemperatureUnit::Celsius => "°C",
-        TemperatureUnit::Fahrenheit => "°F",
-    };
+    let unit_str = app.temperature_unit.symbol();
 
     let status = format!(
         " Probes: {} | Scanning: {} | Unit: {} | Press ? for help ",
@@ -1630,15 +1792,15 @@ fn render_dialog(frame: &mut Frame, dialog: &DialogState, area: Rect) {
                     )));
                     content.push(Line::from(""));
 
-                    let products: Vec<&str> = if fs.selected_mode == 0 {
+                    let products: Vec<String> = if fs.selected_mode == 0 {
                         get_simplified_products()
-                            .iter()
-                            .map(|(_, name)| *name)
+                            .into_iter()
+                            .map(|(_, name)| name)
                             .collect()
                     } else {
                         get_integrated_products()
-                            .iter()
-                            .map(|(_, name)| *name)
+                            .into_iter()
+                            .map(|(_, name)| name)
                             .collect()
                     };
 
@@ -1772,8 +1934,14 @@ fn render_dialog(frame: &mut Frame, dialog: &DialogState, area: Rect) {
             let alarm_types = [
                 ("Core HIGH alarm", "Alert when core exceeds temperature"),
                 ("Core LOW alarm", "Alert when core drops below temperature"),
-                ("Surface HIGH alarm", "Alert when surface exceeds temperature"),
-                ("Ambient LOW alarm", "Alert when ambient drops below temperature"),
+                (
+                    "Surface HIGH alarm",
+                    "Alert when surface exceeds temperature",
+                ),
+                (
+                    "Ambient LOW alarm",
+                    "Alert when ambient drops below temperature",
+                ),
                 ("Disable ALL alarms", "Turn off all temperature alarms"),
             ];
 
@@ -1836,9 +2004,7 @@ fn render_dialog(frame: &mut Frame, dialog: &DialogState, area: Rect) {
             let content = vec![
                 Line::from(Span::styled(
                     "⚠ WARNING: Reset Thermometer",
-                    Style::default()
-                        .fg(Color::Red)
-                        .add_modifier(Modifier::BOLD),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )),
                 Line::from(""),
                 Line::from("This will reset the thermometer to factory defaults."),
@@ -1858,6 +2024,27 @@ fn render_dialog(frame: &mut Frame, dialog: &DialogState, area: Rect) {
             ];
             (" Confirm Reset ", content)
         }
+        DialogType::Command => {
+            let content = vec![
+                Line::from(Span::styled(
+                    format!(":{}_", dialog.input),
+                    Style::default().fg(Color::Yellow),
+                )),
+                Line::from(""),
+                Line::from("Targets: all, probe ID (1-8), or serial number"),
+                Line::from(""),
+                Line::from("  predict <target> <temp> [resting]"),
+                Line::from("  cancel <target>"),
+                Line::from("  alarm <target> <sensor>-<high|low> <temp>"),
+                Line::from("  alarm <target> off"),
+                Line::from("  silence <target>"),
+                Line::from("  note <target> <text>"),
+                Line::from("  export <target>"),
+                Line::from(""),
+                Line::from("[Enter] Run  [Esc] Cancel"),
+            ];
+            (" Command ", content)
+        }
         DialogType::Help => {
             let content = vec![
                 Line::from("Keyboard Shortcuts:"),
@@ -1877,6 +2064,7 @@ fn render_dialog(frame: &mut Frame, dialog: &DialogState, area: Rect) {
                 Line::from("  E       Export logs to CSV"),
                 Line::from("  S       Start/stop scanning"),
                 Line::from("  U       Toggle temperature units"),
+                Line::from("  :       Open command prompt"),
                 Line::from("  ?       Show this help"),
                 Line::from("  Q/Esc   Quit"),
             ];
@@ -1910,6 +2098,9 @@ fn render_help_overlay(frame: &mut Frame, area: Rect) {
         Line::from("Navigation:"),
         Line::from("  ↑/↓        Navigate probe list"),
         Line::from("  Enter      Connect/disconnect selected probe"),
+        Line::from("  G          Toggle grid view of all probes"),
+        Line::from("  ←/→        Navigate probe tiles (grid view)"),
+        Line::from("  :          Open command prompt (predict, alarm, export, ...)"),
         Line::from(""),
         Line::from("Temperature & Prediction:"),
         Line::from("  P          Set prediction target temperature"),
@@ -2060,13 +2251,10 @@ async fn run_app(terminal: &mut Terminal, mut app: App) -> Result<()> {
                             KeyCode::Left => {
                                 // For FoodSafe, go back to previous stage
                                 if matches!(dialog.dialog_type, DialogType::SetFoodSafe) {
-                                    if dialog.food_safe.stage > 0 {
-                                        dialog.food_safe.stage -= 1;
-                                    }
+                                    dialog.food_safe.stage =
+                                        dialog.food_safe.stage.saturating_sub(1);
                                 } else if matches!(dialog.dialog_type, DialogType::SetAlarm) {
-                                    if dialog.alarm.stage > 0 {
-                                        dialog.alarm.stage -= 1;
-                                    }
+                                    dialog.alarm.stage = dialog.alarm.stage.saturating_sub(1);
                                 }
                             }
                             KeyCode::Char(c) => {
@@ -2074,7 +2262,7 @@ async fn run_app(terminal: &mut Terminal, mut app: App) -> Result<()> {
                                     && dialog.alarm.stage == 1
                                 {
                                     // Alarm temp input
-                                    if c.is_ascii_digit() || c == '.' || c == '-' {
+                                    if c.is_ascii_digit() || c == '.' || c == ',' || c == '-' {
                                         dialog.alarm.temp_input.push(c);
                                     }
                                 } else {
@@ -2106,23 +2294,17 @@ async fn run_app(terminal: &mut Terminal, mut app: App) -> Result<()> {
                                 DialogType::SetFoodSafe => {
                                     // Navigate within current stage
                                     match dialog.food_safe.stage {
-                                        0 => {
-                                            // Mode selection
-                                            if dialog.food_safe.selected_mode > 0 {
-                                                dialog.food_safe.selected_mode -= 1;
-                                            }
+                                        // Mode selection
+                                        0 if dialog.food_safe.selected_mode > 0 => {
+                                            dialog.food_safe.selected_mode -= 1;
                                         }
-                                        1 => {
-                                            // Product selection
-                                            if dialog.food_safe.selected_product > 0 {
-                                                dialog.food_safe.selected_product -= 1;
-                                            }
+                                        // Product selection
+                                        1 if dialog.food_safe.selected_product > 0 => {
+                                            dialog.food_safe.selected_product -= 1;
                                         }
-                                        2 => {
-                                            // Serving selection
-                                            if dialog.food_safe.selected_serving > 0 {
-                                                dialog.food_safe.selected_serving -= 1;
-                                            }
+                                        // Serving selection
+                                        2 if dialog.food_safe.selected_serving > 0 => {
+                                            dialog.food_safe.selected_serving -= 1;
                                         }
                                         _ => {}
                                     }
@@ -2134,52 +2316,43 @@ async fn run_app(terminal: &mut Terminal, mut app: App) -> Result<()> {
                                 }
                             },
                             KeyCode::Down => match dialog.dialog_type {
-                                DialogType::SetPrediction => {
-                                    if dialog.selected_mode < 1 {
-                                        dialog.selected_mode += 1;
-                                    }
+                                DialogType::SetPrediction if dialog.selected_mode < 1 => {
+                                    dialog.selected_mode += 1;
                                 }
-                                DialogType::SetAlarm => {
+                                DialogType::SetAlarm
                                     if dialog.alarm.stage == 0
-                                        && dialog.alarm.selected_alarm_type < 4
-                                    {
-                                        dialog.alarm.selected_alarm_type += 1;
-                                    }
+                                        && dialog.alarm.selected_alarm_type < 4 =>
+                                {
+                                    dialog.alarm.selected_alarm_type += 1;
                                 }
                                 DialogType::SetFoodSafe => {
                                     // Navigate within current stage
                                     match dialog.food_safe.stage {
-                                        0 => {
-                                            // Mode selection (2 options)
-                                            if dialog.food_safe.selected_mode < 1 {
-                                                dialog.food_safe.selected_mode += 1;
-                                            }
+                                        // Mode selection (2 options)
+                                        0 if dialog.food_safe.selected_mode < 1 => {
+                                            dialog.food_safe.selected_mode += 1;
                                         }
                                         1 => {
                                             // Product selection
-                                            let max_products = if dialog.food_safe.selected_mode == 0
-                                            {
-                                                get_simplified_products().len() - 1
-                                            } else {
-                                                get_integrated_products().len() - 1
-                                            };
+                                            let max_products =
+                                                if dialog.food_safe.selected_mode == 0 {
+                                                    get_simplified_products().len() - 1
+                                                } else {
+                                                    get_integrated_products().len() - 1
+                                                };
                                             if dialog.food_safe.selected_product < max_products {
                                                 dialog.food_safe.selected_product += 1;
                                             }
                                         }
-                                        2 => {
-                                            // Serving selection (2 options)
-                                            if dialog.food_safe.selected_serving < 1 {
-                                                dialog.food_safe.selected_serving += 1;
-                                            }
+                                        // Serving selection (2 options)
+                                        2 if dialog.food_safe.selected_serving < 1 => {
+                                            dialog.food_safe.selected_serving += 1;
                                         }
                                         _ => {}
                                     }
                                 }
-                                DialogType::SetProbeColor => {
-                                    if dialog.selected_option < 7 {
-                                        dialog.selected_option += 1;
-                                    }
+                                DialogType::SetProbeColor if dialog.selected_option < 7 => {
+                                    dialog.selected_option += 1;
                                 }
                                 _ => {}
                             },
@@ -2202,12 +2375,24 @@ async fn run_app(terminal: &mut Terminal, mut app: App) -> Result<()> {
                         KeyCode::Char('?') => {
                             app.show_help = true;
                         }
+                        KeyCode::Char(':') => {
+                            app.open_dialog(DialogType::Command);
+                        }
                         KeyCode::Up => {
                             app.select_prev_probe();
                         }
                         KeyCode::Down => {
                             app.select_next_probe();
                         }
+                        KeyCode::Left if app.view == ViewMode::Grid => {
+                            app.select_prev_probe();
+                        }
+                        KeyCode::Right if app.view == ViewMode::Grid => {
+                            app.select_next_probe();
+                        }
+                        KeyCode::Char('g') | KeyCode::Char('G') => {
+                            app.toggle_view();
+                        }
                         KeyCode::Enter => {
                             let _ = app.toggle_connection().await;
                         }
@@ -56,6 +56,8 @@ async fn main() -> Result<()> {
         }
     }
 
+    print_connection_info(&probe);
+
     // Wait a moment for notifications to start
     tokio::time::sleep(Duration::from_millis(500)).await;
 
@@ -120,6 +122,16 @@ fn print_probe_info(probe: &combustion_rust_ble::Probe) {
     println!("  Is Stale: {}", probe.is_stale());
 }
 
+fn print_connection_info(probe: &combustion_rust_ble::Probe) {
+    println!("\n--- Connection ---");
+    if let Some(metrics) = probe.connection_metrics() {
+        println!("  Metrics: {}", metrics);
+    }
+    if let Some(report) = probe.discovery_report() {
+        print!("{}", report);
+    }
+}
+
 fn print_temperatures(probe: &combustion_rust_ble::Probe) {
     println!("\n--- Temperatures ---");
 
@@ -131,34 +143,40 @@ fn print_temperatures(probe: &combustion_rust_ble::Probe) {
     if let Some(core) = vt.core {
         println!(
             "    Core [{}]: {:.1}°C ({:.1}°F)",
-            sel.core_sensor_name(),
+            sel.core().map_or("?", |s| s.name()),
             core,
             celsius_to_fahrenheit(core)
         );
     } else {
-        println!("    Core [{}]: N/A", sel.core_sensor_name());
+        println!("    Core [{}]: N/A", sel.core().map_or("?", |s| s.name()));
     }
 
     if let Some(surface) = vt.surface {
         println!(
             "    Surface [{}]: {:.1}°C ({:.1}°F)",
-            sel.surface_sensor_name(),
+            sel.surface().map_or("?", |s| s.name()),
             surface,
             celsius_to_fahrenheit(surface)
         );
     } else {
-        println!("    Surface [{}]: N/A", sel.surface_sensor_name());
+        println!(
+            "    Surface [{}]: N/A",
+            sel.surface().map_or("?", |s| s.name())
+        );
     }
 
     if let Some(ambient) = vt.ambient {
         println!(
             "    Ambient [{}]: {:.1}°C ({:.1}°F)",
-            sel.ambient_sensor_name(),
+            sel.ambient().map_or("?", |s| s.name()),
             ambient,
             celsius_to_fahrenheit(ambient)
         );
     } else {
-        println!("    Ambient [{}]: N/A", sel.ambient_sensor_name());
+        println!(
+            "    Ambient [{}]: N/A",
+            sel.ambient().map_or("?", |s| s.name())
+        );
     }
 
     // Raw temperatures
@@ -255,13 +273,13 @@ fn print_food_safety_info(probe: &combustion_rust_ble::Probe) {
     println!("\n--- Food Safety ---");
 
     if let Some(data) = probe.food_safe_data() {
-        println!("  Product: {:?}", data.product);
-        println!("  Serving State: {:?}", data.serving_state);
-        println!("  Log Reduction: {:.2}", data.log_reduction);
+        println!("  Config: {:?}", data.config);
+        println!("  State: {:?}", data.state());
+        println!("  Log Reduction: {:.2}", data.log_reduction());
         println!("  Progress: {:.1}%", data.progress_percent());
         println!(
             "  Seconds Above Threshold: {}",
-            data.seconds_above_threshold
+            data.seconds_above_threshold()
         );
         println!("  Is Safe: {}", data.is_safe());
     } else {
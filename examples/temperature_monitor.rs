@@ -1,19 +1,44 @@
 //! Real-time temperature monitoring example
 //!
 //! Run with: cargo run --example temperature_monitor
+//!
+//! Pass `--json` to print one newline-delimited JSON snapshot per update
+//! instead, e.g. for `jq`:
+//!
+//! ```text
+//! cargo run --example temperature_monitor --features serde -- --json | jq .virtual_temperatures
+//! ```
 
-use combustion_rust_ble::{celsius_to_fahrenheit, DeviceManager, Error, ProbeMode, Result};
+use combustion_rust_ble::{celsius_to_fahrenheit, DeviceManager, Error, Probe, Result};
 use std::io::Write;
 use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging (minimal)
-    tracing_subscriber::fmt().with_env_filter("warn").init();
+    tracing_subscriber::fmt()
+        .with_env_filter("warn")
+        .with_writer(std::io::stderr)
+        .init();
+
+    let json = std::env::args().any(|arg| arg == "--json");
+    if json && !cfg!(feature = "serde") {
+        eprintln!("--json needs the serde feature: add --features serde");
+        return Ok(());
+    }
 
-    println!("Temperature Monitor");
-    println!("==================\n");
-    println!("Looking for probes...\n");
+    // In JSON mode stdout carries only records, so messages go to stderr
+    let say = |message: &str| {
+        if json {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    };
+
+    say("Temperature Monitor");
+    say("==================\n");
+    say("Looking for probes...\n");
 
     let manager = DeviceManager::new().await?;
     manager.start_scanning().await?;
@@ -27,24 +52,28 @@ async fn main() -> Result<()> {
             identifier: "any".to_string(),
         })?;
 
-    println!("Found probe: {}", probe.serial_number_string());
-    println!("Connecting...\n");
+    say(&format!("Found probe: {}", probe.serial_number_string()));
+    say("Connecting...\n");
 
     // Connect to get faster updates
     probe.connect().await?;
 
-    println!("Connected! Monitoring temperatures...");
-    println!("Press Ctrl+C to exit.\n");
+    say("Connected! Monitoring temperatures...");
+    say("Press Ctrl+C to exit.\n");
 
-    // Monitor loop
-    loop {
-        tokio::select! {
-            _ = tokio::signal::ctrl_c() => {
-                println!("\n\nExiting...");
-                break;
-            }
-            _ = tokio::time::sleep(Duration::from_millis(500)) => {
-                display_temperatures(&probe);
+    if json {
+        stream_json(&probe).await?;
+    } else {
+        // Monitor loop
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    println!("\n\nExiting...");
+                    break;
+                }
+                _ = tokio::time::sleep(Duration::from_millis(500)) => {
+                    display_temperatures(&probe);
+                }
             }
         }
     }
@@ -55,7 +84,32 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn display_temperatures(probe: &combustion_rust_ble::Probe) {
+/// Print a JSON snapshot of the probe on each temperature update until
+/// Ctrl+C.
+#[cfg(feature = "serde")]
+async fn stream_json(probe: &Probe) -> Result<()> {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut updates = probe.subscribe_temperatures();
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            update = updates.recv() => match update {
+                Ok(_) | Err(RecvError::Lagged(_)) => {
+                    println!("{}", probe.snapshot().to_json_line()?);
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+async fn stream_json(_probe: &Probe) -> Result<()> {
+    Ok(())
+}
+
+fn display_temperatures(probe: &Probe) {
     // Clear screen and move cursor to top
     print!("\x1B[2J\x1B[1;1H");
 
@@ -82,10 +136,13 @@ fn display_temperatures(probe: &combustion_rust_ble::Probe) {
             "  Core:    {:6.1}°C ({:6.1}°F) [from {}]",
             core,
             celsius_to_fahrenheit(core),
-            sel.core_sensor_name()
+            sel.core().map_or("?", |s| s.name())
         );
     } else {
-        println!("  Core:    -- [from {}]", sel.core_sensor_name());
+        println!(
+            "  Core:    -- [from {}]",
+            sel.core().map_or("?", |s| s.name())
+        );
     }
 
     if let Some(surface) = vt.surface {
@@ -93,10 +150,13 @@ fn display_temperatures(probe: &combustion_rust_ble::Probe) {
             "  Surface: {:6.1}°C ({:6.1}°F) [from {}]",
             surface,
             celsius_to_fahrenheit(surface),
-            sel.surface_sensor_name()
+            sel.surface().map_or("?", |s| s.name())
         );
     } else {
-        println!("  Surface: -- [from {}]", sel.surface_sensor_name());
+        println!(
+            "  Surface: -- [from {}]",
+            sel.surface().map_or("?", |s| s.name())
+        );
     }
 
     if let Some(ambient) = vt.ambient {
@@ -104,10 +164,13 @@ fn display_temperatures(probe: &combustion_rust_ble::Probe) {
             "  Ambient: {:6.1}°C ({:6.1}°F) [from {}]",
             ambient,
             celsius_to_fahrenheit(ambient),
-            sel.ambient_sensor_name()
+            sel.ambient().map_or("?", |s| s.name())
         );
     } else {
-        println!("  Ambient: -- [from {}]", sel.ambient_sensor_name());
+        println!(
+            "  Ambient: -- [from {}]",
+            sel.ambient().map_or("?", |s| s.name())
+        );
     }
 
     // Raw sensor temperatures
@@ -115,7 +178,6 @@ fn display_temperatures(probe: &combustion_rust_ble::Probe) {
     println!("--------------------");
 
     let temps = probe.current_temperatures();
-    let is_instant_read = mode == ProbeMode::InstantRead;
 
     for (i, celsius) in temps.to_celsius().iter().enumerate() {
         let sensor_type = match i {
@@ -129,7 +191,7 @@ fn display_temperatures(probe: &combustion_rust_ble::Probe) {
         };
 
         // In Instant Read mode, only T1 has valid data - others are set to 0 by the probe
-        if is_instant_read && i > 0 {
+        if !temps.is_active(i) {
             println!("  T{}: N/A (Instant Read mode) - {}", i + 1, sensor_type);
         } else if let Some(c) = celsius {
             println!(
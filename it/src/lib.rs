@@ -0,0 +1,12 @@
+//! End-to-end tests for `combustion-rust-ble`.
+//!
+//! The tests in `tests/` drive complete flows through [`DeviceManager`] and
+//! [`Probe`] against simulated probes, so they need no Bluetooth hardware.
+//! Run them with `cargo test -p combustion-rust-ble-it`.
+//!
+//! `tests/stress.rs` is a soak test that cycles discovery and connections
+//! for a simulated half hour, failing if tasks, memory or channel lag grow.
+//! Set `COMBUSTION_STRESS_HOURS` to soak for longer.
+//!
+//! [`DeviceManager`]: https://docs.rs/combustion-rust-ble/latest/combustion_rust_ble/struct.DeviceManager.html
+//! [`Probe`]: https://docs.rs/combustion-rust-ble/latest/combustion_rust_ble/struct.Probe.html
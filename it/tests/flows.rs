@@ -0,0 +1,1421 @@
+//! Complete probe flows against simulated probes.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use combustion_rust_ble::frames::DEFAULT_FRAME_INTERVAL;
+use combustion_rust_ble::persistence::{KnownProbeRegistry, TimerStore};
+use combustion_rust_ble::protocol::uart_messages::{
+    build_set_high_low_alarms_request, build_set_prediction_request,
+};
+use combustion_rust_ble::protocol::UartMessageType;
+use combustion_rust_ble::simulator::{Fault, FaultScript, SimulatedProbe};
+use combustion_rust_ble::{
+    AlarmBound, AlarmConfig, AlarmEvent, BatteryModel, BatteryProfiles, ClaimAction, ClaimOptions,
+    ConfigCommand, ConfigKind, ConfigOutcome, ConnectionState, CookSpec, DeviceManager, Error,
+    FirmwareVersion, FoodSafeProduct, ManagerState, OverheatPolicy, PayloadSource, PowerMode,
+    PredictionMode, PredictionState, Probe, ProbeColor, ProbeDefaults, ProbeId, ProbeMatch,
+    ProbeMode, ProductType, ProtocolMismatch, ReadingSource, SensorGroup, StatusUnavailable,
+    TemperatureUnit, VirtualSensor, MAX_PROBES,
+};
+
+const SERIAL: u32 = 0x1000_0001;
+
+/// Start a manager for `sim` and wait for its probe to be discovered.
+///
+/// Returns half an interval after the simulator started, so later waits end
+/// between notifications rather than racing them.
+async fn discover(sim: &Arc<SimulatedProbe>) -> (DeviceManager, Arc<Probe>) {
+    let manager = DeviceManager::with_simulated([sim.clone()]);
+    let mut discovered = manager.subscribe_probe_discovered();
+    manager.start_scanning().await.unwrap();
+
+    let probe = discovered.recv().await.unwrap();
+    assert_eq!(probe.serial_number(), SERIAL);
+    assert!(manager.get_probe("10000001").is_some());
+
+    tokio::time::sleep(SimulatedProbe::DEFAULT_INTERVAL / 2).await;
+    (manager, probe)
+}
+
+/// Let `n` notification intervals pass.
+async fn ticks(n: u32) {
+    tokio::time::sleep(SimulatedProbe::DEFAULT_INTERVAL * n).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_full_cook_flow() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+
+    // Connect
+    probe.connect().await.unwrap();
+    assert_eq!(probe.connection_state(), ConnectionState::Connected);
+
+    // Log sequence range tracks the records the probe has taken
+    ticks(3).await;
+    let max_sequence = probe.max_sequence_number();
+    assert!(max_sequence >= 3);
+    ticks(2).await;
+    assert_eq!(probe.max_sequence_number(), max_sequence + 2);
+
+    // Set prediction
+    probe
+        .set_prediction(PredictionMode::TimeToRemoval, 63.0)
+        .await
+        .unwrap();
+    ticks(1).await;
+    let prediction = probe.prediction_info().unwrap();
+    assert_eq!(prediction.mode, PredictionMode::TimeToRemoval);
+    assert_eq!(prediction.state, PredictionState::Predicting);
+    assert!((prediction.set_point_temperature - 63.0).abs() < 0.05);
+
+    // Alarm trips once the core crosses it
+    probe.set_core_high_alarm(60.0).await.unwrap();
+    let watch = probe.watch_threshold(VirtualSensor::Core, 60.0);
+    ticks(1).await;
+    assert!(!probe.any_alarm_tripped());
+
+    sim.set_temperatures([61.0, 62.0, 64.0, 66.0, 90.0, 120.0, 150.0, 150.0]);
+    assert_eq!(watch.await.unwrap(), 61.0);
+    tokio::time::sleep(SimulatedProbe::DEFAULT_INTERVAL / 2).await;
+    assert!(probe.any_alarm_tripped());
+    assert!(probe.any_alarm_alarming());
+
+    probe.silence_alarms().await.unwrap();
+    ticks(1).await;
+    assert!(probe.any_alarm_tripped());
+    assert!(!probe.any_alarm_alarming());
+
+    // Disconnect
+    probe.disconnect().await.unwrap();
+    assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+    assert!(matches!(
+        probe.silence_alarms().await,
+        Err(Error::NotConnected)
+    ));
+
+    let commands: Vec<_> = sim.commands().iter().map(|c| c.message_type()).collect();
+    assert_eq!(
+        commands,
+        vec![
+            UartMessageType::SetPrediction,
+            UartMessageType::SetHighLowAlarms,
+            UartMessageType::SilenceAlarms,
+        ]
+    );
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_reconnect_after_scripted_disconnect() {
+    let sim = Arc::new(
+        SimulatedProbe::new(SERIAL).with_script(
+            FaultScript::new()
+                .at(Duration::from_millis(2500), Fault::Disconnect)
+                .at(Duration::from_millis(2500), Fault::RefuseConnections(2)),
+        ),
+    );
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    ticks(3).await;
+    assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+    let sequence_at_disconnect = probe.max_sequence_number();
+
+    // Retry until the simulator accepts the connection again
+    let mut attempts = 0;
+    while probe.connect().await.is_err() {
+        attempts += 1;
+        ticks(1).await;
+    }
+    assert_eq!(attempts, 2);
+
+    ticks(2).await;
+    assert!(probe.max_sequence_number() > sequence_at_disconnect);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_lossy_notifications_keep_last_good_state() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    manager.quarantine().set_capacity(4);
+    probe.connect().await.unwrap();
+    ticks(1).await;
+    let before = probe.max_sequence_number();
+
+    sim.inject(Fault::DropNotifications(1));
+    sim.inject(Fault::CorruptFrames(1));
+    ticks(2).await;
+    // Both notifications were lost; the probe still has the last good status
+    assert_eq!(probe.max_sequence_number(), before);
+
+    // The corrupt one was kept for diagnosis
+    let quarantined = manager.quarantine().payloads();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].source, PayloadSource::Status);
+    assert_eq!(quarantined[0].serial_number, Some(SERIAL));
+    assert_eq!(quarantined[0].data.len(), 8);
+
+    ticks(1).await;
+    assert_eq!(probe.max_sequence_number(), before + 3);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_repeated_parse_failures_raise_protocol_mismatch() {
+    let threshold = ProtocolMismatch::FAILURE_THRESHOLD;
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_firmware_revision("v9.0.0"));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+    let mut mismatches = probe.subscribe_protocol_mismatch();
+
+    // A good notification in between restarts the count
+    sim.inject(Fault::CorruptFrames(threshold - 1));
+    ticks(threshold).await;
+    sim.inject(Fault::CorruptFrames(threshold - 1));
+    ticks(threshold - 1).await;
+    assert!(mismatches.try_recv().is_err());
+    assert!(probe.protocol_mismatch().is_none());
+
+    sim.inject(Fault::CorruptFrames(threshold));
+    ticks(1).await;
+    let mismatch = mismatches.try_recv().unwrap();
+    assert_eq!(mismatch.serial_number, SERIAL);
+    assert_eq!(mismatch.consecutive_failures, threshold);
+    assert_eq!(
+        mismatch.firmware_version,
+        Some(FirmwareVersion::new(9, 0, 0))
+    );
+    assert_eq!(probe.protocol_mismatch(), Some(mismatch));
+
+    // Further notifications are ignored, good or bad
+    let quarantined = manager.quarantine().len();
+    let sequence = probe.max_sequence_number();
+    ticks(threshold + 2).await;
+    assert!(mismatches.try_recv().is_err());
+    assert_eq!(manager.quarantine().len(), quarantined);
+    assert_eq!(probe.max_sequence_number(), sequence);
+
+    // Until the probe reconnects
+    probe.disconnect().await.unwrap();
+    probe.connect().await.unwrap();
+    assert!(probe.protocol_mismatch().is_none());
+    ticks(2).await;
+    assert!(probe.max_sequence_number() > sequence);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test(start_paused = true)]
+async fn test_export_encrypted_session_bundle() {
+    use combustion_rust_ble::encryption::{is_encrypted, EncryptionKey};
+
+    let dir = std::env::temp_dir().join(format!(
+        "combustion-it-encrypted-bundle-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+    ticks(3).await;
+
+    let key = EncryptionKey::generate();
+    let bundle = probe
+        .export_session_bundle_encrypted(&dir, TemperatureUnit::Celsius, &key)
+        .unwrap();
+    assert!(!bundle.files.is_empty());
+    for path in &bundle.files {
+        let data = std::fs::read(path).unwrap();
+        assert!(is_encrypted(&data), "{} is not encrypted", path.display());
+    }
+    let log = key
+        .decrypt(&std::fs::read(dir.join("log.csv")).unwrap())
+        .unwrap();
+    assert!(String::from_utf8(log)
+        .unwrap()
+        .starts_with("# Combustion probe log\n# Probe S/N: 10000001\n"));
+
+    manager.shutdown().await.unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_export_session_bundle() {
+    let dir = std::env::temp_dir().join(format!("combustion-it-bundle-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_firmware_revision("v1.4.0"));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+    probe.set_color(ProbeColor::Blue).await.unwrap();
+    probe.annotate("Prediction jumped by an hour");
+    ticks(3).await;
+
+    let bundle = probe
+        .export_session_bundle(&dir, TemperatureUnit::Fahrenheit)
+        .unwrap();
+    assert_eq!(bundle.dir, dir);
+    let names: Vec<_> = bundle
+        .files
+        .iter()
+        .map(|path| path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    // The chart is only written when the library is built with charts,
+    // which other workspace members may enable
+    let charted = names.last() == Some(&"chart.svg");
+    let fixed = &names[..names.len() - usize::from(charted)];
+    assert_eq!(
+        fixed,
+        [
+            "log.csv",
+            "events.json",
+            "audit.json",
+            "diagnostics.json",
+            "report.txt",
+        ]
+    );
+
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+    let log = read("log.csv");
+    assert!(log.starts_with("# Combustion probe log\n# Probe S/N: 10000001\n"));
+    assert!(log.contains("# Firmware: v1.4.0\n"));
+    assert!(log.contains("# Units: Fahrenheit\n"));
+    assert!(read("events.json").contains("Prediction jumped by an hour"));
+    assert!(read("audit.json").contains("Blue"));
+    assert!(read("diagnostics.json").contains("\"library_version\""));
+    let report = read("report.txt");
+    assert!(report.starts_with("Cook report for probe 10000001\n"));
+    assert!(report.contains("Firmware: 1.4.0"));
+    assert!(report.contains("1 annotations, 0 overheat events, 1 configuration commands"));
+    assert!(report.contains("Core: ") && report.contains("°F"));
+    assert!(!report.contains("°C"));
+    if charted {
+        assert!(read("chart.svg").contains("Probe 10000001"));
+    }
+
+    // Exporting again replaces the bundle
+    probe
+        .export_session_bundle(&dir, TemperatureUnit::Celsius)
+        .unwrap();
+    assert!(read("log.csv").contains("# Units: Celsius\n"));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), names.len());
+
+    manager.shutdown().await.unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_rssi_fade_reaches_probe() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    ticks(1).await;
+    assert_eq!(probe.rssi(), Some(-60));
+
+    sim.inject(Fault::RssiFade {
+        to: -95,
+        over: Duration::from_secs(5),
+    });
+    ticks(6).await;
+    assert_eq!(probe.rssi(), Some(-95));
+    assert_eq!(manager.get_nearest_probe().unwrap().serial_number(), SERIAL);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_probe_id_conflicts_are_reported_and_resolved() {
+    let sims: Vec<_> = (1..=3)
+        .map(|n| Arc::new(SimulatedProbe::new(SERIAL + n)))
+        .collect();
+    let manager = DeviceManager::with_simulated(sims.clone());
+    let mut conflicts = manager.subscribe_probe_id_conflicts();
+    manager.start_scanning().await.unwrap();
+
+    // Every simulator starts on ID 1
+    let conflict = conflicts.recv().await.unwrap();
+    assert_eq!(conflict.id, ProbeId::new(1));
+    assert_eq!(
+        conflict.serial_numbers,
+        vec!["10000002", "10000003", "10000004"]
+    );
+
+    // The disconnected probe keeps its ID
+    for sim in &sims[1..] {
+        sim.probe().connect().await.unwrap();
+    }
+    let assigned = manager.auto_assign_ids().await.unwrap();
+    assert_eq!(
+        assigned,
+        vec![
+            ("10000003".to_string(), ProbeId::new(2)),
+            ("10000004".to_string(), ProbeId::new(3)),
+        ]
+    );
+
+    ticks(2).await;
+    assert_eq!(sims[2].probe().id(), ProbeId::new(3));
+    assert!(manager.probe_id_conflicts().is_empty());
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_start_and_end_cook() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    let spec = CookSpec::new(FoodSafeProduct::ChickenBreast, 70.0)
+        .with_carryover(3.0)
+        .with_target_alarm();
+    probe.start_cook(spec.clone()).await.unwrap();
+    assert_eq!(probe.active_cook(), Some(spec));
+    ticks(1).await;
+    let prediction = probe.prediction_info().unwrap();
+    assert_eq!(prediction.state, PredictionState::Predicting);
+    assert!((prediction.remove_at_temperature() - 67.0).abs() < 0.05);
+    assert!((prediction.serve_at_temperature() - 70.0).abs() < 0.05);
+    assert!(probe.any_alarm_enabled());
+
+    probe.end_cook().await.unwrap();
+    ticks(1).await;
+    assert!(probe.active_cook().is_none());
+    assert_eq!(probe.prediction_info().unwrap().mode, PredictionMode::None);
+    assert!(!probe.any_alarm_enabled());
+
+    let commands: Vec<_> = sim.commands().iter().map(|c| c.message_type()).collect();
+    assert_eq!(
+        commands,
+        vec![
+            UartMessageType::SetPrediction,
+            UartMessageType::ConfigureFoodSafe,
+            UartMessageType::SetHighLowAlarms,
+            UartMessageType::SetPrediction,
+            UartMessageType::ResetFoodSafe,
+            UartMessageType::SetHighLowAlarms,
+        ]
+    );
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_start_cook_rolls_back_on_failure() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    sim.inject(Fault::RejectCommand(UartMessageType::SetHighLowAlarms));
+    let spec = CookSpec::new(FoodSafeProduct::ChickenBreast, 70.0).with_target_alarm();
+    assert!(matches!(
+        probe.start_cook(spec).await,
+        Err(Error::ProbeError { .. })
+    ));
+    assert!(probe.active_cook().is_none());
+
+    ticks(1).await;
+    assert_eq!(probe.prediction_info().unwrap().mode, PredictionMode::None);
+    assert!(!probe.any_alarm_enabled());
+
+    // The rejected alarm write is not recorded; the rest are undone in reverse
+    let commands: Vec<_> = sim.commands().iter().map(|c| c.message_type()).collect();
+    assert_eq!(
+        commands,
+        vec![
+            UartMessageType::SetPrediction,
+            UartMessageType::ConfigureFoodSafe,
+            UartMessageType::SetHighLowAlarms,
+            UartMessageType::ResetFoodSafe,
+            UartMessageType::SetPrediction,
+        ]
+    );
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_defaults_start_cook_by_probe_color() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_color(ProbeColor::Red));
+    let (manager, probe) = discover(&sim).await;
+    let poultry = CookSpec::new(FoodSafeProduct::ChickenBreast, 74.0);
+    manager.set_probe_defaults(
+        ProbeDefaults::new()
+            .with(ProbeMatch::Color(ProbeColor::Red), poultry.clone())
+            .with(
+                ProbeMatch::Color(ProbeColor::Blue),
+                CookSpec::new(FoodSafeProduct::BeefSteak, 54.0),
+            ),
+    );
+    let mut applied = probe.subscribe_defaults_applied();
+
+    probe.connect().await.unwrap();
+    let event = applied.try_recv().unwrap();
+    assert_eq!(event.serial_number, SERIAL);
+    assert_eq!(event.probe, ProbeMatch::Color(ProbeColor::Red));
+    assert_eq!(event.spec, poultry);
+    assert_eq!(probe.active_cook(), Some(poultry.clone()));
+    ticks(1).await;
+    assert!((probe.prediction_info().unwrap().set_point_temperature - 74.0).abs() < 0.05);
+
+    // Reconnecting mid-cook leaves the running cook alone
+    probe.disconnect().await.unwrap();
+    probe.connect().await.unwrap();
+    assert!(applied.try_recv().is_err());
+    let commands: Vec<_> = sim.commands().iter().map(|c| c.message_type()).collect();
+    assert_eq!(
+        commands,
+        vec![
+            UartMessageType::SetPrediction,
+            UartMessageType::ConfigureFoodSafe,
+        ]
+    );
+    manager.shutdown().await.unwrap();
+
+    // A cook the probe already runs, e.g. from before an app restart, is
+    // left alone too
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_color(ProbeColor::Red));
+    sim.apply_external_command(&build_set_prediction_request(1, 630));
+    let (manager, probe) = discover(&sim).await;
+    manager
+        .set_probe_defaults(ProbeDefaults::new().with(ProbeMatch::Color(ProbeColor::Red), poultry));
+    let mut applied = probe.subscribe_defaults_applied();
+    probe.connect().await.unwrap();
+    assert!(applied.try_recv().is_err());
+    assert!(sim.commands().is_empty());
+    assert_eq!(
+        probe.prediction_info().unwrap().mode,
+        PredictionMode::TimeToRemoval
+    );
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_annotations_follow_the_session_log() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    ticks(2).await;
+    let annotation = probe.annotate("wrapped in foil");
+    assert_eq!(
+        annotation.sequence_number,
+        Some(probe.max_sequence_number())
+    );
+
+    let log = probe.temperature_log();
+    assert_eq!(log.annotations, vec![annotation]);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_overheat_policy_silences_alarms() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    probe.set_overheat_policy(OverheatPolicy::new().with_limit(SensorGroup::Handle, 300.0));
+    let mut events = probe.subscribe_overheat();
+    ticks(1).await;
+    assert!(probe.overheat_events().is_empty());
+
+    sim.set_temperatures([60.0, 60.0, 60.0, 70.0, 150.0, 220.0, 280.0, 320.0]);
+    ticks(1).await;
+    let event = events.try_recv().unwrap();
+    assert_eq!(event.group, SensorGroup::Handle);
+    assert_eq!(event.sensors, vec![7]);
+    assert_eq!(probe.overheat_events(), vec![event]);
+    let annotations = probe.annotations();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].text, "Handle sensors overheating");
+
+    // One event per episode, and alarms silenced once
+    ticks(2).await;
+    assert!(events.try_recv().is_err());
+    let silenced = sim
+        .commands()
+        .iter()
+        .filter(|c| c.message_type() == UartMessageType::SilenceAlarms)
+        .count();
+    assert_eq!(silenced, 1);
+    let history = probe.command_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].command, ConfigCommand::SilenceAlarms);
+    assert_eq!(history[0].outcome, ConfigOutcome::Sent);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_external_source_joins_the_probe_list() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, _probe) = discover(&sim).await;
+
+    let pit = manager.add_external_source("pit", 0xE000_0001).unwrap();
+    pit.assign(VirtualSensor::Ambient, 0).unwrap();
+    assert!(manager.add_external_source("pit", 0xE000_0001).is_err());
+    assert_eq!(manager.probe_count(), 2);
+
+    // Both appear as devices, the pseudo-probe without a product type
+    let devices = manager.devices();
+    let kinds: Vec<_> = devices
+        .iter()
+        .map(|d| (d.serial_number(), d.product_type()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (SERIAL, ProductType::PredictiveProbe),
+            (0xE000_0001, ProductType::Unknown(0))
+        ]
+    );
+    assert!(devices[0].as_probe().is_some());
+
+    // Snapshots of both are taken together
+    let snapshots = manager.probes_snapshot();
+    let serials: Vec<_> = snapshots.iter().map(|s| s.serial_number).collect();
+    assert_eq!(serials, vec![SERIAL, 0xE000_0001]);
+    assert_eq!(snapshots[0].captured_at, snapshots[1].captured_at);
+    let mut visited = 0;
+    manager.for_each_probe(|_| visited += 1);
+    assert_eq!(visited, manager.probe_count());
+
+    let external = manager.get_probe("E0000001").unwrap();
+    let watch = external.watch_threshold(VirtualSensor::Ambient, 110.0);
+    pit.push(&[Some(100.0)]).unwrap();
+    pit.push(&[Some(120.0)]).unwrap();
+    assert!((watch.await.unwrap() - 120.0).abs() < 0.1);
+
+    // Sharing the real probe's ID is not a conflict
+    ticks(2).await;
+    assert!(manager.probe_id_conflicts().is_empty());
+
+    assert!(manager.remove_external_source(&pit));
+    assert!(manager.get_probe("E0000001").is_none());
+
+    // External sources don't count toward the probe limit
+    for i in 0..MAX_PROBES as u32 {
+        manager
+            .add_external_source("bank", 0xE100_0000 + i)
+            .unwrap();
+    }
+    assert_eq!(manager.probe_count(), MAX_PROBES + 1);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_probes_snapshot_is_consistent_while_probes_change() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, _probe) = discover(&sim).await;
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..200 {
+                let pit = manager.add_external_source("pit", 0xE000_0001).unwrap();
+                assert!(manager.remove_external_source(&pit));
+            }
+        });
+        for _ in 0..200 {
+            // Each snapshot sees the pseudo-probe either fully there or not
+            // at all, and all of it shares one capture time
+            let snapshots = manager.probes_snapshot();
+            let serials: Vec<_> = snapshots.iter().map(|s| s.serial_number).collect();
+            assert!(serials == [SERIAL] || serials == [SERIAL, 0xE000_0001]);
+            assert!(snapshots
+                .iter()
+                .all(|s| s.captured_at == snapshots[0].captured_at));
+        }
+    });
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_cook_group_aggregates_members() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, _probe) = discover(&sim).await;
+
+    let left = manager.add_external_source("left", 0xE000_0001).unwrap();
+    let right = manager.add_external_source("right", 0xE000_0002).unwrap();
+    left.assign(VirtualSensor::Core, 0).unwrap();
+    right.assign(VirtualSensor::Core, 0).unwrap();
+    assert!(manager
+        .cook_group("roast", &["E0000001", "FFFFFFFF"])
+        .is_err());
+
+    let group = manager
+        .cook_group("roast", &["E0000001", "E0000002"])
+        .unwrap();
+    assert_eq!(group.len(), 2);
+    let mut updates = group.subscribe();
+
+    left.push(&[Some(50.0)]).unwrap();
+    let update = updates.recv().await.unwrap();
+    assert_eq!(update.core.unwrap().count, 1);
+
+    right.push(&[Some(60.0)]).unwrap();
+    let core = updates.recv().await.unwrap().core.unwrap();
+    assert_eq!(core.count, 2);
+    assert!((core.mean - 55.0).abs() < 0.1);
+    assert!((core.min - 50.0).abs() < 0.1);
+    assert!((core.max - 60.0).abs() < 0.1);
+
+    // Removed members no longer contribute
+    assert!(group.remove(0xE000_0002));
+    left.push(&[Some(52.0)]).unwrap();
+    let core = updates.recv().await.unwrap().core.unwrap();
+    assert_eq!(core.count, 1);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_pause_keeps_probes_until_resumed() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+    let mut states = manager.subscribe_state();
+    assert_eq!(manager.state(), ManagerState::Scanning);
+
+    manager.pause().await.unwrap();
+    assert_eq!(states.recv().await.unwrap(), ManagerState::Paused);
+    assert!(manager.is_paused());
+    assert!(!manager.is_scanning());
+    assert!(manager.get_probe("10000001").is_some());
+
+    // The connection drops while the host sleeps
+    sim.inject(Fault::Disconnect);
+    ticks(1).await;
+    assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+
+    manager.resume().await.unwrap();
+    assert_eq!(states.recv().await.unwrap(), ManagerState::Scanning);
+    assert!(manager.is_scanning());
+    assert_eq!(manager.probe_count(), 1);
+
+    // The probe is reconnected and updates carry on
+    ticks(1).await;
+    assert_eq!(probe.connection_state(), ConnectionState::Connected);
+    let max_sequence = probe.max_sequence_number();
+    ticks(2).await;
+    assert!(probe.max_sequence_number() > max_sequence);
+
+    manager.shutdown().await.unwrap();
+    assert_eq!(manager.state(), ManagerState::Idle);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_known_probes_are_listed_offline_and_auto_connected() {
+    let path = std::env::temp_dir().join(format!(
+        "combustion-it-known-probes-{}.json",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    // Pair the probe in an earlier run
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    let registry = KnownProbeRegistry::open(&path).unwrap();
+    registry.remember(&probe).unwrap();
+    registry
+        .set_label(SERIAL, Some("Brisket".to_string()))
+        .unwrap();
+    manager.shutdown().await.unwrap();
+
+    // On the next launch it is listed before it is discovered
+    let own = Arc::new(SimulatedProbe::new(SERIAL));
+    let neighbour = Arc::new(SimulatedProbe::new(0x1000_0002));
+    let manager = DeviceManager::with_simulated([own, neighbour]);
+    manager.set_known_probes(Arc::new(KnownProbeRegistry::open(&path).unwrap()));
+    let statuses = manager.known_probe_statuses();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].known.label.as_deref(), Some("Brisket"));
+    assert!(!statuses[0].is_online());
+
+    manager.start_scanning().await.unwrap();
+    ticks(2).await;
+    assert!(manager.known_probe_statuses()[0].is_online());
+
+    // Only the user's own probe is connected
+    let own = manager.get_probe("10000001").unwrap();
+    let neighbour = manager.get_probe("10000002").unwrap();
+    assert_eq!(own.connection_state(), ConnectionState::Connected);
+    assert_eq!(neighbour.connection_state(), ConnectionState::Disconnected);
+
+    // A probe the user disconnected stays disconnected
+    own.disconnect().await.unwrap();
+    manager.set_known_probes(manager.known_probes().unwrap());
+    ticks(2).await;
+    assert_eq!(own.connection_state(), ConnectionState::Disconnected);
+
+    manager.shutdown().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_set_points_limited_to_the_status_field() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_firmware_revision("1.4.0"));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    // The set point is 10 bits of 0.1°C whatever the firmware
+    assert_eq!(
+        probe.capabilities().firmware_version,
+        Some(FirmwareVersion::new(1, 4, 0))
+    );
+    assert!(matches!(
+        probe.set_prediction(PredictionMode::TimeToRemoval, 150.0).await,
+        Err(Error::TemperatureOutOfRange { max, .. }) if (max - 102.3).abs() < 1e-9
+    ));
+    assert!(sim.commands().is_empty());
+
+    probe
+        .set_prediction(PredictionMode::TimeToRemoval, 102.3)
+        .await
+        .unwrap();
+    assert_eq!(sim.commands()[0].payload, vec![0xFF, 0x07]);
+    ticks(1).await;
+    let prediction = probe.prediction_info().unwrap();
+    assert_eq!(prediction.mode, PredictionMode::TimeToRemoval);
+    assert!((prediction.set_point_temperature - 102.3).abs() < 1e-9);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_dropping_manager_disconnects_probes() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+    let mut discovered = manager.subscribe_probe_discovered();
+
+    drop(manager);
+    ticks(1).await;
+    assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+    assert!(matches!(
+        discovered.recv().await,
+        Err(tokio::sync::broadcast::error::RecvError::Closed)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_shutdown_guard_stops_scanning() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let manager = DeviceManager::with_simulated([sim.clone()]);
+    let mut discovered = manager.subscribe_probe_discovered();
+
+    {
+        let scanning = manager.start_scanning_guarded().await.unwrap();
+        let probe = discovered.recv().await.unwrap();
+        probe.connect().await.unwrap();
+        assert_eq!(scanning.state(), ManagerState::Scanning);
+
+        drop(scanning);
+        assert_eq!(manager.state(), ManagerState::Idle);
+        assert!(!manager.is_scanning());
+        assert_eq!(manager.probe_count(), 0);
+
+        ticks(1).await;
+        assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+    }
+
+    // An explicit shutdown has finished when it returns
+    let scanning = manager.start_scanning_guarded().await.unwrap();
+    let probe = discovered.recv().await.unwrap();
+    probe.connect().await.unwrap();
+    scanning.shutdown().await.unwrap();
+    assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+    assert_eq!(manager.probe_count(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_select_probe_by_proximity() {
+    let near = Arc::new(SimulatedProbe::new(SERIAL));
+    let held = Arc::new(SimulatedProbe::new(0x1000_0002));
+    let manager = DeviceManager::with_simulated([near.clone(), held.clone()]);
+    manager.start_scanning().await.unwrap();
+    near.inject(Fault::RssiFade {
+        to: -40,
+        over: Duration::ZERO,
+    });
+
+    let selection = manager.select_probe_interactively(15);
+    tokio::pin!(selection);
+    assert!(tokio::time::timeout(Duration::from_secs(5), &mut selection)
+        .await
+        .is_err());
+
+    held.inject(Fault::RssiFade {
+        to: -35,
+        over: Duration::ZERO,
+    });
+    let probe = tokio::time::timeout(Duration::from_secs(5), selection)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(probe.serial_number(), 0x1000_0002);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_claim_probe_by_physical_action() {
+    let path = std::env::temp_dir().join(format!(
+        "combustion-it-claimed-probes-{}.json",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let own = Arc::new(SimulatedProbe::new(SERIAL));
+    let neighbour = Arc::new(SimulatedProbe::new(0x1000_0002));
+    let manager = DeviceManager::with_simulated([own.clone(), neighbour.clone()]);
+    manager.start_scanning().await.unwrap();
+    ticks(2).await;
+
+    // Nothing happens to either probe
+    let options = ClaimOptions::new().with_window(Duration::from_secs(5));
+    assert!(manager
+        .claim_probe(options.clone())
+        .await
+        .unwrap()
+        .is_none());
+
+    // Both probes switched at once are ambiguous
+    let claim = manager.claim_probe(options.clone());
+    tokio::pin!(claim);
+    assert!(tokio::time::timeout(Duration::from_secs(1), &mut claim)
+        .await
+        .is_err());
+    own.set_mode(ProbeMode::InstantRead);
+    neighbour.set_mode(ProbeMode::InstantRead);
+    assert!(claim.await.unwrap().is_none());
+
+    // The user's probe alone is taken out of instant read
+    manager.set_known_probes(Arc::new(KnownProbeRegistry::open(&path).unwrap()));
+    let claim = manager.claim_probe(options);
+    tokio::pin!(claim);
+    assert!(tokio::time::timeout(Duration::from_secs(1), &mut claim)
+        .await
+        .is_err());
+    own.set_mode(ProbeMode::Normal);
+    let claim = claim.await.unwrap().unwrap();
+    assert_eq!(claim.probe.serial_number(), SERIAL);
+    assert_eq!(
+        claim.action,
+        ClaimAction::ModeChanged {
+            from: ProbeMode::InstantRead,
+            to: ProbeMode::Normal
+        }
+    );
+    assert!(claim.remembered);
+    assert!(manager.known_probes().unwrap().contains(SERIAL));
+    assert!(!manager.known_probes().unwrap().contains(0x1000_0002));
+
+    // A probe that never settles doesn't hold the claim open
+    let fidget = tokio::spawn({
+        let own = own.clone();
+        async move {
+            for mode in [ProbeMode::InstantRead, ProbeMode::Normal]
+                .into_iter()
+                .cycle()
+            {
+                own.set_mode(mode);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    });
+    let started = tokio::time::Instant::now();
+    let options = ClaimOptions::new().with_window(Duration::from_secs(5));
+    assert!(manager
+        .claim_probe(options.clone())
+        .await
+        .unwrap()
+        .is_none());
+    assert!(started.elapsed() <= options.window + options.quiet_period + Duration::from_secs(1));
+    fidget.abort();
+
+    assert!(manager
+        .claim_probe(ClaimOptions::new().with_spike(0.0))
+        .await
+        .is_err());
+
+    manager.shutdown().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_rssi_refreshed_while_advertising_stops() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).without_advertising_while_connected());
+    let (manager, probe) = discover(&sim).await;
+    assert_eq!(probe.rssi(), Some(-60));
+
+    probe.connect().await.unwrap();
+    sim.inject(Fault::RssiFade {
+        to: -80,
+        over: Duration::ZERO,
+    });
+    ticks(2).await;
+    assert_eq!(probe.rssi(), Some(-60));
+
+    // Read over the connection once advertisements have been missing a while
+    tokio::time::sleep(Probe::RSSI_REFRESH_INTERVAL + Duration::from_secs(1)).await;
+    assert_eq!(probe.rssi(), Some(-80));
+
+    probe.disconnect().await.unwrap();
+    assert!(matches!(
+        probe.refresh_rssi().await,
+        Err(Error::NotConnected)
+    ));
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_battery_warning_ahead_of_low_flag() {
+    const MINUTE: Duration = Duration::from_secs(60);
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_firmware_revision("1.4.0"));
+    let (manager, probe) = discover(&sim).await;
+    let short = BatteryModel::new(MINUTE * 10).with_warning_lead(MINUTE * 4);
+    probe.set_battery_profiles(
+        BatteryProfiles::default().with_firmware(FirmwareVersion::new(1, 4, 0), short),
+    );
+    let mut warnings = probe.subscribe_battery_warnings();
+
+    // The model applies once the firmware revision is known
+    assert_eq!(probe.battery_model(), BatteryModel::default());
+    probe.connect().await.unwrap();
+    assert_eq!(probe.battery_model(), short);
+
+    tokio::time::sleep(MINUTE * 5).await;
+    assert!(warnings.try_recv().is_err());
+    tokio::time::sleep(MINUTE * 2).await;
+    let warning = warnings.try_recv().unwrap();
+    assert_eq!(warning.serial_number, SERIAL);
+    assert!(!warning.battery_low);
+    assert!(warning.estimated_remaining <= MINUTE * 4);
+    assert!(warning.on_time >= MINUTE * 6);
+
+    // Once per charge; charging clears the low flag and, once it stays
+    // clear, the count
+    sim.set_battery_low(true);
+    ticks(2).await;
+    assert!(warnings.try_recv().is_err());
+    sim.set_battery_low(false);
+    ticks(2).await;
+    assert!(probe.battery_on_time() >= MINUTE * 7);
+    tokio::time::sleep(MINUTE * 5).await;
+    assert!(probe.battery_on_time() < MINUTE);
+    assert!(probe.estimated_battery_remaining() > MINUTE * 9);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_missing_status_characteristic_is_diagnosed() {
+    let sim = Arc::new(
+        SimulatedProbe::new(SERIAL)
+            .with_firmware_revision("1.2.0")
+            .without_status_characteristic(),
+    );
+    let (manager, probe) = discover(&sim).await;
+    assert!(probe.capabilities().status_characteristic);
+    let mut diagnostics = probe.subscribe_status_unavailable();
+
+    probe.connect().await.unwrap();
+    let diagnostic = diagnostics.try_recv().unwrap();
+    assert_eq!(diagnostic.serial_number, SERIAL);
+    assert_eq!(
+        diagnostic.firmware_version,
+        Some(FirmwareVersion::new(1, 2, 0))
+    );
+    assert!(diagnostic.to_string().contains("prediction, food safety"));
+    assert!(!probe.capabilities().status_characteristic);
+
+    // Readings keep coming from advertising, without a log or prediction
+    sim.set_temperatures([60.0; 8]);
+    ticks(2).await;
+    assert_eq!(
+        probe.current_temperatures().values[0].to_celsius(),
+        Some(60.0)
+    );
+    assert!(probe.prediction_info().is_none());
+    assert_eq!(probe.max_sequence_number(), 0);
+    assert!(StatusUnavailable::UNAVAILABLE.contains(&"prediction"));
+
+    // Session information and the log are polled over UART instead
+    assert!(!StatusUnavailable::UNAVAILABLE.contains(&"temperature log"));
+    let polled: Vec<_> = sim.commands().iter().map(|c| c.message_type()).collect();
+    assert!(polled.contains(&UartMessageType::ReadSessionInfo));
+    assert!(polled.contains(&UartMessageType::ReadLogs));
+
+    // The responses fill in the session and the log
+    ticks(5).await;
+    assert_eq!(probe.session_info().unwrap().session_id, SERIAL);
+    let log = probe.temperature_log();
+    assert!(!log.is_empty());
+    assert_eq!(log.min_sequence(), Some(0));
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_manager_shared_across_threads() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    let manager = Arc::new(manager);
+
+    let connecting = tokio::spawn({
+        let probe = probe.clone();
+        async move { probe.connect().await }
+    });
+    let reading = std::thread::spawn({
+        let manager = manager.clone();
+        move || manager.get_probe("10000001").map(|p| p.snapshot())
+    });
+    connecting.await.unwrap().unwrap();
+    assert_eq!(reading.join().unwrap().unwrap().serial_number, SERIAL);
+
+    tokio::spawn(async move { manager.shutdown().await })
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_probe_handles_outlive_manager_safely() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    let handles = manager.probe_handles();
+    assert_eq!(handles.len(), 1);
+    assert_eq!(handles[0].serial_number(), SERIAL);
+    assert_eq!(manager.probe_handle("10000001").as_ref(), Some(&handles[0]));
+    assert!(Arc::ptr_eq(&handles[0].probe().unwrap(), &probe));
+
+    // A handle follows its probe when it is removed and added again
+    let source = manager.add_external_source("pit", 0xE000_0001).unwrap();
+    let external = manager.probe_handle("E0000001").unwrap();
+    assert!(manager.remove_external_source(&source));
+    assert!(!external.is_alive());
+    let source = manager.add_external_source("pit", 0xE000_0001).unwrap();
+    assert!(Arc::ptr_eq(&external.probe().unwrap(), &source.probe()));
+
+    manager.shutdown().await.unwrap();
+    drop((manager, probe, sim));
+    // Let the aborted simulator task release its reference
+    ticks(1).await;
+    assert!(!handles[0].is_alive());
+    assert!(matches!(
+        handles[0].probe(),
+        Err(Error::ProbeNotFound { .. })
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_instant_read_stream_prefers_status_when_connected() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    let mut readings = probe.subscribe_instant_reads();
+
+    // Nothing outside instant read
+    ticks(2).await;
+    assert!(readings.try_recv().is_err());
+
+    sim.set_mode(ProbeMode::InstantRead);
+    sim.set_temperatures([55.0; 8]);
+    ticks(2).await;
+    let first = readings.try_recv().unwrap();
+    assert_eq!(first.source, ReadingSource::Advertising);
+    assert!((first.temperature - 55.0).abs() < 0.1);
+    assert_eq!(first.since_previous, None);
+    let second = readings.try_recv().unwrap();
+    assert!(second.since_previous.is_some());
+
+    probe.connect().await.unwrap();
+    ticks(3).await;
+    let sources: Vec<_> = std::iter::from_fn(|| readings.try_recv().ok())
+        .map(|r| r.source)
+        .collect();
+    assert!(sources.len() >= 3);
+    assert_eq!(sources.last(), Some(&ReadingSource::Status));
+    // One reading per notification once status is flowing, not one per packet
+    assert!(sources.len() <= 4);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_command_history_records_configuration() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    probe
+        .set_prediction(PredictionMode::TimeToRemoval, 63.0)
+        .await
+        .unwrap();
+    probe.set_core_high_alarm(60.0).await.unwrap();
+    probe.set_power_mode(PowerMode::AlwaysOn).await.unwrap();
+
+    // Commands rejected while disconnected never reach the history
+    probe.disconnect().await.unwrap();
+    assert!(probe.set_color(ProbeColor::Blue).await.is_err());
+
+    let history = probe.command_history();
+    assert_eq!(history.len(), 3);
+    assert!(history.iter().all(|r| r.outcome == ConfigOutcome::Sent));
+    assert!(history.windows(2).all(|w| w[0].sent_at <= w[1].sent_at));
+    assert_eq!(
+        history[0].command,
+        ConfigCommand::SetPrediction {
+            mode: PredictionMode::TimeToRemoval,
+            set_point_celsius: 63.0,
+        }
+    );
+    assert!(
+        matches!(&history[1].command, ConfigCommand::SetAlarms(config) if config.any_enabled())
+    );
+    assert_eq!(
+        history[2].command,
+        ConfigCommand::SetPowerMode(PowerMode::AlwaysOn)
+    );
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_alarm_command_keeps_other_alarms() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+
+    // Until the probe reports its alarms, setting one would clear the rest
+    let outcomes = manager
+        .execute_line("alarm all core-high 95")
+        .await
+        .unwrap();
+    assert!(matches!(outcomes[0].result, Err(Error::ProbeError { .. })));
+    assert!(sim.commands().is_empty());
+
+    probe.connect().await.unwrap();
+    probe.set_core_low_alarm(40.0).await.unwrap();
+    ticks(1).await;
+    let outcomes = manager
+        .execute_line("alarm all core-high 95")
+        .await
+        .unwrap();
+    assert!(outcomes[0].result.is_ok());
+    let alarms = probe.alarm_config().unwrap();
+    assert!(alarms.core_high_alarm().set);
+    assert!(alarms.core_low_alarm().set);
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_external_config_changes_are_reconciled() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    let mut changes = probe.subscribe_config_changes();
+    probe.connect().await.unwrap();
+    probe
+        .start_cook(CookSpec::new(FoodSafeProduct::BeefSteak, 54.0))
+        .await
+        .unwrap();
+    probe.set_core_high_alarm(60.0).await.unwrap();
+    ticks(2).await;
+
+    // Own writes coming back in status are not external
+    assert!(changes.try_recv().is_err());
+
+    // Another client moves the set point and adds an alarm
+    sim.apply_external_command(&build_set_prediction_request(
+        PredictionMode::TimeToRemoval.to_raw(),
+        700,
+    ));
+    let mut alarms = probe.alarm_config().unwrap();
+    alarms.set_core_low_alarm(5.0, true);
+    sim.apply_external_command(&build_set_high_low_alarms_request(
+        &alarms.try_to_bytes().unwrap(),
+    ));
+    ticks(1).await;
+
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change.kind, ConfigKind::Prediction);
+    assert_eq!(
+        change.current,
+        ConfigCommand::SetPrediction {
+            mode: PredictionMode::TimeToRemoval,
+            set_point_celsius: 70.0,
+        }
+    );
+    assert!(matches!(
+        change.overwritten,
+        Some(ConfigCommand::SetPrediction { .. })
+    ));
+    assert_eq!(changes.try_recv().unwrap().kind, ConfigKind::Alarms);
+    assert!(changes.try_recv().is_err());
+    assert!((probe.active_cook().unwrap().target - 70.0).abs() < 0.05);
+
+    // The next write builds on the other client's alarms
+    probe.set_core_high_alarm(65.0).await.unwrap();
+    ticks(1).await;
+    let alarms = probe.alarm_config().unwrap();
+    assert!(alarms.core_low_alarm().set);
+    assert!((alarms.core_high_alarm().temperature - 65.0).abs() < 0.05);
+    assert!(changes.try_recv().is_err());
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_wait_until_helpers() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+    probe
+        .set_prediction(PredictionMode::TimeToRemoval, 63.0)
+        .await
+        .unwrap();
+
+    let minute = Duration::from_secs(60);
+    let heat = async {
+        ticks(3).await;
+        sim.set_temperatures([64.0, 66.0, 70.0, 75.0, 90.0, 120.0, 150.0, 150.0]);
+    };
+    let (core, _) = tokio::join!(probe.wait_until_core_at_least(63.0, minute), heat);
+    assert_eq!(core.unwrap(), 64.0);
+
+    let prediction = probe
+        .wait_until_prediction_state(PredictionState::RemovalPredictionDone, minute)
+        .await
+        .unwrap();
+    assert!((prediction.set_point_temperature - 63.0).abs() < 0.05);
+
+    // Already met returns straight away
+    let core = probe
+        .wait_until_core_at_least(60.0, Duration::ZERO)
+        .await
+        .unwrap();
+    assert_eq!(core, 64.0);
+
+    assert!(matches!(
+        probe.wait_until_food_safe(Duration::from_secs(5)).await,
+        Err(Error::Timeout)
+    ));
+
+    manager.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_snapshot_frames_at_fixed_rate() {
+    let sim = Arc::new(SimulatedProbe::new(SERIAL).with_interval(Duration::from_millis(20)));
+    let (manager, probe) = discover(&sim).await;
+    probe.connect().await.unwrap();
+
+    let mut frames = probe.snapshot_frames(DEFAULT_FRAME_INTERVAL);
+    let start = tokio::time::Instant::now();
+    let mut times = Vec::new();
+    let mut temperature = 20.0;
+    while start.elapsed() < Duration::from_secs(1) {
+        temperature += 0.5;
+        sim.set_temperatures([temperature; 8]);
+        if tokio::time::timeout(Duration::from_millis(20), frames.next())
+            .await
+            .is_ok()
+        {
+            times.push(tokio::time::Instant::now());
+        }
+    }
+
+    // About 10 frames despite 50 updates a second
+    assert!((8..=11).contains(&times.len()), "{} frames", times.len());
+    assert!(times
+        .windows(2)
+        .all(|w| w[1] - w[0] >= DEFAULT_FRAME_INTERVAL));
+    let core = frames.latest().virtual_temperatures.core.unwrap();
+    // At most a frame and a notification behind
+    assert!(temperature - core <= 4.0, "{} behind", temperature - core);
+
+    // Frames end once the probe is gone
+    manager.shutdown().await.unwrap();
+    drop(manager);
+    drop(probe);
+    drop(sim);
+    let ended = tokio::time::timeout(Duration::from_secs(5), async {
+        while frames.next().await.is_ok() {}
+    });
+    assert!(ended.await.is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_temperature_alarms_and_timers_share_alarm_stream() {
+    let path =
+        std::env::temp_dir().join(format!("combustion-it-timers-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    manager.set_timer_store(Arc::new(TimerStore::open(&path).unwrap()));
+    probe.connect().await.unwrap();
+    let mut alarms = probe.subscribe_alarms();
+
+    // A probe alarm starting to sound
+    let mut config = AlarmConfig::new();
+    config.set_high_alarm(0, 60.0, true);
+    probe.set_alarms(&config).await.unwrap();
+    sim.set_temperatures([65.0; 8]);
+    ticks(2).await;
+    match alarms.try_recv().unwrap() {
+        AlarmEvent::Temperature {
+            sensor,
+            bound,
+            threshold,
+            ..
+        } => {
+            assert_eq!(sensor.name(), "T1");
+            assert_eq!(bound, AlarmBound::High);
+            assert!((threshold - 60.0).abs() < 0.05);
+        }
+        other => panic!("unexpected alarm {:?}", other),
+    }
+    assert!(alarms.try_recv().is_err());
+
+    // A timer firing
+    let baste = probe.schedule_timer_after("Baste", Duration::from_secs(60));
+    let wrap = probe.schedule_timer_after("Wrap", Duration::from_secs(600));
+    assert_eq!(probe.timers(), vec![baste.clone(), wrap.clone()]);
+    tokio::time::sleep(Duration::from_secs(61)).await;
+    match alarms.try_recv().unwrap() {
+        AlarmEvent::Timer { timer, .. } => assert_eq!(timer, baste),
+        other => panic!("unexpected alarm {:?}", other),
+    }
+    assert_eq!(TimerStore::open(&path).unwrap().list(), vec![wrap.clone()]);
+    drop(probe);
+    drop(manager);
+
+    // The other timer is picked up again after a restart
+    let sim = Arc::new(SimulatedProbe::new(SERIAL));
+    let (manager, probe) = discover(&sim).await;
+    manager.set_timer_store(Arc::new(TimerStore::open(&path).unwrap()));
+    ticks(1).await;
+    assert_eq!(probe.timers(), vec![wrap.clone()]);
+    let mut alarms = probe.subscribe_alarms();
+    tokio::time::sleep(Duration::from_secs(600)).await;
+    match alarms.try_recv().unwrap() {
+        AlarmEvent::Timer { timer, .. } => assert_eq!(timer, wrap),
+        other => panic!("unexpected alarm {:?}", other),
+    }
+    assert!(TimerStore::open(&path).unwrap().list().is_empty());
+
+    manager.shutdown().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,208 @@
+//! Soak test: discovery, connect and disconnect loops against simulated
+//! probes, checking that tasks, memory and channel lag stay bounded.
+//!
+//! Time is simulated, so hours of cycling take seconds. Set
+//! `COMBUSTION_STRESS_HOURS` to soak for longer than the default half hour:
+//!
+//! ```text
+//! COMBUSTION_STRESS_HOURS=8 cargo test -p combustion-rust-ble-it --test stress
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use combustion_rust_ble::runtime::live_tasks;
+use combustion_rust_ble::simulator::SimulatedProbe;
+use combustion_rust_ble::DeviceManager;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Counts the bytes allocated and not yet freed.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Probes simulated per cycle.
+const PROBES: u32 = 3;
+/// Connect/disconnect rounds per cycle.
+const ROUNDS: u32 = 5;
+/// Time connected per round.
+const CONNECTED: Duration = Duration::from_secs(30);
+/// Time disconnected per round.
+const DISCONNECTED: Duration = Duration::from_secs(5);
+/// Cycles run before taking the baseline, to fill lazily built caches.
+const WARMUP_CYCLES: usize = 2;
+/// Growth in live bytes allowed between the baseline and any later cycle.
+const MEMORY_SLACK: usize = 64 * 1024;
+
+/// Measurements taken after a cycle has been torn down.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    /// Library tasks still running.
+    tasks: usize,
+    /// Bytes allocated.
+    bytes: usize,
+}
+
+impl Sample {
+    fn take() -> Self {
+        Self {
+            tasks: live_tasks(),
+            bytes: ALLOCATED.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Simulated hours to soak for.
+fn soak_hours() -> f64 {
+    std::env::var("COMBUSTION_STRESS_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse().ok())
+        .unwrap_or(0.5)
+}
+
+/// Let the simulators and the library's tasks run for `duration`.
+async fn run_for(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Discover the probes, cycle their connections, and shut down.
+///
+/// Returns how many temperature updates subscribers missed.
+async fn cycle(first_serial: u32) -> u64 {
+    let sims: Vec<_> = (0..PROBES)
+        .map(|i| Arc::new(SimulatedProbe::new(first_serial + i)))
+        .collect();
+    let manager = DeviceManager::with_simulated(sims.iter().cloned());
+    let mut discovered = manager.subscribe_probe_discovered();
+    manager.start_scanning().await.unwrap();
+
+    let mut probes = Vec::new();
+    for _ in 0..PROBES {
+        probes.push(discovered.recv().await.unwrap());
+    }
+
+    // A subscriber per probe that keeps up, counting any updates it misses
+    let lagged = Arc::new(AtomicU64::new(0));
+    let subscribers: Vec<_> = probes
+        .iter()
+        .map(|probe| {
+            let mut rx = probe.subscribe_temperatures();
+            let lagged = lagged.clone();
+            tokio::spawn(async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(n)) => {
+                            lagged.fetch_add(n, Ordering::Relaxed);
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            })
+        })
+        .collect();
+
+    // Connections must not leave tasks behind, so every disconnected round
+    // runs the same number of tasks
+    let mut disconnected_tasks = Vec::new();
+    for round in 0..ROUNDS {
+        for probe in &probes {
+            probe.connect().await.unwrap();
+        }
+        let callbacks: Vec<_> = probes
+            .iter()
+            .map(|probe| probe.on_temperatures_updated(|_, _| {}))
+            .collect();
+        run_for(CONNECTED).await;
+        drop(callbacks);
+        for probe in &probes {
+            probe.disconnect().await.unwrap();
+        }
+        run_for(DISCONNECTED).await;
+        disconnected_tasks.push(live_tasks());
+        assert_eq!(
+            disconnected_tasks[round as usize], disconnected_tasks[0],
+            "tasks leaked by connection round {}: {:?}",
+            round, disconnected_tasks
+        );
+    }
+
+    manager.shutdown().await.unwrap();
+    drop(probes);
+    drop(manager);
+    drop(sims);
+    for subscriber in subscribers {
+        subscriber.abort();
+    }
+    run_for(DISCONNECTED).await;
+    lagged.load(Ordering::Relaxed)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_soak_has_no_unbounded_growth() {
+    let cycle_time = (CONNECTED + DISCONNECTED) * ROUNDS + DISCONNECTED;
+    let cycles = ((soak_hours() * 3600.0 / cycle_time.as_secs_f64()).ceil() as usize).max(4);
+
+    let mut samples = Vec::new();
+    for n in 0..WARMUP_CYCLES + cycles {
+        let serial = 0x1000_0000 + (n as u32 % 16) * PROBES;
+        let lagged = cycle(serial).await;
+        assert_eq!(lagged, 0, "subscribers lagged in cycle {}", n);
+        samples.push(Sample::take());
+    }
+
+    let baseline = samples[WARMUP_CYCLES - 1];
+    let soaked = &samples[WARMUP_CYCLES..];
+    for (n, sample) in soaked.iter().enumerate() {
+        assert_eq!(
+            sample.tasks, baseline.tasks,
+            "tasks leaked by cycle {}: {:?}",
+            n, samples
+        );
+        assert!(
+            sample.bytes <= baseline.bytes + MEMORY_SLACK,
+            "memory grew by cycle {}: {:?}",
+            n,
+            samples
+        );
+    }
+
+    // Slack absorbs noise, not a steady climb
+    let half = soaked.len() / 2;
+    let mean =
+        |samples: &[Sample]| samples.iter().map(|s| s.bytes).sum::<usize>() / samples.len().max(1);
+    assert!(
+        mean(&soaked[half..]) <= mean(&soaked[..half]) + MEMORY_SLACK / 4,
+        "memory keeps growing: {:?}",
+        samples
+    );
+}
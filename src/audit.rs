@@ -0,0 +1,446 @@
+//! Audit trail of configuration sent to probes.
+//!
+//! Every configuration command a [`Probe`](crate::Probe) writes, such as a
+//! prediction set point, alarm configuration, food-safe configuration or
+//! power mode, is recorded with when it was sent and whether the write
+//! succeeded. When several clients share a probe, the history shows what this
+//! client changed, e.g. to answer "who changed my alarm?":
+//!
+//! ```rust,no_run
+//! # fn example(probe: &combustion_rust_ble::Probe) {
+//! for record in probe.command_history() {
+//!     println!("{} {:?} {:?}", record.sent_at, record.command, record.outcome);
+//! }
+//! # }
+//! ```
+//!
+//! Changes made by other clients, such as the official app connected through
+//! MeatNet, show up in the probe's status notifications. The probe compares
+//! the reported prediction, alarms and power mode against what it last wrote
+//! and publishes a [`ConfigChangedExternally`] for anything it did not
+//! change itself. Its cached configuration follows the probe, so the next
+//! write builds on the other client's change rather than undoing it.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::{ProbeColor, ProbeId};
+use crate::data::{AlarmConfig, FoodSafeConfig, PowerMode, PredictionMode};
+use crate::error::Result;
+use crate::protocol::ProbeStatus;
+
+/// Number of commands kept per probe; older ones are dropped first.
+pub const COMMAND_HISTORY_CAPACITY: usize = 256;
+
+/// A configuration command sent to a probe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConfigCommand {
+    /// Start a prediction.
+    SetPrediction {
+        /// Prediction mode.
+        mode: PredictionMode,
+        /// Set point in Celsius.
+        set_point_celsius: f64,
+    },
+    /// Cancel the prediction.
+    CancelPrediction,
+    /// Configure the high and low alarms.
+    SetAlarms(Box<AlarmConfig>),
+    /// Silence sounding alarms.
+    SilenceAlarms,
+    /// Configure food-safe monitoring.
+    ConfigureFoodSafe(FoodSafeConfig),
+    /// Reset food-safe monitoring.
+    ResetFoodSafe,
+    /// Set the power mode.
+    SetPowerMode(PowerMode),
+    /// Reset the thermometer to factory defaults.
+    ResetThermometer,
+    /// Set the probe ID.
+    SetId(ProbeId),
+    /// Set the probe color.
+    SetColor(ProbeColor),
+}
+
+/// Whether a configuration command was written to the probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConfigOutcome {
+    /// The command was written.
+    Sent,
+    /// Writing the command failed.
+    Failed {
+        /// Description of the error.
+        error: String,
+    },
+}
+
+impl ConfigOutcome {
+    /// Check if the command was written.
+    pub fn is_sent(&self) -> bool {
+        matches!(self, Self::Sent)
+    }
+}
+
+impl<T> From<&Result<T>> for ConfigOutcome {
+    fn from(result: &Result<T>) -> Self {
+        match result {
+            Ok(_) => Self::Sent,
+            Err(e) => Self::Failed {
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
+/// A configuration command in a probe's history.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandRecord {
+    /// The command.
+    pub command: ConfigCommand,
+    /// When the command was sent.
+    pub sent_at: DateTime<Utc>,
+    /// Whether it was written.
+    pub outcome: ConfigOutcome,
+}
+
+/// Bounded history of a probe's configuration commands.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommandHistory {
+    /// Records, oldest first.
+    records: VecDeque<CommandRecord>,
+}
+
+impl CommandHistory {
+    /// Record a command, dropping the oldest record when full.
+    pub(crate) fn record(&mut self, record: CommandRecord) {
+        if self.records.len() == COMMAND_HISTORY_CAPACITY {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Get the records, oldest first.
+    pub(crate) fn records(&self) -> Vec<CommandRecord> {
+        self.records.iter().cloned().collect()
+    }
+}
+
+/// Configuration that other clients can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConfigKind {
+    /// Prediction mode and set point.
+    Prediction,
+    /// High and low alarm settings.
+    Alarms,
+    /// Power mode.
+    PowerMode,
+}
+
+impl ConfigKind {
+    /// All kinds, in the order they are checked.
+    const ALL: [ConfigKind; 3] = [Self::Prediction, Self::Alarms, Self::PowerMode];
+}
+
+/// Another client changed a probe's configuration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigChangedExternally {
+    /// Probe serial number.
+    pub serial_number: u32,
+    /// What changed.
+    pub kind: ConfigKind,
+    /// The configuration now on the probe, as the command that sets it.
+    pub current: ConfigCommand,
+    /// This client's last write of the same configuration, which the change
+    /// replaced.
+    pub overwritten: Option<ConfigCommand>,
+    /// When the change was noticed.
+    pub at: DateTime<Utc>,
+}
+
+impl ConfigCommand {
+    /// Get the reported configuration this command changes, and the value it
+    /// sets in a form that compares at the probe's resolution.
+    fn key(&self) -> Option<(ConfigKind, Vec<i32>)> {
+        match self {
+            Self::SetPrediction {
+                mode,
+                set_point_celsius,
+            } => Some((
+                ConfigKind::Prediction,
+                prediction_key(*mode, *set_point_celsius),
+            )),
+            Self::CancelPrediction => Some((
+                ConfigKind::Prediction,
+                prediction_key(PredictionMode::None, 0.0),
+            )),
+            Self::SetAlarms(config) => Some((ConfigKind::Alarms, alarms_key(config))),
+            Self::SetPowerMode(mode) => Some((ConfigKind::PowerMode, vec![mode.to_raw().into()])),
+            _ => None,
+        }
+    }
+}
+
+/// Compare predictions by mode and set point, as the status notification's
+/// 10-bit, 0.1°C set point field holds it.
+fn prediction_key(mode: PredictionMode, set_point_celsius: f64) -> Vec<i32> {
+    match mode {
+        PredictionMode::None => vec![mode.to_raw().into()],
+        _ => vec![
+            mode.to_raw().into(),
+            ((set_point_celsius * 10.0).round() as i32).clamp(0, 0x3FF),
+        ],
+    }
+}
+
+/// Compare alarms by whether each is set and its threshold as the probe
+/// encodes it, ignoring whether it has tripped or is sounding.
+fn alarms_key(config: &AlarmConfig) -> Vec<i32> {
+    config
+        .high_alarms
+        .iter()
+        .chain(&config.low_alarms)
+        .flat_map(|alarm| {
+            let temperature = if alarm.set {
+                i32::from(u16::from_le_bytes(alarm.to_bytes()) >> 3)
+            } else {
+                0
+            };
+            [i32::from(alarm.set), temperature]
+        })
+        .collect()
+}
+
+/// Get a kind of configuration from a status notification, as the command
+/// that sets it.
+fn reported(kind: ConfigKind, status: &ProbeStatus) -> Option<ConfigCommand> {
+    match kind {
+        ConfigKind::Prediction => {
+            status
+                .prediction
+                .as_ref()
+                .map(|prediction| match prediction.mode {
+                    PredictionMode::None => ConfigCommand::CancelPrediction,
+                    mode => ConfigCommand::SetPrediction {
+                        mode,
+                        set_point_celsius: prediction.set_point_temperature,
+                    },
+                })
+        }
+        ConfigKind::Alarms => status
+            .alarm_config
+            .as_ref()
+            .map(|config| ConfigCommand::SetAlarms(Box::new(config.clone()))),
+        ConfigKind::PowerMode => status
+            .thermometer_preferences
+            .map(|preferences| ConfigCommand::SetPowerMode(preferences.power_mode)),
+    }
+}
+
+/// Most writes of one kind kept waiting for the probe to report them.
+const AWAITING_CAPACITY: usize = 8;
+
+/// What is known about one kind of configuration.
+#[derive(Debug, Clone, Default)]
+struct TrackedConfig {
+    /// Value last reported by the probe.
+    reported: Option<Vec<i32>>,
+    /// Values written by this client that the probe has not reported yet,
+    /// oldest first.
+    awaiting: VecDeque<Vec<i32>>,
+    /// Last command this client wrote.
+    written: Option<ConfigCommand>,
+}
+
+/// Tells this client's configuration changes apart from other clients'.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConfigTracker {
+    /// Tracked configuration, by [`ConfigKind::ALL`] index.
+    tracked: [TrackedConfig; 3],
+}
+
+impl ConfigTracker {
+    /// Note a command this client wrote successfully.
+    pub(crate) fn wrote(&mut self, command: &ConfigCommand) {
+        let Some((kind, key)) = command.key() else {
+            return;
+        };
+        let tracked = &mut self.tracked[kind as usize];
+        if tracked.awaiting.is_empty() && tracked.reported.as_ref() == Some(&key) {
+            tracked.written = Some(command.clone());
+            return;
+        }
+        if tracked.awaiting.len() == AWAITING_CAPACITY {
+            tracked.awaiting.pop_front();
+        }
+        tracked.awaiting.push_back(key);
+        tracked.written = Some(command.clone());
+    }
+
+    /// Compare a status notification with what was last reported and
+    /// written.
+    ///
+    /// # Returns
+    ///
+    /// Changes this client did not make. The first report of each kind is
+    /// taken as the starting point rather than a change.
+    pub(crate) fn observe(
+        &mut self,
+        serial_number: u32,
+        status: &ProbeStatus,
+    ) -> Vec<ConfigChangedExternally> {
+        let mut changes = Vec::new();
+        for kind in ConfigKind::ALL {
+            let Some((current, key)) =
+                reported(kind, status).and_then(|c| c.key().map(|(_, key)| (c, key)))
+            else {
+                continue;
+            };
+            let tracked = &mut self.tracked[kind as usize];
+            let previous = tracked.reported.replace(key.clone());
+            if previous.is_none() || previous.as_ref() == Some(&key) {
+                continue;
+            }
+            // The probe may skip reporting a write that was quickly
+            // followed by another, so a match settles earlier writes too
+            if let Some(index) = tracked.awaiting.iter().position(|k| *k == key) {
+                tracked.awaiting.drain(..=index);
+                continue;
+            }
+            tracked.awaiting.clear();
+            changes.push(ConfigChangedExternally {
+                serial_number,
+                kind,
+                current,
+                overwritten: tracked.written.take(),
+                at: Utc::now(),
+            });
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::PredictionInfo;
+    use crate::error::Error;
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = CommandHistory::default();
+        for i in 0..=COMMAND_HISTORY_CAPACITY {
+            let result: Result<()> = if i == 0 {
+                Err(Error::NotConnected)
+            } else {
+                Ok(())
+            };
+            history.record(CommandRecord {
+                command: ConfigCommand::SetId(ProbeId::new((i % 8) as u8 + 1)),
+                sent_at: Utc::now(),
+                outcome: ConfigOutcome::from(&result),
+            });
+        }
+
+        let records = history.records();
+        assert_eq!(records.len(), COMMAND_HISTORY_CAPACITY);
+        assert!(records.iter().all(|r| r.outcome.is_sent()));
+        assert_eq!(records[0].command, ConfigCommand::SetId(ProbeId::new(2)));
+        assert_eq!(
+            ConfigOutcome::from(&Err::<(), _>(Error::NotConnected)),
+            ConfigOutcome::Failed {
+                error: Error::NotConnected.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_external_changes_told_apart_from_own() {
+        let status = |set_point: f64, alarm: Option<f64>| {
+            let mut status = ProbeStatus::parse(&[0u8; 30]).unwrap();
+            let mut prediction = crate::data::PredictionInfo::new();
+            prediction.mode = PredictionMode::TimeToRemoval;
+            prediction.set_point_temperature = set_point;
+            status.prediction = Some(prediction);
+            let mut alarms = AlarmConfig::new();
+            if let Some(temperature) = alarm {
+                alarms.set_core_high_alarm(temperature, true);
+            }
+            status.alarm_config = Some(alarms);
+            status
+        };
+        let mut tracker = ConfigTracker::default();
+
+        // The first report is the starting point
+        assert!(tracker.observe(1, &status(50.0, None)).is_empty());
+
+        // A write reported back is not external, even after a stale report
+        let own = ConfigCommand::SetPrediction {
+            mode: PredictionMode::TimeToRemoval,
+            set_point_celsius: 63.0,
+        };
+        tracker.wrote(&own);
+        assert!(tracker.observe(1, &status(50.0, None)).is_empty());
+        assert!(tracker.observe(1, &status(63.0, None)).is_empty());
+
+        // Tripping or silencing alarms is not a configuration change
+        let mut tripped = status(63.0, None);
+        tripped.alarm_config.as_mut().unwrap().high_alarms[0].tripped = true;
+        assert!(tracker.observe(1, &tripped).is_empty());
+
+        let changes = tracker.observe(1, &status(70.0, Some(60.0)));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind, ConfigKind::Prediction);
+        assert_eq!(changes[0].overwritten, Some(own));
+        assert_eq!(
+            changes[0].current,
+            ConfigCommand::SetPrediction {
+                mode: PredictionMode::TimeToRemoval,
+                set_point_celsius: 70.0,
+            }
+        );
+        assert_eq!(changes[1].kind, ConfigKind::Alarms);
+        assert_eq!(changes[1].overwritten, None);
+    }
+
+    #[test]
+    fn test_quick_own_writes_are_not_external() {
+        let status = |set_point: f64| {
+            let mut status = ProbeStatus::parse(&[0u8; 30]).unwrap();
+            let mut prediction = crate::data::PredictionInfo::new();
+            prediction.mode = PredictionMode::TimeToRemoval;
+            prediction.set_point_temperature = set_point;
+            status.prediction = Some(prediction);
+            status
+        };
+        let write = |set_point_celsius: f64| ConfigCommand::SetPrediction {
+            mode: PredictionMode::TimeToRemoval,
+            set_point_celsius,
+        };
+        let mut tracker = ConfigTracker::default();
+        assert!(tracker.observe(1, &status(50.0)).is_empty());
+
+        // Both writes reported in turn
+        tracker.wrote(&write(60.0));
+        tracker.wrote(&write(65.0));
+        assert!(tracker.observe(1, &status(60.0)).is_empty());
+        assert!(tracker.observe(1, &status(65.0)).is_empty());
+
+        // Or only the last one
+        tracker.wrote(&write(70.0));
+        tracker.wrote(&write(72.0));
+        assert!(tracker.observe(1, &status(72.0)).is_empty());
+        assert_eq!(tracker.observe(1, &status(70.0)).len(), 1);
+
+        // Set points compare as the 10-bit status field reports them
+        tracker.wrote(&write(150.0));
+        assert!(tracker
+            .observe(1, &status(PredictionInfo::MAX_SET_POINT))
+            .is_empty());
+    }
+}
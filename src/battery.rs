@@ -0,0 +1,361 @@
+//! Early warning of a flat battery.
+//!
+//! The probe's own [`BatteryStatus::Low`] flag tends to appear with under an
+//! hour of life left, often too late to finish a cook. The probe tracks how
+//! long it has been seen running since its last charge and applies a simple
+//! [`BatteryModel`] to estimate the time remaining, publishing a
+//! [`BatteryWarning`] once the estimate drops below the model's warning lead
+//! time. Battery life differs between hardware and firmware revisions, so the
+//! model is picked from [`BatteryProfiles`].
+//!
+//! The default model's figures, 40 hours on a full charge with an hour left
+//! once the low battery flag appears, are rough estimates rather than
+//! figures published by Combustion. Replace them with figures measured for
+//! your probes through [`Probe::set_battery_profiles`](crate::Probe::set_battery_profiles),
+//! e.g. from [`Probe::battery_on_time`](crate::Probe::battery_on_time) when
+//! a probe runs flat.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::time::Instant;
+
+use crate::ble::advertising::BatteryStatus;
+use crate::capabilities::FirmwareVersion;
+
+/// Gaps between updates longer than this are not counted as on-time, since
+/// the probe was likely charging, switched off, or out of range.
+const MAX_COUNTED_GAP: Duration = Duration::from_secs(60);
+
+/// How long the low battery flag must stay clear, without a gap in updates,
+/// before the probe is taken to have been charged. The flag can flicker as
+/// the battery's voltage recovers between loads.
+const CHARGE_CONFIRMATION: Duration = Duration::from_secs(5 * 60);
+
+/// How long a probe runs on a full charge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryModel {
+    /// Running time on a full charge.
+    pub rated_life: Duration,
+    /// Running time left when the probe raises its low battery flag.
+    pub low_flag_remaining: Duration,
+    /// Warn once the estimated time remaining drops to this.
+    pub warning_lead: Duration,
+}
+
+impl BatteryModel {
+    /// Running time on a full charge assumed by the default model. An
+    /// estimate; see the [module docs](self).
+    pub const DEFAULT_RATED_LIFE: Duration = Duration::from_secs(40 * 60 * 60);
+
+    /// Running time left when the low battery flag appears, assumed unless
+    /// set with [`with_low_flag_remaining`](Self::with_low_flag_remaining).
+    /// An estimate; see the [module docs](self).
+    pub const DEFAULT_LOW_FLAG_REMAINING: Duration = Duration::from_secs(60 * 60);
+
+    /// Warning lead used unless set with
+    /// [`with_warning_lead`](Self::with_warning_lead).
+    pub const DEFAULT_WARNING_LEAD: Duration = Duration::from_secs(2 * 60 * 60);
+
+    /// Create a model for a probe rated to run for `rated_life`, assuming
+    /// [`DEFAULT_LOW_FLAG_REMAINING`](Self::DEFAULT_LOW_FLAG_REMAINING) and
+    /// warning [`DEFAULT_WARNING_LEAD`](Self::DEFAULT_WARNING_LEAD) before it
+    /// runs out.
+    pub fn new(rated_life: Duration) -> Self {
+        Self {
+            rated_life,
+            low_flag_remaining: Self::DEFAULT_LOW_FLAG_REMAINING,
+            warning_lead: Self::DEFAULT_WARNING_LEAD,
+        }
+    }
+
+    /// Set the running time left when the low battery flag appears.
+    pub fn with_low_flag_remaining(mut self, remaining: Duration) -> Self {
+        self.low_flag_remaining = remaining;
+        self
+    }
+
+    /// Set how long before the battery runs out to warn.
+    pub fn with_warning_lead(mut self, lead: Duration) -> Self {
+        self.warning_lead = lead;
+        self
+    }
+
+    /// Estimate the running time left.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_time` - Running time since the last charge
+    /// * `low_for` - Running time since the low battery flag appeared, if it
+    ///   has
+    pub fn estimate_remaining(&self, on_time: Duration, low_for: Option<Duration>) -> Duration {
+        let modelled = self.rated_life.saturating_sub(on_time);
+        match low_for {
+            Some(low_for) => modelled.min(self.low_flag_remaining.saturating_sub(low_for)),
+            None => modelled,
+        }
+    }
+}
+
+impl Default for BatteryModel {
+    /// A probe rated for [`DEFAULT_RATED_LIFE`](Self::DEFAULT_RATED_LIFE).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_RATED_LIFE)
+    }
+}
+
+/// Battery models for different probe revisions.
+///
+/// A model registered for the probe's hardware revision wins; otherwise the
+/// model registered for the newest firmware version the probe has reached
+/// applies, and failing that the default model.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryProfiles {
+    /// Model for probes matching no other profile.
+    pub default: BatteryModel,
+    /// Models by hardware revision.
+    pub hardware: Vec<(String, BatteryModel)>,
+    /// Models by the first firmware version they apply to.
+    pub firmware: Vec<(FirmwareVersion, BatteryModel)>,
+}
+
+impl BatteryProfiles {
+    /// Create profiles using `default` for every probe.
+    pub fn new(default: BatteryModel) -> Self {
+        Self {
+            default,
+            hardware: Vec::new(),
+            firmware: Vec::new(),
+        }
+    }
+
+    /// Use `model` for probes with this hardware revision.
+    pub fn with_hardware(mut self, revision: impl Into<String>, model: BatteryModel) -> Self {
+        self.hardware.push((revision.into(), model));
+        self
+    }
+
+    /// Use `model` for probes running firmware `since` or newer.
+    pub fn with_firmware(mut self, since: FirmwareVersion, model: BatteryModel) -> Self {
+        self.firmware.push((since, model));
+        self
+    }
+
+    /// Pick the model for a probe's revisions.
+    pub fn select(
+        &self,
+        hardware: Option<&str>,
+        firmware: Option<FirmwareVersion>,
+    ) -> &BatteryModel {
+        let by_hardware = hardware.and_then(|revision| {
+            self.hardware
+                .iter()
+                .find(|(r, _)| r.trim() == revision.trim())
+                .map(|(_, model)| model)
+        });
+        let by_firmware = || {
+            let version = firmware?;
+            self.firmware
+                .iter()
+                .filter(|(since, _)| *since <= version)
+                .max_by_key(|(since, _)| *since)
+                .map(|(_, model)| model)
+        };
+        by_hardware.or_else(by_firmware).unwrap_or(&self.default)
+    }
+}
+
+/// A probe's battery is expected to run out soon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryWarning {
+    /// Probe serial number.
+    pub serial_number: u32,
+    /// Running time since the last charge.
+    pub on_time: Duration,
+    /// Estimated running time left.
+    pub estimated_remaining: Duration,
+    /// Whether the probe itself reports a low battery.
+    pub battery_low: bool,
+    /// When the warning was raised.
+    pub at: DateTime<Utc>,
+}
+
+/// Tracks a probe's running time since its last charge.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BatteryMonitor {
+    /// Running time since the last charge.
+    on_time: Duration,
+    /// Last update.
+    last_seen: Option<Instant>,
+    /// On-time when the low battery flag appeared.
+    low_since: Option<Duration>,
+    /// When the low battery flag was last seen to clear.
+    clear_since: Option<Instant>,
+    /// Whether a warning was raised since the last charge.
+    warned: bool,
+}
+
+impl BatteryMonitor {
+    /// Get the running time since the last charge.
+    pub(crate) fn on_time(&self) -> Duration {
+        self.on_time
+    }
+
+    /// Set the running time since the last charge, e.g. restored from an
+    /// earlier run. Zero marks the battery as freshly charged.
+    pub(crate) fn set_on_time(&mut self, on_time: Duration) {
+        self.on_time = on_time;
+        if on_time.is_zero() {
+            self.low_since = None;
+            self.clear_since = None;
+            self.warned = false;
+        }
+    }
+
+    /// Estimate the running time left under `model`.
+    pub(crate) fn estimate_remaining(&self, model: &BatteryModel) -> Duration {
+        let low_for = self
+            .low_since
+            .map(|since| self.on_time.saturating_sub(since));
+        model.estimate_remaining(self.on_time, low_for)
+    }
+
+    /// Count the time since the last update and check the estimate.
+    ///
+    /// The low battery flag clearing means the probe was charged, which
+    /// starts the count again, once the flag has stayed clear for
+    /// [`CHARGE_CONFIRMATION`] or right away if the probe reappears after a
+    /// gap.
+    ///
+    /// # Returns
+    ///
+    /// The on-time and estimated time remaining the first time the estimate
+    /// reaches the model's warning lead after a charge.
+    pub(crate) fn update(
+        &mut self,
+        status: BatteryStatus,
+        model: &BatteryModel,
+        now: Instant,
+    ) -> Option<(Duration, Duration)> {
+        let gap = self.last_seen.map(|last| now.duration_since(last));
+        let continuous = gap.is_some_and(|gap| gap <= MAX_COUNTED_GAP);
+        if let Some(gap) = gap.filter(|_| continuous) {
+            self.on_time += gap;
+        }
+        self.last_seen = Some(now);
+
+        match status {
+            BatteryStatus::Low => {
+                self.clear_since = None;
+                if self.low_since.is_none() {
+                    self.low_since = Some(self.on_time);
+                }
+            }
+            BatteryStatus::Ok if self.low_since.is_some() => {
+                let clear_since = match self.clear_since {
+                    Some(since) if continuous => since,
+                    _ => now,
+                };
+                self.clear_since = Some(clear_since);
+                if !continuous || now.duration_since(clear_since) >= CHARGE_CONFIRMATION {
+                    self.set_on_time(Duration::ZERO);
+                }
+            }
+            _ => {}
+        }
+
+        let remaining = self.estimate_remaining(model);
+        if self.warned || remaining > model.warning_lead {
+            return None;
+        }
+        self.warned = true;
+        Some((self.on_time, remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    #[test]
+    fn test_battery_warning_before_low_flag() {
+        let model = BatteryModel::new(HOUR * 4).with_warning_lead(HOUR);
+        let mut monitor = BatteryMonitor::default();
+        let start = Instant::now();
+
+        monitor.set_on_time(HOUR * 3 - Duration::from_secs(30));
+        assert_eq!(monitor.update(BatteryStatus::Ok, &model, start), None);
+
+        // Long gaps are not counted
+        let later = start + HOUR;
+        assert_eq!(monitor.update(BatteryStatus::Ok, &model, later), None);
+
+        let (on_time, remaining) = monitor
+            .update(BatteryStatus::Ok, &model, later + Duration::from_secs(30))
+            .unwrap();
+        assert_eq!(on_time, HOUR * 3);
+        assert_eq!(remaining, HOUR);
+        assert_eq!(
+            monitor.update(BatteryStatus::Low, &model, later + Duration::from_secs(60)),
+            None
+        );
+
+        // A flickering flag is not a charge
+        let mut at = later + Duration::from_secs(90);
+        monitor.update(BatteryStatus::Ok, &model, at);
+        at += Duration::from_secs(30);
+        monitor.update(BatteryStatus::Low, &model, at);
+        assert_eq!(monitor.on_time(), HOUR * 3 + Duration::from_secs(90));
+
+        // Charging clears the flag and starts over
+        let cleared = at + Duration::from_secs(30);
+        while at < cleared + CHARGE_CONFIRMATION {
+            at += Duration::from_secs(30);
+            assert_ne!(monitor.on_time(), Duration::ZERO);
+            monitor.update(BatteryStatus::Ok, &model, at);
+        }
+        assert_eq!(monitor.on_time(), Duration::ZERO);
+        assert_eq!(monitor.estimate_remaining(&model), HOUR * 4);
+
+        // A probe back from the charger starts over at once
+        monitor.set_on_time(HOUR * 4);
+        monitor.update(BatteryStatus::Low, &model, at);
+        monitor.update(BatteryStatus::Ok, &model, at + HOUR);
+        assert_eq!(monitor.on_time(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_low_flag_caps_estimate() {
+        let model = BatteryModel::new(HOUR * 40);
+        let mut monitor = BatteryMonitor::default();
+        let start = Instant::now();
+
+        monitor.update(BatteryStatus::Ok, &model, start);
+        let (_, remaining) = monitor
+            .update(BatteryStatus::Low, &model, start + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(remaining, HOUR);
+    }
+
+    #[test]
+    fn test_battery_profiles_select() {
+        let short = BatteryModel::new(HOUR * 20);
+        let long = BatteryModel::new(HOUR * 50);
+        let profiles = BatteryProfiles::default()
+            .with_firmware(FirmwareVersion::new(1, 2, 0), short)
+            .with_firmware(FirmwareVersion::new(1, 4, 0), long)
+            .with_hardware("v2", short);
+
+        let v = FirmwareVersion::new;
+        assert_eq!(profiles.select(None, None), &BatteryModel::default());
+        assert_eq!(profiles.select(None, Some(v(1, 1, 0))), &profiles.default);
+        assert_eq!(profiles.select(None, Some(v(1, 3, 0))), &short);
+        assert_eq!(profiles.select(None, Some(v(2, 0, 0))), &long);
+        assert_eq!(profiles.select(Some("v2"), Some(v(2, 0, 0))), &short);
+    }
+}
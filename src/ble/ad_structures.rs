@@ -0,0 +1,402 @@
+//! Raw advertisement parsing.
+//!
+//! Platform scanners hand the library manufacturer data already split out
+//! by company ID. Applications reading raw advertising reports instead, from
+//! HCI, a sniffer or a gateway of their own, can hand the full payload to
+//! [`RawAdvertisement`], which splits it into AD structures (length, type,
+//! data), appends those of an optional scan response, and picks out
+//! Combustion's manufacturer-specific block for [`AdvertisingData::parse`].
+//!
+//! ```rust
+//! use combustion_rust_ble::ble::ad_structures::RawAdvertisement;
+//!
+//! # fn example(report: &[u8], scan_response: &[u8]) -> combustion_rust_ble::Result<()> {
+//! let advertisement = RawAdvertisement::parse(report, Some(scan_response))?;
+//! let data = advertisement.advertising_data()?;
+//! println!("{} at {:?}", data.serial_number_string(), advertisement.local_name());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Some platforms report an advertisement and its scan response as separate
+//! manufacturer data, so a probe's payload can arrive in two parts, in
+//! either order. [`ManufacturerDataAssembler`] holds the first part of each
+//! device's payload until the rest arrives.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ble::advertising::{AdvertisingData, ProductType};
+use crate::ble::uuids::COMBUSTION_MANUFACTURER_ID;
+use crate::error::{Error, Result};
+
+/// AD type of the advertising flags.
+pub const AD_TYPE_FLAGS: u8 = 0x01;
+/// AD type of a shortened local name.
+pub const AD_TYPE_SHORTENED_LOCAL_NAME: u8 = 0x08;
+/// AD type of a complete local name.
+pub const AD_TYPE_COMPLETE_LOCAL_NAME: u8 = 0x09;
+/// AD type of manufacturer-specific data.
+pub const AD_TYPE_MANUFACTURER_DATA: u8 = 0xFF;
+
+/// One AD structure of an advertisement or scan response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdStructure<'a> {
+    /// AD type, e.g. [`AD_TYPE_MANUFACTURER_DATA`].
+    pub ad_type: u8,
+    /// Data following the type byte.
+    pub data: &'a [u8],
+}
+
+impl<'a> AdStructure<'a> {
+    /// Get the company ID of manufacturer-specific data.
+    ///
+    /// Returns `None` for other AD types, or data too short for an ID.
+    pub fn company_id(&self) -> Option<u16> {
+        if self.ad_type != AD_TYPE_MANUFACTURER_DATA || self.data.len() < 2 {
+            return None;
+        }
+        Some(u16::from_le_bytes([self.data[0], self.data[1]]))
+    }
+
+    /// Get manufacturer-specific data after the company ID.
+    pub fn manufacturer_data(&self) -> Option<&'a [u8]> {
+        self.company_id().map(|_| &self.data[2..])
+    }
+}
+
+/// Split an advertising payload into its AD structures.
+///
+/// A zero length ends the significant part of the payload, so any padding
+/// after it is ignored.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidData`] if a structure runs past the end of the
+/// payload.
+pub fn parse_ad_structures(payload: &[u8]) -> Result<Vec<AdStructure<'_>>> {
+    let mut structures = Vec::new();
+    let mut offset = 0;
+    while offset < payload.len() {
+        let length = payload[offset] as usize;
+        if length == 0 {
+            break;
+        }
+        let end = offset + 1 + length;
+        if end > payload.len() {
+            return Err(Error::invalid_payload(
+                format!(
+                    "AD structure at byte {} has length {} but only {} bytes remain",
+                    offset,
+                    length,
+                    payload.len() - offset - 1
+                ),
+                payload,
+            ));
+        }
+        structures.push(AdStructure {
+            ad_type: payload[offset + 1],
+            data: &payload[offset + 2..end],
+        });
+        offset = end;
+    }
+    Ok(structures)
+}
+
+/// An advertisement and optional scan response, split into AD structures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawAdvertisement<'a> {
+    /// Structures of the advertisement.
+    advertising: Vec<AdStructure<'a>>,
+    /// Structures of the scan response, if one was given.
+    scan_response: Vec<AdStructure<'a>>,
+}
+
+impl<'a> RawAdvertisement<'a> {
+    /// Parse an advertisement and, if one was received, its scan response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidData`] if either payload is malformed.
+    pub fn parse(advertising: &'a [u8], scan_response: Option<&'a [u8]>) -> Result<Self> {
+        Ok(Self {
+            advertising: parse_ad_structures(advertising)?,
+            scan_response: scan_response
+                .map(parse_ad_structures)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Iterate over the structures of the advertisement, then those of the
+    /// scan response.
+    pub fn structures(&self) -> impl Iterator<Item = &AdStructure<'a>> {
+        self.advertising.iter().chain(&self.scan_response)
+    }
+
+    /// Get the manufacturer-specific data for `company_id`, without the ID.
+    ///
+    /// Blocks for the same company in the advertisement and the scan
+    /// response are joined in that order.
+    pub fn manufacturer_data(&self, company_id: u16) -> Option<Vec<u8>> {
+        let mut blocks = self
+            .structures()
+            .filter(|s| s.company_id() == Some(company_id))
+            .filter_map(|s| s.manufacturer_data())
+            .peekable();
+        blocks.peek()?;
+        Some(blocks.flatten().copied().collect())
+    }
+
+    /// Get Combustion's manufacturer-specific data, without the company ID.
+    pub fn combustion_data(&self) -> Option<Vec<u8>> {
+        self.manufacturer_data(COMBUSTION_MANUFACTURER_ID)
+    }
+
+    /// Get the local name, preferring a complete name to a shortened one.
+    pub fn local_name(&self) -> Option<String> {
+        let name = |ad_type: u8| {
+            self.structures()
+                .find(|s| s.ad_type == ad_type)
+                .map(|s| String::from_utf8_lossy(s.data).into_owned())
+        };
+        name(AD_TYPE_COMPLETE_LOCAL_NAME).or_else(|| name(AD_TYPE_SHORTENED_LOCAL_NAME))
+    }
+
+    /// Parse Combustion's manufacturer-specific data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidData`] if there is no Combustion data or it
+    /// fails to parse.
+    pub fn advertising_data(&self) -> Result<AdvertisingData> {
+        let data = self.combustion_data().ok_or_else(|| Error::InvalidData {
+            context: format!(
+                "No manufacturer data for company ID {:#06X}",
+                COMBUSTION_MANUFACTURER_ID
+            ),
+            payload: None,
+        })?;
+        AdvertisingData::parse(&data)
+    }
+}
+
+/// Joins Combustion manufacturer data split across packets, per device.
+///
+/// A payload of at least [`AdvertisingData::MIN_SIZE`] bytes is complete on
+/// its own. A shorter one is held until another short one arrives from the
+/// same device, and the two are joined with the part starting with a known
+/// [`ProductType`] first, since that byte leads the payload. Parts older
+/// than the timeout, or replaced before being joined, are dropped and kept
+/// for [`take_unjoined`](Self::take_unjoined).
+#[derive(Debug, Clone)]
+pub struct ManufacturerDataAssembler {
+    /// Part of a payload waiting for the rest, by device.
+    pending: HashMap<String, (Vec<u8>, Instant)>,
+    /// Parts dropped without being joined, by device, oldest first.
+    unjoined: Vec<(String, Vec<u8>)>,
+    /// How long a part waits for the rest.
+    timeout: Duration,
+}
+
+impl ManufacturerDataAssembler {
+    /// Default time a part waits for the rest of its payload.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Most dropped parts kept for [`take_unjoined`](Self::take_unjoined).
+    pub const MAX_UNJOINED: usize = 16;
+
+    /// Create an assembler with the default timeout.
+    pub fn new() -> Self {
+        Self::with_timeout(Self::DEFAULT_TIMEOUT)
+    }
+
+    /// Create an assembler holding parts for `timeout`.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            pending: HashMap::new(),
+            unjoined: Vec::new(),
+            timeout,
+        }
+    }
+
+    /// Add manufacturer data received from `device` at `now`.
+    ///
+    /// Returns the complete payload, or `None` while waiting for the rest.
+    pub fn push(&mut self, device: &str, data: &[u8], now: Instant) -> Option<Vec<u8>> {
+        self.expire(now);
+        if data.len() >= AdvertisingData::MIN_SIZE {
+            if let Some((held, _)) = self.pending.remove(device) {
+                self.drop_part(device, held);
+            }
+            return Some(data.to_vec());
+        }
+
+        match self.pending.remove(device) {
+            // A repeat of the held part, e.g. from a re-read of the same
+            // properties, just refreshes it
+            Some((held, _)) if held != data => {
+                let joined = if leads_payload(data) && !leads_payload(&held) {
+                    [data, &held].concat()
+                } else {
+                    [&held[..], data].concat()
+                };
+                if joined.len() >= AdvertisingData::MIN_SIZE {
+                    return Some(joined);
+                }
+                self.drop_part(device, held);
+            }
+            _ => {}
+        }
+        self.pending
+            .insert(device.to_string(), (data.to_vec(), now));
+        None
+    }
+
+    /// Check if part of a payload from `device` is waiting for the rest.
+    pub fn is_pending(&self, device: &str) -> bool {
+        self.pending.contains_key(device)
+    }
+
+    /// Drop parts that have waited longer than the timeout at `now`.
+    pub fn expire(&mut self, now: Instant) {
+        let timeout = self.timeout;
+        let expired: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, received_at))| now.duration_since(*received_at) > timeout)
+            .map(|(device, _)| device.clone())
+            .collect();
+        for device in expired {
+            if let Some((part, _)) = self.pending.remove(&device) {
+                self.drop_part(&device, part);
+            }
+        }
+    }
+
+    /// Take the parts dropped without being joined, with the devices they
+    /// came from, e.g. to quarantine them.
+    pub fn take_unjoined(&mut self) -> Vec<(String, Vec<u8>)> {
+        std::mem::take(&mut self.unjoined)
+    }
+
+    /// Keep a dropped part for [`take_unjoined`](Self::take_unjoined).
+    fn drop_part(&mut self, device: &str, part: Vec<u8>) {
+        if self.unjoined.len() >= Self::MAX_UNJOINED {
+            self.unjoined.remove(0);
+        }
+        self.unjoined.push((device.to_string(), part));
+    }
+}
+
+impl Default for ManufacturerDataAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check if a part starts with a known product type, as a payload does.
+fn leads_payload(part: &[u8]) -> bool {
+    part.first()
+        .is_some_and(|byte| !matches!(ProductType::from_raw(*byte), ProductType::Unknown(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::fixtures;
+
+    /// Wrap manufacturer data in an AD structure with `company_id`.
+    fn manufacturer_structure(company_id: u16, data: &[u8]) -> Vec<u8> {
+        let mut structure = vec![(data.len() + 3) as u8, AD_TYPE_MANUFACTURER_DATA];
+        structure.extend_from_slice(&company_id.to_le_bytes());
+        structure.extend_from_slice(data);
+        structure
+    }
+
+    #[test]
+    fn test_raw_advertisement_finds_combustion_data() {
+        let fixture = fixtures::bytes(fixtures::NORMAL);
+        let mut report = vec![0x02, AD_TYPE_FLAGS, 0x06];
+        report.extend(manufacturer_structure(0x004C, &[0xAA, 0xBB]));
+        report.extend(manufacturer_structure(COMBUSTION_MANUFACTURER_ID, &fixture));
+        report.extend([0x00, 0x00, 0x00]);
+        let scan_response = [0x04, AD_TYPE_SHORTENED_LOCAL_NAME, b'C', b'P', b'T'];
+
+        let advertisement = RawAdvertisement::parse(&report, Some(&scan_response)).unwrap();
+        assert_eq!(advertisement.structures().count(), 4);
+        assert_eq!(advertisement.combustion_data(), Some(fixture.clone()));
+        assert_eq!(
+            advertisement.manufacturer_data(0x004C),
+            Some(vec![0xAA, 0xBB])
+        );
+        assert_eq!(advertisement.local_name().as_deref(), Some("CPT"));
+        assert_eq!(
+            advertisement.advertising_data().unwrap(),
+            AdvertisingData::parse(&fixture).unwrap()
+        );
+
+        // Split across the advertisement and scan response
+        let first = manufacturer_structure(COMBUSTION_MANUFACTURER_ID, &fixture[..5]);
+        let rest = manufacturer_structure(COMBUSTION_MANUFACTURER_ID, &fixture[5..]);
+        let advertisement = RawAdvertisement::parse(&first, Some(&rest)).unwrap();
+        assert_eq!(advertisement.combustion_data(), Some(fixture));
+
+        // Malformed and missing data
+        assert!(parse_ad_structures(&[0x05, AD_TYPE_FLAGS, 0x06]).is_err());
+        let other = RawAdvertisement::parse(&[0x02, AD_TYPE_FLAGS, 0x06], None).unwrap();
+        assert_eq!(other.combustion_data(), None);
+        assert!(other.advertising_data().is_err());
+    }
+
+    #[test]
+    fn test_assembler_joins_parts_in_either_order() {
+        let fixture = fixtures::bytes(fixtures::NORMAL);
+        let (serial, status) = fixture.split_at(5);
+        let start = Instant::now();
+        let mut assembler = ManufacturerDataAssembler::new();
+
+        // Complete payloads pass straight through
+        assert_eq!(assembler.push("a", &fixture, start), Some(fixture.clone()));
+
+        // Serial first, then status
+        assert_eq!(assembler.push("a", serial, start), None);
+        assert!(assembler.is_pending("a"));
+        assert_eq!(assembler.push("a", status, start), Some(fixture.clone()));
+        assert!(!assembler.is_pending("a"));
+
+        // Status first, then serial, with a repeat and another device's
+        // parts arriving in between
+        assert_eq!(assembler.push("a", status, start), None);
+        assert_eq!(assembler.push("a", status, start), None);
+        assert_eq!(assembler.push("b", serial, start), None);
+        assert_eq!(assembler.push("a", serial, start), Some(fixture.clone()));
+        assert_eq!(assembler.push("b", status, start), Some(fixture.clone()));
+
+        // A complete payload replaces a held part
+        assert_eq!(assembler.push("a", serial, start), None);
+        assert_eq!(assembler.push("a", &fixture, start), Some(fixture.clone()));
+        assert!(!assembler.is_pending("a"));
+
+        assert_eq!(
+            assembler.take_unjoined(),
+            vec![("a".to_string(), serial.to_vec())]
+        );
+
+        // Parts that wait too long are dropped
+        assert_eq!(assembler.push("a", serial, start), None);
+        let late = start + ManufacturerDataAssembler::DEFAULT_TIMEOUT * 2;
+        assert_eq!(assembler.push("a", status, late), None);
+        assert_eq!(assembler.push("a", serial, late), Some(fixture.clone()));
+        assert_eq!(
+            assembler.take_unjoined(),
+            vec![("a".to_string(), serial.to_vec())]
+        );
+
+        assert_eq!(assembler.push("b", status, late), None);
+        assembler.expire(late + ManufacturerDataAssembler::DEFAULT_TIMEOUT * 2);
+        assert!(!assembler.is_pending("b"));
+        assert_eq!(assembler.take_unjoined().len(), 1);
+        assert!(assembler.take_unjoined().is_empty());
+    }
+}
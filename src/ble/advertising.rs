@@ -10,24 +10,29 @@ use crate::error::{Error, Result};
 /// Values defined in the MeatNet Node BLE specification:
 /// <https://github.com/combustion-inc/combustion-documentation/blob/main/meatnet_node_ble_specification.rst#product-type>
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum ProductType {
-    /// Unknown product type.
-    Unknown = 0,
-    /// Predictive Thermometer probe.
-    PredictiveProbe = 1,
-    /// MeatNet Repeater Node.
-    MeatNetRepeater = 2,
-    /// Giant Grill Gauge.
-    GiantGrillGauge = 3,
-    /// Display (Timer).
-    Display = 4,
-    /// Booster (Charger).
-    Booster = 5,
+    /// Predictive Thermometer probe (1).
+    PredictiveProbe,
+    /// MeatNet Repeater Node (2).
+    MeatNetRepeater,
+    /// Giant Grill Gauge (3).
+    GiantGrillGauge,
+    /// Display (Timer) (4).
+    Display,
+    /// Booster (Charger) (5).
+    Booster,
+    /// Unknown product type (0, per the specification), or a value this
+    /// library does not recognize, as received.
+    Unknown(u8),
 }
 
 impl ProductType {
     /// Create from raw byte value.
+    ///
+    /// Values without a variant map to [`Unknown`](Self::Unknown) carrying
+    /// the value.
     pub fn from_raw(value: u8) -> Self {
         match value {
             1 => Self::PredictiveProbe,
@@ -35,7 +40,19 @@ impl ProductType {
             3 => Self::GiantGrillGauge,
             4 => Self::Display,
             5 => Self::Booster,
-            _ => Self::Unknown,
+            _ => Self::Unknown(value),
+        }
+    }
+
+    /// Convert to raw byte value.
+    pub fn to_raw(&self) -> u8 {
+        match self {
+            Self::PredictiveProbe => 1,
+            Self::MeatNetRepeater => 2,
+            Self::GiantGrillGauge => 3,
+            Self::Display => 4,
+            Self::Booster => 5,
+            Self::Unknown(value) => *value,
         }
     }
 
@@ -53,7 +70,9 @@ impl ProductType {
 
 /// Probe operational mode from advertising data.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum ProbeMode {
     /// Normal cooking mode (250ms advertising interval).
     #[default]
@@ -62,19 +81,20 @@ pub enum ProbeMode {
     InstantRead = 1,
     /// Reserved for future use.
     Reserved = 2,
-    /// Error state.
+    /// Error state. Readings are not valid.
     Error = 3,
 }
 
 impl ProbeMode {
     /// Create from raw byte value.
+    ///
+    /// Only the low 2 bits are used, and every 2-bit value has a variant.
     pub fn from_raw(value: u8) -> Self {
         match value & 0x03 {
             0 => Self::Normal,
             1 => Self::InstantRead,
             2 => Self::Reserved,
-            3 => Self::Error,
-            _ => Self::Normal,
+            _ => Self::Error,
         }
     }
 
@@ -82,10 +102,21 @@ impl ProbeMode {
     pub fn to_raw(&self) -> u8 {
         *self as u8
     }
+
+    /// Get the sensors that carry readings in this mode, as a
+    /// [`ProbeTemperatures::active_mask`].
+    pub fn active_sensor_mask(&self) -> u8 {
+        match self {
+            Self::InstantRead => ProbeTemperatures::T1_ONLY,
+            Self::Error => 0,
+            _ => ProbeTemperatures::ALL_SENSORS,
+        }
+    }
 }
 
 /// Battery status from advertising data.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum BatteryStatus {
     /// Battery is OK.
@@ -112,6 +143,7 @@ impl BatteryStatus {
 
 /// Probe ID (1-8) from advertising data.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProbeId(pub u8);
 
 impl ProbeId {
@@ -149,6 +181,7 @@ impl std::fmt::Display for ProbeId {
 
 /// Probe color (silicone ring color).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ProbeColor {
     /// Yellow ring.
@@ -237,7 +270,7 @@ pub struct AdvertisingData {
 
 impl AdvertisingData {
     /// Minimum size of advertising data payload.
-    const MIN_SIZE: usize = 20;
+    pub const MIN_SIZE: usize = 20;
 
     /// Parse advertising data from raw bytes.
     ///
@@ -250,13 +283,14 @@ impl AdvertisingData {
     /// Parsed advertising data or an error.
     pub fn parse(data: &[u8]) -> Result<Self> {
         if data.len() < Self::MIN_SIZE {
-            return Err(Error::InvalidData {
-                context: format!(
+            return Err(Error::invalid_payload(
+                format!(
                     "Advertising data too short: {} bytes (need at least {})",
                     data.len(),
                     Self::MIN_SIZE
                 ),
-            });
+                data,
+            ));
         }
 
         // Byte 0: Product type
@@ -266,11 +300,8 @@ impl AdvertisingData {
         let serial_number = u32::from_le_bytes([data[1], data[2], data[3], data[4]]);
 
         // Bytes 5-17: Packed temperatures (13 bytes for 8 x 13-bit values)
-        let temperatures = ProbeTemperatures::from_packed_bytes(&data[5..18]).ok_or_else(|| {
-            Error::InvalidData {
-                context: "Failed to parse packed temperatures".to_string(),
-            }
-        })?;
+        let temperatures = ProbeTemperatures::from_packed_bytes(&data[5..18])
+            .ok_or_else(|| Error::invalid_payload("Failed to parse packed temperatures", data))?;
 
         // Byte 18: Mode and ID (packed 8-bit field)
         // Per MeatNet Node BLE spec:
@@ -281,6 +312,7 @@ impl AdvertisingData {
         let mode = ProbeMode::from_raw(mode_id_byte & 0x03);
         let color = ProbeColor::from_raw((mode_id_byte >> 2) & 0x07);
         let probe_id = ProbeId::from_raw((mode_id_byte >> 5) & 0x07);
+        let temperatures = temperatures.with_active_mask(mode.active_sensor_mask());
 
         // Byte 19: Battery status and virtual sensor selection
         // Bit 0: Battery status (0 = OK, 1 = Low)
@@ -289,8 +321,9 @@ impl AdvertisingData {
         let battery_status = BatteryStatus::from_raw(status_byte & 0x01);
 
         // Parse virtual sensor selection from bits 1-7 and compute virtual temperatures
-        let virtual_sensors_byte = status_byte >> 1;
-        let virtual_temperatures = Self::compute_virtual_temps(&temperatures, virtual_sensors_byte);
+        let sensor_selection = VirtualSensorSelection::from_byte(status_byte >> 1);
+        let virtual_temperatures =
+            VirtualTemperatures::from_selection(&temperatures, sensor_selection);
 
         // Byte 20: Network info (unused)
         // Byte 21: Overheating sensors
@@ -309,41 +342,6 @@ impl AdvertisingData {
         })
     }
 
-    /// Compute virtual temperatures from raw temperatures and sensor selection byte.
-    ///
-    /// The virtual sensor selection byte encodes which physical sensor to use:
-    /// - Bits 0-2: Virtual Core sensor (selects from T1-T6, values 0-5)
-    /// - Bits 3-4: Virtual Surface sensor (selects from T4-T7, values 0-3 map to T4-T7)
-    /// - Bits 5-6: Virtual Ambient sensor (selects from T5-T8, values 0-3 map to T5-T8)
-    fn compute_virtual_temps(
-        temperatures: &ProbeTemperatures,
-        virtual_sensors_byte: u8,
-    ) -> VirtualTemperatures {
-        // Parse the sensor selection
-        let sensor_selection = VirtualSensorSelection::from_byte(virtual_sensors_byte);
-
-        // Get temperatures from the selected sensors
-        let core = if (sensor_selection.core_sensor as usize) < 6 {
-            temperatures.values[sensor_selection.core_sensor as usize].to_celsius()
-        } else {
-            None
-        };
-
-        let surface = if (sensor_selection.surface_sensor as usize) < 8 {
-            temperatures.values[sensor_selection.surface_sensor as usize].to_celsius()
-        } else {
-            None
-        };
-
-        let ambient = if (sensor_selection.ambient_sensor as usize) < 8 {
-            temperatures.values[sensor_selection.ambient_sensor as usize].to_celsius()
-        } else {
-            None
-        };
-
-        VirtualTemperatures::with_selection(core, surface, ambient, sensor_selection)
-    }
-
     /// Get the serial number as a formatted string.
     pub fn serial_number_string(&self) -> String {
         format!("{:08X}", self.serial_number)
@@ -365,6 +363,7 @@ impl AdvertisingData {
 
 /// Overheating information from advertising or status data.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Overheating {
     /// Bitmask of sensors currently overheating (bit 0 = T1, bit 7 = T8).
     pub overheating_sensors: u8,
@@ -414,16 +413,20 @@ impl Overheating {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ble::fixtures;
 
     #[test]
     fn test_product_type() {
-        assert_eq!(ProductType::from_raw(0), ProductType::Unknown);
+        assert_eq!(ProductType::from_raw(0), ProductType::Unknown(0));
         assert_eq!(ProductType::from_raw(1), ProductType::PredictiveProbe);
         assert_eq!(ProductType::from_raw(2), ProductType::MeatNetRepeater);
         assert_eq!(ProductType::from_raw(3), ProductType::GiantGrillGauge);
         assert_eq!(ProductType::from_raw(4), ProductType::Display);
         assert_eq!(ProductType::from_raw(5), ProductType::Booster);
-        assert_eq!(ProductType::from_raw(99), ProductType::Unknown);
+        assert_eq!(ProductType::from_raw(99), ProductType::Unknown(99));
+        for raw in 0..=u8::MAX {
+            assert_eq!(ProductType::from_raw(raw).to_raw(), raw);
+        }
 
         assert!(ProductType::PredictiveProbe.is_predictive_probe());
         assert!(!ProductType::Display.is_predictive_probe());
@@ -435,6 +438,7 @@ mod tests {
         assert_eq!(ProbeMode::from_raw(0), ProbeMode::Normal);
         assert_eq!(ProbeMode::from_raw(1), ProbeMode::InstantRead);
         assert_eq!(ProbeMode::from_raw(3), ProbeMode::Error);
+        assert_eq!(ProbeMode::Error.active_sensor_mask(), 0);
     }
 
     #[test]
@@ -480,6 +484,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_advertising_data_parse() {
         // Create test data with minimum size
         let mut data = vec![0u8; 27];
@@ -508,6 +513,146 @@ mod tests {
         assert_eq!(parsed.battery_status, BatteryStatus::Ok);
     }
 
+    /// Expected parse of an advertising fixture.
+    struct Expected {
+        fixture: &'static str,
+        serial_number: u32,
+        mode: ProbeMode,
+        color: ProbeColor,
+        probe_id: u8,
+        battery_status: BatteryStatus,
+        raw_temperatures: [u16; 8],
+        active_mask: u8,
+        selection: VirtualSensorSelection,
+        core: Option<f64>,
+        surface: Option<f64>,
+        ambient: Option<f64>,
+        overheating_sensors: u8,
+    }
+
+    fn assert_celsius(name: &str, actual: Option<f64>, expected: Option<f64>) {
+        match (actual, expected) {
+            (Some(actual), Some(expected)) => {
+                assert!(
+                    (actual - expected).abs() < 1e-6,
+                    "{name}: {actual} != {expected}"
+                )
+            }
+            _ => assert_eq!(actual, expected, "{name}"),
+        }
+    }
+
+    #[test]
+    fn test_advertising_data_parse_fixtures() {
+        const INVALID: u16 = 0x1FFF;
+        let cases = [
+            Expected {
+                fixture: fixtures::NORMAL,
+                serial_number: 0x10005A3C,
+                mode: ProbeMode::Normal,
+                color: ProbeColor::Blue,
+                probe_id: 2,
+                battery_status: BatteryStatus::Ok,
+                raw_temperatures: [870, 882, 946, 1020, 1312, 2164, 2828, 3400],
+                active_mask: ProbeTemperatures::ALL_SENSORS,
+                selection: VirtualSensorSelection::new(0, 3, 7),
+                core: Some(23.5),
+                surface: Some(31.0),
+                ambient: Some(150.0),
+                overheating_sensors: 0,
+            },
+            Expected {
+                fixture: fixtures::INSTANT_READ,
+                serial_number: 0x10005A3C,
+                mode: ProbeMode::InstantRead,
+                color: ProbeColor::Blue,
+                probe_id: 2,
+                battery_status: BatteryStatus::Ok,
+                raw_temperatures: [
+                    1668, INVALID, INVALID, INVALID, INVALID, INVALID, INVALID, INVALID,
+                ],
+                active_mask: ProbeTemperatures::T1_ONLY,
+                selection: VirtualSensorSelection::new(0, 3, 7),
+                core: Some(63.4),
+                surface: None,
+                ambient: None,
+                overheating_sensors: 0,
+            },
+            Expected {
+                fixture: fixtures::LOW_BATTERY,
+                serial_number: 0x2001B7E2,
+                mode: ProbeMode::Normal,
+                color: ProbeColor::Pink,
+                probe_id: 7,
+                battery_status: BatteryStatus::Low,
+                raw_temperatures: [484, 486, 500, 502, 506, 520, 796, 808],
+                active_mask: ProbeTemperatures::ALL_SENSORS,
+                selection: VirtualSensorSelection::new(0, 3, 5),
+                core: Some(4.2),
+                surface: Some(5.1),
+                ambient: Some(6.0),
+                overheating_sensors: 0,
+            },
+            Expected {
+                fixture: fixtures::OVERHEATING,
+                serial_number: 0x2001B7E2,
+                mode: ProbeMode::Normal,
+                color: ProbeColor::Pink,
+                probe_id: 7,
+                battery_status: BatteryStatus::Ok,
+                raw_temperatures: [2360, 2490, 3020, 3804, 5600, 6450, 7020, 7600],
+                active_mask: ProbeTemperatures::ALL_SENSORS,
+                selection: VirtualSensorSelection::new(1, 4, 7),
+                core: Some(104.5),
+                surface: Some(260.0),
+                ambient: Some(360.0),
+                overheating_sensors: 0xF0,
+            },
+        ];
+
+        for case in cases {
+            let parsed = AdvertisingData::parse(&fixtures::bytes(case.fixture)).unwrap();
+            let name = case.fixture;
+
+            assert_eq!(parsed.product_type, ProductType::PredictiveProbe, "{name}");
+            assert_eq!(parsed.serial_number, case.serial_number, "{name}");
+            assert_eq!(parsed.mode, case.mode, "{name}");
+            assert_eq!(parsed.color, case.color, "{name}");
+            assert_eq!(parsed.probe_id.as_u8(), case.probe_id, "{name}");
+            assert_eq!(parsed.battery_status, case.battery_status, "{name}");
+            assert_eq!(
+                parsed.temperatures.values.map(|t| t.raw_value()),
+                case.raw_temperatures,
+                "{name}"
+            );
+            assert_eq!(parsed.temperatures.active_mask, case.active_mask, "{name}");
+            assert_eq!(
+                parsed.virtual_temperatures.sensor_selection, case.selection,
+                "{name}"
+            );
+            assert_celsius(name, parsed.virtual_temperatures.core, case.core);
+            assert_celsius(name, parsed.virtual_temperatures.surface, case.surface);
+            assert_celsius(name, parsed.virtual_temperatures.ambient, case.ambient);
+            assert_eq!(
+                parsed.overheating_sensors, case.overheating_sensors,
+                "{name}"
+            );
+            assert_eq!(
+                parsed.is_any_overheating(),
+                case.overheating_sensors != 0,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_advertising_data_repeated_over_meatnet() {
+        let direct = AdvertisingData::parse(&fixtures::bytes(fixtures::NORMAL)).unwrap();
+        let repeated =
+            AdvertisingData::parse(&fixtures::bytes(fixtures::REPEATED_OVER_MEATNET)).unwrap();
+        assert_eq!(repeated, direct);
+    }
+
     #[test]
     fn test_advertising_data_too_short() {
         let data = vec![0u8; 10];
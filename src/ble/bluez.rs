@@ -0,0 +1,96 @@
+//! Linux (BlueZ) scan tuning.
+//!
+//! btleplug always starts discovery with an interleaved LE + BR/EDR filter.
+//! When custom [`ScanOptions`] are set, discovery is started here instead with
+//! an LE-only filter that reports every advertisement. btleplug's event stream
+//! still delivers the results, since it listens to BlueZ regardless of which
+//! client started discovery.
+//!
+//! BlueZ's D-Bus API does not expose passive scanning or scan interval/window
+//! for discovery, so [`BleScanner::set_scan_options`] refuses those options
+//! on Linux.
+//!
+//! Requires the `bluez` feature.
+//!
+//! [`BleScanner::set_scan_options`]: crate::ble::BleScanner::set_scan_options
+
+use bluez_async::{AdapterId, BluetoothSession, DiscoveryFilter, Transport};
+use btleplug::api::Central;
+use btleplug::platform::Adapter;
+use tracing::{debug, warn};
+
+use crate::ble::scanner::ScanOptions;
+use crate::error::{Error, Result};
+
+/// Discovery session started with custom options.
+pub(crate) struct BluezDiscovery {
+    /// D-Bus session.
+    session: BluetoothSession,
+    /// Adapter discovery was started on.
+    adapter: AdapterId,
+    /// D-Bus connection task.
+    connection: crate::runtime::TaskHandle<()>,
+}
+
+impl BluezDiscovery {
+    /// Start discovery on `adapter` with `options`.
+    pub(crate) async fn start(adapter: &Adapter, options: &ScanOptions) -> Result<Self> {
+        let (connection, session) = BluetoothSession::new().await.map_err(bluez_error)?;
+        let connection = crate::runtime::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!("BlueZ D-Bus connection ended: {}", e);
+            }
+        });
+
+        // btleplug reports adapters as "hci0 (modalias)"
+        let name = adapter.adapter_info().await.map_err(Error::Bluetooth)?;
+        let name = name
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let adapter_id = session
+            .get_adapters()
+            .await
+            .map_err(bluez_error)?
+            .into_iter()
+            .map(|info| info.id)
+            .find(|id| id.to_string() == name)
+            .ok_or(Error::BluetoothUnavailable)?;
+
+        let filter = DiscoveryFilter {
+            transport: Some(Transport::Le),
+            duplicate_data: Some(true),
+            rssi_threshold: options.rssi_threshold,
+            ..Default::default()
+        };
+        session
+            .start_discovery_on_adapter_with_filter(&adapter_id, &filter)
+            .await
+            .map_err(bluez_error)?;
+
+        debug!("Started BlueZ LE discovery on {}", name);
+
+        Ok(Self {
+            session,
+            adapter: adapter_id,
+            connection,
+        })
+    }
+
+    /// Stop discovery.
+    pub(crate) async fn stop(self) -> Result<()> {
+        let result = self
+            .session
+            .stop_discovery_on_adapter(&self.adapter)
+            .await
+            .map_err(bluez_error);
+        self.connection.abort();
+        result
+    }
+}
+
+/// Convert a BlueZ error into a crate error.
+fn bluez_error(e: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::Bluetooth(btleplug::Error::Other(Box::new(e)))
+}
@@ -13,6 +13,7 @@ use tokio::sync::broadcast;
 use tracing::{debug, error, trace};
 use uuid::Uuid;
 
+use crate::ble::discovery::DiscoveryReport;
 use crate::ble::uuids::*;
 use crate::error::{Error, Result};
 
@@ -36,7 +37,7 @@ pub struct CharacteristicHandler {
     /// Whether we're currently listening for notifications.
     is_listening: Arc<RwLock<bool>>,
     /// Handle to the notification listener task.
-    listener_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    listener_handle: Arc<RwLock<Option<crate::runtime::TaskHandle<()>>>>,
 }
 
 impl CharacteristicHandler {
@@ -79,11 +80,25 @@ impl CharacteristicHandler {
         Ok(())
     }
 
+    /// Build a report of which expected services and characteristics were
+    /// discovered.
+    pub fn discovery_report(&self) -> DiscoveryReport {
+        DiscoveryReport::from_discovered(
+            self.peripheral.services().into_iter().map(|s| s.uuid),
+            self.characteristic_uuids(),
+        )
+    }
+
     /// Get a characteristic by UUID.
     pub fn get_characteristic(&self, uuid: &Uuid) -> Option<Characteristic> {
         self.characteristics.read().get(uuid).cloned()
     }
 
+    /// Get the UUIDs of all discovered characteristics.
+    pub fn characteristic_uuids(&self) -> Vec<Uuid> {
+        self.characteristics.read().keys().copied().collect()
+    }
+
     /// Check if a characteristic exists.
     pub fn has_characteristic(&self, uuid: &Uuid) -> bool {
         self.characteristics.read().contains_key(uuid)
@@ -215,7 +230,7 @@ impl CharacteristicHandler {
         let is_listening = self.is_listening.clone();
         let notification_tx = self.notification_tx.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = crate::runtime::spawn(async move {
             debug!("Notification listener task starting");
 
             let mut notifications = match peripheral.notifications().await {
@@ -270,7 +285,8 @@ impl CharacteristicHandler {
     pub async fn stop_notifications(&self) {
         *self.is_listening.write() = false;
 
-        if let Some(handle) = self.listener_handle.write().take() {
+        let handle = self.listener_handle.write().take();
+        if let Some(handle) = handle {
             let _ = handle.await;
         }
     }
@@ -285,6 +301,7 @@ impl CharacteristicHandler {
         let data = self.read(uuid).await?;
         String::from_utf8(data).map_err(|_| Error::InvalidData {
             context: format!("Invalid UTF-8 in characteristic {}", uuid),
+            payload: None,
         })
     }
 
@@ -4,9 +4,10 @@
 
 use btleplug::api::Peripheral as _;
 use btleplug::platform::Peripheral;
+use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
@@ -14,6 +15,7 @@ use crate::error::{Error, Result};
 
 /// Connection state for a probe.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConnectionState {
     /// Not connected to the probe.
     #[default]
@@ -49,6 +51,138 @@ impl std::fmt::Display for ConnectionState {
     }
 }
 
+/// Application-level heartbeat for connected probes.
+///
+/// A connected probe sends a status notification about once a second. Some
+/// platform stacks keep a connection open that no longer delivers them, so
+/// a connection that stays silent for `timeout` is declared unhealthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatPolicy {
+    /// Longest silence before the connection is unhealthy.
+    pub timeout: Duration,
+    /// Whether to drop and re-establish an unhealthy BLE connection.
+    pub recycle: bool,
+}
+
+impl Default for HeartbeatPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            recycle: false,
+        }
+    }
+}
+
+impl HeartbeatPolicy {
+    /// Create a policy with the given timeout.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Recycle unhealthy connections.
+    pub fn with_recycle(mut self) -> Self {
+        self.recycle = true;
+        self
+    }
+}
+
+/// A connection stopped delivering status notifications.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionUnhealthy {
+    /// Probe serial number.
+    pub serial_number: u32,
+    /// Time since the last status notification, or since connecting.
+    pub silent_for: Duration,
+    /// When the connection was declared unhealthy.
+    pub detected_at: DateTime<Utc>,
+    /// Whether the connection is being recycled.
+    pub recycling: bool,
+}
+
+/// Connection attempt history for a probe.
+///
+/// Counts every BLE connection attempt, including retries, so flaky adapters
+/// and radio interference show up as a low success rate or slow connects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionMetrics {
+    /// Attempts that connected.
+    pub successes: u32,
+    /// Attempts that failed.
+    pub failures: u32,
+    /// Time to connect on the last successful attempt.
+    pub last_connect_time: Option<Duration>,
+    /// Time to discover services and characteristics after the last
+    /// successful attempt.
+    pub last_discovery_time: Option<Duration>,
+    /// Total time to connect across successful attempts.
+    pub total_connect_time: Duration,
+}
+
+impl ConnectionMetrics {
+    /// Get the number of connection attempts.
+    pub fn attempts(&self) -> u32 {
+        self.successes + self.failures
+    }
+
+    /// Get the fraction of attempts that connected (0.0-1.0), or `None`
+    /// before the first attempt.
+    pub fn success_rate(&self) -> Option<f64> {
+        match self.attempts() {
+            0 => None,
+            attempts => Some(self.successes as f64 / attempts as f64),
+        }
+    }
+
+    /// Get the mean time to connect across successful attempts.
+    pub fn average_connect_time(&self) -> Option<Duration> {
+        (self.successes > 0).then(|| self.total_connect_time / self.successes)
+    }
+
+    /// Record a successful attempt.
+    pub(crate) fn record_success(&mut self, connect_time: Duration, discovery_time: Duration) {
+        self.successes += 1;
+        self.last_connect_time = Some(connect_time);
+        self.last_discovery_time = Some(discovery_time);
+        self.total_connect_time += connect_time;
+    }
+
+    /// Record a failed attempt.
+    pub(crate) fn record_failure(&mut self) {
+        self.failures += 1;
+    }
+}
+
+impl std::fmt::Display for ConnectionMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} attempts connected",
+            self.successes,
+            self.attempts()
+        )?;
+        if let Some(rate) = self.success_rate() {
+            write!(f, " ({:.0}%)", rate * 100.0)?;
+        }
+        if let (Some(connect), Some(discovery)) = (self.last_connect_time, self.last_discovery_time)
+        {
+            write!(
+                f,
+                ", last connect {} ms, discovery {} ms",
+                connect.as_millis(),
+                discovery.as_millis()
+            )?;
+        }
+        if let Some(average) = self.average_connect_time() {
+            write!(f, ", average connect {} ms", average.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
 /// Event for connection state changes.
 #[derive(Debug, Clone)]
 pub struct ConnectionEvent {
@@ -61,17 +195,21 @@ pub struct ConnectionEvent {
 /// Manages connections to Combustion probes.
 pub struct ConnectionManager {
     /// The peripheral to manage.
-    peripheral: Peripheral,
+    peripheral: RwLock<Peripheral>,
     /// Current connection state.
     state: Arc<RwLock<ConnectionState>>,
     /// Whether to maintain the connection (auto-reconnect).
     maintain_connection: Arc<RwLock<bool>>,
+    /// Whether reconnection attempts are suspended, e.g. while the host sleeps.
+    reconnection_suspended: RwLock<bool>,
     /// Channel for connection events.
     event_tx: broadcast::Sender<ConnectionEvent>,
     /// Maximum reconnection attempts.
     max_reconnect_attempts: u32,
     /// Reconnection delay.
     reconnect_delay: Duration,
+    /// Connection attempt history.
+    metrics: RwLock<ConnectionMetrics>,
 }
 
 impl ConnectionManager {
@@ -80,12 +218,14 @@ impl ConnectionManager {
         let (event_tx, _) = broadcast::channel(16);
 
         Self {
-            peripheral,
+            peripheral: RwLock::new(peripheral),
             state: Arc::new(RwLock::new(ConnectionState::Disconnected)),
             maintain_connection: Arc::new(RwLock::new(false)),
+            reconnection_suspended: RwLock::new(false),
             event_tx,
             max_reconnect_attempts: 3,
             reconnect_delay: Duration::from_secs(1),
+            metrics: RwLock::new(ConnectionMetrics::default()),
         }
     }
 
@@ -99,14 +239,36 @@ impl ConnectionManager {
         self.state().is_connected()
     }
 
+    /// Get the connection attempt history.
+    pub fn metrics(&self) -> ConnectionMetrics {
+        *self.metrics.read()
+    }
+
     /// Subscribe to connection events.
     pub fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
         self.event_tx.subscribe()
     }
 
     /// Get the peripheral.
-    pub fn peripheral(&self) -> &Peripheral {
-        &self.peripheral
+    pub fn peripheral(&self) -> Peripheral {
+        self.peripheral.read().clone()
+    }
+
+    /// Replace the peripheral, e.g. after the platform assigned the probe a
+    /// new identifier.
+    ///
+    /// Only done while disconnected, since an active connection is tied to
+    /// the old peripheral.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the peripheral was replaced.
+    pub fn rebind(&self, peripheral: Peripheral) -> bool {
+        if self.state() != ConnectionState::Disconnected {
+            return false;
+        }
+        *self.peripheral.write() = peripheral;
+        true
     }
 
     /// Attempt to connect to the probe.
@@ -132,8 +294,10 @@ impl ConnectionManager {
 
         self.set_state(ConnectionState::Connecting);
 
+        let peripheral = self.peripheral();
+
         // Check if already connected at BLE level
-        if self.peripheral.is_connected().await.unwrap_or(false) {
+        if peripheral.is_connected().await.unwrap_or(false) {
             info!("Peripheral already connected at BLE level");
             self.set_state(ConnectionState::Connected);
             return Ok(());
@@ -148,24 +312,35 @@ impl ConnectionManager {
         };
 
         while attempts < max_attempts {
+            if attempts > 0 && self.is_reconnection_suspended() {
+                debug!("Reconnection suspended, giving up");
+                break;
+            }
             attempts += 1;
 
             debug!("Connection attempt {} of {}", attempts, max_attempts);
 
-            match self.peripheral.connect().await {
+            let started = Instant::now();
+            match peripheral.connect().await {
                 Ok(_) => {
-                    info!("Successfully connected to probe");
+                    let connect_time = started.elapsed();
+                    info!("Successfully connected to probe in {:?}", connect_time);
 
                     // Discover services
-                    if let Err(e) = self.peripheral.discover_services().await {
+                    let started = Instant::now();
+                    if let Err(e) = peripheral.discover_services().await {
                         warn!("Failed to discover services: {}", e);
                     }
+                    self.metrics
+                        .write()
+                        .record_success(connect_time, started.elapsed());
 
                     self.set_state(ConnectionState::Connected);
                     return Ok(());
                 }
                 Err(e) => {
                     warn!("Connection attempt {} failed: {}", attempts, e);
+                    self.metrics.write().record_failure();
 
                     if attempts < max_attempts {
                         tokio::time::sleep(self.reconnect_delay).await;
@@ -196,7 +371,7 @@ impl ConnectionManager {
 
         self.set_state(ConnectionState::Disconnecting);
 
-        match self.peripheral.disconnect().await {
+        match self.peripheral().disconnect().await {
             Ok(_) => {
                 info!("Successfully disconnected from probe");
                 self.set_state(ConnectionState::Disconnected);
@@ -215,6 +390,37 @@ impl ConnectionManager {
         *self.maintain_connection.read()
     }
 
+    /// Suspend or resume reconnection attempts.
+    ///
+    /// While suspended, a lost connection is not re-established and a
+    /// connection attempt in progress stops retrying. The connection is
+    /// still maintained once resumed.
+    pub fn set_reconnection_suspended(&self, suspended: bool) {
+        *self.reconnection_suspended.write() = suspended;
+    }
+
+    /// Check if reconnection attempts are suspended.
+    pub fn is_reconnection_suspended(&self) -> bool {
+        *self.reconnection_suspended.read()
+    }
+
+    /// Read the signal strength of the active connection.
+    ///
+    /// btleplug has no connected RSSI read on any platform: the RSSI in a
+    /// peripheral's properties is the one from its last advertisement, which
+    /// is exactly what goes stale while connected. Until a platform read is
+    /// available this reports the operation as unsupported rather than
+    /// returning the cached value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`].
+    pub async fn read_rssi(&self) -> Result<i16> {
+        Err(Error::NotSupported {
+            operation: "reading RSSI over a BLE connection".to_string(),
+        })
+    }
+
     /// Set the reconnection parameters.
     pub fn set_reconnect_params(&mut self, max_attempts: u32, delay: Duration) {
         self.max_reconnect_attempts = max_attempts;
@@ -223,7 +429,7 @@ impl ConnectionManager {
 
     /// Handle a disconnection event (called externally when disconnect is detected).
     pub async fn handle_disconnection(&self) {
-        if !*self.maintain_connection.read() {
+        if !*self.maintain_connection.read() || self.is_reconnection_suspended() {
             self.set_state(ConnectionState::Disconnected);
             return;
         }
@@ -236,6 +442,21 @@ impl ConnectionManager {
         }
     }
 
+    /// Treat the connection as lost, e.g. after the host slept.
+    ///
+    /// Tells the platform to drop the link and reports the probe
+    /// disconnected, but keeps whether the connection should be maintained.
+    pub async fn mark_lost(&self) {
+        if self.state() == ConnectionState::Disconnected {
+            return;
+        }
+
+        if let Err(e) = self.peripheral().disconnect().await {
+            debug!("Failed to drop lost connection: {}", e);
+        }
+        self.set_state(ConnectionState::Disconnected);
+    }
+
     /// Update the connection state and emit an event.
     fn set_state(&self, new_state: ConnectionState) {
         let old_state = {
@@ -249,7 +470,7 @@ impl ConnectionManager {
             debug!("Connection state changed: {} -> {}", old_state, new_state);
 
             let _ = self.event_tx.send(ConnectionEvent {
-                identifier: format!("{:?}", self.peripheral.id()),
+                identifier: format!("{:?}", self.peripheral.read().id()),
                 state: new_state,
             });
         }
@@ -271,6 +492,35 @@ mod tests {
         assert!(!ConnectionState::Connected.is_transitioning());
     }
 
+    #[test]
+    fn test_connection_metrics() {
+        let mut metrics = ConnectionMetrics::default();
+        assert_eq!(metrics.success_rate(), None);
+        assert_eq!(metrics.average_connect_time(), None);
+
+        metrics.record_failure();
+        metrics.record_success(Duration::from_millis(800), Duration::from_millis(300));
+        metrics.record_success(Duration::from_millis(400), Duration::from_millis(100));
+        metrics.record_failure();
+
+        assert_eq!(metrics.attempts(), 4);
+        assert_eq!(metrics.success_rate(), Some(0.5));
+        assert_eq!(metrics.last_connect_time, Some(Duration::from_millis(400)));
+        assert_eq!(
+            metrics.last_discovery_time,
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            metrics.average_connect_time(),
+            Some(Duration::from_millis(600))
+        );
+        assert_eq!(
+            metrics.to_string(),
+            "2/4 attempts connected (50%), last connect 400 ms, discovery 100 ms, \
+             average connect 600 ms"
+        );
+    }
+
     #[test]
     fn test_connection_state_display() {
         assert_eq!(format!("{}", ConnectionState::Connected), "Connected");
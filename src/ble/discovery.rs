@@ -0,0 +1,155 @@
+//! GATT discovery reports.
+//!
+//! After connecting, the crate records which of the characteristics it
+//! expects were actually discovered. Firmware or platform quirks (e.g. a stale
+//! GATT cache) can hide characteristics, which otherwise shows up only as
+//! silently missing data.
+
+use uuid::Uuid;
+
+use crate::ble::uuids::{GattCharacteristic, GattService};
+
+/// Result of service and characteristic discovery on a connected probe.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiscoveryReport {
+    /// Known services that were discovered.
+    pub services: Vec<GattService>,
+    /// Known characteristics that were discovered.
+    pub found: Vec<GattCharacteristic>,
+    /// Known characteristics that were not discovered.
+    pub missing: Vec<GattCharacteristic>,
+    /// Discovered service and characteristic UUIDs not in the registry.
+    pub unknown: Vec<Uuid>,
+}
+
+impl DiscoveryReport {
+    /// Build a report from discovered service and characteristic UUIDs.
+    pub fn from_discovered(
+        services: impl IntoIterator<Item = Uuid>,
+        characteristics: impl IntoIterator<Item = Uuid>,
+    ) -> Self {
+        let mut report = Self::default();
+
+        for uuid in services {
+            match GattService::from_uuid(&uuid) {
+                Some(service) if !report.services.contains(&service) => {
+                    report.services.push(service)
+                }
+                Some(_) => {}
+                None => report.unknown.push(uuid),
+            }
+        }
+
+        for uuid in characteristics {
+            match GattCharacteristic::from_uuid(&uuid) {
+                Some(characteristic) if !report.found.contains(&characteristic) => {
+                    report.found.push(characteristic)
+                }
+                Some(_) => {}
+                None => report.unknown.push(uuid),
+            }
+        }
+
+        report.missing = GattCharacteristic::ALL
+            .into_iter()
+            .filter(|c| !report.found.contains(c))
+            .collect();
+        report.services.sort_by_key(|s| s.uuid());
+        report.found.sort_by_key(|c| c.uuid());
+        report.unknown.sort();
+        report.unknown.dedup();
+
+        report
+    }
+
+    /// Get the missing characteristics the crate needs for core functionality.
+    pub fn missing_required(&self) -> Vec<GattCharacteristic> {
+        self.missing
+            .iter()
+            .copied()
+            .filter(|c| c.is_required())
+            .collect()
+    }
+
+    /// Check if every required characteristic was found.
+    pub fn is_complete(&self) -> bool {
+        self.missing.iter().all(|c| !c.is_required())
+    }
+
+    /// Check if a characteristic was found.
+    pub fn has(&self, characteristic: GattCharacteristic) -> bool {
+        self.found.contains(&characteristic)
+    }
+}
+
+impl std::fmt::Display for DiscoveryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Services:")?;
+        for service in &self.services {
+            writeln!(f, "  {} ({})", service, service.uuid())?;
+        }
+        writeln!(f, "Found characteristics:")?;
+        for characteristic in &self.found {
+            writeln!(f, "  {} ({})", characteristic, characteristic.uuid())?;
+        }
+        writeln!(f, "Missing characteristics:")?;
+        for characteristic in &self.missing {
+            let required = if characteristic.is_required() {
+                " [required]"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "  {} ({}){}",
+                characteristic,
+                characteristic.uuid(),
+                required
+            )?;
+        }
+        if !self.unknown.is_empty() {
+            writeln!(f, "Unknown UUIDs:")?;
+            for uuid in &self.unknown {
+                writeln!(f, "  {}", uuid)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::uuids::*;
+
+    #[test]
+    fn test_report_found_and_missing() {
+        let vendor = Uuid::from_u128(0x1234);
+        let report = DiscoveryReport::from_discovered(
+            [UART_SERVICE_UUID, PROBE_STATUS_SERVICE_UUID, vendor],
+            [UART_RX_UUID, UART_TX_UUID, FIRMWARE_REVISION_UUID],
+        );
+
+        assert!(report.has(GattCharacteristic::UartRx));
+        assert!(report.has(GattCharacteristic::FirmwareRevision));
+        assert!(!report.is_complete());
+        assert_eq!(
+            report.missing_required(),
+            vec![GattCharacteristic::ProbeStatus]
+        );
+        assert_eq!(report.unknown, vec![vendor]);
+        assert!(report.to_string().contains("Probe Status"));
+    }
+
+    #[test]
+    fn test_report_complete() {
+        let report = DiscoveryReport::from_discovered(
+            GattService::ALL.map(|s| s.uuid()),
+            GattCharacteristic::ALL.map(|c| c.uuid()),
+        );
+        assert!(report.is_complete());
+        assert!(report.missing.is_empty());
+        assert!(report.unknown.is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+//! Synthetic advertising payload fixtures.
+//!
+//! Manufacturer-specific data in the layout a probe advertises, without the
+//! Combustion company ID, written as space-separated hex. These payloads
+//! were encoded by hand from the protocol specification, not captured from
+//! real probes, so they check the parser against the specification rather
+//! than against firmware behaviour. Each fixture exercises a different state
+//! of the probe; the expected field values are listed alongside the
+//! table-driven tests in [`advertising`](super::advertising).
+//!
+//! The scanner logs every advertisement it parses in this format at trace
+//! level, so running the `probe_debug` example next to a real probe yields
+//! captures that can be added here as they are.
+
+/// Cooking in normal mode: Blue probe with ID 2, battery OK, core on T1,
+/// surface on T4, and ambient on T8.
+pub(crate) const NORMAL: &str = "01 3C 5A 00 10 66 43 6E C8 0E FE 01 52 E8 10 C3 42 6A 30 C0 00 00";
+
+/// Instant read mode: only T1 carries a reading.
+pub(crate) const INSTANT_READ: &str =
+    "01 3C 5A 00 10 84 E6 FF FF FF FF FF FF FF FF FF FF FF 31 C0 00 00";
+
+/// Pink probe with ID 7 in the fridge with a low battery: core on T1,
+/// surface on T4, and ambient on T6.
+pub(crate) const LOW_BATTERY: &str =
+    "01 E2 B7 01 20 E4 C1 3C D0 07 FB A0 1F 10 04 C7 40 19 DC 41 00 00";
+
+/// Over a live fire with T5-T8 overheating: core on T2, surface on T5, and
+/// ambient on T8.
+pub(crate) const OVERHEATING: &str =
+    "01 E2 B7 01 20 38 49 37 31 2F 6E 07 5E 65 32 DB 86 ED DC D2 00 F0";
+
+/// [`NORMAL`] as relayed over MeatNet, with the network information byte
+/// set.
+pub(crate) const REPEATED_OVER_MEATNET: &str =
+    "01 3C 5A 00 10 66 43 6E C8 0E FE 01 52 E8 10 C3 42 6A 30 C0 04 00";
+
+/// Decode a fixture into bytes.
+pub(crate) fn bytes(fixture: &str) -> Vec<u8> {
+    fixture
+        .split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).expect("fixture is hex"))
+        .collect()
+}
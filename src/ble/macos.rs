@@ -0,0 +1,44 @@
+//! macOS app bundle checks.
+//!
+//! macOS terminates a bundled app the first time it uses Bluetooth if its
+//! `Info.plist` lacks `NSBluetoothAlwaysUsageDescription`, which looks like an
+//! unexplained crash from inside this crate. Check for it up front and log
+//! what's missing instead. Command-line binaries outside a bundle inherit the
+//! terminal's permission and are not checked.
+
+use std::path::Path;
+use tracing::warn;
+
+/// Info.plist keys a bundle using this crate should declare.
+const REQUIRED_KEYS: [&str; 1] = ["NSBluetoothAlwaysUsageDescription"];
+
+/// Warn about missing Bluetooth keys if running from an app bundle.
+pub(crate) fn check_bundle() {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    // <App>.app/Contents/MacOS/<binary>
+    let Some(contents) = exe.parent().and_then(Path::parent) else {
+        return;
+    };
+    if contents.file_name() != Some("Contents".as_ref()) {
+        return;
+    }
+
+    let plist = contents.join("Info.plist");
+    let Ok(data) = std::fs::read(&plist) else {
+        warn!("App bundle has no readable {}", plist.display());
+        return;
+    };
+
+    // Keys are stored verbatim in both XML and binary plists
+    for key in REQUIRED_KEYS {
+        if !data.windows(key.len()).any(|w| w == key.as_bytes()) {
+            warn!(
+                "{} does not declare {}; macOS will terminate the app when Bluetooth is used",
+                plist.display(),
+                key
+            );
+        }
+    }
+}
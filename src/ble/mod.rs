@@ -3,14 +3,28 @@
 //! This module provides low-level Bluetooth Low Energy functionality
 //! for discovering and communicating with Combustion probes.
 
+pub mod ad_structures;
 pub mod advertising;
+#[cfg(all(target_os = "linux", feature = "bluez"))]
+mod bluez;
 pub mod characteristics;
 pub mod connection;
+pub mod discovery;
+#[cfg(test)]
+pub(crate) mod fixtures;
+#[cfg(target_os = "macos")]
+mod macos;
 pub mod scanner;
 pub mod uuids;
+#[cfg(all(target_os = "windows", feature = "winrt"))]
+mod winrt;
 
+pub use ad_structures::{AdStructure, ManufacturerDataAssembler, RawAdvertisement};
 pub use advertising::{AdvertisingData, ProductType};
 pub use characteristics::CharacteristicHandler;
-pub use connection::{ConnectionManager, ConnectionState};
-pub use scanner::BleScanner;
+pub use connection::{
+    ConnectionManager, ConnectionMetrics, ConnectionState, ConnectionUnhealthy, HeartbeatPolicy,
+};
+pub use discovery::DiscoveryReport;
+pub use scanner::{AdvertisementEvent, BleScanner, DutyCycle, ScanMode, ScanOptions, ScanPhase};
 pub use uuids::*;
@@ -2,19 +2,25 @@
 //!
 //! Provides the scanner for discovering Combustion probes.
 
-use btleplug::api::{Central, Manager as _, Peripheral as _, ScanFilter};
+use btleplug::api::{Central, CentralState, Manager as _, Peripheral as _, ScanFilter};
 use btleplug::platform::{Adapter, Manager, Peripheral};
 use futures::stream::StreamExt;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
+use crate::ble::ad_structures::ManufacturerDataAssembler;
 use crate::ble::advertising::AdvertisingData;
+#[cfg(all(target_os = "linux", feature = "bluez"))]
+use crate::ble::bluez::BluezDiscovery;
 use crate::ble::uuids::COMBUSTION_MANUFACTURER_ID;
+#[cfg(all(target_os = "windows", feature = "winrt"))]
+use crate::ble::winrt::FullRateWatcher;
 use crate::error::{Error, Result};
+use crate::quarantine::{PayloadQuarantine, PayloadSource};
 
 /// Event emitted when a probe is discovered or updated.
 #[derive(Debug, Clone)]
@@ -29,18 +35,277 @@ pub struct ProbeDiscoveryEvent {
     pub rssi: Option<i16>,
 }
 
+/// Advertisement received outside btleplug's event stream.
+///
+/// Emitted by platform-specific watchers (see [`ScanOptions::full_rate`]) for
+/// probes that are already known, so the advertisement can be applied without
+/// waiting for btleplug to report the peripheral again.
+#[derive(Debug, Clone)]
+pub struct AdvertisementEvent {
+    /// Parsed advertising data.
+    pub advertising_data: AdvertisingData,
+    /// Signal strength in dBm.
+    pub rssi: Option<i16>,
+}
+
+/// How the adapter scans for advertisements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScanMode {
+    /// Send scan requests to advertisers (platform default).
+    #[default]
+    Active,
+    /// Only listen for advertisements.
+    Passive,
+}
+
+/// What the scanner is doing under a [`DutyCycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScanPhase {
+    /// Scanning without pause, because a probe is stale or in instant read
+    /// mode.
+    Continuous,
+    /// Scanning during the window of a duty cycle.
+    Listening,
+    /// Paused for the rest of a duty cycle.
+    Resting,
+}
+
+impl ScanPhase {
+    /// Check if the adapter is scanning in this phase.
+    pub fn is_scanning(&self) -> bool {
+        !matches!(self, Self::Resting)
+    }
+}
+
+/// Host-side scan duty cycle.
+///
+/// Scans for `window` out of every `period` while all known probes are
+/// fresh, and continuously while any probe is stale or in instant read mode,
+/// to save power on battery-powered hosts. Keep `period` well under the
+/// probe stale timeout ([`Probe::DEFAULT_STALE_TIMEOUT`](crate::Probe::DEFAULT_STALE_TIMEOUT))
+/// so resting doesn't make probes stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DutyCycle {
+    /// Time spent scanning in each period.
+    pub window: Duration,
+    /// Time between the start of consecutive windows.
+    pub period: Duration,
+}
+
+impl Default for DutyCycle {
+    /// Scan for 2 seconds every 10 seconds.
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(2),
+            period: Duration::from_secs(10),
+        }
+    }
+}
+
+impl DutyCycle {
+    /// Create a duty cycle.
+    pub fn new(window: Duration, period: Duration) -> Self {
+        Self { window, period }
+    }
+
+    /// Get the phase `elapsed` into the current period.
+    pub fn phase(&self, continuous: bool, elapsed: Duration) -> ScanPhase {
+        if continuous {
+            ScanPhase::Continuous
+        } else if elapsed < self.window {
+            ScanPhase::Listening
+        } else {
+            ScanPhase::Resting
+        }
+    }
+
+    /// Validate the duty cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the window is zero or longer than the period.
+    pub fn validate(&self) -> Result<()> {
+        if self.window.is_zero() || self.window > self.period {
+            return Err(Error::InvalidParameter {
+                name: "duty_cycle".to_string(),
+                value: format!("{:?} every {:?}", self.window, self.period),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Platform scan tuning.
+///
+/// Options the platform can't honor are logged and ignored, except on Linux,
+/// where [`BleScanner::set_scan_options`] refuses passive scanning and a scan
+/// interval/window with [`Error::NotSupported`]. Linux tuning needs the
+/// `bluez` feature and the Windows watcher the `winrt` feature; without them
+/// the options are logged and ignored there too.
+///
+/// | Option | Linux (BlueZ) | Windows | macOS |
+/// |---|---|---|---|
+/// | Any non-default option | LE-only discovery reporting every advertisement | - | - |
+/// | `full_rate` | Covered by the above | Native watcher without event coalescing | Not exposed |
+/// | `mode` | Passive not supported | Applied with `full_rate` | Not exposed; active |
+/// | `interval` / `window` | Not supported | Not exposed | Not exposed |
+/// | `rssi_threshold` | Applied by BlueZ | Applied with `full_rate` | Not exposed |
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScanOptions {
+    /// Scan mode.
+    pub mode: ScanMode,
+    /// Time between the start of consecutive scan windows.
+    pub interval: Option<Duration>,
+    /// Time spent listening in each scan interval.
+    pub window: Option<Duration>,
+    /// Ignore advertisements weaker than this (dBm).
+    pub rssi_threshold: Option<i16>,
+    /// Report every advertisement instead of letting the platform coalesce
+    /// them.
+    ///
+    /// Windows delivers at most about one advertisement event per device per
+    /// second by default, which hides the faster instant read update rate.
+    /// With this set and the `winrt` feature enabled, a native watcher
+    /// configured to report every advertisement runs alongside btleplug's.
+    pub full_rate: bool,
+    /// Pause scanning between windows while all probes are fresh.
+    ///
+    /// Applied by [`DeviceManager`](crate::DeviceManager) on every platform.
+    pub duty_cycle: Option<DutyCycle>,
+}
+
+impl ScanOptions {
+    /// Shortest scan interval/window allowed by the Bluetooth specification.
+    pub const MIN_SCAN_TIME: Duration = Duration::from_micros(2_500);
+
+    /// Longest scan interval/window allowed by the Bluetooth specification.
+    pub const MAX_SCAN_TIME: Duration = Duration::from_millis(10_240);
+
+    /// Create options for passive scanning.
+    pub fn passive() -> Self {
+        Self {
+            mode: ScanMode::Passive,
+            ..Self::default()
+        }
+    }
+
+    /// Set the scan interval and window.
+    pub fn with_timing(mut self, interval: Duration, window: Duration) -> Self {
+        self.interval = Some(interval);
+        self.window = Some(window);
+        self
+    }
+
+    /// Report every advertisement (see [`full_rate`](Self::full_rate)).
+    pub fn with_full_rate(mut self) -> Self {
+        self.full_rate = true;
+        self
+    }
+
+    /// Set the RSSI threshold (dBm).
+    pub fn with_rssi_threshold(mut self, rssi: i16) -> Self {
+        self.rssi_threshold = Some(rssi);
+        self
+    }
+
+    /// Scan on a duty cycle (see [`duty_cycle`](Self::duty_cycle)).
+    pub fn with_duty_cycle(mut self, duty_cycle: DutyCycle) -> Self {
+        self.duty_cycle = Some(duty_cycle);
+        self
+    }
+
+    /// Check if these are the platform default options.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Refuse the options BlueZ can't apply.
+    ///
+    /// BlueZ's D-Bus API has no passive discovery and no scan interval or
+    /// window.
+    #[cfg(target_os = "linux")]
+    fn check_bluez(&self) -> Result<()> {
+        let operation = if self.mode == ScanMode::Passive {
+            "passive scanning with BlueZ"
+        } else if self.interval.is_some() || self.window.is_some() {
+            "setting the scan interval/window with BlueZ"
+        } else {
+            return Ok(());
+        };
+        Err(Error::NotSupported {
+            operation: operation.to_string(),
+        })
+    }
+
+    /// Get the options applied by the platform scan.
+    fn platform(&self) -> Self {
+        Self {
+            duty_cycle: None,
+            ..self.clone()
+        }
+    }
+
+    /// Validate the options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interval or window is out of range, the
+    /// window is longer than the interval, or the duty cycle is invalid.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(duty_cycle) = &self.duty_cycle {
+            duty_cycle.validate()?;
+        }
+        for (name, value) in [("interval", self.interval), ("window", self.window)] {
+            if let Some(value) = value {
+                if value < Self::MIN_SCAN_TIME || value > Self::MAX_SCAN_TIME {
+                    return Err(Error::InvalidParameter {
+                        name: name.to_string(),
+                        value: format!("{:?}", value),
+                    });
+                }
+            }
+        }
+        if let (Some(interval), Some(window)) = (self.interval, self.window) {
+            if window > interval {
+                return Err(Error::InvalidParameter {
+                    name: "window".to_string(),
+                    value: format!("{:?} (longer than interval {:?})", window, interval),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
 /// BLE scanner for discovering Combustion probes.
 pub struct BleScanner {
     /// The BLE adapter to use for scanning.
     adapter: Adapter,
     /// Whether scanning is currently active.
     is_scanning: Arc<RwLock<bool>>,
+    /// Whether the adapter scan is running, i.e. scanning is not resting.
+    listening: tokio::sync::Mutex<bool>,
     /// Discovered peripherals.
     discovered: Arc<RwLock<HashMap<String, ProbeDiscoveryEvent>>>,
     /// Channel for discovery events.
     event_tx: broadcast::Sender<ProbeDiscoveryEvent>,
+    /// Channel for advertisements from platform-specific watchers.
+    advertisement_tx: broadcast::Sender<AdvertisementEvent>,
     /// Handle to the scanning task.
-    scan_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    scan_handle: Arc<RwLock<Option<crate::runtime::TaskHandle<()>>>>,
+    /// Platform scan tuning.
+    options: RwLock<ScanOptions>,
+    /// Buffer of advertisements that failed to parse.
+    quarantine: PayloadQuarantine,
+    /// Advertisements split across packets, waiting for their other part.
+    fragments: Arc<parking_lot::Mutex<ManufacturerDataAssembler>>,
+    /// Discovery session started with custom options.
+    #[cfg(all(target_os = "linux", feature = "bluez"))]
+    bluez_discovery: parking_lot::Mutex<Option<BluezDiscovery>>,
+    /// Native watcher started for full-rate scanning.
+    #[cfg(all(target_os = "windows", feature = "winrt"))]
+    full_rate_watcher: parking_lot::Mutex<Option<FullRateWatcher>>,
 }
 
 impl BleScanner {
@@ -50,6 +315,9 @@ impl BleScanner {
     ///
     /// Returns an error if Bluetooth is not available.
     pub async fn new() -> Result<Self> {
+        #[cfg(target_os = "macos")]
+        crate::ble::macos::check_bundle();
+
         let manager = Manager::new()
             .await
             .map_err(|_e| Error::BluetoothUnavailable)?;
@@ -66,30 +334,52 @@ impl BleScanner {
             adapter.adapter_info().await.ok()
         );
 
-        let (event_tx, _) = broadcast::channel(100);
-
-        Ok(Self {
-            adapter,
-            is_scanning: Arc::new(RwLock::new(false)),
-            discovered: Arc::new(RwLock::new(HashMap::new())),
-            event_tx,
-            scan_handle: Arc::new(RwLock::new(None)),
-        })
+        Ok(Self::with_adapter(adapter))
     }
 
     /// Create a new BLE scanner with a specific adapter.
     pub fn with_adapter(adapter: Adapter) -> Self {
         let (event_tx, _) = broadcast::channel(100);
+        let (advertisement_tx, _) = broadcast::channel(100);
 
         Self {
             adapter,
             is_scanning: Arc::new(RwLock::new(false)),
+            listening: tokio::sync::Mutex::new(false),
             discovered: Arc::new(RwLock::new(HashMap::new())),
             event_tx,
+            advertisement_tx,
             scan_handle: Arc::new(RwLock::new(None)),
+            options: RwLock::new(ScanOptions::default()),
+            quarantine: PayloadQuarantine::default(),
+            fragments: Arc::default(),
+            #[cfg(all(target_os = "linux", feature = "bluez"))]
+            bluez_discovery: parking_lot::Mutex::new(None),
+            #[cfg(all(target_os = "windows", feature = "winrt"))]
+            full_rate_watcher: parking_lot::Mutex::new(None),
         }
     }
 
+    /// Set the scan options used the next time scanning starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the options are invalid, or
+    /// [`Error::NotSupported`] if they ask for passive scanning or a scan
+    /// interval/window on Linux.
+    pub fn set_scan_options(&self, options: ScanOptions) -> Result<()> {
+        options.validate()?;
+        #[cfg(target_os = "linux")]
+        options.check_bluez()?;
+        *self.options.write() = options;
+        Ok(())
+    }
+
+    /// Get the scan options.
+    pub fn scan_options(&self) -> ScanOptions {
+        self.options.read().clone()
+    }
+
     /// Start scanning for probes.
     ///
     /// # Errors
@@ -104,10 +394,8 @@ impl BleScanner {
         info!("Starting BLE scan for Combustion probes");
 
         // Start the BLE scan
-        self.adapter
-            .start_scan(ScanFilter::default())
-            .await
-            .map_err(Error::Bluetooth)?;
+        self.start_platform_scan().await?;
+        *self.listening.lock().await = true;
 
         *self.is_scanning.write() = true;
 
@@ -116,8 +404,10 @@ impl BleScanner {
         let is_scanning = self.is_scanning.clone();
         let discovered = self.discovered.clone();
         let event_tx = self.event_tx.clone();
+        let quarantine = self.quarantine.clone();
+        let fragments = self.fragments.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = crate::runtime::spawn(async move {
             let mut events = match adapter.events().await {
                 Ok(events) => events,
                 Err(e) => {
@@ -134,6 +424,8 @@ impl BleScanner {
                             &adapter,
                             &discovered,
                             &event_tx,
+                            &quarantine,
+                            &fragments,
                         ).await;
                     }
                     _ = tokio::time::sleep(Duration::from_millis(100)) => {
@@ -164,16 +456,125 @@ impl BleScanner {
 
         *self.is_scanning.write() = false;
 
-        self.adapter.stop_scan().await.map_err(Error::Bluetooth)?;
+        let mut listening = self.listening.lock().await;
+        if *listening {
+            self.stop_platform_scan().await?;
+            *listening = false;
+        }
+        drop(listening);
 
         // Wait for the scan task to complete
-        if let Some(handle) = self.scan_handle.write().take() {
+        let handle = self.scan_handle.write().take();
+        if let Some(handle) = handle {
             let _ = handle.await;
         }
 
         Ok(())
     }
 
+    /// Pause or resume the adapter scan without ending the scanning session.
+    ///
+    /// Used to apply [`ScanOptions::duty_cycle`]. Does nothing when not
+    /// scanning.
+    pub(crate) async fn set_listening(&self, listen: bool) -> Result<()> {
+        let mut listening = self.listening.lock().await;
+        if !self.is_scanning() || *listening == listen {
+            return Ok(());
+        }
+
+        if listen {
+            trace!("Resuming BLE scan");
+            self.start_platform_scan().await?;
+        } else {
+            trace!("Resting BLE scan");
+            self.stop_platform_scan().await?;
+        }
+        *listening = listen;
+        Ok(())
+    }
+
+    /// Check the adapter and restart the adapter scan, e.g. after the host
+    /// slept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BluetoothUnavailable`] if the adapter is powered off,
+    /// or an error if the scan cannot be restarted.
+    pub(crate) async fn recover(&self) -> Result<()> {
+        match self.adapter.adapter_state().await {
+            Ok(CentralState::PoweredOff) => return Err(Error::BluetoothUnavailable),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to read adapter state: {}", e),
+        }
+
+        let listening = self.listening.lock().await;
+        if !self.is_scanning() || !*listening {
+            return Ok(());
+        }
+
+        info!("Restarting BLE scan");
+        if let Err(e) = self.stop_platform_scan().await {
+            debug!("Failed to stop BLE scan: {}", e);
+        }
+        self.start_platform_scan().await
+    }
+
+    /// Start the adapter scan, applying the scan options where supported.
+    async fn start_platform_scan(&self) -> Result<()> {
+        let options = self.scan_options().platform();
+
+        #[cfg(all(target_os = "linux", feature = "bluez"))]
+        if !options.is_default() {
+            let discovery = BluezDiscovery::start(&self.adapter, &options).await?;
+            *self.bluez_discovery.lock() = Some(discovery);
+            return Ok(());
+        }
+
+        #[cfg(all(target_os = "windows", feature = "winrt"))]
+        if options.full_rate {
+            let watcher = FullRateWatcher::start(&options, self.advertisement_tx.clone())?;
+            *self.full_rate_watcher.lock() = Some(watcher);
+        } else if !options.is_default() {
+            tracing::warn!("Scan options other than full_rate are not supported on Windows");
+        }
+
+        #[cfg(not(any(
+            all(target_os = "linux", feature = "bluez"),
+            all(target_os = "windows", feature = "winrt")
+        )))]
+        if !options.is_default() {
+            tracing::warn!("Scan options are not supported on this platform; using defaults");
+        }
+
+        self.adapter
+            .start_scan(ScanFilter::default())
+            .await
+            .map_err(Error::Bluetooth)
+    }
+
+    /// Stop the adapter scan.
+    async fn stop_platform_scan(&self) -> Result<()> {
+        #[cfg(all(target_os = "linux", feature = "bluez"))]
+        {
+            let discovery = self.bluez_discovery.lock().take();
+            if let Some(discovery) = discovery {
+                return discovery.stop().await;
+            }
+        }
+
+        #[cfg(all(target_os = "windows", feature = "winrt"))]
+        {
+            let watcher = self.full_rate_watcher.lock().take();
+            if let Some(watcher) = watcher {
+                if let Err(e) = watcher.stop() {
+                    tracing::warn!("Failed to stop full-rate watcher: {}", e);
+                }
+            }
+        }
+
+        self.adapter.stop_scan().await.map_err(Error::Bluetooth)
+    }
+
     /// Check if currently scanning.
     pub fn is_scanning(&self) -> bool {
         *self.is_scanning.read()
@@ -189,28 +590,48 @@ impl BleScanner {
         self.event_tx.subscribe()
     }
 
+    /// Subscribe to advertisements from platform-specific watchers.
+    ///
+    /// Only carries events when [`ScanOptions::full_rate`] is set on a
+    /// platform that needs a native watcher; otherwise advertisements arrive
+    /// through [`subscribe`](Self::subscribe).
+    pub fn subscribe_advertisements(&self) -> broadcast::Receiver<AdvertisementEvent> {
+        self.advertisement_tx.subscribe()
+    }
+
     /// Get the underlying adapter.
     pub fn adapter(&self) -> &Adapter {
         &self.adapter
     }
 
+    /// Get the buffer of Combustion advertisements that failed to parse.
+    ///
+    /// Disabled until given a capacity.
+    pub fn quarantine(&self) -> &PayloadQuarantine {
+        &self.quarantine
+    }
+
     /// Handle a BLE central event.
     async fn handle_event(
         event: btleplug::api::CentralEvent,
         adapter: &Adapter,
         discovered: &Arc<RwLock<HashMap<String, ProbeDiscoveryEvent>>>,
         event_tx: &broadcast::Sender<ProbeDiscoveryEvent>,
+        quarantine: &PayloadQuarantine,
+        fragments: &parking_lot::Mutex<ManufacturerDataAssembler>,
     ) {
         use btleplug::api::CentralEvent;
 
         match event {
             CentralEvent::DeviceDiscovered(id) => {
                 trace!("Device discovered: {:?}", id);
-                Self::process_peripheral(adapter, id, discovered, event_tx).await;
+                Self::process_peripheral(adapter, id, discovered, event_tx, quarantine, fragments)
+                    .await;
             }
             CentralEvent::DeviceUpdated(id) => {
                 trace!("Device updated: {:?}", id);
-                Self::process_peripheral(adapter, id, discovered, event_tx).await;
+                Self::process_peripheral(adapter, id, discovered, event_tx, quarantine, fragments)
+                    .await;
             }
             CentralEvent::DeviceConnected(id) => {
                 debug!("Device connected: {:?}", id);
@@ -225,7 +646,10 @@ impl BleScanner {
                 // Check for Combustion manufacturer data
                 if manufacturer_data.contains_key(&COMBUSTION_MANUFACTURER_ID) {
                     trace!("Combustion device advertisement: {:?}", id);
-                    Self::process_peripheral(adapter, id, discovered, event_tx).await;
+                    Self::process_peripheral(
+                        adapter, id, discovered, event_tx, quarantine, fragments,
+                    )
+                    .await;
                 }
             }
             CentralEvent::ServiceDataAdvertisement { .. } => {}
@@ -240,6 +664,8 @@ impl BleScanner {
         id: btleplug::platform::PeripheralId,
         discovered: &Arc<RwLock<HashMap<String, ProbeDiscoveryEvent>>>,
         event_tx: &broadcast::Sender<ProbeDiscoveryEvent>,
+        quarantine: &PayloadQuarantine,
+        fragments: &parking_lot::Mutex<ManufacturerDataAssembler>,
     ) {
         let peripheral = match adapter.peripheral(&id).await {
             Ok(p) => p,
@@ -254,11 +680,48 @@ impl BleScanner {
             _ => return,
         };
 
-        // Check for Combustion manufacturer data
-        let advertising_data = properties
-            .manufacturer_data
-            .get(&COMBUSTION_MANUFACTURER_ID)
-            .and_then(|data| AdvertisingData::parse(data).ok());
+        let identifier = id.to_string();
+
+        // Check for Combustion manufacturer data, which may arrive in parts
+        let (payload, waiting, unjoined) = {
+            let mut fragments = fragments.lock();
+            let payload = match properties
+                .manufacturer_data
+                .get(&COMBUSTION_MANUFACTURER_ID)
+            {
+                Some(data) => fragments.push(&identifier, data, Instant::now()),
+                None => {
+                    fragments.expire(Instant::now());
+                    None
+                }
+            };
+            let waiting = payload.is_none() && fragments.is_pending(&identifier);
+            (payload, waiting, fragments.take_unjoined())
+        };
+        for (device, part) in unjoined {
+            let e = Error::invalid_payload("Split advertisement never completed", &part);
+            trace!("Dropping part of an advertisement from {}: {}", device, e);
+            quarantine.record(PayloadSource::Advertising, None, &part, &e);
+        }
+        let advertising_data =
+            payload
+                .as_deref()
+                .and_then(|data| match AdvertisingData::parse(data) {
+                    Ok(advertising_data) => {
+                        // In the fixture format, for capturing real payloads
+                        trace!(
+                            "Advertisement from {}: {}",
+                            identifier,
+                            crate::error::hex_dump(data, data.len())
+                        );
+                        Some(advertising_data)
+                    }
+                    Err(e) => {
+                        trace!("Ignoring unparseable advertisement: {}", e);
+                        quarantine.record(PayloadSource::Advertising, None, data, &e);
+                        None
+                    }
+                });
 
         // Only process Combustion probes
         let is_combustion = advertising_data.is_some()
@@ -269,10 +732,16 @@ impl BleScanner {
                 .unwrap_or(false);
 
         if !is_combustion {
+            if waiting {
+                trace!("Waiting for the rest of a split advertisement: {:?}", id);
+            }
+            return;
+        }
+        // Keep what a complete advertisement reported until the rest of a
+        // split one arrives
+        if waiting && discovered.read().contains_key(&identifier) {
             return;
         }
-
-        let identifier = id.to_string();
 
         let event = ProbeDiscoveryEvent {
             identifier: identifier.clone(),
@@ -299,6 +768,71 @@ impl Drop for BleScanner {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_scan_options_validate() {
+        assert!(ScanOptions::default().is_default());
+        assert!(ScanOptions::passive().validate().is_ok());
+        assert!(!ScanOptions::default().with_full_rate().is_default());
+
+        let ok = ScanOptions::passive()
+            .with_timing(Duration::from_millis(100), Duration::from_millis(100));
+        assert!(ok.validate().is_ok());
+
+        let window_too_long = ScanOptions::default()
+            .with_timing(Duration::from_millis(50), Duration::from_millis(100));
+        assert!(window_too_long.validate().is_err());
+
+        let too_short =
+            ScanOptions::default().with_timing(Duration::from_millis(1), Duration::from_millis(1));
+        assert!(too_short.validate().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_bluez_refuses_unsupported_options() {
+        assert!(ScanOptions::default()
+            .with_full_rate()
+            .with_rssi_threshold(-80)
+            .check_bluez()
+            .is_ok());
+        assert!(matches!(
+            ScanOptions::passive().check_bluez(),
+            Err(Error::NotSupported { .. })
+        ));
+        let timed = ScanOptions::default()
+            .with_timing(Duration::from_millis(100), Duration::from_millis(50));
+        assert!(matches!(
+            timed.check_bluez(),
+            Err(Error::NotSupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_duty_cycle() {
+        let duty_cycle = DutyCycle::default();
+        assert!(duty_cycle.validate().is_ok());
+        assert_eq!(
+            duty_cycle.phase(false, Duration::from_secs(1)),
+            ScanPhase::Listening
+        );
+        assert_eq!(
+            duty_cycle.phase(false, Duration::from_secs(5)),
+            ScanPhase::Resting
+        );
+        assert_eq!(
+            duty_cycle.phase(true, Duration::from_secs(5)),
+            ScanPhase::Continuous
+        );
+
+        let options = ScanOptions::default().with_duty_cycle(duty_cycle);
+        assert!(options.platform().is_default());
+        let too_long = DutyCycle::new(Duration::from_secs(3), Duration::from_secs(2));
+        assert!(ScanOptions::default()
+            .with_duty_cycle(too_long)
+            .validate()
+            .is_err());
+    }
+
     #[test]
     fn test_probe_discovery_event_clone() {
         // Just verify the struct is Clone
@@ -1,6 +1,8 @@
 //! BLE Service and Characteristic UUIDs.
 //!
-//! Contains all UUID constants used for Combustion probe communication.
+//! Contains all UUID constants used for Combustion probe communication, and
+//! a typed registry ([`GattService`], [`GattCharacteristic`]) grouping them
+//! by service with human-readable names.
 
 use uuid::Uuid;
 
@@ -52,6 +54,172 @@ pub fn is_probe_service(uuid: &Uuid) -> bool {
     *uuid == PROBE_STATUS_SERVICE_UUID
 }
 
+/// A known GATT service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GattService {
+    /// Standard Device Information Service.
+    DeviceInformation,
+    /// Combustion Probe Status Service.
+    ProbeStatus,
+    /// Nordic UART Service.
+    Uart,
+    /// Nordic Buttonless DFU Service.
+    Dfu,
+}
+
+impl GattService {
+    /// All known services.
+    pub const ALL: [GattService; 4] = [
+        Self::DeviceInformation,
+        Self::ProbeStatus,
+        Self::Uart,
+        Self::Dfu,
+    ];
+
+    /// Get the service UUID.
+    pub fn uuid(&self) -> Uuid {
+        match self {
+            Self::DeviceInformation => DEVICE_INFO_SERVICE_UUID,
+            Self::ProbeStatus => PROBE_STATUS_SERVICE_UUID,
+            Self::Uart => UART_SERVICE_UUID,
+            Self::Dfu => DFU_SERVICE_UUID,
+        }
+    }
+
+    /// Look up a service by UUID.
+    pub fn from_uuid(uuid: &Uuid) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.uuid() == *uuid)
+    }
+
+    /// Get the service name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DeviceInformation => "Device Information",
+            Self::ProbeStatus => "Probe Status",
+            Self::Uart => "UART",
+            Self::Dfu => "DFU",
+        }
+    }
+
+    /// Get the characteristics the crate uses in this service.
+    pub fn characteristics(&self) -> Vec<GattCharacteristic> {
+        GattCharacteristic::ALL
+            .into_iter()
+            .filter(|c| c.service() == *self)
+            .collect()
+    }
+}
+
+impl std::fmt::Display for GattService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A known GATT characteristic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GattCharacteristic {
+    /// Manufacturer Name.
+    ManufacturerName,
+    /// Model Number.
+    ModelNumber,
+    /// Serial Number.
+    SerialNumber,
+    /// Hardware Revision.
+    HardwareRevision,
+    /// Firmware Revision.
+    FirmwareRevision,
+    /// Probe Status (read, notify).
+    ProbeStatus,
+    /// UART RX (write to probe).
+    UartRx,
+    /// UART TX (notifications from probe).
+    UartTx,
+}
+
+impl GattCharacteristic {
+    /// All known characteristics.
+    pub const ALL: [GattCharacteristic; 8] = [
+        Self::ManufacturerName,
+        Self::ModelNumber,
+        Self::SerialNumber,
+        Self::HardwareRevision,
+        Self::FirmwareRevision,
+        Self::ProbeStatus,
+        Self::UartRx,
+        Self::UartTx,
+    ];
+
+    /// Get the characteristic UUID.
+    pub fn uuid(&self) -> Uuid {
+        match self {
+            Self::ManufacturerName => MANUFACTURER_NAME_UUID,
+            Self::ModelNumber => MODEL_NUMBER_UUID,
+            Self::SerialNumber => SERIAL_NUMBER_UUID,
+            Self::HardwareRevision => HARDWARE_REVISION_UUID,
+            Self::FirmwareRevision => FIRMWARE_REVISION_UUID,
+            Self::ProbeStatus => PROBE_STATUS_CHARACTERISTIC_UUID,
+            Self::UartRx => UART_RX_UUID,
+            Self::UartTx => UART_TX_UUID,
+        }
+    }
+
+    /// Look up a characteristic by UUID.
+    pub fn from_uuid(uuid: &Uuid) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.uuid() == *uuid)
+    }
+
+    /// Get the characteristic name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ManufacturerName => "Manufacturer Name",
+            Self::ModelNumber => "Model Number",
+            Self::SerialNumber => "Serial Number",
+            Self::HardwareRevision => "Hardware Revision",
+            Self::FirmwareRevision => "Firmware Revision",
+            Self::ProbeStatus => "Probe Status",
+            Self::UartRx => "UART RX",
+            Self::UartTx => "UART TX",
+        }
+    }
+
+    /// Get the service this characteristic belongs to.
+    pub fn service(&self) -> GattService {
+        match self {
+            Self::ManufacturerName
+            | Self::ModelNumber
+            | Self::SerialNumber
+            | Self::HardwareRevision
+            | Self::FirmwareRevision => GattService::DeviceInformation,
+            Self::ProbeStatus => GattService::ProbeStatus,
+            Self::UartRx | Self::UartTx => GattService::Uart,
+        }
+    }
+
+    /// Check if the crate needs this characteristic for core functionality.
+    ///
+    /// Without Probe Status there are no predictions or food safety data;
+    /// without UART no commands can be sent.
+    pub fn is_required(&self) -> bool {
+        matches!(self, Self::ProbeStatus | Self::UartRx | Self::UartTx)
+    }
+}
+
+impl std::fmt::Display for GattCharacteristic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Get a human-readable name for a known service or characteristic UUID.
+pub fn uuid_name(uuid: &Uuid) -> Option<&'static str> {
+    GattCharacteristic::from_uuid(uuid)
+        .map(|c| c.name())
+        .or_else(|| GattService::from_uuid(uuid).map(|s| s.name()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,4 +270,25 @@ mod tests {
             uuid_str
         );
     }
+
+    #[test]
+    fn test_registry_lookup() {
+        for characteristic in GattCharacteristic::ALL {
+            assert_eq!(
+                GattCharacteristic::from_uuid(&characteristic.uuid()),
+                Some(characteristic)
+            );
+            assert!(characteristic
+                .service()
+                .characteristics()
+                .contains(&characteristic));
+        }
+        assert_eq!(
+            GattService::from_uuid(&UART_SERVICE_UUID),
+            Some(GattService::Uart)
+        );
+        assert_eq!(uuid_name(&UART_RX_UUID), Some("UART RX"));
+        assert_eq!(uuid_name(&DFU_SERVICE_UUID), Some("DFU"));
+        assert_eq!(uuid_name(&Uuid::nil()), None);
+    }
 }
@@ -0,0 +1,162 @@
+//! Windows full-rate advertisement watcher.
+//!
+//! Windows coalesces advertisement events per device, so with btleplug's
+//! watcher alone temperature updates arrive at under 1 Hz even in instant
+//! read mode. This runs a second `BluetoothLEAdvertisementWatcher` with a zero
+//! signal-strength sampling interval, which makes Windows report every
+//! advertisement, and forwards Combustion manufacturer data as
+//! [`AdvertisementEvent`]s. Windows reports scan responses as advertisements
+//! of their own, so payloads split between the two are joined first.
+//!
+//! Requires the `winrt` feature.
+
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use tokio::sync::broadcast;
+use tracing::{debug, trace};
+use windows::core::{Interface, Ref};
+use windows::Devices::Bluetooth::Advertisement::{
+    BluetoothLEAdvertisementReceivedEventArgs, BluetoothLEAdvertisementWatcher,
+    BluetoothLEScanningMode,
+};
+use windows::Foundation::{IReference, PropertyValue, TimeSpan, TypedEventHandler};
+use windows::Storage::Streams::DataReader;
+
+use crate::ble::ad_structures::ManufacturerDataAssembler;
+use crate::ble::advertising::AdvertisingData;
+use crate::ble::scanner::{AdvertisementEvent, ScanMode, ScanOptions};
+use crate::ble::uuids::COMBUSTION_MANUFACTURER_ID;
+use crate::error::{Error, Result};
+
+/// Supplemental advertisement watcher.
+///
+/// The watcher is stopped and its event handler revoked when dropped.
+pub(crate) struct FullRateWatcher {
+    /// Underlying WinRT watcher.
+    watcher: BluetoothLEAdvertisementWatcher,
+    /// Registration token of the `Received` handler.
+    received: i64,
+}
+
+impl FullRateWatcher {
+    /// Start watching, sending parsed Combustion advertisements to `tx`.
+    pub(crate) fn start(
+        options: &ScanOptions,
+        tx: broadcast::Sender<AdvertisementEvent>,
+    ) -> Result<Self> {
+        let watcher = BluetoothLEAdvertisementWatcher::new().map_err(winrt_error)?;
+
+        let mode = match options.mode {
+            ScanMode::Active => BluetoothLEScanningMode::Active,
+            ScanMode::Passive => BluetoothLEScanningMode::Passive,
+        };
+        watcher.SetScanningMode(mode).map_err(winrt_error)?;
+        // Not available before Windows 10 2004
+        let _ = watcher.SetAllowExtendedAdvertisements(true);
+
+        // A zero sampling interval disables per-device event coalescing
+        let interval: IReference<TimeSpan> = PropertyValue::CreateTimeSpan(TimeSpan::default())
+            .and_then(|v| v.cast())
+            .map_err(winrt_error)?;
+        watcher
+            .SignalStrengthFilter()
+            .and_then(|filter| {
+                if let Some(rssi) = options.rssi_threshold {
+                    let threshold: IReference<i16> = PropertyValue::CreateInt16(rssi)?.cast()?;
+                    filter.SetInRangeThresholdInDBm(&threshold)?;
+                }
+                filter.SetSamplingInterval(&interval)
+            })
+            .map_err(winrt_error)?;
+
+        let fragments = Mutex::new(ManufacturerDataAssembler::new());
+        let handler: TypedEventHandler<
+            BluetoothLEAdvertisementWatcher,
+            BluetoothLEAdvertisementReceivedEventArgs,
+        > = TypedEventHandler::new(
+            move |_sender, args: Ref<BluetoothLEAdvertisementReceivedEventArgs>| {
+                if let Ok(args) = args.ok() {
+                    forward_advertisement(args, &tx, &fragments);
+                }
+                Ok(())
+            },
+        );
+
+        let received = watcher.Received(&handler).map_err(winrt_error)?;
+        let watcher = Self { watcher, received };
+        watcher.watcher.Start().map_err(winrt_error)?;
+
+        debug!(
+            "Started full-rate advertisement watcher ({:?})",
+            options.mode
+        );
+
+        Ok(watcher)
+    }
+
+    /// Stop watching.
+    pub(crate) fn stop(self) -> Result<()> {
+        self.watcher.Stop().map_err(winrt_error)
+    }
+}
+
+impl Drop for FullRateWatcher {
+    fn drop(&mut self) {
+        // Stopping an already stopped watcher is a no-op
+        let _ = self.watcher.Stop();
+        if let Err(e) = self.watcher.RemoveReceived(self.received) {
+            debug!("Failed to revoke advertisement handler: {}", e);
+        }
+    }
+}
+
+/// Parse a received advertisement and forward it if it is from a Combustion device.
+fn forward_advertisement(
+    args: &BluetoothLEAdvertisementReceivedEventArgs,
+    tx: &broadcast::Sender<AdvertisementEvent>,
+    fragments: &Mutex<ManufacturerDataAssembler>,
+) {
+    let Ok(manufacturer_data) = args.Advertisement().and_then(|a| a.ManufacturerData()) else {
+        return;
+    };
+    let rssi = args.RawSignalStrengthInDBm().ok();
+    let Ok(address) = args.BluetoothAddress() else {
+        return;
+    };
+
+    for entry in manufacturer_data {
+        if entry.CompanyId().ok() != Some(COMBUSTION_MANUFACTURER_ID) {
+            continue;
+        }
+        let Ok(data) = entry.Data().and_then(|buffer| {
+            let reader = DataReader::FromBuffer(&buffer)?;
+            let mut data = vec![0u8; reader.UnconsumedBufferLength()? as usize];
+            reader.ReadBytes(&mut data)?;
+            Ok(data)
+        }) else {
+            continue;
+        };
+        let Some(data) = fragments
+            .lock()
+            .push(&address.to_string(), &data, Instant::now())
+        else {
+            continue;
+        };
+
+        match AdvertisingData::parse(&data) {
+            Ok(advertising_data) => {
+                let _ = tx.send(AdvertisementEvent {
+                    advertising_data,
+                    rssi,
+                });
+            }
+            Err(e) => trace!("Ignoring unparseable advertisement: {}", e),
+        }
+    }
+}
+
+/// Convert a WinRT error into a crate error.
+fn winrt_error(e: windows::core::Error) -> Error {
+    Error::Bluetooth(btleplug::Error::Other(Box::new(e)))
+}
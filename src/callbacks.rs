@@ -0,0 +1,325 @@
+//! Callback registration and isolation.
+//!
+//! Callbacks registered with methods such as
+//! [`Probe::on_temperatures_updated`](crate::Probe::on_temperatures_updated)
+//! each run in their own task. A panicking callback is caught, reported as a
+//! [`CallbackError`], and keeps receiving later events. The owning probe or
+//! device manager lists its active callbacks as [`CallbackInfo`] for
+//! debugging.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Callback handle for unregistering callbacks.
+///
+/// Dropping the handle unregisters the callback, so keep it for as long as
+/// the callback should run, or call [`detach`](Self::detach) to keep the
+/// callback for the lifetime of its event source. To register the same
+/// closure again after unregistering, register a clone of it (for example
+/// by sharing its state through an `Arc`).
+#[must_use = "dropping a CallbackHandle unregisters the callback; call detach() to keep it"]
+pub struct CallbackHandle {
+    id: u64,
+    unregister_fn: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl CallbackHandle {
+    /// Create a new callback handle.
+    pub(crate) fn new(id: u64, unregister_fn: impl FnOnce() + Send + Sync + 'static) -> Self {
+        Self {
+            id,
+            unregister_fn: Some(Box::new(unregister_fn)),
+        }
+    }
+
+    /// Unregister this callback.
+    pub fn unregister(mut self) {
+        if let Some(f) = self.unregister_fn.take() {
+            f();
+        }
+    }
+
+    /// Keep the callback registered without holding the handle.
+    ///
+    /// The callback runs until its event source (the probe or device
+    /// manager) is dropped, and can no longer be unregistered.
+    pub fn detach(mut self) {
+        self.unregister_fn.take();
+    }
+
+    /// Get the callback ID.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for CallbackHandle {
+    fn drop(&mut self) {
+        if let Some(f) = self.unregister_fn.take() {
+            f();
+        }
+    }
+}
+
+/// Description of a registered callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackInfo {
+    /// Callback ID, as returned by [`CallbackHandle::id`].
+    pub id: u64,
+    /// Event the callback is registered for, e.g. `"temperatures_updated"`.
+    pub event: &'static str,
+    /// When the callback was registered.
+    pub registered_at: DateTime<Utc>,
+    /// Number of times the callback has been called.
+    pub invocations: u64,
+    /// Number of calls that panicked.
+    pub panics: u64,
+    /// Panic message of the most recent panicking call.
+    pub last_panic: Option<String>,
+}
+
+/// A callback panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackError {
+    /// Callback ID.
+    pub id: u64,
+    /// Event the callback is registered for.
+    pub event: &'static str,
+    /// Panic message.
+    pub message: String,
+    /// When the panic was caught.
+    pub at: DateTime<Utc>,
+}
+
+/// Registered callbacks of one probe or device manager.
+#[derive(Clone)]
+pub(crate) struct CallbackRegistry {
+    inner: Arc<RegistryInner>,
+}
+
+struct RegistryInner {
+    /// Next callback ID.
+    next_id: AtomicU64,
+    /// Active callbacks by ID.
+    callbacks: Mutex<BTreeMap<u64, CallbackInfo>>,
+    /// Panic report channel.
+    error_tx: broadcast::Sender<CallbackError>,
+}
+
+impl CallbackRegistry {
+    /// Create an empty registry.
+    pub(crate) fn new() -> Self {
+        let (error_tx, _) = broadcast::channel(16);
+        Self {
+            inner: Arc::new(RegistryInner {
+                next_id: AtomicU64::new(0),
+                callbacks: Mutex::new(BTreeMap::new()),
+                error_tx,
+            }),
+        }
+    }
+
+    /// Run `callback` on each message from `rx` in its own task until the
+    /// handle is dropped or the channel closes.
+    ///
+    /// Panics in `callback` are caught and reported. Messages missed
+    /// because the callback fell behind are skipped.
+    pub(crate) fn spawn<T, F>(
+        &self,
+        event: &'static str,
+        mut rx: broadcast::Receiver<T>,
+        callback: F,
+    ) -> CallbackHandle
+    where
+        T: Clone + Send + 'static,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let id = self.insert(event);
+        let registry = self.clone();
+        let handle = crate::runtime::spawn(async move {
+            loop {
+                let message = match rx.recv().await {
+                    Ok(message) => message,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Callback {} ({}) skipped {} events", id, event, skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let result = std::panic::catch_unwind(AssertUnwindSafe(|| callback(message)));
+                registry.record_call(id, event, result.err().map(panic_message));
+            }
+            registry.remove(id);
+        });
+
+        let registry = self.clone();
+        CallbackHandle::new(id, move || {
+            handle.abort();
+            registry.remove(id);
+        })
+    }
+
+    /// List a background task, such as a relay, as a callback that runs
+    /// until the handle is dropped.
+    #[cfg(feature = "cloud")]
+    pub(crate) fn track(
+        &self,
+        event: &'static str,
+        task: crate::runtime::TaskHandle<()>,
+    ) -> CallbackHandle {
+        let id = self.insert(event);
+        let registry = self.clone();
+        CallbackHandle::new(id, move || {
+            task.abort();
+            registry.remove(id);
+        })
+    }
+
+    /// Add a callback, returning its ID.
+    fn insert(&self, event: &'static str) -> u64 {
+        let id = self.inner.next_id.fetch_add(1, Ordering::SeqCst);
+        self.inner.callbacks.lock().insert(
+            id,
+            CallbackInfo {
+                id,
+                event,
+                registered_at: Utc::now(),
+                invocations: 0,
+                panics: 0,
+                last_panic: None,
+            },
+        );
+        id
+    }
+
+    /// Get the active callbacks, in registration order.
+    pub(crate) fn list(&self) -> Vec<CallbackInfo> {
+        self.inner.callbacks.lock().values().cloned().collect()
+    }
+
+    /// Subscribe to panic reports.
+    pub(crate) fn subscribe_errors(&self) -> broadcast::Receiver<CallbackError> {
+        self.inner.error_tx.subscribe()
+    }
+
+    /// Record a call, and its panic message if it panicked.
+    fn record_call(&self, id: u64, event: &'static str, panic: Option<String>) {
+        let mut callbacks = self.inner.callbacks.lock();
+        let Some(info) = callbacks.get_mut(&id) else {
+            return;
+        };
+        info.invocations += 1;
+        let Some(message) = panic else {
+            return;
+        };
+
+        warn!("Callback {} ({}) panicked: {}", id, event, message);
+        info.panics += 1;
+        info.last_panic = Some(message.clone());
+        let _ = self.inner.error_tx.send(CallbackError {
+            id,
+            event,
+            message,
+            at: Utc::now(),
+        });
+    }
+
+    /// Forget a callback.
+    fn remove(&self, id: u64) {
+        self.inner.callbacks.lock().remove(&id);
+    }
+}
+
+/// Extract the message from a panic payload.
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_panics_are_isolated_and_reported() {
+        let registry = CallbackRegistry::new();
+        let mut errors = registry.subscribe_errors();
+        let (tx, _) = broadcast::channel(16);
+
+        let handle = registry.spawn("numbers", tx.subscribe(), |n: u32| {
+            if n == 2 {
+                panic!("bad number {}", n);
+            }
+        });
+        assert_eq!(registry.list().len(), 1);
+
+        for n in 1..=3 {
+            tx.send(n).unwrap();
+        }
+        let error = errors.recv().await.unwrap();
+        assert_eq!(error.id, handle.id());
+        assert_eq!(error.event, "numbers");
+        assert_eq!(error.message, "bad number 2");
+
+        // The callback keeps running after the panic
+        while registry.list()[0].invocations < 3 {
+            tokio::task::yield_now().await;
+        }
+        let info = &registry.list()[0];
+        assert_eq!(info.panics, 1);
+        assert_eq!(info.last_panic.as_deref(), Some("bad number 2"));
+
+        handle.unregister();
+        assert!(registry.list().is_empty());
+    }
+
+    /// Wait until every callback has handled `count` events.
+    async fn settle(registry: &CallbackRegistry, count: u64) {
+        while registry.list().iter().any(|info| info.invocations < count) {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_unregisters_and_detach_keeps() {
+        let registry = CallbackRegistry::new();
+        let (tx, _) = broadcast::channel(16);
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = {
+            let calls = calls.clone();
+            move |_: u32| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        // Dropping the handle unregisters
+        drop(registry.spawn("numbers", tx.subscribe(), counter.clone()));
+        assert!(registry.list().is_empty());
+
+        // The same closure can be registered again, and detached
+        registry
+            .spawn("numbers", tx.subscribe(), counter.clone())
+            .detach();
+        assert_eq!(registry.list().len(), 1);
+        tx.send(1).unwrap();
+        settle(&registry, 1).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A detached callback ends with its channel
+        drop(tx);
+        while !registry.list().is_empty() {
+            tokio::task::yield_now().await;
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! What a probe's firmware supports.
+//!
+//! The library reads the firmware revision when a probe connects and
+//! derives [`ProbeCapabilities`] from it. Only behavior documented in the
+//! probe BLE specification is keyed on the revision; message layouts are
+//! the specification's for every release.
+//!
+//! Older firmware also lacks the Probe Status characteristic, which carries
+//! everything but the advertised readings. Such probes are flagged in
+//! [`ProbeCapabilities::status_characteristic`] when they connect, and a
+//! [`StatusUnavailable`] diagnostic is published so the missing prediction
+//! and food safety data is explained rather than silently absent. Session
+//! information and the temperature log are polled over UART instead.
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// A firmware release number, e.g. `1.4.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirmwareVersion {
+    /// Major version.
+    pub major: u16,
+    /// Minor version.
+    pub minor: u16,
+    /// Patch version.
+    pub patch: u16,
+}
+
+impl FirmwareVersion {
+    /// Create a firmware version.
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for FirmwareVersion {
+    type Err = Error;
+
+    /// Parse a revision string such as `1.4.0`, `v1.4` or `1.4.0-rc2`.
+    ///
+    /// Missing minor or patch numbers read as 0, and anything after the
+    /// numbers is ignored.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidParameter {
+            name: "firmware_version".to_string(),
+            value: s.to_string(),
+        };
+
+        let trimmed = s.trim();
+        let trimmed = trimmed
+            .strip_prefix(['v', 'V'])
+            .unwrap_or(trimmed)
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()
+            .unwrap_or("");
+
+        let mut numbers = trimmed.split('.').map(|part| part.parse::<u16>());
+        let major = numbers.next().and_then(|n| n.ok()).ok_or_else(invalid)?;
+        let minor = numbers.next().transpose().map_err(|_| invalid())?;
+        let patch = numbers.next().transpose().map_err(|_| invalid())?;
+        Ok(Self::new(major, minor.unwrap_or(0), patch.unwrap_or(0)))
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Message layouts and features a probe supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProbeCapabilities {
+    /// Firmware version the capabilities were derived from, if known.
+    pub firmware_version: Option<FirmwareVersion>,
+    /// Whether the probe has the Probe Status characteristic.
+    ///
+    /// Assumed until a connection finds it missing. Without it, readings,
+    /// mode and battery come from advertising only, session information and
+    /// the temperature log are polled over UART, and prediction, food
+    /// safety, alarm and power mode data are unavailable.
+    #[cfg_attr(feature = "serde", serde(default = "status_characteristic_default"))]
+    pub status_characteristic: bool,
+}
+
+/// Default for [`ProbeCapabilities::status_characteristic`] when
+/// deserializing.
+#[cfg(feature = "serde")]
+fn status_characteristic_default() -> bool {
+    true
+}
+
+impl Default for ProbeCapabilities {
+    fn default() -> Self {
+        Self {
+            firmware_version: None,
+            status_characteristic: true,
+        }
+    }
+}
+
+impl ProbeCapabilities {
+    /// Derive capabilities from a firmware version.
+    pub fn for_firmware(version: FirmwareVersion) -> Self {
+        Self {
+            firmware_version: Some(version),
+            ..Self::default()
+        }
+    }
+
+    /// Derive capabilities from a firmware revision string, as read from the
+    /// Device Information service.
+    ///
+    /// Unparseable revisions give the default capabilities.
+    pub fn from_firmware_revision(revision: &str) -> Self {
+        revision.parse().map(Self::for_firmware).unwrap_or_default()
+    }
+
+    /// Get the highest prediction set point the probe accepts, in Celsius.
+    ///
+    /// The Set Prediction request and the prediction status both carry the
+    /// set point in a 10-bit field of 0.1°C steps, so this is
+    /// [`PredictionInfo::MAX_SET_POINT`](crate::data::PredictionInfo::MAX_SET_POINT)
+    /// for every firmware release documented in the
+    /// [probe BLE specification](https://github.com/combustion-inc/combustion-documentation/blob/main/probe_ble_specification.rst).
+    pub fn max_set_point(&self) -> f64 {
+        crate::data::PredictionInfo::MAX_SET_POINT
+    }
+}
+
+/// Diagnostic raised when a connected probe has no Probe Status
+/// characteristic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusUnavailable {
+    /// Serial number of the probe.
+    pub serial_number: u32,
+    /// Firmware version of the probe, if known.
+    pub firmware_version: Option<FirmwareVersion>,
+    /// When the connection found the characteristic missing.
+    pub detected_at: DateTime<Utc>,
+}
+
+impl StatusUnavailable {
+    /// Data the probe cannot provide without the characteristic.
+    ///
+    /// The probe offers no UART request returning this data, so there is
+    /// nothing to poll instead. Session information and the temperature
+    /// log are polled over UART.
+    pub const UNAVAILABLE: &'static [&'static str] =
+        &["prediction", "food safety", "alarm status", "power mode"];
+}
+
+impl fmt::Display for StatusUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Probe {:08X}", self.serial_number)?;
+        if let Some(version) = self.firmware_version {
+            write!(f, " (firmware {})", version)?;
+        }
+        write!(
+            f,
+            " has no Probe Status characteristic: {} are unavailable, \
+             readings come from advertising only and the log is polled; \
+             updating the probe's firmware adds it",
+            Self::UNAVAILABLE.join(", ")
+        )
+    }
+}
+
+/// Diagnostic raised when a probe's status notifications keep failing to
+/// parse, usually because its firmware is newer than this library.
+///
+/// Once raised, status notifications are ignored until the probe
+/// reconnects, so a mismatched probe doesn't fill the logs and the
+/// quarantine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolMismatch {
+    /// Serial number of the probe.
+    pub serial_number: u32,
+    /// Firmware version of the probe, if known.
+    pub firmware_version: Option<FirmwareVersion>,
+    /// Consecutive notifications that failed to parse.
+    pub consecutive_failures: u32,
+    /// Why the last notification failed to parse.
+    pub last_error: String,
+    /// When status parsing was suspended.
+    pub detected_at: DateTime<Utc>,
+}
+
+impl ProtocolMismatch {
+    /// Consecutive parse failures that raise the diagnostic.
+    pub const FAILURE_THRESHOLD: u32 = 10;
+}
+
+impl fmt::Display for ProtocolMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Probe {:08X}", self.serial_number)?;
+        if let Some(version) = self.firmware_version {
+            write!(f, " (firmware {})", version)?;
+        }
+        write!(
+            f,
+            " sent {} status notifications in a row that failed to parse \
+             (last: {}); ignoring status notifications until it reconnects",
+            self.consecutive_failures, self.last_error
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_firmware_version() {
+        assert_eq!(
+            "1.4.0".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(1, 4, 0)
+        );
+        assert_eq!(
+            "v2.1".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(2, 1, 0)
+        );
+        assert_eq!(
+            " 1.3.12-rc2 ".parse::<FirmwareVersion>().unwrap(),
+            FirmwareVersion::new(1, 3, 12)
+        );
+        assert!("".parse::<FirmwareVersion>().is_err());
+        assert!("beta".parse::<FirmwareVersion>().is_err());
+        assert_eq!(FirmwareVersion::new(1, 4, 0).to_string(), "1.4.0");
+    }
+
+    #[test]
+    fn test_capabilities_from_firmware() {
+        let capabilities = ProbeCapabilities::from_firmware_revision("v1.4.0");
+        assert_eq!(
+            capabilities.firmware_version,
+            Some(FirmwareVersion::new(1, 4, 0))
+        );
+        assert!((capabilities.max_set_point() - 102.3).abs() < 1e-9);
+
+        assert_eq!(
+            ProbeCapabilities::from_firmware_revision("unknown"),
+            ProbeCapabilities::default()
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Rendering a session's temperature curves to PNG or SVG.
+//!
+//! A [`SessionChart`] plots the virtual Core, Surface, and Ambient
+//! temperatures of a [`TemperatureLog`] against minutes since the start of
+//! the log, with the prediction setpoint as a horizontal line and the moment
+//! the food became safe as a vertical marker. Logs without prediction data
+//! plot the physical sensors T1-T8 instead.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::charts::SessionChart;
+//!
+//! # fn example(log: &combustion_rust_ble::TemperatureLog) -> combustion_rust_ble::Result<()> {
+//! SessionChart::new(log)
+//!     .with_title("Brisket")
+//!     .with_setpoint(95.0)
+//!     .save_png("brisket.png")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Text is set in DejaVu Sans, which is embedded in the library, so charts
+//! look the same on every host, including ones without any fonts installed.
+//! Requires the `charts` feature.
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::register_font;
+use std::path::Path;
+use std::sync::Once;
+
+use crate::data::{AlarmSensor, LoggedDataPoint, PhysicalSensor, TemperatureLog, VirtualSensor};
+use crate::error::{Error, Result};
+use crate::utils::TemperatureUnit;
+
+/// Font all chart text is set in.
+const FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+
+/// Family name the embedded font is registered under, which plotters also
+/// uses for labels without a font of their own.
+const FONT_FAMILY: &str = "sans-serif";
+
+/// Default image width in pixels.
+const DEFAULT_WIDTH: u32 = 1024;
+
+/// Default image height in pixels.
+const DEFAULT_HEIGHT: u32 = 576;
+
+/// Colors of the Core, Surface, and Ambient curves.
+const VIRTUAL_COLORS: [RGBColor; 3] = [
+    RGBColor(214, 39, 40),
+    RGBColor(255, 127, 14),
+    RGBColor(31, 119, 180),
+];
+
+/// A chart of the temperature curves of one session.
+#[derive(Debug, Clone)]
+pub struct SessionChart<'a> {
+    /// Log being plotted.
+    log: &'a TemperatureLog,
+    /// Caption above the chart.
+    title: Option<String>,
+    /// Image size in pixels.
+    size: (u32, u32),
+    /// Setpoint in Celsius, overriding the one in the log.
+    setpoint: Option<f64>,
+    /// Sequence number at which the food became safe.
+    food_safe_at: Option<u32>,
+    /// Unit temperatures are plotted in.
+    unit: TemperatureUnit,
+}
+
+/// One curve of the chart.
+struct Curve {
+    /// Legend label.
+    label: &'static str,
+    /// Line color.
+    color: RGBColor,
+    /// (minutes, temperature) points in the chart's unit, oldest first.
+    points: Vec<(f64, f64)>,
+}
+
+impl<'a> SessionChart<'a> {
+    /// Create a chart of `log`.
+    pub fn new(log: &'a TemperatureLog) -> Self {
+        Self {
+            log,
+            title: None,
+            size: (DEFAULT_WIDTH, DEFAULT_HEIGHT),
+            setpoint: None,
+            food_safe_at: None,
+            unit: TemperatureUnit::Celsius,
+        }
+    }
+
+    /// Plot temperatures in `unit`. Defaults to Celsius.
+    pub fn with_unit(mut self, unit: TemperatureUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Set the caption above the chart.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the image size in pixels.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width.max(1), height.max(1));
+        self
+    }
+
+    /// Set the setpoint line, in Celsius.
+    ///
+    /// By default the last prediction setpoint in the log is drawn, if any.
+    pub fn with_setpoint(mut self, celsius: f64) -> Self {
+        self.setpoint = Some(celsius);
+        self
+    }
+
+    /// Mark the log sequence number at which the food became safe.
+    pub fn with_food_safe_at(mut self, sequence_number: u32) -> Self {
+        self.food_safe_at = Some(sequence_number);
+        self
+    }
+
+    /// Render the chart as an SVG document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if the chart cannot be rendered.
+    pub fn to_svg(&self) -> Result<String> {
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg, self.size).into_drawing_area();
+            self.draw(&root).map_err(chart_error)?;
+        }
+        Ok(svg)
+    }
+
+    /// Render the chart as SVG to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if the chart cannot be rendered or the
+    /// file cannot be written.
+    pub fn save_svg(&self, path: impl AsRef<Path>) -> Result<()> {
+        let root = SVGBackend::new(path.as_ref(), self.size).into_drawing_area();
+        self.draw(&root).map_err(chart_error)
+    }
+
+    /// Render the chart as PNG to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if the chart cannot be rendered or the
+    /// file cannot be written.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<()> {
+        let root = BitMapBackend::new(path.as_ref(), self.size).into_drawing_area();
+        self.draw(&root).map_err(chart_error)
+    }
+
+    /// Get the setpoint line, in Celsius.
+    fn setpoint(&self) -> Option<f64> {
+        self.setpoint.or_else(|| {
+            self.log
+                .data_points
+                .iter()
+                .rev()
+                .filter_map(|p| p.prediction_log.as_ref())
+                .map(|p| p.prediction_set_point)
+                .find(|celsius| *celsius > 0.0)
+        })
+    }
+
+    /// Get the minutes from the start of the log to a sequence number.
+    fn minutes(&self, sequence_number: u32) -> f64 {
+        let start = self.log.min_sequence().unwrap_or(0);
+        let samples = sequence_number.saturating_sub(start) as f64;
+        samples * self.log.sample_period_ms as f64 / 60_000.0
+    }
+
+    /// Collect the curves to plot.
+    fn curves(&self) -> Vec<Curve> {
+        let mut points: Vec<&LoggedDataPoint> = self.log.data_points.iter().collect();
+        points.sort_by_key(|p| p.sequence_number);
+        let series = |sensor: AlarmSensor| -> Vec<(f64, f64)> {
+            points
+                .iter()
+                .filter_map(|p| {
+                    let celsius = p.reading(sensor)?;
+                    Some((
+                        self.minutes(p.sequence_number),
+                        self.unit.from_celsius(celsius),
+                    ))
+                })
+                .collect()
+        };
+
+        if points.iter().any(|p| p.prediction_log.is_some()) {
+            VirtualSensor::ALL
+                .into_iter()
+                .zip(VIRTUAL_COLORS)
+                .map(|(sensor, color)| Curve {
+                    label: sensor.name(),
+                    color,
+                    points: series(sensor.into()),
+                })
+                .collect()
+        } else {
+            PhysicalSensor::ALL
+                .into_iter()
+                .enumerate()
+                .map(|(i, sensor)| Curve {
+                    label: sensor.name(),
+                    color: {
+                        let (r, g, b) = Palette99::pick(i).rgb();
+                        RGBColor(r, g, b)
+                    },
+                    points: series(sensor.into()),
+                })
+                .filter(|curve| !curve.points.is_empty())
+                .collect()
+        }
+    }
+
+    /// Draw the chart onto `root`.
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> std::result::Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        static REGISTER_FONT: Once = Once::new();
+        REGISTER_FONT.call_once(|| {
+            // Should the font fail to load, laying out text fails and the
+            // error is returned from rendering
+            let _ = register_font(FONT_FAMILY, FontStyle::Normal, FONT);
+        });
+
+        let curves = self.curves();
+        let setpoint = self
+            .setpoint()
+            .map(|celsius| self.unit.from_celsius(celsius));
+        let food_safe_at = self.food_safe_at.map(|seq| self.minutes(seq));
+
+        let end = self.minutes(self.log.max_sequence().unwrap_or(0)).max(1.0);
+        let temperatures = curves
+            .iter()
+            .flat_map(|c| c.points.iter().map(|(_, temperature)| *temperature))
+            .chain(setpoint);
+        let low = temperatures.clone().fold(f64::INFINITY, f64::min);
+        let high = temperatures.fold(f64::NEG_INFINITY, f64::max);
+        let (low, high) = if low <= high {
+            (low.min(0.0), high + 10.0)
+        } else {
+            (0.0, 100.0)
+        };
+
+        root.fill(&WHITE)?;
+        let mut builder = ChartBuilder::on(root);
+        builder
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(50);
+        if let Some(title) = &self.title {
+            builder.caption(title, (FONT_FAMILY, 24));
+        }
+        let mut chart = builder.build_cartesian_2d(0.0..end, low..high)?;
+        chart
+            .configure_mesh()
+            .x_desc("Minutes")
+            .y_desc(format!("Temperature ({})", self.unit.symbol()))
+            .draw()?;
+
+        for curve in curves {
+            let color = curve.color;
+            chart
+                .draw_series(LineSeries::new(curve.points, color.stroke_width(2)))?
+                .label(curve.label)
+                .legend(move |(x, y)| {
+                    PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2))
+                });
+        }
+        if let Some(setpoint) = setpoint {
+            chart
+                .draw_series(LineSeries::new([(0.0, setpoint), (end, setpoint)], BLACK))?
+                .label("Setpoint")
+                .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], BLACK));
+        }
+        if let Some(minutes) = food_safe_at {
+            let green = RGBColor(44, 160, 44);
+            chart
+                .draw_series(LineSeries::new([(minutes, low), (minutes, high)], green))?
+                .label("Food safe")
+                .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], green));
+        }
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+        root.present()
+    }
+}
+
+/// Create a storage error for a failed chart rendering.
+fn chart_error(error: impl std::fmt::Display) -> Error {
+    Error::Storage {
+        reason: format!("failed to render chart: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PredictionLog, ProbeTemperatures, RawTemperature};
+
+    fn log() -> TemperatureLog {
+        let mut log = TemperatureLog::new(1, 60_000);
+        for i in 0..30 {
+            let mut temperatures = ProbeTemperatures::new();
+            temperatures.values[0] = RawTemperature::from_celsius(20.0 + i as f64 * 2.0);
+            let prediction = PredictionLog {
+                virtual_core: 20.0 + i as f64 * 2.0,
+                virtual_surface: 30.0 + i as f64 * 3.0,
+                virtual_ambient: 150.0,
+                prediction_set_point: 63.0,
+                ..Default::default()
+            };
+            log.add_data_point(LoggedDataPoint::with_prediction(
+                i,
+                temperatures,
+                prediction,
+            ));
+        }
+        log
+    }
+
+    #[test]
+    fn test_svg_contains_curves_and_markers() {
+        let log = log();
+        let chart = SessionChart::new(&log).with_title("Chicken");
+        assert_eq!(chart.setpoint(), Some(63.0));
+        assert_eq!(chart.curves().len(), 3);
+        assert_eq!(chart.minutes(29), 29.0);
+
+        let svg = chart.with_food_safe_at(25).to_svg().unwrap();
+        assert!(svg.starts_with("<svg"));
+        for text in [
+            "Chicken",
+            "Core",
+            "Surface",
+            "Ambient",
+            "Setpoint",
+            "Food safe",
+        ] {
+            assert!(svg.contains(text), "missing {}", text);
+        }
+    }
+
+    #[test]
+    fn test_fahrenheit() {
+        let log = log();
+        let chart = SessionChart::new(&log).with_unit(TemperatureUnit::Fahrenheit);
+        assert_eq!(chart.curves()[0].points[0], (0.0, 68.0));
+
+        let svg = chart.to_svg().unwrap();
+        assert!(svg.contains("Temperature (°F)"));
+        assert!(!svg.contains("°C"));
+    }
+
+    #[test]
+    fn test_physical_sensors_without_predictions() {
+        let mut log = log();
+        for point in &mut log.data_points {
+            point.prediction_log = None;
+        }
+        let chart = SessionChart::new(&log);
+        assert_eq!(chart.setpoint(), None);
+        let curves = chart.curves();
+        assert_eq!(curves.len(), 1);
+        assert_eq!(curves[0].label, "T1");
+
+        let path =
+            std::env::temp_dir().join(format!("combustion-chart-{}.png", std::process::id()));
+        chart.with_size(320, 200).save_png(&path).unwrap();
+        assert!(std::fs::read(&path).unwrap().starts_with(b"\x89PNG"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
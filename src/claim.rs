@@ -0,0 +1,413 @@
+//! Claiming a probe by a physical action.
+//!
+//! Where many probes are in range, as in a shared kitchen or at a
+//! competition, discovery alone can't say which probe is the user's.
+//! [`DeviceManager::claim_probe`](crate::DeviceManager::claim_probe) asks
+//! the user to do something to their probe, such as taking it out of the
+//! charger, switching it into instant read, or gripping the tip, and
+//! returns the one probe that reacted as a [`ProbeClaim`]. With the
+//! `persistence` feature and a known probe registry set, the claimed probe
+//! is remembered as the user's own.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::claim::ClaimOptions;
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! # async fn example(manager: &DeviceManager) -> combustion_rust_ble::Result<()> {
+//! println!("Take your probe out of its charger...");
+//! match manager.claim_probe(ClaimOptions::new()).await? {
+//!     Some(claim) => println!("Claimed {} ({})", claim.probe.serial_number_string(), claim.action),
+//!     None => println!("No probe reacted"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::{BatteryStatus, ProbeMode};
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+
+/// A physical action that picked out a probe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClaimAction {
+    /// The probe was first discovered while claiming, e.g. because it woke
+    /// when taken out of the charger.
+    Appeared,
+    /// The probe switched mode.
+    ModeChanged {
+        /// Mode before the change.
+        from: ProbeMode,
+        /// Mode after the change.
+        to: ProbeMode,
+    },
+    /// The probe's battery status changed.
+    BatteryChanged {
+        /// Status before the change.
+        from: BatteryStatus,
+        /// Status after the change.
+        to: BatteryStatus,
+    },
+    /// The instant read temperature rose sharply, e.g. from gripping the
+    /// tip.
+    InstantReadSpike {
+        /// Rise above the lowest reading since entering instant read, in
+        /// Celsius.
+        rise_celsius: f64,
+    },
+}
+
+impl std::fmt::Display for ClaimAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Appeared => f.write_str("appeared"),
+            Self::ModeChanged { from, to } => write!(f, "mode changed from {:?} to {:?}", from, to),
+            Self::BatteryChanged { from, to } => {
+                write!(f, "battery changed from {:?} to {:?}", from, to)
+            }
+            Self::InstantReadSpike { rise_celsius } => {
+                write!(f, "instant read rose {:.1}°C", rise_celsius)
+            }
+        }
+    }
+}
+
+/// How to claim a probe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimOptions {
+    /// How long to wait for an action.
+    pub window: Duration,
+    /// How long no other probe may act either side of the claimed one's
+    /// action, so a foreign probe acting at the same time isn't claimed.
+    pub quiet_period: Duration,
+    /// Rise in the instant read temperature that counts as a spike, in
+    /// Celsius.
+    pub spike_celsius: f64,
+    /// Whether a probe discovered while claiming counts as acting.
+    pub claim_new_probes: bool,
+}
+
+impl ClaimOptions {
+    /// Create options waiting 60 seconds, with a 2 second quiet period and
+    /// a 5°C instant read spike.
+    pub fn new() -> Self {
+        Self {
+            window: Duration::from_secs(60),
+            quiet_period: Duration::from_secs(2),
+            spike_celsius: 5.0,
+            claim_new_probes: true,
+        }
+    }
+
+    /// Set how long to wait for an action.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set how long no other probe may act around the claimed one's action.
+    pub fn with_quiet_period(mut self, quiet_period: Duration) -> Self {
+        self.quiet_period = quiet_period;
+        self
+    }
+
+    /// Set the rise in instant read temperature that counts as a spike.
+    pub fn with_spike(mut self, celsius: f64) -> Self {
+        self.spike_celsius = celsius;
+        self
+    }
+
+    /// Ignore probes discovered while claiming.
+    pub fn without_new_probes(mut self) -> Self {
+        self.claim_new_probes = false;
+        self
+    }
+
+    /// Check that the options can pick out a probe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if the window is zero or the
+    /// spike is not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.window.is_zero() {
+            return Err(Error::InvalidParameter {
+                name: "window".to_string(),
+                value: format!("{:?}", self.window),
+            });
+        }
+        if self.spike_celsius.is_nan() || self.spike_celsius <= 0.0 {
+            return Err(Error::InvalidParameter {
+                name: "spike_celsius".to_string(),
+                value: self.spike_celsius.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for ClaimOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A probe picked out by a physical action.
+#[derive(Clone)]
+pub struct ProbeClaim {
+    /// The claimed probe.
+    pub probe: Arc<Probe>,
+    /// What the probe did.
+    pub action: ClaimAction,
+    /// When the claim was confirmed.
+    pub claimed_at: DateTime<Utc>,
+    /// Whether the probe was remembered in the manager's known probe
+    /// registry.
+    pub remembered: bool,
+}
+
+impl std::fmt::Debug for ProbeClaim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProbeClaim")
+            .field("serial_number", &self.probe.serial_number_string())
+            .field("action", &self.action)
+            .field("claimed_at", &self.claimed_at)
+            .field("remembered", &self.remembered)
+            .finish()
+    }
+}
+
+/// What [`ClaimDetector`] samples from each probe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ClaimSample {
+    /// Operating mode.
+    pub(crate) mode: ProbeMode,
+    /// Battery status.
+    pub(crate) battery: BatteryStatus,
+    /// T1 in Celsius while in instant read.
+    pub(crate) instant_read: Option<f64>,
+}
+
+impl ClaimSample {
+    /// Sample a probe's current state.
+    pub(crate) fn of(probe: &Probe) -> Self {
+        let mode = probe.mode();
+        Self {
+            mode,
+            battery: probe.battery_status(),
+            instant_read: (mode == ProbeMode::InstantRead)
+                .then(|| probe.current_temperatures().values[0].to_celsius())
+                .flatten(),
+        }
+    }
+}
+
+/// Picks out the one probe that acted, from periodic samples of every
+/// probe.
+///
+/// The first samples set each probe's baseline. A probe that then acts is
+/// claimed once [`quiet_period`](ClaimOptions::quiet_period) has passed
+/// with no other probe acting since a quiet period before its action.
+#[derive(Debug)]
+pub(crate) struct ClaimDetector {
+    /// Options in use.
+    options: ClaimOptions,
+    /// Whether baselines have been taken.
+    started: bool,
+    /// Latest sample by serial number.
+    samples: HashMap<u32, ClaimSample>,
+    /// Lowest instant read since entering instant read, by serial number.
+    floors: HashMap<u32, f64>,
+    /// Latest action by serial number.
+    actions: HashMap<u32, (ClaimAction, Instant)>,
+}
+
+impl ClaimDetector {
+    pub(crate) fn new(options: ClaimOptions) -> Self {
+        Self {
+            options,
+            started: false,
+            samples: HashMap::new(),
+            floors: HashMap::new(),
+            actions: HashMap::new(),
+        }
+    }
+
+    /// Record one sample per probe at `now`, returning the claimed probe's
+    /// serial number and action once one is confirmed.
+    pub(crate) fn update(
+        &mut self,
+        now: Instant,
+        samples: impl IntoIterator<Item = (u32, ClaimSample)>,
+    ) -> Option<(u32, ClaimAction)> {
+        for (serial, sample) in samples {
+            if let Some(action) = self.detect(serial, sample) {
+                self.actions.insert(serial, (action, now));
+            }
+        }
+        self.started = true;
+        self.confirmed(now)
+    }
+
+    /// Check if a probe has acted but is not yet confirmed.
+    pub(crate) fn is_pending(&self) -> bool {
+        !self.actions.is_empty()
+    }
+
+    /// Compare a sample with the probe's previous one.
+    fn detect(&mut self, serial: u32, sample: ClaimSample) -> Option<ClaimAction> {
+        let floor = match sample.instant_read {
+            Some(celsius) => {
+                let floor = self.floors.entry(serial).or_insert(celsius);
+                *floor = floor.min(celsius);
+                Some(floor)
+            }
+            None => {
+                self.floors.remove(&serial);
+                None
+            }
+        };
+        let spike = match (floor, sample.instant_read) {
+            (Some(floor), Some(celsius)) if celsius - *floor >= self.options.spike_celsius => {
+                let rise_celsius = celsius - *floor;
+                *floor = celsius;
+                Some(ClaimAction::InstantReadSpike { rise_celsius })
+            }
+            _ => None,
+        };
+
+        let Some(previous) = self.samples.insert(serial, sample) else {
+            return (self.started && self.options.claim_new_probes)
+                .then_some(ClaimAction::Appeared);
+        };
+        if previous.mode != sample.mode {
+            Some(ClaimAction::ModeChanged {
+                from: previous.mode,
+                to: sample.mode,
+            })
+        } else if previous.battery != sample.battery {
+            Some(ClaimAction::BatteryChanged {
+                from: previous.battery,
+                to: sample.battery,
+            })
+        } else {
+            spike
+        }
+    }
+
+    /// Get the probe whose action has outlasted the quiet period alone.
+    fn confirmed(&mut self, now: Instant) -> Option<(u32, ClaimAction)> {
+        let quiet = self.options.quiet_period;
+        // Actions too old to conflict with any still waiting
+        self.actions
+            .retain(|_, (_, at)| now.duration_since(*at) <= quiet * 2);
+
+        let (&serial, &(action, at)) = self
+            .actions
+            .iter()
+            .filter(|(_, (_, at))| now.duration_since(*at) >= quiet)
+            .min_by_key(|(_, (_, at))| *at)?;
+        let contested = self
+            .actions
+            .iter()
+            .any(|(&other, (_, other_at))| other != serial && *other_at + quiet >= at);
+        if contested {
+            return None;
+        }
+        self.actions.clear();
+        Some((serial, action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(mode: ProbeMode, instant_read: Option<f64>) -> ClaimSample {
+        ClaimSample {
+            mode,
+            battery: BatteryStatus::Ok,
+            instant_read,
+        }
+    }
+
+    #[test]
+    fn test_claim_detector() {
+        let normal = sample(ProbeMode::Normal, None);
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut detector = ClaimDetector::new(ClaimOptions::new());
+
+        // Baselines, then a new probe and a mode change at the same time
+        assert_eq!(detector.update(at(0), [(1, normal), (2, normal)]), None);
+        let instant_read = sample(ProbeMode::InstantRead, Some(20.0));
+        assert_eq!(
+            detector.update(at(1), [(1, instant_read), (2, normal), (3, normal)]),
+            None
+        );
+        assert!(detector.is_pending());
+        assert_eq!(
+            detector.update(at(4), [(1, instant_read), (2, normal), (3, normal)]),
+            None
+        );
+        // Both dropped once too old to matter
+        assert_eq!(
+            detector.update(at(6), [(1, instant_read), (2, normal), (3, normal)]),
+            None
+        );
+        assert!(!detector.is_pending());
+
+        // Gripping the tip warms it past the spike in steps
+        let warming = |celsius| sample(ProbeMode::InstantRead, Some(celsius));
+        assert_eq!(
+            detector.update(at(7), [(1, warming(19.0)), (2, normal)]),
+            None
+        );
+        assert_eq!(
+            detector.update(at(8), [(1, warming(22.0)), (2, normal)]),
+            None
+        );
+        assert_eq!(
+            detector.update(at(9), [(1, warming(25.0)), (2, normal)]),
+            None
+        );
+        let claimed = detector.update(at(11), [(1, warming(25.0)), (2, normal)]);
+        assert_eq!(
+            claimed,
+            Some((1, ClaimAction::InstantReadSpike { rise_celsius: 6.0 }))
+        );
+        assert!(!detector.is_pending());
+
+        // Battery change, with new probes ignored
+        let mut detector = ClaimDetector::new(ClaimOptions::new().without_new_probes());
+        detector.update(at(0), [(1, normal)]);
+        let low = ClaimSample {
+            battery: BatteryStatus::Low,
+            ..normal
+        };
+        detector.update(at(1), [(1, normal), (2, low)]);
+        detector.update(at(2), [(1, low), (2, low)]);
+        assert_eq!(
+            detector.update(at(4), [(1, low), (2, low)]),
+            Some((
+                1,
+                ClaimAction::BatteryChanged {
+                    from: BatteryStatus::Ok,
+                    to: BatteryStatus::Low
+                }
+            ))
+        );
+
+        assert!(ClaimOptions::new().with_spike(0.0).validate().is_err());
+        assert!(ClaimOptions::new()
+            .with_window(Duration::ZERO)
+            .validate()
+            .is_err());
+    }
+}
@@ -0,0 +1,415 @@
+//! Cloud relay client for remote monitoring.
+//!
+//! Periodically captures [`ProbeSnapshot`]s from a [`DeviceManager`] and pushes
+//! them in batches to a user-configured HTTPS endpoint using bearer-token
+//! authentication. Snapshots are held in a bounded offline queue and retried
+//! with exponential backoff while the endpoint is unreachable.
+//!
+//! Requires the `cloud` feature.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::cloud::{CloudRelay, CloudRelayConfig};
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! # async fn example() -> combustion_rust_ble::Result<()> {
+//! let manager = DeviceManager::new().await?;
+//! manager.start_scanning().await?;
+//!
+//! let relay = CloudRelay::new(CloudRelayConfig::new(
+//!     "https://example.com/api/snapshots",
+//!     "my-token",
+//! ))?;
+//! let _handle = relay.start(&manager);
+//! # Ok(())
+//! # }
+//! ```
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::device_manager::DeviceManager;
+use crate::error::{Error, Result};
+use crate::probe::CallbackHandle;
+use crate::snapshot::ProbeSnapshot;
+
+/// Configuration for a [`CloudRelay`].
+#[derive(Clone)]
+pub struct CloudRelayConfig {
+    /// HTTPS endpoint that receives snapshot batches via POST.
+    pub endpoint: String,
+
+    /// Bearer token sent in the `Authorization` header.
+    pub token: String,
+
+    /// Maximum number of snapshots per request.
+    pub batch_size: usize,
+
+    /// How often snapshots are captured from the device manager.
+    pub snapshot_interval: Duration,
+
+    /// How often queued snapshots are flushed to the endpoint.
+    pub flush_interval: Duration,
+
+    /// Maximum number of snapshots held while offline. Oldest are dropped first.
+    pub max_queue_len: usize,
+
+    /// Timeout for a single request.
+    pub request_timeout: Duration,
+
+    /// Upper bound for the retry backoff after failed flushes.
+    pub max_backoff: Duration,
+}
+
+impl CloudRelayConfig {
+    /// Create a configuration with default batching and retry settings.
+    pub fn new(endpoint: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            token: token.into(),
+            batch_size: 50,
+            snapshot_interval: Duration::from_secs(10),
+            flush_interval: Duration::from_secs(30),
+            max_queue_len: 10_000,
+            request_timeout: Duration::from_secs(15),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+
+    /// Set the maximum number of snapshots per request.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the snapshot capture interval.
+    pub fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = interval;
+        self
+    }
+
+    /// Set the flush interval.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Set the maximum offline queue length.
+    pub fn with_max_queue_len(mut self, max_queue_len: usize) -> Self {
+        self.max_queue_len = max_queue_len;
+        self
+    }
+
+    /// Validate the configuration.
+    fn validate(&self) -> Result<()> {
+        if !self.endpoint.starts_with("https://") {
+            return Err(Error::InvalidParameter {
+                name: "endpoint".to_string(),
+                value: self.endpoint.clone(),
+            });
+        }
+        if self.batch_size == 0 {
+            return Err(Error::InvalidParameter {
+                name: "batch_size".to_string(),
+                value: self.batch_size.to_string(),
+            });
+        }
+        if self.max_queue_len == 0 {
+            return Err(Error::InvalidParameter {
+                name: "max_queue_len".to_string(),
+                value: self.max_queue_len.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CloudRelayConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudRelayConfig")
+            .field("endpoint", &self.endpoint)
+            .field("token", &"..")
+            .field("batch_size", &self.batch_size)
+            .field("snapshot_interval", &self.snapshot_interval)
+            .field("flush_interval", &self.flush_interval)
+            .field("max_queue_len", &self.max_queue_len)
+            .field("request_timeout", &self.request_timeout)
+            .field("max_backoff", &self.max_backoff)
+            .finish()
+    }
+}
+
+/// Request body posted to the endpoint.
+#[derive(serde::Serialize)]
+struct SnapshotBatch<'a> {
+    snapshots: &'a [ProbeSnapshot],
+}
+
+/// Pushes probe snapshots to a remote endpoint.
+#[derive(Clone)]
+pub struct CloudRelay {
+    /// Relay configuration.
+    config: Arc<CloudRelayConfig>,
+    /// HTTP client.
+    client: reqwest::Client,
+    /// Offline queue of snapshots awaiting upload, each with an ID that
+    /// increases through the queue.
+    queue: Arc<Mutex<VecDeque<(u64, ProbeSnapshot)>>>,
+    /// ID of the next queued snapshot.
+    next_id: Arc<AtomicU64>,
+    /// Number of snapshots dropped because the queue was full.
+    dropped: Arc<AtomicU64>,
+}
+
+impl CloudRelay {
+    /// Create a new relay.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid (e.g. a non-HTTPS endpoint).
+    pub fn new(config: CloudRelayConfig) -> Result<Self> {
+        config.validate()?;
+
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()
+            .map_err(|e| Error::Network {
+                reason: e.to_string(),
+            })?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            client,
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            next_id: Arc::new(AtomicU64::new(0)),
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Get the relay configuration.
+    pub fn config(&self) -> &CloudRelayConfig {
+        &self.config
+    }
+
+    /// Queue a snapshot for upload.
+    ///
+    /// If the queue is full, the oldest snapshot is dropped.
+    pub fn enqueue(&self, snapshot: ProbeSnapshot) {
+        let mut queue = self.queue.lock();
+        while queue.len() >= self.config.max_queue_len {
+            queue.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        queue.push_back((id, snapshot));
+    }
+
+    /// Get the number of snapshots waiting to be uploaded.
+    pub fn pending(&self) -> usize {
+        self.queue.lock().len()
+    }
+
+    /// Get the number of snapshots dropped because the queue overflowed.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Upload all queued snapshots in batches.
+    ///
+    /// Stops at the first failed batch, leaving it and any remaining
+    /// snapshots queued for the next attempt.
+    ///
+    /// # Returns
+    ///
+    /// The number of snapshots uploaded.
+    pub async fn flush(&self) -> Result<usize> {
+        let mut sent = 0;
+
+        loop {
+            let (last_id, batch): (u64, Vec<ProbeSnapshot>) = {
+                let queue = self.queue.lock();
+                let len = queue.len().min(self.config.batch_size);
+                let Some(&(last_id, _)) = len.checked_sub(1).and_then(|i| queue.get(i)) else {
+                    return Ok(sent);
+                };
+                let batch = queue
+                    .iter()
+                    .take(self.config.batch_size)
+                    .map(|(_, snapshot)| snapshot.clone())
+                    .collect();
+                (last_id, batch)
+            };
+
+            self.post_batch(&batch).await?;
+
+            self.remove_sent(last_id);
+            sent += batch.len();
+        }
+    }
+
+    /// Remove snapshots up to and including `last_id` from the queue.
+    ///
+    /// The queue may have overflowed while they were sent, dropping some of
+    /// them, so only those still queued are removed.
+    fn remove_sent(&self, last_id: u64) {
+        let mut queue = self.queue.lock();
+        while queue.front().is_some_and(|(id, _)| *id <= last_id) {
+            queue.pop_front();
+        }
+    }
+
+    /// Start capturing snapshots from a device manager and uploading them.
+    ///
+    /// The relay is listed in the manager's
+    /// [`callbacks`](DeviceManager::callbacks) as `"cloud_relay"`. Dropping
+    /// or unregistering the returned handle stops it. Queued snapshots are
+    /// kept and can still be flushed manually.
+    pub fn start(&self, manager: &DeviceManager) -> CallbackHandle {
+        let relay = self.clone();
+        let probes = manager.probe_registry();
+
+        let handle = crate::runtime::spawn(async move {
+            let mut snapshot_timer = tokio::time::interval(relay.config.snapshot_interval);
+            let mut next_flush = tokio::time::Instant::now() + relay.config.flush_interval;
+            let mut backoff = relay.config.flush_interval;
+
+            loop {
+                tokio::select! {
+                    _ = snapshot_timer.tick() => {
+                        let snapshots: Vec<_> = probes
+                            .read()
+                            .values()
+                            .filter(|p| !p.is_stale())
+                            .map(|p| p.snapshot())
+                            .collect();
+                        for snapshot in snapshots {
+                            relay.enqueue(snapshot);
+                        }
+                    }
+                    _ = tokio::time::sleep_until(next_flush) => {
+                        match relay.flush().await {
+                            Ok(sent) => {
+                                if sent > 0 {
+                                    debug!("Cloud relay uploaded {} snapshots", sent);
+                                }
+                                backoff = relay.config.flush_interval;
+                            }
+                            Err(e) => {
+                                backoff = (backoff * 2).min(relay.config.max_backoff);
+                                warn!(
+                                    "Cloud relay upload failed ({} pending, retry in {:?}): {}",
+                                    relay.pending(),
+                                    backoff,
+                                    e
+                                );
+                            }
+                        }
+                        next_flush = tokio::time::Instant::now() + backoff;
+                    }
+                }
+            }
+        });
+
+        manager.track_task("cloud_relay", handle)
+    }
+
+    /// POST a single batch to the endpoint.
+    async fn post_batch(&self, batch: &[ProbeSnapshot]) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.config.endpoint)
+            .bearer_auth(&self.config.token)
+            .json(&SnapshotBatch { snapshots: batch })
+            .send()
+            .await
+            .map_err(|e| Error::Network {
+                reason: e.to_string(),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(Error::Network {
+                reason: format!("endpoint returned {}", response.status()),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CloudRelay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudRelay")
+            .field("endpoint", &self.config.endpoint)
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(serial_number: u32) -> ProbeSnapshot {
+        ProbeSnapshot {
+            serial_number,
+            ..ProbeSnapshot::test_default()
+        }
+    }
+
+    #[test]
+    fn test_config_requires_https() {
+        assert!(CloudRelay::new(CloudRelayConfig::new("http://example.com", "t")).is_err());
+        assert!(CloudRelay::new(CloudRelayConfig::new("https://example.com", "t")).is_ok());
+    }
+
+    #[test]
+    fn test_config_debug_hides_token() {
+        let config = CloudRelayConfig::new("https://example.com", "secret-token");
+        let debug = format!("{:?}", config);
+        assert!(debug.contains("https://example.com"));
+        assert!(!debug.contains("secret-token"));
+    }
+
+    #[test]
+    fn test_queue_drops_oldest_when_full() {
+        let relay = CloudRelay::new(
+            CloudRelayConfig::new("https://example.com", "t").with_max_queue_len(2),
+        )
+        .unwrap();
+
+        relay.enqueue(snapshot(1));
+        relay.enqueue(snapshot(2));
+        relay.enqueue(snapshot(3));
+
+        assert_eq!(relay.pending(), 2);
+        assert_eq!(relay.dropped(), 1);
+        assert_eq!(relay.queue.lock().front().unwrap().1.serial_number, 2);
+    }
+
+    #[test]
+    fn test_overflow_while_sending_keeps_unsent_snapshots() {
+        let relay = CloudRelay::new(
+            CloudRelayConfig::new("https://example.com", "t").with_max_queue_len(3),
+        )
+        .unwrap();
+        relay.enqueue(snapshot(1));
+        relay.enqueue(snapshot(2));
+        let last_id = relay.queue.lock().back().unwrap().0;
+
+        // Snapshot 1 is dropped while the batch of 1 and 2 is in flight
+        relay.enqueue(snapshot(3));
+        relay.enqueue(snapshot(4));
+        relay.remove_sent(last_id);
+
+        let queued: Vec<_> = relay
+            .queue
+            .lock()
+            .iter()
+            .map(|(_, snapshot)| snapshot.serial_number)
+            .collect();
+        assert_eq!(queued, vec![3, 4]);
+    }
+}
@@ -0,0 +1,520 @@
+//! Text commands for driving probes.
+//!
+//! A [`Command`] is one line such as `predict 3 63C`, `alarm all core-high
+//! 95` or `export all`, parsed with [`Command::parse`] and run against the
+//! probes of a [`DeviceManager`] with [`DeviceManager::execute`]. Frontends
+//! can offer the same commands, e.g. as a dashboard's command prompt or a
+//! batch script, without each mapping them to library calls.
+//!
+//! Each command starts with its name and a target: `all`, a probe ID
+//! (`1`-`8`), or a serial number (8 hex digits). Temperatures take an
+//! optional unit (`63C`, `145F`) and otherwise use the unit passed to
+//! [`Command::parse_with_unit`].
+//!
+//! | Command | Action |
+//! |---------|--------|
+//! | `predict <target> <temp> [resting]` | Start a removal prediction |
+//! | `cancel <target>` | Cancel the prediction |
+//! | `alarm <target> <sensor>-<high\|low> <temp>` | Enable an alarm, e.g. `core-high` or `t8-low` |
+//! | `alarm <target> off` | Disable all alarms |
+//! | `silence <target>` | Silence sounding alarms |
+//! | `note <target> <text>` | Annotate the session |
+//! | `export <target>` | Get the temperature log |
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::command::{Command, CommandOutput};
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! # async fn example(manager: &DeviceManager) -> Result<(), Box<dyn std::error::Error>> {
+//! for outcome in manager.execute_line("export all").await? {
+//!     if let Ok(CommandOutput::Log(log)) = outcome.result {
+//!         std::fs::write(format!("{}.csv", outcome.probe.serial_number_string()), log.to_csv())?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::ble::advertising::ProbeId;
+use crate::data::{AlarmSensor, PredictionMode, TemperatureLog, ALARM_COUNT};
+use crate::device_manager::DeviceManager;
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+use crate::utils::{parse_temperature, TemperatureUnit};
+
+/// Which probes a [`Command`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProbeSelector {
+    /// Every discovered probe.
+    All,
+    /// Probes set to an ID.
+    Id(ProbeId),
+    /// The probe with a serial number.
+    Serial(u32),
+}
+
+impl ProbeSelector {
+    /// Check if a probe is selected.
+    pub fn matches(&self, probe: &Probe) -> bool {
+        match self {
+            Self::All => true,
+            Self::Id(id) => probe.id() == *id,
+            Self::Serial(serial_number) => probe.serial_number() == *serial_number,
+        }
+    }
+}
+
+impl FromStr for ProbeSelector {
+    type Err = Error;
+
+    /// Parse `all`, a probe ID (`1`-`8`), or a serial number (8 hex digits).
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+        if let Ok(id) = s.parse::<u8>() {
+            if (ProbeId::MIN..=ProbeId::MAX).contains(&id) {
+                return Ok(Self::Id(ProbeId::new(id)));
+            }
+        }
+        if s.len() == 8 {
+            if let Ok(serial_number) = u32::from_str_radix(s, 16) {
+                return Ok(Self::Serial(serial_number));
+            }
+        }
+        Err(invalid("target", s))
+    }
+}
+
+impl fmt::Display for ProbeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Id(id) => write!(f, "{}", id),
+            Self::Serial(serial_number) => write!(f, "{:08X}", serial_number),
+        }
+    }
+}
+
+/// Whether an alarm trips above or below its temperature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlarmBound {
+    /// Trips at or above the temperature.
+    High,
+    /// Trips at or below the temperature.
+    Low,
+}
+
+/// A command for one or more probes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Start a prediction towards a set point.
+    Predict {
+        /// Probes to apply to.
+        target: ProbeSelector,
+        /// Set point in Celsius.
+        set_point: f64,
+        /// Prediction mode.
+        mode: PredictionMode,
+    },
+    /// Cancel the prediction.
+    CancelPrediction {
+        /// Probes to apply to.
+        target: ProbeSelector,
+    },
+    /// Enable an alarm, keeping the others.
+    SetAlarm {
+        /// Probes to apply to.
+        target: ProbeSelector,
+        /// Sensor the alarm watches.
+        sensor: AlarmSensor,
+        /// Whether it is a high or low alarm.
+        bound: AlarmBound,
+        /// Temperature in Celsius.
+        temperature: f64,
+    },
+    /// Disable all alarms.
+    DisableAlarms {
+        /// Probes to apply to.
+        target: ProbeSelector,
+    },
+    /// Silence sounding alarms.
+    SilenceAlarms {
+        /// Probes to apply to.
+        target: ProbeSelector,
+    },
+    /// Annotate the session.
+    Annotate {
+        /// Probes to apply to.
+        target: ProbeSelector,
+        /// Note text.
+        text: String,
+    },
+    /// Get the temperature log.
+    Export {
+        /// Probes to apply to.
+        target: ProbeSelector,
+    },
+}
+
+impl Command {
+    /// Parse a command, reading temperatures without a unit as Celsius.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] naming the part of the command
+    /// that could not be parsed.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with_unit(input, TemperatureUnit::Celsius)
+    }
+
+    /// Parse a command, reading temperatures without a unit in `unit`.
+    pub fn parse_with_unit(input: &str, unit: TemperatureUnit) -> Result<Self> {
+        let mut words = input.split_whitespace();
+        let name = words.next().ok_or_else(|| invalid("command", input))?;
+        let target = words
+            .next()
+            .ok_or_else(|| invalid("target", input))?
+            .parse()?;
+        let rest: Vec<&str> = words.collect();
+        let temperature = |word: Option<&&str>| match word {
+            Some(word) => parse_temperature(word, unit),
+            None => Err(invalid("temperature", input)),
+        };
+
+        let command = match (name.to_ascii_lowercase().as_str(), rest.as_slice()) {
+            ("predict", [set_point, options @ ..]) => {
+                let mode = match options {
+                    [] => PredictionMode::TimeToRemoval,
+                    [mode] if mode.eq_ignore_ascii_case("resting") => {
+                        PredictionMode::RemovalAndResting
+                    }
+                    _ => return Err(invalid("mode", &options.join(" "))),
+                };
+                Self::Predict {
+                    target,
+                    set_point: temperature(Some(set_point))?,
+                    mode,
+                }
+            }
+            ("cancel", []) => Self::CancelPrediction { target },
+            ("alarm", [off]) if off.eq_ignore_ascii_case("off") => Self::DisableAlarms { target },
+            ("alarm", [alarm, value]) => {
+                let (sensor, bound) = parse_alarm(alarm)?;
+                Self::SetAlarm {
+                    target,
+                    sensor,
+                    bound,
+                    temperature: temperature(Some(value))?,
+                }
+            }
+            ("silence", []) => Self::SilenceAlarms { target },
+            ("note", words) if !words.is_empty() => Self::Annotate {
+                target,
+                text: words.join(" "),
+            },
+            ("export", []) => Self::Export { target },
+            _ => return Err(invalid("command", input)),
+        };
+        Ok(command)
+    }
+
+    /// Parse a script of one command per line.
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] for the first line that cannot
+    /// be parsed, naming its line number.
+    pub fn parse_script(script: &str) -> Result<Vec<Self>> {
+        script
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(i, line)| {
+                Self::parse(line)
+                    .map_err(|e| invalid(&format!("line {}", i + 1), &format!("{} ({})", line, e)))
+            })
+            .collect()
+    }
+
+    /// Get the probes the command applies to.
+    pub fn target(&self) -> ProbeSelector {
+        match self {
+            Self::Predict { target, .. }
+            | Self::CancelPrediction { target }
+            | Self::SetAlarm { target, .. }
+            | Self::DisableAlarms { target }
+            | Self::SilenceAlarms { target }
+            | Self::Annotate { target, .. }
+            | Self::Export { target } => *target,
+        }
+    }
+
+    /// Run the command on one probe.
+    ///
+    /// # Errors
+    ///
+    /// Returns the probe's error for the command, or
+    /// [`Error::ProbeError`] for an `alarm` command before the probe has
+    /// reported its alarm settings, which the new alarm is added to.
+    pub async fn run(&self, probe: &Probe) -> Result<CommandOutput> {
+        match self {
+            Self::Predict {
+                set_point, mode, ..
+            } => probe.set_prediction(*mode, *set_point).await?,
+            Self::CancelPrediction { .. } => probe.cancel_prediction().await?,
+            Self::SetAlarm {
+                sensor,
+                bound,
+                temperature,
+                ..
+            } => {
+                // Writing defaults would clear the probe's other alarms
+                let mut config = probe.alarm_config().ok_or_else(|| Error::ProbeError {
+                    message: "alarm settings not reported yet".to_string(),
+                })?;
+                match bound {
+                    AlarmBound::High => config.set_high(*sensor, *temperature, true),
+                    AlarmBound::Low => config.set_low(*sensor, *temperature, true),
+                }
+                probe.set_alarms(&config).await?
+            }
+            Self::DisableAlarms { .. } => probe.disable_all_alarms().await?,
+            Self::SilenceAlarms { .. } => probe.silence_alarms().await?,
+            Self::Annotate { text, .. } => {
+                probe.annotate(text.clone());
+            }
+            Self::Export { .. } => return Ok(CommandOutput::Log(probe.temperature_log())),
+        }
+        Ok(CommandOutput::Done)
+    }
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Command {
+    /// Format the command so that [`Command::parse`] reads it back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Predict {
+                target,
+                set_point,
+                mode,
+            } => {
+                write!(f, "predict {} {}C", target, set_point)?;
+                if *mode == PredictionMode::RemovalAndResting {
+                    f.write_str(" resting")?;
+                }
+                Ok(())
+            }
+            Self::CancelPrediction { target } => write!(f, "cancel {}", target),
+            Self::SetAlarm {
+                target,
+                sensor,
+                bound,
+                temperature,
+            } => {
+                let bound = match bound {
+                    AlarmBound::High => "high",
+                    AlarmBound::Low => "low",
+                };
+                let sensor = sensor.name().to_ascii_lowercase();
+                write!(f, "alarm {} {}-{} {}C", target, sensor, bound, temperature)
+            }
+            Self::DisableAlarms { target } => write!(f, "alarm {} off", target),
+            Self::SilenceAlarms { target } => write!(f, "silence {}", target),
+            Self::Annotate { target, text } => write!(f, "note {} {}", target, text),
+            Self::Export { target } => write!(f, "export {}", target),
+        }
+    }
+}
+
+/// The result of a [`Command`] on one probe.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandOutput {
+    /// The command was carried out.
+    Done,
+    /// The probe's temperature log, from [`Command::Export`].
+    Log(TemperatureLog),
+}
+
+/// The outcome of a [`Command`] on one of its target probes.
+#[derive(Debug)]
+pub struct CommandOutcome {
+    /// The probe.
+    pub probe: Arc<Probe>,
+    /// What the command produced, or why it failed on this probe.
+    pub result: Result<CommandOutput>,
+}
+
+impl DeviceManager {
+    /// Run a command on each probe it targets, in serial number order.
+    ///
+    /// A failure on one probe, e.g. because it is not connected, does not
+    /// stop the command running on the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ProbeNotFound`] if no discovered probe matches the
+    /// command's target.
+    pub async fn execute(&self, command: &Command) -> Result<Vec<CommandOutcome>> {
+        let target = command.target();
+        let mut probes: Vec<_> = self
+            .probes()
+            .into_values()
+            .filter(|probe| target.matches(probe))
+            .collect();
+        if probes.is_empty() {
+            return Err(Error::ProbeNotFound {
+                identifier: target.to_string(),
+            });
+        }
+        probes.sort_by_key(|probe| probe.serial_number());
+
+        let mut outcomes = Vec::with_capacity(probes.len());
+        for probe in probes {
+            let result = command.run(&probe).await;
+            outcomes.push(CommandOutcome { probe, result });
+        }
+        Ok(outcomes)
+    }
+
+    /// Parse and run a command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if the line cannot be parsed, or
+    /// [`Error::ProbeNotFound`] if no probe matches its target.
+    pub async fn execute_line(&self, line: &str) -> Result<Vec<CommandOutcome>> {
+        self.execute(&Command::parse(line)?).await
+    }
+}
+
+/// Parse an alarm such as `core-high` or `t8-low`.
+fn parse_alarm(alarm: &str) -> Result<(AlarmSensor, AlarmBound)> {
+    let (sensor, bound) = alarm
+        .rsplit_once('-')
+        .ok_or_else(|| invalid("alarm", alarm))?;
+    let sensor = (0..ALARM_COUNT)
+        .filter_map(AlarmSensor::from_index)
+        .find(|s| s.name().eq_ignore_ascii_case(sensor))
+        .ok_or_else(|| invalid("sensor", sensor))?;
+    let bound = match bound.to_ascii_lowercase().as_str() {
+        "high" => AlarmBound::High,
+        "low" => AlarmBound::Low,
+        _ => return Err(invalid("alarm", alarm)),
+    };
+    Ok((sensor, bound))
+}
+
+/// Create an error for an unparseable part of a command.
+fn invalid(name: &str, value: &str) -> Error {
+    Error::InvalidParameter {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::VirtualSensor;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            Command::parse("predict 3 63C").unwrap(),
+            Command::Predict {
+                target: ProbeSelector::Id(ProbeId::new(3)),
+                set_point: 63.0,
+                mode: PredictionMode::TimeToRemoval,
+            }
+        );
+        assert_eq!(
+            Command::parse("alarm all core-high 95").unwrap(),
+            Command::SetAlarm {
+                target: ProbeSelector::All,
+                sensor: AlarmSensor::Virtual(VirtualSensor::Core),
+                bound: AlarmBound::High,
+                temperature: 95.0,
+            }
+        );
+        assert_eq!(
+            Command::parse("note 10000001 wrapped in foil").unwrap(),
+            Command::Annotate {
+                target: ProbeSelector::Serial(0x1000_0001),
+                text: "wrapped in foil".to_string(),
+            }
+        );
+
+        let command =
+            Command::parse_with_unit("predict all 203 resting", TemperatureUnit::Fahrenheit)
+                .unwrap();
+        let Command::Predict {
+            set_point, mode, ..
+        } = command
+        else {
+            panic!("expected predict");
+        };
+        assert!((set_point - 95.0).abs() < 0.01);
+        assert_eq!(mode, PredictionMode::RemovalAndResting);
+
+        for bad in [
+            "",
+            "predict",
+            "predict 9 63C",
+            "alarm all core-hot 95",
+            "export all now",
+        ] {
+            assert!(Command::parse(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for line in [
+            "predict 3 63C resting",
+            "cancel all",
+            "alarm 1 t8-low 10.5C",
+            "alarm all off",
+            "silence all",
+            "note all wrapped",
+            "export 1000ABCD",
+        ] {
+            let command = Command::parse(line).unwrap();
+            assert_eq!(command.to_string(), line);
+            assert_eq!(Command::parse(&command.to_string()).unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = "# Start the cook\npredict all 63C\n\nalarm all ambient-high 150\n";
+        assert_eq!(Command::parse_script(script).unwrap().len(), 2);
+
+        let Err(Error::InvalidParameter { name, .. }) = Command::parse_script("cancel all\nbogus")
+        else {
+            panic!("expected an error");
+        };
+        assert_eq!(name, "line 2");
+        let Err(Error::InvalidParameter { value, .. }) = Command::parse_script("predict all hot")
+        else {
+            panic!("expected an error");
+        };
+        assert!(value.starts_with("predict all hot ("));
+        assert!(value.contains("Invalid parameter"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Compression for exports and stored history.
+//!
+//! Overnight cooks with several probes produce large CSV and NDJSON files,
+//! which matters on SD-card based gateways. [`Compression`] selects gzip, for
+//! compatibility with every tool, or zstd, for better ratios at lower CPU
+//! cost.
+//!
+//! Requires the `compression` feature.
+
+use std::io::{Read, Write};
+
+use crate::error::{Error, Result};
+
+/// Default zstd compression level.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Compression {
+    /// gzip (RFC 1952).
+    Gzip,
+    /// Zstandard.
+    Zstd,
+}
+
+impl Compression {
+    /// Get the file extension for this format, without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+
+    /// Get the format for a file extension (without the leading dot).
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "gz" => Some(Self::Gzip),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Compress `data`.
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).map_err(compression_error)?;
+                encoder.finish().map_err(compression_error)
+            }
+            Self::Zstd => zstd::encode_all(data, ZSTD_LEVEL).map_err(compression_error),
+        }
+    }
+
+    /// Decompress `data`.
+    ///
+    /// Concatenated gzip members or zstd frames decode as one stream.
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        match self {
+            Self::Gzip => {
+                flate2::read::MultiGzDecoder::new(data)
+                    .read_to_end(&mut out)
+                    .map_err(compression_error)?;
+            }
+            Self::Zstd => {
+                out = zstd::decode_all(data).map_err(compression_error)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Convert a compression I/O error into a crate error.
+fn compression_error(e: std::io::Error) -> Error {
+    Error::Storage {
+        reason: format!("compression failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = "Sequence,T1\n".repeat(1000);
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let compressed = compression.compress(data.as_bytes()).unwrap();
+            assert!(compressed.len() < data.len() / 10);
+            assert_eq!(
+                compression.decompress(&compressed).unwrap(),
+                data.as_bytes()
+            );
+            assert_eq!(
+                Compression::from_extension(compression.extension()),
+                Some(compression)
+            );
+        }
+    }
+
+    #[test]
+    fn test_concatenated_streams() {
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let mut data = compression.compress(b"first\n").unwrap();
+            data.extend(compression.compress(b"second\n").unwrap());
+            assert_eq!(compression.decompress(&data).unwrap(), b"first\nsecond\n");
+        }
+    }
+
+    #[test]
+    fn test_corrupt_input() {
+        assert!(matches!(
+            Compression::Gzip.decompress(b"not gzip"),
+            Err(Error::Storage { .. })
+        ));
+    }
+}
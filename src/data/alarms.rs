@@ -3,6 +3,9 @@
 //! Contains types for managing high and low temperature alarms on the probe.
 //! Based on the Combustion Probe BLE Specification.
 
+use crate::data::{PhysicalSensor, VirtualSensor};
+use crate::error::{Error, Result};
+
 /// Alarm status for a single temperature alarm.
 ///
 /// Each alarm is a 16-bit packed structure:
@@ -73,11 +76,22 @@ impl AlarmStatus {
         })
     }
 
+    /// Lowest encodable alarm temperature in Celsius.
+    pub const MIN_TEMPERATURE: f64 = -20.0;
+
+    /// Highest encodable alarm temperature in Celsius.
+    pub const MAX_TEMPERATURE: f64 = 0x1FFF as f64 * 0.1 - 20.0;
+
     /// Encode to a 2-byte packed structure.
+    ///
+    /// Temperatures outside [`MIN_TEMPERATURE`](Self::MIN_TEMPERATURE) to
+    /// [`MAX_TEMPERATURE`](Self::MAX_TEMPERATURE) saturate at the nearest
+    /// limit. Use [`try_to_bytes`](Self::try_to_bytes) to reject them instead.
     pub fn to_bytes(&self) -> [u8; 2] {
-        // Encode temperature: (celsius + 20) / 0.1, clamped to 13 bits
+        // Encode temperature: (celsius + 20) / 0.1, saturating at 0 and the
+        // 13-bit max
         let temp_raw = ((self.temperature + 20.0) / 0.1).round() as u16;
-        let temp_raw = temp_raw.min(0x1FFF); // 13-bit max
+        let temp_raw = temp_raw.min(0x1FFF);
 
         let mut packed: u16 = 0;
         if self.set {
@@ -94,6 +108,31 @@ impl AlarmStatus {
         packed.to_le_bytes()
     }
 
+    /// Encode to a 2-byte packed structure, rejecting temperatures that
+    /// cannot be represented.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if the temperature is outside
+    /// [`MIN_TEMPERATURE`](Self::MIN_TEMPERATURE) to
+    /// [`MAX_TEMPERATURE`](Self::MAX_TEMPERATURE) or not a number.
+    pub fn try_to_bytes(&self) -> Result<[u8; 2]> {
+        self.check_temperature("temperature")?;
+        Ok(self.to_bytes())
+    }
+
+    /// Check that the temperature is encodable, reporting it as `name`.
+    fn check_temperature(&self, name: &str) -> Result<()> {
+        if (Self::MIN_TEMPERATURE..=Self::MAX_TEMPERATURE).contains(&self.temperature) {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter {
+                name: name.to_string(),
+                value: self.temperature.to_string(),
+            })
+        }
+    }
+
     /// Check if the alarm is enabled.
     pub fn is_enabled(&self) -> bool {
         self.set
@@ -121,6 +160,66 @@ pub const ALARM_COUNT: usize = 11;
 /// Size of the alarm array in bytes (11 alarms × 2 bytes each).
 pub const ALARM_ARRAY_SIZE: usize = ALARM_COUNT * AlarmStatus::SIZE;
 
+/// A physical or virtual sensor, e.g. the one an alarm watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AlarmSensor {
+    /// A physical thermistor (T1-T8).
+    Physical(PhysicalSensor),
+    /// A virtual sensor (Core, Surface, Ambient).
+    Virtual(VirtualSensor),
+}
+
+impl AlarmSensor {
+    /// Create from an alarm index (0-7 for T1-T8, 8=Core, 9=Surface,
+    /// 10=Ambient).
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0..=7 => PhysicalSensor::from_index(index).map(Self::Physical),
+            8 => Some(Self::Virtual(VirtualSensor::Core)),
+            9 => Some(Self::Virtual(VirtualSensor::Surface)),
+            10 => Some(Self::Virtual(VirtualSensor::Ambient)),
+            _ => None,
+        }
+    }
+
+    /// Get the index of this sensor's alarm in the alarm arrays.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Physical(sensor) => sensor.index(),
+            Self::Virtual(VirtualSensor::Core) => 8,
+            Self::Virtual(VirtualSensor::Surface) => 9,
+            Self::Virtual(VirtualSensor::Ambient) => 10,
+        }
+    }
+
+    /// Get the display name (e.g., "T1" or "Core").
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Physical(sensor) => sensor.name(),
+            Self::Virtual(sensor) => sensor.name(),
+        }
+    }
+}
+
+impl From<PhysicalSensor> for AlarmSensor {
+    fn from(sensor: PhysicalSensor) -> Self {
+        Self::Physical(sensor)
+    }
+}
+
+impl From<VirtualSensor> for AlarmSensor {
+    fn from(sensor: VirtualSensor) -> Self {
+        Self::Virtual(sensor)
+    }
+}
+
+impl std::fmt::Display for AlarmSensor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// High and low temperature alarm configuration for all sensors.
 ///
 /// The probe supports alarms for:
@@ -168,6 +267,16 @@ impl AlarmConfig {
         }
     }
 
+    /// Set the high temperature alarm for a sensor.
+    pub fn set_high(&mut self, sensor: impl Into<AlarmSensor>, temperature: f64, enabled: bool) {
+        self.set_high_alarm(sensor.into().index(), temperature, enabled);
+    }
+
+    /// Set the low temperature alarm for a sensor.
+    pub fn set_low(&mut self, sensor: impl Into<AlarmSensor>, temperature: f64, enabled: bool) {
+        self.set_low_alarm(sensor.into().index(), temperature, enabled);
+    }
+
     /// Set high alarm for the core (virtual) sensor.
     pub fn set_core_high_alarm(&mut self, temperature: f64, enabled: bool) {
         self.set_high_alarm(8, temperature, enabled);
@@ -208,6 +317,16 @@ impl AlarmConfig {
         self.low_alarms.get(sensor_index)
     }
 
+    /// Get the high temperature alarm for a sensor.
+    pub fn high(&self, sensor: impl Into<AlarmSensor>) -> &AlarmStatus {
+        &self.high_alarms[sensor.into().index()]
+    }
+
+    /// Get the low temperature alarm for a sensor.
+    pub fn low(&self, sensor: impl Into<AlarmSensor>) -> &AlarmStatus {
+        &self.low_alarms[sensor.into().index()]
+    }
+
     /// Get the core high alarm.
     pub fn core_high_alarm(&self) -> &AlarmStatus {
         &self.high_alarms[8]
@@ -228,15 +347,15 @@ impl AlarmConfig {
         let mut low_alarms = [AlarmStatus::default(); ALARM_COUNT];
 
         // Parse high alarms (first 22 bytes)
-        for i in 0..ALARM_COUNT {
+        for (i, alarm) in high_alarms.iter_mut().enumerate() {
             let offset = i * 2;
-            high_alarms[i] = AlarmStatus::from_bytes(&bytes[offset..offset + 2])?;
+            *alarm = AlarmStatus::from_bytes(&bytes[offset..offset + 2])?;
         }
 
         // Parse low alarms (next 22 bytes)
-        for i in 0..ALARM_COUNT {
+        for (i, alarm) in low_alarms.iter_mut().enumerate() {
             let offset = ALARM_ARRAY_SIZE + i * 2;
-            low_alarms[i] = AlarmStatus::from_bytes(&bytes[offset..offset + 2])?;
+            *alarm = AlarmStatus::from_bytes(&bytes[offset..offset + 2])?;
         }
 
         Some(Self {
@@ -268,6 +387,25 @@ impl AlarmConfig {
         bytes
     }
 
+    /// Encode to bytes, rejecting alarm temperatures that cannot be
+    /// represented.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] naming the first offending alarm,
+    /// e.g. `high_alarms[8].temperature`.
+    pub fn try_to_bytes(&self) -> Result<[u8; Self::SIZE]> {
+        for (name, alarms) in [
+            ("high_alarms", &self.high_alarms),
+            ("low_alarms", &self.low_alarms),
+        ] {
+            for (i, alarm) in alarms.iter().enumerate() {
+                alarm.check_temperature(&format!("{}[{}].temperature", name, i))?;
+            }
+        }
+        Ok(self.to_bytes())
+    }
+
     /// Check if any alarm is currently triggered.
     pub fn any_tripped(&self) -> bool {
         self.high_alarms.iter().any(|a| a.tripped) || self.low_alarms.iter().any(|a| a.tripped)
@@ -301,22 +439,47 @@ impl AlarmConfig {
             .collect()
     }
 
+    /// Trip and sound the enabled alarms that `readings` in Celsius,
+    /// indexed like the alarms, have reached, as a probe's firmware would.
+    ///
+    /// A silenced alarm stays quiet while tripped, and both flags clear once
+    /// its reading is back within the threshold or missing.
+    pub(crate) fn evaluate(&mut self, readings: &[Option<f64>; ALARM_COUNT]) {
+        for (alarms, high) in [(&mut self.high_alarms, true), (&mut self.low_alarms, false)] {
+            for (alarm, reading) in alarms.iter_mut().zip(readings) {
+                let reached = alarm.set
+                    && reading.is_some_and(|t| {
+                        if high {
+                            t >= alarm.temperature
+                        } else {
+                            t <= alarm.temperature
+                        }
+                    });
+                if !reached {
+                    alarm.tripped = false;
+                    alarm.alarming = false;
+                } else if !alarm.tripped {
+                    alarm.tripped = true;
+                    alarm.alarming = true;
+                }
+            }
+        }
+    }
+
+    /// Stop sounding alarms, leaving them tripped.
+    pub(crate) fn silence(&mut self) {
+        for alarm in self
+            .high_alarms
+            .iter_mut()
+            .chain(self.low_alarms.iter_mut())
+        {
+            alarm.alarming = false;
+        }
+    }
+
     /// Get the sensor name for an alarm index.
     pub fn sensor_name(index: usize) -> &'static str {
-        match index {
-            0 => "T1",
-            1 => "T2",
-            2 => "T3",
-            3 => "T4",
-            4 => "T5",
-            5 => "T6",
-            6 => "T7",
-            7 => "T8",
-            8 => "Core",
-            9 => "Surface",
-            10 => "Ambient",
-            _ => "Unknown",
-        }
+        AlarmSensor::from_index(index).map_or("Unknown", |sensor| sensor.name())
     }
 }
 
@@ -324,6 +487,47 @@ impl AlarmConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_evaluate_trips_and_rearms() {
+        let mut config = AlarmConfig::new();
+        config.set_high_alarm(0, 500.0, true);
+        config.set_low_alarm(8, 2.0, true);
+        let mut readings = [None; ALARM_COUNT];
+
+        readings[0] = Some(520.0);
+        config.evaluate(&readings);
+        assert!(config.high_alarms[0].tripped && config.high_alarms[0].alarming);
+        assert!(!config.low_alarms[8].tripped);
+
+        config.silence();
+        config.evaluate(&readings);
+        assert!(config.high_alarms[0].tripped && !config.high_alarms[0].alarming);
+
+        readings[0] = Some(480.0);
+        readings[8] = Some(1.0);
+        config.evaluate(&readings);
+        assert!(!config.high_alarms[0].tripped);
+        assert!(config.low_alarms[8].alarming);
+    }
+
+    #[test]
+    fn test_alarm_sensor_index_roundtrip() {
+        for index in 0..ALARM_COUNT {
+            let sensor = AlarmSensor::from_index(index).unwrap();
+            assert_eq!(sensor.index(), index);
+            assert_eq!(sensor.name(), AlarmConfig::sensor_name(index));
+        }
+        assert_eq!(AlarmSensor::from_index(ALARM_COUNT), None);
+
+        let mut config = AlarmConfig::new();
+        config.set_high(VirtualSensor::Core, 63.0, true);
+        config.set_low(PhysicalSensor::T3, 5.0, true);
+        assert!(config.core_high_alarm().set);
+        assert!(config.high(AlarmSensor::Virtual(VirtualSensor::Core)).set);
+        assert!(config.low_alarms[2].set);
+        assert!(!config.low(PhysicalSensor::T4).set);
+    }
+
     #[test]
     fn test_alarm_status_roundtrip() {
         let alarm = AlarmStatus {
@@ -377,6 +581,23 @@ mod tests {
         assert!(parsed.high_alarm(0).unwrap().is_enabled());
     }
 
+    #[test]
+    fn test_alarm_out_of_range() {
+        let hot = AlarmStatus::new(900.0, true);
+        let parsed = AlarmStatus::from_bytes(&hot.to_bytes()).unwrap();
+        assert!((parsed.temperature - AlarmStatus::MAX_TEMPERATURE).abs() < 0.01);
+        assert!(hot.try_to_bytes().is_err());
+
+        let mut config = AlarmConfig::new();
+        config.set_core_low_alarm(-40.0, true);
+        match config.try_to_bytes() {
+            Err(Error::InvalidParameter { name, .. }) => {
+                assert_eq!(name, "low_alarms[8].temperature")
+            }
+            other => panic!("expected invalid parameter, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_alarm_config_any_methods() {
         let mut config = AlarmConfig::new();
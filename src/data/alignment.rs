@@ -0,0 +1,316 @@
+//! Resampling several probes' logs onto a common time base.
+//!
+//! Probes sample on their own schedules, so the logs of a cook group never
+//! line up row for row. [`AlignedLogs`] resamples the timestamped data
+//! points of each log at a fixed [`AlignmentOptions::interval`], filling in
+//! each time according to the [`Interpolation`] policy, and produces one
+//! table with a column per probe and sensor. Logs need timestamps, e.g. from
+//! [`Probe::temperature_log`](crate::Probe::temperature_log); points without
+//! one are skipped.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::time::Duration;
+
+use super::log::TemperatureLog;
+use super::temperatures::{PhysicalSensor, VirtualSensor};
+
+/// How a reading is filled in between two samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    /// Linear interpolation between the samples either side.
+    #[default]
+    Linear,
+    /// The most recent sample at or before the time.
+    Previous,
+    /// Whichever sample is closest in time.
+    Nearest,
+}
+
+/// Time base and interpolation for [`AlignedLogs`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlignmentOptions {
+    /// Spacing of the rows.
+    pub interval: Duration,
+    /// How readings are filled in between samples.
+    pub interpolation: Interpolation,
+    /// Longest span between samples that is filled in. Times within a
+    /// longer gap, e.g. while a probe was out of range, are left empty.
+    pub max_gap: Duration,
+}
+
+impl AlignmentOptions {
+    /// Create options with the given row spacing and the default
+    /// interpolation and maximum gap.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            ..Self::default()
+        }
+    }
+
+    /// Set how readings are filled in between samples.
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Set the longest span between samples that is filled in.
+    pub fn with_max_gap(mut self, max_gap: Duration) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+}
+
+impl Default for AlignmentOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            interpolation: Interpolation::Linear,
+            max_gap: Duration::from_secs(60),
+        }
+    }
+}
+
+/// One row of [`AlignedLogs`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlignedRow {
+    /// Time of the row.
+    pub timestamp: DateTime<Utc>,
+    /// Readings in Celsius, in the order of [`AlignedLogs::columns`].
+    pub values: Vec<Option<f64>>,
+}
+
+/// Several logs resampled onto a common time base.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlignedLogs {
+    /// Column names, `"<label> <sensor>"`, e.g. `"10000001 T1"` or
+    /// `"10000001 Core"`.
+    pub columns: Vec<String>,
+    /// Rows, oldest first.
+    pub rows: Vec<AlignedRow>,
+}
+
+/// Timestamped samples of one column, oldest first.
+type Series = Vec<(DateTime<Utc>, f64)>;
+
+impl AlignedLogs {
+    /// Resample `logs`, each paired with the label used in its column names.
+    ///
+    /// Each log contributes a column for T1-T8 and, if it carries
+    /// prediction data, for the virtual Core, Surface, and Ambient sensors.
+    /// Rows run from the earliest to the latest timestamp in any log,
+    /// starting on a whole multiple of the interval.
+    pub fn new<'a, S: AsRef<str> + 'a>(
+        logs: impl IntoIterator<Item = (S, &'a TemperatureLog)>,
+        options: &AlignmentOptions,
+    ) -> Self {
+        let mut columns = Vec::new();
+        let mut series: Vec<Series> = Vec::new();
+        for (label, log) in logs {
+            let (log_columns, log_series) = Self::series(label.as_ref(), log);
+            columns.extend(log_columns);
+            series.extend(log_series);
+        }
+
+        let interval_ms = options.interval.as_millis().max(1) as i64;
+        let times = series.iter().flat_map(|s| s.iter().map(|(t, _)| *t));
+        let (Some(first), Some(last)) = (times.clone().min(), times.max()) else {
+            return Self {
+                columns,
+                rows: Vec::new(),
+            };
+        };
+
+        let mut rows = Vec::new();
+        let start_ms = first.timestamp_millis().div_euclid(interval_ms) * interval_ms;
+        let mut cursors = vec![0; series.len()];
+        let mut time_ms = start_ms;
+        while time_ms <= last.timestamp_millis() {
+            let Some(timestamp) = DateTime::<Utc>::from_timestamp_millis(time_ms) else {
+                break;
+            };
+            let values = series
+                .iter()
+                .zip(&mut cursors)
+                .map(|(series, cursor)| Self::sample(series, cursor, timestamp, options))
+                .collect();
+            rows.push(AlignedRow { timestamp, values });
+            time_ms += interval_ms;
+        }
+
+        Self { columns, rows }
+    }
+
+    /// Export the table to CSV with a `Timestamp` column followed by one
+    /// column per probe and sensor.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Timestamp");
+        for column in &self.columns {
+            csv.push(',');
+            csv.push_str(column);
+        }
+        csv.push('\n');
+
+        for row in &self.rows {
+            csv.push_str(&row.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true));
+            for value in &row.values {
+                csv.push(',');
+                if let Some(celsius) = value {
+                    csv.push_str(&format!("{:.2}", celsius));
+                }
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Split a log into one named series per sensor.
+    fn series(label: &str, log: &TemperatureLog) -> (Vec<String>, Vec<Series>) {
+        let mut points: Vec<_> = log
+            .data_points
+            .iter()
+            .filter_map(|p| p.timestamp.map(|t| (t, p)))
+            .collect();
+        points.sort_by_key(|(t, _)| *t);
+        let has_predictions = points.iter().any(|(_, p)| p.prediction_log.is_some());
+
+        let mut columns = Vec::new();
+        let mut series = Vec::new();
+        for sensor in PhysicalSensor::ALL {
+            columns.push(format!("{} {}", label, sensor.name()));
+            series.push(
+                points
+                    .iter()
+                    .filter_map(|(t, p)| p.reading(sensor).map(|c| (*t, c)))
+                    .collect(),
+            );
+        }
+        if has_predictions {
+            for sensor in VirtualSensor::ALL {
+                columns.push(format!("{} {}", label, sensor.name()));
+                series.push(
+                    points
+                        .iter()
+                        .filter_map(|(t, p)| p.reading(sensor).map(|c| (*t, c)))
+                        .collect(),
+                );
+            }
+        }
+
+        (columns, series)
+    }
+
+    /// Sample a series at `time`.
+    ///
+    /// `cursor` is the index of the first sample after the previous time
+    /// sampled, and only moves forward as times increase.
+    fn sample(
+        series: &Series,
+        cursor: &mut usize,
+        time: DateTime<Utc>,
+        options: &AlignmentOptions,
+    ) -> Option<f64> {
+        while *cursor < series.len() && series[*cursor].0 <= time {
+            *cursor += 1;
+        }
+        let (before_at, before) = *series.get(cursor.checked_sub(1)?)?;
+        if before_at == time {
+            return Some(before);
+        }
+        let (after_at, after) = *series.get(*cursor)?;
+
+        let span = (after_at - before_at).to_std().ok()?;
+        if span > options.max_gap {
+            return None;
+        }
+        let elapsed = (time - before_at).num_milliseconds() as f64;
+        let fraction = elapsed / span.as_millis() as f64;
+        Some(match options.interpolation {
+            Interpolation::Linear => before + (after - before) * fraction,
+            Interpolation::Previous => before,
+            Interpolation::Nearest if fraction <= 0.5 => before,
+            Interpolation::Nearest => after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{LoggedDataPoint, PredictionLog, ProbeTemperatures, RawTemperature};
+
+    /// A log with T1 at each (seconds after `start`, Celsius) sample.
+    fn log(start: DateTime<Utc>, samples: &[(i64, f64)]) -> TemperatureLog {
+        let mut log = TemperatureLog::new(1, 1000);
+        for (i, (seconds, celsius)) in samples.iter().enumerate() {
+            let mut temperatures = ProbeTemperatures::new();
+            temperatures.values[0] = RawTemperature::from_celsius(*celsius);
+            let mut point = LoggedDataPoint::new(i as u32, temperatures);
+            point.timestamp = Some(start + chrono::Duration::seconds(*seconds));
+            log.add_data_point(point);
+        }
+        log
+    }
+
+    fn column(aligned: &AlignedLogs, name: &str) -> Vec<Option<f64>> {
+        let index = aligned.columns.iter().position(|c| c == name).unwrap();
+        aligned.rows.iter().map(|r| r.values[index]).collect()
+    }
+
+    #[test]
+    fn test_alignment_interpolates_onto_common_time_base() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let a = log(start, &[(0, 20.0), (10, 30.0), (20, 40.0)]);
+        let b = log(start, &[(3, 50.0), (13, 60.0)]);
+        let options = AlignmentOptions::new(Duration::from_secs(5));
+
+        let aligned = AlignedLogs::new([("A", &a), ("B", &b)], &options);
+        assert_eq!(aligned.columns.len(), 16);
+        assert_eq!(aligned.rows.len(), 5);
+        assert_eq!(
+            column(&aligned, "A T1"),
+            vec![Some(20.0), Some(25.0), Some(30.0), Some(35.0), Some(40.0)]
+        );
+        assert_eq!(
+            column(&aligned, "B T1"),
+            vec![None, Some(52.0), Some(57.0), None, None]
+        );
+        assert!(column(&aligned, "B T2").iter().all(Option::is_none));
+
+        let previous = options.with_interpolation(Interpolation::Previous);
+        let aligned = AlignedLogs::new([("B", &b)], &previous);
+        assert_eq!(column(&aligned, "B T1"), vec![None, Some(50.0), Some(50.0)]);
+
+        let csv = AlignedLogs::new([("A", &a)], &options).to_csv();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("Timestamp,A T1,A T2"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("2023-11-14T22:13:20.000Z,20.00,"));
+    }
+
+    #[test]
+    fn test_alignment_leaves_gaps_empty() {
+        let start = DateTime::<Utc>::from_timestamp(1_699_999_980, 0).unwrap();
+        let mut a = log(start, &[(0, 20.0), (120, 30.0)]);
+        a.data_points[1].prediction_log = Some(PredictionLog {
+            virtual_core: 28.0,
+            ..Default::default()
+        });
+        let options = AlignmentOptions::new(Duration::from_secs(60));
+
+        let aligned = AlignedLogs::new([("A", &a)], &options);
+        assert_eq!(column(&aligned, "A T1"), vec![Some(20.0), None, Some(30.0)]);
+        assert_eq!(column(&aligned, "A Core"), vec![None, None, Some(28.0)]);
+
+        let wide = options.with_max_gap(Duration::from_secs(300));
+        let aligned = AlignedLogs::new([("A", &a)], &wide);
+        assert_eq!(column(&aligned, "A T1")[1], Some(25.0));
+    }
+}
@@ -0,0 +1,35 @@
+//! Session annotations.
+//!
+//! Markers such as "wrapped in foil" or "opened lid", attached to a session
+//! so that changes in the temperature curve can be explained later.
+
+use chrono::{DateTime, Utc};
+
+/// A timestamped note attached to a cooking session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
+    /// Note text.
+    pub text: String,
+    /// When the note applies.
+    pub recorded_at: DateTime<Utc>,
+    /// Log sequence number current when the note was made, if known.
+    pub sequence_number: Option<u32>,
+}
+
+impl Annotation {
+    /// Create an annotation at `recorded_at`.
+    pub fn new(text: impl Into<String>, recorded_at: DateTime<Utc>) -> Self {
+        Self {
+            text: text.into(),
+            recorded_at,
+            sequence_number: None,
+        }
+    }
+
+    /// Tie the annotation to a log sequence number.
+    pub fn at_sequence(mut self, sequence_number: u32) -> Self {
+        self.sequence_number = Some(sequence_number);
+        self
+    }
+}
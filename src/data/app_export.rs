@@ -0,0 +1,301 @@
+//! Exports readable by Combustion's official apps and web tools.
+//!
+//! [`TemperatureLog::to_app_csv`] writes the layout the official apps use for
+//! their own CSV exports: a metadata header describing the probe and
+//! session, a blank line, then one row per logged sample. Temperatures are
+//! in Celsius, and columns the library has no data for are left empty.
+//! With the `serde` feature, `TemperatureLog::to_app_json` carries the same
+//! metadata and columns as JSON.
+
+use chrono::{DateTime, Utc};
+
+use super::log::{LoggedDataPoint, TemperatureLog};
+use crate::capabilities::FirmwareVersion;
+
+/// Version of the app CSV layout written.
+pub const APP_CSV_VERSION: u32 = 4;
+
+/// Column names of the app CSV layout, in order.
+pub const APP_CSV_COLUMNS: [&str; 23] = [
+    "Timestamp",
+    "SessionID",
+    "SequenceNumber",
+    "T1",
+    "T2",
+    "T3",
+    "T4",
+    "T5",
+    "T6",
+    "T7",
+    "T8",
+    "VirtualCoreTemperature",
+    "VirtualSurfaceTemperature",
+    "VirtualAmbientTemperature",
+    "EstimatedCoreTemperature",
+    "PredictionSetPointTemperature",
+    "VirtualCoreSensor",
+    "VirtualSurfaceSensor",
+    "VirtualAmbientSensor",
+    "PredictionState",
+    "PredictionMode",
+    "PredictionType",
+    "PredictionValueSeconds",
+];
+
+/// Probe and session details written in the header of an app export.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppExportMetadata {
+    /// Probe serial number.
+    pub serial_number: u32,
+    /// Probe firmware version, if known.
+    pub firmware_version: Option<FirmwareVersion>,
+    /// Probe hardware revision, if known.
+    pub hardware_revision: Option<String>,
+    /// Name and version of the exporting application.
+    pub app: String,
+    /// When the export was created.
+    pub created: DateTime<Utc>,
+}
+
+impl AppExportMetadata {
+    /// Create metadata for a probe, created now by this library.
+    pub fn new(serial_number: u32) -> Self {
+        Self {
+            serial_number,
+            firmware_version: None,
+            hardware_revision: None,
+            app: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            created: Utc::now(),
+        }
+    }
+
+    /// Set the probe firmware version.
+    pub fn with_firmware_version(mut self, version: FirmwareVersion) -> Self {
+        self.firmware_version = Some(version);
+        self
+    }
+
+    /// Set the probe hardware revision.
+    pub fn with_hardware_revision(mut self, revision: impl Into<String>) -> Self {
+        self.hardware_revision = Some(revision.into());
+        self
+    }
+
+    /// Set the exporting application's name and version.
+    pub fn with_app(mut self, app: impl Into<String>) -> Self {
+        self.app = app.into();
+        self
+    }
+
+    /// Set when the export was created.
+    pub fn with_created(mut self, created: DateTime<Utc>) -> Self {
+        self.created = created;
+        self
+    }
+
+    /// Get the header lines, without the trailing blank line.
+    fn header_lines(&self, log: &TemperatureLog) -> Vec<String> {
+        let unknown = || "??".to_string();
+        vec![
+            "Combustion Inc. Probe Data".to_string(),
+            format!("App: {}", self.app),
+            format!("CSV version: {}", APP_CSV_VERSION),
+            format!("Probe S/N: {:08X}", self.serial_number),
+            format!(
+                "Probe FW version: {}",
+                self.firmware_version
+                    .map_or_else(unknown, |v| format!("v{}", v))
+            ),
+            format!(
+                "Probe HW revision: {}",
+                self.hardware_revision.clone().unwrap_or_else(unknown)
+            ),
+            "Framework: Rust".to_string(),
+            format!("Sample Period: {}", log.sample_period_ms),
+            format!("Created: {}", self.created.format("%Y-%m-%d %H:%M:%S")),
+        ]
+    }
+}
+
+/// Format an optional temperature for the CSV, empty when missing.
+fn temperature_cell(celsius: Option<f64>) -> String {
+    celsius.map(|c| format!("{:.2}", c)).unwrap_or_default()
+}
+
+impl TemperatureLog {
+    /// Get the cells of a row in the app layout, empty where there is no
+    /// data.
+    fn app_row(&self, point: &LoggedDataPoint, first_sequence: u32) -> Vec<String> {
+        let elapsed_ms = u64::from(point.sequence_number.saturating_sub(first_sequence))
+            * u64::from(self.sample_period_ms);
+        let mut row = vec![
+            format!("{:.3}", elapsed_ms as f64 / 1000.0),
+            self.session_id.to_string(),
+            point.sequence_number.to_string(),
+        ];
+        row.extend(
+            point
+                .temperatures
+                .values
+                .iter()
+                .map(|t| temperature_cell(t.to_celsius())),
+        );
+        match &point.prediction_log {
+            Some(prediction) => {
+                row.push(temperature_cell(Some(prediction.virtual_core)));
+                row.push(temperature_cell(Some(prediction.virtual_surface)));
+                row.push(temperature_cell(Some(prediction.virtual_ambient)));
+                // Estimated core and virtual sensor selection are not logged
+                row.push(String::new());
+                row.push(temperature_cell(Some(prediction.prediction_set_point)));
+                row.extend(std::iter::repeat(String::new()).take(3));
+                row.push(prediction.prediction_state.to_string());
+                row.push(String::new());
+                row.push(prediction.prediction_type.to_string());
+                row.push(prediction.prediction_value_seconds.to_string());
+            }
+            None => row.extend(std::iter::repeat(String::new()).take(12)),
+        }
+        row
+    }
+
+    /// Export the log in the CSV layout of Combustion's official apps, so it
+    /// can be imported into their tools.
+    pub fn to_app_csv(&self, metadata: &AppExportMetadata) -> String {
+        let mut csv = metadata.header_lines(self).join("\n");
+        csv.push_str("\n\n");
+        csv.push_str(&APP_CSV_COLUMNS.join(","));
+        csv.push('\n');
+
+        let first_sequence = self.min_sequence().unwrap_or(0);
+        for point in &self.data_points {
+            csv.push_str(&self.app_row(point, first_sequence).join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Export the log as JSON with the metadata and columns of
+    /// [`to_app_csv`](Self::to_app_csv).
+    ///
+    /// The document has a `metadata` object keyed by the header field names
+    /// and a `samples` array of objects keyed by the column names, with
+    /// `null` where there is no data.
+    #[cfg(feature = "serde")]
+    pub fn to_app_json(&self, metadata: &AppExportMetadata) -> crate::error::Result<String> {
+        let header: serde_json::Map<_, _> = metadata
+            .header_lines(self)
+            .iter()
+            .filter_map(|line| line.split_once(": "))
+            .map(|(key, value)| (key.to_string(), serde_json::Value::from(value)))
+            .collect();
+
+        let first_sequence = self.min_sequence().unwrap_or(0);
+        let samples: Vec<serde_json::Value> = self
+            .data_points
+            .iter()
+            .map(|point| {
+                let row = self.app_row(point, first_sequence);
+                let sample: serde_json::Map<_, _> = APP_CSV_COLUMNS
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| {
+                        let value = match cell.parse::<f64>() {
+                            Ok(number) => serde_json::Value::from(number),
+                            Err(_) => serde_json::Value::Null,
+                        };
+                        (column.to_string(), value)
+                    })
+                    .collect();
+                serde_json::Value::Object(sample)
+            })
+            .collect();
+
+        let document = serde_json::json!({
+            "metadata": header,
+            "samples": samples,
+        });
+        serde_json::to_string_pretty(&document).map_err(|e| crate::error::Error::InvalidData {
+            context: format!("failed to serialize app export: {}", e),
+            payload: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{PredictionLog, ProbeTemperatures, RawTemperature};
+
+    fn log() -> TemperatureLog {
+        let mut log = TemperatureLog::new(7, 5000);
+        let mut temperatures =
+            ProbeTemperatures::from_raw([RawTemperature::from_celsius(20.0).raw_value(); 8]);
+        temperatures.values[7] = RawTemperature::INVALID;
+        log.add_data_point(LoggedDataPoint::new(10, temperatures.clone()));
+        log.add_data_point(LoggedDataPoint::with_prediction(
+            11,
+            temperatures,
+            PredictionLog {
+                virtual_core: 21.5,
+                virtual_surface: 22.0,
+                virtual_ambient: 30.0,
+                prediction_state: 3,
+                prediction_set_point: 63.0,
+                prediction_type: 1,
+                prediction_value_seconds: 600,
+            },
+        ));
+        log
+    }
+
+    fn metadata() -> AppExportMetadata {
+        AppExportMetadata::new(0x1000_0001)
+            .with_firmware_version(FirmwareVersion::new(1, 4, 0))
+            .with_app("test 1.0")
+            .with_created(DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+    }
+
+    #[test]
+    fn test_app_csv_layout() {
+        let csv = log().to_app_csv(&metadata());
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "Combustion Inc. Probe Data");
+        assert_eq!(lines[1], "App: test 1.0");
+        assert_eq!(lines[3], "Probe S/N: 10000001");
+        assert_eq!(lines[4], "Probe FW version: v1.4.0");
+        assert_eq!(lines[5], "Probe HW revision: ??");
+        assert_eq!(lines[7], "Sample Period: 5000");
+        assert_eq!(lines[8], "Created: 2023-11-14 22:13:20");
+        assert_eq!(lines[9], "");
+        assert_eq!(lines[10], APP_CSV_COLUMNS.join(","));
+        assert_eq!(
+            lines[11],
+            "0.000,7,10,20.00,20.00,20.00,20.00,20.00,20.00,20.00,,,,,,,,,,,,,"
+        );
+        assert_eq!(
+            lines[12],
+            "5.000,7,11,20.00,20.00,20.00,20.00,20.00,20.00,20.00,,21.50,22.00,30.00,,63.00,,,,3,,1,600"
+        );
+        for line in &lines[10..] {
+            assert_eq!(line.split(',').count(), APP_CSV_COLUMNS.len());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_json_matches_csv() {
+        let json = log().to_app_json(&metadata()).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(document["metadata"]["Probe S/N"], "10000001");
+        assert_eq!(document["metadata"]["Sample Period"], "5000");
+        let samples = document["samples"].as_array().unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1]["SequenceNumber"], 11.0);
+        assert_eq!(samples[1]["VirtualCoreTemperature"], 21.5);
+        assert!(samples[0]["T8"].is_null());
+    }
+}
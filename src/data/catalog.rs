@@ -0,0 +1,417 @@
+//! Catalog of food-safe products.
+//!
+//! One canonical list of the products the probe firmware knows, with display
+//! names, descriptions, and lookups by firmware code or stable key, so
+//! frontends don't each carry their own copy of the USDA mapping. Thresholds
+//! and default parameters come from the same functions used to build
+//! [`FoodSafeConfig`]s.
+//!
+//! Names are English. A [`ProductLocalization`] table overrides them per key
+//! for other languages.
+
+use std::collections::HashMap;
+
+use super::food_safety::{
+    FoodSafeConfig, FoodSafeMode, IntegratedProduct, Serving, SimplifiedProduct,
+};
+
+/// A product in the catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CatalogProduct {
+    /// Simplified mode product.
+    Simplified(SimplifiedProduct),
+    /// Integrated mode product.
+    Integrated(IntegratedProduct),
+}
+
+/// Catalog entry for a food-safe product.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogEntry {
+    /// The product.
+    pub product: CatalogProduct,
+    /// Stable identifier, unique across modes, for localization tables and
+    /// persisted settings.
+    pub key: &'static str,
+    /// English display name.
+    pub name: &'static str,
+    /// English description of what the product covers.
+    pub description: &'static str,
+}
+
+impl CatalogEntry {
+    /// Get the food safe mode of the product.
+    pub fn mode(&self) -> FoodSafeMode {
+        match self.product {
+            CatalogProduct::Simplified(_) => FoodSafeMode::Simplified,
+            CatalogProduct::Integrated(_) => FoodSafeMode::Integrated,
+        }
+    }
+
+    /// Get the firmware product code.
+    pub fn code(&self) -> u16 {
+        match self.product {
+            CatalogProduct::Simplified(product) => product.to_raw(),
+            CatalogProduct::Integrated(product) => product.to_raw(),
+        }
+    }
+
+    /// Build the default configuration for the product.
+    pub fn config(&self, serving: Serving) -> FoodSafeConfig {
+        match self.product {
+            CatalogProduct::Simplified(product) => FoodSafeConfig::simplified(product, serving),
+            CatalogProduct::Integrated(product) => FoodSafeConfig::integrated(product, serving),
+        }
+    }
+
+    /// Get the temperature the core must reach (simplified mode) or above
+    /// which lethality is integrated (integrated mode), in Celsius.
+    pub fn threshold_temperature(&self) -> f64 {
+        self.config(Serving::ServedImmediately)
+            .threshold_temperature
+    }
+}
+
+/// Shorthand for a simplified mode catalog entry.
+const fn simplified(
+    product: SimplifiedProduct,
+    key: &'static str,
+    name: &'static str,
+    description: &'static str,
+) -> CatalogEntry {
+    CatalogEntry {
+        product: CatalogProduct::Simplified(product),
+        key,
+        name,
+        description,
+    }
+}
+
+/// Shorthand for an integrated mode catalog entry.
+const fn integrated(
+    product: IntegratedProduct,
+    key: &'static str,
+    name: &'static str,
+    description: &'static str,
+) -> CatalogEntry {
+    CatalogEntry {
+        product: CatalogProduct::Integrated(product),
+        key,
+        name,
+        description,
+    }
+}
+
+/// Every product, simplified mode first, in firmware code order.
+const CATALOG: &[CatalogEntry] = &[
+    simplified(
+        SimplifiedProduct::Default,
+        "default",
+        "Default",
+        "Generic product held to the poultry temperature",
+    ),
+    simplified(
+        SimplifiedProduct::AnyPoultry,
+        "any_poultry",
+        "Any Poultry",
+        "Chicken, turkey, duck, and other poultry, whole or ground",
+    ),
+    simplified(
+        SimplifiedProduct::BeefCuts,
+        "beef_cuts",
+        "Beef Cuts",
+        "Beef steaks, roasts, and chops, with a 3 minute rest",
+    ),
+    simplified(
+        SimplifiedProduct::PorkCuts,
+        "pork_cuts",
+        "Pork Cuts",
+        "Pork steaks, roasts, and chops, with a 3 minute rest",
+    ),
+    simplified(
+        SimplifiedProduct::VealCuts,
+        "veal_cuts",
+        "Veal Cuts",
+        "Veal steaks, roasts, and chops, with a 3 minute rest",
+    ),
+    simplified(
+        SimplifiedProduct::LambCuts,
+        "lamb_cuts",
+        "Lamb Cuts",
+        "Lamb steaks, roasts, and chops, with a 3 minute rest",
+    ),
+    simplified(
+        SimplifiedProduct::GroundMeats,
+        "ground_meats",
+        "Ground Meats",
+        "Ground beef, pork, veal, and lamb",
+    ),
+    simplified(
+        SimplifiedProduct::HamFreshOrSmoked,
+        "ham_fresh_or_smoked",
+        "Ham Fresh/Smoked",
+        "Fresh or smoked ham that has not been cooked",
+    ),
+    simplified(
+        SimplifiedProduct::HamCookedAndReheated,
+        "ham_cooked_and_reheated",
+        "Ham Reheated",
+        "Fully cooked ham being reheated",
+    ),
+    simplified(SimplifiedProduct::Eggs, "eggs", "Eggs", "Egg dishes"),
+    simplified(
+        SimplifiedProduct::FishAndShellfish,
+        "fish_and_shellfish",
+        "Fish & Shellfish",
+        "Fin fish and shellfish",
+    ),
+    simplified(
+        SimplifiedProduct::Leftovers,
+        "leftovers",
+        "Leftovers",
+        "Cooked food being reheated",
+    ),
+    simplified(
+        SimplifiedProduct::Casseroles,
+        "casseroles",
+        "Casseroles",
+        "Casseroles and mixed dishes",
+    ),
+    integrated(
+        IntegratedProduct::Poultry,
+        "poultry",
+        "Poultry",
+        "Whole muscle poultry",
+    ),
+    integrated(
+        IntegratedProduct::Meats,
+        "meats",
+        "Meats",
+        "Whole muscle beef, pork, veal, and lamb",
+    ),
+    integrated(
+        IntegratedProduct::MeatsGroundChoppedOrStuffed,
+        "meats_ground",
+        "Ground Meats",
+        "Ground, chopped, or stuffed meats",
+    ),
+    integrated(
+        IntegratedProduct::PoultryGroundChoppedOrStuffed,
+        "poultry_ground",
+        "Ground Poultry",
+        "Ground, chopped, or stuffed poultry",
+    ),
+    integrated(
+        IntegratedProduct::Seafood,
+        "seafood",
+        "Seafood",
+        "Whole fish and shellfish",
+    ),
+    integrated(
+        IntegratedProduct::SeafoodGroundOrChopped,
+        "seafood_ground",
+        "Ground Seafood",
+        "Ground or chopped fish and shellfish",
+    ),
+    integrated(
+        IntegratedProduct::DairyMilk,
+        "dairy_milk",
+        "Dairy - Milk",
+        "Milk and dairy products under 10% fat",
+    ),
+    integrated(
+        IntegratedProduct::Other,
+        "other",
+        "Other",
+        "Products not covered by another category",
+    ),
+    integrated(
+        IntegratedProduct::SeafoodStuffed,
+        "seafood_stuffed",
+        "Stuffed Seafood",
+        "Stuffed fish and shellfish",
+    ),
+    integrated(IntegratedProduct::Eggs, "eggs_whole", "Eggs", "Whole eggs"),
+    integrated(
+        IntegratedProduct::EggsYolk,
+        "eggs_yolk",
+        "Egg Yolk",
+        "Egg yolks",
+    ),
+    integrated(
+        IntegratedProduct::EggsWhite,
+        "eggs_white",
+        "Egg White",
+        "Egg whites",
+    ),
+    integrated(
+        IntegratedProduct::DairyCreams,
+        "dairy_creams",
+        "Dairy - Creams",
+        "Creams and dairy products over 10% fat",
+    ),
+    integrated(
+        IntegratedProduct::DairyIceCreamMixEggnog,
+        "dairy_ice_cream_mix_eggnog",
+        "Ice Cream/Eggnog",
+        "Ice cream mix and eggnog",
+    ),
+    integrated(
+        IntegratedProduct::Custom,
+        "custom",
+        "Custom",
+        "User-defined lethality parameters",
+    ),
+];
+
+/// Get every product in the catalog.
+pub fn catalog() -> &'static [CatalogEntry] {
+    CATALOG
+}
+
+/// Get the products of one food safe mode.
+pub fn products(mode: FoodSafeMode) -> impl Iterator<Item = &'static CatalogEntry> {
+    CATALOG.iter().filter(move |entry| entry.mode() == mode)
+}
+
+/// Look up a product by mode and firmware code, e.g. from a
+/// [`FoodSafeConfig`].
+pub fn lookup(mode: FoodSafeMode, code: u16) -> Option<&'static CatalogEntry> {
+    products(mode).find(|entry| entry.code() == code)
+}
+
+/// Look up a product by its stable key.
+pub fn lookup_key(key: &str) -> Option<&'static CatalogEntry> {
+    CATALOG.iter().find(|entry| entry.key == key)
+}
+
+impl FoodSafeConfig {
+    /// Get the catalog entry for the configured product.
+    pub fn catalog_entry(&self) -> Option<&'static CatalogEntry> {
+        lookup(self.mode, self.product)
+    }
+}
+
+/// Translated product name and description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalizedProduct {
+    /// Display name.
+    pub name: String,
+    /// Description, or `None` to keep the English description.
+    pub description: Option<String>,
+}
+
+/// Product names for one language, keyed by [`CatalogEntry::key`].
+///
+/// Products missing from the table fall back to English.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProductLocalization {
+    /// Language tag, e.g. "de" or "pt-BR".
+    pub language: String,
+    /// Translations by catalog key.
+    pub products: HashMap<String, LocalizedProduct>,
+}
+
+impl ProductLocalization {
+    /// Create an empty table for a language.
+    pub fn new(language: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            products: HashMap::new(),
+        }
+    }
+
+    /// Add a translated name.
+    pub fn with_name(mut self, key: impl Into<String>, name: impl Into<String>) -> Self {
+        self.products.insert(
+            key.into(),
+            LocalizedProduct {
+                name: name.into(),
+                description: None,
+            },
+        );
+        self
+    }
+
+    /// Add a translated name and description.
+    pub fn with_product(
+        mut self,
+        key: impl Into<String>,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.products.insert(
+            key.into(),
+            LocalizedProduct {
+                name: name.into(),
+                description: Some(description.into()),
+            },
+        );
+        self
+    }
+
+    /// Get the display name of a product.
+    pub fn name<'a>(&'a self, entry: &'a CatalogEntry) -> &'a str {
+        self.products
+            .get(entry.key)
+            .map_or(entry.name, |product| product.name.as_str())
+    }
+
+    /// Get the description of a product.
+    pub fn description<'a>(&'a self, entry: &'a CatalogEntry) -> &'a str {
+        self.products
+            .get(entry.key)
+            .and_then(|product| product.description.as_deref())
+            .unwrap_or(entry.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_covers_every_product() {
+        for code in 0..=12 {
+            let product = SimplifiedProduct::from_raw(code).unwrap();
+            let entry = lookup(FoodSafeMode::Simplified, code).unwrap();
+            assert_eq!(entry.product, CatalogProduct::Simplified(product));
+        }
+        for code in 0..1024 {
+            if let Some(product) = IntegratedProduct::from_raw(code) {
+                let entry = lookup(FoodSafeMode::Integrated, code).unwrap();
+                assert_eq!(entry.product, CatalogProduct::Integrated(product));
+            }
+        }
+
+        let mut keys: Vec<_> = catalog().iter().map(|entry| entry.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), catalog().len());
+    }
+
+    #[test]
+    fn test_lookup_matches_config() {
+        let config = FoodSafeConfig::simplified(SimplifiedProduct::BeefCuts, Serving::default());
+        let entry = config.catalog_entry().unwrap();
+        assert_eq!(entry.name, "Beef Cuts");
+        assert_eq!(entry.threshold_temperature(), 63.0);
+        assert_eq!(lookup_key("beef_cuts"), Some(entry));
+        assert_eq!(entry.config(Serving::default()), config);
+    }
+
+    #[test]
+    fn test_localization_falls_back_to_english() {
+        let german = ProductLocalization::new("de").with_product(
+            "beef_cuts",
+            "Rindfleisch",
+            "Steaks und Braten vom Rind",
+        );
+        let beef = lookup_key("beef_cuts").unwrap();
+        let pork = lookup_key("pork_cuts").unwrap();
+        assert_eq!(german.name(beef), "Rindfleisch");
+        assert_eq!(german.description(beef), "Steaks und Braten vom Rind");
+        assert_eq!(german.name(pork), "Pork Cuts");
+    }
+}
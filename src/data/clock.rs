@@ -0,0 +1,229 @@
+//! Mapping probe log sequence numbers to wall-clock time.
+//!
+//! The probe numbers its log samples but does not timestamp them. Deriving
+//! times from the nominal sample period drifts against the host clock over a
+//! long cook, so a [`SequenceClock`] records anchors (a sequence number and
+//! the host time it was first reported) as status notifications arrive, fits
+//! the probe's effective sample period to them, and maps sequence numbers to
+//! drift-corrected timestamps.
+
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Maximum anchors kept; older anchors are thinned out beyond this.
+const MAX_ANCHORS: usize = 256;
+
+/// Minimum sequence span before the fitted period replaces the nominal one.
+const MIN_FIT_SPAN: u32 = 10;
+
+/// A sequence number and the host time it was first reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClockAnchor {
+    /// Log sequence number.
+    pub sequence: u32,
+    /// Host time the sequence number was first reported.
+    pub host_time: DateTime<Utc>,
+}
+
+/// Drift-corrected mapping from sequence numbers to wall-clock time.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceClock {
+    /// Nominal sample period reported by the probe, in milliseconds.
+    nominal_period_ms: Option<u32>,
+    /// Anchors in sequence order.
+    anchors: Vec<ClockAnchor>,
+}
+
+impl SequenceClock {
+    /// Create a clock with no anchors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a clock with the probe's nominal sample period.
+    pub fn with_nominal_period(period_ms: u32) -> Self {
+        Self {
+            nominal_period_ms: (period_ms > 0).then_some(period_ms),
+            anchors: Vec::new(),
+        }
+    }
+
+    /// Set the nominal sample period, used until enough anchors are recorded.
+    pub fn set_nominal_period(&mut self, period_ms: u32) {
+        self.nominal_period_ms = (period_ms > 0).then_some(period_ms);
+    }
+
+    /// Record that `sequence` was first reported at `host_time`.
+    ///
+    /// Anchors for sequence numbers at or below the newest anchor are
+    /// ignored. When the anchor list is full, every other older anchor is
+    /// dropped so the fit keeps covering the whole session.
+    pub fn anchor(&mut self, sequence: u32, host_time: DateTime<Utc>) {
+        if self.anchors.last().is_some_and(|a| sequence <= a.sequence) {
+            return;
+        }
+
+        if self.anchors.len() >= MAX_ANCHORS {
+            let mut index = 0;
+            self.anchors.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+        }
+        self.anchors.push(ClockAnchor {
+            sequence,
+            host_time,
+        });
+    }
+
+    /// Drop all anchors, e.g. when a new session starts.
+    pub fn reset(&mut self) {
+        self.anchors.clear();
+    }
+
+    /// Get the recorded anchors in sequence order.
+    pub fn anchors(&self) -> &[ClockAnchor] {
+        &self.anchors
+    }
+
+    /// Get the probe's effective sample period.
+    ///
+    /// Fitted to the anchors once they span enough samples, otherwise the
+    /// nominal period. `None` if neither is known.
+    pub fn effective_sample_period(&self) -> Option<Duration> {
+        let ms = self
+            .fit()
+            .map(|(_, slope)| slope)
+            .or(self.nominal_period_ms.map(f64::from))?;
+        Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0))
+    }
+
+    /// Get the drift of the effective period against the nominal period, in
+    /// parts per million. Positive when the probe samples slower than nominal.
+    pub fn drift_ppm(&self) -> Option<f64> {
+        let nominal = f64::from(self.nominal_period_ms?);
+        let (_, slope) = self.fit()?;
+        Some((slope - nominal) / nominal * 1_000_000.0)
+    }
+
+    /// Get the wall-clock time `sequence` was sampled.
+    ///
+    /// Returns `None` until at least one anchor is recorded and a sample
+    /// period is known.
+    pub fn timestamp(&self, sequence: u32) -> Option<DateTime<Utc>> {
+        let origin = self.anchors.first()?;
+        let (intercept_ms, slope_ms) = match self.fit() {
+            Some(fit) => fit,
+            None => {
+                // Extrapolate from the newest anchor with the nominal period
+                let last = self.anchors.last()?;
+                let slope = f64::from(self.nominal_period_ms?);
+                let offset = (last.host_time - origin.host_time).num_milliseconds() as f64;
+                let intercept = offset - slope * f64::from(last.sequence - origin.sequence);
+                (intercept, slope)
+            }
+        };
+
+        let delta = f64::from(sequence) - f64::from(origin.sequence);
+        let offset_ms = intercept_ms + slope_ms * delta;
+        Some(origin.host_time + chrono::Duration::milliseconds(offset_ms.round() as i64))
+    }
+
+    /// Least-squares fit of host time (ms since the first anchor) against
+    /// sequence offset, as `(intercept, slope)`.
+    fn fit(&self) -> Option<(f64, f64)> {
+        let origin = self.anchors.first()?;
+        let last = self.anchors.last()?;
+        if last.sequence - origin.sequence < MIN_FIT_SPAN {
+            return None;
+        }
+
+        let points: Vec<(f64, f64)> = self
+            .anchors
+            .iter()
+            .map(|a| {
+                (
+                    f64::from(a.sequence - origin.sequence),
+                    (a.host_time - origin.host_time).num_milliseconds() as f64,
+                )
+            })
+            .collect();
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let covariance: f64 = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        if variance == 0.0 {
+            return None;
+        }
+
+        let slope = covariance / variance;
+        Some((mean_y - slope * mean_x, slope))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        "2026-01-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_nominal_period_before_fit() {
+        let mut clock = SequenceClock::with_nominal_period(5000);
+        assert!(clock.timestamp(0).is_none());
+
+        clock.anchor(100, start());
+        assert_eq!(
+            clock.timestamp(102),
+            Some(start() + chrono::Duration::seconds(10))
+        );
+        assert_eq!(
+            clock.effective_sample_period(),
+            Some(Duration::from_secs(5))
+        );
+        assert!(clock.drift_ppm().is_none());
+    }
+
+    #[test]
+    fn test_fit_corrects_drift() {
+        // The probe samples every 5.005 s against a nominal 5 s (1000 ppm slow)
+        let mut clock = SequenceClock::with_nominal_period(5000);
+        for sequence in (0..10_000).step_by(60) {
+            let jitter = if sequence % 120 == 0 { 300 } else { 0 };
+            let at = start() + chrono::Duration::milliseconds(sequence as i64 * 5005 + jitter);
+            clock.anchor(sequence, at);
+        }
+        assert!(clock.anchors().len() <= MAX_ANCHORS);
+
+        let drift = clock.drift_ppm().unwrap();
+        assert!((drift - 1000.0).abs() < 5.0, "drift {}", drift);
+
+        // 14 hours in, nominal timing would be off by about 50 s
+        let sequence = 10_080;
+        let expected = start() + chrono::Duration::milliseconds(sequence as i64 * 5005);
+        let error = (clock.timestamp(sequence).unwrap() - expected)
+            .num_milliseconds()
+            .abs();
+        assert!(error < 500, "error {} ms", error);
+    }
+
+    #[test]
+    fn test_ignores_stale_anchors() {
+        let mut clock = SequenceClock::new();
+        clock.anchor(10, start());
+        clock.anchor(10, start() + chrono::Duration::seconds(1));
+        clock.anchor(9, start() + chrono::Duration::seconds(2));
+        assert_eq!(clock.anchors().len(), 1);
+
+        clock.reset();
+        assert!(clock.anchors().is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! Guided cook specification.
+//!
+//! A [`CookSpec`] bundles the prediction, food safety, and alarm settings
+//! for one cook so they can be applied together with
+//! [`Probe::start_cook`](crate::Probe::start_cook).
+
+use crate::data::alarms::AlarmConfig;
+use crate::data::food_safety::{FoodSafeConfig, FoodSafeProduct, Serving};
+use crate::data::prediction::{PredictionInfo, PredictionMode};
+use crate::error::{Error, Result};
+
+/// Prediction set points the probe accepts, in Celsius.
+const TARGET_RANGE: std::ops::RangeInclusive<f64> = 0.0..=PredictionInfo::MAX_SET_POINT;
+/// Alarm temperature range representable on the wire, in Celsius.
+const ALARM_RANGE: std::ops::RangeInclusive<f64> = -20.0..=799.0;
+
+/// Settings for a guided cook.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CookSpec {
+    /// Product being cooked.
+    pub product: FoodSafeProduct,
+    /// Core temperature to serve at, in Celsius.
+    pub target: f64,
+    /// Expected core temperature rise while resting, in Celsius.
+    ///
+    /// The probe predicts towards `target - carryover` so the food reaches
+    /// `target` after resting.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub carryover: f64,
+    /// Prediction mode. `PredictionMode::None` leaves prediction off.
+    pub prediction_mode: PredictionMode,
+    /// Food safety configuration, or `None` to leave food safety off.
+    pub food_safe: Option<FoodSafeConfig>,
+    /// Alarms to set, or `None` to leave the probe's alarms unchanged.
+    pub alarms: Option<AlarmConfig>,
+    /// Whether to add a core high alarm at the removal temperature; see
+    /// [`resolved_alarms`](Self::resolved_alarms).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub target_alarm: bool,
+}
+
+impl CookSpec {
+    /// Create a spec that predicts time to removal at `target` and monitors
+    /// food safety for `product`, served immediately.
+    pub fn new(product: FoodSafeProduct, target: f64) -> Self {
+        Self {
+            product,
+            target,
+            carryover: 0.0,
+            prediction_mode: PredictionMode::TimeToRemoval,
+            food_safe: Some(product.to_config(Serving::ServedImmediately)),
+            alarms: None,
+            target_alarm: false,
+        }
+    }
+
+    /// Set the expected resting carryover in Celsius.
+    pub fn with_carryover(mut self, carryover: f64) -> Self {
+        self.carryover = carryover;
+        self
+    }
+
+    /// Get the core temperature at which to remove the food, in Celsius.
+    pub fn removal_target(&self) -> f64 {
+        self.target - self.carryover
+    }
+
+    /// Set the prediction mode.
+    pub fn with_prediction_mode(mut self, mode: PredictionMode) -> Self {
+        self.prediction_mode = mode;
+        self
+    }
+
+    /// Monitor food safety for the product with the given serving mode.
+    pub fn with_serving(mut self, serving: Serving) -> Self {
+        self.food_safe = Some(self.product.to_config(serving));
+        self
+    }
+
+    /// Use a custom food safety configuration.
+    pub fn with_food_safe_config(mut self, config: FoodSafeConfig) -> Self {
+        self.food_safe = Some(config);
+        self
+    }
+
+    /// Leave food safety monitoring off.
+    pub fn without_food_safe(mut self) -> Self {
+        self.food_safe = None;
+        self
+    }
+
+    /// Set alarms as part of the cook.
+    pub fn with_alarms(mut self, alarms: AlarmConfig) -> Self {
+        self.alarms = Some(alarms);
+        self
+    }
+
+    /// Set a core high alarm at the removal temperature.
+    ///
+    /// The alarm is placed when the cook starts, so it follows the target
+    /// and carryover whatever order they are set in, and is added to any
+    /// [`with_alarms`](Self::with_alarms) configuration.
+    pub fn with_target_alarm(mut self) -> Self {
+        self.target_alarm = true;
+        self
+    }
+
+    /// Get the alarms to set when the cook starts: [`alarms`](Self::alarms)
+    /// plus the core high alarm at the removal temperature if
+    /// [`target_alarm`](Self::target_alarm) is set.
+    pub fn resolved_alarms(&self) -> Option<AlarmConfig> {
+        if !self.target_alarm {
+            return self.alarms.clone();
+        }
+        let mut alarms = self.alarms.clone().unwrap_or_default();
+        alarms.set_core_high_alarm(self.removal_target(), true);
+        Some(alarms)
+    }
+
+    /// Check that every setting can be sent to the probe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] naming the first invalid setting,
+    /// or [`Error::InvalidFoodSafeConfig`] if the food safety configuration
+    /// cannot be encoded.
+    pub fn validate(&self) -> Result<()> {
+        if !TARGET_RANGE.contains(&self.target) {
+            return Err(Error::InvalidParameter {
+                name: "target".to_string(),
+                value: self.target.to_string(),
+            });
+        }
+
+        let carryover_valid = self.carryover >= 0.0
+            && self.carryover.is_finite()
+            && TARGET_RANGE.contains(&self.removal_target());
+        if !carryover_valid {
+            return Err(Error::InvalidParameter {
+                name: "carryover".to_string(),
+                value: self.carryover.to_string(),
+            });
+        }
+
+        if let Some(config) = &self.food_safe {
+            config.validate()?;
+        }
+
+        if let Some(alarms) = &self.resolved_alarms() {
+            let out_of_range = alarms
+                .high_alarms
+                .iter()
+                .chain(alarms.low_alarms.iter())
+                .find(|alarm| alarm.set && !ALARM_RANGE.contains(&alarm.temperature));
+            if let Some(alarm) = out_of_range {
+                return Err(Error::InvalidParameter {
+                    name: "alarms".to_string(),
+                    value: alarm.temperature.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_defaults() {
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 54.0);
+        assert_eq!(spec.prediction_mode, PredictionMode::TimeToRemoval);
+        assert!(spec.food_safe.is_some());
+        assert!(spec.alarms.is_none());
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_target_alarm() {
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 54.0).with_target_alarm();
+        let alarms = spec.resolved_alarms().unwrap();
+        assert!(alarms.core_high_alarm().set);
+        assert_eq!(alarms.core_high_alarm().temperature, 54.0);
+    }
+
+    #[test]
+    fn test_carryover_lowers_removal_target() {
+        let spec = CookSpec::new(FoodSafeProduct::BeefRoast, 57.0)
+            .with_carryover(3.0)
+            .with_target_alarm();
+        assert_eq!(spec.removal_target(), 54.0);
+        assert_eq!(
+            spec.resolved_alarms()
+                .unwrap()
+                .core_high_alarm()
+                .temperature,
+            54.0
+        );
+
+        // The builder order doesn't matter
+        let mut low = AlarmConfig::new();
+        low.set_core_low_alarm(2.0, true);
+        let spec = CookSpec::new(FoodSafeProduct::BeefRoast, 57.0)
+            .with_target_alarm()
+            .with_alarms(low)
+            .with_carryover(3.0);
+        let alarms = spec.resolved_alarms().unwrap();
+        assert_eq!(alarms.core_high_alarm().temperature, 54.0);
+        assert!(alarms.core_low_alarm().set);
+
+        let spec = CookSpec::new(FoodSafeProduct::BeefRoast, 57.0).with_carryover(-1.0);
+        assert!(matches!(
+            spec.validate(),
+            Err(Error::InvalidParameter { name, .. }) if name == "carryover"
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_settings() {
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 350.0);
+        assert!(matches!(
+            spec.validate(),
+            Err(Error::InvalidParameter { name, .. }) if name == "target"
+        ));
+
+        // Above what the 10-bit set point field can hold
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 110.0);
+        assert!(matches!(
+            spec.validate(),
+            Err(Error::InvalidParameter { name, .. }) if name == "target"
+        ));
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, PredictionInfo::MAX_SET_POINT);
+        assert!(spec.validate().is_ok());
+
+        let mut alarms = AlarmConfig::new();
+        alarms.set_core_high_alarm(900.0, true);
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 54.0).with_alarms(alarms);
+        assert!(matches!(
+            spec.validate(),
+            Err(Error::InvalidParameter { name, .. }) if name == "alarms"
+        ));
+
+        let config = FoodSafeConfig {
+            z_value: 0.0,
+            ..FoodSafeConfig::default()
+        };
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 54.0).with_food_safe_config(config);
+        assert!(matches!(
+            spec.validate(),
+            Err(Error::InvalidFoodSafeConfig { field, .. }) if field == "z_value"
+        ));
+
+        let config = FoodSafeConfig {
+            threshold_temperature: 1000.0,
+            ..FoodSafeConfig::default()
+        };
+        let spec = CookSpec::new(FoodSafeProduct::BeefSteak, 54.0).with_food_safe_config(config);
+        assert!(matches!(
+            spec.validate(),
+            Err(Error::InvalidFoodSafeConfig { field, .. }) if field == "threshold_temperature"
+        ));
+    }
+}
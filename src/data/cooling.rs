@@ -0,0 +1,404 @@
+//! Cooling-phase monitoring for cooked and chilled food.
+//!
+//! Food served cooked and chilled must cool through the temperature range
+//! that favours pathogen growth quickly. A [`CoolingProfile`] lists the
+//! stages and time limits of a cooling guideline, and a [`CoolingMonitor`]
+//! tracks core temperatures against it, raising [`CoolingEvent`]s as stages
+//! complete or when cooling is too slow.
+
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Fraction of a stage's time limit to wait before projecting its end time.
+const PROJECTION_DELAY: f64 = 0.1;
+
+/// One stage of a cooling guideline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoolingStage {
+    /// Temperature at which the stage starts, in Celsius.
+    pub from: f64,
+    /// Temperature the food must reach, in Celsius.
+    pub to: f64,
+    /// Time allowed to go from `from` to `to`.
+    pub within: Duration,
+}
+
+/// Stages of a cooling guideline, hottest first.
+///
+/// Each stage's time limit runs from when the previous stage completed, or
+/// for the first stage from when the core temperature fell to its `from`
+/// temperature.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoolingProfile {
+    /// Stages in order.
+    pub stages: Vec<CoolingStage>,
+}
+
+impl CoolingProfile {
+    /// FDA Food Code: 57°C to 21°C within 2 hours, then to 5°C within a
+    /// further 4 hours.
+    pub fn food_code() -> Self {
+        Self {
+            stages: vec![
+                CoolingStage {
+                    from: 57.0,
+                    to: 21.0,
+                    within: Duration::from_secs(2 * 3600),
+                },
+                CoolingStage {
+                    from: 21.0,
+                    to: 5.0,
+                    within: Duration::from_secs(4 * 3600),
+                },
+            ],
+        }
+    }
+
+    /// USDA FSIS Appendix B: 54.4°C to 26.7°C within 1.5 hours, then to
+    /// 4.4°C within a further 5 hours.
+    pub fn fsis() -> Self {
+        Self {
+            stages: vec![
+                CoolingStage {
+                    from: 54.4,
+                    to: 26.7,
+                    within: Duration::from_secs(90 * 60),
+                },
+                CoolingStage {
+                    from: 26.7,
+                    to: 4.4,
+                    within: Duration::from_secs(5 * 3600),
+                },
+            ],
+        }
+    }
+
+    /// Get the temperature at which cooling starts, in Celsius.
+    pub fn start_temperature(&self) -> Option<f64> {
+        self.stages.first().map(|stage| stage.from)
+    }
+}
+
+impl Default for CoolingProfile {
+    fn default() -> Self {
+        Self::food_code()
+    }
+}
+
+/// Progress through a cooling profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoolingPhase {
+    /// Waiting for the core to fall to the profile's start temperature.
+    Armed,
+    /// Cooling through a stage (index into the profile's stages).
+    Cooling(usize),
+    /// Every stage completed in time.
+    Complete,
+    /// A stage ran past its time limit (index into the profile's stages).
+    Failed(usize),
+}
+
+/// Cooling progress reported by a [`CoolingMonitor`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoolingEvent {
+    /// The core fell to the profile's start temperature.
+    Started {
+        /// When cooling started.
+        at: DateTime<Utc>,
+    },
+    /// A stage reached its target temperature in time.
+    StageComplete {
+        /// Stage index.
+        stage: usize,
+        /// Time the stage took.
+        elapsed: Duration,
+    },
+    /// At the current rate a stage will not finish in time.
+    ///
+    /// Raised at most once per stage.
+    TooSlow {
+        /// Stage index.
+        stage: usize,
+        /// Projected stage duration at the current rate, or `None` if the
+        /// core is not cooling.
+        projected: Option<Duration>,
+        /// Time allowed for the stage.
+        allowed: Duration,
+    },
+    /// A stage ran past its time limit.
+    Failed {
+        /// Stage index.
+        stage: usize,
+        /// Core temperature at the deadline, in Celsius.
+        temperature: f64,
+    },
+    /// Every stage completed in time.
+    Complete {
+        /// Total cooling time.
+        elapsed: Duration,
+    },
+}
+
+/// Tracks core temperatures against a [`CoolingProfile`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoolingMonitor {
+    /// Profile being followed.
+    profile: CoolingProfile,
+    /// Current phase.
+    phase: CoolingPhase,
+    /// When cooling started.
+    started_at: Option<DateTime<Utc>>,
+    /// Start time and temperature of the current stage.
+    stage_start: Option<(DateTime<Utc>, f64)>,
+    /// Whether the current stage has been reported as too slow.
+    warned: bool,
+    /// Most recent core temperature, in Celsius.
+    #[cfg_attr(feature = "serde", serde(default))]
+    last_core: Option<f64>,
+}
+
+impl CoolingMonitor {
+    /// Create a monitor waiting for the core to fall to the profile's start
+    /// temperature.
+    pub fn new(profile: CoolingProfile) -> Self {
+        let phase = if profile.stages.is_empty() {
+            CoolingPhase::Complete
+        } else {
+            CoolingPhase::Armed
+        };
+        Self {
+            profile,
+            phase,
+            started_at: None,
+            stage_start: None,
+            warned: false,
+            last_core: None,
+        }
+    }
+
+    /// Get the profile being followed.
+    pub fn profile(&self) -> &CoolingProfile {
+        &self.profile
+    }
+
+    /// Get the current phase.
+    pub fn phase(&self) -> CoolingPhase {
+        self.phase
+    }
+
+    /// Get when cooling started, if it has.
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.started_at
+    }
+
+    /// Get when the current stage runs out of time, if cooling.
+    pub fn deadline(&self) -> Option<DateTime<Utc>> {
+        let CoolingPhase::Cooling(index) = self.phase else {
+            return None;
+        };
+        let (stage_started, _) = self.stage_start?;
+        let within = chrono::Duration::from_std(self.profile.stages[index].within).ok()?;
+        Some(stage_started + within)
+    }
+
+    /// Fail the current stage if its deadline has passed by `at`.
+    ///
+    /// Readings may stop arriving, e.g. when the probe disconnects, so call
+    /// this at the [`deadline`](Self::deadline) rather than waiting for the
+    /// next [`update`](Self::update). The event reports the last core
+    /// temperature fed in.
+    pub fn expire(&mut self, at: DateTime<Utc>) -> Option<CoolingEvent> {
+        let CoolingPhase::Cooling(index) = self.phase else {
+            return None;
+        };
+        if self.deadline().is_some_and(|deadline| at < deadline) {
+            return None;
+        }
+        self.phase = CoolingPhase::Failed(index);
+        let temperature = self
+            .last_core
+            .or(self.stage_start.map(|(_, temperature)| temperature))?;
+        Some(CoolingEvent::Failed {
+            stage: index,
+            temperature,
+        })
+    }
+
+    /// Feed a core temperature (Celsius) taken at `at`.
+    pub fn update(&mut self, core: f64, at: DateTime<Utc>) -> Vec<CoolingEvent> {
+        let mut events = Vec::new();
+        self.last_core = Some(core);
+
+        if self.phase == CoolingPhase::Armed {
+            let Some(start) = self.profile.start_temperature() else {
+                return events;
+            };
+            if core > start {
+                return events;
+            }
+            self.started_at = Some(at);
+            self.begin_stage(0, at, core);
+            events.push(CoolingEvent::Started { at });
+        }
+
+        // A reading may complete several stages at once
+        while let CoolingPhase::Cooling(index) = self.phase {
+            let stage = self.profile.stages[index];
+            let Some((stage_started, start_temperature)) = self.stage_start else {
+                break;
+            };
+            let elapsed = (at - stage_started).to_std().unwrap_or_default();
+
+            if core <= stage.to && elapsed <= stage.within {
+                events.push(CoolingEvent::StageComplete {
+                    stage: index,
+                    elapsed,
+                });
+                if index + 1 < self.profile.stages.len() {
+                    self.begin_stage(index + 1, at, core);
+                    continue;
+                }
+                self.phase = CoolingPhase::Complete;
+                let total = self.started_at.map_or(elapsed, |started| {
+                    (at - started).to_std().unwrap_or_default()
+                });
+                events.push(CoolingEvent::Complete { elapsed: total });
+                break;
+            }
+
+            if elapsed > stage.within {
+                self.phase = CoolingPhase::Failed(index);
+                events.push(CoolingEvent::Failed {
+                    stage: index,
+                    temperature: core,
+                });
+                break;
+            }
+
+            let settled = elapsed.as_secs_f64() >= stage.within.as_secs_f64() * PROJECTION_DELAY;
+            if settled && !self.warned {
+                let projected = Self::project(start_temperature, core, stage.to, elapsed);
+                if projected.map_or(true, |p| p > stage.within) {
+                    self.warned = true;
+                    events.push(CoolingEvent::TooSlow {
+                        stage: index,
+                        projected,
+                        allowed: stage.within,
+                    });
+                }
+            }
+            break;
+        }
+
+        events
+    }
+
+    /// Start timing a stage.
+    fn begin_stage(&mut self, index: usize, at: DateTime<Utc>, core: f64) {
+        self.phase = CoolingPhase::Cooling(index);
+        self.stage_start = Some((at, core));
+        self.warned = false;
+    }
+
+    /// Project a stage's duration from its average cooling rate so far.
+    fn project(start: f64, core: f64, target: f64, elapsed: Duration) -> Option<Duration> {
+        let cooled = start - core;
+        if cooled <= 0.0 || elapsed.is_zero() {
+            return None;
+        }
+        let rate = cooled / elapsed.as_secs_f64();
+        Some(Duration::from_secs_f64((start - target).max(0.0) / rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        "2026-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+            + chrono::Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_compliant_cooling() {
+        let mut monitor = CoolingMonitor::new(CoolingProfile::food_code());
+        assert!(monitor.update(70.0, at(0)).is_empty());
+        assert_eq!(monitor.phase(), CoolingPhase::Armed);
+
+        assert_eq!(
+            monitor.update(57.0, at(10)),
+            vec![CoolingEvent::Started { at: at(10) }]
+        );
+        assert!(monitor.update(40.0, at(60)).is_empty());
+        assert_eq!(
+            monitor.update(20.0, at(100)),
+            vec![CoolingEvent::StageComplete {
+                stage: 0,
+                elapsed: Duration::from_secs(90 * 60),
+            }]
+        );
+        assert_eq!(monitor.phase(), CoolingPhase::Cooling(1));
+
+        let events = monitor.update(4.0, at(280));
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1],
+            CoolingEvent::Complete {
+                elapsed: Duration::from_secs(270 * 60),
+            }
+        );
+        assert_eq!(monitor.phase(), CoolingPhase::Complete);
+    }
+
+    #[test]
+    fn test_slow_cooling_warns_then_fails() {
+        let mut monitor = CoolingMonitor::new(CoolingProfile::food_code());
+        monitor.update(57.0, at(0));
+
+        // 6°C in 30 minutes projects 3 hours for the 36°C stage
+        let events = monitor.update(51.0, at(30));
+        assert!(matches!(
+            events.as_slice(),
+            [CoolingEvent::TooSlow { stage: 0, projected: Some(p), .. }]
+                if p.as_secs_f64().round() == (3 * 3600) as f64
+        ));
+        assert!(monitor.update(45.0, at(60)).is_empty());
+
+        assert_eq!(
+            monitor.update(30.0, at(121)),
+            vec![CoolingEvent::Failed {
+                stage: 0,
+                temperature: 30.0,
+            }]
+        );
+        assert_eq!(monitor.phase(), CoolingPhase::Failed(0));
+        assert!(monitor.update(20.0, at(130)).is_empty());
+    }
+
+    #[test]
+    fn test_stage_fails_at_deadline_without_readings() {
+        let mut monitor = CoolingMonitor::new(CoolingProfile::food_code());
+        assert_eq!(monitor.deadline(), None);
+        monitor.update(57.0, at(0));
+        monitor.update(40.0, at(30));
+        assert_eq!(monitor.deadline(), Some(at(120)));
+
+        assert_eq!(monitor.expire(at(119)), None);
+        assert_eq!(
+            monitor.expire(at(120)),
+            Some(CoolingEvent::Failed {
+                stage: 0,
+                temperature: 40.0,
+            })
+        );
+        assert_eq!(monitor.phase(), CoolingPhase::Failed(0));
+        assert_eq!(monitor.deadline(), None);
+        assert_eq!(monitor.expire(at(121)), None);
+    }
+}
@@ -0,0 +1,386 @@
+//! Self-describing CSV exports.
+//!
+//! [`TemperatureLog::to_csv_with`] writes the columns of
+//! [`TemperatureLog::to_csv`] after a header block of `#` comment lines
+//! naming the probe, firmware, session, sample period, units, export time
+//! and exporting library, so a file makes sense on its own. Temperatures can
+//! be written in Celsius, Fahrenheit or both, in either order; Fahrenheit
+//! columns carry an `_F` suffix, e.g. `T1_F`. [`TemperatureLog::from_csv`] reads these files
+//! back.
+//!
+//! ```text
+//! # Combustion probe log
+//! # Probe S/N: 10000001
+//! # Firmware: v1.4.0
+//! # Session ID: 7
+//! # Sample period: 1000 ms
+//! # Units: Celsius and Fahrenheit
+//! # Exported: 2024-03-09T06:30:00.000Z
+//! # Exporter: combustion-rust-ble 0.1.0
+//! Sequence,T1,...,T8,T1_F,...,T8_F
+//! ```
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::log::TemperatureLog;
+use super::temperatures::{PhysicalSensor, VirtualSensor};
+use crate::capabilities::FirmwareVersion;
+use crate::utils::TemperatureUnit;
+
+/// Prefix of the header lines of a CSV export.
+pub const CSV_COMMENT_PREFIX: &str = "#";
+
+/// Suffix of the names of Fahrenheit columns.
+pub const FAHRENHEIT_SUFFIX: &str = "_F";
+
+/// Temperature units written to a CSV export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CsvUnits {
+    /// Celsius columns only.
+    #[default]
+    Celsius,
+    /// Fahrenheit columns only.
+    Fahrenheit,
+    /// Celsius columns followed by Fahrenheit columns.
+    Both,
+    /// Fahrenheit columns followed by Celsius columns.
+    BothFahrenheitFirst,
+}
+
+impl CsvUnits {
+    /// Get the units for columns in `unit`, followed by the other unit if
+    /// `dual` is set.
+    pub fn for_unit(unit: TemperatureUnit, dual: bool) -> Self {
+        match (unit, dual) {
+            (TemperatureUnit::Celsius, false) => Self::Celsius,
+            (TemperatureUnit::Fahrenheit, false) => Self::Fahrenheit,
+            (TemperatureUnit::Celsius, true) => Self::Both,
+            (TemperatureUnit::Fahrenheit, true) => Self::BothFahrenheitFirst,
+        }
+    }
+
+    /// Check if Celsius columns are written.
+    pub fn celsius(&self) -> bool {
+        !matches!(self, Self::Fahrenheit)
+    }
+
+    /// Check if Fahrenheit columns are written.
+    pub fn fahrenheit(&self) -> bool {
+        !matches!(self, Self::Celsius)
+    }
+
+    /// Get the units written, in column order.
+    pub fn order(&self) -> &'static [TemperatureUnit] {
+        match self {
+            Self::Celsius => &[TemperatureUnit::Celsius],
+            Self::Fahrenheit => &[TemperatureUnit::Fahrenheit],
+            Self::Both => &[TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit],
+            Self::BothFahrenheitFirst => &[TemperatureUnit::Fahrenheit, TemperatureUnit::Celsius],
+        }
+    }
+
+    /// Get the suffix of column names in `unit`.
+    pub(crate) fn suffix(unit: TemperatureUnit) -> &'static str {
+        match unit {
+            TemperatureUnit::Celsius => "",
+            TemperatureUnit::Fahrenheit => FAHRENHEIT_SUFFIX,
+        }
+    }
+
+    /// Get the name written in the header.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Celsius => "Celsius",
+            Self::Fahrenheit => "Fahrenheit",
+            Self::Both => "Celsius and Fahrenheit",
+            Self::BothFahrenheitFirst => "Fahrenheit and Celsius",
+        }
+    }
+}
+
+/// Metadata and units for [`TemperatureLog::to_csv_with`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CsvExportOptions {
+    /// Probe serial number, if known.
+    pub serial_number: Option<u32>,
+    /// Probe firmware version, if known.
+    pub firmware_version: Option<FirmwareVersion>,
+    /// Temperature units to write.
+    pub units: CsvUnits,
+    /// Export time written in the header, or `None` for the time of export.
+    pub exported_at: Option<DateTime<Utc>>,
+    /// Whether to write the header block.
+    pub header: bool,
+}
+
+impl CsvExportOptions {
+    /// Create options writing the header block and Celsius columns.
+    pub fn new() -> Self {
+        Self {
+            serial_number: None,
+            firmware_version: None,
+            units: CsvUnits::Celsius,
+            exported_at: None,
+            header: true,
+        }
+    }
+
+    /// Set the probe serial number.
+    pub fn with_serial_number(mut self, serial_number: u32) -> Self {
+        self.serial_number = Some(serial_number);
+        self
+    }
+
+    /// Set the probe firmware version.
+    pub fn with_firmware_version(mut self, version: FirmwareVersion) -> Self {
+        self.firmware_version = Some(version);
+        self
+    }
+
+    /// Set the temperature units to write.
+    pub fn with_units(mut self, units: CsvUnits) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Set the export time written in the header.
+    pub fn with_exported_at(mut self, exported_at: DateTime<Utc>) -> Self {
+        self.exported_at = Some(exported_at);
+        self
+    }
+
+    /// Leave out the header block, writing the columns only.
+    pub fn without_header(mut self) -> Self {
+        self.header = false;
+        self
+    }
+
+    /// Get the header lines for a log, without trailing newlines.
+    fn header_lines(&self, log: &TemperatureLog) -> Vec<String> {
+        let mut lines = vec!["Combustion probe log".to_string()];
+        if let Some(serial_number) = self.serial_number {
+            lines.push(format!("Probe S/N: {:08X}", serial_number));
+        }
+        if let Some(version) = self.firmware_version {
+            lines.push(format!("Firmware: v{}", version));
+        }
+        lines.push(format!("Session ID: {}", log.session_id));
+        lines.push(format!("Sample period: {} ms", log.sample_period_ms));
+        lines.push(format!("Units: {}", self.units.name()));
+        let exported_at = self.exported_at.unwrap_or_else(Utc::now);
+        lines.push(format!(
+            "Exported: {}",
+            exported_at.to_rfc3339_opts(SecondsFormat::Millis, true)
+        ));
+        lines.push(format!(
+            "Exporter: {} {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        ));
+        lines
+            .into_iter()
+            .map(|line| format!("{} {}", CSV_COMMENT_PREFIX, line))
+            .collect()
+    }
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format temperature cells in the chosen units, empty when missing.
+fn push_temperatures(csv: &mut String, celsius: &[Option<f64>], units: CsvUnits) {
+    for unit in units.order() {
+        for value in celsius {
+            csv.push(',');
+            if let Some(c) = value {
+                csv.push_str(&format!("{:.2}", unit.from_celsius(*c)));
+            }
+        }
+    }
+}
+
+/// Push column names in the chosen units.
+fn push_names<'a>(csv: &mut String, names: impl Iterator<Item = &'a str> + Clone, units: CsvUnits) {
+    for unit in units.order() {
+        for name in names.clone() {
+            csv.push(',');
+            csv.push_str(name);
+            csv.push_str(CsvUnits::suffix(*unit));
+        }
+    }
+}
+
+impl TemperatureLog {
+    /// Export the log to CSV with a metadata header and the chosen units.
+    ///
+    /// The columns are those of [`to_csv`](Self::to_csv), with temperature
+    /// columns repeated per unit.
+    pub fn to_csv_with(&self, options: &CsvExportOptions) -> String {
+        let mut csv = String::new();
+        if options.header {
+            for line in options.header_lines(self) {
+                csv.push_str(&line);
+                csv.push('\n');
+            }
+        }
+
+        let units = options.units;
+        let timestamped = self.data_points.iter().any(|p| p.timestamp.is_some());
+        let has_predictions = self.data_points.iter().any(|p| p.prediction_log.is_some());
+        let annotated = self.annotations.iter().any(|a| a.sequence_number.is_some());
+
+        // Header
+        csv.push_str("Sequence");
+        if timestamped {
+            csv.push_str(",Timestamp");
+        }
+        push_names(
+            &mut csv,
+            PhysicalSensor::ALL.iter().map(|s| s.name()),
+            units,
+        );
+        if has_predictions {
+            let names = VirtualSensor::ALL.map(|sensor| format!("Virtual{}", sensor.name()));
+            push_names(&mut csv, names.iter().map(String::as_str), units);
+            csv.push_str(",PredictionState");
+        }
+        if annotated {
+            csv.push_str(",Annotation");
+        }
+        csv.push('\n');
+
+        // Data rows
+        for point in &self.data_points {
+            csv.push_str(&format!("{}", point.sequence_number));
+            if timestamped {
+                csv.push(',');
+                if let Some(timestamp) = point.timestamp {
+                    csv.push_str(&timestamp.to_rfc3339_opts(SecondsFormat::Millis, true));
+                }
+            }
+
+            let temperatures = point.temperatures.values.map(|t| t.to_celsius());
+            push_temperatures(&mut csv, &temperatures, units);
+
+            if let Some(pred) = &point.prediction_log {
+                let virtuals = [
+                    Some(pred.virtual_core),
+                    Some(pred.virtual_surface),
+                    Some(pred.virtual_ambient),
+                ];
+                push_temperatures(&mut csv, &virtuals, units);
+                csv.push_str(&format!(",{}", pred.prediction_state));
+            } else if has_predictions {
+                push_temperatures(&mut csv, &[None; 3], units);
+                csv.push(',');
+            }
+
+            if annotated {
+                let notes: Vec<&str> = self
+                    .annotations_at(point.sequence_number)
+                    .map(|a| a.text.as_str())
+                    .collect();
+                csv.push(',');
+                if !notes.is_empty() {
+                    csv.push_str(&format!("\"{}\"", notes.join("; ").replace('"', "\"\"")));
+                }
+            }
+
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{LoggedDataPoint, PredictionLog, ProbeTemperatures, RawTemperature};
+
+    fn log() -> TemperatureLog {
+        let mut log = TemperatureLog::new(7, 1000);
+        let mut temperatures =
+            ProbeTemperatures::from_raw([RawTemperature::from_celsius(100.0).raw_value(); 8]);
+        temperatures.values[7] = RawTemperature::INVALID;
+        log.add_data_point(LoggedDataPoint::with_prediction(
+            3,
+            temperatures,
+            PredictionLog {
+                virtual_core: 0.0,
+                virtual_surface: 20.0,
+                virtual_ambient: 100.0,
+                prediction_state: 2,
+                ..Default::default()
+            },
+        ));
+        log
+    }
+
+    #[test]
+    fn test_csv_header_block() {
+        let options = CsvExportOptions::new()
+            .with_serial_number(0x1000_0001)
+            .with_firmware_version(FirmwareVersion::new(1, 4, 0))
+            .with_exported_at(DateTime::from_timestamp(1_700_000_000, 0).unwrap());
+        let csv = log().to_csv_with(&options);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "# Combustion probe log");
+        assert_eq!(lines[1], "# Probe S/N: 10000001");
+        assert_eq!(lines[2], "# Firmware: v1.4.0");
+        assert_eq!(lines[3], "# Session ID: 7");
+        assert_eq!(lines[4], "# Sample period: 1000 ms");
+        assert_eq!(lines[5], "# Units: Celsius");
+        assert_eq!(lines[6], "# Exported: 2023-11-14T22:13:20.000Z");
+        assert!(lines[7].starts_with("# Exporter: combustion-rust-ble "));
+        assert_eq!(lines[8..].join("\n") + "\n", log().to_csv());
+
+        let bare = log().to_csv_with(&CsvExportOptions::new().without_header());
+        assert_eq!(bare, log().to_csv());
+    }
+
+    #[test]
+    fn test_csv_units() {
+        let fahrenheit = log().to_csv_with(
+            &CsvExportOptions::new()
+                .with_units(CsvUnits::Fahrenheit)
+                .without_header(),
+        );
+        let lines: Vec<&str> = fahrenheit.lines().collect();
+        assert!(lines[0].starts_with("Sequence,T1_F,T2_F,"));
+        assert!(lines[0].ends_with(",VirtualAmbient_F,PredictionState"));
+        assert_eq!(
+            lines[1],
+            "3,212.00,212.00,212.00,212.00,212.00,212.00,212.00,,32.00,68.00,212.00,2"
+        );
+
+        let both = log().to_csv_with(&CsvExportOptions::new().with_units(CsvUnits::Both));
+        assert!(both.contains("# Units: Celsius and Fahrenheit\n"));
+        let columns = both.lines().find(|l| l.starts_with("Sequence")).unwrap();
+        assert!(columns.contains(",T8,T1_F,"));
+        assert!(columns.contains(",VirtualAmbient,VirtualCore_F,"));
+        assert_eq!(columns.split(',').count(), 1 + 16 + 6 + 1);
+
+        let fahrenheit_first = log().to_csv_with(
+            &CsvExportOptions::new()
+                .with_units(CsvUnits::for_unit(TemperatureUnit::Fahrenheit, true))
+                .without_header(),
+        );
+        let lines: Vec<&str> = fahrenheit_first.lines().collect();
+        assert!(lines[0].starts_with("Sequence,T1_F,"));
+        assert!(lines[0].contains(",T8_F,T1,"));
+        assert!(lines[1].starts_with("3,212.00,"));
+        assert!(lines[1].contains(",,100.00,"));
+        let imported = TemperatureLog::from_csv(&fahrenheit_first).unwrap();
+        assert_eq!(
+            imported.data_points[0].temperatures,
+            log().data_points[0].temperatures
+        );
+    }
+}
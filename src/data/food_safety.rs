@@ -3,35 +3,69 @@
 //! Contains types for managing USDA food safety compliance monitoring.
 //! Based on the Combustion Probe BLE Specification for Food Safe Data.
 
+use std::time::Duration;
+
+use super::prediction::PredictionInfo;
+use crate::error::{Error, Result};
+use crate::utils::TemperatureFormat;
+
+/// Resolution of the 13-bit temperature and parameter fields.
+const RESOLUTION_13BIT: f64 = 0.05;
+
+/// Largest value a 13-bit field can hold.
+const MAX_13BIT_VALUE: f64 = 0x1FFF as f64 * RESOLUTION_13BIT;
+
+/// Resolution of the 8-bit target log reduction field.
+const RESOLUTION_8BIT: f64 = 0.1;
+
+/// Largest value the 8-bit target log reduction field can hold.
+const MAX_8BIT_VALUE: f64 = 0xFF as f64 * RESOLUTION_8BIT;
+
+/// Largest product code (10 bits).
+const MAX_PRODUCT: u16 = 0x3FF;
+
+/// Longest trajectory simulated when estimating the time to safe.
+const ESTIMATE_HORIZON: Duration = Duration::from_secs(12 * 3600);
+
 /// Food Safe Mode - determines how safety calculations are performed.
 ///
 /// 3-bit enumeration (bits 0-2 of Food Safe Data).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[repr(u8)]
+#[non_exhaustive]
 pub enum FoodSafeMode {
     /// Simplified mode - uses predefined USDA temperature thresholds.
     /// The product type determines the safety rules to follow.
     #[default]
-    Simplified = 0,
+    Simplified,
     /// Integrated mode - uses time-temperature integration with custom parameters.
     /// Log reduction is calculated based on Z-value, D-value, and reference temperature.
-    Integrated = 1,
+    Integrated,
+    /// A mode reserved by the specification (2-7), as received.
+    Unknown(u8),
 }
 
 impl FoodSafeMode {
     /// Create from raw value.
+    ///
+    /// Only the low 3 bits are used: 0 is [`Simplified`](Self::Simplified),
+    /// 1 is [`Integrated`](Self::Integrated), and reserved values map to
+    /// [`Unknown`](Self::Unknown).
     pub fn from_raw(value: u8) -> Self {
         match value & 0x07 {
             0 => Self::Simplified,
             1 => Self::Integrated,
-            _ => Self::Simplified, // Reserved values default to Simplified
+            reserved => Self::Unknown(reserved),
         }
     }
 
     /// Convert to raw value.
     pub fn to_raw(&self) -> u8 {
-        *self as u8
+        match self {
+            Self::Simplified => 0,
+            Self::Integrated => 1,
+            Self::Unknown(value) => *value,
+        }
     }
 }
 
@@ -41,6 +75,7 @@ impl FoodSafeMode {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
+#[non_exhaustive]
 pub enum SimplifiedProduct {
     /// Default product type.
     #[default]
@@ -124,6 +159,7 @@ impl SimplifiedProduct {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
+#[non_exhaustive]
 pub enum IntegratedProduct {
     /// Poultry (default for integrated mode).
     #[default]
@@ -241,28 +277,37 @@ impl IntegratedProduct {
 /// 3-bit enumeration (bits 13-15 of Food Safe Data).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[repr(u8)]
+#[non_exhaustive]
 pub enum Serving {
     /// Food will be served immediately after cooking.
     #[default]
-    ServedImmediately = 0,
+    ServedImmediately,
     /// Food will be cooked and then chilled for later use.
-    CookedAndChilled = 1,
+    CookedAndChilled,
+    /// A serving mode reserved by the specification (2-7), as received.
+    Unknown(u8),
 }
 
 impl Serving {
     /// Create from raw value.
+    ///
+    /// Only the low 3 bits are used; reserved values map to
+    /// [`Unknown`](Self::Unknown).
     pub fn from_raw(value: u8) -> Self {
         match value & 0x07 {
             0 => Self::ServedImmediately,
             1 => Self::CookedAndChilled,
-            _ => Self::ServedImmediately, // Reserved values default
+            reserved => Self::Unknown(reserved),
         }
     }
 
     /// Convert to raw value.
     pub fn to_raw(&self) -> u8 {
-        *self as u8
+        match self {
+            Self::ServedImmediately => 0,
+            Self::CookedAndChilled => 1,
+            Self::Unknown(value) => *value,
+        }
     }
 }
 
@@ -271,31 +316,41 @@ impl Serving {
 /// 3-bit enumeration from Food Safe Status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[repr(u8)]
+#[non_exhaustive]
 pub enum FoodSafeState {
     /// Food has not reached safe serving criteria.
     #[default]
-    NotSafe = 0,
+    NotSafe,
     /// Food is safe to serve according to USDA guidelines.
-    Safe = 1,
+    Safe,
     /// Safety is impossible to achieve (e.g., temperature went below threshold).
-    SafetyImpossible = 2,
+    SafetyImpossible,
+    /// A state reserved by the specification (3-7), as received.
+    Unknown(u8),
 }
 
 impl FoodSafeState {
     /// Create from raw value.
+    ///
+    /// Only the low 3 bits are used; reserved values map to
+    /// [`Unknown`](Self::Unknown).
     pub fn from_raw(value: u8) -> Self {
         match value & 0x07 {
             0 => Self::NotSafe,
             1 => Self::Safe,
             2 => Self::SafetyImpossible,
-            _ => Self::NotSafe, // Reserved values
+            reserved => Self::Unknown(reserved),
         }
     }
 
     /// Convert to raw value.
     pub fn to_raw(&self) -> u8 {
-        *self as u8
+        match self {
+            Self::NotSafe => 0,
+            Self::Safe => 1,
+            Self::SafetyImpossible => 2,
+            Self::Unknown(value) => *value,
+        }
     }
 
     /// Check if food is safe to serve.
@@ -398,6 +453,117 @@ impl FoodSafeConfig {
         }
     }
 
+    /// Create a custom integrated mode configuration, rejecting parameters
+    /// the probe cannot use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFoodSafeConfig`] naming the first offending
+    /// parameter; see [`validate`](Self::validate).
+    pub fn try_custom(
+        threshold_temperature: f64,
+        z_value: f64,
+        reference_temperature: f64,
+        d_value_at_reference: f64,
+        target_log_reduction: f64,
+        serving: Serving,
+    ) -> Result<Self> {
+        let config = Self::custom(
+            threshold_temperature,
+            z_value,
+            reference_temperature,
+            d_value_at_reference,
+            target_log_reduction,
+            serving,
+        );
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that every parameter can be encoded and makes physical sense.
+    ///
+    /// - Temperatures, Z-value, and D-value must fit the 13-bit encoding
+    ///   (0 to 409.55 in 0.05 steps), with Z and D at least one step.
+    /// - Target log reduction must fit the 8-bit encoding (0.1 to 25.5).
+    /// - In integrated mode the threshold must be below the reference
+    ///   temperature.
+    /// - The product code must fit in 10 bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFoodSafeConfig`] naming the first offending
+    /// field.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |field: &str, reason: String| {
+            Err(Error::InvalidFoodSafeConfig {
+                field: field.to_string(),
+                reason,
+            })
+        };
+        let check = |field: &str, value: f64, min: f64, max: f64| {
+            // Written so that NaN fails
+            if value >= min && value <= max {
+                Ok(())
+            } else {
+                invalid(field, format!("{} is outside {} to {}", value, min, max))
+            }
+        };
+
+        if self.product > MAX_PRODUCT {
+            return invalid(
+                "product",
+                format!("{} does not fit in 10 bits", self.product),
+            );
+        }
+        for (field, raw) in [
+            ("mode", self.mode.to_raw()),
+            ("serving", self.serving.to_raw()),
+        ] {
+            if raw > 0x07 {
+                return invalid(field, format!("{} does not fit in 3 bits", raw));
+            }
+        }
+        check(
+            "threshold_temperature",
+            self.threshold_temperature,
+            0.0,
+            MAX_13BIT_VALUE,
+        )?;
+        check("z_value", self.z_value, RESOLUTION_13BIT, MAX_13BIT_VALUE)?;
+        check(
+            "reference_temperature",
+            self.reference_temperature,
+            0.0,
+            MAX_13BIT_VALUE,
+        )?;
+        check(
+            "d_value_at_reference",
+            self.d_value_at_reference,
+            RESOLUTION_13BIT,
+            MAX_13BIT_VALUE,
+        )?;
+        check(
+            "target_log_reduction",
+            self.target_log_reduction,
+            RESOLUTION_8BIT,
+            MAX_8BIT_VALUE,
+        )?;
+
+        if self.mode == FoodSafeMode::Integrated
+            && self.threshold_temperature >= self.reference_temperature
+        {
+            return invalid(
+                "threshold_temperature",
+                format!(
+                    "{} must be below the reference temperature {}",
+                    self.threshold_temperature, self.reference_temperature
+                ),
+            );
+        }
+
+        Ok(())
+    }
+
     /// Encode to 10-byte packed format for BLE transmission.
     ///
     /// Layout (80 bits):
@@ -409,11 +575,16 @@ impl FoodSafeConfig {
     /// - Bits 42-54: Reference Temperature (13 bits, value / 0.05)
     /// - Bits 55-67: D-value at RT (13 bits, value / 0.05)
     /// - Bits 68-75: Target Log Reduction (8 bits, value / 0.1)
+    ///
+    /// Values outside a field's range saturate at the nearest limit rather
+    /// than wrapping. Use [`try_to_bytes`](Self::try_to_bytes) to reject them
+    /// instead.
     pub fn to_bytes(&self) -> [u8; 10] {
         let mut bytes = [0u8; 10];
 
-        // Helper to encode temperature/value as 13-bit with 0.05 resolution
-        let encode_13bit = |value: f64| -> u16 { (value / 0.05).round() as u16 & 0x1FFF };
+        // Helper to encode temperature/value as 13-bit with 0.05 resolution,
+        // saturating at 0 and the 13-bit max
+        let encode_13bit = |value: f64| -> u16 { ((value / 0.05).round() as u16).min(0x1FFF) };
 
         // Helper to encode log reduction as 8-bit with 0.1 resolution
         let encode_8bit = |value: f64| -> u8 { (value / 0.1).round() as u8 };
@@ -448,12 +619,40 @@ impl FoodSafeConfig {
 
         // Bytes 8-9: Target Log Reduction (8 bits starting at bit 68)
         let log_red = encode_8bit(self.target_log_reduction);
-        bytes[8] |= ((log_red & 0x0F) << 4) as u8;
-        bytes[9] = ((log_red >> 4) & 0x0F) as u8;
+        bytes[8] |= (log_red & 0x0F) << 4;
+        bytes[9] = (log_red >> 4) & 0x0F;
 
         bytes
     }
 
+    /// Get the log reduction accumulated per second at a core temperature in
+    /// Celsius.
+    ///
+    /// Zero at or below the threshold temperature; otherwise
+    /// `10^((T - reference) / z) / D`.
+    pub fn log_reduction_rate(&self, celsius: f64) -> f64 {
+        if celsius <= self.threshold_temperature
+            || self.z_value <= 0.0
+            || self.d_value_at_reference <= 0.0
+        {
+            return 0.0;
+        }
+        10f64.powf((celsius - self.reference_temperature) / self.z_value)
+            / self.d_value_at_reference
+    }
+
+    /// Encode to 10-byte packed format, rejecting configurations that fail
+    /// [`validate`](Self::validate).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFoodSafeConfig`] naming the first offending
+    /// field.
+    pub fn try_to_bytes(&self) -> Result<[u8; 10]> {
+        self.validate()?;
+        Ok(self.to_bytes())
+    }
+
     /// Decode from 10-byte packed format.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
         if bytes.len() < 10 {
@@ -494,7 +693,7 @@ impl FoodSafeConfig {
         let d_value_at_reference = decode_13bit(d_raw);
 
         // Bytes 8-9: Target Log Reduction (8 bits starting at bit 68)
-        let log_raw = ((bytes[8] >> 4) as u8) | ((bytes[9] & 0x0F) << 4);
+        let log_raw = (bytes[8] >> 4) | ((bytes[9] & 0x0F) << 4);
         let target_log_reduction = decode_8bit(log_raw);
 
         Some(Self {
@@ -544,13 +743,12 @@ impl FoodSafeStatus {
         let state = FoodSafeState::from_raw(bytes[0] & 0x07);
 
         // Bits 3-10: Log Reduction (8 bits)
-        let log_raw = ((bytes[0] >> 3) as u8) | ((bytes[1] & 0x07) << 5);
+        let log_raw = (bytes[0] >> 3) | ((bytes[1] & 0x07) << 5);
         let log_reduction = log_raw as f64 * 0.1;
 
         // Bits 11-26: Seconds above threshold (16 bits)
-        let seconds_raw = ((bytes[1] >> 3) as u16)
-            | ((bytes[2] as u16) << 5)
-            | ((bytes[3] & 0x07) as u16) << 13;
+        let seconds_raw =
+            ((bytes[1] >> 3) as u16) | ((bytes[2] as u16) << 5) | ((bytes[3] & 0x07) as u16) << 13;
         let seconds_above_threshold = seconds_raw as u32;
 
         // Bits 27-58: Sequence number (32 bits)
@@ -698,7 +896,9 @@ impl FoodSafeProduct {
     /// Convert to SimplifiedProduct for firmware configuration.
     pub fn to_simplified(&self) -> SimplifiedProduct {
         match self {
-            Self::ChickenBreast | Self::ChickenWhole | Self::Turkey => SimplifiedProduct::AnyPoultry,
+            Self::ChickenBreast | Self::ChickenWhole | Self::Turkey => {
+                SimplifiedProduct::AnyPoultry
+            }
             Self::BeefSteak | Self::BeefRoast => SimplifiedProduct::BeefCuts,
             Self::PorkChop | Self::PorkRoast => SimplifiedProduct::PorkCuts,
             Self::GroundBeef | Self::GroundPork => SimplifiedProduct::GroundMeats,
@@ -812,64 +1012,69 @@ impl FoodSafeServingState {
 }
 
 /// Complete food safety data from the probe.
+///
+/// [`config`](Self::config) (what the probe was told to monitor) and
+/// [`status`](Self::status) (what it last reported) are the source of truth;
+/// everything else is derived from them by the accessor methods.
+///
+/// The remaining public fields are deprecated mirrors kept for
+/// compatibility with earlier releases. They are updated alongside
+/// `status` but are not read by any helper, and will be removed in a
+/// future release.
 #[derive(Debug, Clone, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FoodSafeData {
+    /// Configuration sent to the probe.
+    pub config: Option<FoodSafeConfig>,
+
+    /// Latest status reported by the probe.
+    pub status: Option<FoodSafeStatus>,
+
     /// The food product being monitored (legacy).
+    #[deprecated(since = "0.1.0", note = "use `config` instead")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub product: FoodSafeProduct,
 
     /// Current serving state (legacy).
+    #[deprecated(since = "0.1.0", note = "use `state()` instead")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub serving_state: FoodSafeServingState,
 
-    /// Current log reduction achieved.
+    /// Current log reduction achieved (legacy).
+    #[deprecated(since = "0.1.0", note = "use `log_reduction()` instead")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub log_reduction: f64,
 
-    /// Seconds the food has been above the minimum safe temperature.
+    /// Seconds the food has been above the minimum safe temperature (legacy).
+    #[deprecated(since = "0.1.0", note = "use `seconds_above_threshold()` instead")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub seconds_above_threshold: u32,
-
-    /// Configuration sent to the probe.
-    pub config: Option<FoodSafeConfig>,
-
-    /// Current status from the probe.
-    pub status: Option<FoodSafeStatus>,
 }
 
+#[allow(deprecated)]
 impl FoodSafeData {
     /// Create new food safety data for a product (legacy compatibility).
+    #[deprecated(since = "0.1.0", note = "use `with_config` instead")]
     pub fn new(product: FoodSafeProduct) -> Self {
         Self {
             product,
-            serving_state: FoodSafeServingState::NotSafe,
-            log_reduction: 0.0,
-            seconds_above_threshold: 0,
-            config: None,
-            status: None,
+            ..Self::default()
         }
     }
 
     /// Create new food safety data with configuration.
     pub fn with_config(config: FoodSafeConfig) -> Self {
         Self {
-            product: FoodSafeProduct::default(),
-            serving_state: FoodSafeServingState::NotSafe,
-            log_reduction: 0.0,
-            seconds_above_threshold: 0,
             config: Some(config),
-            status: None,
+            ..Self::default()
         }
     }
 
     /// Create food safety data from config and status (for external updates).
     pub fn from_config_and_status(config: FoodSafeConfig, status: FoodSafeStatus) -> Self {
-        let serving_state = FoodSafeServingState::from_state(status.state);
-        Self {
-            product: FoodSafeProduct::default(),
-            serving_state,
-            log_reduction: status.log_reduction,
-            seconds_above_threshold: status.seconds_above_threshold,
-            config: Some(config),
-            status: Some(status),
-        }
+        let mut data = Self::with_config(config);
+        data.update_from_status(status);
+        data
     }
 
     /// Update the configuration (e.g., when changed externally).
@@ -885,40 +1090,165 @@ impl FoodSafeData {
         self.status = Some(status);
     }
 
-    /// Get the progress towards safe serving as a percentage.
-    pub fn progress_percent(&self) -> f64 {
-        let target = self
-            .config
+    /// Get the current food safe state, [`FoodSafeState::NotSafe`] until
+    /// the probe reports a status.
+    pub fn state(&self) -> FoodSafeState {
+        self.status
             .as_ref()
-            .map(|c| c.target_log_reduction)
-            .unwrap_or_else(|| self.product.default_log_reduction());
-        if target <= 0.0 {
-            return 100.0;
-        }
-        (self.log_reduction / target * 100.0).min(100.0)
+            .map(|s| s.state)
+            .unwrap_or(FoodSafeState::NotSafe)
     }
 
     /// Check if the food is safe to serve.
     pub fn is_safe(&self) -> bool {
-        self.serving_state.is_safe()
+        self.state().is_safe()
     }
 
-    /// Get the remaining log reduction needed.
-    pub fn remaining_reduction(&self) -> f64 {
-        let target = self
-            .config
+    /// Get the log reduction achieved, zero until the probe reports a
+    /// status. Always zero in simplified mode.
+    pub fn log_reduction(&self) -> f64 {
+        self.status.as_ref().map_or(0.0, |s| s.log_reduction)
+    }
+
+    /// Get the seconds the core has been above the threshold, zero until
+    /// the probe reports a status.
+    pub fn seconds_above_threshold(&self) -> u32 {
+        self.status
+            .as_ref()
+            .map_or(0, |s| s.seconds_above_threshold)
+    }
+
+    /// Get the log reduction required for safety, from the configuration.
+    ///
+    /// Falls back to the default of the legacy
+    /// [`product`](Self::product) without a configuration.
+    pub fn target_log_reduction(&self) -> f64 {
+        self.config
             .as_ref()
             .map(|c| c.target_log_reduction)
-            .unwrap_or_else(|| self.product.default_log_reduction());
-        (target - self.log_reduction).max(0.0)
+            .unwrap_or_else(|| self.product.default_log_reduction())
     }
 
-    /// Get the current food safe state.
-    pub fn state(&self) -> FoodSafeState {
+    /// Get the progress towards safe serving as a percentage.
+    pub fn progress_percent(&self) -> f64 {
+        let target = self.target_log_reduction();
+        if target <= 0.0 {
+            return 100.0;
+        }
+        (self.log_reduction() / target * 100.0).min(100.0)
+    }
+
+    /// Get the remaining log reduction needed.
+    pub fn remaining_reduction(&self) -> f64 {
+        (self.target_log_reduction() - self.log_reduction()).max(0.0)
+    }
+
+    /// Estimate how long until the food is safe at its current trajectory.
+    ///
+    /// The core is assumed to follow `prediction` while it is predicting,
+    /// rising linearly to the set point in the predicted time and holding
+    /// there; otherwise it holds at `core_celsius`. In simplified mode the
+    /// estimate is the time for the core to reach the threshold; in
+    /// integrated mode, the time to accumulate the remaining log reduction.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Duration::ZERO)` if the food is already safe, or `None` if
+    /// there is no configuration, safety is impossible, or the trajectory
+    /// does not reach safety within 12 hours.
+    pub fn estimated_time_to_safe(
+        &self,
+        core_celsius: f64,
+        prediction: Option<&PredictionInfo>,
+    ) -> Option<Duration> {
+        if self.is_safe() {
+            return Some(Duration::ZERO);
+        }
+        if !self.state().is_achievable() {
+            return None;
+        }
+        let config = self.config.as_ref()?;
+
+        // Core temperature `t` seconds from now
+        let trajectory = |t: f64| match prediction {
+            Some(p) if p.is_active() && p.set_point_temperature > core_celsius => {
+                let seconds = f64::from(p.prediction_value_seconds);
+                if t >= seconds {
+                    p.set_point_temperature
+                } else {
+                    core_celsius + (p.set_point_temperature - core_celsius) * t / seconds
+                }
+            }
+            _ => core_celsius,
+        };
+
+        let horizon = ESTIMATE_HORIZON.as_secs();
+        match config.mode {
+            FoodSafeMode::Simplified => (0..=horizon)
+                .find(|&t| trajectory(t as f64) >= config.threshold_temperature)
+                .map(Duration::from_secs),
+            FoodSafeMode::Integrated => {
+                let remaining = self.remaining_reduction();
+                if remaining <= 0.0 {
+                    return Some(Duration::ZERO);
+                }
+                // Each step adds the reduction over the second ending at `t`
+                let mut accumulated = 0.0;
+                (1..=horizon)
+                    .find(|&t| {
+                        accumulated += config.log_reduction_rate(trajectory(t as f64));
+                        accumulated >= remaining
+                    })
+                    .map(Duration::from_secs)
+            }
+            FoodSafeMode::Unknown(_) => None,
+        }
+    }
+
+    /// Describe the food safe status for display, rendering temperatures
+    /// with `format`.
+    ///
+    /// For example, with a dual Fahrenheit format in integrated mode:
+    /// `Not yet safe: 3.5 of 7.0 log reduction (50%), 2 min above 129.9°F (54.4°C)`.
+    pub fn summary(&self, format: &TemperatureFormat) -> String {
+        let Some(config) = &self.config else {
+            return "Food safe not configured".to_string();
+        };
+        let state = self.state();
+        let label = match state {
+            FoodSafeState::Safe => "Safe to serve".to_string(),
+            FoodSafeState::NotSafe => "Not yet safe".to_string(),
+            FoodSafeState::SafetyImpossible => "Safety impossible".to_string(),
+            FoodSafeState::Unknown(value) => format!("Unknown state {}", value),
+        };
+        let threshold = format.format(config.threshold_temperature);
+
+        match config.mode {
+            FoodSafeMode::Integrated => format!(
+                "{}: {:.1} of {:.1} log reduction ({:.0}%), {} min above {}",
+                label,
+                self.log_reduction(),
+                self.target_log_reduction(),
+                self.progress_percent(),
+                self.seconds_above_threshold() / 60,
+                threshold
+            ),
+            _ if state.is_safe() => format!("{}: core reached {}", label, threshold),
+            _ => format!("{}: core must reach {}", label, threshold),
+        }
+    }
+
+    /// Get the log sequence number at which the probe started food safe
+    /// monitoring, once the probe has reported a status.
+    ///
+    /// Probes that were never configured report an all-zero food safe block,
+    /// which does not pass [`FoodSafeConfig::validate`] and has no start.
+    pub fn start_sequence(&self) -> Option<u32> {
+        let configured = self.config.as_ref().is_some_and(|c| c.validate().is_ok());
         self.status
             .as_ref()
-            .map(|s| s.state)
-            .unwrap_or(FoodSafeState::NotSafe)
+            .filter(|_| configured)
+            .map(|s| s.sequence_number)
     }
 }
 
@@ -930,7 +1260,10 @@ mod tests {
     fn test_food_safe_mode() {
         assert_eq!(FoodSafeMode::from_raw(0), FoodSafeMode::Simplified);
         assert_eq!(FoodSafeMode::from_raw(1), FoodSafeMode::Integrated);
-        assert_eq!(FoodSafeMode::from_raw(7), FoodSafeMode::Simplified); // Reserved
+        assert_eq!(FoodSafeMode::from_raw(7), FoodSafeMode::Unknown(7)); // Reserved
+        assert_eq!(FoodSafeMode::from_raw(0x0A), FoodSafeMode::Unknown(2)); // Only bottom 3 bits matter
+        assert_eq!(FoodSafeMode::Unknown(7).to_raw(), 7);
+        assert_eq!(FoodSafeMode::Unknown(9).to_raw(), 9);
     }
 
     #[test]
@@ -972,7 +1305,8 @@ mod tests {
     fn test_serving() {
         assert_eq!(Serving::from_raw(0), Serving::ServedImmediately);
         assert_eq!(Serving::from_raw(1), Serving::CookedAndChilled);
-        assert_eq!(Serving::from_raw(5), Serving::ServedImmediately); // Reserved
+        assert_eq!(Serving::from_raw(5), Serving::Unknown(5)); // Reserved
+        assert_eq!(Serving::Unknown(5).to_raw(), 5);
     }
 
     #[test]
@@ -980,6 +1314,8 @@ mod tests {
         assert_eq!(FoodSafeState::from_raw(0), FoodSafeState::NotSafe);
         assert_eq!(FoodSafeState::from_raw(1), FoodSafeState::Safe);
         assert_eq!(FoodSafeState::from_raw(2), FoodSafeState::SafetyImpossible);
+        assert_eq!(FoodSafeState::from_raw(6), FoodSafeState::Unknown(6)); // Reserved
+        assert_eq!(FoodSafeState::Unknown(6).to_raw(), 6);
 
         assert!(!FoodSafeState::NotSafe.is_safe());
         assert!(FoodSafeState::Safe.is_safe());
@@ -1017,6 +1353,92 @@ mod tests {
         assert!((parsed.target_log_reduction - config.target_log_reduction).abs() < 0.2);
     }
 
+    #[test]
+    fn test_food_safe_config_validation() {
+        for code in 0..=MAX_PRODUCT {
+            if let Some(product) = IntegratedProduct::from_raw(code) {
+                FoodSafeConfig::integrated(product, Serving::default())
+                    .validate()
+                    .unwrap();
+            }
+            if let Some(product) = SimplifiedProduct::from_raw(code) {
+                FoodSafeConfig::simplified(product, Serving::default())
+                    .validate()
+                    .unwrap();
+            }
+        }
+
+        let field = |result: Result<FoodSafeConfig>| match result {
+            Err(Error::InvalidFoodSafeConfig { field, .. }) => field,
+            other => panic!("expected invalid config, got {:?}", other),
+        };
+        let serving = Serving::default();
+        assert!(FoodSafeConfig::try_custom(54.4, 5.5, 70.0, 1.0, 7.0, serving).is_ok());
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                -1.0, 5.5, 70.0, 1.0, 7.0, serving
+            )),
+            "threshold_temperature"
+        );
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                54.4, 0.0, 70.0, 1.0, 7.0, serving
+            )),
+            "z_value"
+        );
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                54.4, 5.5, 410.0, 1.0, 7.0, serving
+            )),
+            "reference_temperature"
+        );
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                54.4,
+                5.5,
+                70.0,
+                f64::NAN,
+                7.0,
+                serving
+            )),
+            "d_value_at_reference"
+        );
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                54.4, 5.5, 70.0, 1.0, 26.0, serving
+            )),
+            "target_log_reduction"
+        );
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                75.0, 5.5, 70.0, 1.0, 7.0, serving
+            )),
+            "threshold_temperature"
+        );
+        assert_eq!(
+            field(FoodSafeConfig::try_custom(
+                54.4,
+                5.5,
+                70.0,
+                1.0,
+                7.0,
+                Serving::Unknown(9)
+            )),
+            "serving"
+        );
+    }
+
+    #[test]
+    fn test_food_safe_config_saturates() {
+        let config = FoodSafeConfig::custom(500.0, -1.0, 70.0, 1.0, 30.0, Serving::default());
+        let parsed = FoodSafeConfig::from_bytes(&config.to_bytes()).unwrap();
+        assert!((parsed.threshold_temperature - MAX_13BIT_VALUE).abs() < 0.01);
+        assert_eq!(parsed.z_value, 0.0);
+        assert!((parsed.reference_temperature - 70.0).abs() < 0.01);
+        assert!((parsed.target_log_reduction - MAX_8BIT_VALUE).abs() < 0.01);
+        assert!(config.try_to_bytes().is_err());
+    }
+
     #[test]
     fn test_food_safe_config_simplified() {
         let config = FoodSafeConfig::simplified(SimplifiedProduct::AnyPoultry, Serving::ServedImmediately);
@@ -1065,23 +1487,147 @@ mod tests {
         assert_eq!(custom.z_value(), 6.0);
     }
 
+    fn status(log_reduction: f64) -> FoodSafeStatus {
+        FoodSafeStatus {
+            log_reduction,
+            ..FoodSafeStatus::default()
+        }
+    }
+
     #[test]
     fn test_food_safe_data_progress() {
-        let mut data = FoodSafeData::new(FoodSafeProduct::ChickenBreast);
-        data.log_reduction = 3.5;
+        let mut data = FoodSafeData::with_config(FoodSafeConfig::custom(
+            54.4,
+            5.5,
+            70.0,
+            10.0,
+            7.0,
+            Serving::default(),
+        ));
+        data.update_from_status(status(3.5));
         assert!((data.progress_percent() - 50.0).abs() < 0.1);
 
-        data.log_reduction = 7.0;
+        data.update_from_status(status(7.0));
         assert!((data.progress_percent() - 100.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_food_safe_data_summary() {
+        use crate::utils::TemperatureUnit;
+
+        let fahrenheit = TemperatureFormat::new(TemperatureUnit::Fahrenheit).with_dual(true);
+        let mut data = FoodSafeData::with_config(FoodSafeConfig::custom(
+            54.4,
+            5.5,
+            70.0,
+            10.0,
+            7.0,
+            Serving::default(),
+        ));
+        data.update_from_status(FoodSafeStatus {
+            seconds_above_threshold: 150,
+            ..status(3.5)
+        });
+        assert_eq!(
+            data.summary(&fahrenheit),
+            "Not yet safe: 3.5 of 7.0 log reduction (50%), 2 min above 129.9°F (54.4°C)"
+        );
+
+        let mut simplified = FoodSafeData::with_config(FoodSafeConfig::simplified(
+            SimplifiedProduct::AnyPoultry,
+            Serving::default(),
+        ));
+        assert_eq!(
+            simplified.summary(&TemperatureFormat::default()),
+            "Not yet safe: core must reach 74.0°C"
+        );
+        simplified.update_from_status(FoodSafeStatus {
+            state: FoodSafeState::Safe,
+            ..status(0.0)
+        });
+        assert_eq!(
+            simplified.summary(&fahrenheit),
+            "Safe to serve: core reached 165.2°F (74.0°C)"
+        );
+        assert_eq!(
+            FoodSafeData::default().summary(&fahrenheit),
+            "Food safe not configured"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_food_safe_data_legacy_fields_mirror_status() {
+        let mut data = FoodSafeData::new(FoodSafeProduct::ChickenBreast);
+        assert_eq!(data.log_reduction(), 0.0);
+        assert_eq!(data.target_log_reduction(), 7.0);
+
+        data.update_from_status(FoodSafeStatus {
+            state: FoodSafeState::Safe,
+            log_reduction: 7.0,
+            seconds_above_threshold: 30,
+            sequence_number: 4,
+        });
+        assert!(data.is_safe());
+        assert_eq!(data.serving_state, FoodSafeServingState::SafeToServe);
+        assert_eq!(data.log_reduction, data.log_reduction());
+        assert_eq!(data.seconds_above_threshold, 30);
+
+        // Helpers read status, not the mirrors
+        data.log_reduction = 0.0;
+        assert!((data.progress_percent() - 100.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_estimated_time_to_safe() {
+        use crate::data::PredictionState;
+
+        let config = FoodSafeConfig::custom(54.4, 5.5, 70.0, 10.0, 6.0, Serving::default());
+        let mut data = FoodSafeData::with_config(config);
+        data.update_from_status(status(3.0));
+
+        // Holding at the reference temperature: 3 logs at 10 s per log
+        let estimate = data.estimated_time_to_safe(70.0, None).unwrap();
+        assert!((29..=31).contains(&estimate.as_secs()));
+        assert_eq!(data.estimated_time_to_safe(50.0, None), None);
+
+        // Rising to 70°C over 10 minutes reaches safety after arriving
+        let prediction = PredictionInfo {
+            state: PredictionState::Predicting,
+            set_point_temperature: 70.0,
+            prediction_value_seconds: 600,
+            ..PredictionInfo::default()
+        };
+        let estimate = data
+            .estimated_time_to_safe(50.0, Some(&prediction))
+            .unwrap();
+        assert!(estimate > Duration::from_secs(500) && estimate < Duration::from_secs(630));
+
+        let simplified = FoodSafeData::with_config(FoodSafeConfig::simplified(
+            SimplifiedProduct::BeefCuts,
+            Serving::default(),
+        ));
+        // 43°C to 70°C over 10 minutes passes 63°C after 444.4 s
+        assert_eq!(
+            simplified.estimated_time_to_safe(43.0, Some(&prediction)),
+            Some(Duration::from_secs(445))
+        );
+    }
+
     #[test]
     fn test_food_safe_data_remaining() {
-        let mut data = FoodSafeData::new(FoodSafeProduct::BeefSteak);
-        data.log_reduction = 4.0;
+        let mut data = FoodSafeData::with_config(FoodSafeConfig::custom(
+            54.4,
+            5.5,
+            70.0,
+            10.0,
+            6.5,
+            Serving::default(),
+        ));
+        data.update_from_status(status(4.0));
         assert!((data.remaining_reduction() - 2.5).abs() < 0.1);
 
-        data.log_reduction = 10.0;
+        data.update_from_status(status(10.0));
         assert_eq!(data.remaining_reduction(), 0.0);
     }
 }
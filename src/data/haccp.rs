@@ -0,0 +1,290 @@
+//! HACCP record keeping for instant-read measurements.
+//!
+//! When HACCP logging is enabled on a probe, each stabilized instant-read
+//! measurement is captured as a discrete [`HaccpRecord`] in a [`HaccpLog`]
+//! suitable for health-inspection record keeping.
+
+use chrono::{DateTime, Utc};
+
+use super::csv_export::CsvUnits;
+use crate::utils::TemperatureUnit;
+
+/// A single stabilized instant-read measurement.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HaccpRecord {
+    /// Time the reading stabilized.
+    pub timestamp: DateTime<Utc>,
+
+    /// Stabilized temperature in Celsius.
+    pub temperature_celsius: f64,
+
+    /// Serial number of the probe that took the reading (hex formatted).
+    pub probe_serial: String,
+
+    /// Optional user-entered item label (e.g. "Chicken thighs, walk-in #2").
+    pub label: Option<String>,
+}
+
+/// Log of HACCP instant-read records.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HaccpLog {
+    /// Recorded measurements, in capture order.
+    pub records: Vec<HaccpRecord>,
+}
+
+impl HaccpLog {
+    /// Create a new empty HaccpLog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a record to the log.
+    pub fn push(&mut self, record: HaccpRecord) {
+        self.records.push(record);
+    }
+
+    /// Get the number of records in the log.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if the log is empty.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Remove all records from the log.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Export the log to CSV format.
+    ///
+    /// # Returns
+    ///
+    /// A string containing CSV-formatted data with headers. Labels are
+    /// quoted so that commas in user-entered text are preserved.
+    pub fn to_csv(&self) -> String {
+        self.to_csv_with(CsvUnits::Celsius)
+    }
+
+    /// Export the log to CSV with temperatures in the chosen units.
+    ///
+    /// Each unit gets a column, `TemperatureC` or `TemperatureF`, in the
+    /// order of `units`, so [`CsvUnits::BothFahrenheitFirst`] gives US
+    /// kitchens Fahrenheit first with Celsius alongside.
+    pub fn to_csv_with(&self, units: CsvUnits) -> String {
+        let mut csv = String::new();
+
+        csv.push_str("Timestamp,ProbeSerial");
+        for unit in units.order() {
+            csv.push_str(match unit {
+                TemperatureUnit::Celsius => ",TemperatureC",
+                TemperatureUnit::Fahrenheit => ",TemperatureF",
+            });
+        }
+        csv.push_str(",Label\n");
+
+        for record in &self.records {
+            csv.push_str(&format!(
+                "{},{},",
+                record.timestamp.to_rfc3339(),
+                record.probe_serial
+            ));
+            for unit in units.order() {
+                csv.push_str(&format!(
+                    "{:.1},",
+                    unit.from_celsius(record.temperature_celsius)
+                ));
+            }
+            if let Some(label) = &record.label {
+                csv.push('"');
+                csv.push_str(&label.replace('"', "\"\""));
+                csv.push('"');
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Export the log to encrypted CSV.
+    #[cfg(feature = "encryption")]
+    pub fn to_csv_encrypted(
+        &self,
+        key: &crate::encryption::EncryptionKey,
+    ) -> crate::error::Result<Vec<u8>> {
+        key.encrypt(self.to_csv().as_bytes())
+    }
+
+    /// Export the log to pretty-printed JSON.
+    ///
+    /// The output is a flat array of records, which is convenient for
+    /// feeding into report/PDF templating tools.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> crate::error::Result<String> {
+        serde_json::to_string_pretty(&self.records).map_err(|e| crate::error::Error::InvalidData {
+            context: format!("failed to serialize HACCP log: {}", e),
+            payload: None,
+        })
+    }
+}
+
+/// Detects when a stream of instant-read temperatures has stabilized.
+///
+/// A reading is considered stable once `required_samples` consecutive samples
+/// stay within `tolerance_celsius` of each other. After a stable reading has
+/// been reported, the recorder will not report again until the temperature
+/// moves outside the tolerance band (i.e. the probe is moved to a new item)
+/// or [`reset`](Self::reset) is called.
+#[derive(Debug, Clone)]
+pub struct HaccpRecorder {
+    /// Maximum spread (max - min) allowed across the sample window.
+    pub tolerance_celsius: f64,
+
+    /// Number of consecutive samples required for a reading to be stable.
+    pub required_samples: usize,
+
+    window: Vec<f64>,
+    captured: bool,
+}
+
+impl HaccpRecorder {
+    /// Default stability tolerance in Celsius.
+    pub const DEFAULT_TOLERANCE_CELSIUS: f64 = 0.3;
+
+    /// Default number of consecutive samples required.
+    pub const DEFAULT_REQUIRED_SAMPLES: usize = 4;
+
+    /// Create a new recorder with the given stability parameters.
+    pub fn new(tolerance_celsius: f64, required_samples: usize) -> Self {
+        Self {
+            tolerance_celsius,
+            required_samples: required_samples.max(1),
+            window: Vec::new(),
+            captured: false,
+        }
+    }
+
+    /// Feed a new instant-read sample.
+    ///
+    /// # Returns
+    ///
+    /// The stabilized temperature when the window first becomes stable,
+    /// otherwise `None`.
+    pub fn feed(&mut self, temperature_celsius: f64) -> Option<f64> {
+        self.window.push(temperature_celsius);
+        if self.window.len() > self.required_samples {
+            self.window.remove(0);
+        }
+
+        let (min, max) = self
+            .window
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), &t| (lo.min(t), hi.max(t)));
+        let in_band = max - min <= self.tolerance_celsius;
+
+        if !in_band {
+            // Temperature is moving - re-arm for the next measurement
+            self.captured = false;
+            return None;
+        }
+
+        if self.captured || self.window.len() < self.required_samples {
+            return None;
+        }
+
+        self.captured = true;
+        let avg = self.window.iter().sum::<f64>() / self.window.len() as f64;
+        Some((avg * 10.0).round() / 10.0)
+    }
+
+    /// Clear the sample window and re-arm the recorder.
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.captured = false;
+    }
+}
+
+impl Default for HaccpRecorder {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_TOLERANCE_CELSIUS,
+            Self::DEFAULT_REQUIRED_SAMPLES,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_captures_once_when_stable() {
+        let mut recorder = HaccpRecorder::new(0.3, 3);
+
+        assert_eq!(recorder.feed(20.0), None);
+        assert_eq!(recorder.feed(50.0), None);
+        assert_eq!(recorder.feed(74.0), None);
+        assert_eq!(recorder.feed(74.1), None);
+        assert_eq!(recorder.feed(74.2), Some(74.1));
+
+        // Holding steady does not produce duplicate records
+        assert_eq!(recorder.feed(74.1), None);
+        assert_eq!(recorder.feed(74.2), None);
+    }
+
+    #[test]
+    fn test_recorder_rearms_after_movement() {
+        let mut recorder = HaccpRecorder::new(0.3, 2);
+
+        recorder.feed(74.0);
+        assert!(recorder.feed(74.0).is_some());
+
+        // Probe moved to a new item
+        assert_eq!(recorder.feed(40.0), None);
+        assert_eq!(recorder.feed(5.0), None);
+        assert_eq!(recorder.feed(5.1), Some(5.1));
+    }
+
+    #[test]
+    fn test_log_to_csv() {
+        let mut log = HaccpLog::new();
+        log.push(HaccpRecord {
+            timestamp: DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            temperature_celsius: 74.12,
+            probe_serial: "10001234".to_string(),
+            label: Some("Soup, \"batch\" 2".to_string()),
+        });
+        log.push(HaccpRecord {
+            timestamp: DateTime::parse_from_rfc3339("2024-01-01T12:05:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            temperature_celsius: 3.0,
+            probe_serial: "10001234".to_string(),
+            label: None,
+        });
+
+        let csv = log.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Timestamp,ProbeSerial,TemperatureC,Label");
+        assert_eq!(
+            lines[1],
+            "2024-01-01T12:00:00+00:00,10001234,74.1,\"Soup, \"\"batch\"\" 2\""
+        );
+        assert_eq!(lines[2], "2024-01-01T12:05:00+00:00,10001234,3.0,");
+        assert_eq!(log.len(), 2);
+
+        let csv = log.to_csv_with(CsvUnits::BothFahrenheitFirst);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Timestamp,ProbeSerial,TemperatureF,TemperatureC,Label"
+        );
+        assert_eq!(lines[2], "2024-01-01T12:05:00+00:00,10001234,37.4,3.0,");
+    }
+}
@@ -0,0 +1,580 @@
+//! Reading logs back from the library's export formats.
+//!
+//! [`TemperatureLog::from_csv`] reads the output of
+//! [`TemperatureLog::to_csv`], and with the `serde` feature,
+//! `TemperatureLog::from_json` reads a serialized log or the output of
+//! `TemperatureLog::to_ndjson`, so archived sessions can be analysed and
+//! reported on without the probe. Input is
+//! validated rather than trusted: malformed rows, unknown columns,
+//! out-of-range temperatures and repeated sequence numbers are rejected
+//! with the line they were found on.
+
+use chrono::{DateTime, Utc};
+
+use super::annotation::Annotation;
+use super::csv_export::{CSV_COMMENT_PREFIX, FAHRENHEIT_SUFFIX};
+use super::log::{LoggedDataPoint, PredictionLog, TemperatureLog};
+use super::temperatures::{PhysicalSensor, ProbeTemperatures, RawTemperature, VirtualSensor};
+use crate::error::{Error, Result};
+use crate::utils::fahrenheit_to_celsius;
+
+/// Lowest temperature a log can hold, in Celsius.
+const MIN_CELSIUS: f64 = -20.0;
+
+/// Create an [`Error::InvalidData`] for a problem with the input.
+fn invalid_data(context: String) -> Error {
+    Error::InvalidData {
+        context,
+        payload: None,
+    }
+}
+
+/// Create an [`Error::InvalidData`] for a problem on a line of the input.
+fn invalid(line: usize, message: impl std::fmt::Display) -> Error {
+    invalid_data(format!("line {}: {}", line, message))
+}
+
+/// Split a CSV line into fields, unquoting quoted fields.
+fn split_fields(line: &str, line_number: usize) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid(line_number, "unterminated quoted field"));
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Columns of a CSV export.
+struct CsvColumns {
+    /// Index of the timestamp column.
+    timestamp: Option<usize>,
+    /// Index of T1 in the unit being read; the other sensors follow.
+    sensors: usize,
+    /// Index of the virtual core column in the unit being read; surface
+    /// and ambient follow.
+    prediction: Option<usize>,
+    /// Index of the prediction state column.
+    prediction_state: usize,
+    /// Whether the columns being read hold Fahrenheit.
+    fahrenheit: bool,
+    /// Index of the annotation column.
+    annotation: Option<usize>,
+    /// Number of columns.
+    count: usize,
+}
+
+/// Take the column at `index` if it is called `name`.
+fn take(names: &[&str], index: &mut usize, name: &str) -> bool {
+    let found = names.get(*index) == Some(&name);
+    if found {
+        *index += 1;
+    }
+    found
+}
+
+/// Take a group of temperature columns written in Celsius, Fahrenheit or
+/// both, noting any that are missing.
+///
+/// Returns the index of the group to read, preferring Celsius, and whether
+/// it holds Fahrenheit.
+fn take_temperatures(
+    names: &[&str],
+    index: &mut usize,
+    group: &[String],
+    missing: &mut Vec<String>,
+) -> (usize, bool) {
+    let fahrenheit: Vec<String> = group
+        .iter()
+        .map(|name| format!("{}{}", name, FAHRENHEIT_SUFFIX))
+        .collect();
+    let mut take_all = |index: &mut usize, group: &[String]| {
+        for name in group {
+            if !take(names, index, name) {
+                missing.push(name.clone());
+            }
+        }
+    };
+
+    let start = *index;
+    if names.get(start) == Some(&fahrenheit[0].as_str()) {
+        take_all(index, &fahrenheit);
+        let celsius = *index;
+        if names.get(celsius) == Some(&group[0].as_str()) {
+            take_all(index, group);
+            return (celsius, false);
+        }
+        return (start, true);
+    }
+    take_all(index, group);
+    if names.get(*index) == Some(&fahrenheit[0].as_str()) {
+        take_all(index, &fahrenheit);
+    }
+    (start, false)
+}
+
+impl CsvColumns {
+    /// Check the header row on line `line` and locate the optional columns.
+    fn parse(header: &[String], line: usize) -> Result<Self> {
+        let names: Vec<&str> = header.iter().map(|name| name.trim()).collect();
+        if names.first() != Some(&"Sequence") {
+            return Err(invalid(line, "expected a header starting with Sequence"));
+        }
+
+        let mut index = 1;
+        let mut missing = Vec::new();
+
+        let timestamp = take(&names, &mut index, "Timestamp").then_some(1);
+        let sensor_names = PhysicalSensor::ALL.map(|s| s.name().to_string());
+        let (sensors, fahrenheit) =
+            take_temperatures(&names, &mut index, &sensor_names, &mut missing);
+        let virtual_names = VirtualSensor::ALL.map(|s| format!("Virtual{}", s.name()));
+        let has_prediction = missing.is_empty()
+            && [
+                &virtual_names[0],
+                &format!("{}{}", virtual_names[0], FAHRENHEIT_SUFFIX),
+            ]
+            .iter()
+            .any(|name| names.get(index) == Some(&name.as_str()));
+        let mut prediction = None;
+        let mut prediction_state = 0;
+        if has_prediction {
+            let (at, virtual_fahrenheit) =
+                take_temperatures(&names, &mut index, &virtual_names, &mut missing);
+            if virtual_fahrenheit != fahrenheit {
+                return Err(invalid(line, "virtual and sensor columns differ in units"));
+            }
+            prediction = Some(at);
+            prediction_state = index;
+            if !take(&names, &mut index, "PredictionState") {
+                missing.push("PredictionState".to_string());
+            }
+        }
+        let annotation =
+            (missing.is_empty() && names.get(index) == Some(&"Annotation")).then_some(index);
+        if annotation.is_some() {
+            index += 1;
+        }
+
+        if !missing.is_empty() {
+            return Err(invalid(
+                line,
+                format!("missing columns: {}", missing.join(", ")),
+            ));
+        }
+        if let Some(unknown) = names.get(index) {
+            return Err(invalid(line, format!("unknown column '{}'", unknown)));
+        }
+        Ok(Self {
+            timestamp,
+            sensors,
+            prediction,
+            prediction_state,
+            fahrenheit,
+            annotation,
+            count: index,
+        })
+    }
+
+    /// Convert a temperature read from the file to Celsius.
+    fn to_celsius(&self, value: f64) -> f64 {
+        if self.fahrenheit {
+            fahrenheit_to_celsius(value)
+        } else {
+            value
+        }
+    }
+}
+
+/// Session details read from the header block of a CSV export.
+#[derive(Default)]
+struct CsvMetadata {
+    /// Session ID, if given.
+    session_id: Option<u32>,
+    /// Sample period in milliseconds, if given.
+    sample_period_ms: Option<u32>,
+}
+
+impl CsvMetadata {
+    /// Read a header line, with its comment prefix removed. Lines that
+    /// aren't `key: value` pairs and unknown keys are ignored.
+    fn read(&mut self, comment: &str, line: usize) -> Result<()> {
+        let Some((key, value)) = comment.split_once(':') else {
+            return Ok(());
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Session ID" => self.session_id = Some(parse_number(value, "session ID", line)?),
+            "Sample period" => {
+                let ms = value.strip_suffix("ms").unwrap_or(value);
+                self.sample_period_ms = Some(parse_number(ms, "sample period", line)?);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Parse a temperature cell in the file's units, empty for no reading.
+fn parse_temperature(cell: &str, line: usize, columns: &CsvColumns) -> Result<RawTemperature> {
+    let cell = cell.trim();
+    if cell.is_empty() {
+        return Ok(RawTemperature::INVALID);
+    }
+    let value: f64 = cell
+        .parse()
+        .map_err(|_| invalid(line, format!("invalid temperature '{}'", cell)))?;
+    let celsius = columns.to_celsius(value);
+    let max = RawTemperature::new(RawTemperature::MAX_VALUE)
+        .to_celsius()
+        .unwrap_or_default();
+    if !(MIN_CELSIUS..=max).contains(&celsius) {
+        return Err(invalid(
+            line,
+            format!("temperature {} outside {}..={}", celsius, MIN_CELSIUS, max),
+        ));
+    }
+    Ok(RawTemperature::from_celsius(celsius))
+}
+
+/// Parse a required numeric cell.
+fn parse_number<T: std::str::FromStr>(cell: &str, name: &str, line: usize) -> Result<T> {
+    cell.trim()
+        .parse()
+        .map_err(|_| invalid(line, format!("invalid {} '{}'", name, cell.trim())))
+}
+
+impl TemperatureLog {
+    /// Read a log exported with [`to_csv`](Self::to_csv) or
+    /// [`to_csv_with`](Self::to_csv_with).
+    ///
+    /// The session ID and sample period are taken from the header block if
+    /// there is one. Otherwise the session ID is left at 0, and the sample
+    /// period is worked out from the timestamps if there are any and is
+    /// otherwise 0. Fahrenheit columns are read when there are no Celsius
+    /// ones. Notes in the `Annotation` column are split at `"; "`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidData`] naming the offending line if the
+    /// header or a row is malformed, a temperature is out of range, or a
+    /// sequence number repeats.
+    pub fn from_csv(csv: &str) -> Result<Self> {
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty());
+        let mut metadata = CsvMetadata::default();
+        let (header_line, header) = loop {
+            let (line, text) = lines.next().ok_or_else(|| invalid(1, "missing header"))?;
+            match text.trim_start().strip_prefix(CSV_COMMENT_PREFIX) {
+                Some(comment) => metadata.read(comment, line)?,
+                None => break (line, text),
+            }
+        };
+        let columns = CsvColumns::parse(&split_fields(header, header_line)?, header_line)?;
+
+        let mut log = Self::new(metadata.session_id.unwrap_or(0), 0);
+        for (line, text) in lines {
+            if text.trim_start().starts_with(CSV_COMMENT_PREFIX) {
+                continue;
+            }
+            let fields = split_fields(text, line)?;
+            if fields.len() != columns.count {
+                return Err(invalid(
+                    line,
+                    format!("expected {} fields, found {}", columns.count, fields.len()),
+                ));
+            }
+
+            let sequence_number: u32 = parse_number(&fields[0], "sequence number", line)?;
+            let timestamp = match columns.timestamp.map(|i| fields[i].trim()) {
+                Some(cell) if !cell.is_empty() => Some(
+                    DateTime::parse_from_rfc3339(cell)
+                        .map_err(|_| invalid(line, format!("invalid timestamp '{}'", cell)))?
+                        .with_timezone(&Utc),
+                ),
+                _ => None,
+            };
+
+            let mut raw = [RawTemperature::INVALID.raw_value(); 8];
+            for (i, value) in raw.iter_mut().enumerate() {
+                *value =
+                    parse_temperature(&fields[columns.sensors + i], line, &columns)?.raw_value();
+            }
+
+            let prediction_log = match columns.prediction {
+                Some(at)
+                    if fields[at..at + 3]
+                        .iter()
+                        .chain([&fields[columns.prediction_state]])
+                        .any(|c| !c.trim().is_empty()) =>
+                {
+                    let virtual_celsius = |offset: usize, name: &str| {
+                        parse_number(&fields[at + offset], name, line)
+                            .map(|v| columns.to_celsius(v))
+                    };
+                    Some(PredictionLog {
+                        virtual_core: virtual_celsius(0, "virtual core")?,
+                        virtual_surface: virtual_celsius(1, "virtual surface")?,
+                        virtual_ambient: virtual_celsius(2, "virtual ambient")?,
+                        prediction_state: parse_number(
+                            &fields[columns.prediction_state],
+                            "prediction state",
+                            line,
+                        )?,
+                        ..Default::default()
+                    })
+                }
+                _ => None,
+            };
+
+            if log.data_point(sequence_number).is_some() {
+                return Err(invalid(
+                    line,
+                    format!("repeated sequence number {}", sequence_number),
+                ));
+            }
+            log.add_data_point(LoggedDataPoint {
+                sequence_number,
+                temperatures: ProbeTemperatures::from_raw(raw),
+                prediction_log,
+                timestamp,
+            });
+
+            if let Some(cell) = columns.annotation.map(|i| fields[i].trim()) {
+                for text in cell.split("; ").filter(|text| !text.is_empty()) {
+                    let recorded_at = timestamp.unwrap_or_default();
+                    log.add_annotation(
+                        Annotation::new(text, recorded_at).at_sequence(sequence_number),
+                    );
+                }
+            }
+        }
+
+        log.sample_period_ms = metadata
+            .sample_period_ms
+            .unwrap_or_else(|| log.inferred_sample_period_ms());
+        Ok(log)
+    }
+
+    /// Read a log from JSON: either a whole serialized log, or one data
+    /// point per line as written by [`to_ndjson`](Self::to_ndjson).
+    ///
+    /// Data points read line by line get session ID 0 and a sample period
+    /// worked out from their timestamps, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidData`] naming the offending line if the JSON
+    /// is malformed, a temperature is not a valid raw value, or a sequence
+    /// number repeats.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        if let Ok(document) = serde_json::from_str::<Self>(json) {
+            let mut log = Self {
+                data_points: Vec::new(),
+                ..document
+            };
+            for (i, point) in document.data_points.into_iter().enumerate() {
+                log.add_checked(point)
+                    .map_err(|message| invalid_data(format!("data point {}: {}", i, message)))?;
+            }
+            return Ok(log);
+        }
+
+        let mut log = Self::new(0, 0);
+        for (i, line) in json.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let point: LoggedDataPoint =
+                serde_json::from_str(line).map_err(|e| invalid(i + 1, e))?;
+            log.add_checked(point)
+                .map_err(|message| invalid(i + 1, message))?;
+        }
+        log.sample_period_ms = log.inferred_sample_period_ms();
+        Ok(log)
+    }
+
+    /// Add a deserialized data point, or describe why it can't be added.
+    #[cfg(feature = "serde")]
+    fn add_checked(&mut self, point: LoggedDataPoint) -> std::result::Result<(), String> {
+        if let Some(raw) = point
+            .temperatures
+            .values
+            .iter()
+            .find(|t| t.raw_value() > RawTemperature::INVALID.raw_value())
+        {
+            return Err(format!("invalid raw temperature {}", raw.raw_value()));
+        }
+        if self.data_point(point.sequence_number).is_some() {
+            return Err(format!(
+                "repeated sequence number {}",
+                point.sequence_number
+            ));
+        }
+        self.add_data_point(point);
+        Ok(())
+    }
+
+    /// Work out the sample period from the first two timestamped data
+    /// points, or 0 if there aren't two.
+    fn inferred_sample_period_ms(&self) -> u32 {
+        let mut timestamped = self
+            .data_points
+            .iter()
+            .filter_map(|p| p.timestamp.map(|t| (p.sequence_number, t)));
+        let (Some((first_seq, first)), Some((second_seq, second))) =
+            (timestamped.next(), timestamped.next())
+        else {
+            return 0;
+        };
+        let elapsed_ms = (second - first).num_milliseconds();
+        let samples = i64::from(second_seq - first_seq);
+        u32::try_from((elapsed_ms as f64 / samples as f64).round() as i64).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> TemperatureLog {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut log = TemperatureLog::new(0, 5000);
+        for sequence in 0..3u32 {
+            let mut raw = [RawTemperature::from_celsius(20.0 + f64::from(sequence)).raw_value(); 8];
+            raw[7] = RawTemperature::INVALID.raw_value();
+            let mut point = LoggedDataPoint::new(sequence, ProbeTemperatures::from_raw(raw));
+            point.timestamp = Some(start + chrono::Duration::seconds(5 * i64::from(sequence)));
+            if sequence > 0 {
+                point.prediction_log = Some(PredictionLog {
+                    virtual_core: 21.5,
+                    virtual_surface: 22.0,
+                    virtual_ambient: 30.0,
+                    prediction_state: 3,
+                    ..Default::default()
+                });
+            }
+            log.add_data_point(point);
+        }
+        log.add_annotation(Annotation::new("lid \"opened\"", start).at_sequence(1));
+        log
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let original = log();
+        let imported = TemperatureLog::from_csv(&original.to_csv()).unwrap();
+
+        assert_eq!(imported.sample_period_ms, 5000);
+        assert_eq!(imported.data_points.len(), 3);
+        for (imported, original) in imported.data_points.iter().zip(&original.data_points) {
+            assert_eq!(imported.sequence_number, original.sequence_number);
+            assert_eq!(imported.timestamp, original.timestamp);
+            assert_eq!(imported.prediction_log, original.prediction_log);
+            assert_eq!(imported.temperatures.values, original.temperatures.values);
+        }
+        assert_eq!(imported.annotations.len(), 1);
+        assert_eq!(imported.annotations[0].text, "lid \"opened\"");
+        assert_eq!(imported.annotations[0].sequence_number, Some(1));
+
+        // The minimal layout, without timestamps or predictions
+        let mut bare = TemperatureLog::new(0, 0);
+        bare.add_data_point(original.data_points[0].clone());
+        bare.data_points[0].timestamp = None;
+        let imported = TemperatureLog::from_csv(&bare.to_csv()).unwrap();
+        assert_eq!(imported.data_points, bare.data_points);
+    }
+
+    #[test]
+    fn test_csv_round_trip_with_header_and_units() {
+        use crate::data::{CsvExportOptions, CsvUnits};
+
+        let mut original = log();
+        original.session_id = 42;
+        for units in [CsvUnits::Celsius, CsvUnits::Fahrenheit, CsvUnits::Both] {
+            let csv = original.to_csv_with(
+                &CsvExportOptions::new()
+                    .with_serial_number(0x1000_0001)
+                    .with_units(units),
+            );
+            let imported = TemperatureLog::from_csv(&csv).unwrap();
+            assert_eq!(imported.session_id, 42);
+            assert_eq!(imported.sample_period_ms, 5000);
+            for (imported, original) in imported.data_points.iter().zip(&original.data_points) {
+                assert_eq!(imported.temperatures.values, original.temperatures.values);
+                let (Some(imported), Some(original)) =
+                    (&imported.prediction_log, &original.prediction_log)
+                else {
+                    continue;
+                };
+                assert!((imported.virtual_ambient - original.virtual_ambient).abs() < 0.01);
+            }
+        }
+
+        // The header's sample period wins over the timestamps
+        let csv = format!("# Sample period: 250 ms\n{}", original.to_csv());
+        assert_eq!(
+            TemperatureLog::from_csv(&csv).unwrap().sample_period_ms,
+            250
+        );
+        let error = TemperatureLog::from_csv(&format!("# Session ID: x\n{}", original.to_csv()))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("line 1: invalid session ID"));
+    }
+
+    #[test]
+    fn test_csv_validation() {
+        let header = "Sequence,T1,T2,T3,T4,T5,T6,T7,T8";
+        let error = |csv: &str| TemperatureLog::from_csv(csv).unwrap_err().to_string();
+
+        assert!(error("").contains("missing header"));
+        assert!(error("Seq,T1").contains("line 1"));
+        assert!(error("Sequence,T1,T2").contains("missing columns: T3"));
+        assert!(error(&format!("{},Extra", header)).contains("unknown column 'Extra'"));
+        assert!(error(&format!("{}\n1,20", header)).contains("line 2: expected 9 fields"));
+        assert!(error(&format!("{}\nx,1,2,3,4,5,6,7,8", header)).contains("sequence number"));
+        assert!(error(&format!("{}\n1,20,20,20,20,20,20,20,900", header)).contains("outside"));
+        assert!(error(&format!(
+            "{}\n1,20,20,20,20,20,20,20,20\n1,20,20,20,20,20,20,20,20",
+            header
+        ))
+        .contains("line 3: repeated sequence number 1"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let original = log();
+        let document = serde_json::to_string(&original).unwrap();
+        assert_eq!(TemperatureLog::from_json(&document).unwrap(), original);
+
+        let imported = TemperatureLog::from_json(&original.to_ndjson().unwrap()).unwrap();
+        assert_eq!(imported.data_points, original.data_points);
+        assert_eq!(imported.sample_period_ms, 5000);
+
+        let error = TemperatureLog::from_json("{\"sequence_number\": 1}\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("line 1"));
+    }
+}
@@ -2,8 +2,15 @@
 //!
 //! Contains types for storing and managing temperature history from probes.
 
-use super::temperatures::ProbeTemperatures;
+use super::alarms::AlarmSensor;
+use super::annotation::Annotation;
+use super::clock::SequenceClock;
+use super::csv_export::CsvExportOptions;
+use super::temperatures::{
+    PhysicalSensor, ProbeTemperatures, VirtualSensor, VirtualSensorSelection, VirtualTemperatures,
+};
 use chrono::{DateTime, Utc};
+use std::time::Duration;
 
 /// Prediction data logged with a temperature sample.
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -49,6 +56,26 @@ pub struct LoggedDataPoint {
 }
 
 impl LoggedDataPoint {
+    /// Size of a record in a Read Logs response.
+    pub const SIZE: usize = 24;
+
+    /// Get the reading of a sensor in Celsius.
+    ///
+    /// Virtual sensors only have readings in points with prediction data.
+    pub fn reading(&self, sensor: impl Into<AlarmSensor>) -> Option<f64> {
+        match sensor.into() {
+            AlarmSensor::Physical(sensor) => self.temperatures.get(sensor),
+            AlarmSensor::Virtual(sensor) => {
+                let prediction = self.prediction_log.as_ref()?;
+                Some(match sensor {
+                    VirtualSensor::Core => prediction.virtual_core,
+                    VirtualSensor::Surface => prediction.virtual_surface,
+                    VirtualSensor::Ambient => prediction.virtual_ambient,
+                })
+            }
+        }
+    }
+
     /// Create a new LoggedDataPoint with just temperatures.
     pub fn new(sequence_number: u32, temperatures: ProbeTemperatures) -> Self {
         Self {
@@ -59,6 +86,55 @@ impl LoggedDataPoint {
         }
     }
 
+    /// Parse a record from a Read Logs response payload.
+    ///
+    /// The payload is the sequence number (4 bytes), the packed
+    /// temperatures (13 bytes), and the 7-byte packed prediction log:
+    /// - Bits 0-6: Virtual sensor selection
+    /// - Bits 7-10: Prediction State
+    /// - Bits 11-12: Prediction Mode
+    /// - Bits 13-14: Prediction Type
+    /// - Bits 15-24: Set Point Temperature (value * 0.1°C)
+    /// - Bits 25-41: Prediction Value Seconds
+    /// - Bits 42-52: Estimated Core Temperature
+    ///
+    /// The prediction data is left out if a selected virtual sensor has no
+    /// reading. Returns `None` if the payload is too short.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::SIZE {
+            return None;
+        }
+        let sequence_number = u32::from_le_bytes(data[0..4].try_into().ok()?);
+        let temperatures = ProbeTemperatures::from_packed_bytes(&data[4..17])?;
+
+        let mut packed = [0u8; 8];
+        packed[..7].copy_from_slice(&data[17..24]);
+        let packed = u64::from_le_bytes(packed);
+        let bits = |start: u32, len: u32| (packed >> start) & ((1 << len) - 1);
+
+        let selection = VirtualSensorSelection::from_byte(bits(0, 7) as u8);
+        let virtuals = VirtualTemperatures::from_selection(&temperatures, selection);
+        let prediction_log = match (virtuals.core, virtuals.surface, virtuals.ambient) {
+            (Some(core), Some(surface), Some(ambient)) => Some(PredictionLog {
+                virtual_core: core,
+                virtual_surface: surface,
+                virtual_ambient: ambient,
+                prediction_state: bits(7, 4) as u8,
+                prediction_set_point: bits(15, 10) as f64 * 0.1,
+                prediction_type: bits(13, 2) as u8,
+                prediction_value_seconds: bits(25, 17) as u32,
+            }),
+            _ => None,
+        };
+
+        Some(Self {
+            sequence_number,
+            temperatures,
+            prediction_log,
+            timestamp: None,
+        })
+    }
+
     /// Create a new LoggedDataPoint with prediction data.
     pub fn with_prediction(
         sequence_number: u32,
@@ -74,6 +150,75 @@ impl LoggedDataPoint {
     }
 }
 
+/// Summary of one sensor's readings in a [`TemperatureLog`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensorStats {
+    /// Lowest reading in Celsius.
+    pub min: f64,
+    /// Highest reading in Celsius.
+    pub max: f64,
+    /// Mean reading in Celsius.
+    pub mean: f64,
+    /// Number of data points with a reading.
+    pub samples: usize,
+}
+
+impl SensorStats {
+    /// Summarize readings, or `None` if there are none.
+    fn from_readings(readings: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut stats: Option<Self> = None;
+        let mut sum = 0.0;
+        for reading in readings {
+            sum += reading;
+            let stats = stats.get_or_insert(Self {
+                min: reading,
+                max: reading,
+                mean: 0.0,
+                samples: 0,
+            });
+            stats.min = stats.min.min(reading);
+            stats.max = stats.max.max(reading);
+            stats.samples += 1;
+        }
+        stats.map(|stats| Self {
+            mean: sum / stats.samples as f64,
+            ..stats
+        })
+    }
+}
+
+/// Summary statistics of a [`TemperatureLog`], from
+/// [`TemperatureLog::stats`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogStats {
+    /// Statistics of T1-T8, `None` for sensors with no valid reading.
+    pub physical: [Option<SensorStats>; 8],
+    /// Statistics of the virtual core, if the log has prediction data.
+    pub core: Option<SensorStats>,
+    /// Statistics of the virtual surface, if the log has prediction data.
+    pub surface: Option<SensorStats>,
+    /// Statistics of the virtual ambient, if the log has prediction data.
+    pub ambient: Option<SensorStats>,
+    /// Number of data points.
+    pub samples: usize,
+    /// Time covered by the log.
+    pub duration: Duration,
+}
+
+impl LogStats {
+    /// Get the statistics of a sensor.
+    pub fn get(&self, sensor: impl Into<AlarmSensor>) -> Option<&SensorStats> {
+        match sensor.into() {
+            AlarmSensor::Physical(sensor) => self.physical[sensor.index()].as_ref(),
+            AlarmSensor::Virtual(VirtualSensor::Core) => self.core.as_ref(),
+            AlarmSensor::Virtual(VirtualSensor::Surface) => self.surface.as_ref(),
+            AlarmSensor::Virtual(VirtualSensor::Ambient) => self.ambient.as_ref(),
+        }
+    }
+}
+
 /// Temperature log containing a session's data points.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -86,6 +231,10 @@ pub struct TemperatureLog {
 
     /// All logged data points, sorted by sequence number.
     pub data_points: Vec<LoggedDataPoint>,
+
+    /// Notes attached to the session, sorted by time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub annotations: Vec<Annotation>,
 }
 
 impl TemperatureLog {
@@ -95,9 +244,35 @@ impl TemperatureLog {
             session_id,
             sample_period_ms,
             data_points: Vec::new(),
+            annotations: Vec::new(),
         }
     }
 
+    /// Attach a note to the session.
+    ///
+    /// Annotations are kept sorted by time.
+    pub fn add_annotation(&mut self, annotation: Annotation) {
+        let pos = self
+            .annotations
+            .partition_point(|a| a.recorded_at <= annotation.recorded_at);
+        self.annotations.insert(pos, annotation);
+    }
+
+    /// Get the annotations tied to a sequence number.
+    pub fn annotations_at(&self, sequence_number: u32) -> impl Iterator<Item = &Annotation> {
+        self.annotations
+            .iter()
+            .filter(move |a| a.sequence_number == Some(sequence_number))
+    }
+
+    /// Get the data point with a sequence number.
+    pub fn data_point(&self, sequence_number: u32) -> Option<&LoggedDataPoint> {
+        self.data_points
+            .binary_search_by_key(&sequence_number, |p| p.sequence_number)
+            .ok()
+            .map(|i| &self.data_points[i])
+    }
+
     /// Add a data point to the log.
     ///
     /// Points are inserted in sorted order by sequence number.
@@ -186,60 +361,139 @@ impl TemperatureLog {
         missing
     }
 
+    /// Summarize the readings of every sensor.
+    pub fn stats(&self) -> LogStats {
+        let stats = |sensor: AlarmSensor| {
+            SensorStats::from_readings(self.data_points.iter().filter_map(|p| p.reading(sensor)))
+        };
+
+        LogStats {
+            physical: PhysicalSensor::ALL.map(|sensor| stats(sensor.into())),
+            core: stats(VirtualSensor::Core.into()),
+            surface: stats(VirtualSensor::Surface.into()),
+            ambient: stats(VirtualSensor::Ambient.into()),
+            samples: self.data_points.len(),
+            duration: self.duration(),
+        }
+    }
+
+    /// Get the time a sensor spent at or above `threshold` (Celsius).
+    ///
+    /// Each data point at or above the threshold counts for one sample
+    /// period.
+    pub fn time_above(&self, sensor: impl Into<AlarmSensor>, threshold: f64) -> Duration {
+        let sensor = sensor.into();
+        let samples = self
+            .data_points
+            .iter()
+            .filter(|p| p.reading(sensor).is_some_and(|r| r >= threshold))
+            .count();
+        Duration::from_millis(samples as u64 * self.sample_period_ms as u64)
+    }
+
+    /// Get the first data point where a sensor reached `threshold`
+    /// (Celsius).
+    ///
+    /// The point's sequence number and, once timestamped, its timestamp say
+    /// when the threshold was first crossed.
+    pub fn first_crossing(
+        &self,
+        sensor: impl Into<AlarmSensor>,
+        threshold: f64,
+    ) -> Option<&LoggedDataPoint> {
+        let sensor = sensor.into();
+        self.data_points
+            .iter()
+            .find(|p| p.reading(sensor).is_some_and(|r| r >= threshold))
+    }
+
+    /// Set each data point's timestamp from a drift-corrected clock.
+    ///
+    /// Points the clock cannot place keep their existing timestamp.
+    pub fn apply_timestamps(&mut self, clock: &SequenceClock) {
+        for point in &mut self.data_points {
+            if let Some(timestamp) = clock.timestamp(point.sequence_number) {
+                point.timestamp = Some(timestamp);
+            }
+        }
+    }
+
     /// Export the log to CSV format.
     ///
+    /// A `Timestamp` column (RFC 3339, UTC) follows the sequence number when
+    /// any data point has a timestamp, and a final `Annotation` column holds
+    /// the notes tied to each sequence number when there are any.
+    ///
+    /// Temperatures are in Celsius. Use [`to_csv_with`](Self::to_csv_with)
+    /// for a metadata header or other units.
+    ///
     /// # Returns
     ///
     /// A string containing CSV-formatted data with headers.
     pub fn to_csv(&self) -> String {
-        let mut csv = String::new();
+        self.to_csv_with(&CsvExportOptions::new().without_header())
+    }
 
-        // Header
-        csv.push_str("Sequence,T1,T2,T3,T4,T5,T6,T7,T8");
-        if self.data_points.iter().any(|p| p.prediction_log.is_some()) {
-            csv.push_str(",VirtualCore,VirtualSurface,VirtualAmbient,PredictionState");
-        }
-        csv.push('\n');
+    /// Export the log to gzip-compressed CSV.
+    #[cfg(feature = "compression")]
+    pub fn to_csv_gz(&self) -> crate::error::Result<Vec<u8>> {
+        self.to_csv_compressed(crate::compression::Compression::Gzip)
+    }
 
-        // Data rows
-        for point in &self.data_points {
-            csv.push_str(&format!("{}", point.sequence_number));
-
-            for temp in &point.temperatures.values {
-                if let Some(celsius) = temp.to_celsius() {
-                    csv.push_str(&format!(",{:.2}", celsius));
-                } else {
-                    csv.push(',');
-                }
-            }
+    /// Export the log to compressed CSV.
+    #[cfg(feature = "compression")]
+    pub fn to_csv_compressed(
+        &self,
+        compression: crate::compression::Compression,
+    ) -> crate::error::Result<Vec<u8>> {
+        compression.compress(self.to_csv().as_bytes())
+    }
 
-            if let Some(pred) = &point.prediction_log {
-                csv.push_str(&format!(
-                    ",{:.2},{:.2},{:.2},{}",
-                    pred.virtual_core,
-                    pred.virtual_surface,
-                    pred.virtual_ambient,
-                    pred.prediction_state
-                ));
-            }
+    /// Export the log to encrypted CSV.
+    #[cfg(feature = "encryption")]
+    pub fn to_csv_encrypted(
+        &self,
+        key: &crate::encryption::EncryptionKey,
+    ) -> crate::error::Result<Vec<u8>> {
+        key.encrypt(self.to_csv().as_bytes())
+    }
 
-            csv.push('\n');
+    /// Export the log as newline-delimited JSON, one data point per line.
+    #[cfg(feature = "serde")]
+    pub fn to_ndjson(&self) -> crate::error::Result<String> {
+        let mut ndjson = String::new();
+        for point in &self.data_points {
+            let line =
+                serde_json::to_string(point).map_err(|e| crate::error::Error::InvalidData {
+                    context: format!("failed to serialize temperature log: {}", e),
+                    payload: None,
+                })?;
+            ndjson.push_str(&line);
+            ndjson.push('\n');
         }
+        Ok(ndjson)
+    }
 
-        csv
+    /// Export the log as compressed newline-delimited JSON.
+    #[cfg(all(feature = "serde", feature = "compression"))]
+    pub fn to_ndjson_compressed(
+        &self,
+        compression: crate::compression::Compression,
+    ) -> crate::error::Result<Vec<u8>> {
+        compression.compress(self.to_ndjson()?.as_bytes())
     }
 
     /// Calculate the duration of the log based on sequence numbers.
-    pub fn duration(&self) -> std::time::Duration {
+    pub fn duration(&self) -> Duration {
         if self.data_points.is_empty() || self.sample_period_ms == 0 {
-            return std::time::Duration::ZERO;
+            return Duration::ZERO;
         }
 
         let min_seq = self.min_sequence().unwrap_or(0);
         let max_seq = self.max_sequence().unwrap_or(0);
         let samples = max_seq.saturating_sub(min_seq);
 
-        std::time::Duration::from_millis(samples as u64 * self.sample_period_ms as u64)
+        Duration::from_millis(samples as u64 * self.sample_period_ms as u64)
     }
 }
 
@@ -266,6 +520,7 @@ mod tests {
                 RawTemperature::new(base + 60),
                 RawTemperature::new(base + 70),
             ],
+            active_mask: ProbeTemperatures::ALL_SENSORS,
         }
     }
 
@@ -291,6 +546,16 @@ mod tests {
         assert_eq!(log.data_points[2].sequence_number, 15);
     }
 
+    #[test]
+    fn test_temperature_log_data_point() {
+        let mut log = TemperatureLog::new(1, 1000);
+        for seq in [3, 1, 2] {
+            log.add_data_point(LoggedDataPoint::new(seq, ProbeTemperatures::new()));
+        }
+        assert_eq!(log.data_point(2).unwrap().sequence_number, 2);
+        assert!(log.data_point(4).is_none());
+    }
+
     #[test]
     fn test_temperature_log_percent_synced() {
         let mut log = TemperatureLog::new(0, 1000);
@@ -325,6 +590,71 @@ mod tests {
         assert!(csv.contains("0,"));
     }
 
+    #[test]
+    fn test_temperature_log_annotations() {
+        let start: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+        let mut log = TemperatureLog::new(0, 1000);
+        log.add_data_point(LoggedDataPoint::new(0, make_temperatures(1000)));
+        log.add_data_point(LoggedDataPoint::new(1, make_temperatures(1000)));
+        log.add_annotation(
+            Annotation::new("opened \"lid\"", start + chrono::Duration::seconds(1)).at_sequence(1),
+        );
+        log.add_annotation(Annotation::new("wrapped in foil", start).at_sequence(1));
+        assert_eq!(log.annotations[0].text, "wrapped in foil");
+        assert_eq!(log.annotations_at(1).count(), 2);
+
+        let csv = log.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].ends_with(",T8,Annotation"));
+        assert!(lines[1].ends_with(','));
+        assert!(lines[2].ends_with(",\"wrapped in foil; opened \"\"lid\"\"\""));
+    }
+
+    #[cfg(all(feature = "serde", feature = "compression"))]
+    #[test]
+    fn test_temperature_log_compressed_exports() {
+        use crate::compression::Compression;
+
+        let mut log = TemperatureLog::new(0, 1000);
+        for i in 0..100 {
+            log.add_data_point(LoggedDataPoint::new(i, make_temperatures(1000)));
+        }
+
+        let csv = Compression::Gzip
+            .decompress(&log.to_csv_gz().unwrap())
+            .unwrap();
+        assert_eq!(csv, log.to_csv().as_bytes());
+
+        let ndjson = log.to_ndjson().unwrap();
+        assert_eq!(ndjson.lines().count(), 100);
+        let compressed = log.to_ndjson_compressed(Compression::Zstd).unwrap();
+        assert!(compressed.len() < ndjson.len());
+        assert_eq!(
+            Compression::Zstd.decompress(&compressed).unwrap(),
+            ndjson.as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_temperature_log_drift_corrected_timestamps() {
+        let start: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+        let mut clock = SequenceClock::with_nominal_period(1000);
+        clock.anchor(0, start);
+        clock.anchor(100, start + chrono::Duration::milliseconds(101_000));
+
+        let mut log = TemperatureLog::new(1, 1000);
+        log.add_data_point(LoggedDataPoint::new(50, make_temperatures(400)));
+        log.apply_timestamps(&clock);
+        assert_eq!(
+            log.data_points[0].timestamp,
+            Some(start + chrono::Duration::milliseconds(50_500))
+        );
+
+        let csv = log.to_csv();
+        assert!(csv.starts_with("Sequence,Timestamp,T1"));
+        assert!(csv.contains("50,2026-01-01T00:00:50.500Z,"));
+    }
+
     #[test]
     fn test_temperature_log_duration() {
         let mut log = TemperatureLog::new(0, 1000);
@@ -335,4 +665,61 @@ mod tests {
         let duration = log.duration();
         assert_eq!(duration, std::time::Duration::from_secs(60));
     }
+
+    #[test]
+    fn test_logged_data_point_from_bytes() {
+        let temperatures = make_temperatures(1200);
+        let mut data = 42u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&temperatures.to_packed_bytes());
+        // Core T1, Predicting, 63.0°C set point, 300 seconds left
+        let packed: u64 = (3 << 7) | (630 << 15) | (300 << 25);
+        data.extend_from_slice(&packed.to_le_bytes()[..7]);
+
+        let point = LoggedDataPoint::from_bytes(&data).unwrap();
+        assert_eq!(point.sequence_number, 42);
+        assert_eq!(point.temperatures, temperatures);
+        let prediction = point.prediction_log.unwrap();
+        assert!((prediction.virtual_core - 40.0).abs() < 1e-9);
+        assert_eq!(prediction.prediction_state, 3);
+        assert!((prediction.prediction_set_point - 63.0).abs() < 1e-9);
+        assert_eq!(prediction.prediction_value_seconds, 300);
+
+        assert!(LoggedDataPoint::from_bytes(&data[..23]).is_none());
+    }
+
+    #[test]
+    fn test_temperature_log_stats() {
+        let mut log = TemperatureLog::new(0, 1000);
+        log.add_data_point(LoggedDataPoint::new(0, make_temperatures(1000)));
+        log.add_data_point(LoggedDataPoint::new(1, make_temperatures(1200)));
+        log.add_data_point(LoggedDataPoint::with_prediction(
+            2,
+            make_temperatures(1400),
+            PredictionLog {
+                virtual_core: 45.0,
+                ..Default::default()
+            },
+        ));
+
+        let stats = log.stats();
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.duration, Duration::from_secs(2));
+        let t1 = stats.get(PhysicalSensor::T1).unwrap();
+        assert!((t1.min - 30.0).abs() < 1e-9);
+        assert!((t1.max - 50.0).abs() < 1e-9);
+        assert!((t1.mean - 40.0).abs() < 1e-9);
+        assert_eq!(stats.get(VirtualSensor::Core).unwrap().samples, 1);
+
+        assert_eq!(
+            log.time_above(PhysicalSensor::T1, 40.0),
+            Duration::from_secs(2)
+        );
+        assert_eq!(log.time_above(VirtualSensor::Core, 50.0), Duration::ZERO);
+        assert_eq!(
+            log.first_crossing(PhysicalSensor::T1, 40.0)
+                .map(|p| p.sequence_number),
+            Some(1)
+        );
+        assert!(log.first_crossing(PhysicalSensor::T8, 100.0).is_none());
+    }
 }
@@ -0,0 +1,260 @@
+//! Stitching sessions split by a probe restart back into one cook.
+//!
+//! A probe that resets mid-cook, e.g. when its battery is pulled, starts a
+//! new session with a new ID and fresh sequence numbers, so the history of
+//! the cook splits in two. [`MergedCook::from_sessions`] joins consecutive
+//! sessions of a probe that are no further apart than a chosen gap into one
+//! log for reporting and export. Later sessions' sequence numbers are
+//! shifted to follow on from the earlier ones, leaving a run of missing
+//! sequence numbers for the time the probe was down, and each restart is
+//! marked with an annotation.
+
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use super::annotation::Annotation;
+use super::log::TemperatureLog;
+
+/// Text of the annotation marking where a merged cook's probe restarted.
+pub const RESTART_ANNOTATION: &str = "Probe restarted";
+
+/// One of the sessions making up a [`MergedCook`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CookSegment {
+    /// Session ID reported by the probe.
+    pub session_id: u32,
+    /// When the session started.
+    pub started_at: DateTime<Utc>,
+    /// When the session's last sample was taken.
+    pub ended_at: DateTime<Utc>,
+    /// First sequence number of the session in the merged log, if it has
+    /// any samples.
+    pub first_sequence: Option<u32>,
+    /// First sequence number of the session as the probe reported it.
+    pub original_first_sequence: Option<u32>,
+}
+
+impl CookSegment {
+    /// Get the sequence number the probe reported for a sequence number of
+    /// the merged log in this segment.
+    pub fn original_sequence(&self, sequence_number: u32) -> Option<u32> {
+        let offset = sequence_number.checked_sub(self.first_sequence?)?;
+        self.original_first_sequence?.checked_add(offset)
+    }
+}
+
+/// A cook stitched together from consecutive sessions of one probe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MergedCook {
+    /// Combined log, with the first session's ID and sample period.
+    pub log: TemperatureLog,
+    /// Sessions making up the cook, oldest first.
+    pub segments: Vec<CookSegment>,
+}
+
+impl MergedCook {
+    /// Merge sessions, each paired with its start time, into cooks.
+    ///
+    /// Sessions are taken in order of start time. A session joins the cook
+    /// before it if it starts no more than `max_gap` after that cook's last
+    /// sample and has the same sample period; otherwise it starts a new
+    /// cook. Overlapping sessions are never merged.
+    pub fn from_sessions<'a>(
+        sessions: impl IntoIterator<Item = (DateTime<Utc>, &'a TemperatureLog)>,
+        max_gap: Duration,
+    ) -> Vec<MergedCook> {
+        let mut sessions: Vec<_> = sessions.into_iter().collect();
+        sessions.sort_by_key(|(started_at, _)| *started_at);
+
+        let max_gap = chrono::Duration::from_std(max_gap).unwrap_or(chrono::Duration::MAX);
+        let mut cooks: Vec<MergedCook> = Vec::new();
+        for (started_at, log) in sessions {
+            match cooks.last_mut() {
+                Some(cook)
+                    if cook.log.sample_period_ms == log.sample_period_ms
+                        && started_at >= cook.ended_at()
+                        && started_at - cook.ended_at() <= max_gap =>
+                {
+                    cook.append(started_at, log)
+                }
+                _ => cooks.push(Self::new(started_at, log)),
+            }
+        }
+        cooks
+    }
+
+    /// Start a cook from its first session.
+    fn new(started_at: DateTime<Utc>, log: &TemperatureLog) -> Self {
+        let first_sequence = log.min_sequence();
+        Self {
+            log: log.clone(),
+            segments: vec![CookSegment {
+                session_id: log.session_id,
+                started_at,
+                ended_at: end_of(started_at, log),
+                first_sequence,
+                original_first_sequence: first_sequence,
+            }],
+        }
+    }
+
+    /// Append the next session of the cook.
+    fn append(&mut self, started_at: DateTime<Utc>, log: &TemperatureLog) {
+        let previous_end = self.ended_at();
+        let Some(original_first) = log.min_sequence() else {
+            self.segments.push(CookSegment {
+                session_id: log.session_id,
+                started_at,
+                ended_at: started_at,
+                first_sequence: None,
+                original_first_sequence: None,
+            });
+            return;
+        };
+
+        // Leave a sample slot for each sample period the probe was down
+        let period_ms = i64::from(self.log.sample_period_ms.max(1));
+        let missed = ((started_at - previous_end).num_milliseconds() / period_ms).max(1);
+        let first = match self.log.max_sequence() {
+            Some(last) => last.saturating_add(u32::try_from(missed).unwrap_or(u32::MAX)),
+            None => original_first,
+        };
+        let shift = |sequence: u32| first.saturating_add(sequence - original_first);
+
+        for point in &log.data_points {
+            let mut point = point.clone();
+            point.sequence_number = shift(point.sequence_number);
+            self.log.add_data_point(point);
+        }
+        for annotation in &log.annotations {
+            let mut annotation = annotation.clone();
+            annotation.sequence_number = annotation
+                .sequence_number
+                .and_then(|sequence| sequence.checked_sub(original_first))
+                .map(|offset| first.saturating_add(offset));
+            self.log.add_annotation(annotation);
+        }
+        self.log
+            .add_annotation(Annotation::new(RESTART_ANNOTATION, started_at).at_sequence(first));
+
+        self.segments.push(CookSegment {
+            session_id: log.session_id,
+            started_at,
+            ended_at: end_of(started_at, log),
+            first_sequence: Some(first),
+            original_first_sequence: Some(original_first),
+        });
+    }
+
+    /// Get when the cook started.
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.segments[0].started_at
+    }
+
+    /// Get when the cook's last sample was taken.
+    pub fn ended_at(&self) -> DateTime<Utc> {
+        self.segments[self.segments.len() - 1].ended_at
+    }
+
+    /// Get how many times the probe restarted during the cook.
+    pub fn restarts(&self) -> usize {
+        self.segments.len() - 1
+    }
+
+    /// Get the session IDs making up the cook, oldest first.
+    pub fn session_ids(&self) -> Vec<u32> {
+        self.segments.iter().map(|s| s.session_id).collect()
+    }
+}
+
+/// Get when the last sample of a session starting at `started_at` was taken.
+fn end_of(started_at: DateTime<Utc>, log: &TemperatureLog) -> DateTime<Utc> {
+    started_at
+        + chrono::Duration::from_std(log.duration()).unwrap_or_else(|_| chrono::Duration::zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{LoggedDataPoint, ProbeTemperatures};
+
+    fn log(session_id: u32, sequences: std::ops::Range<u32>) -> TemperatureLog {
+        let mut log = TemperatureLog::new(session_id, 1000);
+        for sequence in sequences {
+            log.add_data_point(LoggedDataPoint::new(sequence, ProbeTemperatures::new()));
+        }
+        log
+    }
+
+    #[test]
+    fn test_merge_sessions_within_gap() {
+        let start = Utc::now();
+        let first = log(1, 0..10);
+        let mut second = log(2, 0..5);
+        second.add_annotation(Annotation::new("Wrapped", start).at_sequence(2));
+        // Restarted 30 s after the first session's last sample
+        let restart = start + chrono::Duration::seconds(39);
+
+        let cooks = MergedCook::from_sessions(
+            [(restart, &second), (start, &first)],
+            Duration::from_secs(60),
+        );
+        assert_eq!(cooks.len(), 1);
+        let cook = &cooks[0];
+        assert_eq!(cook.session_ids(), vec![1, 2]);
+        assert_eq!(cook.restarts(), 1);
+        assert_eq!(cook.log.session_id, 1);
+        assert_eq!(cook.log.len(), 15);
+        assert_eq!(cook.log.min_sequence(), Some(0));
+        assert_eq!(cook.log.max_sequence(), Some(43));
+        assert_eq!(
+            cook.log.missing_sequences(0, 43),
+            (10..39).collect::<Vec<_>>()
+        );
+        assert_eq!(cook.ended_at(), restart + chrono::Duration::seconds(4));
+
+        let segment = &cook.segments[1];
+        assert_eq!(segment.first_sequence, Some(39));
+        assert_eq!(segment.original_sequence(41), Some(2));
+        assert_eq!(segment.original_sequence(38), None);
+        assert_eq!(cook.log.annotations_at(41).next().unwrap().text, "Wrapped");
+        assert_eq!(
+            cook.log.annotations_at(39).next().unwrap().text,
+            RESTART_ANNOTATION
+        );
+    }
+
+    #[test]
+    fn test_merge_sessions_keeps_separate_cooks() {
+        let start = Utc::now();
+        let first = log(1, 0..10);
+        let later = log(2, 0..10);
+        let mut faster = log(3, 0..10);
+        faster.sample_period_ms = 500;
+
+        // Too far apart
+        let cooks = MergedCook::from_sessions(
+            [
+                (start, &first),
+                (start + chrono::Duration::hours(2), &later),
+            ],
+            Duration::from_secs(600),
+        );
+        assert_eq!(cooks.len(), 2);
+        assert_eq!(cooks[1].log, later);
+        assert_eq!(cooks[1].restarts(), 0);
+
+        // Different sample period, or overlapping
+        let cooks = MergedCook::from_sessions(
+            [
+                (start, &first),
+                (start + chrono::Duration::seconds(20), &faster),
+                (start + chrono::Duration::seconds(21), &later),
+            ],
+            Duration::from_secs(600),
+        );
+        assert_eq!(cooks.len(), 3);
+    }
+}
@@ -2,25 +2,64 @@
 //!
 //! This module contains all the core data types used to represent
 //! temperature data, predictions, sessions, food safety information,
-//! alarms, and thermometer preferences.
+//! the food-safe product catalog, HACCP records, alarms, annotations, cook
+//! specifications, cooling
+//! guidelines, searing detection, smoothing filters, thermometer
+//! preferences, sequence-to-wall-clock mapping, multi-probe log
+//! alignment, sanity limits for readings, importing exported logs, and
+//! merging sessions split by a probe restart.
 
 pub mod alarms;
+pub mod alignment;
+pub mod annotation;
+pub mod app_export;
+pub mod catalog;
+pub mod clock;
+pub mod cook;
+pub mod cooling;
+pub mod csv_export;
 pub mod food_safety;
+pub mod haccp;
+pub mod import;
 pub mod log;
+pub mod merge;
 pub mod prediction;
 pub mod preferences;
+pub mod sanity;
+pub mod searing;
 pub mod session;
+pub mod smoothing;
+pub mod sync;
 pub mod temperatures;
+pub mod thermal_profile;
 
-pub use alarms::{AlarmConfig, AlarmStatus, ALARM_ARRAY_SIZE, ALARM_COUNT};
+pub use alarms::{AlarmConfig, AlarmSensor, AlarmStatus, ALARM_ARRAY_SIZE, ALARM_COUNT};
+pub use alignment::{AlignedLogs, AlignedRow, AlignmentOptions, Interpolation};
+pub use annotation::Annotation;
+pub use app_export::{AppExportMetadata, APP_CSV_COLUMNS, APP_CSV_VERSION};
+pub use catalog::{CatalogEntry, CatalogProduct, LocalizedProduct, ProductLocalization};
+pub use clock::{ClockAnchor, SequenceClock};
+pub use cook::CookSpec;
+pub use cooling::{CoolingEvent, CoolingMonitor, CoolingPhase, CoolingProfile, CoolingStage};
+pub use csv_export::{CsvExportOptions, CsvUnits};
 pub use food_safety::{
     FoodSafeConfig, FoodSafeData, FoodSafeMode, FoodSafeProduct, FoodSafeServingState,
     FoodSafeState, FoodSafeStatus, IntegratedProduct, Serving, SimplifiedProduct,
 };
-pub use log::{LoggedDataPoint, PredictionLog, TemperatureLog};
-pub use prediction::{PredictionInfo, PredictionMode, PredictionState, PredictionType};
+pub use haccp::{HaccpLog, HaccpRecord, HaccpRecorder};
+pub use log::{LogStats, LoggedDataPoint, PredictionLog, SensorStats, TemperatureLog};
+pub use merge::{CookSegment, MergedCook, RESTART_ANNOTATION};
+pub use prediction::{
+    PredictionInfo, PredictionMode, PredictionState, PredictionType, SetpointReached,
+};
 pub use preferences::{PowerMode, ThermometerPreferences};
+pub use sanity::{Outlier, OutlierAction, OutlierReason, SanityLimits, SanityStats};
+pub use searing::{SearingEvent, SearingMonitor, SearingThresholds};
 pub use session::SessionInfo;
+pub use smoothing::{Smoother, SmoothingFilter};
+pub use sync::{SyncComplete, SyncProgress};
 pub use temperatures::{
-    ProbeTemperatures, RawTemperature, VirtualSensorSelection, VirtualTemperatures,
+    Extremes, PhysicalSensor, ProbeTemperatures, RawTemperature, VirtualExtremes, VirtualSensor,
+    VirtualSensorSelection, VirtualTemperatures,
 };
+pub use thermal_profile::{ProfilePoint, SensorLayout, ThermalProfile};
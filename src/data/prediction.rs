@@ -3,12 +3,21 @@
 //! Contains types for managing the probe's temperature prediction system
 //! which estimates when food will reach target temperatures.
 
+use chrono::{DateTime, Utc};
+
+use crate::error::{Error, Result};
+
+/// Drop in Celsius below the set point after which reaching it again raises
+/// another [`SetpointReached`].
+const SETPOINT_REARM_DROP: f64 = 5.0;
+
 /// The current state of the prediction engine.
 ///
 /// 4-bit enumeration (values 0-15) per the BLE specification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum PredictionState {
     /// Probe is not inserted into food.
     #[default]
@@ -67,7 +76,7 @@ impl PredictionState {
             12 => Self::ReservedState12,
             13 => Self::ReservedState13,
             14 => Self::ReservedState14,
-            15 | _ => Self::Unknown,
+            _ => Self::Unknown,
         }
     }
 
@@ -91,6 +100,7 @@ impl PredictionState {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum PredictionMode {
     /// No prediction active.
     #[default]
@@ -105,6 +115,9 @@ pub enum PredictionMode {
 
 impl PredictionMode {
     /// Create a PredictionMode from a raw byte value.
+    ///
+    /// The field is 2 bits wide; 3 and any larger value map to
+    /// [`Reserved`](Self::Reserved).
     pub fn from_raw(value: u8) -> Self {
         match value {
             0 => Self::None,
@@ -124,6 +137,7 @@ impl PredictionMode {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum PredictionType {
     /// No prediction type.
     #[default]
@@ -138,6 +152,9 @@ pub enum PredictionType {
 
 impl PredictionType {
     /// Create a PredictionType from a raw byte value.
+    ///
+    /// The field is 2 bits wide; 3 and any larger value map to
+    /// [`Reserved`](Self::Reserved).
     pub fn from_raw(value: u8) -> Self {
         match value {
             0 => Self::None,
@@ -183,19 +200,65 @@ pub struct PredictionInfo {
 
     /// Which sensor (0-7) is considered the core sensor.
     pub core_sensor_index: u8,
+
+    /// Expected temperature rise while resting in Celsius, from the active
+    /// cook's [`CookSpec`](crate::CookSpec). Zero without carryover.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub carryover_temperature: f64,
 }
 
 impl PredictionInfo {
+    /// Highest set point the 10-bit prediction field can hold, in Celsius.
+    pub const MAX_SET_POINT: f64 = 0x3FF as f64 * 0.1;
+
     /// Create a new PredictionInfo with default values.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Encode a set point in Celsius to its raw 10-bit value (0.1°C steps).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TemperatureOutOfRange`] if the set point is below
+    /// 0°C or above [`MAX_SET_POINT`](Self::MAX_SET_POINT), or
+    /// [`Error::InvalidParameter`] if it is not a number.
+    pub fn encode_set_point(celsius: f64) -> Result<u16> {
+        if celsius.is_nan() {
+            return Err(Error::InvalidParameter {
+                name: "set_point_celsius".to_string(),
+                value: celsius.to_string(),
+            });
+        }
+        if !(0.0..=Self::MAX_SET_POINT).contains(&celsius) {
+            return Err(Error::TemperatureOutOfRange {
+                name: "set_point".to_string(),
+                celsius,
+                min: 0.0,
+                max: Self::MAX_SET_POINT,
+            });
+        }
+        Ok(((celsius * 10.0).round() as u16).min(0x3FF))
+    }
+
     /// Get the target temperature in Fahrenheit.
     pub fn set_point_fahrenheit(&self) -> f64 {
         crate::utils::celsius_to_fahrenheit(self.set_point_temperature)
     }
 
+    /// Get the core temperature at which to remove the food, in Celsius.
+    ///
+    /// This is the set point the probe is predicting towards.
+    pub fn remove_at_temperature(&self) -> f64 {
+        self.set_point_temperature
+    }
+
+    /// Get the core temperature the food is expected to reach after
+    /// resting, in Celsius.
+    pub fn serve_at_temperature(&self) -> f64 {
+        self.set_point_temperature + self.carryover_temperature
+    }
+
     /// Get the estimated core temperature in Fahrenheit.
     pub fn estimated_core_fahrenheit(&self) -> f64 {
         crate::utils::celsius_to_fahrenheit(self.estimated_core_temperature)
@@ -239,6 +302,77 @@ impl PredictionInfo {
     }
 }
 
+/// The core temperature reached the prediction set point.
+///
+/// Status updates arrive about once a second, so the reading that first
+/// meets the set point usually lands after the actual crossing.
+/// [`interpolated_at`](Self::interpolated_at) estimates the moment the core
+/// passed the set point from the readings either side.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetpointReached {
+    /// Set point in Celsius.
+    pub set_point: f64,
+    /// Estimated time the core crossed the set point.
+    pub interpolated_at: DateTime<Utc>,
+    /// Time of the first reading at or above the set point.
+    pub observed_at: DateTime<Utc>,
+    /// Core temperature of that reading in Celsius.
+    pub core: f64,
+}
+
+/// Detects the core crossing the prediction set point.
+///
+/// Only a rise from below counts as a crossing, so a core already past the
+/// set point when tracking starts or the set point changes raises nothing.
+/// Once reached, the set point is reported again only after the core drops
+/// well below it, e.g. when the probe goes into the next piece of food.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SetpointTracker {
+    /// Set point being tracked, in Celsius.
+    set_point: Option<f64>,
+    /// Previous core reading.
+    previous: Option<(DateTime<Utc>, f64)>,
+    /// Whether the set point has been reached.
+    reached: bool,
+}
+
+impl SetpointTracker {
+    /// Feed the current set point and a core reading taken at `at`.
+    pub(crate) fn update(
+        &mut self,
+        set_point: Option<f64>,
+        core: f64,
+        at: DateTime<Utc>,
+    ) -> Option<SetpointReached> {
+        if set_point != self.set_point {
+            self.set_point = set_point;
+            self.reached = false;
+        }
+        let previous = self.previous.replace((at, core));
+        let set_point = self.set_point?;
+
+        if core < set_point - SETPOINT_REARM_DROP {
+            self.reached = false;
+        }
+        if self.reached || core < set_point {
+            return None;
+        }
+        self.reached = true;
+
+        let (previous_at, previous) = previous.filter(|(_, previous)| *previous < set_point)?;
+        let fraction = (set_point - previous) / (core - previous);
+        let span_ms = (at - previous_at).num_milliseconds() as f64;
+        let offset = chrono::Duration::milliseconds((span_ms * fraction).round() as i64);
+        Some(SetpointReached {
+            set_point,
+            interpolated_at: previous_at + offset,
+            observed_at: at,
+            core,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,6 +432,34 @@ mod tests {
         assert_eq!(info.prediction_time_formatted(), (2, 5));
     }
 
+    #[test]
+    fn test_encode_set_point() {
+        assert_eq!(PredictionInfo::encode_set_point(63.0).unwrap(), 630);
+        assert_eq!(PredictionInfo::encode_set_point(54.45).unwrap(), 545);
+        assert_eq!(PredictionInfo::encode_set_point(102.3).unwrap(), 0x3FF);
+        assert!(PredictionInfo::encode_set_point(102.4).is_err());
+        assert!(PredictionInfo::encode_set_point(-1.0).is_err());
+        assert!(PredictionInfo::encode_set_point(f64::NAN).is_err());
+
+        let error = PredictionInfo::encode_set_point(110.0).unwrap_err();
+        assert!(matches!(error, Error::TemperatureOutOfRange { .. }));
+        assert_eq!(
+            error.to_string(),
+            "Temperature out of range: set_point = 110.0°C (230.0°F), must be 0.0°C to 102.3°C"
+        );
+    }
+
+    #[test]
+    fn test_prediction_info_carryover() {
+        let info = PredictionInfo {
+            set_point_temperature: 60.0,
+            carryover_temperature: 3.0,
+            ..Default::default()
+        };
+        assert_eq!(info.remove_at_temperature(), 60.0);
+        assert_eq!(info.serve_at_temperature(), 63.0);
+    }
+
     #[test]
     fn test_prediction_info_temperature_progress() {
         let info = PredictionInfo {
@@ -331,4 +493,37 @@ mod tests {
         };
         assert_eq!(info.temperature_progress().unwrap(), 100.0);
     }
+
+    #[test]
+    fn test_setpoint_crossing_is_interpolated() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let at = |ms: i64| start + chrono::Duration::milliseconds(ms);
+        let mut tracker = SetpointTracker::default();
+
+        assert_eq!(tracker.update(Some(63.0), 62.0, at(0)), None);
+        let reached = tracker.update(Some(63.0), 66.0, at(1000)).unwrap();
+        assert_eq!(reached.interpolated_at, at(250));
+        assert_eq!(reached.observed_at, at(1000));
+
+        // Reported once until the core drops well below the set point
+        assert_eq!(tracker.update(Some(63.0), 62.5, at(2000)), None);
+        assert_eq!(tracker.update(Some(63.0), 64.0, at(3000)), None);
+        assert_eq!(tracker.update(Some(63.0), 20.0, at(4000)), None);
+        assert_eq!(
+            tracker
+                .update(Some(63.0), 70.0, at(5000))
+                .unwrap()
+                .interpolated_at,
+            at(4860)
+        );
+    }
+
+    #[test]
+    fn test_setpoint_already_passed_is_not_a_crossing() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut tracker = SetpointTracker::default();
+        assert_eq!(tracker.update(None, 70.0, start), None);
+        assert_eq!(tracker.update(Some(63.0), 71.0, start), None);
+        assert_eq!(tracker.update(Some(60.0), 72.0, start), None);
+    }
 }
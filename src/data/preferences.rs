@@ -8,28 +8,37 @@
 /// 2-bit enumeration controlling auto power-off behavior.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[repr(u8)]
+#[non_exhaustive]
 pub enum PowerMode {
     /// Normal mode - probe will auto power-off when placed in charger.
     #[default]
-    Normal = 0,
+    Normal,
     /// Always on mode - probe stays powered even in charger.
-    AlwaysOn = 1,
+    AlwaysOn,
+    /// A mode reserved by the specification (2-3), as received.
+    Unknown(u8),
 }
 
 impl PowerMode {
     /// Create from raw value.
+    ///
+    /// Only the low 2 bits are used; reserved values map to
+    /// [`Unknown`](Self::Unknown).
     pub fn from_raw(value: u8) -> Self {
         match value & 0x03 {
             0 => Self::Normal,
             1 => Self::AlwaysOn,
-            _ => Self::Normal, // Reserved values default to Normal
+            reserved => Self::Unknown(reserved),
         }
     }
 
     /// Convert to raw value.
     pub fn to_raw(&self) -> u8 {
-        *self as u8
+        match self {
+            Self::Normal => 0,
+            Self::AlwaysOn => 1,
+            Self::Unknown(value) => *value,
+        }
     }
 
     /// Check if this is always-on mode.
@@ -42,6 +51,7 @@ impl PowerMode {
         match self {
             Self::Normal => "Normal",
             Self::AlwaysOn => "Always On",
+            Self::Unknown(_) => "Unknown",
         }
     }
 }
@@ -49,7 +59,13 @@ impl PowerMode {
 /// Thermometer preferences.
 ///
 /// This is a 1-byte packed structure containing thermometer settings.
-/// Currently only power mode is defined, with remaining bits reserved.
+/// The specification defines only the power mode; display units and alarm
+/// tones are settings of the Display rather than the probe and are not
+/// carried here.
+///
+/// Bits the specification leaves reserved, and reserved power mode values
+/// (as [`PowerMode::Unknown`]), are retained as received so that preferences
+/// read from a newer firmware encode back to the same byte.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ThermometerPreferences {
@@ -63,6 +79,12 @@ impl ThermometerPreferences {
     /// Size of the preferences structure in bytes.
     pub const SIZE: usize = 1;
 
+    /// Mask of the power mode bits.
+    pub const POWER_MODE_MASK: u8 = 0x03;
+
+    /// Mask of the reserved bits.
+    pub const RESERVED_MASK: u8 = 0xFC;
+
     /// Create new preferences with default values.
     pub fn new() -> Self {
         Self::default()
@@ -83,14 +105,38 @@ impl ThermometerPreferences {
     /// - Bits 2-7: Reserved
     pub fn from_byte(byte: u8) -> Self {
         Self {
-            power_mode: PowerMode::from_raw(byte & 0x03),
+            power_mode: PowerMode::from_raw(byte & Self::POWER_MODE_MASK),
             reserved: (byte >> 2) & 0x3F,
         }
     }
 
     /// Encode to a single byte.
+    ///
+    /// Unknown bits retained by [`from_byte`](Self::from_byte) are written
+    /// back unchanged unless the power mode has been changed since.
     pub fn to_byte(&self) -> u8 {
-        (self.power_mode.to_raw() & 0x03) | ((self.reserved & 0x3F) << 2)
+        (self.power_mode.to_raw() & Self::POWER_MODE_MASK) | ((self.reserved & 0x3F) << 2)
+    }
+
+    /// Change the power mode, keeping the reserved bits.
+    pub fn set_power_mode(&mut self, power_mode: PowerMode) {
+        self.power_mode = power_mode;
+    }
+
+    /// Get the bits this library does not interpret, in their wire positions.
+    ///
+    /// Includes the power mode bits when they hold a reserved value.
+    pub fn unknown_bits(&self) -> u8 {
+        let unknown_power_mode = match self.power_mode {
+            PowerMode::Unknown(value) => value & Self::POWER_MODE_MASK,
+            _ => 0,
+        };
+        unknown_power_mode | ((self.reserved & 0x3F) << 2)
+    }
+
+    /// Check whether any bits this library does not interpret are set.
+    pub fn has_unknown_bits(&self) -> bool {
+        self.unknown_bits() != 0
     }
 
     /// Check if the probe is in always-on mode.
@@ -107,18 +153,25 @@ mod tests {
     fn test_power_mode_from_raw() {
         assert_eq!(PowerMode::from_raw(0), PowerMode::Normal);
         assert_eq!(PowerMode::from_raw(1), PowerMode::AlwaysOn);
-        assert_eq!(PowerMode::from_raw(2), PowerMode::Normal); // Reserved
-        assert_eq!(PowerMode::from_raw(3), PowerMode::Normal); // Reserved
-        assert_eq!(PowerMode::from_raw(0xFF), PowerMode::Normal); // Only bottom 2 bits matter
+        assert_eq!(PowerMode::from_raw(2), PowerMode::Unknown(2)); // Reserved
+        assert_eq!(PowerMode::from_raw(3), PowerMode::Unknown(3)); // Reserved
+        assert_eq!(PowerMode::from_raw(0xFC), PowerMode::Normal); // Only bottom 2 bits matter
     }
 
     #[test]
     fn test_power_mode_roundtrip() {
-        for mode in [PowerMode::Normal, PowerMode::AlwaysOn] {
+        for mode in [
+            PowerMode::Normal,
+            PowerMode::AlwaysOn,
+            PowerMode::Unknown(3),
+        ] {
             let raw = mode.to_raw();
             let parsed = PowerMode::from_raw(raw);
             assert_eq!(mode, parsed);
         }
+
+        // Values that don't fit the field are kept, not aliased
+        assert_eq!(PowerMode::Unknown(5).to_raw(), 5);
     }
 
     #[test]
@@ -145,6 +198,31 @@ mod tests {
         assert_eq!(prefs.power_mode, PowerMode::AlwaysOn); // Bottom 2 bits = 01
     }
 
+    #[test]
+    fn test_thermometer_preferences_retains_unknown_bits() {
+        let prefs = ThermometerPreferences::from_byte(0xA6);
+        assert_eq!(prefs.power_mode, PowerMode::Unknown(2));
+        assert!(prefs.has_unknown_bits());
+        assert_eq!(prefs.unknown_bits(), 0xA6);
+        assert_eq!(prefs.to_byte(), 0xA6);
+
+        let prefs = ThermometerPreferences::from_byte(0x01);
+        assert!(!prefs.has_unknown_bits());
+    }
+
+    #[test]
+    fn test_thermometer_preferences_set_power_mode_keeps_reserved() {
+        let mut prefs = ThermometerPreferences::from_byte(0xA6);
+        prefs.set_power_mode(PowerMode::AlwaysOn);
+        assert_eq!(prefs.to_byte(), 0xA5);
+        assert_eq!(prefs.unknown_bits(), 0xA4);
+
+        // Assigning the field directly also takes effect
+        let mut prefs = ThermometerPreferences::from_byte(0xA6);
+        prefs.power_mode = PowerMode::AlwaysOn;
+        assert_eq!(prefs.to_byte(), 0xA5);
+    }
+
     #[test]
     fn test_power_mode_names() {
         assert_eq!(PowerMode::Normal.name(), "Normal");
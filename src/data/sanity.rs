@@ -0,0 +1,470 @@
+//! Sanity limits for temperature readings.
+//!
+//! A corrupted packet now and then decodes to an impossible reading, such
+//! as a 380°C core, which would trip alarms and end up in exports.
+//! [`SanityLimits`] set with
+//! [`Probe::set_sanity_limits`](crate::Probe::set_sanity_limits) check every
+//! reading against a plausible range per sensor and a maximum change per
+//! second before it reaches the probe's state. Outliers are dropped or
+//! flagged according to the [`OutlierAction`], and counted in
+//! [`SanityStats`].
+
+use chrono::{DateTime, Utc};
+use std::time::Instant;
+use tracing::debug;
+
+use super::alarms::{AlarmSensor, ALARM_COUNT};
+use super::log::{LoggedDataPoint, TemperatureLog};
+use super::temperatures::{
+    PhysicalSensor, ProbeTemperatures, RawTemperature, VirtualSensor, VirtualTemperatures,
+};
+
+/// Consecutive spikes after which a reading is accepted as the new level.
+///
+/// Keeps a genuine jump, e.g. a probe moved into hot oil, from being
+/// rejected indefinitely.
+const MAX_CONSECUTIVE_SPIKES: u8 = 3;
+
+/// What happens to a reading that fails a sanity check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutlierAction {
+    /// Discard the reading, leaving the sensor without one.
+    #[default]
+    Drop,
+    /// Keep the reading, only counting and logging it.
+    Flag,
+}
+
+/// Why a reading failed a sanity check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutlierReason {
+    /// Outside the sensor's plausible range.
+    OutOfRange,
+    /// Changed faster than the maximum step from the last good reading.
+    Spike,
+}
+
+/// A reading that failed a sanity check.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outlier {
+    /// Sensor that reported the reading.
+    pub sensor: AlarmSensor,
+    /// The reading in Celsius.
+    pub celsius: f64,
+    /// Why it failed.
+    pub reason: OutlierReason,
+    /// When it was received.
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Plausible readings for each sensor.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SanityLimits {
+    /// (min, max) Celsius per sensor, indexed by [`AlarmSensor::index`].
+    ranges: [(f64, f64); ALARM_COUNT],
+    /// Largest plausible change in Celsius per second.
+    max_step_per_second: f64,
+    /// What happens to outliers.
+    action: OutlierAction,
+}
+
+impl SanityLimits {
+    /// Create limits with the default ranges, maximum step, and action.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the plausible range of a sensor, in Celsius.
+    pub fn with_range(mut self, sensor: impl Into<AlarmSensor>, min: f64, max: f64) -> Self {
+        self.ranges[sensor.into().index()] = (min, max);
+        self
+    }
+
+    /// Set the largest plausible change in Celsius per second.
+    pub fn with_max_step(mut self, celsius_per_second: f64) -> Self {
+        self.max_step_per_second = celsius_per_second.abs();
+        self
+    }
+
+    /// Set what happens to outliers.
+    pub fn with_action(mut self, action: OutlierAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Get the plausible range of a sensor, in Celsius.
+    pub fn range(&self, sensor: impl Into<AlarmSensor>) -> (f64, f64) {
+        self.ranges[sensor.into().index()]
+    }
+
+    /// Get the largest plausible change in Celsius per second.
+    pub fn max_step(&self) -> f64 {
+        self.max_step_per_second
+    }
+
+    /// Get what happens to outliers.
+    pub fn action(&self) -> OutlierAction {
+        self.action
+    }
+
+    /// Check if a reading is within a sensor's plausible range.
+    pub fn is_plausible(&self, sensor: impl Into<AlarmSensor>, celsius: f64) -> bool {
+        let (min, max) = self.range(sensor);
+        (min..=max).contains(&celsius)
+    }
+}
+
+impl Default for SanityLimits {
+    /// T1-T7, Core, and Surface from -20°C to 150°C, T8 and Ambient from
+    /// -20°C to 320°C, changing by at most 25°C per second, with outliers
+    /// dropped.
+    fn default() -> Self {
+        let mut ranges = [(-20.0, 150.0); ALARM_COUNT];
+        ranges[AlarmSensor::from(PhysicalSensor::T8).index()] = (-20.0, 320.0);
+        ranges[AlarmSensor::from(VirtualSensor::Ambient).index()] = (-20.0, 320.0);
+        Self {
+            ranges,
+            max_step_per_second: 25.0,
+            action: OutlierAction::Drop,
+        }
+    }
+}
+
+/// Counts of readings checked against [`SanityLimits`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SanityStats {
+    /// Readings checked.
+    pub checked: u64,
+    /// Readings outside their sensor's range.
+    pub out_of_range: u64,
+    /// Readings that changed too fast.
+    pub spikes: u64,
+    /// Outliers discarded.
+    pub dropped: u64,
+    /// The most recent outlier.
+    pub last_outlier: Option<Outlier>,
+}
+
+/// Applies [`SanityLimits`] to successive readings of a probe.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SanityFilter {
+    /// Limits being applied, or `None` to pass readings through.
+    limits: Option<SanityLimits>,
+    /// Last good reading per sensor, indexed by [`AlarmSensor::index`].
+    last_good: [Option<(Instant, f64)>; ALARM_COUNT],
+    /// Consecutive spikes per sensor.
+    spikes: [u8; ALARM_COUNT],
+    /// Counts so far.
+    stats: SanityStats,
+}
+
+impl SanityFilter {
+    /// Get the limits being applied.
+    pub(crate) fn limits(&self) -> Option<&SanityLimits> {
+        self.limits.as_ref()
+    }
+
+    /// Set or clear the limits, forgetting earlier readings but keeping
+    /// the counts.
+    pub(crate) fn set_limits(&mut self, limits: Option<SanityLimits>) {
+        self.limits = limits;
+        self.last_good = [None; ALARM_COUNT];
+        self.spikes = [0; ALARM_COUNT];
+    }
+
+    /// Get the counts so far.
+    pub(crate) fn stats(&self) -> &SanityStats {
+        &self.stats
+    }
+
+    /// Check the readings received at `now`, dropping outliers if the
+    /// limits say so.
+    pub(crate) fn apply(
+        &mut self,
+        temperatures: &mut ProbeTemperatures,
+        virtual_temperatures: &mut VirtualTemperatures,
+        now: Instant,
+    ) {
+        if self.limits.is_none() {
+            return;
+        }
+
+        for sensor in PhysicalSensor::ALL {
+            let raw = &mut temperatures.values[sensor.index()];
+            if let Some(celsius) = raw.to_celsius() {
+                if !self.check(sensor.into(), celsius, now) {
+                    *raw = RawTemperature::INVALID;
+                }
+            }
+        }
+        for (sensor, reading) in [
+            (VirtualSensor::Core, &mut virtual_temperatures.core),
+            (VirtualSensor::Surface, &mut virtual_temperatures.surface),
+            (VirtualSensor::Ambient, &mut virtual_temperatures.ambient),
+        ] {
+            if let Some(celsius) = *reading {
+                if !self.check(sensor.into(), celsius, now) {
+                    *reading = None;
+                }
+            }
+        }
+    }
+
+    /// Check logged readings downloaded from the probe, dropping outliers if
+    /// the limits say so.
+    ///
+    /// Records may arrive out of order and long after they were taken, so
+    /// only ranges apply, and earlier live readings are unaffected.
+    pub(crate) fn apply_logged(&mut self, point: &mut LoggedDataPoint) {
+        let Some(limits) = &self.limits else {
+            return;
+        };
+        let stats = &mut self.stats;
+        check_logged(point, |sensor, celsius| {
+            stats.checked += 1;
+            limits.is_plausible(sensor, celsius)
+                || record_outlier(
+                    stats,
+                    limits.action,
+                    sensor,
+                    celsius,
+                    OutlierReason::OutOfRange,
+                )
+        });
+    }
+
+    /// Check one reading, returning whether to keep it.
+    fn check(&mut self, sensor: AlarmSensor, celsius: f64, now: Instant) -> bool {
+        let Some(limits) = &self.limits else {
+            return true;
+        };
+        let index = sensor.index();
+        self.stats.checked += 1;
+
+        let reason = if !limits.is_plausible(sensor, celsius) {
+            Some(OutlierReason::OutOfRange)
+        } else {
+            self.last_good[index].and_then(|(at, last)| {
+                let seconds = now.saturating_duration_since(at).as_secs_f64().max(1.0);
+                let too_fast = (celsius - last).abs() > limits.max_step_per_second * seconds;
+                (too_fast && self.spikes[index] < MAX_CONSECUTIVE_SPIKES)
+                    .then_some(OutlierReason::Spike)
+            })
+        };
+        let Some(reason) = reason else {
+            self.last_good[index] = Some((now, celsius));
+            self.spikes[index] = 0;
+            return true;
+        };
+
+        if reason == OutlierReason::Spike {
+            self.spikes[index] += 1;
+        }
+        record_outlier(&mut self.stats, limits.action, sensor, celsius, reason)
+    }
+}
+
+/// Count an outlier in `stats`, returning whether to keep it.
+fn record_outlier(
+    stats: &mut SanityStats,
+    action: OutlierAction,
+    sensor: AlarmSensor,
+    celsius: f64,
+    reason: OutlierReason,
+) -> bool {
+    match reason {
+        OutlierReason::OutOfRange => stats.out_of_range += 1,
+        OutlierReason::Spike => stats.spikes += 1,
+    }
+    debug!(
+        "{} reading {:.1}°C failed sanity check: {:?}",
+        sensor.name(),
+        celsius,
+        reason
+    );
+    stats.last_outlier = Some(Outlier {
+        sensor,
+        celsius,
+        reason,
+        detected_at: Utc::now(),
+    });
+    if action == OutlierAction::Drop {
+        stats.dropped += 1;
+        return false;
+    }
+    true
+}
+
+/// Check each reading of a logged record with `keep`, dropping those it
+/// rejects.
+///
+/// A record has no way to mark a single virtual reading missing, so a
+/// rejected virtual reading drops the record's prediction data.
+fn check_logged(point: &mut LoggedDataPoint, mut keep: impl FnMut(AlarmSensor, f64) -> bool) {
+    for sensor in PhysicalSensor::ALL {
+        let raw = &mut point.temperatures.values[sensor.index()];
+        if let Some(celsius) = raw.to_celsius() {
+            if !keep(sensor.into(), celsius) {
+                *raw = RawTemperature::INVALID;
+            }
+        }
+    }
+    let mut keep_prediction = true;
+    if let Some(prediction) = &point.prediction_log {
+        for (sensor, celsius) in [
+            (VirtualSensor::Core, prediction.virtual_core),
+            (VirtualSensor::Surface, prediction.virtual_surface),
+            (VirtualSensor::Ambient, prediction.virtual_ambient),
+        ] {
+            keep_prediction &= keep(sensor.into(), celsius);
+        }
+    }
+    if !keep_prediction {
+        point.prediction_log = None;
+    }
+}
+
+impl TemperatureLog {
+    /// Check logged readings against the plausible ranges of `limits`, e.g.
+    /// before exporting a downloaded log, dropping outliers if the limits
+    /// say so.
+    ///
+    /// Only ranges apply; log records are not checked for spikes. A log
+    /// record has no way to mark a single virtual reading missing, so a
+    /// dropped virtual reading drops the record's prediction data.
+    ///
+    /// # Returns
+    ///
+    /// The number of outliers found, whether dropped or flagged.
+    pub fn apply_sanity_limits(&mut self, limits: &SanityLimits) -> usize {
+        let mut outliers = 0;
+        for point in &mut self.data_points {
+            check_logged(point, |sensor, celsius| {
+                if limits.is_plausible(sensor, celsius) {
+                    return true;
+                }
+                outliers += 1;
+                limits.action == OutlierAction::Flag
+            });
+        }
+        outliers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::PredictionLog;
+    use std::time::Duration;
+
+    fn readings(core: f64) -> (ProbeTemperatures, VirtualTemperatures) {
+        let mut temperatures = ProbeTemperatures::new();
+        temperatures.values[0] = RawTemperature::from_celsius(core);
+        (
+            temperatures,
+            VirtualTemperatures::new(Some(core), None, None),
+        )
+    }
+
+    #[test]
+    fn test_out_of_range_readings_are_dropped() {
+        let mut filter = SanityFilter::default();
+        filter.set_limits(Some(SanityLimits::new()));
+
+        let (mut temperatures, mut virtuals) = readings(380.0);
+        filter.apply(&mut temperatures, &mut virtuals, Instant::now());
+        assert_eq!(temperatures.values[0], RawTemperature::INVALID);
+        assert_eq!(virtuals.core, None);
+
+        let stats = filter.stats();
+        assert_eq!(
+            (stats.checked, stats.out_of_range, stats.dropped),
+            (2, 2, 2)
+        );
+        let outlier = stats.last_outlier.as_ref().unwrap();
+        assert_eq!(outlier.sensor, AlarmSensor::Virtual(VirtualSensor::Core));
+        assert_eq!(outlier.reason, OutlierReason::OutOfRange);
+    }
+
+    #[test]
+    fn test_spikes_are_rejected_until_sustained() {
+        let limits = SanityLimits::new().with_range(PhysicalSensor::T1, -20.0, 300.0);
+        let mut filter = SanityFilter::default();
+        filter.set_limits(Some(limits.with_action(OutlierAction::Flag)));
+        let start = Instant::now();
+        let core = |filter: &mut SanityFilter, celsius: f64, seconds: u64| {
+            let (mut temperatures, mut virtuals) = readings(celsius);
+            filter.apply(
+                &mut temperatures,
+                &mut virtuals,
+                start + Duration::from_secs(seconds),
+            );
+            virtuals.core
+        };
+
+        assert_eq!(core(&mut filter, 60.0, 0), Some(60.0));
+        // Flagged but kept
+        assert_eq!(core(&mut filter, 140.0, 1), Some(140.0));
+        assert_eq!(filter.stats().spikes, 2);
+        assert_eq!(filter.stats().dropped, 0);
+
+        filter.set_limits(Some(SanityLimits::new().with_max_step(10.0)));
+        assert_eq!(core(&mut filter, 60.0, 2), Some(60.0));
+        assert_eq!(core(&mut filter, 140.0, 3), None);
+        assert_eq!(core(&mut filter, 80.0, 4), Some(80.0));
+        // A sustained jump is accepted after a few readings
+        for seconds in 5..8 {
+            assert_eq!(core(&mut filter, 145.0, seconds), None);
+        }
+        assert_eq!(core(&mut filter, 145.0, 8), Some(145.0));
+    }
+
+    #[test]
+    fn test_log_readings_out_of_range_are_invalidated() {
+        let mut log = TemperatureLog::new(1, 1000);
+        for (sequence, celsius) in [(0, 60.0), (1, 380.0)] {
+            let (temperatures, _) = readings(celsius);
+            log.add_data_point(LoggedDataPoint::new(sequence, temperatures));
+        }
+        assert_eq!(log.apply_sanity_limits(&SanityLimits::new()), 1);
+        assert_eq!(
+            log.data_points[1].temperatures.values[0],
+            RawTemperature::INVALID
+        );
+        assert!(log.data_points[0].temperatures.values[0].is_valid());
+    }
+
+    #[test]
+    fn test_log_virtual_readings_are_checked() {
+        let mut log = TemperatureLog::new(1, 1000);
+        for (sequence, core) in [(0, 60.0), (1, 380.0)] {
+            let (temperatures, _) = readings(60.0);
+            let prediction = PredictionLog {
+                virtual_core: core,
+                virtual_surface: 60.0,
+                virtual_ambient: 100.0,
+                ..Default::default()
+            };
+            log.add_data_point(LoggedDataPoint::with_prediction(
+                sequence,
+                temperatures,
+                prediction,
+            ));
+        }
+
+        // Flagged outliers are counted but kept
+        let flag = SanityLimits::new().with_action(OutlierAction::Flag);
+        assert_eq!(log.apply_sanity_limits(&flag), 1);
+        assert_eq!(log.data_points[1].reading(VirtualSensor::Core), Some(380.0));
+
+        assert_eq!(log.apply_sanity_limits(&SanityLimits::new()), 1);
+        assert!(log.data_points[0].prediction_log.is_some());
+        assert!(log.data_points[1].prediction_log.is_none());
+        assert!(log.data_points[1].temperatures.values[0].is_valid());
+    }
+}
@@ -0,0 +1,254 @@
+//! Searing detection from the surface temperature.
+//!
+//! Searing or broiling drives the surface temperature up far faster than
+//! any other part of a cook. A [`SearingMonitor`] watches the virtual
+//! surface temperature for a rise steeper than
+//! [`SearingThresholds::min_slope`] that takes it past
+//! [`SearingThresholds::start_temperature`], and raises
+//! [`SearingEvent::Started`]. Searing ends when the surface falls below
+//! [`SearingThresholds::end_temperature`] or drops as steeply as it rose,
+//! e.g. when the food is flipped or pulled.
+
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// When a surface temperature counts as searing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearingThresholds {
+    /// Rise in Celsius per minute that starts searing, and fall that ends it.
+    pub min_slope: f64,
+    /// Surface temperature in Celsius searing must reach to start.
+    pub start_temperature: f64,
+    /// Surface temperature in Celsius below which searing ends.
+    pub end_temperature: f64,
+    /// Span of readings the slope is measured over.
+    pub window: Duration,
+}
+
+impl SearingThresholds {
+    /// Create thresholds with the default temperatures and window.
+    pub fn new(min_slope: f64) -> Self {
+        Self {
+            min_slope,
+            ..Self::default()
+        }
+    }
+
+    /// Set the surface temperatures that start and end searing, in Celsius.
+    pub fn with_temperatures(mut self, start: f64, end: f64) -> Self {
+        self.start_temperature = start;
+        self.end_temperature = end;
+        self
+    }
+
+    /// Set the span of readings the slope is measured over.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+impl Default for SearingThresholds {
+    fn default() -> Self {
+        Self {
+            min_slope: 20.0,
+            start_temperature: 70.0,
+            end_temperature: 60.0,
+            window: Duration::from_secs(20),
+        }
+    }
+}
+
+/// Searing transitions reported by a [`SearingMonitor`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SearingEvent {
+    /// The surface started rising like a sear.
+    Started {
+        /// When searing started.
+        at: DateTime<Utc>,
+        /// Surface temperature in Celsius.
+        surface: f64,
+        /// Rise in Celsius per minute.
+        slope: f64,
+    },
+    /// The surface stopped searing.
+    Ended {
+        /// When searing ended.
+        at: DateTime<Utc>,
+        /// Time spent searing.
+        duration: Duration,
+        /// Highest surface temperature while searing, in Celsius.
+        peak_surface: f64,
+    },
+}
+
+/// Tracks surface temperatures against [`SearingThresholds`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearingMonitor {
+    /// Thresholds being applied.
+    thresholds: SearingThresholds,
+    /// Readings within the slope window, oldest first.
+    readings: VecDeque<(DateTime<Utc>, f64)>,
+    /// Start time and peak surface temperature of the current sear.
+    searing: Option<(DateTime<Utc>, f64)>,
+}
+
+impl SearingMonitor {
+    /// Create a monitor applying `thresholds`.
+    pub fn new(thresholds: SearingThresholds) -> Self {
+        Self {
+            thresholds,
+            readings: VecDeque::new(),
+            searing: None,
+        }
+    }
+
+    /// Get the thresholds being applied.
+    pub fn thresholds(&self) -> &SearingThresholds {
+        &self.thresholds
+    }
+
+    /// Check if the surface is currently searing.
+    pub fn is_searing(&self) -> bool {
+        self.searing.is_some()
+    }
+
+    /// Get when the current sear started, if searing.
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.searing.map(|(started, _)| started)
+    }
+
+    /// Get the slope of the readings in the window, in Celsius per minute.
+    ///
+    /// `None` until the readings span at least half the window.
+    pub fn slope(&self) -> Option<f64> {
+        let (first_at, first) = *self.readings.front()?;
+        let (last_at, last) = *self.readings.back()?;
+        let span = (last_at - first_at).to_std().ok()?;
+        if span.is_zero() || span < self.thresholds.window / 2 {
+            return None;
+        }
+        Some((last - first) / (span.as_secs_f64() / 60.0))
+    }
+
+    /// Feed a surface temperature (Celsius) taken at `at`.
+    pub fn update(&mut self, surface: f64, at: DateTime<Utc>) -> Option<SearingEvent> {
+        self.readings.push_back((at, surface));
+        while let Some(&(oldest, _)) = self.readings.front() {
+            let age = (at - oldest).to_std().unwrap_or_default();
+            if age <= self.thresholds.window {
+                break;
+            }
+            self.readings.pop_front();
+        }
+        let slope = self.slope();
+
+        match &mut self.searing {
+            None => {
+                let slope = slope?;
+                if slope < self.thresholds.min_slope || surface < self.thresholds.start_temperature
+                {
+                    return None;
+                }
+                self.searing = Some((at, surface));
+                Some(SearingEvent::Started { at, surface, slope })
+            }
+            Some((started, peak)) => {
+                *peak = peak.max(surface);
+                let falling = slope.is_some_and(|s| s <= -self.thresholds.min_slope);
+                if surface >= self.thresholds.end_temperature && !falling {
+                    return None;
+                }
+                let event = SearingEvent::Ended {
+                    at,
+                    duration: (at - *started).to_std().unwrap_or_default(),
+                    peak_surface: *peak,
+                };
+                self.searing = None;
+                // Start the next slope from the current reading
+                self.readings.clear();
+                self.readings.push_back((at, surface));
+                Some(event)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(
+        monitor: &mut SearingMonitor,
+        start: DateTime<Utc>,
+        readings: &[f64],
+    ) -> Vec<SearingEvent> {
+        readings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, surface)| {
+                monitor.update(*surface, start + chrono::Duration::seconds(5 * i as i64))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_slow_rise_is_not_searing() {
+        let mut monitor = SearingMonitor::new(SearingThresholds::default());
+        // 1.2 °C per minute, as in a low oven
+        let readings: Vec<f64> = (0..40).map(|i| 60.0 + 0.1 * i as f64).collect();
+        assert!(feed(&mut monitor, Utc::now(), &readings).is_empty());
+        assert!(!monitor.is_searing());
+    }
+
+    #[test]
+    fn test_sear_starts_and_ends_on_flip() {
+        let mut monitor = SearingMonitor::new(SearingThresholds::default());
+        let start = Utc::now();
+
+        // Rising 60 °C per minute into a hot pan
+        let events = feed(&mut monitor, start, &[50.0, 55.0, 60.0, 65.0, 70.0, 75.0]);
+        assert_eq!(events.len(), 1);
+        let SearingEvent::Started { surface, slope, .. } = events[0] else {
+            panic!("expected searing to start");
+        };
+        assert_eq!(surface, 70.0);
+        assert!((slope - 60.0).abs() < 1e-9);
+        assert!(monitor.is_searing());
+
+        // Flipped: the surface falls away from the pan
+        let events = feed(
+            &mut monitor,
+            start + chrono::Duration::seconds(30),
+            &[80.0, 74.0, 68.0, 62.0, 56.0],
+        );
+        assert_eq!(events.len(), 1);
+        let SearingEvent::Ended { peak_surface, .. } = events[0] else {
+            panic!("expected searing to end");
+        };
+        assert_eq!(peak_surface, 80.0);
+        assert!(!monitor.is_searing());
+    }
+
+    #[test]
+    fn test_sear_ends_below_end_temperature() {
+        let thresholds = SearingThresholds::new(10.0).with_temperatures(70.0, 65.0);
+        let mut monitor = SearingMonitor::new(thresholds);
+        let start = Utc::now();
+
+        feed(&mut monitor, start, &[60.0, 64.0, 68.0, 72.0, 76.0]);
+        assert!(monitor.is_searing());
+
+        // Cooling slowly, but below the end temperature
+        let events = feed(
+            &mut monitor,
+            start + chrono::Duration::seconds(25),
+            &[66.0, 65.5, 64.9],
+        );
+        assert!(matches!(events[..], [SearingEvent::Ended { .. }]));
+    }
+}
@@ -27,6 +27,26 @@ impl SessionInfo {
         }
     }
 
+    /// Parse the payload of a Read Session Information response: the
+    /// session ID (u32) and sample period in milliseconds (u16),
+    /// little-endian.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let session_id = u32::from_le_bytes(bytes.get(0..4)?.try_into().ok()?);
+        let sample_period_ms = u16::from_le_bytes(bytes.get(4..6)?.try_into().ok()?);
+        Some(Self::new(session_id, sample_period_ms.into()))
+    }
+
+    /// Encode as a Read Session Information response payload.
+    ///
+    /// Sample periods beyond the 16-bit field saturate.
+    pub fn to_bytes(&self) -> [u8; 6] {
+        let mut bytes = [0; 6];
+        bytes[0..4].copy_from_slice(&self.session_id.to_le_bytes());
+        let period = u16::try_from(self.sample_period_ms).unwrap_or(u16::MAX);
+        bytes[4..6].copy_from_slice(&period.to_le_bytes());
+        bytes
+    }
+
     /// Get the sample period as a duration.
     pub fn sample_period(&self) -> std::time::Duration {
         std::time::Duration::from_millis(self.sample_period_ms as u64)
@@ -47,6 +67,13 @@ mod tests {
     use super::*;
     use std::time::Duration;
 
+    #[test]
+    fn test_session_info_bytes() {
+        let session = SessionInfo::new(0x12345678, 1000);
+        assert_eq!(SessionInfo::from_bytes(&session.to_bytes()), Some(session));
+        assert_eq!(SessionInfo::from_bytes(&[0; 5]), None);
+    }
+
     #[test]
     fn test_session_info_new() {
         let session = SessionInfo::new(0x12345678, 1000);
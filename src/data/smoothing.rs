@@ -0,0 +1,208 @@
+//! Smoothing filters for virtual temperatures.
+//!
+//! Ambient readings in a grill oscillate as the fan cycles, which makes
+//! host-side controllers and alarms chatter around their thresholds. A
+//! [`SmoothingFilter`] set per virtual sensor with
+//! [`Probe::set_smoothing`](crate::Probe::set_smoothing) filters the
+//! readings the probe reports, while the unfiltered readings stay
+//! available.
+
+use std::collections::VecDeque;
+
+use crate::data::{VirtualSensor, VirtualTemperatures};
+
+/// A filter applied to successive readings of one sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SmoothingFilter {
+    /// Exponential moving average. Each reading moves the output by
+    /// `alpha` (0.0-1.0) of the way towards it; smaller values smooth more.
+    Ema {
+        /// Weight of the newest reading.
+        alpha: f64,
+    },
+    /// Median of the last `window` readings, which rejects short spikes.
+    Median {
+        /// Number of readings.
+        window: usize,
+    },
+}
+
+impl SmoothingFilter {
+    /// Create an exponential moving average, clamping `alpha` to 0.01-1.0.
+    pub fn ema(alpha: f64) -> Self {
+        Self::Ema {
+            alpha: alpha.clamp(0.01, 1.0),
+        }
+    }
+
+    /// Create a median of the last `window` readings (at least 1).
+    pub fn median(window: usize) -> Self {
+        Self::Median {
+            window: window.max(1),
+        }
+    }
+}
+
+/// Applies a [`SmoothingFilter`] to successive readings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Smoother {
+    /// Filter being applied.
+    filter: SmoothingFilter,
+    /// Last output of an exponential moving average.
+    average: Option<f64>,
+    /// Recent readings for a median, oldest first.
+    history: VecDeque<f64>,
+}
+
+impl Smoother {
+    /// Create a smoother applying `filter`.
+    pub fn new(filter: SmoothingFilter) -> Self {
+        Self {
+            filter,
+            average: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Get the filter being applied.
+    pub fn filter(&self) -> SmoothingFilter {
+        self.filter
+    }
+
+    /// Feed a reading, returning the filtered value.
+    pub fn update(&mut self, value: f64) -> f64 {
+        match self.filter {
+            SmoothingFilter::Ema { alpha } => {
+                let average = self
+                    .average
+                    .map_or(value, |average| average + alpha * (value - average));
+                self.average = Some(average);
+                average
+            }
+            SmoothingFilter::Median { window } => {
+                if self.history.len() == window {
+                    self.history.pop_front();
+                }
+                self.history.push_back(value);
+
+                let mut sorted: Vec<f64> = self.history.iter().copied().collect();
+                sorted.sort_by(f64::total_cmp);
+                let middle = sorted.len() / 2;
+                if sorted.len() % 2 == 0 {
+                    (sorted[middle - 1] + sorted[middle]) / 2.0
+                } else {
+                    sorted[middle]
+                }
+            }
+        }
+    }
+
+    /// Forget earlier readings.
+    pub fn reset(&mut self) {
+        self.average = None;
+        self.history.clear();
+    }
+}
+
+/// Smoothers for each virtual sensor.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VirtualSmoothing {
+    /// Smoothers indexed like [`VirtualSensor::ALL`].
+    smoothers: [Option<Smoother>; 3],
+}
+
+impl VirtualSmoothing {
+    /// Index of a sensor in [`VirtualSensor::ALL`].
+    fn slot(sensor: VirtualSensor) -> usize {
+        match sensor {
+            VirtualSensor::Core => 0,
+            VirtualSensor::Surface => 1,
+            VirtualSensor::Ambient => 2,
+        }
+    }
+
+    /// Get the filter applied to a sensor.
+    pub(crate) fn filter(&self, sensor: VirtualSensor) -> Option<SmoothingFilter> {
+        self.smoothers[Self::slot(sensor)]
+            .as_ref()
+            .map(Smoother::filter)
+    }
+
+    /// Set or clear the filter applied to a sensor.
+    pub(crate) fn set_filter(&mut self, sensor: VirtualSensor, filter: Option<SmoothingFilter>) {
+        self.smoothers[Self::slot(sensor)] = filter.map(Smoother::new);
+    }
+
+    /// Forget earlier readings of a sensor.
+    pub(crate) fn reset(&mut self, sensor: VirtualSensor) {
+        if let Some(smoother) = &mut self.smoothers[Self::slot(sensor)] {
+            smoother.reset();
+        }
+    }
+
+    /// Feed readings, returning them filtered.
+    ///
+    /// Missing readings pass through as `None` without disturbing the
+    /// filters.
+    pub(crate) fn apply(&mut self, readings: &VirtualTemperatures) -> VirtualTemperatures {
+        let mut filtered = readings.clone();
+        for sensor in VirtualSensor::ALL {
+            let (Some(smoother), Some(value)) = (
+                &mut self.smoothers[Self::slot(sensor)],
+                readings.get(sensor),
+            ) else {
+                continue;
+            };
+            let value = smoother.update(value);
+            match sensor {
+                VirtualSensor::Core => filtered.core = Some(value),
+                VirtualSensor::Surface => filtered.surface = Some(value),
+                VirtualSensor::Ambient => filtered.ambient = Some(value),
+            }
+        }
+        filtered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema() {
+        let mut smoother = Smoother::new(SmoothingFilter::ema(0.5));
+        assert_eq!(smoother.update(100.0), 100.0);
+        assert_eq!(smoother.update(120.0), 110.0);
+        assert_eq!(smoother.update(100.0), 105.0);
+
+        smoother.reset();
+        assert_eq!(smoother.update(80.0), 80.0);
+    }
+
+    #[test]
+    fn test_median_rejects_spikes() {
+        let mut smoother = Smoother::new(SmoothingFilter::median(3));
+        assert_eq!(smoother.update(150.0), 150.0);
+        assert_eq!(smoother.update(152.0), 151.0);
+        assert_eq!(smoother.update(190.0), 152.0);
+        assert_eq!(smoother.update(151.0), 152.0);
+        assert_eq!(smoother.update(149.0), 151.0);
+    }
+
+    #[test]
+    fn test_virtual_smoothing_filters_configured_sensors() {
+        let mut smoothing = VirtualSmoothing::default();
+        smoothing.set_filter(VirtualSensor::Ambient, Some(SmoothingFilter::ema(0.5)));
+        assert_eq!(
+            smoothing.filter(VirtualSensor::Ambient),
+            Some(SmoothingFilter::Ema { alpha: 0.5 })
+        );
+
+        smoothing.apply(&VirtualTemperatures::new(Some(40.0), None, Some(200.0)));
+        let filtered = smoothing.apply(&VirtualTemperatures::new(Some(42.0), None, Some(220.0)));
+        assert_eq!(filtered.core, Some(42.0));
+        assert_eq!(filtered.surface, None);
+        assert_eq!(filtered.ambient, Some(210.0));
+    }
+}
@@ -0,0 +1,180 @@
+//! Log sync progress.
+//!
+//! While a probe's log is downloaded, [`SyncProgress`] reports how far the
+//! sync has got, at most once per chunk of records and only when the
+//! percentage synced has moved by at least
+//! [`SyncProgress::MIN_STEP_PERCENT`], so a progress bar can be drawn
+//! straight from the events. A [`SyncComplete`] follows once every record
+//! the probe holds has arrived.
+
+use std::time::{Duration, Instant};
+
+/// Progress of a log sync.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncProgress {
+    /// Records synced of those the probe holds.
+    pub records: u32,
+    /// Records the probe holds.
+    pub total: u32,
+    /// Percentage synced (0.0 to 100.0).
+    pub percent: f64,
+    /// Records synced per second since the sync started, once measurable.
+    pub records_per_second: Option<f64>,
+    /// Estimated time until the sync completes, once the rate is known.
+    pub eta: Option<Duration>,
+}
+
+impl SyncProgress {
+    /// Smallest change in percentage synced that is reported.
+    pub const MIN_STEP_PERCENT: f64 = 1.0;
+
+    /// Check if every record has been synced.
+    pub fn is_complete(&self) -> bool {
+        self.records >= self.total
+    }
+}
+
+/// Totals of a completed log sync.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncComplete {
+    /// Records the probe holds, all now synced.
+    pub total: u32,
+    /// Records received during this sync.
+    pub received: u32,
+    /// Time from the first chunk to the last.
+    pub duration: Duration,
+    /// Records received per second, if the sync took measurable time.
+    pub records_per_second: Option<f64>,
+}
+
+/// Turns the records synced after each chunk into rate-limited progress.
+///
+/// A sync starts with the first chunk received while records are missing
+/// and ends when none are.
+#[derive(Debug, Default)]
+pub(crate) struct SyncTracker {
+    /// Sync in progress, if any.
+    run: Option<SyncRun>,
+}
+
+/// A sync in progress.
+#[derive(Debug)]
+struct SyncRun {
+    /// When the first chunk arrived.
+    started_at: Instant,
+    /// Records synced before the first chunk.
+    initial_records: u32,
+    /// Percentage last reported.
+    reported_percent: f64,
+}
+
+impl SyncTracker {
+    /// Record a chunk, given the records synced before it and after it,
+    /// of `total` held by the probe.
+    ///
+    /// Returns the progress to report, if it moved enough, and the totals
+    /// if the sync completed.
+    pub(crate) fn chunk(
+        &mut self,
+        before: u32,
+        records: u32,
+        total: u32,
+        now: Instant,
+    ) -> (Option<SyncProgress>, Option<SyncComplete>) {
+        let records = records.min(total);
+        let run = match &mut self.run {
+            Some(run) => run,
+            None if before >= total => return (None, None),
+            None => self.run.insert(SyncRun {
+                started_at: now,
+                initial_records: before,
+                reported_percent: percent(before, total),
+            }),
+        };
+
+        let elapsed = now.duration_since(run.started_at);
+        let received = records.saturating_sub(run.initial_records);
+        let records_per_second = (!elapsed.is_zero() && received > 0)
+            .then(|| f64::from(received) / elapsed.as_secs_f64());
+        let progress = SyncProgress {
+            records,
+            total,
+            percent: percent(records, total),
+            records_per_second,
+            eta: records_per_second
+                .map(|rate| Duration::from_secs_f64(f64::from(total - records) / rate)),
+        };
+
+        if progress.is_complete() {
+            let run = self.run.take().expect("sync in progress");
+            let complete = SyncComplete {
+                total,
+                received: records.saturating_sub(run.initial_records),
+                duration: elapsed,
+                records_per_second,
+            };
+            return (Some(progress), Some(complete));
+        }
+        if progress.percent - run.reported_percent < SyncProgress::MIN_STEP_PERCENT {
+            return (None, None);
+        }
+        run.reported_percent = progress.percent;
+        (Some(progress), None)
+    }
+}
+
+/// Percentage of `total` that `records` is.
+fn percent(records: u32, total: u32) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    f64::from(records) / f64::from(total) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_tracker() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut tracker = SyncTracker::default();
+
+        // Nothing to sync
+        assert_eq!(tracker.chunk(1000, 1000, 1000, at(0)), (None, None));
+
+        // Chunks under a percent are held back
+        assert_eq!(tracker.chunk(0, 5, 1000, at(0)), (None, None));
+        let (progress, complete) = tracker.chunk(5, 10, 1000, at(1));
+        assert_eq!(complete, None);
+        let progress = progress.unwrap();
+        assert_eq!(progress.records, 10);
+        assert_eq!(progress.percent, 1.0);
+        assert_eq!(progress.records_per_second, Some(10.0));
+        assert_eq!(progress.eta, Some(Duration::from_secs(99)));
+        assert_eq!(tracker.chunk(10, 19, 1000, at(2)), (None, None));
+
+        let (progress, complete) = tracker.chunk(19, 1000, 1000, at(10));
+        let progress = progress.unwrap();
+        assert!(progress.is_complete());
+        assert_eq!(progress.eta, Some(Duration::ZERO));
+        assert_eq!(
+            complete,
+            Some(SyncComplete {
+                total: 1000,
+                received: 1000,
+                duration: Duration::from_secs(10),
+                records_per_second: Some(100.0),
+            })
+        );
+
+        // New records start another sync
+        let (progress, complete) = tracker.chunk(1000, 1010, 1010, at(20));
+        assert!(progress.unwrap().is_complete());
+        assert_eq!(complete.unwrap().received, 10);
+        assert_eq!(complete.unwrap().records_per_second, None);
+    }
+}
@@ -3,6 +3,8 @@
 //! Contains types for raw temperature values from sensors and
 //! virtual temperature calculations.
 
+use chrono::{DateTime, Utc};
+
 use crate::utils::{celsius_to_fahrenheit, fahrenheit_to_celsius};
 
 /// Raw temperature value from a sensor (13-bit).
@@ -91,6 +93,21 @@ impl RawTemperature {
         Self(raw.min(Self::MAX_VALUE))
     }
 
+    /// Create a RawTemperature from a Celsius value, using the full 16 bits
+    /// rather than the probe's 13 so readings from other thermometers are
+    /// not cut off at about 389°C.
+    ///
+    /// Saturates at -20°C and about 3256°C, and never produces
+    /// [`INVALID`](Self::INVALID).
+    pub(crate) fn from_celsius_extended(celsius: f64) -> Self {
+        let raw = ((celsius + 20.0) * 20.0).round() as u16;
+        if raw == Self::INVALID.0 {
+            Self(Self::MAX_VALUE)
+        } else {
+            Self(raw)
+        }
+    }
+
     /// Create a RawTemperature from a Fahrenheit value.
     ///
     /// # Arguments
@@ -127,18 +144,43 @@ impl Default for RawTemperature {
 /// - T6: High-temperature thermistor
 /// - T7: High-temperature thermistor
 /// - T8: High-temperature thermistor at handle (ambient temperature)
+///
+/// Not every sensor carries a reading in every probe mode: in instant read
+/// mode only T1 does, and the other values are placeholders. The
+/// [`active_mask`](Self::active_mask) records which sensors are meaningful.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProbeTemperatures {
     /// Raw temperature values for all 8 sensors (T1-T8).
     pub values: [RawTemperature; 8],
+
+    /// Sensors carrying readings in the probe's current mode, bit 0 for T1.
+    #[cfg_attr(feature = "serde", serde(default = "all_sensors"))]
+    pub active_mask: u8,
+}
+
+/// Default for [`ProbeTemperatures::active_mask`] when deserializing.
+#[cfg(feature = "serde")]
+fn all_sensors() -> u8 {
+    ProbeTemperatures::ALL_SENSORS
 }
 
 impl ProbeTemperatures {
+    /// Mask with every sensor active.
+    pub const ALL_SENSORS: u8 = 0xFF;
+
+    /// Mask with only T1 active, as in instant read mode.
+    pub const T1_ONLY: u8 = 0x01;
+
+    /// Smallest step between adjacent sensors, in Celsius, taken as the
+    /// boundary between food and air.
+    pub const INSERTION_STEP: f64 = 3.0;
+
     /// Create a new ProbeTemperatures with all invalid values.
     pub fn new() -> Self {
         Self {
             values: [RawTemperature::INVALID; 8],
+            active_mask: Self::ALL_SENSORS,
         }
     }
 
@@ -150,9 +192,45 @@ impl ProbeTemperatures {
     pub fn from_raw(values: [u16; 8]) -> Self {
         Self {
             values: values.map(RawTemperature::new),
+            active_mask: Self::ALL_SENSORS,
         }
     }
 
+    /// Set which sensors are active (see [`active_mask`](Self::active_mask)).
+    pub fn with_active_mask(mut self, mask: u8) -> Self {
+        self.active_mask = mask;
+        self
+    }
+
+    /// Check if a sensor carries a reading in the current mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Sensor index (0-7, where 0 is T1 at tip)
+    pub fn is_active(&self, index: usize) -> bool {
+        index < 8 && self.active_mask & (1 << index) != 0
+    }
+
+    /// Get the temperatures of active sensors in Celsius.
+    ///
+    /// Like [`to_celsius`](Self::to_celsius), but inactive sensors are
+    /// `None` too.
+    pub fn active_celsius(&self) -> [Option<f64>; 8] {
+        let mut celsius = self.to_celsius();
+        for (i, value) in celsius.iter_mut().enumerate() {
+            if !self.is_active(i) {
+                *value = None;
+            }
+        }
+        celsius
+    }
+
+    /// Get the temperatures of active sensors in Fahrenheit.
+    pub fn active_fahrenheit(&self) -> [Option<f64>; 8] {
+        self.active_celsius()
+            .map(|celsius| celsius.map(celsius_to_fahrenheit))
+    }
+
     /// Get temperature for a specific sensor index.
     ///
     /// # Arguments
@@ -166,6 +244,11 @@ impl ProbeTemperatures {
         self.values.get(index)
     }
 
+    /// Get a sensor's temperature in Celsius, or `None` if invalid.
+    pub fn get(&self, sensor: PhysicalSensor) -> Option<f64> {
+        self.values[sensor.index()].to_celsius()
+    }
+
     /// Get all temperatures in Celsius.
     ///
     /// # Returns
@@ -184,6 +267,61 @@ impl ProbeTemperatures {
         self.values.map(|t| t.to_fahrenheit())
     }
 
+    /// Get the hottest active sensor and its temperature in Celsius.
+    pub fn max_sensor(&self) -> Option<(PhysicalSensor, f64)> {
+        self.readings().max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Get the coldest active sensor and its temperature in Celsius.
+    pub fn min_sensor(&self) -> Option<(PhysicalSensor, f64)> {
+        self.readings().min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Iterate over the active sensors with valid readings, in Celsius.
+    fn readings(&self) -> impl Iterator<Item = (PhysicalSensor, f64)> {
+        let celsius = self.active_celsius();
+        PhysicalSensor::ALL
+            .into_iter()
+            .zip(celsius)
+            .filter_map(|(sensor, value)| value.map(|c| (sensor, c)))
+    }
+
+    /// Get the temperature steps along the probe, from the tip to the
+    /// handle, in Celsius.
+    ///
+    /// Element `i` is the reading of sensor `i + 2` less that of sensor
+    /// `i + 1`, e.g. T2 - T1 first, or `None` if either is missing or
+    /// inactive.
+    pub fn gradient(&self) -> [Option<f64>; 7] {
+        let celsius = self.active_celsius();
+        std::array::from_fn(|i| Some(celsius[i + 1]? - celsius[i]?))
+    }
+
+    /// Estimate how many sensors, counted from the tip, are in the food.
+    ///
+    /// The food-air boundary is taken to be the largest step along the
+    /// probe, if it is at least [`INSERTION_STEP`](Self::INSERTION_STEP).
+    /// Returns `None` without readings from every sensor, or if the probe
+    /// reads evenly along its length: it is then either out of the food or
+    /// in food already at the temperature of its surroundings.
+    pub fn insertion_depth(&self) -> Option<usize> {
+        let gradient = self.gradient();
+        let steps: Option<Vec<f64>> = gradient.iter().copied().collect();
+        let (boundary, step) = steps?
+            .into_iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?;
+        (step.abs() >= Self::INSERTION_STEP).then_some(boundary + 1)
+    }
+
+    /// Check if the probe looks inserted in food.
+    ///
+    /// See [`insertion_depth`](Self::insertion_depth) for the heuristic and
+    /// when it cannot tell.
+    pub fn is_probe_inserted(&self) -> bool {
+        self.insertion_depth().is_some()
+    }
+
     /// Parse temperatures from packed 13-byte advertising data.
     ///
     /// The 8 temperatures are packed as 13-bit values in 13 bytes (104 bits).
@@ -227,7 +365,10 @@ impl ProbeTemperatures {
             let _ = i; // Suppress unused variable warning
         }
 
-        Some(Self { values })
+        Some(Self {
+            values,
+            active_mask: Self::ALL_SENSORS,
+        })
     }
 
     /// Pack temperatures into 13-byte format for transmission.
@@ -306,17 +447,50 @@ impl VirtualSensorSelection {
         }
     }
 
+    /// Get the physical sensor used for a virtual sensor, or `None` if the
+    /// selection is outside the sensor's range (T1-T6 for core, T4-T7 for
+    /// surface, T5-T8 for ambient).
+    pub fn get(&self, sensor: VirtualSensor) -> Option<PhysicalSensor> {
+        let (index, range) = match sensor {
+            VirtualSensor::Core => (self.core_sensor, 0..=5),
+            VirtualSensor::Surface => (self.surface_sensor, 3..=6),
+            VirtualSensor::Ambient => (self.ambient_sensor, 4..=7),
+        };
+        range
+            .contains(&index)
+            .then(|| PhysicalSensor::from_index(index as usize))
+            .flatten()
+    }
+
+    /// Get the physical sensor used for the virtual core.
+    pub fn core(&self) -> Option<PhysicalSensor> {
+        self.get(VirtualSensor::Core)
+    }
+
+    /// Get the physical sensor used for the virtual surface.
+    pub fn surface(&self) -> Option<PhysicalSensor> {
+        self.get(VirtualSensor::Surface)
+    }
+
+    /// Get the physical sensor used for the virtual ambient.
+    pub fn ambient(&self) -> Option<PhysicalSensor> {
+        self.get(VirtualSensor::Ambient)
+    }
+
     /// Get the display name for the core sensor (e.g., "T1", "T2", etc.).
+    #[deprecated(since = "0.1.0", note = "use core() instead")]
     pub fn core_sensor_name(&self) -> String {
         format!("T{}", self.core_sensor + 1)
     }
 
     /// Get the display name for the surface sensor (e.g., "T4", "T5", etc.).
+    #[deprecated(since = "0.1.0", note = "use surface() instead")]
     pub fn surface_sensor_name(&self) -> String {
         format!("T{}", self.surface_sensor + 1)
     }
 
     /// Get the display name for the ambient sensor (e.g., "T5", "T6", etc.).
+    #[deprecated(since = "0.1.0", note = "use ambient() instead")]
     pub fn ambient_sensor_name(&self) -> String {
         format!("T{}", self.ambient_sensor + 1)
     }
@@ -359,6 +533,28 @@ impl VirtualTemperatures {
         }
     }
 
+    /// Read the virtual temperatures from the physical sensors a selection
+    /// points at.
+    ///
+    /// A virtual sensor is `None` if its selection is out of range or the
+    /// selected sensor's reading is invalid.
+    pub fn from_selection(
+        temperatures: &ProbeTemperatures,
+        sensor_selection: VirtualSensorSelection,
+    ) -> Self {
+        let read = |sensor| {
+            sensor_selection
+                .get(sensor)
+                .and_then(|physical| temperatures.get(physical))
+        };
+        Self::with_selection(
+            read(VirtualSensor::Core),
+            read(VirtualSensor::Surface),
+            read(VirtualSensor::Ambient),
+            sensor_selection,
+        )
+    }
+
     /// Create new virtual temperatures with sensor selection info.
     pub fn with_selection(
         core: Option<f64>,
@@ -388,12 +584,206 @@ impl VirtualTemperatures {
     pub fn ambient_fahrenheit(&self) -> Option<f64> {
         self.ambient.map(celsius_to_fahrenheit)
     }
+
+    /// Get the temperature of a virtual sensor in Celsius.
+    pub fn get(&self, sensor: VirtualSensor) -> Option<f64> {
+        match sensor {
+            VirtualSensor::Core => self.core,
+            VirtualSensor::Surface => self.surface,
+            VirtualSensor::Ambient => self.ambient,
+        }
+    }
+}
+
+/// A virtual (computed) temperature sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VirtualSensor {
+    /// Virtual core temperature.
+    Core,
+    /// Virtual surface temperature.
+    Surface,
+    /// Virtual ambient temperature.
+    Ambient,
+}
+
+impl VirtualSensor {
+    /// All virtual sensors.
+    pub const ALL: [VirtualSensor; 3] = [Self::Core, Self::Surface, Self::Ambient];
+
+    /// Get the display name of the sensor.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Core => "Core",
+            Self::Surface => "Surface",
+            Self::Ambient => "Ambient",
+        }
+    }
+}
+
+/// A physical temperature sensor, from T1 at the tip to T8 at the handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PhysicalSensor {
+    /// High-precision sensor at the tip.
+    T1,
+    /// High-precision sensor.
+    T2,
+    /// MCU temperature sensor.
+    T3,
+    /// High-precision sensor.
+    T4,
+    /// High-temperature thermistor.
+    T5,
+    /// High-temperature thermistor.
+    T6,
+    /// High-temperature thermistor.
+    T7,
+    /// High-temperature thermistor at the handle.
+    T8,
+}
+
+impl PhysicalSensor {
+    /// All physical sensors, from tip to handle.
+    pub const ALL: [PhysicalSensor; 8] = [
+        Self::T1,
+        Self::T2,
+        Self::T3,
+        Self::T4,
+        Self::T5,
+        Self::T6,
+        Self::T7,
+        Self::T8,
+    ];
+
+    /// Get the sensor at an index (0-7, where 0 is T1).
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// Get the sensor's index (0-7, where 0 is T1).
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Get the display name of the sensor (e.g., "T1").
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::T1 => "T1",
+            Self::T2 => "T2",
+            Self::T3 => "T3",
+            Self::T4 => "T4",
+            Self::T5 => "T5",
+            Self::T6 => "T6",
+            Self::T7 => "T7",
+            Self::T8 => "T8",
+        }
+    }
+}
+
+impl std::fmt::Display for PhysicalSensor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Lowest and highest reading of a sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extremes {
+    /// Lowest reading in Celsius.
+    pub min: f64,
+    /// When the lowest reading was taken.
+    pub min_at: DateTime<Utc>,
+    /// Highest reading in Celsius.
+    pub max: f64,
+    /// When the highest reading was taken.
+    pub max_at: DateTime<Utc>,
+}
+
+impl Extremes {
+    /// Start tracking from a first reading.
+    pub fn new(celsius: f64, at: DateTime<Utc>) -> Self {
+        Self {
+            min: celsius,
+            min_at: at,
+            max: celsius,
+            max_at: at,
+        }
+    }
+
+    /// Include a reading.
+    pub fn update(&mut self, celsius: f64, at: DateTime<Utc>) {
+        if celsius < self.min {
+            self.min = celsius;
+            self.min_at = at;
+        }
+        if celsius > self.max {
+            self.max = celsius;
+            self.max_at = at;
+        }
+    }
+}
+
+/// Session high and low watermarks of the virtual sensors.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VirtualExtremes {
+    /// Core extremes, once a core reading has been seen.
+    pub core: Option<Extremes>,
+    /// Surface extremes, once a surface reading has been seen.
+    pub surface: Option<Extremes>,
+    /// Ambient extremes, once an ambient reading has been seen.
+    pub ambient: Option<Extremes>,
+}
+
+impl VirtualExtremes {
+    /// Include the readings of an update.
+    pub fn update(&mut self, temperatures: &VirtualTemperatures, at: DateTime<Utc>) {
+        for sensor in VirtualSensor::ALL {
+            let Some(celsius) = temperatures.get(sensor) else {
+                continue;
+            };
+            let extremes = self.get_mut(sensor);
+            match extremes {
+                Some(extremes) => extremes.update(celsius, at),
+                None => *extremes = Some(Extremes::new(celsius, at)),
+            }
+        }
+    }
+
+    /// Get the extremes of a virtual sensor.
+    pub fn get(&self, sensor: VirtualSensor) -> Option<&Extremes> {
+        match sensor {
+            VirtualSensor::Core => self.core.as_ref(),
+            VirtualSensor::Surface => self.surface.as_ref(),
+            VirtualSensor::Ambient => self.ambient.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, sensor: VirtualSensor) -> &mut Option<Extremes> {
+        match sensor {
+            VirtualSensor::Core => &mut self.core,
+            VirtualSensor::Surface => &mut self.surface,
+            VirtualSensor::Ambient => &mut self.ambient,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_physical_sensor() {
+        for (index, sensor) in PhysicalSensor::ALL.iter().enumerate() {
+            assert_eq!(sensor.index(), index);
+            assert_eq!(PhysicalSensor::from_index(index), Some(*sensor));
+        }
+        assert_eq!(PhysicalSensor::from_index(8), None);
+        assert_eq!(PhysicalSensor::T2.to_string(), "T2");
+    }
+
     #[test]
     fn test_raw_temperature_to_celsius() {
         // Formula: celsius = raw * 0.05 - 20
@@ -475,6 +865,77 @@ mod tests {
         assert!(temps.sensor(8).is_none());
     }
 
+    #[test]
+    fn test_probe_temperatures_active_mask() {
+        let temps = ProbeTemperatures::from_raw([400; 8]);
+        assert!((0..8).all(|i| temps.is_active(i)));
+
+        let instant_read = temps.with_active_mask(ProbeTemperatures::T1_ONLY);
+        assert!(instant_read.is_active(0));
+        assert!(!instant_read.is_active(1));
+        assert!(!instant_read.is_active(8));
+        let celsius = instant_read.active_celsius();
+        assert_eq!(celsius[0], Some(0.0));
+        assert!(celsius[1..].iter().all(Option::is_none));
+        // Raw values are kept
+        assert_eq!(instant_read.to_celsius()[1], Some(0.0));
+    }
+
+    #[test]
+    fn test_probe_temperatures_profile() {
+        // Tip in a roast at 40C, T6 onwards in a 150C oven
+        let celsius = [40.0, 40.5, 41.0, 42.0, 45.0, 140.0, 148.0, 150.0];
+        let temps = ProbeTemperatures::from_raw(
+            celsius.map(|c| RawTemperature::from_celsius(c).raw_value()),
+        );
+        assert_eq!(temps.max_sensor(), Some((PhysicalSensor::T8, 150.0)));
+        assert_eq!(temps.min_sensor(), Some((PhysicalSensor::T1, 40.0)));
+        let gradient = temps.gradient();
+        assert!((gradient[0].unwrap() - 0.5).abs() < 1e-9);
+        assert!((gradient[4].unwrap() - 95.0).abs() < 1e-9);
+        assert_eq!(temps.insertion_depth(), Some(5));
+        assert!(temps.is_probe_inserted());
+
+        // Even readings can't tell in from out
+        let even = ProbeTemperatures::from_raw([RawTemperature::from_celsius(21.0).raw_value(); 8]);
+        assert_eq!(even.insertion_depth(), None);
+        assert!(!even.is_probe_inserted());
+
+        // Missing and inactive sensors
+        let mut missing = temps.clone();
+        missing.values[7] = RawTemperature::INVALID;
+        assert_eq!(missing.max_sensor(), Some((PhysicalSensor::T7, 148.0)));
+        assert_eq!(missing.gradient()[6], None);
+        assert_eq!(missing.insertion_depth(), None);
+        let instant_read = temps.with_active_mask(ProbeTemperatures::T1_ONLY);
+        assert_eq!(instant_read.max_sensor(), Some((PhysicalSensor::T1, 40.0)));
+        assert!(instant_read.gradient().iter().all(Option::is_none));
+        assert_eq!(ProbeTemperatures::new().min_sensor(), None);
+    }
+
+    #[test]
+    fn test_virtual_extremes() {
+        let start = Utc::now();
+        let later = start + chrono::Duration::seconds(60);
+        let mut extremes = VirtualExtremes::default();
+
+        extremes.update(
+            &VirtualTemperatures::new(Some(20.0), None, Some(150.0)),
+            start,
+        );
+        extremes.update(
+            &VirtualTemperatures::new(Some(45.0), None, Some(140.0)),
+            later,
+        );
+
+        let core = extremes.get(VirtualSensor::Core).unwrap();
+        assert_eq!((core.min, core.max), (20.0, 45.0));
+        assert_eq!((core.min_at, core.max_at), (start, later));
+        let ambient = extremes.get(VirtualSensor::Ambient).unwrap();
+        assert_eq!((ambient.min, ambient.max), (140.0, 150.0));
+        assert!(extremes.surface.is_none());
+    }
+
     #[test]
     fn test_virtual_temperatures() {
         let vt = VirtualTemperatures::new(Some(63.0), Some(100.0), Some(200.0));
@@ -487,6 +948,39 @@ mod tests {
         assert!((vt.core_fahrenheit().unwrap() - 145.4).abs() < 0.1);
     }
 
+    #[test]
+    fn test_virtual_sensor_selection_get() {
+        // Core T1, surface T4, ambient T8
+        let selection = VirtualSensorSelection::from_byte(0b0110_0000);
+        assert_eq!(selection.core(), Some(PhysicalSensor::T1));
+        assert_eq!(selection.surface(), Some(PhysicalSensor::T4));
+        assert_eq!(selection.ambient(), Some(PhysicalSensor::T8));
+
+        // Core values 6 and 7 are reserved
+        let reserved = VirtualSensorSelection::from_byte(0b0000_0110);
+        assert_eq!(reserved.core(), None);
+        assert_eq!(reserved.surface(), Some(PhysicalSensor::T4));
+
+        let out_of_range = VirtualSensorSelection::new(0, 2, 3);
+        assert_eq!(out_of_range.surface(), None);
+        assert_eq!(out_of_range.ambient(), None);
+    }
+
+    #[test]
+    fn test_virtual_temperatures_from_selection() {
+        let mut temps = ProbeTemperatures::from_raw([400, 420, 440, 460, 480, 500, 520, 540]);
+        temps.values[6] = RawTemperature::INVALID;
+        assert_eq!(temps.get(PhysicalSensor::T2), Some(1.0));
+        assert_eq!(temps.get(PhysicalSensor::T7), None);
+
+        let selection = VirtualSensorSelection::new(1, 6, 7);
+        let virtual_temps = VirtualTemperatures::from_selection(&temps, selection);
+        assert_eq!(virtual_temps.get(VirtualSensor::Core), Some(1.0));
+        assert_eq!(virtual_temps.get(VirtualSensor::Surface), None);
+        assert_eq!(virtual_temps.get(VirtualSensor::Ambient), Some(7.0));
+        assert_eq!(virtual_temps.sensor_selection, selection);
+    }
+
     #[test]
     fn test_packed_bytes_roundtrip() {
         let raw = [1000, 1500, 2000, 2500, 3000, 3500, 4000, 4500];
@@ -0,0 +1,240 @@
+//! Temperatures along the probe shaft.
+//!
+//! [`ThermalProfile`] places the eight sensor readings at their positions
+//! along the probe, measured from the tip, and interpolates between them,
+//! which is what apps need to draw the probe as a heat map. Positions come
+//! from a [`SensorLayout`] the app supplies: the probe BLE specification
+//! does not document where the sensors sit, so the library does not guess.
+//!
+//! ```rust
+//! use combustion_rust_ble::{ProbeTemperatures, SensorLayout, ThermalProfile};
+//!
+//! # fn example(temperatures: &ProbeTemperatures) -> combustion_rust_ble::Result<()> {
+//! // Positions measured on the app's probe drawing
+//! let layout = SensorLayout::new([5.0, 15.0, 25.0, 35.0, 55.0, 75.0, 95.0, 115.0])?;
+//! let profile = ThermalProfile::new(temperatures, &layout);
+//! for point in profile.sample(32) {
+//!     println!("{:5.1} mm: {:?}", point.position_mm, point.celsius);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::temperatures::{PhysicalSensor, ProbeTemperatures};
+use crate::error::{Error, Result};
+
+/// Positions of the sensors along the probe, in millimetres from the tip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensorLayout {
+    /// Position of each sensor, T1 first, increasing towards the handle.
+    positions_mm: [f64; 8],
+}
+
+impl SensorLayout {
+    /// Create a layout from sensor positions, T1 first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] unless the positions are finite
+    /// and strictly increase from T1 to T8.
+    pub fn new(positions_mm: [f64; 8]) -> Result<Self> {
+        let increasing = positions_mm.iter().all(|p| p.is_finite())
+            && positions_mm.windows(2).all(|pair| pair[0] < pair[1]);
+        if !increasing {
+            return Err(Error::InvalidParameter {
+                name: "positions_mm".to_string(),
+                value: format!("{:?}", positions_mm),
+            });
+        }
+        Ok(Self { positions_mm })
+    }
+
+    /// Get the positions of the sensors, T1 first.
+    pub fn positions_mm(&self) -> [f64; 8] {
+        self.positions_mm
+    }
+
+    /// Get the position of a sensor in millimetres from the tip.
+    pub fn position(&self, sensor: PhysicalSensor) -> f64 {
+        self.positions_mm[sensor.index()]
+    }
+}
+
+/// A temperature at a position along the probe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProfilePoint {
+    /// Millimetres from the tip.
+    pub position_mm: f64,
+    /// Temperature in Celsius, or `None` where it is unknown.
+    pub celsius: Option<f64>,
+    /// Sensor at this position, or `None` for an interpolated point.
+    pub sensor: Option<PhysicalSensor>,
+}
+
+/// Sensor readings placed along the probe shaft.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThermalProfile {
+    /// Readings of the sensors, T1 first.
+    ///
+    /// Missing and inactive sensors have no temperature.
+    pub sensors: [ProfilePoint; 8],
+    /// Estimated depth of the food-air boundary in millimetres from the tip,
+    /// if the probe looks inserted.
+    pub insertion_depth_mm: Option<f64>,
+}
+
+impl ThermalProfile {
+    /// Build the profile of `temperatures` on `layout`.
+    ///
+    /// The insertion depth is halfway between the sensors either side of
+    /// the boundary found by [`ProbeTemperatures::insertion_depth`].
+    pub fn new(temperatures: &ProbeTemperatures, layout: &SensorLayout) -> Self {
+        let celsius = temperatures.active_celsius();
+        let sensors = PhysicalSensor::ALL.map(|sensor| ProfilePoint {
+            position_mm: layout.position(sensor),
+            celsius: celsius[sensor.index()],
+            sensor: Some(sensor),
+        });
+        let insertion_depth_mm = temperatures
+            .insertion_depth()
+            .map(|depth| (layout.positions_mm[depth - 1] + layout.positions_mm[depth]) / 2.0);
+        Self {
+            sensors,
+            insertion_depth_mm,
+        }
+    }
+
+    /// Get the span of the probe covered by sensors, in millimetres from
+    /// the tip.
+    pub fn span(&self) -> (f64, f64) {
+        (self.sensors[0].position_mm, self.sensors[7].position_mm)
+    }
+
+    /// Get the lowest and highest readings in Celsius, e.g. to scale a
+    /// heat map's colors.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.readings().fold(None, |range, (_, c)| match range {
+            None => Some((c, c)),
+            Some((low, high)) => Some((f64::min(low, c), f64::max(high, c))),
+        })
+    }
+
+    /// Interpolate the temperature at `position_mm` from the tip.
+    ///
+    /// Temperatures between sensors are interpolated linearly from the
+    /// nearest sensors with readings on either side. Returns `None` outside
+    /// those sensors, or without readings.
+    pub fn temperature_at(&self, position_mm: f64) -> Option<f64> {
+        let below = self
+            .readings()
+            .filter(|(position, _)| *position <= position_mm)
+            .last()?;
+        let above = self
+            .readings()
+            .find(|(position, _)| *position >= position_mm)?;
+        if above.0 == below.0 {
+            return Some(below.1);
+        }
+        let fraction = (position_mm - below.0) / (above.0 - below.0);
+        Some(below.1 + (above.1 - below.1) * fraction)
+    }
+
+    /// Sample `count` evenly spaced points from the first sensor to the
+    /// last.
+    ///
+    /// Points falling on a sensor carry it; the rest are interpolated.
+    pub fn sample(&self, count: usize) -> Vec<ProfilePoint> {
+        let (start, end) = self.span();
+        let step = if count > 1 {
+            (end - start) / (count - 1) as f64
+        } else {
+            0.0
+        };
+        (0..count)
+            .map(|i| {
+                let position_mm = start + step * i as f64;
+                ProfilePoint {
+                    position_mm,
+                    celsius: self.temperature_at(position_mm),
+                    sensor: self
+                        .sensors
+                        .iter()
+                        .find(|point| (point.position_mm - position_mm).abs() < 1e-9)
+                        .and_then(|point| point.sensor),
+                }
+            })
+            .collect()
+    }
+
+    /// Iterate over the positions and temperatures of sensors with
+    /// readings, from the tip.
+    fn readings(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.sensors
+            .iter()
+            .filter_map(|point| point.celsius.map(|c| (point.position_mm, c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RawTemperature;
+
+    fn temperatures(celsius: [f64; 8]) -> ProbeTemperatures {
+        ProbeTemperatures::from_raw(celsius.map(|c| RawTemperature::from_celsius(c).raw_value()))
+    }
+
+    #[test]
+    fn test_thermal_profile() {
+        let layout = SensorLayout::new([0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0]).unwrap();
+        let mut temps = temperatures([40.0, 40.0, 42.0, 44.0, 100.0, 140.0, 150.0, 150.0]);
+        let profile = ThermalProfile::new(&temps, &layout);
+        assert_eq!(profile.insertion_depth_mm, Some(35.0));
+
+        temps.values[1] = RawTemperature::INVALID;
+        let profile = ThermalProfile::new(&temps, &layout);
+
+        assert_eq!(profile.span(), (0.0, 70.0));
+        assert_eq!(profile.range(), Some((40.0, 150.0)));
+        assert_eq!(profile.sensors[1].celsius, None);
+
+        // Interpolated across the missing T2
+        assert_eq!(profile.temperature_at(10.0), Some(41.0));
+        assert_eq!(profile.temperature_at(35.0), Some(72.0));
+        assert_eq!(profile.temperature_at(40.0), Some(100.0));
+        assert_eq!(profile.temperature_at(-1.0), None);
+        assert_eq!(profile.temperature_at(71.0), None);
+
+        let samples = profile.sample(15);
+        assert_eq!(samples.len(), 15);
+        assert_eq!(samples[0].sensor, Some(PhysicalSensor::T1));
+        assert_eq!(samples[1].sensor, None);
+        assert_eq!(samples[1].celsius, Some(40.5));
+        assert_eq!(samples[14].sensor, Some(PhysicalSensor::T8));
+        assert_eq!(profile.sample(1).len(), 1);
+
+        // Nothing to draw without readings
+        let empty = ThermalProfile::new(&ProbeTemperatures::new(), &layout);
+        assert_eq!(empty.range(), None);
+        assert!(empty.sample(4).iter().all(|p| p.celsius.is_none()));
+        assert_eq!(empty.insertion_depth_mm, None);
+    }
+
+    #[test]
+    fn test_layout_positions_must_increase() {
+        assert!(SensorLayout::new([0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0]).is_ok());
+        for positions in [
+            [0.0, 10.0, 10.0, 30.0, 40.0, 50.0, 60.0, 70.0],
+            [70.0, 60.0, 50.0, 40.0, 30.0, 20.0, 10.0, 0.0],
+            [0.0, 10.0, 20.0, f64::NAN, 40.0, 50.0, 60.0, 70.0],
+        ] {
+            assert!(matches!(
+                SensorLayout::new(positions),
+                Err(Error::InvalidParameter { name, .. }) if name == "positions_mm"
+            ));
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Default cooks for probes by ring color or ID.
+//!
+//! A kitchen that always uses the red-ringed probe for poultry can say so
+//! once: [`ProbeDefaults`] maps probe colors and IDs to [`CookSpec`]s, and a
+//! probe given the defaults with
+//! [`DeviceManager::set_probe_defaults`](crate::DeviceManager::set_probe_defaults)
+//! starts the matching cook each time it connects without one running. Each
+//! applied default is announced as a [`DefaultsApplied`] on
+//! [`Probe::subscribe_defaults_applied`](crate::Probe::subscribe_defaults_applied).
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::defaults::{ProbeDefaults, ProbeMatch};
+//! use combustion_rust_ble::{CookSpec, DeviceManager, FoodSafeProduct, ProbeColor};
+//!
+//! # fn example(manager: &DeviceManager) {
+//! let defaults = ProbeDefaults::new().with(
+//!     ProbeMatch::Color(ProbeColor::Red),
+//!     CookSpec::new(FoodSafeProduct::ChickenBreast, 74.0),
+//! );
+//! manager.set_probe_defaults(defaults);
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::{ProbeColor, ProbeId};
+use crate::data::CookSpec;
+use crate::error::Result;
+
+/// Which probes a default cook applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProbeMatch {
+    /// Probes with this ring color.
+    Color(ProbeColor),
+    /// Probes set to this ID.
+    Id(ProbeId),
+}
+
+impl ProbeMatch {
+    /// Check if a probe with `color` and `id` matches.
+    pub fn matches(&self, color: ProbeColor, id: ProbeId) -> bool {
+        match self {
+            Self::Color(c) => *c == color,
+            Self::Id(i) => *i == id,
+        }
+    }
+}
+
+impl std::fmt::Display for ProbeMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Color(color) => write!(f, "{} probe", color.name()),
+            Self::Id(id) => write!(f, "probe ID {}", id),
+        }
+    }
+}
+
+/// A default cook and the probes it applies to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefaultCook {
+    /// Probes the cook applies to.
+    pub probe: ProbeMatch,
+    /// Cook to start.
+    pub spec: CookSpec,
+}
+
+/// Default cooks keyed by probe color or ID.
+///
+/// A probe's ID is more specific than its color, so a default for its ID
+/// wins over one for its color.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProbeDefaults {
+    /// Defaults, at most one per [`ProbeMatch`].
+    cooks: Vec<DefaultCook>,
+}
+
+impl ProbeDefaults {
+    /// Create an empty set of defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a default, replacing any for the same probes.
+    ///
+    /// Invalid specs are kept; they fail when applied. Use
+    /// [`set`](Self::set) to check them up front.
+    pub fn with(mut self, probe: ProbeMatch, spec: CookSpec) -> Self {
+        self.insert(probe, spec);
+        self
+    }
+
+    /// Set the default for `probe`, returning the one it replaces.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`](crate::Error::InvalidParameter)
+    /// if `spec` is invalid.
+    pub fn set(&mut self, probe: ProbeMatch, spec: CookSpec) -> Result<Option<CookSpec>> {
+        spec.validate()?;
+        Ok(self.insert(probe, spec))
+    }
+
+    /// Add or replace the default for `probe`.
+    fn insert(&mut self, probe: ProbeMatch, spec: CookSpec) -> Option<CookSpec> {
+        match self.cooks.iter_mut().find(|cook| cook.probe == probe) {
+            Some(cook) => Some(std::mem::replace(&mut cook.spec, spec)),
+            None => {
+                self.cooks.push(DefaultCook { probe, spec });
+                None
+            }
+        }
+    }
+
+    /// Remove the default for `probe`, returning it if there was one.
+    pub fn remove(&mut self, probe: ProbeMatch) -> Option<CookSpec> {
+        let index = self.cooks.iter().position(|cook| cook.probe == probe)?;
+        Some(self.cooks.remove(index).spec)
+    }
+
+    /// List the defaults in the order they were added.
+    pub fn cooks(&self) -> &[DefaultCook] {
+        &self.cooks
+    }
+
+    /// Check if there are no defaults.
+    pub fn is_empty(&self) -> bool {
+        self.cooks.is_empty()
+    }
+
+    /// Get the default for a probe with `color` and `id`, if any.
+    pub fn for_probe(&self, color: ProbeColor, id: ProbeId) -> Option<&DefaultCook> {
+        let find = |by_id: bool| {
+            self.cooks.iter().find(|cook| {
+                matches!(cook.probe, ProbeMatch::Id(_)) == by_id && cook.probe.matches(color, id)
+            })
+        };
+        find(true).or_else(|| find(false))
+    }
+}
+
+/// A default cook started on a probe when it connected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaultsApplied {
+    /// Serial number of the probe.
+    pub serial_number: u32,
+    /// Which default matched the probe.
+    pub probe: ProbeMatch,
+    /// Cook that was started.
+    pub spec: CookSpec,
+    /// When the cook was started.
+    pub applied_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::FoodSafeProduct;
+
+    #[test]
+    fn test_defaults_prefer_id_over_color() {
+        let poultry = CookSpec::new(FoodSafeProduct::ChickenBreast, 74.0);
+        let steak = CookSpec::new(FoodSafeProduct::BeefSteak, 54.0);
+        let mut defaults = ProbeDefaults::new()
+            .with(ProbeMatch::Color(ProbeColor::Red), poultry.clone())
+            .with(ProbeMatch::Id(ProbeId::new(3)), steak.clone());
+
+        let matched = |defaults: &ProbeDefaults, color, id| {
+            defaults
+                .for_probe(color, ProbeId::new(id))
+                .map(|cook| cook.probe)
+        };
+        assert_eq!(
+            matched(&defaults, ProbeColor::Red, 1),
+            Some(ProbeMatch::Color(ProbeColor::Red))
+        );
+        assert_eq!(
+            matched(&defaults, ProbeColor::Red, 3),
+            Some(ProbeMatch::Id(ProbeId::new(3)))
+        );
+        assert_eq!(matched(&defaults, ProbeColor::Blue, 1), None);
+
+        // Replacing and removing
+        assert_eq!(
+            defaults
+                .set(ProbeMatch::Color(ProbeColor::Red), steak.clone())
+                .unwrap(),
+            Some(poultry)
+        );
+        assert_eq!(defaults.cooks().len(), 2);
+        assert!(defaults
+            .set(
+                ProbeMatch::Color(ProbeColor::Blue),
+                CookSpec::new(FoodSafeProduct::BeefSteak, 400.0)
+            )
+            .is_err());
+        assert_eq!(
+            defaults.remove(ProbeMatch::Id(ProbeId::new(3))),
+            Some(steak)
+        );
+        assert_eq!(defaults.remove(ProbeMatch::Id(ProbeId::new(3))), None);
+        assert_eq!(ProbeMatch::Color(ProbeColor::Red).to_string(), "Red probe");
+    }
+}
@@ -0,0 +1,94 @@
+//! Common interface for Combustion devices.
+//!
+//! Every device type the crate manages implements [`CombustionDevice`], so
+//! integrations can list and report on a mixed fleet through
+//! [`DeviceManager::devices`](crate::DeviceManager::devices) without
+//! matching on concrete types. Only [`Probe`] is supported today; displays,
+//! boosters, and repeaters will implement the same trait.
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::ProductType;
+use crate::ble::connection::ConnectionState;
+use crate::probe::Probe;
+use crate::snapshot::ProbeSnapshot;
+
+/// State of a device at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum DeviceSnapshot {
+    /// A Predictive Thermometer probe.
+    Probe(ProbeSnapshot),
+}
+
+impl DeviceSnapshot {
+    /// Get the device's serial number.
+    pub fn serial_number(&self) -> u32 {
+        match self {
+            Self::Probe(snapshot) => snapshot.serial_number,
+        }
+    }
+
+    /// Get when the snapshot was captured.
+    pub fn captured_at(&self) -> DateTime<Utc> {
+        match self {
+            Self::Probe(snapshot) => snapshot.captured_at,
+        }
+    }
+}
+
+/// A Combustion device.
+pub trait CombustionDevice: Send + Sync {
+    /// Get the unique serial number.
+    fn serial_number(&self) -> u32;
+
+    /// Get the kind of device.
+    fn product_type(&self) -> ProductType;
+
+    /// Get the signal strength (RSSI), if known.
+    fn rssi(&self) -> Option<i16>;
+
+    /// Get the current connection state.
+    fn connection_state(&self) -> ConnectionState;
+
+    /// Capture a snapshot of the device's current state.
+    fn device_snapshot(&self) -> DeviceSnapshot;
+
+    /// Get the device as a probe, if it is one.
+    fn as_probe(&self) -> Option<&Probe> {
+        None
+    }
+}
+
+impl CombustionDevice for Probe {
+    fn serial_number(&self) -> u32 {
+        Probe::serial_number(self)
+    }
+
+    /// External sensor sources report [`ProductType::Unknown`] with the
+    /// specification's unknown value, 0.
+    fn product_type(&self) -> ProductType {
+        if self.is_external() {
+            ProductType::Unknown(0)
+        } else {
+            ProductType::PredictiveProbe
+        }
+    }
+
+    fn rssi(&self) -> Option<i16> {
+        Probe::rssi(self)
+    }
+
+    fn connection_state(&self) -> ConnectionState {
+        Probe::connection_state(self)
+    }
+
+    fn device_snapshot(&self) -> DeviceSnapshot {
+        DeviceSnapshot::Probe(self.snapshot())
+    }
+
+    fn as_probe(&self) -> Option<&Probe> {
+        Some(self)
+    }
+}
@@ -5,19 +5,38 @@
 //! and managed. Other Combustion devices (Display, Booster, MeatNet Repeater,
 //! Giant Grill Gauge) are intentionally filtered out.
 
+use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
-use crate::ble::scanner::{BleScanner, ProbeDiscoveryEvent};
-use crate::error::Result;
-use crate::probe::{CallbackHandle, Probe};
+use crate::ble::advertising::{ProbeId, ProbeMode};
+use crate::ble::scanner::{BleScanner, DutyCycle, ProbeDiscoveryEvent, ScanOptions, ScanPhase};
+use crate::callbacks::{CallbackError, CallbackHandle, CallbackInfo, CallbackRegistry};
+use crate::claim::{ClaimDetector, ClaimOptions, ClaimSample, ProbeClaim};
+use crate::defaults::ProbeDefaults;
+use crate::device::CombustionDevice;
+use crate::error::{Error, Result};
+use crate::external::ExternalSensorSource;
+#[cfg(feature = "gateway")]
+use crate::gateway::RemoteBackend;
+use crate::group::CookGroup;
+use crate::handle::ProbeHandle;
+#[cfg(feature = "persistence")]
+use crate::persistence::{KnownProbeRegistry, KnownProbeStatus, TimerStore};
+use crate::probe::Probe;
+use crate::quarantine::PayloadQuarantine;
+#[cfg(feature = "simulator")]
+use crate::simulator::SimulatedProbe;
+use crate::snapshot::ProbeSnapshot;
 
 /// Maximum number of probes that can be managed simultaneously.
+///
+/// External sensor sources don't count toward the limit.
 pub const MAX_PROBES: usize = 8;
 
 /// Event emitted when a probe is discovered.
@@ -27,10 +46,246 @@ pub struct ProbeEvent {
     pub identifier: String,
 }
 
+/// Two or more discovered probes set to the same [`ProbeId`].
+///
+/// Displays and the Combustion app tell probes apart by ID, so duplicates
+/// make readings ambiguous. Resolve with [`DeviceManager::auto_assign_ids`]
+/// or by setting IDs with [`Probe::set_id`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeIdConflict {
+    /// The shared ID.
+    pub id: ProbeId,
+    /// Serial numbers (as hex strings) of the probes using it, in order.
+    pub serial_numbers: Vec<String>,
+}
+
+/// The host woke from sleep.
+///
+/// Detected from gaps between the manager's periodic checks. Connections
+/// open before the sleep are treated as lost and re-established.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WakeEvent {
+    /// Approximate time the host was asleep.
+    pub slept: Duration,
+    /// When the wake was detected.
+    pub detected_at: DateTime<Utc>,
+}
+
+/// The BLE scan was restarted because it stopped delivering
+/// advertisements.
+///
+/// BlueZ in particular can silently stop reporting advertisements while the
+/// scan is nominally active. See [`DeviceManager::set_scan_watchdog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanRestarted {
+    /// How long no advertisements had arrived.
+    pub silent_for: Duration,
+    /// When the scan was restarted.
+    pub restarted_at: DateTime<Utc>,
+}
+
+/// Detects a scan that has stopped delivering advertisements.
+///
+/// While nothing arrives, e.g. because every probe is switched off, the
+/// timeout doubles after each restart up to [`MAX_BACKOFF`](Self::MAX_BACKOFF)
+/// so the scan isn't restarted over and over.
+struct ScanWatchdog {
+    /// Time since the last advertisement before the next restart.
+    timeout: Duration,
+    /// When the last advertisement arrived, or the watchdog last fired.
+    last_activity: tokio::time::Instant,
+}
+
+impl ScanWatchdog {
+    /// Longest timeout after repeated restarts.
+    const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+    fn new(timeout: Duration, now: tokio::time::Instant) -> Self {
+        Self {
+            timeout,
+            last_activity: now,
+        }
+    }
+
+    /// Record an advertisement, resetting the timeout to `timeout`.
+    fn advertisement(&mut self, timeout: Duration, now: tokio::time::Instant) {
+        self.timeout = timeout;
+        self.last_activity = now;
+    }
+
+    /// Check the scan, returning how long it has been silent if it is
+    /// overdue for a restart.
+    ///
+    /// `expecting` is whether advertisements should be arriving: the
+    /// adapter is listening and probes have been seen before.
+    fn check(&mut self, expecting: bool, now: tokio::time::Instant) -> Option<Duration> {
+        if !expecting {
+            self.last_activity = now;
+            return None;
+        }
+        let silent_for = now.duration_since(self.last_activity);
+        if silent_for < self.timeout {
+            return None;
+        }
+        self.last_activity = now;
+        if self.timeout < Self::MAX_BACKOFF {
+            self.timeout = (self.timeout * 2).min(Self::MAX_BACKOFF);
+        }
+        Some(silent_for)
+    }
+}
+
+/// Picks out the probe whose signal jumps above its own baseline, as when it
+/// is held next to the adapter.
+///
+/// Each probe's baseline is an average of its earlier readings, so probes
+/// that are simply closer than others don't count. A probe must read
+/// `threshold` dB above its baseline on [`SPIKE_SAMPLES`](Self::SPIKE_SAMPLES)
+/// consecutive samples, so a single noisy reading doesn't pick it.
+#[derive(Debug)]
+struct ProximityDetector {
+    /// Rise over baseline that counts as a spike, in dB.
+    threshold: f64,
+    /// Baselines by probe key.
+    probes: HashMap<String, RssiBaseline>,
+}
+
+/// A probe's usual signal strength, for [`ProximityDetector`].
+#[derive(Debug)]
+struct RssiBaseline {
+    /// Average RSSI, in dBm.
+    rssi: f64,
+    /// Samples averaged so far.
+    samples: u32,
+    /// Consecutive samples above the threshold.
+    spiking: u32,
+}
+
+impl ProximityDetector {
+    /// Samples averaged before a probe's baseline is trusted.
+    const BASELINE_SAMPLES: u32 = 4;
+    /// Consecutive samples above the threshold that make a spike.
+    const SPIKE_SAMPLES: u32 = 2;
+    /// Weight of each new sample in the baseline once established.
+    const BASELINE_WEIGHT: f64 = 0.2;
+
+    fn new(threshold: i16) -> Self {
+        Self {
+            threshold: f64::from(threshold),
+            probes: HashMap::new(),
+        }
+    }
+
+    /// Record one RSSI sample per probe, returning the probe that spiked
+    /// the most above its baseline, if any did.
+    fn update<'a>(&mut self, samples: impl IntoIterator<Item = (&'a str, i16)>) -> Option<String> {
+        let mut best: Option<(String, f64)> = None;
+        for (key, rssi) in samples {
+            let rssi = f64::from(rssi);
+            let baseline = self.probes.entry(key.to_string()).or_insert(RssiBaseline {
+                rssi,
+                samples: 0,
+                spiking: 0,
+            });
+
+            let delta = rssi - baseline.rssi;
+            if baseline.samples >= Self::BASELINE_SAMPLES && delta >= self.threshold {
+                baseline.spiking += 1;
+                if baseline.spiking >= Self::SPIKE_SAMPLES
+                    && best.as_ref().map_or(true, |b| delta > b.1)
+                {
+                    best = Some((key.to_string(), delta));
+                }
+                continue;
+            }
+
+            baseline.spiking = 0;
+            baseline.samples += 1;
+            let weight = if baseline.samples <= Self::BASELINE_SAMPLES {
+                1.0 / f64::from(baseline.samples)
+            } else {
+                Self::BASELINE_WEIGHT
+            };
+            baseline.rssi += delta * weight;
+        }
+        best.map(|(key, _)| key)
+    }
+}
+
+/// Detects host sleep from gaps between periodic checks.
+///
+/// Depending on the platform, the monotonic clock either stops or keeps
+/// running while the host sleeps, so a gap in either it or the wall clock
+/// counts.
+struct SleepDetector {
+    /// Time between checks.
+    interval: Duration,
+    /// Wall clock time of the last check.
+    wall: DateTime<Utc>,
+    /// Monotonic time of the last check.
+    monotonic: tokio::time::Instant,
+}
+
+impl SleepDetector {
+    /// Time between checks.
+    const INTERVAL: Duration = Duration::from_secs(2);
+
+    /// Shortest gap reported as sleep.
+    const MIN_SLEEP: Duration = Duration::from_secs(10);
+
+    fn new(interval: Duration, wall: DateTime<Utc>, monotonic: tokio::time::Instant) -> Self {
+        Self {
+            interval,
+            wall,
+            monotonic,
+        }
+    }
+
+    /// Record a check, returning how long the host slept since the last one,
+    /// if it did.
+    fn check(&mut self, wall: DateTime<Utc>, monotonic: tokio::time::Instant) -> Option<Duration> {
+        let wall_elapsed = (wall - self.wall).to_std().unwrap_or_default();
+        let monotonic_elapsed = monotonic.duration_since(self.monotonic);
+        self.wall = wall;
+        self.monotonic = monotonic;
+
+        let slept = wall_elapsed
+            .max(monotonic_elapsed)
+            .saturating_sub(self.interval);
+        (slept >= Self::MIN_SLEEP).then_some(slept)
+    }
+}
+
+/// What a device manager is doing, for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ManagerState {
+    /// Not scanning.
+    #[default]
+    Idle,
+    /// Scanning for probes.
+    Scanning,
+    /// Paused with [`DeviceManager::pause`]; probes are kept but not updated
+    /// by scanning, and do not reconnect.
+    Paused,
+}
+
+/// Source of probes for a device manager.
+enum Backend {
+    /// Local Bluetooth adapter.
+    Ble(Arc<BleScanner>),
+    /// Probes served by a remote gateway.
+    #[cfg(feature = "gateway")]
+    Remote(Arc<RemoteBackend>),
+    /// Simulated probes.
+    #[cfg(feature = "simulator")]
+    Simulated(Arc<Vec<Arc<SimulatedProbe>>>),
+}
+
 /// Central manager for discovering and managing Combustion probes.
 pub struct DeviceManager {
-    /// BLE scanner.
-    scanner: Arc<BleScanner>,
+    /// Probe source.
+    backend: Backend,
     /// Discovered probes by serial number (as hex string).
     probes: Arc<RwLock<HashMap<String, Arc<Probe>>>>,
     /// Whether MeatNet is enabled.
@@ -39,15 +294,60 @@ pub struct DeviceManager {
     probe_discovered_tx: broadcast::Sender<Arc<Probe>>,
     /// Probe stale channel.
     probe_stale_tx: broadcast::Sender<Arc<Probe>>,
-    /// Callback ID counter.
-    callback_counter: AtomicU64,
+    /// Probe ID conflict channel.
+    probe_id_conflict_tx: broadcast::Sender<ProbeIdConflict>,
+    /// Current scan duty cycle phase.
+    scan_phase: Arc<RwLock<Option<ScanPhase>>>,
+    /// Scan phase channel.
+    scan_phase_tx: broadcast::Sender<ScanPhase>,
+    /// Task applying the scan duty cycle.
+    duty_cycle_handle: RwLock<Option<crate::runtime::TaskHandle<()>>>,
+    /// Silence before the BLE scan is restarted, or `None` to never.
+    scan_watchdog: Arc<RwLock<Option<Duration>>>,
+    /// Scan restart channel.
+    scan_restarted_tx: broadcast::Sender<ScanRestarted>,
+    /// Wake event channel.
+    wake_tx: broadcast::Sender<WakeEvent>,
+    /// Task detecting host sleep.
+    wake_handle: RwLock<Option<crate::runtime::TaskHandle<()>>>,
+    /// Current state.
+    state: RwLock<ManagerState>,
+    /// Serial numbers of the probes connected when the manager paused.
+    paused_connections: RwLock<HashSet<String>>,
+    /// State change channel.
+    state_tx: broadcast::Sender<ManagerState>,
+    /// Buffer of payloads that failed to parse, shared with probes.
+    quarantine: PayloadQuarantine,
+    /// Registered callbacks.
+    callbacks: CallbackRegistry,
     /// Background task handle.
-    background_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    background_handle: RwLock<Option<crate::runtime::TaskHandle<()>>>,
+    /// Registry of the user's own probes.
+    #[cfg(feature = "persistence")]
+    known_probes: RwLock<Option<Arc<KnownProbeRegistry>>>,
+    /// Task refreshing and connecting known probes.
+    #[cfg(feature = "persistence")]
+    known_probes_handle: RwLock<Option<crate::runtime::TaskHandle<()>>>,
+    /// Task handing the timer store to discovered probes.
+    #[cfg(feature = "persistence")]
+    timer_store_handle: RwLock<Option<crate::runtime::TaskHandle<()>>>,
+    /// Task giving newly discovered probes the default cooks.
+    defaults_handle: RwLock<Option<crate::runtime::TaskHandle<()>>>,
     /// Running flag.
     is_running: Arc<AtomicBool>,
 }
 
 impl DeviceManager {
+    /// Default silence before the BLE scan is restarted.
+    pub const DEFAULT_SCAN_WATCHDOG: Duration = Duration::from_secs(30);
+
+    /// Time between RSSI samples in
+    /// [`select_probe_interactively`](Self::select_probe_interactively).
+    const PROXIMITY_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Time between stale, heartbeat and ID conflict checks.
+    const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(1);
+
     /// Create a new DeviceManager instance.
     ///
     /// # Errors
@@ -55,20 +355,84 @@ impl DeviceManager {
     /// Returns an error if Bluetooth is not available.
     pub async fn new() -> Result<Self> {
         let scanner = BleScanner::new().await?;
+        Ok(Self::with_backend(Backend::Ble(Arc::new(scanner))))
+    }
+
+    /// Create a DeviceManager with platform scan tuning.
+    ///
+    /// See [`ScanOptions`] for which options each platform honors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if Bluetooth is not available or the options are invalid.
+    pub async fn with_scan_options(options: ScanOptions) -> Result<Self> {
+        let scanner = BleScanner::new().await?;
+        scanner.set_scan_options(options)?;
+        Ok(Self::with_backend(Backend::Ble(Arc::new(scanner))))
+    }
+
+    /// Create a DeviceManager that uses the probes served by a gateway.
+    ///
+    /// Call [`start_scanning`](Self::start_scanning) to begin receiving probes.
+    #[cfg(feature = "gateway")]
+    pub fn with_remote(backend: RemoteBackend) -> Self {
+        Self::with_backend(Backend::Remote(Arc::new(backend)))
+    }
+
+    /// Create a DeviceManager that discovers simulated probes.
+    ///
+    /// Scanning starts each simulator and discovers its probe. Keep the
+    /// `Arc`s to inject faults while the manager is running.
+    #[cfg(feature = "simulator")]
+    pub fn with_simulated(probes: impl IntoIterator<Item = Arc<SimulatedProbe>>) -> Self {
+        Self::with_backend(Backend::Simulated(Arc::new(probes.into_iter().collect())))
+    }
 
+    fn with_backend(backend: Backend) -> Self {
         let (probe_discovered_tx, _) = broadcast::channel(32);
         let (probe_stale_tx, _) = broadcast::channel(32);
+        let (probe_id_conflict_tx, _) = broadcast::channel(16);
+        let (scan_phase_tx, _) = broadcast::channel(16);
+        let (state_tx, _) = broadcast::channel(16);
+        let (scan_restarted_tx, _) = broadcast::channel(16);
+        let (wake_tx, _) = broadcast::channel(16);
+        let quarantine = match &backend {
+            Backend::Ble(scanner) => scanner.quarantine().clone(),
+            #[cfg(feature = "gateway")]
+            Backend::Remote(_) => PayloadQuarantine::default(),
+            #[cfg(feature = "simulator")]
+            Backend::Simulated(_) => PayloadQuarantine::default(),
+        };
 
-        Ok(Self {
-            scanner: Arc::new(scanner),
+        Self {
+            backend,
             probes: Arc::new(RwLock::new(HashMap::new())),
             meatnet_enabled: AtomicBool::new(false),
             probe_discovered_tx,
             probe_stale_tx,
-            callback_counter: AtomicU64::new(0),
+            probe_id_conflict_tx,
+            scan_phase: Arc::new(RwLock::new(None)),
+            scan_phase_tx,
+            duty_cycle_handle: RwLock::new(None),
+            scan_watchdog: Arc::new(RwLock::new(Some(Self::DEFAULT_SCAN_WATCHDOG))),
+            scan_restarted_tx,
+            wake_tx,
+            wake_handle: RwLock::new(None),
+            state: RwLock::new(ManagerState::Idle),
+            paused_connections: RwLock::new(HashSet::new()),
+            state_tx,
+            quarantine,
+            callbacks: CallbackRegistry::new(),
             background_handle: RwLock::new(None),
+            #[cfg(feature = "persistence")]
+            known_probes: RwLock::new(None),
+            #[cfg(feature = "persistence")]
+            known_probes_handle: RwLock::new(None),
+            #[cfg(feature = "persistence")]
+            timer_store_handle: RwLock::new(None),
+            defaults_handle: RwLock::new(None),
             is_running: Arc::new(AtomicBool::new(false)),
-        })
+        }
     }
 
     /// Initialize Bluetooth and start scanning for probes.
@@ -80,45 +444,437 @@ impl DeviceManager {
 
         info!("Starting device manager scanning");
 
-        self.scanner.start_scanning().await?;
-        self.is_running.store(true, Ordering::SeqCst);
+        let handle = match &self.backend {
+            Backend::Ble(scanner) => {
+                scanner.start_scanning().await?;
+                self.is_running.store(true, Ordering::SeqCst);
+                if let Some(duty_cycle) = scanner.scan_options().duty_cycle {
+                    let handle = self.spawn_duty_cycle_task(scanner.clone(), duty_cycle);
+                    *self.duty_cycle_handle.write() = Some(handle);
+                }
+                *self.wake_handle.write() = Some(self.spawn_wake_task(scanner.clone()));
+                self.spawn_ble_task(scanner.clone())
+            }
+            #[cfg(feature = "gateway")]
+            Backend::Remote(remote) => {
+                self.is_running.store(true, Ordering::SeqCst);
+                self.spawn_remote_task(remote.clone())
+            }
+            #[cfg(feature = "simulator")]
+            Backend::Simulated(simulators) => {
+                self.is_running.store(true, Ordering::SeqCst);
+                self.spawn_simulated_task(simulators.clone())
+            }
+        };
+
+        *self.background_handle.write() = Some(handle);
+        self.resume_probes();
+        self.set_state(ManagerState::Scanning);
 
-        // Start background task to process discovery events
-        let scanner = self.scanner.clone();
+        Ok(())
+    }
+
+    /// Start the background task that processes BLE discovery events.
+    fn spawn_ble_task(&self, scanner: Arc<BleScanner>) -> crate::runtime::TaskHandle<()> {
         let probes = self.probes.clone();
         let probe_discovered_tx = self.probe_discovered_tx.clone();
         let probe_stale_tx = self.probe_stale_tx.clone();
+        let probe_id_conflict_tx = self.probe_id_conflict_tx.clone();
         let is_running = self.is_running.clone();
+        let quarantine = self.quarantine.clone();
+        let scan_phase = self.scan_phase.clone();
+        let scan_watchdog = self.scan_watchdog.clone();
+        let scan_restarted_tx = self.scan_restarted_tx.clone();
 
-        let handle = tokio::spawn(async move {
+        crate::runtime::spawn(async move {
             let mut rx = scanner.subscribe();
+            let mut advertisements = scanner.subscribe_advertisements();
+            let mut reported_conflicts = Vec::new();
+            let mut watchdog = ScanWatchdog::new(
+                scan_watchdog.read().unwrap_or(Self::DEFAULT_SCAN_WATCHDOG),
+                tokio::time::Instant::now(),
+            );
+            // An interval rather than a sleep per select, which every
+            // advertisement would restart
+            let mut housekeeping = Self::housekeeping_interval();
 
             while is_running.load(Ordering::SeqCst) {
                 tokio::select! {
                     Ok(event) = rx.recv() => {
+                        let timeout = scan_watchdog.read().unwrap_or(Self::DEFAULT_SCAN_WATCHDOG);
+                        watchdog.advertisement(timeout, tokio::time::Instant::now());
                         Self::handle_discovery_event(
                             event,
                             &probes,
                             &probe_discovered_tx,
+                            &quarantine,
                         ).await;
                     }
-                    _ = tokio::time::sleep(Duration::from_secs(1)) => {
+                    Ok(event) = advertisements.recv() => {
+                        let timeout = scan_watchdog.read().unwrap_or(Self::DEFAULT_SCAN_WATCHDOG);
+                        watchdog.advertisement(timeout, tokio::time::Instant::now());
+                        // New probes still need a peripheral from the discovery path
+                        let serial_key = format!("{:08X}", event.advertising_data.serial_number);
+                        let existing = probes.read().get(&serial_key).cloned();
+                        if let Some(probe) = existing {
+                            probe.update_from_advertising(&event.advertising_data, event.rssi);
+                        }
+                    }
+                    _ = housekeeping.tick() => {
                         // Check for stale probes
                         Self::check_stale_probes(&probes, &probe_stale_tx);
+                        Self::check_heartbeats(&probes);
+                        Self::refresh_connected_rssi(&probes);
+                        Self::check_id_conflicts(
+                            &probes,
+                            &probe_id_conflict_tx,
+                            &mut reported_conflicts,
+                        );
+
+                        let listening = scan_phase.read().map_or(true, |p| p.is_scanning());
+                        let seen_probes = probes.read().values().any(|p| !p.is_external());
+                        let expecting = scan_watchdog.read().is_some() && listening && seen_probes;
+                        if let Some(silent_for) =
+                            watchdog.check(expecting, tokio::time::Instant::now())
+                        {
+                            warn!("No advertisements for {:?}, restarting BLE scan", silent_for);
+                            if let Err(e) = scanner.recover().await {
+                                warn!("Failed to restart BLE scan: {}", e);
+                            }
+                            let _ = scan_restarted_tx.send(ScanRestarted {
+                                silent_for,
+                                restarted_at: Utc::now(),
+                            });
+                        }
                     }
                 }
             }
 
             debug!("Device manager background task ended");
-        });
+        })
+    }
 
-        *self.background_handle.write() = Some(handle);
+    /// Start the task that pauses and resumes scanning on a duty cycle.
+    fn spawn_duty_cycle_task(
+        &self,
+        scanner: Arc<BleScanner>,
+        duty_cycle: DutyCycle,
+    ) -> crate::runtime::TaskHandle<()> {
+        let probes = self.probes.clone();
+        let scan_phase = self.scan_phase.clone();
+        let scan_phase_tx = self.scan_phase_tx.clone();
+        let is_running = self.is_running.clone();
 
-        Ok(())
+        crate::runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(250));
+            let mut period_start = tokio::time::Instant::now();
+
+            while is_running.load(Ordering::SeqCst) {
+                ticker.tick().await;
+                let now = tokio::time::Instant::now();
+                if now.duration_since(period_start) >= duty_cycle.period {
+                    period_start = now;
+                }
+
+                let continuous = Self::needs_continuous_scan(&probes.read());
+                let phase = duty_cycle.phase(continuous, now.duration_since(period_start));
+                if *scan_phase.read() == Some(phase) {
+                    continue;
+                }
+
+                if let Err(e) = scanner.set_listening(phase.is_scanning()).await {
+                    warn!("Failed to apply scan duty cycle: {}", e);
+                    continue;
+                }
+                debug!("Scan phase: {:?}", phase);
+                *scan_phase.write() = Some(phase);
+                let _ = scan_phase_tx.send(phase);
+            }
+
+            debug!("Scan duty cycle task ended");
+        })
+    }
+
+    /// Start the task that detects host sleep and recovers afterwards.
+    ///
+    /// BLE connections don't survive sleep, although the platform may still
+    /// report them, so on wake the adapter scan is restarted and connected
+    /// probes reconnect.
+    fn spawn_wake_task(&self, scanner: Arc<BleScanner>) -> crate::runtime::TaskHandle<()> {
+        let probes = self.probes.clone();
+        let wake_tx = self.wake_tx.clone();
+        let is_running = self.is_running.clone();
+
+        crate::runtime::spawn(async move {
+            let mut detector = SleepDetector::new(
+                SleepDetector::INTERVAL,
+                Utc::now(),
+                tokio::time::Instant::now(),
+            );
+
+            while is_running.load(Ordering::SeqCst) {
+                tokio::time::sleep(SleepDetector::INTERVAL).await;
+                let Some(slept) = detector.check(Utc::now(), tokio::time::Instant::now()) else {
+                    continue;
+                };
+
+                info!("Host woke after about {:?}, recovering", slept);
+                let _ = wake_tx.send(WakeEvent {
+                    slept,
+                    detected_at: Utc::now(),
+                });
+
+                if let Err(e) = scanner.recover().await {
+                    warn!("Failed to recover BLE scan after wake: {}", e);
+                }
+                let connected: Vec<_> = probes
+                    .read()
+                    .values()
+                    .filter(|p| !p.is_external())
+                    .cloned()
+                    .collect();
+                for probe in connected {
+                    if let Err(e) = probe.recycle_connection().await {
+                        warn!(
+                            "Failed to reconnect probe {} after wake: {}",
+                            probe.serial_number_string(),
+                            e
+                        );
+                    }
+                }
+            }
+
+            debug!("Wake detection task ended");
+        })
+    }
+
+    /// Count the Combustion probes among `probes`, leaving out external
+    /// sensor sources.
+    fn combustion_probe_count(probes: &HashMap<String, Arc<Probe>>) -> usize {
+        probes.values().filter(|probe| !probe.is_external()).count()
+    }
+
+    /// Check if any probe needs every advertisement: a stale probe may be
+    /// about to come back, and instant read updates several times a second.
+    fn needs_continuous_scan(probes: &HashMap<String, Arc<Probe>>) -> bool {
+        probes
+            .values()
+            .filter(|p| !p.is_external())
+            .any(|p| p.is_stale() || p.mode() == ProbeMode::InstantRead)
+    }
+
+    /// Start the background task that processes snapshots from a gateway.
+    #[cfg(feature = "gateway")]
+    fn spawn_remote_task(&self, remote: Arc<RemoteBackend>) -> crate::runtime::TaskHandle<()> {
+        let probes = self.probes.clone();
+        let probe_discovered_tx = self.probe_discovered_tx.clone();
+        let probe_stale_tx = self.probe_stale_tx.clone();
+        let probe_id_conflict_tx = self.probe_id_conflict_tx.clone();
+        let is_running = self.is_running.clone();
+        let quarantine = self.quarantine.clone();
+
+        crate::runtime::spawn(async move {
+            let mut rx = remote.subscribe_snapshots();
+            let mut reported_conflicts = Vec::new();
+            let mut housekeeping = Self::housekeeping_interval();
+
+            while is_running.load(Ordering::SeqCst) {
+                tokio::select! {
+                    Ok(snapshot) = rx.recv() => {
+                        let serial_key = snapshot.serial_number_string();
+                        let existing = probes.read().get(&serial_key).cloned();
+
+                        let probe = match existing {
+                            Some(probe) => probe,
+                            None => {
+                                if Self::combustion_probe_count(&probes.read()) >= MAX_PROBES {
+                                    warn!(
+                                        "Maximum probe count ({}) reached, ignoring new probe",
+                                        MAX_PROBES
+                                    );
+                                    continue;
+                                }
+
+                                let probe = Arc::new(Probe::with_transport(
+                                    format!("gateway:{}", serial_key),
+                                    snapshot.serial_number,
+                                    remote.transport(snapshot.serial_number),
+                                ));
+                                probe.set_quarantine(quarantine.clone());
+                                info!("Discovered remote probe: {}", serial_key);
+                                probes.write().insert(serial_key, probe.clone());
+                                probe
+                            }
+                        };
+
+                        probe.update_from_snapshot(&snapshot);
+                        let _ = probe_discovered_tx.send(probe);
+                    }
+                    _ = housekeeping.tick() => {
+                        Self::check_stale_probes(&probes, &probe_stale_tx);
+                        Self::check_heartbeats(&probes);
+                        Self::refresh_connected_rssi(&probes);
+                        Self::check_id_conflicts(
+                            &probes,
+                            &probe_id_conflict_tx,
+                            &mut reported_conflicts,
+                        );
+                    }
+                }
+            }
+
+            debug!("Device manager remote task ended");
+        })
+    }
+
+    /// Start scanning, stopping it again when the returned guard is dropped.
+    ///
+    /// Dropping the guard shuts the manager down as dropping the manager
+    /// does; call [`ShutdownGuard::shutdown`] instead to wait for the
+    /// cleanup to finish.
+    ///
+    /// ```rust,no_run
+    /// # use combustion_rust_ble::DeviceManager;
+    /// # async fn example(manager: &DeviceManager) -> combustion_rust_ble::Result<()> {
+    /// {
+    ///     let scanning = manager.start_scanning_guarded().await?;
+    ///     tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+    ///     println!("Found {} probes", scanning.probe_count());
+    /// } // Scanning stops and probes disconnect here
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if scanning fails to start.
+    pub async fn start_scanning_guarded(&self) -> Result<ShutdownGuard<'_>> {
+        self.start_scanning().await?;
+        Ok(ShutdownGuard {
+            manager: self,
+            armed: true,
+        })
     }
 
     /// Stop scanning for probes.
+    ///
+    /// Also ends a pause.
     pub async fn stop_scanning(&self) -> Result<()> {
+        self.stop_backend().await?;
+        self.resume_probes();
+        self.set_state(ManagerState::Idle);
+        Ok(())
+    }
+
+    /// Pause scanning and reconnection, keeping the discovered probes.
+    ///
+    /// Use before the host sleeps; [`resume`](Self::resume) picks up where
+    /// the manager left off. Probes keep their state and connections, but a
+    /// lost connection is not re-established while paused.
+    pub async fn pause(&self) -> Result<()> {
+        if self.is_paused() {
+            return Ok(());
+        }
+
+        info!("Pausing device manager");
+        self.stop_backend().await?;
+        let mut paused_connections = self.paused_connections.write();
+        paused_connections.clear();
+        for (key, probe) in self.probes.read().iter() {
+            probe.set_reconnection_suspended(true);
+            if probe.connection_state().is_connected() || probe.is_maintaining_connection() {
+                paused_connections.insert(key.clone());
+            }
+        }
+        drop(paused_connections);
+        self.set_state(ManagerState::Paused);
+        Ok(())
+    }
+
+    /// Resume scanning and reconnection after [`pause`](Self::pause).
+    ///
+    /// Probes that were connected when the manager paused and lost their
+    /// connection since are connected again, unless
+    /// [disconnected](Probe::disconnect) in the meantime. Does nothing unless
+    /// paused.
+    pub async fn resume(&self) -> Result<()> {
+        if !self.is_paused() {
+            return Ok(());
+        }
+
+        info!("Resuming device manager");
+        self.start_scanning().await?;
+        self.reconnect_paused_probes();
+        Ok(())
+    }
+
+    /// Reconnect the probes that were connected when the manager paused.
+    fn reconnect_paused_probes(&self) {
+        let paused_connections = std::mem::take(&mut *self.paused_connections.write());
+        let probes = self.probes.read();
+        for probe in paused_connections.iter().filter_map(|key| probes.get(key)) {
+            if probe.is_disconnected_by_user()
+                || probe.connection_state() != crate::ble::ConnectionState::Disconnected
+            {
+                continue;
+            }
+
+            info!("Reconnecting probe {}", probe.serial_number_string());
+            let probe = probe.clone();
+            crate::runtime::spawn(async move {
+                if let Err(e) = probe.connect().await {
+                    warn!(
+                        "Failed to reconnect probe {}: {}",
+                        probe.serial_number_string(),
+                        e
+                    );
+                }
+            });
+        }
+    }
+
+    /// Check if the manager is paused.
+    pub fn is_paused(&self) -> bool {
+        self.state() == ManagerState::Paused
+    }
+
+    /// Get the manager's current state.
+    pub fn state(&self) -> ManagerState {
+        *self.state.read()
+    }
+
+    /// Subscribe to manager state changes.
+    pub fn subscribe_state(&self) -> broadcast::Receiver<ManagerState> {
+        self.state_tx.subscribe()
+    }
+
+    /// Register a callback for manager state changes.
+    pub fn on_state_changed<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(ManagerState) + Send + Sync + 'static,
+    {
+        self.callbacks
+            .spawn("state_changed", self.state_tx.subscribe(), callback)
+    }
+
+    /// Update the state and publish changes.
+    fn set_state(&self, state: ManagerState) {
+        let previous = std::mem::replace(&mut *self.state.write(), state);
+        if previous != state {
+            debug!("Device manager state: {:?} -> {:?}", previous, state);
+            let _ = self.state_tx.send(state);
+        }
+    }
+
+    /// Let probes reconnect again.
+    fn resume_probes(&self) {
+        for probe in self.probes.read().values() {
+            probe.set_reconnection_suspended(false);
+        }
+    }
+
+    /// Stop the backend and the background tasks.
+    async fn stop_backend(&self) -> Result<()> {
         if !self.is_running.load(Ordering::SeqCst) {
             return Ok(());
         }
@@ -126,34 +882,319 @@ impl DeviceManager {
         info!("Stopping device manager scanning");
 
         self.is_running.store(false, Ordering::SeqCst);
-        self.scanner.stop_scanning().await?;
+        for handle in [&self.duty_cycle_handle, &self.wake_handle] {
+            if let Some(handle) = handle.write().take() {
+                handle.abort();
+            }
+        }
+        *self.scan_phase.write() = None;
+        match &self.backend {
+            Backend::Ble(scanner) => scanner.stop_scanning().await?,
+            #[cfg(feature = "gateway")]
+            Backend::Remote(_) => {}
+            #[cfg(feature = "simulator")]
+            Backend::Simulated(_) => {}
+        }
 
-        // Wait for background task
-        if let Some(handle) = self.background_handle.write().take() {
+        // Wait for background task, without holding the lock across the
+        // await so the future stays `Send`
+        let handle = self.background_handle.write().take();
+        if let Some(handle) = handle {
             let _ = handle.await;
         }
 
         Ok(())
     }
 
-    /// Get all discovered probes.
-    pub fn probes(&self) -> HashMap<String, Arc<Probe>> {
-        self.probes.read().clone()
+    /// Start the background task that discovers simulated probes.
+    #[cfg(feature = "simulator")]
+    fn spawn_simulated_task(
+        &self,
+        simulators: Arc<Vec<Arc<SimulatedProbe>>>,
+    ) -> crate::runtime::TaskHandle<()> {
+        let probes = self.probes.clone();
+        let probe_discovered_tx = self.probe_discovered_tx.clone();
+        let probe_stale_tx = self.probe_stale_tx.clone();
+        let probe_id_conflict_tx = self.probe_id_conflict_tx.clone();
+        let is_running = self.is_running.clone();
+        let quarantine = self.quarantine.clone();
+
+        crate::runtime::spawn(async move {
+            let mut reported_conflicts = Vec::new();
+            for simulator in simulators.iter() {
+                let serial_key = format!("{:08X}", simulator.serial_number());
+                if probes.read().contains_key(&serial_key) {
+                    continue;
+                }
+                if Self::combustion_probe_count(&probes.read()) >= MAX_PROBES {
+                    warn!(
+                        "Maximum probe count ({}) reached, ignoring new probe",
+                        MAX_PROBES
+                    );
+                    break;
+                }
+
+                simulator.start();
+                let probe = simulator.probe();
+                probe.set_quarantine(quarantine.clone());
+                info!("Discovered simulated probe: {}", serial_key);
+                probes.write().insert(serial_key, probe.clone());
+                let _ = probe_discovered_tx.send(probe);
+            }
+
+            let mut housekeeping = Self::housekeeping_interval();
+            while is_running.load(Ordering::SeqCst) {
+                housekeeping.tick().await;
+                Self::check_stale_probes(&probes, &probe_stale_tx);
+                Self::check_heartbeats(&probes);
+                Self::refresh_connected_rssi(&probes);
+                Self::check_id_conflicts(&probes, &probe_id_conflict_tx, &mut reported_conflicts);
+            }
+
+            debug!("Device manager simulator task ended");
+        })
+    }
+
+    /// Get all discovered probes.
+    ///
+    /// Clones the probe map; to poll state frequently, prefer
+    /// [`for_each_probe`](Self::for_each_probe) or
+    /// [`probes_snapshot`](Self::probes_snapshot).
+    pub fn probes(&self) -> HashMap<String, Arc<Probe>> {
+        self.probes.read().clone()
+    }
+
+    /// Get handles to all discovered probes, ordered by serial number.
+    ///
+    /// Cheaper than [`probes`](Self::probes) for listings, and the handles
+    /// don't keep probes alive once the manager drops them. Each handle
+    /// looks its probe up by serial number, so it reaches the new instance
+    /// if the probe is forgotten and discovered again.
+    pub fn probe_handles(&self) -> Vec<ProbeHandle> {
+        let mut handles: Vec<_> = self
+            .probes
+            .read()
+            .values()
+            .map(|probe| ProbeHandle::in_manager(probe.serial_number(), &self.probes))
+            .collect();
+        handles.sort_by_key(|h| h.serial_number());
+        handles
+    }
+
+    /// Get a handle to a probe by serial number (as hex string, e.g.,
+    /// "100120BA").
+    pub fn probe_handle(&self, serial_number: &str) -> Option<ProbeHandle> {
+        self.probes
+            .read()
+            .get(serial_number)
+            .map(|probe| ProbeHandle::in_manager(probe.serial_number(), &self.probes))
+    }
+
+    /// Call `f` with each discovered probe, in no particular order, without
+    /// cloning the probe map.
+    ///
+    /// The probe list stays locked while `f` runs, so `f` must not call
+    /// back into the `DeviceManager` at all: even a lookup can deadlock
+    /// once a probe is being discovered or removed. Use [`probes`](Self::probes)
+    /// when the callback needs the manager.
+    pub fn for_each_probe(&self, mut f: impl FnMut(&Arc<Probe>)) {
+        for probe in self.probes.read().values() {
+            f(probe);
+        }
+    }
+
+    /// Capture snapshots of all discovered probes, ordered by serial number.
+    ///
+    /// The snapshots are taken together, with no probe discovered or
+    /// removed in between, and share the same
+    /// [`captured_at`](ProbeSnapshot::captured_at) time.
+    pub fn probes_snapshot(&self) -> Vec<ProbeSnapshot> {
+        let captured_at = Utc::now();
+        let mut snapshots: Vec<_> = self
+            .probes
+            .read()
+            .values()
+            .map(|p| p.snapshot_at(captured_at))
+            .collect();
+        snapshots.sort_by_key(|s| s.serial_number);
+        snapshots
+    }
+
+    /// Get all discovered devices, ordered by serial number.
+    ///
+    /// Use this to handle a mixed fleet through [`CombustionDevice`];
+    /// [`probes`](Self::probes) returns the probes with their full API.
+    pub fn devices(&self) -> Vec<Arc<dyn CombustionDevice>> {
+        let mut probes: Vec<_> = self.probes.read().values().cloned().collect();
+        probes.sort_by_key(|p| p.serial_number());
+        probes
+            .into_iter()
+            .map(|p| p as Arc<dyn CombustionDevice>)
+            .collect()
+    }
+
+    /// Get a shared handle to the probe registry for background tasks.
+    #[cfg(any(feature = "cloud", feature = "gateway"))]
+    pub(crate) fn probe_registry(&self) -> Arc<RwLock<HashMap<String, Arc<Probe>>>> {
+        self.probes.clone()
+    }
+
+    /// Get a specific probe by serial number (as hex string, e.g., "100120BA").
+    pub fn get_probe(&self, serial_number: &str) -> Option<Arc<Probe>> {
+        self.probes.read().get(serial_number).cloned()
+    }
+
+    /// Resolve a probe's serial number (e.g., "100120BA") to its current
+    /// platform identifier.
+    ///
+    /// Persist serial numbers rather than identifiers: on macOS identifiers
+    /// change between app launches. Returns `None` until the probe has been
+    /// seen in the current scan session.
+    pub fn resolve_identifier(&self, serial_number: &str) -> Option<String> {
+        self.get_probe(serial_number).map(|p| p.identifier())
+    }
+
+    /// Get the nearest probe by signal strength.
+    pub fn get_nearest_probe(&self) -> Option<Arc<Probe>> {
+        self.probes
+            .read()
+            .values()
+            .filter(|p| !p.is_stale())
+            .max_by_key(|p| p.rssi().unwrap_or(i16::MIN))
+            .cloned()
+    }
+
+    /// Wait for the user to hold a probe next to the adapter and return it.
+    ///
+    /// Each probe's signal strength is sampled to learn its baseline; the
+    /// probe whose RSSI then rises at least `threshold` dB above that
+    /// baseline, and stays there for a moment, is returned. Answers "which
+    /// probe is this?" without a display, e.g. when pairing probes to
+    /// zones in a headless install. 15-20 dB works for most adapters.
+    ///
+    /// Waits indefinitely while scanning; wrap in `tokio::time::timeout` to
+    /// give up.
+    ///
+    /// ```rust,no_run
+    /// # use combustion_rust_ble::DeviceManager;
+    /// # use std::time::Duration;
+    /// # async fn example(manager: &DeviceManager) -> combustion_rust_ble::Result<()> {
+    /// println!("Hold the probe for the smoker against the adapter...");
+    /// if let Ok(probe) =
+    ///     tokio::time::timeout(Duration::from_secs(60), manager.select_probe_interactively(15))
+    ///         .await
+    /// {
+    ///     println!("That's {}", probe?.serial_number_string());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if `threshold` is not positive.
+    pub async fn select_probe_interactively(&self, threshold: i16) -> Result<Arc<Probe>> {
+        if threshold <= 0 {
+            return Err(Error::InvalidParameter {
+                name: "threshold".to_string(),
+                value: threshold.to_string(),
+            });
+        }
+
+        let mut detector = ProximityDetector::new(threshold);
+        let mut interval = tokio::time::interval(Self::PROXIMITY_SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let probes = self.probes();
+            let samples = probes
+                .iter()
+                .filter(|(_, probe)| !probe.is_stale())
+                .filter_map(|(key, probe)| Some((key.as_str(), probe.rssi()?)));
+            if let Some(key) = detector.update(samples) {
+                info!("Probe {} selected by proximity", key);
+                return Ok(probes[&key].clone());
+            }
+        }
+    }
+
+    /// Claim the probe the user physically acts on.
+    ///
+    /// Samples every probe in range and returns the one that, alone, is
+    /// taken out of its charger, changes mode or battery status, or spikes
+    /// in instant read, so a user can pick out their probe among many
+    /// without pairing. With a known probe registry set (see
+    /// `set_known_probes`), the claimed probe is remembered as the user's
+    /// own.
+    ///
+    /// Returns `None` if no probe acted alone within the options' window.
+    /// An action just before the window ends is waited on for up to the
+    /// quiet period more. See [`claim`](crate::claim) for an example.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if the options are invalid.
+    pub async fn claim_probe(&self, options: ClaimOptions) -> Result<Option<ProbeClaim>> {
+        options.validate()?;
+
+        let deadline = tokio::time::Instant::now() + options.window;
+        // A probe that keeps acting is never confirmed, so stop waiting
+        // once an action seen just before the deadline would have been
+        let extended_deadline = deadline + options.quiet_period;
+        let mut detector = ClaimDetector::new(options);
+        let mut interval = tokio::time::interval(Self::PROXIMITY_SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            let now = interval.tick().await;
+            // Let an action seen just before the deadline be confirmed
+            if now > extended_deadline || (now >= deadline && !detector.is_pending()) {
+                debug!("No probe claimed");
+                return Ok(None);
+            }
+            let probes: HashMap<u32, Arc<Probe>> = self
+                .probes()
+                .into_values()
+                .filter(|probe| !probe.is_stale())
+                .map(|probe| (probe.serial_number(), probe))
+                .collect();
+            let samples = probes
+                .iter()
+                .map(|(&serial, probe)| (serial, ClaimSample::of(probe)));
+            if let Some((serial, action)) = detector.update(now.into_std(), samples) {
+                let probe = probes[&serial].clone();
+                info!("Probe {} claimed: {}", probe.serial_number_string(), action);
+                return Ok(Some(ProbeClaim {
+                    remembered: self.remember_claimed(&probe),
+                    probe,
+                    action,
+                    claimed_at: Utc::now(),
+                }));
+            }
+        }
     }
 
-    /// Get a specific probe by serial number (as hex string, e.g., "100120BA").
-    pub fn get_probe(&self, serial_number: &str) -> Option<Arc<Probe>> {
-        self.probes.read().get(serial_number).cloned()
+    /// Remember a claimed probe in the known probe registry, if set.
+    #[cfg(feature = "persistence")]
+    fn remember_claimed(&self, probe: &Probe) -> bool {
+        let Some(registry) = self.known_probes() else {
+            return false;
+        };
+        match registry.remember(probe) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!(
+                    "Failed to remember claimed probe {}: {}",
+                    probe.serial_number_string(),
+                    e
+                );
+                false
+            }
+        }
     }
 
-    /// Get the nearest probe by signal strength.
-    pub fn get_nearest_probe(&self) -> Option<Arc<Probe>> {
-        self.probes
-            .read()
-            .values()
-            .filter(|p| !p.is_stale())
-            .max_by_key(|p| p.rssi().unwrap_or(i16::MIN))
-            .cloned()
+    #[cfg(not(feature = "persistence"))]
+    fn remember_claimed(&self, _probe: &Probe) -> bool {
+        false
     }
 
     /// Get probes sorted by signal strength (strongest first).
@@ -170,28 +1211,151 @@ impl DeviceManager {
         probes
     }
 
+    /// Group probes monitoring the same cook, by serial number (as hex
+    /// strings, e.g., "100120BA").
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ProbeNotFound`] if a serial number is unknown.
+    pub fn cook_group<S: AsRef<str>>(
+        &self,
+        name: impl Into<String>,
+        serial_numbers: &[S],
+    ) -> Result<CookGroup> {
+        let probes = serial_numbers
+            .iter()
+            .map(|serial| {
+                self.get_probe(serial.as_ref())
+                    .ok_or_else(|| Error::ProbeNotFound {
+                        identifier: serial.as_ref().to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CookGroup::new(name, probes))
+    }
+
     /// Subscribe to probe discovery events.
     pub fn subscribe_probe_discovered(&self) -> broadcast::Receiver<Arc<Probe>> {
         self.probe_discovered_tx.subscribe()
     }
 
+    /// Get the buffer of payloads that failed to parse, for bug reports.
+    ///
+    /// Disabled until given a capacity with
+    /// [`PayloadQuarantine::set_capacity`]. Shared by the scanner, which
+    /// quarantines unparseable advertisements, and by discovered probes,
+    /// which quarantine unparseable status notifications.
+    pub fn quarantine(&self) -> &PayloadQuarantine {
+        &self.quarantine
+    }
+
+    /// List a background task driven by this manager, such as a cloud
+    /// relay, with its callbacks until the handle is dropped.
+    #[cfg(feature = "cloud")]
+    pub(crate) fn track_task(
+        &self,
+        event: &'static str,
+        task: crate::runtime::TaskHandle<()>,
+    ) -> CallbackHandle {
+        self.callbacks.track(event, task)
+    }
+
+    /// Get the callbacks registered on this manager, for debugging.
+    pub fn callbacks(&self) -> Vec<CallbackInfo> {
+        self.callbacks.list()
+    }
+
+    /// Subscribe to reports of panicking callbacks.
+    ///
+    /// A callback that panics is reported here and keeps receiving events.
+    pub fn subscribe_callback_errors(&self) -> broadcast::Receiver<CallbackError> {
+        self.callbacks.subscribe_errors()
+    }
+
     /// Register a callback for when probes are discovered/updated.
     pub fn on_probe_discovered<F>(&self, callback: F) -> CallbackHandle
     where
         F: Fn(Arc<Probe>) + Send + Sync + 'static,
     {
-        let callback_id = self.callback_counter.fetch_add(1, Ordering::SeqCst);
-        let mut rx = self.probe_discovered_tx.subscribe();
+        self.callbacks.spawn(
+            "probe_discovered",
+            self.probe_discovered_tx.subscribe(),
+            callback,
+        )
+    }
 
-        let handle = tokio::spawn(async move {
-            while let Ok(probe) = rx.recv().await {
-                callback(probe);
-            }
-        });
+    /// Set how long the BLE scan may go without advertisements before it
+    /// is restarted, or `None` to never restart it.
+    ///
+    /// The watchdog only runs once probes have been discovered, and not
+    /// while a [`DutyCycle`] has the scan paused. After a restart that
+    /// brings no advertisements, the wait doubles up to five minutes, so
+    /// probes that are genuinely off don't cause a restart loop. Defaults to
+    /// [`DEFAULT_SCAN_WATCHDOG`](Self::DEFAULT_SCAN_WATCHDOG). Has no effect
+    /// on gateway and simulated managers.
+    pub fn set_scan_watchdog(&self, timeout: Option<Duration>) {
+        *self.scan_watchdog.write() = timeout;
+    }
 
-        CallbackHandle::new(callback_id, move || {
-            handle.abort();
-        })
+    /// Get how long the BLE scan may go without advertisements before it
+    /// is restarted.
+    pub fn scan_watchdog(&self) -> Option<Duration> {
+        *self.scan_watchdog.read()
+    }
+
+    /// Subscribe to BLE scan restarts by the watchdog.
+    pub fn subscribe_scan_restarted(&self) -> broadcast::Receiver<ScanRestarted> {
+        self.scan_restarted_tx.subscribe()
+    }
+
+    /// Register a callback for BLE scan restarts by the watchdog.
+    pub fn on_scan_restarted<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(ScanRestarted) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "scan_restarted",
+            self.scan_restarted_tx.subscribe(),
+            callback,
+        )
+    }
+
+    /// Subscribe to host wake events.
+    pub fn subscribe_wake(&self) -> broadcast::Receiver<WakeEvent> {
+        self.wake_tx.subscribe()
+    }
+
+    /// Register a callback for host wake events.
+    pub fn on_wake<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(WakeEvent) + Send + Sync + 'static,
+    {
+        self.callbacks
+            .spawn("wake", self.wake_tx.subscribe(), callback)
+    }
+
+    /// Get the phase of the scan duty cycle.
+    ///
+    /// Returns `None` unless scanning with [`ScanOptions::duty_cycle`] set.
+    pub fn scan_phase(&self) -> Option<ScanPhase> {
+        *self.scan_phase.read()
+    }
+
+    /// Subscribe to scan duty cycle phase changes.
+    pub fn subscribe_scan_phase(&self) -> broadcast::Receiver<ScanPhase> {
+        self.scan_phase_tx.subscribe()
+    }
+
+    /// Register a callback for scan duty cycle phase changes.
+    pub fn on_scan_phase_changed<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(ScanPhase) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "scan_phase_changed",
+            self.scan_phase_tx.subscribe(),
+            callback,
+        )
     }
 
     /// Subscribe to probe stale events.
@@ -204,18 +1368,360 @@ impl DeviceManager {
     where
         F: Fn(Arc<Probe>) + Send + Sync + 'static,
     {
-        let callback_id = self.callback_counter.fetch_add(1, Ordering::SeqCst);
-        let mut rx = self.probe_stale_tx.subscribe();
+        self.callbacks
+            .spawn("probe_stale", self.probe_stale_tx.subscribe(), callback)
+    }
+
+    /// Get the current probe ID conflicts among probes that are not stale.
+    pub fn probe_id_conflicts(&self) -> Vec<ProbeIdConflict> {
+        Self::find_id_conflicts(&self.probes.read())
+    }
+
+    /// Subscribe to probe ID conflict events.
+    ///
+    /// A conflict is reported once when it appears, and again if the set of
+    /// probes sharing the ID changes.
+    pub fn subscribe_probe_id_conflicts(&self) -> broadcast::Receiver<ProbeIdConflict> {
+        self.probe_id_conflict_tx.subscribe()
+    }
+
+    /// Register a callback for when probes with duplicate IDs are detected.
+    pub fn on_probe_id_conflict<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(ProbeIdConflict) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "probe_id_conflict",
+            self.probe_id_conflict_tx.subscribe(),
+            callback,
+        )
+    }
+
+    /// Assign free IDs to connected probes that share an ID.
+    ///
+    /// In each conflict one probe keeps the ID, preferring a disconnected
+    /// probe since its ID cannot be changed. The remaining connected probes
+    /// are moved to the lowest IDs not used by any probe that is not stale.
+    /// Disconnected probes are left alone, so connect them first to resolve
+    /// every conflict.
+    ///
+    /// Returns the serial numbers (as hex strings) and new IDs of the probes
+    /// that were reassigned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no free ID is left or a probe rejects its new ID.
+    /// Assignments made before the failure are kept.
+    pub async fn auto_assign_ids(&self) -> Result<Vec<(String, ProbeId)>> {
+        let (conflicts, mut used) = {
+            let probes = self.probes.read();
+            let used: Vec<ProbeId> = probes
+                .values()
+                .filter(|p| !p.is_stale() && !p.is_external())
+                .map(|p| p.id())
+                .collect();
+            (Self::find_id_conflicts(&probes), used)
+        };
+
+        let mut assigned = Vec::new();
+        for conflict in conflicts {
+            let members: Vec<_> = conflict
+                .serial_numbers
+                .iter()
+                .filter_map(|serial| self.get_probe(serial).map(|p| (serial.clone(), p)))
+                .collect();
+            let keeper = members
+                .iter()
+                .position(|(_, p)| !p.connection_state().is_connected())
+                .unwrap_or(0);
+
+            for (index, (serial, probe)) in members.into_iter().enumerate() {
+                if index == keeper {
+                    continue;
+                }
+                if !probe.connection_state().is_connected() {
+                    warn!(
+                        "Probe {} shares ID {} but is not connected, leaving it",
+                        serial, conflict.id
+                    );
+                    continue;
+                }
+
+                let id = (ProbeId::MIN..=ProbeId::MAX)
+                    .map(ProbeId::new)
+                    .find(|id| !used.contains(id))
+                    .ok_or_else(|| Error::InvalidParameter {
+                        name: "probe_id".to_string(),
+                        value: format!("no free ID for probe {}", serial),
+                    })?;
+
+                probe.set_id(id).await?;
+                info!(
+                    "Reassigned probe {} from ID {} to {}",
+                    serial, conflict.id, id
+                );
+                used.push(id);
+                assigned.push((serial, id));
+            }
+        }
+
+        Ok(assigned)
+    }
 
-        let handle = tokio::spawn(async move {
-            while let Ok(probe) = rx.recv().await {
-                callback(probe);
+    /// Use a registry of the user's own probes.
+    ///
+    /// Known probes are restored from the registry and refreshed in it as
+    /// they are discovered, and every
+    /// [`LAST_SEEN_SAVE_INTERVAL`](KnownProbeRegistry::LAST_SEEN_SAVE_INTERVAL)
+    /// after, and those marked for auto-connect are connected. Other probes are
+    /// still discovered but neither remembered nor connected; add them with
+    /// [`KnownProbeRegistry::remember`]. Must be called from within a Tokio
+    /// runtime.
+    #[cfg(feature = "persistence")]
+    pub fn set_known_probes(&self, registry: Arc<KnownProbeRegistry>) {
+        let mut rx = self.probe_discovered_tx.subscribe();
+        let discovered: Vec<_> = self.probes.read().values().cloned().collect();
+        let task_registry = registry.clone();
+        let probes = self.probes.clone();
+        let connecting = Arc::new(parking_lot::Mutex::new(HashSet::new()));
+        let handle = crate::runtime::spawn(async move {
+            for probe in discovered {
+                Self::handle_known_probe(&task_registry, &connecting, probe);
+            }
+            let mut save = tokio::time::interval(KnownProbeRegistry::LAST_SEEN_SAVE_INTERVAL);
+            save.tick().await;
+            loop {
+                tokio::select! {
+                    received = rx.recv() => match received {
+                        Ok(probe) => Self::handle_known_probe(&task_registry, &connecting, probe),
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = save.tick() => Self::refresh_known_probes(&task_registry, &probes),
+                }
             }
         });
 
-        CallbackHandle::new(callback_id, move || {
-            handle.abort();
-        })
+        if let Some(previous) = self.known_probes_handle.write().replace(handle) {
+            previous.abort();
+        }
+        *self.known_probes.write() = Some(registry);
+    }
+
+    /// Get the registry of the user's own probes, if set.
+    #[cfg(feature = "persistence")]
+    pub fn known_probes(&self) -> Option<Arc<KnownProbeRegistry>> {
+        self.known_probes.read().clone()
+    }
+
+    /// List the known probes with the discovered probe for each, ordered by
+    /// serial number.
+    ///
+    /// Probes not discovered since startup are listed with no probe, so
+    /// they can be shown as offline. Empty if no registry is set.
+    #[cfg(feature = "persistence")]
+    pub fn known_probe_statuses(&self) -> Vec<KnownProbeStatus> {
+        let Some(registry) = self.known_probes() else {
+            return Vec::new();
+        };
+        let probes = self.probes.read();
+        registry
+            .list()
+            .into_iter()
+            .map(|known| KnownProbeStatus {
+                probe: probes.get(&known.serial_number_string()).cloned(),
+                known,
+            })
+            .collect()
+    }
+
+    /// Refresh the discovered probes in the registry, saving e.g. their
+    /// battery on-time.
+    #[cfg(feature = "persistence")]
+    fn refresh_known_probes(
+        registry: &KnownProbeRegistry,
+        probes: &Arc<RwLock<HashMap<String, Arc<Probe>>>>,
+    ) {
+        let probes: Vec<_> = probes.read().values().cloned().collect();
+        for probe in probes {
+            if let Err(e) = registry.refresh(&probe) {
+                warn!(
+                    "Failed to update known probe {}: {}",
+                    probe.serial_number_string(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Restore and refresh a discovered probe in the registry and connect it
+    /// if it is marked for auto-connect, unless it is already connecting or
+    /// the user disconnected it.
+    ///
+    /// `connecting` holds the serial numbers of probes being auto-connected,
+    /// so advertisements that arrive before the connection starts do not
+    /// start another.
+    #[cfg(feature = "persistence")]
+    fn handle_known_probe(
+        registry: &KnownProbeRegistry,
+        connecting: &Arc<parking_lot::Mutex<HashSet<u32>>>,
+        probe: Arc<Probe>,
+    ) {
+        registry.restore(&probe);
+        let known = match registry.refresh(&probe) {
+            Ok(Some(known)) => known,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(
+                    "Failed to update known probe {}: {}",
+                    probe.serial_number_string(),
+                    e
+                );
+                return;
+            }
+        };
+        if !known.auto_connect
+            || probe.connection_state() != crate::ble::ConnectionState::Disconnected
+            || probe.is_disconnected_by_user()
+            || !connecting.lock().insert(known.serial_number)
+        {
+            return;
+        }
+
+        info!(
+            "Auto-connecting known probe {}",
+            known.serial_number_string()
+        );
+        let connecting = connecting.clone();
+        crate::runtime::spawn(async move {
+            if let Err(e) = probe.connect().await {
+                warn!(
+                    "Failed to auto-connect probe {}: {}",
+                    probe.serial_number_string(),
+                    e
+                );
+            }
+            connecting.lock().remove(&probe.serial_number());
+        });
+    }
+
+    /// Keep the probes' timers in `store`, so they survive a restart.
+    ///
+    /// Each probe schedules the timers the store holds for it when it is
+    /// discovered; see [`Probe::set_timer_store`]. Must be called from within
+    /// a Tokio runtime.
+    #[cfg(feature = "persistence")]
+    pub fn set_timer_store(&self, store: Arc<TimerStore>) {
+        let mut rx = self.probe_discovered_tx.subscribe();
+        let discovered: Vec<_> = self.probes.read().values().cloned().collect();
+        let handle = crate::runtime::spawn(async move {
+            for probe in discovered {
+                probe.set_timer_store(store.clone());
+            }
+            loop {
+                match rx.recv().await {
+                    Ok(probe) => probe.set_timer_store(store.clone()),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        if let Some(previous) = self.timer_store_handle.write().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    /// Start default cooks on probes by color or ID when they connect.
+    ///
+    /// Every probe, including those discovered later, is given `defaults`;
+    /// see [`Probe::set_cook_defaults`]. Must be called from within a Tokio
+    /// runtime.
+    pub fn set_probe_defaults(&self, defaults: ProbeDefaults) {
+        let defaults = Arc::new(defaults);
+        let mut rx = self.probe_discovered_tx.subscribe();
+        for probe in self.probes.read().values() {
+            probe.set_cook_defaults(Some(defaults.clone()));
+        }
+        let handle = crate::runtime::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(probe) => probe.set_cook_defaults(Some(defaults.clone())),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        if let Some(previous) = self.defaults_handle.write().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    /// Apply the settings of a configuration file.
+    ///
+    /// Starts the [`probe_defaults`](crate::persistence::Config::probe_defaults)
+    /// on connecting probes, if any are configured. Must be called from
+    /// within a Tokio runtime.
+    #[cfg(feature = "persistence")]
+    pub fn apply_config(&self, config: &crate::persistence::Config) {
+        if !config.probe_defaults.is_empty() {
+            self.set_probe_defaults(config.probe_defaults.clone());
+        }
+    }
+
+    /// Register a pseudo-probe for readings from another thermometer.
+    ///
+    /// The source's probe is added to [`probes`](Self::probes) under
+    /// `serial_number` and announced to discovery subscribers, so its
+    /// readings flow through the same APIs as a real probe's. Push readings
+    /// with [`ExternalSensorSource::push`]. External probes are ignored when
+    /// checking for duplicate probe IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if a probe with `serial_number`
+    /// is already known.
+    pub fn add_external_source(
+        &self,
+        name: impl Into<String>,
+        serial_number: u32,
+    ) -> Result<Arc<ExternalSensorSource>> {
+        let serial_key = format!("{:08X}", serial_number);
+        let source = Arc::new(ExternalSensorSource::new(name.into(), serial_number));
+        {
+            let mut probes = self.probes.write();
+            if probes.contains_key(&serial_key) {
+                return Err(Error::InvalidParameter {
+                    name: "serial_number".to_string(),
+                    value: serial_key,
+                });
+            }
+            probes.insert(serial_key.clone(), source.probe());
+        }
+
+        info!(
+            "Added external sensor source {} as {}",
+            source.name(),
+            serial_key
+        );
+        let _ = self.probe_discovered_tx.send(source.probe());
+        Ok(source)
+    }
+
+    /// Unregister an external sensor source.
+    ///
+    /// Returns `false` if it was not registered.
+    pub fn remove_external_source(&self, source: &ExternalSensorSource) -> bool {
+        let serial_key = source.probe().serial_number_string();
+        let mut probes = self.probes.write();
+        match probes.get(&serial_key) {
+            Some(probe) if Arc::ptr_eq(probe, &source.probe()) => {
+                probes.remove(&serial_key);
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Enable MeatNet support for Display/Booster nodes.
@@ -236,6 +1742,10 @@ impl DeviceManager {
     }
 
     /// Clean shutdown of all connections and scanning.
+    ///
+    /// Returns once scanning has stopped and every probe has been
+    /// disconnected. Dropping the manager instead starts the same cleanup
+    /// without waiting for it.
     pub async fn shutdown(&self) -> Result<()> {
         info!("Shutting down device manager");
 
@@ -263,7 +1773,15 @@ impl DeviceManager {
 
     /// Check if scanning is active.
     pub fn is_scanning(&self) -> bool {
-        self.scanner.is_scanning()
+        match &self.backend {
+            Backend::Ble(scanner) => scanner.is_scanning(),
+            #[cfg(feature = "gateway")]
+            Backend::Remote(remote) => {
+                self.is_running.load(Ordering::SeqCst) && remote.is_connected()
+            }
+            #[cfg(feature = "simulator")]
+            Backend::Simulated(_) => self.is_running.load(Ordering::SeqCst),
+        }
     }
 
     /// Handle a discovery event from the scanner.
@@ -274,6 +1792,7 @@ impl DeviceManager {
         event: ProbeDiscoveryEvent,
         probes: &Arc<RwLock<HashMap<String, Arc<Probe>>>>,
         probe_discovered_tx: &broadcast::Sender<Arc<Probe>>,
+        quarantine: &PayloadQuarantine,
     ) {
         let advertising_data = match &event.advertising_data {
             Some(data) => data,
@@ -301,13 +1820,26 @@ impl DeviceManager {
 
         let probe = match existing {
             Some(probe) => {
+                // Follow identifier changes so connecting uses the live peripheral
+                let previous = probe.identifier();
+                if previous != ble_identifier
+                    && probe.rebind(ble_identifier.clone(), event.peripheral)
+                {
+                    info!(
+                        "Probe {} is now {} (was {})",
+                        probe.serial_number_string(),
+                        ble_identifier,
+                        previous
+                    );
+                }
+
                 // Update existing probe with new data
                 probe.update_from_advertising(advertising_data, event.rssi);
                 probe
             }
             None => {
                 // Check if we've hit the limit
-                if probes.read().len() >= MAX_PROBES {
+                if Self::combustion_probe_count(&probes.read()) >= MAX_PROBES {
                     warn!(
                         "Maximum probe count ({}) reached, ignoring new probe",
                         MAX_PROBES
@@ -321,6 +1853,7 @@ impl DeviceManager {
                     event.peripheral,
                     serial_number,
                 ));
+                probe.set_quarantine(quarantine.clone());
                 probe.update_from_advertising(advertising_data, event.rssi);
 
                 info!(
@@ -338,6 +1871,101 @@ impl DeviceManager {
         let _ = probe_discovered_tx.send(probe);
     }
 
+    /// Find probes that are not stale and share an ID.
+    fn find_id_conflicts(probes: &HashMap<String, Arc<Probe>>) -> Vec<ProbeIdConflict> {
+        let mut by_id: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+        for (serial, probe) in probes
+            .iter()
+            .filter(|(_, p)| !p.is_stale() && !p.is_external())
+        {
+            by_id
+                .entry(probe.id().as_u8())
+                .or_default()
+                .push(serial.clone());
+        }
+
+        by_id
+            .into_iter()
+            .filter(|(_, serials)| serials.len() > 1)
+            .map(|(id, mut serial_numbers)| {
+                serial_numbers.sort();
+                ProbeIdConflict {
+                    id: ProbeId::new(id),
+                    serial_numbers,
+                }
+            })
+            .collect()
+    }
+
+    /// Check for probe ID conflicts and emit events for new ones.
+    fn check_id_conflicts(
+        probes: &Arc<RwLock<HashMap<String, Arc<Probe>>>>,
+        probe_id_conflict_tx: &broadcast::Sender<ProbeIdConflict>,
+        reported: &mut Vec<ProbeIdConflict>,
+    ) {
+        let conflicts = Self::find_id_conflicts(&probes.read());
+        for conflict in &conflicts {
+            if !reported.contains(conflict) {
+                warn!(
+                    "Probes {} share ID {}",
+                    conflict.serial_numbers.join(", "),
+                    conflict.id
+                );
+                let _ = probe_id_conflict_tx.send(conflict.clone());
+            }
+        }
+        *reported = conflicts;
+    }
+
+    /// Create the interval driving the periodic checks of a backend task.
+    fn housekeeping_interval() -> tokio::time::Interval {
+        let mut interval = tokio::time::interval(Self::HOUSEKEEPING_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval
+    }
+
+    /// Check the probes' heartbeats, recycling unhealthy connections when
+    /// their policy asks for it.
+    fn check_heartbeats(probes: &Arc<RwLock<HashMap<String, Arc<Probe>>>>) {
+        for probe in probes.read().values() {
+            let Some(event) = probe.check_heartbeat() else {
+                continue;
+            };
+            if event.recycling {
+                let probe = probe.clone();
+                crate::runtime::spawn(async move {
+                    if let Err(e) = probe.recycle_connection().await {
+                        warn!(
+                            "Failed to recycle connection to {}: {}",
+                            probe.serial_number_string(),
+                            e
+                        );
+                    }
+                });
+            }
+        }
+    }
+
+    /// Read the RSSI over the connection for connected probes that stopped
+    /// advertising.
+    fn refresh_connected_rssi(probes: &Arc<RwLock<HashMap<String, Arc<Probe>>>>) {
+        for probe in probes.read().values() {
+            if !probe.needs_rssi_refresh() {
+                continue;
+            }
+            let probe = probe.clone();
+            crate::runtime::spawn(async move {
+                if let Err(e) = probe.refresh_rssi().await {
+                    debug!(
+                        "Failed to refresh RSSI of {}: {}",
+                        probe.serial_number_string(),
+                        e
+                    );
+                }
+            });
+        }
+    }
+
     /// Check for stale probes and emit events.
     fn check_stale_probes(
         probes: &Arc<RwLock<HashMap<String, Arc<Probe>>>>,
@@ -349,11 +1977,112 @@ impl DeviceManager {
             }
         }
     }
+
+    /// Start shutting down without waiting, for use from `Drop`.
+    ///
+    /// Background tasks are stopped and the probes forgotten immediately.
+    /// Stopping the BLE scan and disconnecting the probes needs a runtime,
+    /// so it is spawned if one is available and skipped otherwise.
+    fn shutdown_in_background(&self) {
+        let was_running = self.is_running.swap(false, Ordering::SeqCst);
+        for handle in [
+            &self.duty_cycle_handle,
+            &self.wake_handle,
+            &self.background_handle,
+            &self.defaults_handle,
+            #[cfg(feature = "persistence")]
+            &self.known_probes_handle,
+            #[cfg(feature = "persistence")]
+            &self.timer_store_handle,
+        ] {
+            if let Some(handle) = handle.write().take() {
+                handle.abort();
+            }
+        }
+        *self.scan_phase.write() = None;
+        self.set_state(ManagerState::Idle);
+
+        let probes: Vec<_> = self.probes.write().drain().map(|(_, p)| p).collect();
+        let scanner = match &self.backend {
+            Backend::Ble(scanner) if was_running => Some(scanner.clone()),
+            _ => None,
+        };
+        if probes.is_empty() && scanner.is_none() {
+            return;
+        }
+
+        info!("Shutting down device manager in the background");
+        let spawned = crate::runtime::try_spawn(async move {
+            if let Some(scanner) = scanner {
+                if let Err(e) = scanner.stop_scanning().await {
+                    warn!("Error stopping scan: {}", e);
+                }
+            }
+            for probe in probes {
+                if let Err(e) = probe.disconnect().await {
+                    warn!("Error disconnecting probe {}: {}", probe.identifier(), e);
+                }
+            }
+        });
+        if !spawned {
+            warn!("No runtime to shut down the device manager on; connections are left open");
+        }
+    }
+}
+
+/// Keeps a [`DeviceManager`] scanning until dropped.
+///
+/// Created by [`DeviceManager::start_scanning_guarded`]. Dereferences to the
+/// manager.
+///
+/// When the guard (or a [`DeviceManager`] itself) is dropped:
+///
+/// - Background tasks stop and the discovered probes are forgotten before
+///   the drop returns.
+/// - Stopping the BLE scan and disconnecting the probes are spawned on the
+///   runtime, and finish shortly after. Without a runtime, e.g. when dropped
+///   after the runtime shut down, they are skipped.
+///
+/// For cleanup that has finished when it returns, call
+/// [`shutdown`](Self::shutdown) instead.
+#[must_use = "scanning stops when the guard is dropped"]
+pub struct ShutdownGuard<'a> {
+    /// The guarded manager.
+    manager: &'a DeviceManager,
+    /// Whether to shut down on drop.
+    armed: bool,
+}
+
+impl ShutdownGuard<'_> {
+    /// Shut the manager down, waiting for scanning to stop and the probes
+    /// to disconnect.
+    pub async fn shutdown(mut self) -> Result<()> {
+        self.armed = false;
+        self.manager.shutdown().await
+    }
+}
+
+impl std::ops::Deref for ShutdownGuard<'_> {
+    type Target = DeviceManager;
+
+    fn deref(&self) -> &DeviceManager {
+        self.manager
+    }
+}
+
+impl Drop for ShutdownGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.manager.shutdown_in_background();
+        }
+    }
 }
 
 impl Drop for DeviceManager {
+    /// Shut down in the background; see [`ShutdownGuard`] for what that
+    /// covers.
     fn drop(&mut self) {
-        self.is_running.store(false, Ordering::SeqCst);
+        self.shutdown_in_background();
     }
 }
 
@@ -365,4 +2094,81 @@ mod tests {
     fn test_max_probes_constant() {
         assert_eq!(MAX_PROBES, 8);
     }
+
+    #[test]
+    fn test_proximity_detector_picks_spiking_probe() {
+        let mut detector = ProximityDetector::new(15);
+        // A probe that is simply nearer never counts
+        for _ in 0..10 {
+            assert_eq!(detector.update([("near", -45), ("far", -80)]), None);
+        }
+
+        // One noisy reading is not enough
+        assert_eq!(detector.update([("near", -44), ("far", -60)]), None);
+        assert_eq!(detector.update([("near", -46), ("far", -79)]), None);
+
+        assert_eq!(detector.update([("near", -45), ("far", -50)]), None);
+        assert_eq!(
+            detector.update([("near", -45), ("far", -48)]),
+            Some("far".to_string())
+        );
+    }
+
+    #[test]
+    fn test_scan_watchdog_restarts_with_backoff() {
+        let timeout = Duration::from_secs(30);
+        let start = tokio::time::Instant::now();
+        let mut watchdog = ScanWatchdog::new(timeout, start);
+
+        // Not expecting advertisements, e.g. duty cycle paused
+        assert_eq!(watchdog.check(false, start + Duration::from_secs(60)), None);
+        assert_eq!(watchdog.check(true, start + Duration::from_secs(80)), None);
+        assert_eq!(
+            watchdog.check(true, start + Duration::from_secs(90)),
+            Some(Duration::from_secs(30))
+        );
+
+        // Still silent: the wait doubles
+        assert_eq!(watchdog.check(true, start + Duration::from_secs(140)), None);
+        assert_eq!(
+            watchdog.check(true, start + Duration::from_secs(150)),
+            Some(Duration::from_secs(60))
+        );
+
+        // An advertisement resets the wait
+        watchdog.advertisement(timeout, start + Duration::from_secs(160));
+        assert_eq!(
+            watchdog.check(true, start + Duration::from_secs(190)),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_sleep_detector() {
+        let interval = Duration::from_secs(2);
+        let wall = Utc::now();
+        let monotonic = tokio::time::Instant::now();
+        let mut detector = SleepDetector::new(interval, wall, monotonic);
+
+        // On time
+        let wall = wall + chrono::Duration::seconds(2);
+        let monotonic = monotonic + interval;
+        assert_eq!(detector.check(wall, monotonic), None);
+
+        // Monotonic clock stopped during sleep
+        let wall = wall + chrono::Duration::seconds(62);
+        let monotonic = monotonic + interval;
+        assert_eq!(
+            detector.check(wall, monotonic),
+            Some(Duration::from_secs(60))
+        );
+
+        // Monotonic clock kept running during sleep
+        let wall = wall + chrono::Duration::seconds(32);
+        let monotonic = monotonic + Duration::from_secs(32);
+        assert_eq!(
+            detector.check(wall, monotonic),
+            Some(Duration::from_secs(30))
+        );
+    }
 }
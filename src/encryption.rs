@@ -0,0 +1,209 @@
+//! At-rest encryption for stored sessions and exports.
+//!
+//! Commercial kitchens keeping HACCP records may need them encrypted on
+//! disk. An [`EncryptionKey`] seals data with ChaCha20-Poly1305, so a file
+//! that was tampered with or read with the wrong key fails to decrypt
+//! rather than yielding garbage. Each sealed blob starts with a short magic
+//! header, followed by a random nonce and the ciphertext.
+//!
+//! Keys are provisioned by the application, e.g. from its secret store with
+//! [`EncryptionKey::from_hex`] or from the environment with
+//! [`EncryptionKey::from_env`], and are never written by this library.
+//! With the `persistence` feature, a `persistence::Config` file can name the
+//! environment variable holding the key.
+//!
+//! With the `persistence` feature, `SessionStore` sessions, `HistoryStore`
+//! records and session bundles from `Probe::export_session_bundle_encrypted`
+//! are sealed with a key, as are exports from
+//! [`TemperatureLog::to_csv_encrypted`](crate::data::TemperatureLog::to_csv_encrypted)
+//! and [`HaccpLog::to_csv_encrypted`](crate::data::HaccpLog::to_csv_encrypted).
+//! Timers, known probes and the configuration are written in plain text.
+//!
+//! Requires the `encryption` feature.
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::{Error, Result};
+
+/// Header identifying sealed data and its format version.
+const MAGIC: &[u8; 5] = b"CRBE1";
+
+/// Size of the ChaCha20-Poly1305 nonce in bytes.
+const NONCE_SIZE: usize = 12;
+
+/// Environment variable read by [`EncryptionKey::from_default_env`].
+pub const ENCRYPTION_KEY_ENV: &str = "COMBUSTION_STORAGE_KEY";
+
+/// A 256-bit key for sealing stored data.
+///
+/// The key bytes are not shown by `Debug`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Size of a key in bytes.
+    pub const SIZE: usize = 32;
+
+    /// Generate a random key.
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    /// Create a key from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a key from 64 hex digits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if `hex` is not 64 hex digits.
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+        let invalid = || Error::InvalidParameter {
+            name: "key".to_string(),
+            value: format!("expected {} hex digits", Self::SIZE * 2),
+        };
+        let bytes = decode_hex(hex).ok_or_else(invalid)?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| invalid())?;
+        Ok(Self(bytes))
+    }
+
+    /// Read a key as 64 hex digits from the environment variable `var`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if the variable is unset or not
+    /// a valid key.
+    pub fn from_env(var: &str) -> Result<Self> {
+        let value = std::env::var(var).map_err(|_| Error::InvalidParameter {
+            name: var.to_string(),
+            value: "not set".to_string(),
+        })?;
+        Self::from_hex(&value)
+    }
+
+    /// Read a key from [`ENCRYPTION_KEY_ENV`].
+    pub fn from_default_env() -> Result<Self> {
+        Self::from_env(ENCRYPTION_KEY_ENV)
+    }
+
+    /// Format the key as 64 hex digits, for storing in a secret store.
+    pub fn to_hex(&self) -> String {
+        encode_hex(&self.0)
+    }
+
+    /// Encrypt `data`.
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, data)
+            .map_err(|_| encryption_error("encryption failed"))?;
+
+        let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_SIZE + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypt data sealed by [`encrypt`](Self::encrypt).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if `data` is not sealed, was sealed with
+    /// another key, or was modified.
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !is_encrypted(data) || data.len() < MAGIC.len() + NONCE_SIZE {
+            return Err(encryption_error("decryption failed: data is not encrypted"));
+        }
+        let (nonce, ciphertext) = data[MAGIC.len()..].split_at(NONCE_SIZE);
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| encryption_error("decryption failed: wrong key or corrupted data"))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Check if `data` was sealed by [`EncryptionKey::encrypt`].
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Format bytes as lowercase hex.
+pub(crate) fn encode_hex(data: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(data.len() * 2);
+    for byte in data {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+/// Parse hex into bytes, or `None` if it is not whole bytes of hex.
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+/// Create a storage error for a failed encryption operation.
+fn encryption_error(reason: &str) -> Error {
+    Error::Storage {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = EncryptionKey::generate();
+        let sealed = key.encrypt(b"Sequence,T1\n").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(key.decrypt(&sealed).unwrap(), b"Sequence,T1\n");
+
+        // Nonces are random
+        assert_ne!(key.encrypt(b"Sequence,T1\n").unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering() {
+        let key = EncryptionKey::generate();
+        let mut sealed = key.encrypt(b"haccp").unwrap();
+        assert!(EncryptionKey::generate().decrypt(&sealed).is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(matches!(key.decrypt(&sealed), Err(Error::Storage { .. })));
+        assert!(key.decrypt(b"plain").is_err());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let key = EncryptionKey::generate();
+        assert_eq!(EncryptionKey::from_hex(&key.to_hex()).unwrap(), key);
+        assert!(EncryptionKey::from_hex("abcd").is_err());
+        assert!(EncryptionKey::from_hex(&"zz".repeat(32)).is_err());
+        assert_eq!(format!("{:?}", key), "EncryptionKey(..)");
+    }
+}
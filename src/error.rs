@@ -36,10 +36,16 @@ pub enum Error {
     ConnectionLost,
 
     /// Invalid data was received from the probe.
-    #[error("Invalid data received: {context}")]
+    #[error(
+        "Invalid data received: {context}{}",
+        .payload.as_ref().map(|dump| format!(" (payload: {})", dump)).unwrap_or_default()
+    )]
     InvalidData {
         /// Description of what was invalid about the data.
         context: String,
+        /// Hex dump of the offending bytes when they came from a probe,
+        /// truncated to [`Error::MAX_PAYLOAD_DUMP`] bytes.
+        payload: Option<String>,
     },
 
     /// CRC check failed for a UART message.
@@ -85,6 +91,31 @@ pub enum Error {
         value: String,
     },
 
+    /// A temperature is outside the range the probe can represent.
+    #[error(
+        "Temperature out of range: {name} = {celsius:.1}°C ({:.1}°F), must be {min:.1}°C to {max:.1}°C",
+        crate::utils::celsius_to_fahrenheit(*.celsius)
+    )]
+    TemperatureOutOfRange {
+        /// The name of the parameter.
+        name: String,
+        /// The temperature that was provided, in Celsius.
+        celsius: f64,
+        /// Lowest representable temperature, in Celsius.
+        min: f64,
+        /// Highest representable temperature, in Celsius.
+        max: f64,
+    },
+
+    /// A food safe configuration parameter is out of range.
+    #[error("Invalid food safe config: {field} {reason}")]
+    InvalidFoodSafeConfig {
+        /// The name of the offending field.
+        field: String,
+        /// Why the value was rejected.
+        reason: String,
+    },
+
     /// An internal error occurred.
     #[error("Internal error: {0}")]
     Internal(String),
@@ -102,7 +133,81 @@ pub enum Error {
         /// The UUID of the service that was not found.
         uuid: String,
     },
+
+    /// A network request to a remote service failed.
+    #[error("Network error: {reason}")]
+    Network {
+        /// Description of the failure.
+        reason: String,
+    },
+
+    /// Reading or writing persisted data failed.
+    #[error("Storage error: {reason}")]
+    Storage {
+        /// Description of the failure.
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Most bytes of an offending payload kept in [`Error::InvalidData`].
+    pub const MAX_PAYLOAD_DUMP: usize = 64;
+
+    /// Create an [`Error::InvalidData`] for bytes received from a probe,
+    /// keeping a hex dump of them.
+    pub(crate) fn invalid_payload(context: impl Into<String>, data: &[u8]) -> Self {
+        Self::InvalidData {
+            context: context.into(),
+            payload: Some(hex_dump(data, Self::MAX_PAYLOAD_DUMP)),
+        }
+    }
+
+    /// Get the hex dump of the offending payload, if the error has one.
+    pub fn payload(&self) -> Option<&str> {
+        match self {
+            Self::InvalidData { payload, .. } => payload.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Format up to `max_len` bytes as space-separated hex, noting how many
+/// bytes were left out.
+pub(crate) fn hex_dump(data: &[u8], max_len: usize) -> String {
+    let mut dump = data
+        .iter()
+        .take(max_len)
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if data.len() > max_len {
+        dump.push_str(&format!(" ... (+{} bytes)", data.len() - max_len));
+    }
+    dump
 }
 
 /// A specialized Result type for this crate.
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_payload_dump_is_bounded() {
+        let error = Error::invalid_payload("too short", &[0xCA, 0xFE, 0x01]);
+        assert_eq!(error.payload(), Some("CA FE 01"));
+        assert_eq!(
+            error.to_string(),
+            "Invalid data received: too short (payload: CA FE 01)"
+        );
+
+        let long = vec![0xAB; Error::MAX_PAYLOAD_DUMP + 6];
+        let dump = Error::invalid_payload("too long", &long)
+            .payload()
+            .unwrap()
+            .to_string();
+        assert!(dump.ends_with("AB ... (+6 bytes)"));
+        assert_eq!(dump.matches("AB").count(), Error::MAX_PAYLOAD_DUMP);
+    }
+}
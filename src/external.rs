@@ -0,0 +1,221 @@
+//! Readings from non-Combustion thermometers.
+//!
+//! An [`ExternalSensorSource`] is a pseudo-probe, registered with
+//! [`DeviceManager::add_external_source`](crate::DeviceManager::add_external_source),
+//! whose temperatures are pushed in by the caller, for example from a wired
+//! pit thermocouple. It appears among the manager's probes, so exports,
+//! threshold watches, snapshots, and anything built on them handle its
+//! readings like those of a real probe. Up to eight channels map onto
+//! sensors T1-T8, and any channel can stand in for a virtual sensor.
+//!
+//! The limits of a Combustion probe's hardware don't apply: readings go up
+//! to about 3256°C rather than 389°C, alarms may be set at any temperature
+//! and are evaluated by the library on each reading, and sources don't
+//! count toward [`MAX_PROBES`](crate::MAX_PROBES). Other commands, such as
+//! prediction and food safety settings, return [`Error::NotSupported`].
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+use crate::ble::connection::ConnectionState;
+use crate::data::{ProbeTemperatures, RawTemperature, VirtualSensor, VirtualTemperatures};
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+use crate::transport::ProbeTransport;
+
+/// Number of channels an external source can report.
+pub const MAX_CHANNELS: usize = 8;
+
+/// Transport for a pseudo-probe: always connected, rejects commands.
+struct ExternalTransport;
+
+#[async_trait]
+impl ProbeTransport for ExternalTransport {
+    fn connection_state(&self) -> ConnectionState {
+        ConnectionState::Connected
+    }
+
+    async fn connect(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn write_uart(&self, _data: &[u8]) -> Result<()> {
+        Err(Error::NotSupported {
+            operation: "commands to an external sensor source".to_string(),
+        })
+    }
+}
+
+/// A pseudo-probe fed with readings from another thermometer.
+pub struct ExternalSensorSource {
+    /// Source name.
+    name: String,
+    /// Pseudo-probe registered with the device manager.
+    probe: Arc<Probe>,
+    /// Channel standing in for each virtual sensor (core, surface, ambient).
+    virtual_channels: RwLock<[Option<usize>; 3]>,
+}
+
+impl ExternalSensorSource {
+    /// Create a source and its pseudo-probe.
+    pub(crate) fn new(name: String, serial_number: u32) -> Self {
+        let probe = Probe::with_transport(
+            format!("external:{}", name),
+            serial_number,
+            Arc::new(ExternalTransport),
+        )
+        .into_external();
+
+        Self {
+            name,
+            probe: Arc::new(probe),
+            virtual_channels: RwLock::new([None; 3]),
+        }
+    }
+
+    /// Get the source name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the pseudo-probe carrying this source's readings.
+    pub fn probe(&self) -> Arc<Probe> {
+        self.probe.clone()
+    }
+
+    /// Use a channel's reading as a virtual sensor, e.g. a pit thermocouple
+    /// as [`VirtualSensor::Ambient`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if `channel` is out of range.
+    pub fn assign(&self, sensor: VirtualSensor, channel: usize) -> Result<()> {
+        if channel >= MAX_CHANNELS {
+            return Err(Error::InvalidParameter {
+                name: "channel".to_string(),
+                value: channel.to_string(),
+            });
+        }
+        self.virtual_channels.write()[Self::slot(sensor)] = Some(channel);
+        Ok(())
+    }
+
+    /// Push a reading, one Celsius value per channel starting at T1.
+    ///
+    /// Missing channels and `None` values are reported as no reading.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if more than [`MAX_CHANNELS`]
+    /// values are given.
+    pub fn push(&self, channels: &[Option<f64>]) -> Result<()> {
+        if channels.len() > MAX_CHANNELS {
+            return Err(Error::InvalidParameter {
+                name: "channels".to_string(),
+                value: channels.len().to_string(),
+            });
+        }
+
+        let mut celsius = [None; MAX_CHANNELS];
+        celsius[..channels.len()].copy_from_slice(channels);
+
+        let mut temperatures = ProbeTemperatures::new();
+        for (value, reading) in temperatures.values.iter_mut().zip(celsius) {
+            if let Some(t) = reading {
+                *value = RawTemperature::from_celsius_extended(t);
+            }
+        }
+
+        let [core, surface, ambient] = self
+            .virtual_channels
+            .read()
+            .map(|channel| channel.and_then(|i| celsius[i]));
+        let virtual_temperatures = VirtualTemperatures::new(core, surface, ambient);
+
+        self.probe
+            .update_from_readings(temperatures, virtual_temperatures);
+        Ok(())
+    }
+
+    /// Get the slot of a virtual sensor in `virtual_channels`.
+    fn slot(sensor: VirtualSensor) -> usize {
+        match sensor {
+            VirtualSensor::Core => 0,
+            VirtualSensor::Surface => 1,
+            VirtualSensor::Ambient => 2,
+        }
+    }
+}
+
+impl std::fmt::Debug for ExternalSensorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalSensorSource")
+            .field("name", &self.name)
+            .field("serial_number", &self.probe.serial_number_string())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_push_maps_channels() {
+        let source = ExternalSensorSource::new("pit".to_string(), 0xE000_0001);
+        source.assign(VirtualSensor::Ambient, 1).unwrap();
+        source.push(&[Some(60.0), Some(120.0)]).unwrap();
+
+        let probe = source.probe();
+        assert!(probe.is_external());
+        let celsius = probe.current_temperatures().to_celsius();
+        assert!((celsius[1].unwrap() - 120.0).abs() < 0.1);
+        assert!(celsius[2].is_none());
+        assert!((probe.virtual_temperatures().ambient.unwrap() - 120.0).abs() < 0.1);
+        assert!(probe.virtual_temperatures().core.is_none());
+
+        assert!(matches!(
+            probe
+                .set_prediction(crate::PredictionMode::TimeToRemoval, 60.0)
+                .await,
+            Err(Error::NotSupported { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_beyond_probe_limits() {
+        let source = ExternalSensorSource::new("oven".to_string(), 0xE000_0001);
+        let probe = source.probe();
+        let mut alarms = probe.subscribe_alarms();
+
+        probe.set_core_high_alarm(500.0).await.unwrap();
+        source.assign(VirtualSensor::Core, 0).unwrap();
+        source.push(&[Some(450.0)]).unwrap();
+        let celsius = probe.current_temperatures().to_celsius();
+        assert!((celsius[0].unwrap() - 450.0).abs() < 0.1);
+        assert!(!probe.any_alarm_tripped());
+
+        source.push(&[Some(520.0)]).unwrap();
+        assert!(probe.any_alarm_alarming());
+        assert!(matches!(
+            alarms.try_recv().unwrap(),
+            crate::AlarmEvent::Temperature { threshold, .. } if threshold == 500.0
+        ));
+
+        probe.silence_alarms().await.unwrap();
+        assert!(probe.any_alarm_tripped() && !probe.any_alarm_alarming());
+        assert!(probe.set_core_high_alarm(f64::NAN).await.is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let source = ExternalSensorSource::new("pit".to_string(), 0xE000_0001);
+        assert!(source.assign(VirtualSensor::Core, 8).is_err());
+        assert!(source.push(&[None; 9]).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Probes reporting a fault.
+//!
+//! A probe switches to [`ProbeMode::Error`](crate::ProbeMode::Error) when its
+//! firmware detects a fault. Its readings are not valid then, so the probe
+//! marks every sensor inactive, clears its virtual temperatures, and
+//! publishes a [`ProbeFaulted`] event carrying a [`FaultInfo`] with whatever
+//! diagnostics the probe still reports. [`FaultInfo::guidance`] gives a
+//! message suitable for showing to the user.
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::{BatteryStatus, Overheating};
+use crate::data::ProbeTemperatures;
+
+/// Diagnostics for a probe in error mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FaultInfo {
+    /// When the probe was first seen in error mode.
+    pub faulted_at: DateTime<Utc>,
+    /// Bitmask of sensors reporting no reading (bit 0 = T1, bit 7 = T8).
+    pub invalid_sensors: u8,
+    /// Sensors the probe flags as overheating.
+    pub overheating: Overheating,
+    /// Battery status.
+    pub battery_status: BatteryStatus,
+}
+
+impl FaultInfo {
+    /// Capture diagnostics from the probe's latest readings.
+    pub(crate) fn capture(
+        temperatures: &ProbeTemperatures,
+        overheating: Overheating,
+        battery_status: BatteryStatus,
+        faulted_at: DateTime<Utc>,
+    ) -> Self {
+        let invalid_sensors = temperatures
+            .values
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.is_valid())
+            .fold(0, |mask, (i, _)| mask | (1 << i));
+
+        Self {
+            faulted_at,
+            invalid_sensors,
+            overheating,
+            battery_status,
+        }
+    }
+
+    /// Get the indices (0-7) of sensors reporting no reading.
+    pub fn invalid_sensor_indices(&self) -> Vec<usize> {
+        (0..8)
+            .filter(|i| self.invalid_sensors & (1 << i) != 0)
+            .collect()
+    }
+
+    /// Get advice for the user on how to recover the probe.
+    pub fn guidance(&self) -> &'static str {
+        if self.overheating.is_any_overheating() {
+            "Remove the probe from the heat and let it cool, then return it to \
+             its charger to reset it. If the error persists, the probe may \
+             need replacing."
+        } else if self.battery_status.is_low() {
+            "Return the probe to its charger to recharge and reset it. If the \
+             error persists, the probe may need replacing."
+        } else {
+            "Return the probe to its charger to reset it. If the error \
+             persists, the probe may need replacing."
+        }
+    }
+}
+
+/// A probe entered error mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProbeFaulted {
+    /// Probe serial number.
+    pub serial_number: u32,
+    /// Diagnostics at the time of the fault.
+    pub fault: FaultInfo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fault_info_capture() {
+        let mut temperatures = ProbeTemperatures::from_raw([400; 8]);
+        temperatures.values[2] = crate::data::RawTemperature::INVALID;
+        temperatures.values[7] = crate::data::RawTemperature::INVALID;
+
+        let fault = FaultInfo::capture(
+            &temperatures,
+            Overheating::default(),
+            BatteryStatus::Ok,
+            Utc::now(),
+        );
+        assert_eq!(fault.invalid_sensors, 0b1000_0100);
+        assert_eq!(fault.invalid_sensor_indices(), vec![2, 7]);
+        assert!(fault.guidance().starts_with("Return the probe"));
+
+        let fault = FaultInfo {
+            overheating: Overheating::new(0x10),
+            ..fault
+        };
+        assert!(fault.guidance().starts_with("Remove the probe"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Fixed-rate snapshots for rendering loops.
+//!
+//! Probes update at whatever rate advertisements and status notifications
+//! arrive, often in bursts. [`SnapshotFrames`] coalesces those updates into at
+//! most one [`ProbeSnapshot`] per frame interval, so a GUI can redraw when a
+//! frame arrives without its own timer or event merging. Frames are only
+//! published when something changed, and a slow reader always sees the
+//! latest frame rather than a backlog.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::frames::DEFAULT_FRAME_INTERVAL;
+//!
+//! # async fn example(probe: &combustion_rust_ble::Probe) {
+//! let mut frames = probe.snapshot_frames(DEFAULT_FRAME_INTERVAL);
+//! while let Ok(snapshot) = frames.next().await {
+//!     println!("{:?}", snapshot.virtual_temperatures.core);
+//! }
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use tokio::sync::{broadcast, watch};
+use tokio::time::MissedTickBehavior;
+
+use crate::error::{Error, Result};
+use crate::runtime::TaskHandle;
+use crate::snapshot::{ProbeSnapshot, StateDiff};
+
+/// Frame interval for 10 frames per second.
+pub const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Snapshots of a probe published at a fixed rate.
+///
+/// Created with [`Probe::snapshot_frames`](crate::Probe::snapshot_frames).
+/// Publishing stops when this is dropped.
+pub struct SnapshotFrames {
+    /// Latest frame.
+    rx: watch::Receiver<ProbeSnapshot>,
+    /// Time between frames.
+    interval: Duration,
+    /// Publishes frames.
+    task: TaskHandle<()>,
+}
+
+impl SnapshotFrames {
+    /// Start publishing frames every `interval`, starting from `initial`.
+    ///
+    /// `capture` takes a snapshot of the probe, or returns `None` once the
+    /// probe is gone, and `changes` signals that it may differ from the last
+    /// frame.
+    pub(crate) fn spawn<F>(
+        interval: Duration,
+        initial: ProbeSnapshot,
+        mut changes: broadcast::Receiver<StateDiff>,
+        capture: F,
+    ) -> Self
+    where
+        F: Fn() -> Option<ProbeSnapshot> + Send + 'static,
+    {
+        let interval = interval.max(Duration::from_millis(1));
+        let (tx, rx) = watch::channel(initial);
+        let task = crate::runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = tx.closed() => break,
+                }
+
+                let mut changed = false;
+                loop {
+                    match changes.try_recv() {
+                        Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => changed = true,
+                        Err(broadcast::error::TryRecvError::Empty) => break,
+                        Err(broadcast::error::TryRecvError::Closed) => return,
+                    }
+                }
+
+                // Connection changes are only diffed with the next data update
+                let Some(snapshot) = capture() else {
+                    return;
+                };
+                if changed || snapshot.connection_state != tx.borrow().connection_state {
+                    tx.send_replace(snapshot);
+                }
+            }
+        });
+        Self { rx, interval, task }
+    }
+
+    /// Get the time between frames.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Get the latest frame without waiting.
+    pub fn latest(&self) -> ProbeSnapshot {
+        self.rx.borrow().clone()
+    }
+
+    /// Wait for the next frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConnectionLost`] if the probe is dropped.
+    pub async fn next(&mut self) -> Result<ProbeSnapshot> {
+        self.rx.changed().await.map_err(|_| Error::ConnectionLost)?;
+        Ok(self.rx.borrow_and_update().clone())
+    }
+}
+
+impl Drop for SnapshotFrames {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl std::fmt::Debug for SnapshotFrames {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotFrames")
+            .field("interval", &self.interval)
+            .field("serial_number", &self.rx.borrow().serial_number)
+            .finish()
+    }
+}
@@ -0,0 +1,44 @@
+//! Gateway mode: serve probe data over the network.
+//!
+//! A machine with Bluetooth runs a [`GatewayServer`] that re-serves every
+//! probe known to its [`DeviceManager`](crate::DeviceManager). Another
+//! instance of the crate, e.g. on a kitchen PC without Bluetooth, connects
+//! with a [`RemoteBackend`] and uses the regular `DeviceManager` API via
+//! [`DeviceManager::with_remote`](crate::DeviceManager::with_remote).
+//!
+//! The wire protocol is newline-delimited JSON over TCP. Clients first
+//! present a pre-shared token; the gateway then pushes probe snapshots at a
+//! fixed interval, and clients send connection and configuration command
+//! requests which are answered with a response carrying the same id. Raw
+//! UART frames are not relayed. The token is not encrypted, so keep the
+//! gateway on loopback or a trusted network.
+//!
+//! Requires the `gateway` feature.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::gateway::{GatewayServer, RemoteBackend};
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! # async fn example() -> combustion_rust_ble::Result<()> {
+//! // On the machine with Bluetooth
+//! let manager = DeviceManager::new().await?;
+//! manager.start_scanning().await?;
+//! let server = GatewayServer::bind(&manager, "127.0.0.1:7878", "kitchen-token").await?;
+//!
+//! // On the same machine, or one tunnelled to it
+//! let remote =
+//!     DeviceManager::with_remote(RemoteBackend::connect("127.0.0.1:7878", "kitchen-token").await?);
+//! remote.start_scanning().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod protocol;
+mod remote;
+mod server;
+
+pub use remote::RemoteBackend;
+pub use server::GatewayServer;
+
+/// Default TCP port used by the gateway.
+pub const DEFAULT_GATEWAY_PORT: u16 = 7878;
@@ -0,0 +1,121 @@
+//! Gateway wire protocol.
+//!
+//! Messages are JSON objects tagged by a `type` field, one per line. A client
+//! must send a [`ClientAuth`] first; the gateway answers it with a
+//! [`ServerMessage::Response`] with id [`AUTH_RESPONSE_ID`] and closes the
+//! connection if the token is wrong.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::ConfigCommand;
+use crate::error::{Error, Result};
+use crate::snapshot::ProbeSnapshot;
+
+/// Message sent from the gateway to a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ServerMessage {
+    /// Current state of a probe.
+    Snapshot {
+        /// The probe snapshot.
+        snapshot: Box<ProbeSnapshot>,
+    },
+    /// Result of a client request.
+    Response {
+        /// Request id being answered.
+        id: u64,
+        /// Error message if the request failed.
+        error: Option<String>,
+    },
+}
+
+/// First message sent from a client to the gateway.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ClientAuth {
+    /// Pre-shared token.
+    pub(crate) token: String,
+}
+
+/// Request sent from a client to the gateway once authenticated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ClientMessage {
+    /// Connect to a probe.
+    Connect {
+        /// Request id.
+        id: u64,
+        /// Probe serial number.
+        serial: u32,
+    },
+    /// Disconnect from a probe.
+    Disconnect {
+        /// Request id.
+        id: u64,
+        /// Probe serial number.
+        serial: u32,
+    },
+    /// Send a configuration command to a probe.
+    Command {
+        /// Request id.
+        id: u64,
+        /// Probe serial number.
+        serial: u32,
+        /// The command.
+        command: ConfigCommand,
+    },
+}
+
+/// Request id of the response to [`ClientAuth`].
+pub(crate) const AUTH_RESPONSE_ID: u64 = 0;
+
+/// Encode a message as a single JSON line.
+pub(crate) fn encode<T: Serialize>(message: &T) -> Result<String> {
+    let mut line = serde_json::to_string(message).map_err(|e| Error::InvalidData {
+        context: format!("failed to encode gateway message: {}", e),
+        payload: None,
+    })?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Decode a single JSON line.
+pub(crate) fn decode<T: for<'de> Deserialize<'de>>(line: &str) -> Result<T> {
+    serde_json::from_str(line).map_err(|e| Error::InvalidData {
+        context: format!("invalid gateway message: {}", e),
+        payload: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_message_roundtrip() {
+        let message = ClientMessage::Command {
+            id: 7,
+            serial: 0x1000_1234,
+            command: ConfigCommand::SetPrediction {
+                mode: crate::data::PredictionMode::TimeToRemoval,
+                set_point_celsius: 63.0,
+            },
+        };
+
+        let line = encode(&message).unwrap();
+        assert!(line.ends_with('\n'));
+        assert!(line.contains("\"type\":\"command\""));
+
+        let decoded: ClientMessage = decode(line.trim_end()).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(decode::<ServerMessage>("{\"type\":\"bogus\"}").is_err());
+        // Raw UART frames are not part of the protocol
+        assert!(decode::<ClientMessage>(
+            "{\"type\":\"uart\",\"id\":1,\"serial\":1,\"data\":[202,254]}"
+        )
+        .is_err());
+    }
+}
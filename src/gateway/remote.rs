@@ -0,0 +1,402 @@
+//! Gateway client backend.
+
+use async_trait::async_trait;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::sync::{broadcast, oneshot};
+use tracing::{debug, info};
+
+use super::protocol::{decode, encode, ClientAuth, ClientMessage, ServerMessage, AUTH_RESPONSE_ID};
+use super::server::network_error;
+use crate::audit::ConfigCommand;
+use crate::ble::connection::ConnectionState;
+use crate::error::{Error, Result};
+use crate::snapshot::ProbeSnapshot;
+use crate::transport::ProbeTransport;
+
+/// How long to wait for the gateway to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shared state between the backend, its reader task, and probe transports.
+struct Inner {
+    /// Write half of the gateway connection.
+    writer: tokio::sync::Mutex<OwnedWriteHalf>,
+    /// Snapshot channel.
+    snapshot_tx: broadcast::Sender<ProbeSnapshot>,
+    /// Requests awaiting a response, by id.
+    pending: Mutex<HashMap<u64, oneshot::Sender<Result<()>>>>,
+    /// Request id counter.
+    next_id: AtomicU64,
+    /// Whether the gateway connection is up.
+    connected: AtomicBool,
+    /// Last connection state reported for each probe, by serial number.
+    probe_states: Mutex<HashMap<u32, Arc<RwLock<ConnectionState>>>>,
+}
+
+impl Inner {
+    /// Send a request and wait for the gateway's response.
+    async fn request(&self, build: impl FnOnce(u64) -> ClientMessage) -> Result<()> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err(Error::ConnectionLost);
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let line = encode(&build(id))?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id, tx);
+
+        if let Err(e) = self.writer.lock().await.write_all(line.as_bytes()).await {
+            self.pending.lock().remove(&id);
+            return Err(network_error(e));
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(Error::ConnectionLost),
+            Err(_) => {
+                self.pending.lock().remove(&id);
+                Err(Error::Timeout)
+            }
+        }
+    }
+}
+
+/// Client side of a gateway connection.
+///
+/// Pass to [`DeviceManager::with_remote`](crate::DeviceManager::with_remote)
+/// to use the gateway's probes through the normal `DeviceManager` API.
+pub struct RemoteBackend {
+    /// Shared state.
+    inner: Arc<Inner>,
+    /// Reader task.
+    reader_handle: crate::runtime::TaskHandle<()>,
+}
+
+impl RemoteBackend {
+    /// Connect to a gateway and authenticate with its token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TCP connection cannot be established, or
+    /// [`Error::ConnectionFailed`] if the gateway rejects the token.
+    pub async fn connect(addr: impl ToSocketAddrs, token: impl Into<String>) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await.map_err(network_error)?;
+        let peer = stream.peer_addr().map_err(network_error)?;
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        let auth = encode(&ClientAuth {
+            token: token.into(),
+        })?;
+        writer
+            .write_all(auth.as_bytes())
+            .await
+            .map_err(network_error)?;
+        let reply = tokio::time::timeout(REQUEST_TIMEOUT, lines.next_line())
+            .await
+            .map_err(|_| Error::Timeout)?
+            .map_err(network_error)?;
+        match reply.as_deref().map(decode::<ServerMessage>) {
+            Some(Ok(ServerMessage::Response {
+                id: AUTH_RESPONSE_ID,
+                error: None,
+            })) => {}
+            _ => {
+                return Err(Error::ConnectionFailed {
+                    reason: format!("gateway {} rejected the token", peer),
+                })
+            }
+        }
+
+        let (snapshot_tx, _) = broadcast::channel(64);
+        let inner = Arc::new(Inner {
+            writer: tokio::sync::Mutex::new(writer),
+            snapshot_tx,
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            connected: AtomicBool::new(true),
+            probe_states: Mutex::new(HashMap::new()),
+        });
+
+        info!("Connected to gateway {}", peer);
+
+        let reader_inner = inner.clone();
+        let reader_handle = crate::runtime::spawn(async move {
+            while let Ok(Some(line)) = lines.next_line().await {
+                match decode::<ServerMessage>(&line) {
+                    Ok(ServerMessage::Snapshot { snapshot }) => {
+                        *probe_state(&reader_inner, snapshot.serial_number).write() =
+                            snapshot.connection_state;
+                        let _ = reader_inner.snapshot_tx.send(*snapshot);
+                    }
+                    Ok(ServerMessage::Response { id, error }) => {
+                        if let Some(tx) = reader_inner.pending.lock().remove(&id) {
+                            let _ = tx.send(match error {
+                                None => Ok(()),
+                                Some(message) => Err(Error::ProbeError { message }),
+                            });
+                        }
+                    }
+                    Err(e) => debug!("Ignoring gateway message: {}", e),
+                }
+            }
+
+            reader_inner.connected.store(false, Ordering::SeqCst);
+            // Fail any requests still waiting on a response
+            reader_inner.pending.lock().clear();
+            info!("Gateway connection closed");
+        });
+
+        Ok(Self {
+            inner,
+            reader_handle,
+        })
+    }
+
+    /// Check if the gateway connection is up.
+    pub fn is_connected(&self) -> bool {
+        self.inner.connected.load(Ordering::SeqCst)
+    }
+
+    /// Subscribe to snapshots pushed by the gateway.
+    pub fn subscribe_snapshots(&self) -> broadcast::Receiver<ProbeSnapshot> {
+        self.inner.snapshot_tx.subscribe()
+    }
+
+    /// Get the transport used to control a remote probe.
+    pub(crate) fn transport(&self, serial_number: u32) -> Arc<RemoteProbeTransport> {
+        Arc::new(RemoteProbeTransport {
+            inner: self.inner.clone(),
+            serial_number,
+            state: probe_state(&self.inner, serial_number),
+        })
+    }
+}
+
+/// Get or create the connection state slot for a remote probe.
+fn probe_state(inner: &Inner, serial_number: u32) -> Arc<RwLock<ConnectionState>> {
+    inner
+        .probe_states
+        .lock()
+        .entry(serial_number)
+        .or_default()
+        .clone()
+}
+
+impl Drop for RemoteBackend {
+    fn drop(&mut self) {
+        self.reader_handle.abort();
+    }
+}
+
+impl std::fmt::Debug for RemoteBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteBackend")
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+/// Transport for a single probe served by a gateway.
+pub(crate) struct RemoteProbeTransport {
+    /// Shared backend state.
+    inner: Arc<Inner>,
+    /// Probe serial number.
+    serial_number: u32,
+    /// Last connection state reported by the gateway.
+    state: Arc<RwLock<ConnectionState>>,
+}
+
+#[async_trait]
+impl ProbeTransport for RemoteProbeTransport {
+    fn connection_state(&self) -> ConnectionState {
+        if !self.inner.connected.load(Ordering::SeqCst) {
+            return ConnectionState::Disconnected;
+        }
+        *self.state.read()
+    }
+
+    async fn connect(&self) -> Result<()> {
+        let serial = self.serial_number;
+        self.inner
+            .request(|id| ClientMessage::Connect { id, serial })
+            .await?;
+        *self.state.write() = ConnectionState::Connected;
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        let serial = self.serial_number;
+        self.inner
+            .request(|id| ClientMessage::Disconnect { id, serial })
+            .await?;
+        *self.state.write() = ConnectionState::Disconnected;
+        Ok(())
+    }
+
+    async fn write_uart(&self, _data: &[u8]) -> Result<()> {
+        Err(Error::NotSupported {
+            operation: "raw UART writes through a gateway".to_string(),
+        })
+    }
+
+    async fn send_config(&self, command: &ConfigCommand, _data: &[u8]) -> Result<()> {
+        let serial = self.serial_number;
+        let command = command.clone();
+        self.inner
+            .request(move |id| ClientMessage::Command {
+                id,
+                serial,
+                command,
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::advertising::{ProbeId, ProbeMode};
+    use crate::data::{PredictionMode, VirtualTemperatures};
+    use crate::device_manager::DeviceManager;
+    use tokio::net::TcpListener;
+
+    fn snapshot(serial_number: u32) -> ProbeSnapshot {
+        ProbeSnapshot {
+            serial_number,
+            probe_id: ProbeId::new(2),
+            mode: ProbeMode::Normal,
+            connection_state: ConnectionState::Connected,
+            rssi: Some(-60),
+            virtual_temperatures: VirtualTemperatures::new(Some(50.0), None, None),
+            max_sequence: 10,
+            ..ProbeSnapshot::test_default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_device_manager_against_fake_gateway() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Minimal gateway: accept the token, push snapshots until a request
+        // arrives, then acknowledge it
+        let gateway = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let auth: ClientAuth = decode(&lines.next_line().await.unwrap().unwrap()).unwrap();
+            assert_eq!(auth.token, "secret");
+            let reply = encode(&ServerMessage::Response {
+                id: AUTH_RESPONSE_ID,
+                error: None,
+            })
+            .unwrap();
+            writer.write_all(reply.as_bytes()).await.unwrap();
+
+            let snapshot = encode(&ServerMessage::Snapshot {
+                snapshot: Box::new(snapshot(0x1000_0042)),
+            })
+            .unwrap();
+            let mut ticker = tokio::time::interval(Duration::from_millis(50));
+            let line = loop {
+                tokio::select! {
+                    _ = ticker.tick() => writer.write_all(snapshot.as_bytes()).await.unwrap(),
+                    line = lines.next_line() => break line.unwrap().unwrap(),
+                }
+            };
+            let request: ClientMessage = decode(&line).unwrap();
+            let ClientMessage::Command {
+                id,
+                serial,
+                command,
+            } = request
+            else {
+                panic!("expected command request, got {:?}", request);
+            };
+            let reply = encode(&ServerMessage::Response { id, error: None }).unwrap();
+            writer.write_all(reply.as_bytes()).await.unwrap();
+            (serial, command)
+        });
+
+        let manager =
+            DeviceManager::with_remote(RemoteBackend::connect(addr, "secret").await.unwrap());
+        let mut discovered = manager.subscribe_probe_discovered();
+        manager.start_scanning().await.unwrap();
+
+        let probe = tokio::time::timeout(Duration::from_secs(5), discovered.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(probe.serial_number_string(), "10000042");
+        assert_eq!(probe.id(), ProbeId::new(2));
+        assert_eq!(probe.virtual_temperatures().core, Some(50.0));
+        assert!(probe.connection_state().is_connected());
+
+        probe
+            .set_prediction(PredictionMode::TimeToRemoval, 63.0)
+            .await
+            .unwrap();
+
+        let (serial, command) = gateway.await.unwrap();
+        assert_eq!(serial, 0x1000_0042);
+        assert_eq!(
+            command,
+            ConfigCommand::SetPrediction {
+                mode: PredictionMode::TimeToRemoval,
+                set_point_celsius: 63.0,
+            }
+        );
+        assert_eq!(probe.command_history().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_id_conflicts_detected_while_snapshots_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Gateway streaming two probes sharing ID 2 faster than the
+        // manager's housekeeping interval
+        let _gateway = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            lines.next_line().await.unwrap();
+            let reply = encode(&ServerMessage::Response {
+                id: AUTH_RESPONSE_ID,
+                error: None,
+            })
+            .unwrap();
+            writer.write_all(reply.as_bytes()).await.unwrap();
+            loop {
+                for serial in [0x1000_0042, 0x1000_0043] {
+                    let line = encode(&ServerMessage::Snapshot {
+                        snapshot: Box::new(snapshot(serial)),
+                    })
+                    .unwrap();
+                    writer.write_all(line.as_bytes()).await.unwrap();
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+
+        let manager =
+            DeviceManager::with_remote(RemoteBackend::connect(addr, "secret").await.unwrap());
+        let mut conflicts = manager.subscribe_probe_id_conflicts();
+        manager.start_scanning().await.unwrap();
+
+        let conflict = tokio::time::timeout(Duration::from_secs(5), conflicts.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(conflict.id, ProbeId::new(2));
+        assert_eq!(conflict.serial_numbers, vec!["10000042", "10000043"]);
+    }
+}
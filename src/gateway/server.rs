@@ -0,0 +1,432 @@
+//! Gateway server.
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::{mpsc, Semaphore};
+use tracing::{debug, info, warn};
+
+use super::protocol::{decode, encode, ClientAuth, ClientMessage, ServerMessage, AUTH_RESPONSE_ID};
+use crate::audit::ConfigCommand;
+use crate::device_manager::DeviceManager;
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+
+type ProbeRegistry = Arc<RwLock<HashMap<String, Arc<Probe>>>>;
+
+/// Serves the probes of a [`DeviceManager`] to remote clients.
+///
+/// Clients must present the token the server was bound with before they
+/// receive snapshots or may send commands. Commands are limited to the
+/// typed configuration commands in [`ConfigCommand`], except
+/// [`ConfigCommand::ResetThermometer`], and are recorded in the probe's
+/// [`command_history`](Probe::command_history) as if sent locally. The
+/// token travels in plain text, so bind to loopback or a trusted network.
+///
+/// The server stops when dropped or when [`shutdown`](Self::shutdown) is
+/// called, disconnecting its clients.
+pub struct GatewayServer {
+    /// Address the server is listening on.
+    local_addr: SocketAddr,
+    /// Accept loop task.
+    handle: crate::runtime::TaskHandle<()>,
+}
+
+impl GatewayServer {
+    /// Interval at which probe snapshots are pushed to clients.
+    pub const SNAPSHOT_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// How long a client has to authenticate after connecting.
+    pub const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Longest authentication line accepted, in bytes.
+    pub const MAX_AUTH_LINE_BYTES: usize = 1024;
+
+    /// Longest request line accepted once authenticated, in bytes.
+    pub const MAX_REQUEST_LINE_BYTES: usize = 64 * 1024;
+
+    /// Most requests handled at once for one client. Further requests are
+    /// not read until one finishes.
+    pub const MAX_PENDING_REQUESTS: usize = 8;
+
+    /// Start serving `manager`'s probes on `addr` to clients presenting
+    /// `token`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if `token` is empty, or an error
+    /// if the address cannot be bound.
+    pub async fn bind(
+        manager: &DeviceManager,
+        addr: impl ToSocketAddrs,
+        token: impl Into<String>,
+    ) -> Result<Self> {
+        Self::bind_registry(manager.probe_registry(), addr, token.into()).await
+    }
+
+    /// Start serving the probes in `probes`.
+    async fn bind_registry(
+        probes: ProbeRegistry,
+        addr: impl ToSocketAddrs,
+        token: String,
+    ) -> Result<Self> {
+        let token: Arc<str> = token.into();
+        if token.is_empty() {
+            return Err(Error::InvalidParameter {
+                name: "token".to_string(),
+                value: "empty".to_string(),
+            });
+        }
+        let listener = TcpListener::bind(addr).await.map_err(network_error)?;
+        let local_addr = listener.local_addr().map_err(network_error)?;
+
+        info!("Gateway listening on {}", local_addr);
+
+        let handle = crate::runtime::spawn(async move {
+            // Owned by the accept loop, so stopping it disconnects the clients
+            let mut clients = TaskGroup::default();
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        info!("Gateway client connected: {}", peer);
+                        clients.push(crate::runtime::spawn(serve_client(
+                            stream,
+                            probes.clone(),
+                            token.clone(),
+                        )));
+                    }
+                    Err(e) => {
+                        warn!("Gateway accept failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { local_addr, handle })
+    }
+
+    /// Get the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop accepting new clients and disconnect the connected ones.
+    pub fn shutdown(self) {
+        self.handle.abort();
+    }
+}
+
+impl Drop for GatewayServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl std::fmt::Debug for GatewayServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GatewayServer")
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+/// Serve a single client until it disconnects.
+async fn serve_client(stream: TcpStream, probes: ProbeRegistry, token: Arc<str>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let auth = read_line(&mut reader, GatewayServer::MAX_AUTH_LINE_BYTES);
+    let authenticated = match tokio::time::timeout(GatewayServer::AUTH_TIMEOUT, auth).await {
+        Ok(Ok(Some(line))) => matches!(
+            decode(&line),
+            Ok(ClientAuth { token: presented }) if tokens_match(&presented, &token)
+        ),
+        _ => false,
+    };
+    let reply = ServerMessage::Response {
+        id: AUTH_RESPONSE_ID,
+        error: (!authenticated).then(|| "authentication failed".to_string()),
+    };
+    if let Ok(line) = encode(&reply) {
+        let _ = writer.write_all(line.as_bytes()).await;
+    }
+    if !authenticated {
+        warn!("Gateway client failed to authenticate");
+        return;
+    }
+
+    let (response_tx, mut response_rx) = mpsc::channel::<ServerMessage>(32);
+
+    let request_probes = probes.clone();
+    let pending = Arc::new(Semaphore::new(GatewayServer::MAX_PENDING_REQUESTS));
+    let mut reader_task = TaskGroup::default();
+    reader_task.push(crate::runtime::spawn(async move {
+        loop {
+            let line = match read_line(&mut reader, GatewayServer::MAX_REQUEST_LINE_BYTES).await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("Dropping gateway client: {}", e);
+                    break;
+                }
+            };
+            let message: ClientMessage = match decode(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    debug!("Ignoring gateway request: {}", e);
+                    continue;
+                }
+            };
+
+            // Handle each request concurrently so a slow connect doesn't block commands
+            let Ok(permit) = pending.clone().acquire_owned().await else {
+                break;
+            };
+            let probes = request_probes.clone();
+            let response_tx = response_tx.clone();
+            crate::runtime::spawn(async move {
+                let response = handle_request(message, &probes).await;
+                let _ = response_tx.send(response).await;
+                drop(permit);
+            });
+        }
+    }));
+
+    let mut ticker = tokio::time::interval(GatewayServer::SNAPSHOT_INTERVAL);
+    loop {
+        let messages = tokio::select! {
+            _ = ticker.tick() => {
+                probes
+                    .read()
+                    .values()
+                    .filter(|p| !p.is_stale())
+                    .map(|p| ServerMessage::Snapshot { snapshot: Box::new(p.snapshot()) })
+                    .collect::<Vec<_>>()
+            }
+            Some(response) = response_rx.recv() => vec![response],
+            else => break,
+        };
+
+        let mut failed = false;
+        for message in messages {
+            let line = match encode(&message) {
+                Ok(line) => line,
+                Err(e) => {
+                    warn!("{}", e);
+                    continue;
+                }
+            };
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                failed = true;
+                break;
+            }
+        }
+        if failed || reader_task.is_finished() {
+            break;
+        }
+    }
+
+    drop(reader_task);
+    debug!("Gateway client disconnected");
+}
+
+/// Background tasks that are stopped together when the group is dropped.
+#[derive(Default)]
+struct TaskGroup(Vec<crate::runtime::TaskHandle<()>>);
+
+impl TaskGroup {
+    /// Add a task, forgetting those that have stopped.
+    fn push(&mut self, task: crate::runtime::TaskHandle<()>) {
+        self.0.retain(|task| !task.is_finished());
+        self.0.push(task);
+    }
+
+    /// Check if every task has stopped.
+    fn is_finished(&self) -> bool {
+        self.0.iter().all(|task| task.is_finished())
+    }
+}
+
+impl Drop for TaskGroup {
+    fn drop(&mut self) {
+        for task in &self.0 {
+            task.abort();
+        }
+    }
+}
+
+/// Read a line of at most `max_bytes`, without its line ending, or `None`
+/// at the end of the stream.
+///
+/// A longer line is an error, so a client can't make the server buffer an
+/// endless line.
+async fn read_line<R>(reader: &mut R, max_bytes: usize) -> std::io::Result<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    // One byte over the limit tells a line of exactly `max_bytes` from a longer one
+    let limit = max_bytes as u64 + 1;
+    if reader.take(limit).read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    if line.len() > max_bytes {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("line longer than {} bytes", max_bytes),
+        ));
+    }
+    Ok(Some(line))
+}
+
+/// Execute a client request against the local probes.
+async fn handle_request(message: ClientMessage, probes: &ProbeRegistry) -> ServerMessage {
+    let (id, serial) = match &message {
+        ClientMessage::Connect { id, serial }
+        | ClientMessage::Disconnect { id, serial }
+        | ClientMessage::Command { id, serial, .. } => (*id, *serial),
+    };
+
+    let identifier = format!("{:08X}", serial);
+    let probe = probes.read().get(&identifier).cloned();
+
+    let result = match (probe, message) {
+        (None, _) => Err(Error::ProbeNotFound { identifier }),
+        (Some(probe), ClientMessage::Connect { .. }) => probe.connect().await,
+        (Some(probe), ClientMessage::Disconnect { .. }) => probe.disconnect().await,
+        (Some(_), ClientMessage::Command { command, .. }) if !is_allowed(&command) => {
+            Err(Error::NotSupported {
+                operation: format!("{:?} through a gateway", command),
+            })
+        }
+        (Some(probe), ClientMessage::Command { command, .. }) => {
+            probe.run_config_command(command).await
+        }
+    };
+
+    ServerMessage::Response {
+        id,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+/// Check if a command may be sent by gateway clients.
+fn is_allowed(command: &ConfigCommand) -> bool {
+    !matches!(command, ConfigCommand::ResetThermometer)
+}
+
+/// Compare tokens without returning early on the first difference.
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Convert an I/O error into a network error.
+pub(super) fn network_error(e: std::io::Error) -> Error {
+    Error::Network {
+        reason: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::RemoteBackend;
+
+    #[tokio::test]
+    async fn test_clients_must_present_the_token() {
+        let probes = ProbeRegistry::default();
+        assert!(
+            GatewayServer::bind_registry(probes.clone(), "127.0.0.1:0", String::new())
+                .await
+                .is_err()
+        );
+
+        let server = GatewayServer::bind_registry(probes, "127.0.0.1:0", "secret".to_string())
+            .await
+            .unwrap();
+        let result = RemoteBackend::connect(server.local_addr(), "guess").await;
+        assert!(matches!(result, Err(Error::ConnectionFailed { .. })));
+        assert!(RemoteBackend::connect(server.local_addr(), "secret")
+            .await
+            .unwrap()
+            .is_connected());
+    }
+
+    /// Connect to `server` and present `token`, returning the stream and
+    /// the server's reply.
+    async fn authenticate(server: &GatewayServer, token: &str) -> (BufReader<TcpStream>, String) {
+        let mut stream = BufReader::new(TcpStream::connect(server.local_addr()).await.unwrap());
+        let auth = encode(&ClientAuth {
+            token: token.to_string(),
+        })
+        .unwrap();
+        stream.get_mut().write_all(auth.as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        stream.read_line(&mut reply).await.unwrap();
+        (stream, reply)
+    }
+
+    #[tokio::test]
+    async fn test_overlong_lines_are_refused() {
+        let server =
+            GatewayServer::bind_registry(ProbeRegistry::default(), "127.0.0.1:0", "secret".into())
+                .await
+                .unwrap();
+        let (_, reply) =
+            authenticate(&server, &"x".repeat(GatewayServer::MAX_AUTH_LINE_BYTES)).await;
+        assert!(matches!(
+            decode(&reply),
+            Ok(ServerMessage::Response { error: Some(_), .. })
+        ));
+
+        // An endless request line drops the client rather than being buffered
+        let (mut stream, reply) = authenticate(&server, "secret").await;
+        assert!(matches!(
+            decode(&reply),
+            Ok(ServerMessage::Response { error: None, .. })
+        ));
+        let line = vec![b'x'; GatewayServer::MAX_REQUEST_LINE_BYTES + 1];
+        stream.get_mut().write_all(&line).await.unwrap();
+        let mut rest = Vec::new();
+        let closed = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut rest));
+        assert!(closed.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_disconnects_clients() {
+        let server =
+            GatewayServer::bind_registry(ProbeRegistry::default(), "127.0.0.1:0", "secret".into())
+                .await
+                .unwrap();
+        let (mut stream, _) = authenticate(&server, "secret").await;
+
+        server.shutdown();
+        let mut rest = Vec::new();
+        let closed = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut rest));
+        assert!(closed.await.is_ok());
+    }
+
+    #[test]
+    fn test_command_allow_list() {
+        assert!(is_allowed(&ConfigCommand::SilenceAlarms));
+        assert!(!is_allowed(&ConfigCommand::ResetThermometer));
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "secreT"));
+        assert!(!tokens_match("secret", "secrets"));
+    }
+}
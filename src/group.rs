@@ -0,0 +1,345 @@
+//! Aggregate readings across probes in the same cook.
+//!
+//! A [`CookGroup`] combines the virtual temperatures of several probes, for
+//! example one per piece of the same roast, into [`GroupTemperatures`]: the
+//! mean, median, lowest, and highest reading of each virtual sensor across
+//! the members. The group publishes a new aggregate whenever a member
+//! updates, so dashboards can show the overall doneness of the cook as a
+//! single stream. [`CookGroup::aligned_logs`] exports the members' logs
+//! resampled onto a common time base.
+
+use crate::runtime::TaskHandle;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use std::sync::{Arc, Weak};
+use tokio::sync::broadcast;
+
+use crate::callbacks::{CallbackHandle, CallbackRegistry};
+use crate::data::{AlignedLogs, AlignmentOptions, VirtualSensor, VirtualTemperatures};
+use crate::probe::Probe;
+
+/// Summary of one virtual sensor across group members.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AggregateReading {
+    /// Mean reading in Celsius.
+    pub mean: f64,
+    /// Median reading in Celsius.
+    pub median: f64,
+    /// Lowest reading in Celsius.
+    pub min: f64,
+    /// Highest reading in Celsius.
+    pub max: f64,
+    /// Number of members with a reading.
+    pub count: usize,
+}
+
+impl AggregateReading {
+    /// Summarize readings, or `None` if there are none.
+    pub fn from_readings(readings: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut readings: Vec<f64> = readings.into_iter().filter(|r| r.is_finite()).collect();
+        if readings.is_empty() {
+            return None;
+        }
+        readings.sort_by(f64::total_cmp);
+
+        let count = readings.len();
+        let middle = count / 2;
+        let median = if count % 2 == 0 {
+            (readings[middle - 1] + readings[middle]) / 2.0
+        } else {
+            readings[middle]
+        };
+
+        Some(Self {
+            mean: readings.iter().sum::<f64>() / count as f64,
+            median,
+            min: readings[0],
+            max: readings[count - 1],
+            count,
+        })
+    }
+}
+
+/// Aggregate virtual temperatures of a cook group.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupTemperatures {
+    /// Core across members, if any member reports one.
+    pub core: Option<AggregateReading>,
+    /// Surface across members, if any member reports one.
+    pub surface: Option<AggregateReading>,
+    /// Ambient across members, if any member reports one.
+    pub ambient: Option<AggregateReading>,
+    /// When the aggregate was computed.
+    pub captured_at: DateTime<Utc>,
+}
+
+impl GroupTemperatures {
+    /// Aggregate the virtual temperatures of several probes.
+    pub fn from_members<'a>(members: impl IntoIterator<Item = &'a VirtualTemperatures>) -> Self {
+        let members: Vec<_> = members.into_iter().collect();
+        let aggregate = |sensor: VirtualSensor| {
+            AggregateReading::from_readings(members.iter().filter_map(|t| t.get(sensor)))
+        };
+
+        Self {
+            core: aggregate(VirtualSensor::Core),
+            surface: aggregate(VirtualSensor::Surface),
+            ambient: aggregate(VirtualSensor::Ambient),
+            captured_at: Utc::now(),
+        }
+    }
+
+    /// Get the aggregate of a virtual sensor.
+    pub fn get(&self, sensor: VirtualSensor) -> Option<&AggregateReading> {
+        match sensor {
+            VirtualSensor::Core => self.core.as_ref(),
+            VirtualSensor::Surface => self.surface.as_ref(),
+            VirtualSensor::Ambient => self.ambient.as_ref(),
+        }
+    }
+}
+
+/// A member probe and the task forwarding its updates.
+struct Member {
+    probe: Arc<Probe>,
+    forwarder: TaskHandle<()>,
+}
+
+/// State shared with the forwarding tasks.
+struct GroupInner {
+    /// Members, in the order they were added.
+    members: RwLock<Vec<Member>>,
+    /// Aggregate update channel.
+    update_tx: broadcast::Sender<GroupTemperatures>,
+}
+
+impl GroupInner {
+    /// Aggregate the readings of members that are not stale.
+    fn temperatures(&self) -> GroupTemperatures {
+        let readings: Vec<_> = self
+            .members
+            .read()
+            .iter()
+            .filter(|member| !member.probe.is_stale())
+            .map(|member| member.probe.virtual_temperatures())
+            .collect();
+        GroupTemperatures::from_members(&readings)
+    }
+}
+
+/// Probes monitoring the same cook.
+///
+/// Must be created within a Tokio runtime. Dropping the group stops its
+/// updates; the member probes are unaffected.
+pub struct CookGroup {
+    /// Group name.
+    name: String,
+    /// Members and update channel.
+    inner: Arc<GroupInner>,
+    /// Registered callbacks.
+    callbacks: CallbackRegistry,
+}
+
+impl CookGroup {
+    /// Create a group of probes.
+    pub fn new(name: impl Into<String>, probes: impl IntoIterator<Item = Arc<Probe>>) -> Self {
+        let (update_tx, _) = broadcast::channel(64);
+        let group = Self {
+            name: name.into(),
+            inner: Arc::new(GroupInner {
+                members: RwLock::new(Vec::new()),
+                update_tx,
+            }),
+            callbacks: CallbackRegistry::new(),
+        };
+        for probe in probes {
+            group.add(probe);
+        }
+        group
+    }
+
+    /// Get the group name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Add a probe to the group.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the probe was already a member.
+    pub fn add(&self, probe: Arc<Probe>) -> bool {
+        let mut members = self.inner.members.write();
+        if members
+            .iter()
+            .any(|m| m.probe.serial_number() == probe.serial_number())
+        {
+            return false;
+        }
+
+        let forwarder = spawn_forwarder(&probe, Arc::downgrade(&self.inner));
+        members.push(Member { probe, forwarder });
+        true
+    }
+
+    /// Remove a probe from the group by serial number.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the probe was a member.
+    pub fn remove(&self, serial_number: u32) -> bool {
+        let mut members = self.inner.members.write();
+        let Some(index) = members
+            .iter()
+            .position(|m| m.probe.serial_number() == serial_number)
+        else {
+            return false;
+        };
+        members.remove(index).forwarder.abort();
+        true
+    }
+
+    /// Get the member probes, in the order they were added.
+    pub fn members(&self) -> Vec<Arc<Probe>> {
+        self.inner
+            .members
+            .read()
+            .iter()
+            .map(|m| m.probe.clone())
+            .collect()
+    }
+
+    /// Get the number of member probes.
+    pub fn len(&self) -> usize {
+        self.inner.members.read().len()
+    }
+
+    /// Check if the group has no members.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Aggregate the members' current virtual temperatures.
+    ///
+    /// Stale members, which have not reported recently, are left out so an
+    /// out-of-range probe's last reading does not skew the aggregate.
+    pub fn temperatures(&self) -> GroupTemperatures {
+        self.inner.temperatures()
+    }
+
+    /// Resample the members' temperature logs onto a common time base,
+    /// with columns labeled by serial number.
+    pub fn aligned_logs(&self, options: &AlignmentOptions) -> AlignedLogs {
+        let logs: Vec<_> = self
+            .members()
+            .iter()
+            .map(|probe| (probe.serial_number_string(), probe.temperature_log()))
+            .collect();
+        AlignedLogs::new(logs.iter().map(|(label, log)| (label, log)), options)
+    }
+
+    /// Subscribe to aggregate updates, published whenever a member's
+    /// temperatures update.
+    pub fn subscribe(&self) -> broadcast::Receiver<GroupTemperatures> {
+        self.inner.update_tx.subscribe()
+    }
+
+    /// Register a callback for aggregate updates.
+    pub fn on_updated<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&GroupTemperatures) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "group_updated",
+            self.inner.update_tx.subscribe(),
+            move |temperatures| callback(&temperatures),
+        )
+    }
+}
+
+impl Drop for CookGroup {
+    fn drop(&mut self) {
+        for member in self.inner.members.read().iter() {
+            member.forwarder.abort();
+        }
+    }
+}
+
+/// Publish a new aggregate each time `probe`'s temperatures update.
+fn spawn_forwarder(probe: &Probe, group: Weak<GroupInner>) -> TaskHandle<()> {
+    let mut updates = probe.subscribe_temperatures();
+    crate::runtime::spawn(async move {
+        loop {
+            match updates.recv().await {
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+            let Some(group) = group.upgrade() else {
+                break;
+            };
+            let _ = group.update_tx.send(group.temperatures());
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_reading() {
+        assert!(AggregateReading::from_readings([]).is_none());
+
+        let odd = AggregateReading::from_readings([50.0, 40.0, 60.0]).unwrap();
+        assert_eq!((odd.min, odd.median, odd.max), (40.0, 50.0, 60.0));
+        assert!((odd.mean - 50.0).abs() < 1e-9);
+        assert_eq!(odd.count, 3);
+
+        let even = AggregateReading::from_readings([40.0, 44.0, 50.0, 70.0]).unwrap();
+        assert!((even.median - 47.0).abs() < 1e-9);
+        assert!((even.mean - 51.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_group_temperatures_skip_missing_readings() {
+        let members = [
+            VirtualTemperatures::new(Some(55.0), Some(80.0), None),
+            VirtualTemperatures::new(Some(61.0), None, None),
+            VirtualTemperatures::new(None, None, None),
+        ];
+        let group = GroupTemperatures::from_members(&members);
+
+        let core = group.get(VirtualSensor::Core).unwrap();
+        assert_eq!(core.count, 2);
+        assert_eq!((core.min, core.max), (55.0, 61.0));
+        assert_eq!(group.surface.unwrap().count, 1);
+        assert!(group.ambient.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stale_members_are_left_out() {
+        use crate::data::ProbeTemperatures;
+        use crate::external::ExternalSensorSource;
+
+        let probes: Vec<_> = [(1, 50.0), (2, 60.0)]
+            .into_iter()
+            .map(|(serial_number, core)| {
+                let probe =
+                    ExternalSensorSource::new(serial_number.to_string(), serial_number).probe();
+                probe.update_from_readings(
+                    ProbeTemperatures::new(),
+                    VirtualTemperatures::new(Some(core), None, None),
+                );
+                probe
+            })
+            .collect();
+        let group = CookGroup::new("Roast", probes.clone());
+        assert_eq!(group.temperatures().core.unwrap().count, 2);
+
+        probes[1].mark_updated_at(std::time::Instant::now() - Probe::DEFAULT_STALE_TIMEOUT * 2);
+        let core = group.temperatures().core.unwrap();
+        assert_eq!((core.count, core.mean), (1, 50.0));
+    }
+}
@@ -0,0 +1,207 @@
+//! Lightweight references to discovered probes.
+//!
+//! A [`ProbeHandle`] names a probe by serial number and refers to it weakly,
+//! so listing probes does not copy their state or keep them alive after the
+//! [`DeviceManager`](crate::DeviceManager) forgets them, e.g. on shutdown.
+//! Handles from the manager look the probe up by serial number each time,
+//! so they keep working when the probe is forgotten and discovered again.
+//! Upgrade a handle to reach the full [`Probe`] API:
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! # fn example(manager: &DeviceManager) -> combustion_rust_ble::Result<()> {
+//! for handle in manager.probe_handles() {
+//!     let probe = handle.probe()?;
+//!     println!("{}: {:?}", handle.serial_number_string(), probe.rssi());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+use crate::snapshot::ProbeSnapshot;
+
+/// Probes known to a manager, by serial number (as hex string).
+type ProbeMap = RwLock<HashMap<String, Arc<Probe>>>;
+
+/// Cheap, clonable reference to a probe.
+///
+/// Two handles are equal if they name the same serial number and come from
+/// the same manager, or refer to the same probe instance.
+#[derive(Clone)]
+pub struct ProbeHandle {
+    /// Serial number of the probe.
+    serial_number: u32,
+    /// Where the probe is found.
+    target: Target,
+}
+
+/// What a handle refers to.
+#[derive(Clone)]
+enum Target {
+    /// One probe instance, while anything still holds it.
+    Probe(Weak<Probe>),
+    /// Whichever probe a manager holds under the serial number, while the
+    /// manager exists.
+    Manager(Weak<ProbeMap>),
+}
+
+impl ProbeHandle {
+    /// Create a handle to `probe`.
+    ///
+    /// The handle refers to this probe instance; use
+    /// [`DeviceManager::probe_handle`](crate::DeviceManager::probe_handle)
+    /// for one that follows the probe through rediscovery.
+    pub fn new(probe: &Arc<Probe>) -> Self {
+        Self {
+            serial_number: probe.serial_number(),
+            target: Target::Probe(Arc::downgrade(probe)),
+        }
+    }
+
+    /// Create a handle resolving `serial_number` through a manager's probes.
+    pub(crate) fn in_manager(serial_number: u32, probes: &Arc<ProbeMap>) -> Self {
+        Self {
+            serial_number,
+            target: Target::Manager(Arc::downgrade(probes)),
+        }
+    }
+
+    /// Get the serial number of the probe.
+    pub fn serial_number(&self) -> u32 {
+        self.serial_number
+    }
+
+    /// Get the serial number as a hex string (e.g., "100120BA").
+    pub fn serial_number_string(&self) -> String {
+        format!("{:08X}", self.serial_number)
+    }
+
+    /// Get the probe, or `None` if it has been dropped or its manager no
+    /// longer knows it.
+    pub fn upgrade(&self) -> Option<Arc<Probe>> {
+        match &self.target {
+            Target::Probe(probe) => probe.upgrade(),
+            Target::Manager(probes) => probes
+                .upgrade()?
+                .read()
+                .get(&self.serial_number_string())
+                .cloned(),
+        }
+    }
+
+    /// Get the probe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ProbeNotFound`] if the probe has been dropped or its
+    /// manager no longer knows it.
+    pub fn probe(&self) -> Result<Arc<Probe>> {
+        self.upgrade().ok_or_else(|| Error::ProbeNotFound {
+            identifier: self.serial_number_string(),
+        })
+    }
+
+    /// Check if the probe still exists.
+    pub fn is_alive(&self) -> bool {
+        self.upgrade().is_some()
+    }
+
+    /// Capture a snapshot of the probe's current state, or `None` if it has
+    /// been dropped.
+    pub fn snapshot(&self) -> Option<ProbeSnapshot> {
+        self.upgrade().map(|probe| probe.snapshot())
+    }
+}
+
+impl From<&Arc<Probe>> for ProbeHandle {
+    fn from(probe: &Arc<Probe>) -> Self {
+        Self::new(probe)
+    }
+}
+
+impl PartialEq for ProbeHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.serial_number == other.serial_number
+            && match (&self.target, &other.target) {
+                (Target::Probe(a), Target::Probe(b)) => Weak::ptr_eq(a, b),
+                (Target::Manager(a), Target::Manager(b)) => Weak::ptr_eq(a, b),
+                _ => false,
+            }
+    }
+}
+
+impl Eq for ProbeHandle {}
+
+impl Hash for ProbeHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.serial_number.hash(state);
+    }
+}
+
+impl fmt::Debug for ProbeHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProbeHandle")
+            .field("serial_number", &self.serial_number_string())
+            .field("alive", &self.is_alive())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::external::ExternalSensorSource;
+
+    #[test]
+    fn test_handle_does_not_keep_probe_alive() {
+        let probe = ExternalSensorSource::new("pit".to_string(), 0xE000_0001).probe();
+        let handle = ProbeHandle::new(&probe);
+        assert_eq!(handle.serial_number_string(), "E0000001");
+        assert_eq!(handle, ProbeHandle::from(&probe));
+        assert!(Arc::ptr_eq(&handle.probe().unwrap(), &probe));
+
+        drop(probe);
+        assert!(!handle.is_alive());
+        assert!(handle.snapshot().is_none());
+        assert!(matches!(
+            handle.probe(),
+            Err(Error::ProbeNotFound { identifier }) if identifier == "E0000001"
+        ));
+    }
+
+    #[test]
+    fn test_manager_handle_follows_rediscovery() {
+        let probes: Arc<ProbeMap> = Arc::default();
+        let handle = ProbeHandle::in_manager(0xE000_0001, &probes);
+        assert!(!handle.is_alive());
+
+        let first = ExternalSensorSource::new("pit".to_string(), 0xE000_0001).probe();
+        probes.write().insert("E0000001".to_string(), first.clone());
+        assert!(Arc::ptr_eq(&handle.probe().unwrap(), &first));
+
+        // Forgotten and discovered again as a new instance
+        probes.write().clear();
+        assert!(handle.probe().is_err());
+        let second = ExternalSensorSource::new("pit".to_string(), 0xE000_0001).probe();
+        probes
+            .write()
+            .insert("E0000001".to_string(), second.clone());
+        assert!(Arc::ptr_eq(&handle.probe().unwrap(), &second));
+
+        assert_eq!(handle, ProbeHandle::in_manager(0xE000_0001, &probes));
+        assert_ne!(handle, ProbeHandle::new(&second));
+
+        drop(probes);
+        assert!(handle.probe().is_err());
+    }
+}
@@ -22,7 +22,11 @@
 //! - **Temperature Logging**: Download complete temperature history
 //! - **Prediction Engine**: Set target temperatures and get time predictions
 //! - **Food Safety**: SafeCook/USDA Safe compliance monitoring
+//! - **Battery Warnings**: Estimate remaining battery life and warn before the probe's low flag
+//! - **HACCP Logging**: Record stabilized instant-read measurements for inspections
 //! - **Multi-probe Support**: Manage up to 8 probes simultaneously
+//! - **External Sensors**: Feed readings from other thermometers in as pseudo-probes
+//! - **Cook Groups**: Aggregate readings across probes in the same cook
 //!
 //! ## Quick Start
 //!
@@ -62,45 +66,258 @@
 //! Requires Bluetooth permission. Add `NSBluetoothAlwaysUsageDescription`
 //! to your Info.plist for bundled apps.
 //!
+//! Peripheral identifiers are per-host UUIDs that can change between
+//! launches. Persist [`Probe::serial_number`] instead and resolve it with
+//! [`DeviceManager::resolve_identifier`].
+//!
 //! ### Linux
 //! Requires BlueZ. User may need to be in the `bluetooth` group.
 //!
 //! ### Windows
 //! Requires Windows 10 or later with Bluetooth LE support.
 //!
+//! ## Runtimes
+//!
+//! Background tasks are spawned on the tokio runtime the library is called
+//! from, which may be a multi-thread or a current-thread runtime. To spawn
+//! them some other way, e.g. from a single-threaded service with its own
+//! executor, see [`runtime::set_spawner`].
+//!
+//! [`DeviceManager`], [`Probe`] and the snapshot and event types are `Send`
+//! and `Sync`, and the futures returned by their async methods are `Send`,
+//! so they can be shared between threads and spawned on a multi-thread
+//! runtime.
+//!
 //! ## Feature Flags
 //!
-//! - `serde`: Enable serialization/deserialization for data types
+//! No features are enabled by default, leaving the BLE and protocol stack
+//! with the fewest dependencies.
+//!
+//! - `serde`: Enable serialization/deserialization for data types and JSON exports
+//! - `cloud`: Relay probe snapshots to a remote HTTPS endpoint (implies `serde`)
+//! - `gateway`: Serve probes over TCP and consume them remotely via `RemoteBackend` (implies `serde`)
+//! - `persistence`: Store and browse historical cooking sessions and known probes on disk (implies `serde`)
+//! - `unstable`: Opt into APIs that may change in any release, such as raw GATT access via `Probe::raw_gatt`
+//! - `simulator`: Simulated probes with scripted fault injection for testing without hardware
+//! - `compression`: gzip/zstd-compressed log exports and history segments
+//! - `encryption`: ChaCha20-Poly1305 encrypted log exports, stored sessions and history, and session bundles
+//! - `charts`: Render session temperature curves to PNG/SVG via `SessionChart`
+//! - `bluez`: Apply custom `ScanOptions` through BlueZ on Linux
+//! - `winrt`: Full-rate advertisement scanning on Windows
+//! - `full`: All of the above except `unstable`
+//! - `tui`: Dependencies of the `probe_dashboard` example
 
 // Public modules
+pub mod audit;
+pub mod battery;
 pub mod ble;
+pub mod callbacks;
+pub mod capabilities;
+#[cfg(feature = "charts")]
+pub mod charts;
+pub mod claim;
+#[cfg(feature = "cloud")]
+pub mod cloud;
+pub mod command;
+#[cfg(feature = "compression")]
+pub mod compression;
 pub mod data;
+pub mod defaults;
+pub mod device;
 pub mod device_manager;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod error;
+pub mod external;
+pub mod fault;
+pub mod frames;
+#[cfg(feature = "gateway")]
+pub mod gateway;
+pub mod group;
+pub mod handle;
+pub mod overheat;
+#[cfg(feature = "persistence")]
+pub mod persistence;
 pub mod probe;
 pub mod protocol;
+pub mod quarantine;
+#[cfg(feature = "unstable")]
+pub mod raw_gatt;
+pub mod runtime;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+pub mod snapshot;
+pub mod timers;
+pub mod transport;
 pub mod utils;
+pub mod watch;
 
 // Re-exports for convenience
-pub use device_manager::{DeviceManager, MAX_PROBES};
+pub use audit::{CommandRecord, ConfigChangedExternally, ConfigCommand, ConfigKind, ConfigOutcome};
+pub use battery::{BatteryModel, BatteryProfiles, BatteryWarning};
+pub use callbacks::{CallbackError, CallbackInfo};
+pub use capabilities::{FirmwareVersion, ProbeCapabilities, ProtocolMismatch, StatusUnavailable};
+pub use claim::{ClaimAction, ClaimOptions, ProbeClaim};
+pub use command::{AlarmBound, Command, CommandOutcome, CommandOutput, ProbeSelector};
+pub use defaults::{DefaultsApplied, ProbeDefaults, ProbeMatch};
+pub use device::{CombustionDevice, DeviceSnapshot};
+pub use device_manager::{
+    DeviceManager, ManagerState, ProbeIdConflict, ScanRestarted, ShutdownGuard, WakeEvent,
+    MAX_PROBES,
+};
 pub use error::{Error, Result};
-pub use probe::{CallbackHandle, Probe};
-pub use utils::{celsius_to_fahrenheit, fahrenheit_to_celsius};
+pub use external::ExternalSensorSource;
+pub use fault::{FaultInfo, ProbeFaulted};
+pub use frames::SnapshotFrames;
+pub use group::{AggregateReading, CookGroup, GroupTemperatures};
+pub use handle::ProbeHandle;
+pub use overheat::{OverheatEvent, OverheatPolicy, SensorGroup};
+pub use probe::{CallbackHandle, InstantReading, ModeChange, Probe, ReadingSource};
+pub use protocol::ProbeStatus;
+pub use quarantine::{PayloadQuarantine, PayloadSource, QuarantinedPayload};
+pub use snapshot::{ChangedField, CrossingDirection, ProbeSnapshot, StateDiff, ThresholdCrossing};
+pub use timers::{AlarmEvent, TimerAlarm};
+pub use transport::ProbeTransport;
+pub use utils::{
+    celsius_to_fahrenheit, fahrenheit_to_celsius, format_temperature, format_temperature_dual,
+    parse_temperature, Temperature, TemperatureFormat, TemperatureUnit,
+};
+pub use watch::{ThresholdCondition, ThresholdWatch};
 
 // Re-export commonly used types from submodules
-pub use ble::advertising::{BatteryStatus, Overheating, ProbeColor, ProbeId, ProbeMode};
-pub use ble::connection::ConnectionState;
+pub use ble::advertising::{
+    BatteryStatus, Overheating, ProbeColor, ProbeId, ProbeMode, ProductType,
+};
+pub use ble::connection::{
+    ConnectionMetrics, ConnectionState, ConnectionUnhealthy, HeartbeatPolicy,
+};
+pub use ble::scanner::{DutyCycle, ScanMode, ScanOptions, ScanPhase};
 pub use data::{
-    AlarmConfig, AlarmStatus, FoodSafeConfig, FoodSafeData, FoodSafeMode, FoodSafeProduct,
-    FoodSafeServingState, FoodSafeState, FoodSafeStatus, IntegratedProduct, LoggedDataPoint,
-    PowerMode, PredictionInfo, PredictionLog, PredictionMode, PredictionState, PredictionType,
-    ProbeTemperatures, RawTemperature, Serving, SessionInfo, SimplifiedProduct, TemperatureLog,
-    ThermometerPreferences, VirtualSensorSelection, VirtualTemperatures,
+    AlarmConfig, AlarmSensor, AlarmStatus, AlignedLogs, AlignedRow, AlignmentOptions, Annotation,
+    AppExportMetadata, CatalogEntry, CatalogProduct, ClockAnchor, CookSegment, CookSpec,
+    CoolingEvent, CoolingMonitor, CoolingPhase, CoolingProfile, CoolingStage, CsvExportOptions,
+    CsvUnits, Extremes, FoodSafeConfig, FoodSafeData, FoodSafeMode, FoodSafeProduct,
+    FoodSafeServingState, FoodSafeState, FoodSafeStatus, HaccpLog, HaccpRecord, IntegratedProduct,
+    Interpolation, LocalizedProduct, LoggedDataPoint, MergedCook, Outlier, OutlierAction,
+    OutlierReason, PhysicalSensor, PowerMode, PredictionInfo, PredictionLog, PredictionMode,
+    PredictionState, PredictionType, ProbeTemperatures, ProductLocalization, ProfilePoint,
+    RawTemperature, SanityLimits, SanityStats, SearingEvent, SearingMonitor, SearingThresholds,
+    SensorLayout, SequenceClock, Serving, SessionInfo, SetpointReached, SimplifiedProduct,
+    Smoother, SmoothingFilter, SyncComplete, SyncProgress, TemperatureLog, ThermalProfile,
+    ThermometerPreferences, VirtualExtremes, VirtualSensor, VirtualSensorSelection,
+    VirtualTemperatures,
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use static_assertions::assert_impl_all;
+
+    // Shared across threads by multi-threaded apps and FFI layers
+    assert_impl_all!(DeviceManager: Send, Sync);
+    assert_impl_all!(ShutdownGuard<'static>: Send, Sync);
+    assert_impl_all!(Probe: Send, Sync);
+    assert_impl_all!(std::sync::Arc<Probe>: Send, Sync);
+    assert_impl_all!(CookGroup: Send, Sync);
+    assert_impl_all!(ProbeHandle: Send, Sync, Clone);
+    assert_impl_all!(ThresholdWatch: Send, Sync);
+    assert_impl_all!(SnapshotFrames: Send, Sync);
+    assert_impl_all!(CallbackHandle: Send, Sync);
+    assert_impl_all!(runtime::TaskHandle<()>: Send, Sync);
+    assert_impl_all!(PayloadQuarantine: Send, Sync);
+    assert_impl_all!(ExternalSensorSource: Send, Sync);
+    assert_impl_all!(Error: Send, Sync);
+    #[cfg(feature = "simulator")]
+    assert_impl_all!(simulator::SimulatedProbe: Send, Sync);
+    #[cfg(feature = "gateway")]
+    assert_impl_all!(gateway::RemoteBackend: Send, Sync);
+    #[cfg(feature = "gateway")]
+    assert_impl_all!(gateway::GatewayServer: Send, Sync);
+    #[cfg(feature = "cloud")]
+    assert_impl_all!(cloud::CloudRelay: Send, Sync);
+    #[cfg(feature = "persistence")]
+    assert_impl_all!(persistence::SessionStore: Send, Sync);
+    #[cfg(feature = "persistence")]
+    assert_impl_all!(persistence::HistoryStore: Send, Sync);
+    #[cfg(feature = "persistence")]
+    assert_impl_all!(persistence::KnownProbeRegistry: Send, Sync);
+    #[cfg(feature = "persistence")]
+    assert_impl_all!(persistence::TimerStore: Send, Sync);
+
+    // Snapshots and events are handed between tasks
+    assert_impl_all!(ProbeSnapshot: Send, Sync, Clone);
+    assert_impl_all!(DeviceSnapshot: Send, Sync, Clone);
+    assert_impl_all!(StateDiff: Send, Sync, Clone);
+    assert_impl_all!(device_manager::ProbeEvent: Send, Sync, Clone);
+    assert_impl_all!(ProbeIdConflict: Send, Sync, Clone);
+    assert_impl_all!(WakeEvent: Send, Sync, Clone);
+    assert_impl_all!(ScanRestarted: Send, Sync, Clone);
+    assert_impl_all!(ModeChange: Send, Sync, Clone);
+    assert_impl_all!(InstantReading: Send, Sync, Clone);
+    assert_impl_all!(ProbeFaulted: Send, Sync, Clone);
+    assert_impl_all!(OverheatEvent: Send, Sync, Clone);
+    assert_impl_all!(ConnectionUnhealthy: Send, Sync, Clone);
+    assert_impl_all!(BatteryWarning: Send, Sync, Clone);
+    assert_impl_all!(AlarmEvent: Send, Sync, Clone);
+    assert_impl_all!(DefaultsApplied: Send, Sync, Clone);
+    assert_impl_all!(StatusUnavailable: Send, Sync, Clone);
+    assert_impl_all!(ProtocolMismatch: Send, Sync, Clone);
+    assert_impl_all!(CallbackError: Send, Sync, Clone);
+    assert_impl_all!(probe::TemperatureUpdate: Send, Sync, Clone);
+    assert_impl_all!(PredictionInfo: Send, Sync, Clone);
+    assert_impl_all!(TemperatureLog: Send, Sync, Clone);
+    assert_impl_all!(CommandOutcome: Send, Sync);
+
+    // Trait objects the library holds behind `Arc`
+    assert_impl_all!(dyn ProbeTransport: Send, Sync);
+    assert_impl_all!(dyn runtime::Spawn: Send, Sync);
+
+    /// Futures from the async API must be `Send` to be spawned on a
+    /// multi-threaded runtime. Checked at compile time, never called.
+    #[allow(dead_code)]
+    fn assert_futures_are_send(
+        manager: &DeviceManager,
+        probe: &Probe,
+        command: &Command,
+        watch: &mut ThresholdWatch,
+    ) {
+        fn send<T: Send>(_: T) {}
+        send(DeviceManager::new());
+        send(manager.start_scanning());
+        send(manager.start_scanning_guarded());
+        send(manager.stop_scanning());
+        send(manager.pause());
+        send(manager.resume());
+        send(manager.select_probe_interactively(15));
+        send(manager.auto_assign_ids());
+        send(manager.execute(command));
+        send(manager.shutdown());
+        send(probe.connect());
+        send(probe.disconnect());
+        send(probe.refresh_rssi());
+        send(probe.recycle_connection());
+        send(probe.set_prediction(PredictionMode::TimeToRemoval, 63.0));
+        send(probe.cancel_prediction());
+        send(probe.configure_food_safe_with_config(FoodSafeConfig::default()));
+        send(probe.set_power_mode(PowerMode::Normal));
+        send(probe.set_alarms(&AlarmConfig::new()));
+        send(probe.start_cook(CookSpec::new(FoodSafeProduct::BeefSteak, 54.0)));
+        send(probe.set_id(ProbeId::default()));
+        send(probe.wait_until_core_at_least(63.0, std::time::Duration::ZERO));
+        send(probe.wait_until_food_safe(std::time::Duration::ZERO));
+        send(probe.read_session_info());
+        send(probe.read_firmware_version());
+        send(command.run(probe));
+        send(watch.wait());
+        #[cfg(feature = "gateway")]
+        send(gateway::RemoteBackend::connect("127.0.0.1:0", "token"));
+        #[cfg(feature = "gateway")]
+        send(gateway::GatewayServer::bind(
+            manager,
+            "127.0.0.1:0",
+            "token",
+        ));
+    }
 
     #[test]
     fn test_public_exports() {
@@ -112,6 +329,7 @@ mod tests {
         let _ = std::any::TypeId::of::<VirtualTemperatures>();
         let _ = std::any::TypeId::of::<PredictionInfo>();
         let _ = std::any::TypeId::of::<FoodSafeData>();
+        let _ = std::any::TypeId::of::<ProbeStatus>();
     }
 
     #[test]
@@ -0,0 +1,245 @@
+//! Automatic protective action when probe sensors overheat.
+//!
+//! An [`OverheatPolicy`] set with
+//! [`Probe::set_overheat_policy`](crate::Probe::set_overheat_policy) watches
+//! each [`SensorGroup`] against a temperature limit and the probe's own
+//! overheating flags. When a group starts overheating the probe publishes an
+//! [`OverheatEvent`], records it with the session, and optionally silences
+//! alarms that would otherwise keep sounding for the same condition.
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::Overheating;
+use crate::data::ProbeTemperatures;
+
+/// Group of physical sensors with a shared temperature rating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SensorGroup {
+    /// Sensors T1-T4, nearest the tip.
+    Internal,
+    /// Sensors T5-T8, nearest the handle.
+    Handle,
+}
+
+impl SensorGroup {
+    /// All sensor groups.
+    pub const ALL: [SensorGroup; 2] = [SensorGroup::Internal, SensorGroup::Handle];
+
+    /// Get the sensor indices (0-7) in this group.
+    pub fn sensors(&self) -> std::ops::Range<usize> {
+        match self {
+            Self::Internal => 0..4,
+            Self::Handle => 4..8,
+        }
+    }
+
+    /// Get the display name of this group.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Internal => "Internal",
+            Self::Handle => "Handle",
+        }
+    }
+}
+
+/// When and how to react to overheating sensors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverheatPolicy {
+    /// Limit for the internal sensors in Celsius, or `None` for no limit.
+    pub internal_limit: Option<f64>,
+    /// Limit for the handle sensors in Celsius, or `None` for no limit.
+    pub handle_limit: Option<f64>,
+    /// Also treat the probe's own overheating flags as exceeding the limit.
+    pub use_probe_flags: bool,
+    /// Silence the probe's alarms when a group starts overheating.
+    pub silence_alarms: bool,
+}
+
+impl OverheatPolicy {
+    /// Create a policy that reacts to the probe's overheating flags only and
+    /// silences alarms.
+    pub fn new() -> Self {
+        Self {
+            internal_limit: None,
+            handle_limit: None,
+            use_probe_flags: true,
+            silence_alarms: true,
+        }
+    }
+
+    /// Set the limit for a sensor group in Celsius.
+    pub fn with_limit(mut self, group: SensorGroup, celsius: f64) -> Self {
+        match group {
+            SensorGroup::Internal => self.internal_limit = Some(celsius),
+            SensorGroup::Handle => self.handle_limit = Some(celsius),
+        }
+        self
+    }
+
+    /// Set whether the probe's own overheating flags count.
+    pub fn with_probe_flags(mut self, enabled: bool) -> Self {
+        self.use_probe_flags = enabled;
+        self
+    }
+
+    /// Set whether alarms are silenced when a group starts overheating.
+    pub fn with_silence_alarms(mut self, enabled: bool) -> Self {
+        self.silence_alarms = enabled;
+        self
+    }
+
+    /// Get the limit for a sensor group in Celsius.
+    pub fn limit(&self, group: SensorGroup) -> Option<f64> {
+        match group {
+            SensorGroup::Internal => self.internal_limit,
+            SensorGroup::Handle => self.handle_limit,
+        }
+    }
+}
+
+impl Default for OverheatPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A sensor group started overheating.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverheatEvent {
+    /// Group that is overheating.
+    pub group: SensorGroup,
+    /// Indices (0-7) of the sensors in the group over the limit or flagged by
+    /// the probe.
+    pub sensors: Vec<usize>,
+    /// Highest temperature in the group in Celsius, if any sensor is valid.
+    pub peak_temperature: Option<f64>,
+    /// Limit in effect in Celsius, if any.
+    pub limit: Option<f64>,
+    /// When the condition was detected.
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Applies an [`OverheatPolicy`] to successive readings.
+///
+/// Each group raises one event when it starts overheating and re-arms once
+/// it has cleared.
+#[derive(Debug, Clone)]
+pub(crate) struct OverheatMonitor {
+    /// Policy being applied.
+    policy: OverheatPolicy,
+    /// Whether each group in [`SensorGroup::ALL`] is currently overheating.
+    active: [bool; 2],
+}
+
+impl OverheatMonitor {
+    /// Create a monitor for a policy.
+    pub(crate) fn new(policy: OverheatPolicy) -> Self {
+        Self {
+            policy,
+            active: [false; 2],
+        }
+    }
+
+    /// Get the policy being applied.
+    pub(crate) fn policy(&self) -> &OverheatPolicy {
+        &self.policy
+    }
+
+    /// Check a reading, returning an event for each group that has just
+    /// started overheating.
+    pub(crate) fn check(
+        &mut self,
+        temperatures: &ProbeTemperatures,
+        flags: Overheating,
+        now: DateTime<Utc>,
+    ) -> Vec<OverheatEvent> {
+        let celsius = temperatures.to_celsius();
+        let mut events = Vec::new();
+
+        for (slot, group) in SensorGroup::ALL.iter().enumerate() {
+            let limit = self.policy.limit(*group);
+            let sensors: Vec<usize> = group
+                .sensors()
+                .filter(|&i| {
+                    let over_limit = matches!((celsius[i], limit), (Some(t), Some(l)) if t > l);
+                    let flagged = self.policy.use_probe_flags && flags.is_sensor_overheating(i);
+                    over_limit || flagged
+                })
+                .collect();
+
+            let overheating = !sensors.is_empty();
+            if overheating && !self.active[slot] {
+                events.push(OverheatEvent {
+                    group: *group,
+                    sensors,
+                    peak_temperature: group
+                        .sensors()
+                        .filter_map(|i| celsius[i])
+                        .fold(None, |max: Option<f64>, t| {
+                            Some(max.map_or(t, |m| m.max(t)))
+                        }),
+                    limit,
+                    detected_at: now,
+                });
+            }
+            self.active[slot] = overheating;
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RawTemperature;
+
+    fn temperatures(celsius: [f64; 8]) -> ProbeTemperatures {
+        let mut temperatures = ProbeTemperatures::new();
+        for (value, t) in temperatures.values.iter_mut().zip(celsius) {
+            *value = RawTemperature::from_celsius(t);
+        }
+        temperatures
+    }
+
+    #[test]
+    fn test_limit_raises_one_event_per_episode() {
+        let policy = OverheatPolicy::new().with_limit(SensorGroup::Handle, 300.0);
+        let mut monitor = OverheatMonitor::new(policy);
+        let now = Utc::now();
+
+        let hot = temperatures([50.0, 50.0, 50.0, 60.0, 120.0, 200.0, 280.0, 310.0]);
+        let events = monitor.check(&hot, Overheating::default(), now);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].group, SensorGroup::Handle);
+        assert_eq!(events[0].sensors, vec![7]);
+        assert_eq!(events[0].limit, Some(300.0));
+        assert!((events[0].peak_temperature.unwrap() - 310.0).abs() < 0.1);
+
+        // Still hot: no repeat
+        assert!(monitor.check(&hot, Overheating::default(), now).is_empty());
+
+        // Cools down, then overheats again
+        let cool = temperatures([50.0; 8]);
+        assert!(monitor.check(&cool, Overheating::default(), now).is_empty());
+        assert_eq!(monitor.check(&hot, Overheating::default(), now).len(), 1);
+    }
+
+    #[test]
+    fn test_probe_flags() {
+        let cool = temperatures([50.0; 8]);
+        let flags = Overheating::new(0b0000_0001);
+
+        let mut monitor = OverheatMonitor::new(OverheatPolicy::new());
+        let events = monitor.check(&cool, flags, Utc::now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].group, SensorGroup::Internal);
+        assert_eq!(events[0].sensors, vec![0]);
+
+        let mut monitor = OverheatMonitor::new(OverheatPolicy::new().with_probe_flags(false));
+        assert!(monitor.check(&cool, flags, Utc::now()).is_empty());
+    }
+}
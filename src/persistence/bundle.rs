@@ -0,0 +1,311 @@
+//! Session bundles for bug reports.
+//!
+//! [`Probe::export_session_bundle`] writes everything needed to look into a
+//! cook that went wrong, such as a prediction that was off, to one
+//! directory, ready to zip and attach to a report:
+//!
+//! | File | Contents |
+//! |------|----------|
+//! | `log.csv` | The temperature log, with prediction columns and annotations |
+//! | `events.json` | Annotations, overheat events and timers |
+//! | `audit.json` | Configuration commands sent to the probe |
+//! | `diagnostics.json` | Snapshot, firmware, connection and parse diagnostics |
+//! | `report.txt` | A readable summary of the cook |
+//! | `chart.svg` | The temperature curves, with the `charts` feature |
+//!
+//! The log, report and chart give temperatures in the unit passed in, such
+//! as the [`Config::temperature_unit`](super::Config::temperature_unit)
+//! preference; the JSON files are always in Celsius.
+//!
+//! With the `encryption` feature, `Probe::export_session_bundle_encrypted`
+//! seals each file with an `EncryptionKey`, keeping the file names, for
+//! bundles kept as records rather than sent off straight away.
+//!
+//! ```rust,no_run
+//! # use combustion_rust_ble::{Probe, TemperatureUnit};
+//! # fn example(probe: &Probe) -> combustion_rust_ble::Result<()> {
+//! let bundle = probe.export_session_bundle("bug-report", TemperatureUnit::Fahrenheit)?;
+//! println!("Attach the files in {}", bundle.dir.display());
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::{storage_error, to_json, write_atomic};
+use crate::ble::connection::ConnectionMetrics;
+use crate::ble::discovery::DiscoveryReport;
+use crate::capabilities::ProbeCapabilities;
+use crate::data::{Annotation, CsvExportOptions, CsvUnits, SanityStats, TemperatureLog};
+use crate::error::Result;
+use crate::overheat::OverheatEvent;
+use crate::probe::Probe;
+use crate::quarantine::QuarantinedPayload;
+use crate::snapshot::ProbeSnapshot;
+use crate::timers::TimerAlarm;
+use crate::utils::{TemperatureFormat, TemperatureUnit};
+
+/// Name of the temperature log file in a bundle.
+pub const LOG_FILE: &str = "log.csv";
+
+/// Name of the event file in a bundle.
+pub const EVENTS_FILE: &str = "events.json";
+
+/// Name of the configuration audit file in a bundle.
+pub const AUDIT_FILE: &str = "audit.json";
+
+/// Name of the diagnostics file in a bundle.
+pub const DIAGNOSTICS_FILE: &str = "diagnostics.json";
+
+/// Name of the cook report in a bundle.
+pub const REPORT_FILE: &str = "report.txt";
+
+/// Name of the temperature chart in a bundle.
+#[cfg(feature = "charts")]
+pub const CHART_FILE: &str = "chart.svg";
+
+/// A session bundle written to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionBundle {
+    /// Directory holding the bundle.
+    pub dir: PathBuf,
+    /// Files written, in the order listed in the [module docs](self).
+    pub files: Vec<PathBuf>,
+}
+
+/// Contents of `events.json`.
+#[derive(Serialize)]
+struct BundleEvents {
+    annotations: Vec<Annotation>,
+    overheat: Vec<OverheatEvent>,
+    timers: Vec<TimerAlarm>,
+}
+
+/// Contents of `diagnostics.json`.
+#[derive(Serialize)]
+struct BundleDiagnostics {
+    library_version: &'static str,
+    created: DateTime<Utc>,
+    snapshot: ProbeSnapshot,
+    capabilities: ProbeCapabilities,
+    hardware_revision: Option<String>,
+    connection_metrics: Option<ConnectionMetrics>,
+    discovery_report: Option<DiscoveryReport>,
+    sanity_stats: SanityStats,
+    protocol_mismatch: Option<String>,
+    quarantined_payloads: Vec<QuarantinedPayload>,
+}
+
+/// Write a session bundle for `probe` into `dir`, creating it if needed,
+/// with temperatures in `unit`.
+///
+/// Files already in `dir` with the same names are replaced.
+pub(crate) fn write_bundle(
+    probe: &Probe,
+    dir: &Path,
+    unit: TemperatureUnit,
+) -> Result<SessionBundle> {
+    write_files(probe, dir, unit, |data| Ok(data.to_vec()))
+}
+
+/// Write a session bundle like [`write_bundle`], encrypting each file with
+/// `key`.
+#[cfg(feature = "encryption")]
+pub(crate) fn write_encrypted_bundle(
+    probe: &Probe,
+    dir: &Path,
+    unit: TemperatureUnit,
+    key: &crate::encryption::EncryptionKey,
+) -> Result<SessionBundle> {
+    write_files(probe, dir, unit, |data| key.encrypt(data))
+}
+
+/// Write the bundle files, passing each one's contents through `seal`.
+fn write_files(
+    probe: &Probe,
+    dir: &Path,
+    unit: TemperatureUnit,
+    seal: impl Fn(&[u8]) -> Result<Vec<u8>>,
+) -> Result<SessionBundle> {
+    std::fs::create_dir_all(dir).map_err(|e| storage_error(dir, e))?;
+    let log = probe.temperature_log();
+    let created = Utc::now();
+
+    let events = BundleEvents {
+        annotations: probe.annotations(),
+        overheat: probe.overheat_events(),
+        timers: probe.timers(),
+    };
+    let serial_number = probe.serial_number();
+    let diagnostics = BundleDiagnostics {
+        library_version: env!("CARGO_PKG_VERSION"),
+        created,
+        snapshot: probe.snapshot(),
+        capabilities: probe.capabilities(),
+        hardware_revision: probe.hardware_revision(),
+        connection_metrics: probe.connection_metrics(),
+        discovery_report: probe.discovery_report(),
+        sanity_stats: probe.sanity_stats(),
+        protocol_mismatch: probe.protocol_mismatch().map(|m| m.to_string()),
+        quarantined_payloads: probe
+            .quarantine()
+            .payloads()
+            .into_iter()
+            .filter(|payload| payload.serial_number == Some(serial_number))
+            .collect(),
+    };
+
+    let mut csv = CsvExportOptions::new()
+        .with_serial_number(serial_number)
+        .with_units(CsvUnits::for_unit(unit, false))
+        .with_exported_at(created);
+    if let Some(version) = diagnostics.capabilities.firmware_version {
+        csv = csv.with_firmware_version(version);
+    }
+
+    let mut files = Vec::new();
+    let mut write = |name: &str, data: &[u8]| -> Result<()> {
+        let path = dir.join(name);
+        write_atomic(&path, &seal(data)?)?;
+        files.push(path);
+        Ok(())
+    };
+    write(LOG_FILE, log.to_csv_with(&csv).as_bytes())?;
+    write(EVENTS_FILE, &to_json(&dir.join(EVENTS_FILE), &events)?)?;
+    write(
+        AUDIT_FILE,
+        &to_json(&dir.join(AUDIT_FILE), &probe.command_history())?,
+    )?;
+    write(
+        DIAGNOSTICS_FILE,
+        &to_json(&dir.join(DIAGNOSTICS_FILE), &diagnostics)?,
+    )?;
+    write(
+        REPORT_FILE,
+        cook_report(probe, &log, created, unit).as_bytes(),
+    )?;
+    #[cfg(feature = "charts")]
+    {
+        let chart = crate::charts::SessionChart::new(&log)
+            .with_title(format!("Probe {}", probe.serial_number_string()))
+            .with_unit(unit)
+            .to_svg()?;
+        write(CHART_FILE, chart.as_bytes())?;
+    }
+
+    Ok(SessionBundle {
+        dir: dir.to_path_buf(),
+        files,
+    })
+}
+
+/// Summarize the cook for a reader, with temperatures in `unit`.
+fn cook_report(
+    probe: &Probe,
+    log: &TemperatureLog,
+    created: DateTime<Utc>,
+    unit: TemperatureUnit,
+) -> String {
+    let format = TemperatureFormat::new(unit);
+    let capabilities = probe.capabilities();
+    let mut report = String::new();
+    let mut line = |text: String| {
+        report.push_str(&text);
+        report.push('\n');
+    };
+
+    line(format!(
+        "Cook report for probe {}",
+        probe.serial_number_string()
+    ));
+    line(format!(
+        "Created {} by combustion-rust-ble {}",
+        created.to_rfc3339(),
+        env!("CARGO_PKG_VERSION")
+    ));
+    line(format!(
+        "Firmware: {}",
+        capabilities
+            .firmware_version
+            .map_or_else(|| "unknown".to_string(), |v| v.to_string())
+    ));
+    if let Some(revision) = probe.hardware_revision() {
+        line(format!("Hardware: {}", revision));
+    }
+    line(format!("Mode: {:?}", probe.mode()));
+
+    line(String::new());
+    line(format!(
+        "Session {:08X}: {} samples over {}s, {:.0}% synced",
+        log.session_id,
+        log.len(),
+        log.duration().as_secs(),
+        probe.percent_of_logs_synced()
+    ));
+    if let Some(cook) = probe.active_cook() {
+        line(format!(
+            "Cook: {:?} to {}",
+            cook.product,
+            format.format(cook.target)
+        ));
+    }
+
+    match probe.prediction_info() {
+        Some(prediction) => {
+            line(format!(
+                "Prediction: {:?}, {:?} {:?}, set point {}",
+                prediction.state,
+                prediction.mode,
+                prediction.prediction_type,
+                format.format(prediction.set_point_temperature)
+            ));
+            line(format!(
+                "  Estimated core {}, {}s remaining, {}s since start, core sensor T{}",
+                format.format(prediction.estimated_core_temperature),
+                prediction.prediction_value_seconds,
+                prediction.seconds_since_prediction_start,
+                prediction.core_sensor_index + 1
+            ));
+        }
+        None => line("Prediction: none".to_string()),
+    }
+
+    let extremes = probe.extremes();
+    for (name, extremes) in [
+        ("Core", extremes.core),
+        ("Surface", extremes.surface),
+        ("Ambient", extremes.ambient),
+    ] {
+        if let Some(extremes) = extremes {
+            line(format!(
+                "{}: {} to {}",
+                name,
+                format.format(extremes.min),
+                format.format(extremes.max)
+            ));
+        }
+    }
+
+    if let Some(food_safe) = probe.food_safe_data() {
+        line(format!("Food safety: {}", food_safe.summary(&format)));
+    }
+
+    let overheat = probe.overheat_events().len();
+    let commands = probe.command_history().len();
+    let annotations = probe.annotations().len();
+    line(String::new());
+    line(format!(
+        "{} annotations, {} overheat events, {} configuration commands",
+        annotations, overheat, commands
+    ));
+    if let Some(mismatch) = probe.protocol_mismatch() {
+        line(format!("Protocol mismatch: {}", mismatch));
+    }
+    if !capabilities.status_characteristic {
+        line("No Probe Status characteristic: readings from advertising only".to_string());
+    }
+
+    report
+}
@@ -0,0 +1,293 @@
+//! Application configuration file.
+//!
+//! Settings a deployment keeps between runs, such as the default cooks for
+//! each probe, live in one JSON [`Config`] file, so a kitchen configures
+//! them once rather than in code:
+//!
+//! ```json
+//! {
+//!   "probe_defaults": {
+//!     "cooks": [
+//!       {
+//!         "probe": { "Color": "Red" },
+//!         "spec": {
+//!           "product": "ChickenBreast",
+//!           "target": 74.0,
+//!           "prediction_mode": "TimeToRemoval",
+//!           "food_safe": {
+//!             "mode": "Simplified",
+//!             "product": 1,
+//!             "serving": "ServedImmediately",
+//!             "threshold_temperature": 74.0,
+//!             "z_value": 5.5,
+//!             "reference_temperature": 70.0,
+//!             "d_value_at_reference": 5.0,
+//!             "target_log_reduction": 6.5
+//!           },
+//!           "alarms": null
+//!         }
+//!       }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::persistence::Config;
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! # async fn example() -> combustion_rust_ble::Result<()> {
+//! let manager = DeviceManager::new().await?;
+//! manager.apply_config(&Config::load("combustion.json")?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A `"temperature_unit"` of `"Fahrenheit"` sets the unit the app shows
+//! and exports temperatures in, such as in
+//! [session bundles](crate::Probe::export_session_bundle); it is Celsius by
+//! default.
+//!
+//! Missing settings take their defaults, and a missing file is an empty
+//! configuration.
+//!
+//! With the `encryption` feature, an `"encryption"` section turns on
+//! encryption for stores opened with [`Config::session_store`] and
+//! [`Config::history_store`], and gives the key for encrypted session
+//! bundles, from `Probe::export_session_bundle_encrypted`, through
+//! `Config::encryption_key`. The key itself is never kept in the file; it
+//! is read from the environment variable the section names,
+//! `COMBUSTION_STORAGE_KEY` by default:
+//!
+//! ```json
+//! {
+//!   "encryption": { "key_env": "COMBUSTION_STORAGE_KEY", "plaintext_migration": false }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{read_json, write_json, HistoryStore, SessionStore};
+use crate::defaults::ProbeDefaults;
+#[cfg(feature = "encryption")]
+use crate::encryption::{EncryptionKey, ENCRYPTION_KEY_ENV};
+use crate::error::Result;
+use crate::utils::TemperatureUnit;
+
+/// Settings loaded from a configuration file.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Default cooks started on probes by color or ID; see
+    /// [`DeviceManager::set_probe_defaults`](crate::DeviceManager::set_probe_defaults).
+    pub probe_defaults: ProbeDefaults,
+
+    /// Unit to show and export temperatures in.
+    pub temperature_unit: TemperatureUnit,
+
+    /// Encryption of stored data, off if `None`.
+    #[cfg(feature = "encryption")]
+    pub encryption: Option<EncryptionConfig>,
+}
+
+/// Where to find the key stored data is encrypted with.
+#[cfg(feature = "encryption")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// Environment variable holding the key as 64 hex digits.
+    pub key_env: String,
+
+    /// Read data saved before encryption was enabled; see
+    /// [`SessionStore::with_plaintext_migration`] and
+    /// [`HistoryStore::with_plaintext_migration`].
+    pub plaintext_migration: bool,
+}
+
+#[cfg(feature = "encryption")]
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            key_env: ENCRYPTION_KEY_ENV.to_string(),
+            plaintext_migration: false,
+        }
+    }
+}
+
+impl Config {
+    /// Load a configuration file, or the defaults if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the file cannot
+    /// be read or parsed, or
+    /// [`Error::InvalidParameter`](crate::Error::InvalidParameter) if a
+    /// default cook is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config: Self = read_json(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Write the configuration to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the file cannot
+    /// be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        write_json(path.as_ref(), self)
+    }
+
+    /// Set the default cooks.
+    pub fn with_probe_defaults(mut self, probe_defaults: ProbeDefaults) -> Self {
+        self.probe_defaults = probe_defaults;
+        self
+    }
+
+    /// Set the unit to show and export temperatures in.
+    pub fn with_temperature_unit(mut self, unit: TemperatureUnit) -> Self {
+        self.temperature_unit = unit;
+        self
+    }
+
+    /// Open a session store with the configured encryption.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the directory
+    /// cannot be created, or
+    /// [`Error::InvalidParameter`](crate::Error::InvalidParameter) if
+    /// encryption is configured and its key variable is unset or invalid.
+    pub fn session_store(&self, root: impl Into<std::path::PathBuf>) -> Result<SessionStore> {
+        let store = SessionStore::open(root)?;
+        #[cfg(feature = "encryption")]
+        if let Some(encryption) = &self.encryption {
+            let store = store.with_encryption(EncryptionKey::from_env(&encryption.key_env)?);
+            return Ok(if encryption.plaintext_migration {
+                store.with_plaintext_migration()
+            } else {
+                store
+            });
+        }
+        Ok(store)
+    }
+
+    /// Open a history store with the configured encryption.
+    ///
+    /// # Errors
+    ///
+    /// As for [`session_store`](Self::session_store).
+    pub fn history_store(&self, root: impl Into<std::path::PathBuf>) -> Result<HistoryStore> {
+        let store = HistoryStore::open(root)?;
+        #[cfg(feature = "encryption")]
+        if let Some(encryption) = &self.encryption {
+            let store = store.with_encryption(EncryptionKey::from_env(&encryption.key_env)?);
+            return Ok(if encryption.plaintext_migration {
+                store.with_plaintext_migration()
+            } else {
+                store
+            });
+        }
+        Ok(store)
+    }
+
+    /// Get the configured encryption key, or `None` if encryption is off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`](crate::Error::InvalidParameter) if
+    /// encryption is configured and its key variable is unset or invalid.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_key(&self) -> Result<Option<EncryptionKey>> {
+        self.encryption
+            .as_ref()
+            .map(|encryption| EncryptionKey::from_env(&encryption.key_env))
+            .transpose()
+    }
+
+    /// Check the settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`](crate::Error::InvalidParameter)
+    /// if a default cook is invalid.
+    pub fn validate(&self) -> Result<()> {
+        self.probe_defaults
+            .cooks()
+            .iter()
+            .try_for_each(|cook| cook.spec.validate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::advertising::ProbeColor;
+    use crate::data::{CookSpec, FoodSafeProduct};
+    use crate::defaults::ProbeMatch;
+
+    #[test]
+    fn test_config_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("combustion-config-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        let config = Config::default()
+            .with_probe_defaults(ProbeDefaults::new().with(
+                ProbeMatch::Color(ProbeColor::Red),
+                CookSpec::new(FoodSafeProduct::ChickenBreast, 74.0),
+            ))
+            .with_temperature_unit(TemperatureUnit::Fahrenheit);
+        config.save(&path).unwrap();
+        assert_eq!(Config::load(&path).unwrap(), config);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains(r#""temperature_unit":"Fahrenheit""#));
+
+        std::fs::write(&path, "{}").unwrap();
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        let invalid = Config::default().with_probe_defaults(ProbeDefaults::new().with(
+            ProbeMatch::Color(ProbeColor::Red),
+            CookSpec::new(FoodSafeProduct::ChickenBreast, -5.0),
+        ));
+        invalid.save(&path).unwrap();
+        assert!(Config::load(&path).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_store_encryption() {
+        let root =
+            std::env::temp_dir().join(format!("combustion-config-sessions-{}", std::process::id()));
+        let var = "COMBUSTION_TEST_CONFIG_KEY";
+        let config: Config =
+            serde_json::from_str(&format!(r#"{{"encryption": {{"key_env": "{}"}}}}"#, var))
+                .unwrap();
+        assert_eq!(config.encryption.as_ref().unwrap().key_env, var);
+        assert!(!config.encryption.as_ref().unwrap().plaintext_migration);
+
+        // The key must be provisioned
+        assert!(config.session_store(&root).is_err());
+        assert!(config.history_store(root.join("history")).is_err());
+        assert!(config.encryption_key().is_err());
+        let key = EncryptionKey::generate();
+        std::env::set_var(var, key.to_hex());
+        assert!(config.session_store(&root).is_ok());
+        assert!(config.history_store(root.join("history")).is_ok());
+        assert_eq!(config.encryption_key().unwrap(), Some(key));
+        assert!(Config::default().session_store(&root).is_ok());
+        assert_eq!(Config::default().encryption_key().unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,953 @@
+//! Rotating temperature history for long-running monitors.
+//!
+//! A [`HistoryStore`] appends [`HistoryRecord`]s as newline-delimited JSON to
+//! segment files named after the time of their first record. The active
+//! segment is rotated once it exceeds the [`RotationPolicy`] size or age, and
+//! every rotation applies the [`RetentionPolicy`]: closed segments are
+//! compacted to one record per probe per interval once they are old enough,
+//! then the oldest are deleted until the age and size limits hold. With the
+//! `compression` feature, closed segments can also be stored compressed.
+//!
+//! With the `encryption` feature, a store opened with
+//! `HistoryStore::with_encryption` seals each record on its own and writes
+//! it as a line of hex, so records can still be appended and a line cut
+//! short by a crash only loses that record. Encrypted records hardly
+//! compress, so closed segments gain little from compression.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::persistence::{HistoryStore, RetentionPolicy, RotationPolicy};
+//! use std::time::Duration;
+//!
+//! # fn example(probe: &combustion_rust_ble::Probe) -> combustion_rust_ble::Result<()> {
+//! let store = HistoryStore::open("history")?
+//!     .with_rotation(RotationPolicy::daily())
+//!     .with_retention(
+//!         RetentionPolicy::new()
+//!             .with_max_age(Duration::from_secs(90 * 24 * 3600))
+//!             .with_compaction(Duration::from_secs(7 * 24 * 3600), Duration::from_secs(60)),
+//!     );
+//! store.record_probe(probe)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::storage_error;
+#[cfg(feature = "compression")]
+use crate::compression::Compression;
+use crate::data::VirtualTemperatures;
+#[cfg(feature = "encryption")]
+use crate::encryption::{decode_hex, encode_hex, is_encrypted, EncryptionKey};
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+
+/// Extension of segment files.
+const SEGMENT_EXTENSION: &str = "ndjson";
+
+/// Extensions of compressed segment files.
+const COMPRESSED_EXTENSIONS: [&str; 2] = ["gz", "zst"];
+
+/// Marker in the file name of compacted segments.
+const COMPACTED_MARKER: &str = ".compact";
+
+/// Timestamp format of segment file names.
+const SEGMENT_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// One temperature reading in the history.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HistoryRecord {
+    /// Serial number of the probe.
+    pub probe_serial: u32,
+    /// When the reading was taken.
+    pub recorded_at: DateTime<Utc>,
+    /// Sensor temperatures T1-T8 in Celsius.
+    pub temperatures: [Option<f64>; 8],
+    /// Virtual sensor temperatures.
+    pub virtual_temperatures: VirtualTemperatures,
+}
+
+impl HistoryRecord {
+    /// Capture a probe's current temperatures.
+    pub fn from_probe(probe: &Probe, recorded_at: DateTime<Utc>) -> Self {
+        Self {
+            probe_serial: probe.serial_number(),
+            recorded_at,
+            temperatures: probe.current_temperatures().to_celsius(),
+            virtual_temperatures: probe.virtual_temperatures(),
+        }
+    }
+}
+
+/// When to start a new segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RotationPolicy {
+    /// Rotate once the active segment reaches this many bytes.
+    pub max_segment_bytes: Option<u64>,
+    /// Rotate once a record is this long after the segment's first record.
+    pub max_segment_age: Option<Duration>,
+}
+
+impl RotationPolicy {
+    /// Never rotate automatically.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rotate every 24 hours.
+    pub fn daily() -> Self {
+        Self::new().with_max_age(Duration::from_secs(24 * 3600))
+    }
+
+    /// Rotate once the active segment reaches `bytes`.
+    pub fn with_max_bytes(mut self, bytes: u64) -> Self {
+        self.max_segment_bytes = Some(bytes);
+        self
+    }
+
+    /// Rotate once the active segment spans `age`.
+    pub fn with_max_age(mut self, age: Duration) -> Self {
+        self.max_segment_age = Some(age);
+        self
+    }
+}
+
+/// How long to keep closed segments and when to compact them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetentionPolicy {
+    /// Delete segments whose records are all older than this.
+    pub max_age: Option<Duration>,
+    /// Delete the oldest segments while the store is larger than this.
+    pub max_total_bytes: Option<u64>,
+    /// Compact segments whose records are all older than this.
+    pub compact_after: Option<Duration>,
+    /// Records kept per probe in each interval of a compacted segment.
+    pub compaction_interval: Duration,
+}
+
+impl RetentionPolicy {
+    /// Keep everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delete segments once they are older than `age`.
+    pub fn with_max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Delete the oldest segments while the store exceeds `bytes`.
+    pub fn with_max_total_bytes(mut self, bytes: u64) -> Self {
+        self.max_total_bytes = Some(bytes);
+        self
+    }
+
+    /// Compact segments older than `after` to one record per probe per
+    /// `interval`.
+    pub fn with_compaction(mut self, after: Duration, interval: Duration) -> Self {
+        self.compact_after = Some(after);
+        self.compaction_interval = interval;
+        self
+    }
+}
+
+/// A segment file in a [`HistoryStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentInfo {
+    /// Path of the segment file.
+    pub path: PathBuf,
+    /// Time of the segment's first record.
+    pub started_at: DateTime<Utc>,
+    /// Size of the file in bytes.
+    pub bytes: u64,
+    /// Whether the segment has been compacted.
+    pub compacted: bool,
+    /// Whether the segment file is compressed.
+    pub compressed: bool,
+}
+
+/// What a retention pass changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetentionReport {
+    /// Segments compacted.
+    pub compacted: usize,
+    /// Segments compressed.
+    pub compressed: usize,
+    /// Segments deleted.
+    pub deleted: usize,
+}
+
+/// The segment currently being appended to.
+#[derive(Debug)]
+struct ActiveSegment {
+    /// Path of the segment file.
+    path: PathBuf,
+    /// Time of the segment's first record.
+    started_at: DateTime<Utc>,
+    /// Bytes written so far.
+    bytes: u64,
+}
+
+/// Directory-backed temperature history with rotation and retention.
+#[derive(Debug)]
+pub struct HistoryStore {
+    /// Directory holding the segment files.
+    root: PathBuf,
+    /// When to start a new segment.
+    rotation: RotationPolicy,
+    /// What to keep.
+    retention: RetentionPolicy,
+    /// Format of closed segments.
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
+    /// Key records are encrypted with.
+    #[cfg(feature = "encryption")]
+    encryption: Option<EncryptionKey>,
+    /// Whether unencrypted records are read despite a key being set.
+    #[cfg(feature = "encryption")]
+    plaintext_migration: bool,
+    /// Segment being appended to, if any.
+    active: Mutex<Option<ActiveSegment>>,
+}
+
+impl HistoryStore {
+    /// Open a history store, creating the root directory if needed.
+    ///
+    /// Appending resumes in the newest segment that has not been compacted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created or read.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(|e| storage_error(&root, e))?;
+
+        let store = Self {
+            root,
+            rotation: RotationPolicy::default(),
+            retention: RetentionPolicy::default(),
+            #[cfg(feature = "compression")]
+            compression: None,
+            #[cfg(feature = "encryption")]
+            encryption: None,
+            #[cfg(feature = "encryption")]
+            plaintext_migration: false,
+            active: Mutex::new(None),
+        };
+        let resumed = store
+            .segments()?
+            .into_iter()
+            .last()
+            .filter(|s| !s.compacted && !s.compressed)
+            .map(|s| ActiveSegment {
+                path: s.path,
+                started_at: s.started_at,
+                bytes: s.bytes,
+            });
+        *store.active.lock() = resumed;
+
+        Ok(store)
+    }
+
+    /// Set the rotation policy.
+    pub fn with_rotation(mut self, rotation: RotationPolicy) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Set the retention policy.
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Compress segments once they are closed.
+    ///
+    /// The active segment is always plain NDJSON so records can be appended.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Encrypt appended records with `key`.
+    ///
+    /// Encrypted records can only be read by a store with the same key.
+    /// Reading a segment holding unencrypted records fails, so a file swapped
+    /// in on disk is not trusted, unless
+    /// [`with_plaintext_migration`](Self::with_plaintext_migration) is set.
+    /// Compaction re-encrypts the records it keeps.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, key: EncryptionKey) -> Self {
+        self.encryption = Some(key);
+        self
+    }
+
+    /// Read records appended before encryption was enabled.
+    ///
+    /// Use while switching an existing store over to encryption; the
+    /// unencrypted records are encrypted if their segment is compacted, and
+    /// are otherwise deleted by retention in due course.
+    #[cfg(feature = "encryption")]
+    pub fn with_plaintext_migration(mut self) -> Self {
+        self.plaintext_migration = true;
+        self
+    }
+
+    /// Get the root directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Append a record, rotating first if the active segment is full.
+    pub fn append(&self, record: &HistoryRecord) -> Result<()> {
+        let line = self.encode_line(record)?;
+
+        let mut active = self.active.lock();
+        let rotate = active
+            .as_ref()
+            .is_some_and(|segment| self.is_full(segment, record.recorded_at));
+        if rotate {
+            debug!("Rotating history segment {}", self.root.display());
+            *active = None;
+        }
+
+        let segment = match active.as_mut() {
+            Some(segment) => segment,
+            None => active.insert(ActiveSegment {
+                path: self.segment_path(record.recorded_at, false, None),
+                started_at: record.recorded_at,
+                bytes: 0,
+            }),
+        };
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&segment.path)
+            .map_err(|e| storage_error(&segment.path, e))?;
+        file.write_all(&line)
+            .map_err(|e| storage_error(&segment.path, e))?;
+        segment.bytes += line.len() as u64;
+        drop(active);
+
+        if rotate {
+            self.apply_retention(record.recorded_at)?;
+        }
+        Ok(())
+    }
+
+    /// Append a probe's current temperatures, timestamped now.
+    pub fn record_probe(&self, probe: &Probe) -> Result<()> {
+        self.append(&HistoryRecord::from_probe(probe, Utc::now()))
+    }
+
+    /// Close the active segment so the next record starts a new one.
+    pub fn rotate(&self) {
+        *self.active.lock() = None;
+    }
+
+    /// List segments, oldest first.
+    pub fn segments(&self) -> Result<Vec<SegmentInfo>> {
+        let entries = std::fs::read_dir(&self.root).map_err(|e| storage_error(&self.root, e))?;
+
+        let mut segments = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| storage_error(&self.root, e))?;
+            let path = entry.path();
+            let Some((started_at, compacted, compressed)) = parse_segment_name(&path) else {
+                continue;
+            };
+            let bytes = entry.metadata().map_err(|e| storage_error(&path, e))?.len();
+            segments.push(SegmentInfo {
+                path,
+                started_at,
+                bytes,
+                compacted,
+                compressed,
+            });
+        }
+
+        segments.sort_by_key(|s| s.started_at);
+        Ok(segments)
+    }
+
+    /// Read the records taken in `[from, to)`, oldest first.
+    ///
+    /// Unparseable lines, such as one cut short by a crash, are skipped with
+    /// a warning.
+    pub fn read_range(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<HistoryRecord>> {
+        let segments = self.segments()?;
+        let mut records = Vec::new();
+        for (index, segment) in segments.iter().enumerate() {
+            let ends_before = segments
+                .get(index + 1)
+                .is_some_and(|next| next.started_at <= from);
+            if ends_before || segment.started_at >= to {
+                continue;
+            }
+            records.extend(
+                self.read_segment(&segment.path)?
+                    .into_iter()
+                    .filter(|r| r.recorded_at >= from && r.recorded_at < to),
+            );
+        }
+        Ok(records)
+    }
+
+    /// Compress, compact and delete closed segments according to the
+    /// retention policy.
+    ///
+    /// Runs automatically on every rotation. Ages are measured back from
+    /// `now`, and a segment's records are taken to end where the next segment
+    /// starts. The active segment is never touched.
+    pub fn apply_retention(&self, now: DateTime<Utc>) -> Result<RetentionReport> {
+        let active_path = self.active.lock().as_ref().map(|s| s.path.clone());
+        let mut segments = self.segments()?;
+        let mut report = RetentionReport::default();
+
+        // Pair each closed segment with the time its records end
+        let mut closed: Vec<(SegmentInfo, DateTime<Utc>)> = Vec::new();
+        for (index, segment) in segments.iter().enumerate() {
+            let Some(next) = segments.get(index + 1) else {
+                break;
+            };
+            if Some(&segment.path) != active_path.as_ref() {
+                closed.push((segment.clone(), next.started_at));
+            }
+        }
+
+        #[cfg(feature = "compression")]
+        if self.compression.is_some() {
+            for (segment, _) in closed.iter_mut() {
+                if !segment.compressed {
+                    let data = read_segment_bytes(&segment.path)?;
+                    *segment = self.rewrite(segment, segment.compacted, data)?;
+                    report.compressed += 1;
+                }
+            }
+        }
+
+        if let Some(cutoff) = self.retention.compact_after.and_then(|a| cutoff(now, a)) {
+            for (segment, ended_at) in closed.iter_mut() {
+                if !segment.compacted && *ended_at <= cutoff {
+                    *segment = self.compact(segment)?;
+                    report.compacted += 1;
+                }
+            }
+        }
+
+        let mut deleted = HashSet::new();
+        if let Some(cutoff) = self.retention.max_age.and_then(|a| cutoff(now, a)) {
+            for (segment, ended_at) in &closed {
+                if *ended_at <= cutoff {
+                    remove_segment(&segment.path)?;
+                    deleted.insert(segment.path.clone());
+                }
+            }
+        }
+
+        if let Some(max_total_bytes) = self.retention.max_total_bytes {
+            segments = self.segments()?;
+            let mut total: u64 = segments.iter().map(|s| s.bytes).sum();
+            for (segment, _) in &closed {
+                if total <= max_total_bytes {
+                    break;
+                }
+                if deleted.contains(&segment.path) {
+                    continue;
+                }
+                remove_segment(&segment.path)?;
+                deleted.insert(segment.path.clone());
+                total = total.saturating_sub(segment.bytes);
+            }
+        }
+
+        report.deleted = deleted.len();
+        if report != RetentionReport::default() {
+            debug!(
+                "History retention compressed {}, compacted {} and deleted {} segments",
+                report.compressed, report.compacted, report.deleted
+            );
+        }
+        Ok(report)
+    }
+
+    /// Downsample a segment to one record per probe per compaction interval.
+    fn compact(&self, segment: &SegmentInfo) -> Result<SegmentInfo> {
+        let interval = self.retention.compaction_interval.as_millis().max(1) as i64;
+        let mut seen = HashSet::new();
+        let mut data = Vec::new();
+        for record in self.read_segment(&segment.path)? {
+            let bucket = record.recorded_at.timestamp_millis().div_euclid(interval);
+            if seen.insert((record.probe_serial, bucket)) {
+                data.extend(self.encode_line(&record)?);
+            }
+        }
+
+        self.rewrite(segment, true, data)
+    }
+
+    /// Replace a closed segment with `data`, compressed if configured.
+    fn rewrite(
+        &self,
+        segment: &SegmentInfo,
+        compacted: bool,
+        data: Vec<u8>,
+    ) -> Result<SegmentInfo> {
+        #[cfg(feature = "compression")]
+        let (data, extension) = match self.compression {
+            Some(compression) => (compression.compress(&data)?, Some(compression.extension())),
+            None => (data, None),
+        };
+        #[cfg(not(feature = "compression"))]
+        let extension = None;
+
+        let compressed = extension.is_some();
+        let path = self.segment_path(segment.started_at, compacted, extension);
+        let tmp = self.root.join(format!(
+            "{}.tmp",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        std::fs::write(&tmp, &data).map_err(|e| storage_error(&tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| storage_error(&path, e))?;
+        if path != segment.path {
+            remove_segment(&segment.path)?;
+        }
+
+        Ok(SegmentInfo {
+            path,
+            started_at: segment.started_at,
+            bytes: data.len() as u64,
+            compacted,
+            compressed,
+        })
+    }
+
+    /// Serialize a record as a segment line, encrypting it if configured.
+    fn encode_line(&self, record: &HistoryRecord) -> Result<Vec<u8>> {
+        let mut line = serde_json::to_vec(record).map_err(|e| Error::Storage {
+            reason: format!("failed to serialize history record: {}", e),
+        })?;
+        #[cfg(feature = "encryption")]
+        if let Some(key) = &self.encryption {
+            line = encode_hex(&key.encrypt(&line)?).into_bytes();
+        }
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    /// Read every readable record of a segment.
+    ///
+    /// Unreadable lines, such as one cut short by a crash, are skipped with
+    /// a warning.
+    fn read_segment(&self, path: &Path) -> Result<Vec<HistoryRecord>> {
+        let data = read_segment_bytes(path)?;
+        let mut records = Vec::new();
+        for line in String::from_utf8_lossy(&data).lines() {
+            if line.is_empty() {
+                continue;
+            }
+            let Some(json) = self.open_line(path, line)? else {
+                continue;
+            };
+            match serde_json::from_slice(&json) {
+                Ok(record) => records.push(record),
+                Err(e) => warn!(
+                    "Skipping unreadable history record in {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Get the JSON of a segment line, decrypting it if it is encrypted, or
+    /// `None` if it can't be decrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if the line is encrypted and no key was
+    /// given, or is unencrypted while a key is set without plaintext
+    /// migration.
+    #[cfg(feature = "encryption")]
+    fn open_line(&self, path: &Path, line: &str) -> Result<Option<Vec<u8>>> {
+        // Unencrypted records are JSON objects
+        let plaintext = line.starts_with('{');
+        match &self.encryption {
+            Some(_) if plaintext && !self.plaintext_migration => Err(Error::Storage {
+                reason: format!(
+                    "{} is not encrypted and plaintext migration is off",
+                    path.display()
+                ),
+            }),
+            Some(key) if !plaintext => {
+                let opened = decode_hex(line)
+                    .ok_or_else(|| Error::Storage {
+                        reason: "record is not hex".to_string(),
+                    })
+                    .and_then(|sealed| key.decrypt(&sealed));
+                match opened {
+                    Ok(json) => Ok(Some(json)),
+                    Err(e) => {
+                        warn!(
+                            "Skipping unreadable history record in {}: {}",
+                            path.display(),
+                            e
+                        );
+                        Ok(None)
+                    }
+                }
+            }
+            None if decode_hex(line).is_some_and(|data| is_encrypted(&data)) => {
+                Err(Error::Storage {
+                    reason: format!("{} is encrypted and no key was given", path.display()),
+                })
+            }
+            _ => Ok(Some(line.as_bytes().to_vec())),
+        }
+    }
+
+    /// Get the JSON of a segment line.
+    #[cfg(not(feature = "encryption"))]
+    fn open_line(&self, _path: &Path, line: &str) -> Result<Option<Vec<u8>>> {
+        Ok(Some(line.as_bytes().to_vec()))
+    }
+
+    /// Check whether the active segment must rotate before a record at `at`.
+    fn is_full(&self, segment: &ActiveSegment, at: DateTime<Utc>) -> bool {
+        let too_big = self
+            .rotation
+            .max_segment_bytes
+            .is_some_and(|max| segment.bytes >= max);
+        let too_old = self
+            .rotation
+            .max_segment_age
+            .is_some_and(|max| at - segment.started_at >= to_chrono(max));
+        too_big || too_old
+    }
+
+    /// Get the path of a segment starting at `started_at`, ending in
+    /// `compression_extension` if the segment is compressed.
+    fn segment_path(
+        &self,
+        started_at: DateTime<Utc>,
+        compacted: bool,
+        compression_extension: Option<&str>,
+    ) -> PathBuf {
+        let marker = if compacted { COMPACTED_MARKER } else { "" };
+        let mut name = format!(
+            "{}{}.{}",
+            started_at.format(SEGMENT_TIME_FORMAT),
+            marker,
+            SEGMENT_EXTENSION
+        );
+        if let Some(extension) = compression_extension {
+            name = format!("{}.{}", name, extension);
+        }
+        self.root.join(name)
+    }
+}
+
+/// Parse a segment file name into its start time, compaction flag, and
+/// compression flag.
+fn parse_segment_name(path: &Path) -> Option<(DateTime<Utc>, bool, bool)> {
+    let name = path.file_name()?.to_str()?;
+    let (name, compressed) = match name.rsplit_once('.') {
+        Some((rest, extension)) if COMPRESSED_EXTENSIONS.contains(&extension) => (rest, true),
+        _ => (name, false),
+    };
+    let stem = name.strip_suffix(SEGMENT_EXTENSION)?.strip_suffix('.')?;
+    let (stamp, compacted) = match stem.strip_suffix(COMPACTED_MARKER) {
+        Some(stamp) => (stamp, true),
+        None => (stem, false),
+    };
+    let started_at = NaiveDateTime::parse_from_str(stamp, SEGMENT_TIME_FORMAT)
+        .ok()?
+        .and_utc();
+    Some((started_at, compacted, compressed))
+}
+
+/// Read a segment file, decompressing it if needed.
+#[cfg(feature = "compression")]
+fn read_segment_bytes(path: &Path) -> Result<Vec<u8>> {
+    let data = std::fs::read(path).map_err(|e| storage_error(path, e))?;
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Compression::from_extension)
+    {
+        Some(compression) => compression.decompress(&data),
+        None => Ok(data),
+    }
+}
+
+/// Read a segment file, which must not be compressed.
+#[cfg(not(feature = "compression"))]
+fn read_segment_bytes(path: &Path) -> Result<Vec<u8>> {
+    let compressed = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| COMPRESSED_EXTENSIONS.contains(&e));
+    if compressed {
+        return Err(Error::NotSupported {
+            operation: format!(
+                "reading compressed history segment {} without the `compression` feature",
+                path.display()
+            ),
+        });
+    }
+    std::fs::read(path).map_err(|e| storage_error(path, e))
+}
+
+/// Delete a segment file.
+fn remove_segment(path: &Path) -> Result<()> {
+    debug!("Removing history segment {}", path.display());
+    std::fs::remove_file(path).map_err(|e| storage_error(path, e))
+}
+
+/// Get the time `age` before `now`, if representable.
+fn cutoff(now: DateTime<Utc>, age: Duration) -> Option<DateTime<Utc>> {
+    chrono::Duration::from_std(age)
+        .ok()
+        .and_then(|age| now.checked_sub_signed(age))
+}
+
+/// Convert a std duration to a chrono duration, saturating.
+fn to_chrono(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> HistoryStore {
+        let root = std::env::temp_dir().join(format!(
+            "combustion-history-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        HistoryStore::open(root).unwrap()
+    }
+
+    fn record(serial: u32, at: DateTime<Utc>, core: f64) -> HistoryRecord {
+        HistoryRecord {
+            probe_serial: serial,
+            recorded_at: at,
+            temperatures: [Some(core); 8],
+            virtual_temperatures: VirtualTemperatures::new(Some(core), None, None),
+        }
+    }
+
+    fn start() -> DateTime<Utc> {
+        "2026-01-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_rotates_by_age_and_reads_range() {
+        let store = temp_store("rotate").with_rotation(RotationPolicy::daily());
+        let start = start();
+        for hour in 0..72 {
+            store
+                .append(&record(
+                    1,
+                    start + chrono::Duration::hours(hour),
+                    hour as f64,
+                ))
+                .unwrap();
+        }
+
+        let segments = store.segments().unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[1].started_at, start + chrono::Duration::days(1));
+
+        let records = store
+            .read_range(
+                start + chrono::Duration::hours(23),
+                start + chrono::Duration::hours(26),
+            )
+            .unwrap();
+        let cores: Vec<_> = records
+            .iter()
+            .map(|r| r.virtual_temperatures.core.unwrap())
+            .collect();
+        assert_eq!(cores, vec![23.0, 24.0, 25.0]);
+
+        // Reopening resumes the newest segment
+        let reopened = HistoryStore::open(store.root()).unwrap();
+        reopened
+            .append(&record(1, start + chrono::Duration::hours(72), 72.0))
+            .unwrap();
+        assert_eq!(reopened.segments().unwrap().len(), 3);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn test_retention_compacts_then_deletes() {
+        let store = temp_store("retention")
+            .with_rotation(RotationPolicy::daily())
+            .with_retention(
+                RetentionPolicy::new()
+                    .with_max_age(Duration::from_secs(3 * 24 * 3600))
+                    .with_compaction(Duration::from_secs(24 * 3600), Duration::from_secs(3600)),
+            );
+        let start = start();
+        for minute in (0..5 * 24 * 60).step_by(10) {
+            let at = start + chrono::Duration::minutes(minute);
+            store.append(&record(1, at, 20.0)).unwrap();
+            store.append(&record(2, at, 30.0)).unwrap();
+        }
+
+        // When day 4 starts, day 0 is past the max age and days 1-2 are compacted
+        let segments = store.segments().unwrap();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0].started_at, start + chrono::Duration::days(1));
+        assert!(segments[0].compacted && segments[1].compacted);
+        assert!(!segments[2].compacted);
+
+        let day = store
+            .read_range(segments[0].started_at, segments[1].started_at)
+            .unwrap();
+        assert_eq!(day.len(), 2 * 24);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_closed_segments_are_compressed() {
+        let store = temp_store("compress")
+            .with_rotation(RotationPolicy::daily())
+            .with_compression(Compression::Zstd);
+        let start = start();
+        for hour in 0..48 {
+            store
+                .append(&record(
+                    1,
+                    start + chrono::Duration::hours(hour),
+                    hour as f64,
+                ))
+                .unwrap();
+        }
+
+        let segments = store.segments().unwrap();
+        assert_eq!(segments.len(), 2);
+        assert!(segments[0].compressed);
+        assert!(segments[0].path.to_string_lossy().ends_with(".ndjson.zst"));
+        assert!(!segments[1].compressed);
+
+        let records = store
+            .read_range(start, start + chrono::Duration::days(2))
+            .unwrap();
+        assert_eq!(records.len(), 48);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_segments() {
+        let key = EncryptionKey::generate();
+        let store = temp_store("encrypt")
+            .with_rotation(RotationPolicy::daily())
+            .with_retention(
+                RetentionPolicy::new()
+                    .with_compaction(Duration::from_secs(24 * 3600), Duration::from_secs(3600)),
+            )
+            .with_encryption(key.clone());
+        let start = start();
+        for minute in (0..3 * 24 * 60).step_by(30) {
+            store
+                .append(&record(1, start + chrono::Duration::minutes(minute), 20.0))
+                .unwrap();
+        }
+
+        // Nothing readable on disk, compacted segments included
+        let segments = store.segments().unwrap();
+        assert!(segments[0].compacted);
+        for segment in &segments {
+            let data = std::fs::read_to_string(&segment.path).unwrap();
+            assert!(!data.contains("probe_serial"));
+        }
+        let (from, to) = (start, start + chrono::Duration::days(3));
+        assert_eq!(store.read_range(from, to).unwrap().len(), 24 + 2 * 48);
+
+        // A line cut short loses only that record
+        let active = &segments.last().unwrap().path;
+        let mut data = std::fs::read_to_string(active).unwrap();
+        data.truncate(data.len() - 10);
+        std::fs::write(active, data).unwrap();
+        assert_eq!(store.read_range(from, to).unwrap().len(), 24 + 2 * 48 - 1);
+
+        // Other keys can't read the records, and no key is refused
+        let other = HistoryStore::open(store.root())
+            .unwrap()
+            .with_encryption(EncryptionKey::generate());
+        assert!(other.read_range(from, to).unwrap().is_empty());
+        let plain = HistoryStore::open(store.root()).unwrap();
+        assert!(matches!(
+            plain.read_range(from, to),
+            Err(Error::Storage { .. })
+        ));
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_plaintext_records_need_migration() {
+        let store = temp_store("migrate");
+        let start = start();
+        store.append(&record(1, start, 20.0)).unwrap();
+
+        let key = EncryptionKey::generate();
+        let encrypted = HistoryStore::open(store.root())
+            .unwrap()
+            .with_encryption(key.clone());
+        let end = start + chrono::Duration::hours(1);
+        assert!(encrypted.read_range(start, end).is_err());
+
+        let migrating = HistoryStore::open(store.root())
+            .unwrap()
+            .with_encryption(key)
+            .with_plaintext_migration();
+        migrating
+            .append(&record(1, start + chrono::Duration::minutes(1), 21.0))
+            .unwrap();
+        assert_eq!(migrating.read_range(start, end).unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn test_retention_limits_total_size() {
+        let store = temp_store("size")
+            .with_rotation(RotationPolicy::new().with_max_bytes(1000))
+            .with_retention(RetentionPolicy::new().with_max_total_bytes(3000));
+        let start = start();
+        for second in 0..200 {
+            store
+                .append(&record(1, start + chrono::Duration::seconds(second), 20.0))
+                .unwrap();
+        }
+
+        let segments = store.segments().unwrap();
+        let total: u64 = segments.iter().map(|s| s.bytes).sum();
+        // Retention runs at rotation, so the new segment may add one record
+        assert!(total <= 3000 + segments.last().unwrap().bytes);
+        assert!(segments.len() <= 4);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+}
@@ -0,0 +1,425 @@
+//! Registry of the user's own probes.
+//!
+//! A [`KnownProbeRegistry`] remembers probes across launches in a single
+//! JSON file, with the label, color, calibration, last session, and battery
+//! on-time of each.
+//! An app can list them as offline on startup before they are discovered
+//! again, and a [`DeviceManager`](crate::DeviceManager) given the registry
+//! with [`set_known_probes`](crate::DeviceManager::set_known_probes) keeps
+//! it up to date and connects only to known probes marked for
+//! auto-connect.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::persistence::KnownProbeRegistry;
+//!
+//! # fn example(probe: &combustion_rust_ble::Probe) -> combustion_rust_ble::Result<()> {
+//! let registry = KnownProbeRegistry::open("known_probes.json")?;
+//! registry.remember(probe)?;
+//! registry.set_label(probe.serial_number(), Some("Brisket".to_string()))?;
+//! for known in registry.list() {
+//!     println!("{} {:?}", known.serial_number_string(), known.label);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{read_json, write_json};
+use crate::ble::advertising::ProbeColor;
+use crate::data::SessionInfo;
+use crate::error::Result;
+use crate::probe::Probe;
+
+/// A probe remembered by a [`KnownProbeRegistry`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct KnownProbe {
+    /// Probe serial number.
+    pub serial_number: u32,
+
+    /// Name the user gave the probe.
+    pub label: Option<String>,
+
+    /// Color last reported by the probe.
+    pub color: ProbeColor,
+
+    /// Offset in Celsius the app adds to this probe's readings.
+    pub calibration_offset: f64,
+
+    /// Whether to connect to the probe when it is discovered.
+    pub auto_connect: bool,
+
+    /// When the probe was last seen.
+    pub last_seen: DateTime<Utc>,
+
+    /// The last session the probe reported.
+    pub last_session: Option<SessionInfo>,
+
+    /// Running time since the probe's last charge, restored into the probe
+    /// when it is discovered again; see [`Probe::battery_on_time`].
+    #[serde(default)]
+    pub battery_on_time: Duration,
+}
+
+impl KnownProbe {
+    /// Create an entry for a probe, marked for auto-connect.
+    pub fn from_probe(probe: &Probe) -> Self {
+        Self {
+            serial_number: probe.serial_number(),
+            label: None,
+            color: probe.color(),
+            calibration_offset: 0.0,
+            auto_connect: true,
+            last_seen: Utc::now(),
+            last_session: probe.session_info(),
+            battery_on_time: probe.battery_on_time(),
+        }
+    }
+
+    /// Get the serial number as a hex string.
+    pub fn serial_number_string(&self) -> String {
+        format!("{:08X}", self.serial_number)
+    }
+
+    /// Refresh the details the probe reports, keeping the user's settings.
+    ///
+    /// Returns whether the entry is worth saving: the color or session
+    /// changed, the probe was charged, or `last_seen` moved by more than
+    /// [`LAST_SEEN_SAVE_INTERVAL`](KnownProbeRegistry::LAST_SEEN_SAVE_INTERVAL).
+    fn refresh(&mut self, probe: &Probe) -> bool {
+        let now = Utc::now();
+        let mut changed = (now - self.last_seen)
+            .to_std()
+            .is_ok_and(|elapsed| elapsed >= KnownProbeRegistry::LAST_SEEN_SAVE_INTERVAL);
+        changed |= self.color != probe.color();
+        self.color = probe.color();
+        changed |= probe.battery_on_time() < self.battery_on_time;
+        self.battery_on_time = probe.battery_on_time();
+        self.last_seen = now;
+        if let Some(session) = probe.session_info() {
+            changed |= self.last_session.as_ref() != Some(&session);
+            self.last_session = Some(session);
+        }
+        changed
+    }
+}
+
+/// A known probe and, if it has been discovered, the probe itself.
+#[derive(Debug, Clone)]
+pub struct KnownProbeStatus {
+    /// Registry entry.
+    pub known: KnownProbe,
+    /// The discovered probe, or `None` while it is offline.
+    pub probe: Option<Arc<Probe>>,
+}
+
+impl KnownProbeStatus {
+    /// Check if the probe has been discovered and is not stale.
+    pub fn is_online(&self) -> bool {
+        self.probe.as_ref().is_some_and(|probe| !probe.is_stale())
+    }
+}
+
+/// File-backed registry of known probes, keyed on serial number.
+///
+/// Every change is written to disk before the method returns.
+#[derive(Debug)]
+pub struct KnownProbeRegistry {
+    /// File holding the registry.
+    path: PathBuf,
+    /// Known probes by serial number.
+    probes: RwLock<BTreeMap<u32, KnownProbe>>,
+}
+
+impl KnownProbeRegistry {
+    /// How far `last_seen` may fall behind on disk before a
+    /// [`refresh`](Self::refresh) saves it.
+    pub const LAST_SEEN_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Open a registry, loading it from `path` if the file exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the file exists
+    /// but cannot be read.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let probes = if path.is_file() {
+            read_json::<Vec<KnownProbe>>(&path)?
+                .into_iter()
+                .map(|known| (known.serial_number, known))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            path,
+            probes: RwLock::new(probes),
+        })
+    }
+
+    /// Get the file holding the registry.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// List known probes, ordered by serial number.
+    pub fn list(&self) -> Vec<KnownProbe> {
+        self.probes.read().values().cloned().collect()
+    }
+
+    /// Get a known probe.
+    pub fn get(&self, serial_number: u32) -> Option<KnownProbe> {
+        self.probes.read().get(&serial_number).cloned()
+    }
+
+    /// Check if a probe is known.
+    pub fn contains(&self, serial_number: u32) -> bool {
+        self.probes.read().contains_key(&serial_number)
+    }
+
+    /// Remember a probe, or refresh it if already known.
+    ///
+    /// A refreshed probe keeps its label, calibration, and auto-connect
+    /// setting.
+    ///
+    /// # Returns
+    ///
+    /// The stored entry.
+    pub fn remember(&self, probe: &Probe) -> Result<KnownProbe> {
+        self.modify(|probes| {
+            let known = probes
+                .entry(probe.serial_number())
+                .and_modify(|known| {
+                    known.refresh(probe);
+                })
+                .or_insert_with(|| KnownProbe::from_probe(probe));
+            known.clone()
+        })
+    }
+
+    /// Restore what the registry keeps for a newly discovered probe, if it
+    /// is known.
+    ///
+    /// The probe's [battery on-time](Probe::battery_on_time) carries on from
+    /// the stored one, so a restart doesn't forget how long the probe has
+    /// been running. A charge while the probe was not being tracked is only
+    /// seen once its low battery flag clears; set the on-time to zero after
+    /// charging with [`Probe::set_battery_on_time`] if it never raised one.
+    ///
+    /// # Returns
+    ///
+    /// The entry, or `None` if the probe is not known.
+    pub fn restore(&self, probe: &Probe) -> Option<KnownProbe> {
+        let known = self.get(probe.serial_number())?;
+        if known.battery_on_time > probe.battery_on_time() {
+            probe.set_battery_on_time(known.battery_on_time);
+        }
+        Some(known)
+    }
+
+    /// Refresh a probe if it is known.
+    ///
+    /// Called as probes are discovered and every
+    /// [`LAST_SEEN_SAVE_INTERVAL`](Self::LAST_SEEN_SAVE_INTERVAL) while a
+    /// device manager tracks them, so the file is only written when the
+    /// color or session changes, the probe was charged, or `last_seen` on
+    /// disk is more than the interval old.
+    ///
+    /// # Returns
+    ///
+    /// The updated entry, or `None` if the probe is not known.
+    pub fn refresh(&self, probe: &Probe) -> Result<Option<KnownProbe>> {
+        let mut probes = self.probes.write();
+        let Some(known) = probes.get_mut(&probe.serial_number()) else {
+            return Ok(None);
+        };
+        let changed = known.refresh(probe);
+        let known = known.clone();
+        if changed {
+            self.save(&probes)?;
+        }
+        Ok(Some(known))
+    }
+
+    /// Set or clear the label of a known probe.
+    ///
+    /// # Returns
+    ///
+    /// The updated entry, or `None` if the probe is not known.
+    pub fn set_label(
+        &self,
+        serial_number: u32,
+        label: Option<String>,
+    ) -> Result<Option<KnownProbe>> {
+        self.update(serial_number, |known| known.label = label)
+    }
+
+    /// Set the calibration offset of a known probe, in Celsius.
+    ///
+    /// # Returns
+    ///
+    /// The updated entry, or `None` if the probe is not known.
+    pub fn set_calibration_offset(
+        &self,
+        serial_number: u32,
+        offset: f64,
+    ) -> Result<Option<KnownProbe>> {
+        self.update(serial_number, |known| known.calibration_offset = offset)
+    }
+
+    /// Set whether to connect to a known probe when it is discovered.
+    ///
+    /// # Returns
+    ///
+    /// The updated entry, or `None` if the probe is not known.
+    pub fn set_auto_connect(
+        &self,
+        serial_number: u32,
+        auto_connect: bool,
+    ) -> Result<Option<KnownProbe>> {
+        self.update(serial_number, |known| known.auto_connect = auto_connect)
+    }
+
+    /// Forget a probe.
+    ///
+    /// # Returns
+    ///
+    /// The removed entry, or `None` if the probe was not known.
+    pub fn forget(&self, serial_number: u32) -> Result<Option<KnownProbe>> {
+        self.modify(|probes| probes.remove(&serial_number))
+    }
+
+    /// Apply `f` to a known probe and save.
+    fn update(
+        &self,
+        serial_number: u32,
+        f: impl FnOnce(&mut KnownProbe),
+    ) -> Result<Option<KnownProbe>> {
+        self.modify(|probes| {
+            probes.get_mut(&serial_number).map(|known| {
+                f(known);
+                known.clone()
+            })
+        })
+    }
+
+    /// Apply `f` to the registry and save it.
+    fn modify<T>(&self, f: impl FnOnce(&mut BTreeMap<u32, KnownProbe>) -> T) -> Result<T> {
+        let mut probes = self.probes.write();
+        let result = f(&mut probes);
+        self.save(&probes)?;
+        Ok(result)
+    }
+
+    /// Write `probes` to the registry file.
+    fn save(&self, probes: &BTreeMap<u32, KnownProbe>) -> Result<()> {
+        let entries: Vec<_> = probes.values().collect();
+        write_json(&self.path, &entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::external::ExternalSensorSource;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "combustion-known-probes-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_remember_and_reload() {
+        let path = temp_path("reload");
+        let probe = ExternalSensorSource::new("test".to_string(), 0x1000_0001).probe();
+
+        let registry = KnownProbeRegistry::open(&path).unwrap();
+        assert!(registry.list().is_empty());
+        let known = registry.remember(&probe).unwrap();
+        assert!(known.auto_connect);
+        registry
+            .set_label(0x1000_0001, Some("Brisket".to_string()))
+            .unwrap();
+        registry.set_calibration_offset(0x1000_0001, -0.5).unwrap();
+        assert_eq!(registry.set_auto_connect(0x2000_0002, false).unwrap(), None);
+
+        // Refreshing keeps the user's settings
+        let known = registry.remember(&probe).unwrap();
+        assert_eq!(known.label.as_deref(), Some("Brisket"));
+
+        let reloaded = KnownProbeRegistry::open(&path).unwrap();
+        assert_eq!(reloaded.list(), vec![known]);
+        assert_eq!(reloaded.get(0x1000_0001).unwrap().calibration_offset, -0.5);
+
+        assert!(reloaded.forget(0x1000_0001).unwrap().is_some());
+        assert!(!KnownProbeRegistry::open(&path)
+            .unwrap()
+            .contains(0x1000_0001));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_refresh_saves_only_changes() {
+        let path = temp_path("refresh");
+        let probe = ExternalSensorSource::new("test".to_string(), 0x1000_0001).probe();
+        let registry = KnownProbeRegistry::open(&path).unwrap();
+        registry.remember(&probe).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(registry.refresh(&probe).unwrap().is_some());
+        assert!(!path.exists());
+
+        registry
+            .probes
+            .write()
+            .get_mut(&0x1000_0001)
+            .unwrap()
+            .last_seen -= KnownProbeRegistry::LAST_SEEN_SAVE_INTERVAL;
+        registry.refresh(&probe).unwrap();
+        assert!(path.exists());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_battery_on_time_survives_restart() {
+        let path = temp_path("battery");
+        let hours = |n: u64| Duration::from_secs(n * 60 * 60);
+        let probe = ExternalSensorSource::new("test".to_string(), 0x1000_0001).probe();
+        probe.set_battery_on_time(hours(12));
+        KnownProbeRegistry::open(&path)
+            .unwrap()
+            .remember(&probe)
+            .unwrap();
+
+        let registry = KnownProbeRegistry::open(&path).unwrap();
+        let rediscovered = ExternalSensorSource::new("test".to_string(), 0x1000_0001).probe();
+        assert!(registry.restore(&rediscovered).is_some());
+        assert_eq!(rediscovered.battery_on_time(), hours(12));
+
+        // A charge is saved straight away
+        std::fs::remove_file(&path).unwrap();
+        rediscovered.set_battery_on_time(Duration::ZERO);
+        registry.refresh(&rediscovered).unwrap();
+        let reloaded = KnownProbeRegistry::open(&path).unwrap();
+        assert_eq!(
+            reloaded.get(0x1000_0001).unwrap().battery_on_time,
+            Duration::ZERO
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,85 @@
+//! On-disk persistence.
+//!
+//! Stores probe data as JSON files under a caller-chosen root directory:
+//! cooking sessions in a [`SessionStore`], continuous temperature history,
+//! rotated and pruned for long-running monitors, in a [`HistoryStore`],
+//! the user's own probes in a [`KnownProbeRegistry`], and time-based alarms
+//! in a [`TimerStore`]. A [`SessionBundle`] gathers a session's log and
+//! diagnostics for a bug report, and a [`Config`] file holds settings such
+//! as default cooks.
+//! Whole files are written to a temporary file first and then renamed into
+//! place, so a crash mid-write never leaves a truncated file behind. History
+//! is appended line by line, and readers skip a final line cut short.
+//!
+//! Everything is keyed on probe serial number, never on platform identifiers,
+//! which change between launches on macOS. With the `encryption` feature, a
+//! [`SessionStore`] can encrypt the sessions it saves, a [`HistoryStore`]
+//! the records it appends, and session bundles can be exported encrypted,
+//! using a key found through the [`Config`] file. Timers, known probes and
+//! the configuration itself are written as plain JSON; they hold settings
+//! rather than temperature records.
+//!
+//! Requires the `persistence` feature.
+
+pub mod bundle;
+pub mod config;
+pub mod history;
+pub mod known_probes;
+pub mod session_store;
+pub mod timer_store;
+
+pub use bundle::SessionBundle;
+pub use config::Config;
+#[cfg(feature = "encryption")]
+pub use config::EncryptionConfig;
+pub use history::{
+    HistoryRecord, HistoryStore, RetentionPolicy, RetentionReport, RotationPolicy, SegmentInfo,
+};
+pub use known_probes::{KnownProbe, KnownProbeRegistry, KnownProbeStatus};
+pub use session_store::{SessionMetadata, SessionStore};
+pub use timer_store::TimerStore;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// Serialize `value` as JSON and atomically write it to `path`.
+pub(crate) fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    write_atomic(path, &to_json(path, value)?)
+}
+
+/// Read and deserialize a JSON file.
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let bytes = std::fs::read(path).map_err(|e| storage_error(path, e))?;
+    from_json(path, &bytes)
+}
+
+/// Serialize `value`, destined for `path`, as JSON.
+pub(crate) fn to_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| Error::Storage {
+        reason: format!("failed to serialize {}: {}", path.display(), e),
+    })
+}
+
+/// Deserialize JSON read from `path`.
+pub(crate) fn from_json<T: DeserializeOwned>(path: &Path, bytes: &[u8]) -> Result<T> {
+    serde_json::from_slice(bytes).map_err(|e| Error::Storage {
+        reason: format!("failed to parse {}: {}", path.display(), e),
+    })
+}
+
+/// Atomically write `data` to `path`.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data).map_err(|e| storage_error(&tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| storage_error(path, e))
+}
+
+/// Convert an I/O error on `path` into a storage error.
+pub(crate) fn storage_error(path: &Path, e: std::io::Error) -> Error {
+    Error::Storage {
+        reason: format!("{}: {}", path.display(), e),
+    }
+}
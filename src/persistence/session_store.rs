@@ -0,0 +1,458 @@
+//! Historical cooking session storage.
+//!
+//! Each saved session is a directory under the store root holding a small
+//! `meta.json` file and the full `log.json` temperature log. Listing sessions
+//! only reads the metadata; logs are loaded on demand with
+//! [`SessionStore::load_log`]. With the `encryption` feature, a store opened
+//! with `SessionStore::with_encryption` encrypts both files, and refuses
+//! unencrypted ones unless migrating with
+//! `SessionStore::with_plaintext_migration`.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::persistence::SessionStore;
+//!
+//! # fn example() -> combustion_rust_ble::Result<()> {
+//! let store = SessionStore::open("sessions")?;
+//! for session in store.list()? {
+//!     println!(
+//!         "{} {}: max core {:?}",
+//!         session.probe_serial_string(),
+//!         session.started_at,
+//!         session.max_core_celsius
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{from_json, storage_error, to_json, write_atomic};
+use crate::data::{FoodSafeConfig, FoodSafeData, FoodSafeState, MergedCook, TemperatureLog};
+#[cfg(feature = "encryption")]
+use crate::encryption::{is_encrypted, EncryptionKey};
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+
+/// Name of the metadata file in a session directory.
+const METADATA_FILE: &str = "meta.json";
+
+/// Name of the temperature log file in a session directory.
+const LOG_FILE: &str = "log.json";
+
+/// Summary of a stored cooking session.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SessionMetadata {
+    /// Serial number of the probe that recorded the session.
+    pub probe_serial: u32,
+
+    /// Session ID reported by the probe.
+    pub session_id: u32,
+
+    /// When the session started.
+    pub started_at: DateTime<Utc>,
+
+    /// When the session ended, if it has.
+    pub ended_at: Option<DateTime<Utc>>,
+
+    /// Highest virtual core temperature in the log, in Celsius.
+    pub max_core_celsius: Option<f64>,
+
+    /// Food safety configuration (product and serving) used during the cook.
+    pub product: Option<FoodSafeConfig>,
+
+    /// Final food safety state, if food safety was configured.
+    pub food_safe_outcome: Option<FoodSafeState>,
+}
+
+impl SessionMetadata {
+    /// Build metadata for a temperature log.
+    ///
+    /// The end time is derived from `started_at` and the log duration.
+    pub fn from_log(probe_serial: u32, log: &TemperatureLog, started_at: DateTime<Utc>) -> Self {
+        let max_core_celsius = log
+            .data_points
+            .iter()
+            .filter_map(|p| p.prediction_log.as_ref().map(|l| l.virtual_core))
+            .fold(None, |max: Option<f64>, t| {
+                Some(max.map_or(t, |m| m.max(t)))
+            });
+
+        let ended_at = chrono::Duration::from_std(log.duration())
+            .ok()
+            .map(|d| started_at + d);
+
+        Self {
+            probe_serial,
+            session_id: log.session_id,
+            started_at,
+            ended_at,
+            max_core_celsius,
+            product: None,
+            food_safe_outcome: None,
+        }
+    }
+
+    /// Build metadata for a probe's current session.
+    ///
+    /// The start time is estimated from the current time and the duration of
+    /// the downloaded log.
+    pub fn from_probe(probe: &Probe) -> Self {
+        let log = probe.temperature_log();
+        let started_at = Utc::now()
+            - chrono::Duration::from_std(log.duration())
+                .unwrap_or_else(|_| chrono::Duration::zero());
+        let metadata = Self::from_log(probe.serial_number(), &log, started_at);
+
+        match probe.food_safe_data() {
+            Some(data) => metadata.with_food_safe(&data),
+            None => metadata,
+        }
+    }
+
+    /// Record the food safety product and outcome.
+    pub fn with_food_safe(mut self, data: &FoodSafeData) -> Self {
+        self.product = data.config.clone();
+        self.food_safe_outcome = Some(data.state());
+        self
+    }
+
+    /// Get the probe serial number as a hex string.
+    pub fn probe_serial_string(&self) -> String {
+        format!("{:08X}", self.probe_serial)
+    }
+
+    /// Get the key identifying this session in a [`SessionStore`].
+    pub fn key(&self) -> String {
+        format!("{:08X}-{:08X}", self.probe_serial, self.session_id)
+    }
+}
+
+/// Directory-backed store of historical cooking sessions.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    /// Root directory holding one subdirectory per session.
+    root: PathBuf,
+    /// Key sessions are encrypted with.
+    #[cfg(feature = "encryption")]
+    encryption: Option<EncryptionKey>,
+    /// Whether unencrypted sessions are read despite a key being set.
+    #[cfg(feature = "encryption")]
+    plaintext_migration: bool,
+}
+
+impl SessionStore {
+    /// Open a session store, creating the root directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(|e| storage_error(&root, e))?;
+        Ok(Self {
+            root,
+            #[cfg(feature = "encryption")]
+            encryption: None,
+            #[cfg(feature = "encryption")]
+            plaintext_migration: false,
+        })
+    }
+
+    /// Encrypt saved sessions with `key`.
+    ///
+    /// Encrypted sessions can only be read by a store with the same key.
+    /// Unencrypted sessions are refused, so a file swapped in on disk is not
+    /// trusted, unless [`with_plaintext_migration`](Self::with_plaintext_migration)
+    /// is set.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, key: EncryptionKey) -> Self {
+        self.encryption = Some(key);
+        self
+    }
+
+    /// Read sessions saved before encryption was enabled.
+    ///
+    /// Use while switching an existing store over to encryption; sessions
+    /// are encrypted when they are saved again.
+    #[cfg(feature = "encryption")]
+    pub fn with_plaintext_migration(mut self) -> Self {
+        self.plaintext_migration = true;
+        self
+    }
+
+    /// Get the root directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Save a session, replacing any previously saved copy.
+    pub fn save(&self, metadata: &SessionMetadata, log: &TemperatureLog) -> Result<()> {
+        let dir = self.session_dir(&metadata.key());
+        std::fs::create_dir_all(&dir).map_err(|e| storage_error(&dir, e))?;
+
+        // Write the log first so a listed session always has one
+        self.write(&dir.join(LOG_FILE), log)?;
+        self.write(&dir.join(METADATA_FILE), metadata)
+    }
+
+    /// Save a probe's current session.
+    ///
+    /// # Returns
+    ///
+    /// The metadata that was stored.
+    pub fn save_probe(&self, probe: &Probe) -> Result<SessionMetadata> {
+        let metadata = SessionMetadata::from_probe(probe);
+        self.save(&metadata, &probe.temperature_log())?;
+        Ok(metadata)
+    }
+
+    /// List stored sessions, newest first.
+    ///
+    /// Only session metadata is read. Sessions whose metadata cannot be read
+    /// are skipped with a warning.
+    pub fn list(&self) -> Result<Vec<SessionMetadata>> {
+        let entries = std::fs::read_dir(&self.root).map_err(|e| storage_error(&self.root, e))?;
+
+        let mut sessions = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| storage_error(&self.root, e))?;
+            let path = entry.path().join(METADATA_FILE);
+            if !path.is_file() {
+                continue;
+            }
+            match self.read::<SessionMetadata>(&path) {
+                Ok(metadata) => sessions.push(metadata),
+                Err(e) => warn!("Skipping unreadable session: {}", e),
+            }
+        }
+
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+        Ok(sessions)
+    }
+
+    /// Load the temperature log of a stored session.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if the session does not exist or its log
+    /// cannot be read.
+    pub fn load_log(&self, metadata: &SessionMetadata) -> Result<TemperatureLog> {
+        self.read(&self.session_dir(&metadata.key()).join(LOG_FILE))
+    }
+
+    /// Load a probe's stored sessions merged into cooks, oldest first.
+    ///
+    /// Consecutive sessions no more than `max_gap` apart, e.g. because the
+    /// probe restarted mid-cook, are stitched together; see
+    /// [`MergedCook::from_sessions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if a session's log cannot be read.
+    pub fn merged_cooks(&self, probe_serial: u32, max_gap: Duration) -> Result<Vec<MergedCook>> {
+        let mut sessions = Vec::new();
+        for metadata in self.list()? {
+            if metadata.probe_serial == probe_serial {
+                sessions.push((metadata.started_at, self.load_log(&metadata)?));
+            }
+        }
+        Ok(MergedCook::from_sessions(
+            sessions.iter().map(|(started_at, log)| (*started_at, log)),
+            max_gap,
+        ))
+    }
+
+    /// Delete a stored session.
+    pub fn delete(&self, metadata: &SessionMetadata) -> Result<()> {
+        let dir = self.session_dir(&metadata.key());
+        if !dir.exists() {
+            return Err(Error::Storage {
+                reason: format!("session {} not found", metadata.key()),
+            });
+        }
+        std::fs::remove_dir_all(&dir).map_err(|e| storage_error(&dir, e))
+    }
+
+    /// Serialize `value` as JSON, encrypting it if configured, and
+    /// atomically write it to `path`.
+    fn write<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<()> {
+        let data = to_json(path, value)?;
+        #[cfg(feature = "encryption")]
+        let data = match &self.encryption {
+            Some(key) => key.encrypt(&data)?,
+            None => data,
+        };
+        write_atomic(path, &data)
+    }
+
+    /// Read a JSON file, decrypting it if it is encrypted.
+    fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<T> {
+        let data = std::fs::read(path).map_err(|e| storage_error(path, e))?;
+        #[cfg(feature = "encryption")]
+        let data = match (&self.encryption, is_encrypted(&data)) {
+            (Some(key), true) => key.decrypt(&data)?,
+            (None, true) => {
+                return Err(Error::Storage {
+                    reason: format!("{} is encrypted and no key was given", path.display()),
+                })
+            }
+            (Some(_), false) if !self.plaintext_migration => {
+                return Err(Error::Storage {
+                    reason: format!(
+                        "{} is not encrypted and plaintext migration is off",
+                        path.display()
+                    ),
+                })
+            }
+            (_, false) => data,
+        };
+        from_json(path, &data)
+    }
+
+    /// Get the directory for a session key.
+    fn session_dir(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{LoggedDataPoint, PredictionLog, ProbeTemperatures};
+
+    fn temp_store(name: &str) -> SessionStore {
+        let root = std::env::temp_dir().join(format!(
+            "combustion-session-store-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        SessionStore::open(root).unwrap()
+    }
+
+    fn log(session_id: u32, cores: &[f64]) -> TemperatureLog {
+        let mut log = TemperatureLog::new(session_id, 1000);
+        for (i, core) in cores.iter().enumerate() {
+            let prediction = PredictionLog {
+                virtual_core: *core,
+                ..Default::default()
+            };
+            log.add_data_point(LoggedDataPoint::with_prediction(
+                i as u32,
+                ProbeTemperatures::new(),
+                prediction,
+            ));
+        }
+        log
+    }
+
+    #[test]
+    fn test_metadata_from_log() {
+        let started = Utc::now();
+        let metadata = SessionMetadata::from_log(0x1234, &log(7, &[20.0, 55.5, 54.0]), started);
+
+        assert_eq!(metadata.session_id, 7);
+        assert_eq!(metadata.max_core_celsius, Some(55.5));
+        assert_eq!(
+            metadata.ended_at,
+            Some(started + chrono::Duration::seconds(2))
+        );
+        assert_eq!(metadata.key(), "00001234-00000007");
+    }
+
+    #[test]
+    fn test_list_and_load() {
+        let store = temp_store("list");
+        let now = Utc::now();
+
+        let older_log = log(1, &[30.0]);
+        let older = SessionMetadata::from_log(1, &older_log, now - chrono::Duration::hours(2));
+        let newer_log = log(2, &[40.0, 60.0]);
+        let newer = SessionMetadata::from_log(1, &newer_log, now)
+            .with_food_safe(&FoodSafeData::with_config(FoodSafeConfig::default()));
+
+        store.save(&older, &older_log).unwrap();
+        store.save(&newer, &newer_log).unwrap();
+
+        let sessions = store.list().unwrap();
+        assert_eq!(sessions, vec![newer.clone(), older.clone()]);
+        assert_eq!(sessions[0].food_safe_outcome, Some(FoodSafeState::NotSafe));
+        assert_eq!(store.load_log(&sessions[0]).unwrap(), newer_log);
+
+        store.delete(&older).unwrap();
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.load_log(&older).is_err());
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn test_merged_cooks() {
+        let store = temp_store("merged");
+        let start = Utc::now() - chrono::Duration::hours(3);
+
+        let before = log(1, &[30.0, 40.0]);
+        let after = log(2, &[45.0]);
+        let other_cook = log(3, &[20.0]);
+        for (log, started_at) in [
+            (&before, start),
+            (&after, start + chrono::Duration::minutes(5)),
+            (&other_cook, start + chrono::Duration::hours(2)),
+        ] {
+            store
+                .save(&SessionMetadata::from_log(1, log, started_at), log)
+                .unwrap();
+        }
+        store
+            .save(&SessionMetadata::from_log(2, &after, start), &after)
+            .unwrap();
+
+        let cooks = store.merged_cooks(1, Duration::from_secs(600)).unwrap();
+        assert_eq!(cooks.len(), 2);
+        assert_eq!(cooks[0].session_ids(), vec![1, 2]);
+        assert_eq!(cooks[0].log.len(), 3);
+        assert_eq!(cooks[1].session_ids(), vec![3]);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_sessions() {
+        let key = EncryptionKey::generate();
+        let store = temp_store("encrypted").with_encryption(key.clone());
+        let log = log(3, &[50.0]);
+        let metadata = SessionMetadata::from_log(1, &log, Utc::now());
+        store.save(&metadata, &log).unwrap();
+
+        let raw = std::fs::read(store.root().join(metadata.key()).join(LOG_FILE)).unwrap();
+        assert!(is_encrypted(&raw));
+        assert_eq!(store.list().unwrap(), vec![metadata.clone()]);
+        assert_eq!(store.load_log(&metadata).unwrap(), log);
+
+        // Without the key, sessions are skipped or fail to load
+        let plain = SessionStore::open(store.root()).unwrap();
+        assert!(plain.list().unwrap().is_empty());
+        assert!(plain.load_log(&metadata).is_err());
+        let other = SessionStore::open(store.root())
+            .unwrap()
+            .with_encryption(EncryptionKey::generate());
+        assert!(other.load_log(&metadata).is_err());
+
+        // Unencrypted sessions are only read while migrating
+        plain.save(&metadata, &log).unwrap();
+        assert!(store.load_log(&metadata).is_err());
+        assert!(store.list().unwrap().is_empty());
+        let migrating = store.clone().with_plaintext_migration();
+        assert_eq!(migrating.load_log(&metadata).unwrap(), log);
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+}
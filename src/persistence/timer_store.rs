@@ -0,0 +1,134 @@
+//! Storage for time-based alarms.
+//!
+//! A [`TimerStore`] keeps the [`TimerAlarm`]s scheduled on probes in a single
+//! JSON file, so they survive a restart. Give it to a
+//! [`DeviceManager`](crate::DeviceManager) with
+//! [`set_timer_store`](crate::DeviceManager::set_timer_store) and each probe
+//! picks its timers back up when it is discovered; timers that came due in
+//! the meantime fire straight away.
+
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::{read_json, write_json};
+use crate::error::Result;
+use crate::timers::TimerAlarm;
+
+/// File-backed store of scheduled timers, keyed on timer ID.
+///
+/// Every change is written to disk before the method returns.
+#[derive(Debug)]
+pub struct TimerStore {
+    /// File holding the timers.
+    path: PathBuf,
+    /// Timers by ID.
+    timers: RwLock<BTreeMap<Uuid, TimerAlarm>>,
+}
+
+impl TimerStore {
+    /// Open a store, loading it from `path` if the file exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the file exists
+    /// but cannot be read.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let timers = if path.is_file() {
+            read_json::<Vec<TimerAlarm>>(&path)?
+                .into_iter()
+                .map(|timer| (timer.id, timer))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            path,
+            timers: RwLock::new(timers),
+        })
+    }
+
+    /// Get the file holding the timers.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// List stored timers, soonest first.
+    pub fn list(&self) -> Vec<TimerAlarm> {
+        let mut timers: Vec<_> = self.timers.read().values().cloned().collect();
+        timers.sort_by_key(|timer| timer.fires_at);
+        timers
+    }
+
+    /// List stored timers for one probe, soonest first.
+    pub fn list_for(&self, serial_number: u32) -> Vec<TimerAlarm> {
+        let mut timers = self.list();
+        timers.retain(|timer| timer.serial_number == serial_number);
+        timers
+    }
+
+    /// Add or replace a timer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the store cannot
+    /// be written.
+    pub fn save(&self, timer: &TimerAlarm) -> Result<()> {
+        let mut timers = self.timers.write();
+        timers.insert(timer.id, timer.clone());
+        self.write(&timers)
+    }
+
+    /// Remove a timer, returning it if it was stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`](crate::Error::Storage) if the store cannot
+    /// be written.
+    pub fn remove(&self, id: Uuid) -> Result<Option<TimerAlarm>> {
+        let mut timers = self.timers.write();
+        let removed = timers.remove(&id);
+        if removed.is_some() {
+            self.write(&timers)?;
+        }
+        Ok(removed)
+    }
+
+    /// Write the timers to disk.
+    fn write(&self, timers: &BTreeMap<Uuid, TimerAlarm>) -> Result<()> {
+        let list: Vec<_> = timers.values().collect();
+        write_json(&self.path, &list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_timer_store_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("combustion-timers-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let store = TimerStore::open(&path).unwrap();
+        let wrap = TimerAlarm::new(1, "Wrap".into(), Utc::now() + Duration::hours(2), Some(9));
+        let baste = TimerAlarm::new(2, "Baste".into(), Utc::now() + Duration::hours(1), None);
+        store.save(&wrap).unwrap();
+        store.save(&baste).unwrap();
+
+        let reopened = TimerStore::open(&path).unwrap();
+        assert_eq!(reopened.list(), vec![baste.clone(), wrap.clone()]);
+        assert_eq!(reopened.list_for(1), vec![wrap.clone()]);
+
+        assert_eq!(reopened.remove(wrap.id).unwrap(), Some(wrap.clone()));
+        assert_eq!(reopened.remove(wrap.id).unwrap(), None);
+        assert_eq!(TimerStore::open(&path).unwrap().list(), vec![baste]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
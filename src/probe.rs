@@ -8,62 +8,71 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
-use tracing::info;
+use tracing::{debug, info, warn};
 
+use crate::audit::{
+    CommandHistory, CommandRecord, ConfigChangedExternally, ConfigCommand, ConfigOutcome,
+    ConfigTracker,
+};
+use crate::battery::{BatteryModel, BatteryMonitor, BatteryProfiles, BatteryWarning};
 use crate::ble::advertising::{
     AdvertisingData, BatteryStatus, Overheating, ProbeColor, ProbeId, ProbeMode,
 };
 use crate::ble::characteristics::CharacteristicHandler;
-use crate::ble::connection::{ConnectionManager, ConnectionState};
+use crate::ble::connection::{
+    ConnectionManager, ConnectionMetrics, ConnectionState, ConnectionUnhealthy, HeartbeatPolicy,
+};
+use crate::ble::discovery::DiscoveryReport;
 use crate::ble::uuids::*;
+pub use crate::callbacks::CallbackHandle;
+use crate::callbacks::{CallbackError, CallbackInfo, CallbackRegistry};
+use crate::capabilities::{ProbeCapabilities, ProtocolMismatch, StatusUnavailable};
+use crate::command::AlarmBound;
+use crate::data::prediction::SetpointTracker;
+use crate::data::sanity::SanityFilter;
+use crate::data::smoothing::VirtualSmoothing;
+use crate::data::sync::{SyncComplete, SyncProgress, SyncTracker};
 use crate::data::{
-    AlarmConfig, FoodSafeConfig, FoodSafeData, FoodSafeProduct, PowerMode, PredictionInfo,
-    PredictionMode, ProbeTemperatures, Serving, SessionInfo, TemperatureLog,
-    ThermometerPreferences, VirtualTemperatures,
+    AlarmConfig, AlarmSensor, Annotation, AppExportMetadata, CookSpec, CoolingEvent,
+    CoolingMonitor, CoolingProfile, FoodSafeConfig, FoodSafeData, FoodSafeProduct, HaccpLog,
+    HaccpRecord, HaccpRecorder, LoggedDataPoint, PhysicalSensor, PowerMode, PredictionInfo,
+    PredictionMode, PredictionState, ProbeTemperatures, SanityLimits, SanityStats, SearingEvent,
+    SearingMonitor, SearingThresholds, SequenceClock, Serving, SessionInfo, SetpointReached,
+    SmoothingFilter, TemperatureLog, ThermometerPreferences, VirtualExtremes, VirtualSensor,
+    VirtualTemperatures, ALARM_COUNT,
 };
+use crate::defaults::{DefaultsApplied, ProbeDefaults};
 use crate::error::{Error, Result};
+use crate::fault::{FaultInfo, ProbeFaulted};
+use crate::frames::SnapshotFrames;
+use crate::overheat::{OverheatEvent, OverheatMonitor, OverheatPolicy};
 use crate::protocol::uart_messages::*;
-use crate::protocol::ProbeStatus;
-
-/// Callback handle for unregistering callbacks.
-pub struct CallbackHandle {
-    id: u64,
-    unregister_fn: Option<Box<dyn FnOnce() + Send + Sync>>,
-}
+use crate::protocol::{ProbeStatus, StatusDecoder};
+use crate::quarantine::{PayloadQuarantine, PayloadSource};
+use crate::snapshot::{ProbeSnapshot, StateDiff};
+use crate::timers::{AlarmEvent, TimerAlarm, TimerSchedule, TIMER_RECHECK_INTERVAL};
+use crate::transport::ProbeTransport;
+use crate::utils::Temperature;
+use crate::watch::{ThresholdCondition, ThresholdWatch};
 
-impl CallbackHandle {
-    /// Create a new callback handle.
-    pub(crate) fn new(id: u64, unregister_fn: impl FnOnce() + Send + Sync + 'static) -> Self {
-        Self {
-            id,
-            unregister_fn: Some(Box::new(unregister_fn)),
-        }
-    }
+/// Grace period after setting ID/color before accepting advertising updates.
+/// This allows time for the probe to process the command and start advertising new values.
+const ID_COLOR_GRACE_PERIOD: Duration = Duration::from_secs(5);
 
-    /// Unregister this callback.
-    pub fn unregister(mut self) {
-        if let Some(f) = self.unregister_fn.take() {
-            f();
-        }
-    }
+/// How long a UART request waits for the probe's response.
+const UART_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
 
-    /// Get the callback ID.
-    pub fn id(&self) -> u64 {
-        self.id
-    }
-}
+/// Most UART notification bytes kept while waiting for the rest of a
+/// response.
+const UART_BUFFER_LIMIT: usize = 1024;
 
-impl Drop for CallbackHandle {
-    fn drop(&mut self) {
-        if let Some(f) = self.unregister_fn.take() {
-            f();
-        }
-    }
-}
+/// How often session information and new log records are requested over
+/// UART from a probe without the Probe Status characteristic.
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
-/// Grace period after setting ID/color before accepting advertising updates.
-/// This allows time for the probe to process the command and start advertising new values.
-const ID_COLOR_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How recently a status notification must have arrived for instant read
+/// readings to be taken from status notifications rather than advertisements.
+const STATUS_READING_WINDOW: Duration = Duration::from_secs(3);
 
 /// Internal state for a probe.
 struct ProbeState {
@@ -79,14 +88,39 @@ struct ProbeState {
     color_set_at: Option<Instant>,
     /// Current temperatures.
     temperatures: ProbeTemperatures,
-    /// Virtual temperatures.
+    /// Virtual temperatures, with any core sensor override and smoothing
+    /// applied.
     virtual_temperatures: VirtualTemperatures,
+    /// Virtual temperatures before smoothing.
+    unfiltered_virtual_temperatures: VirtualTemperatures,
+    /// Sanity checks applied to incoming readings.
+    sanity: SanityFilter,
+    /// Smoothing filters for the virtual temperatures.
+    smoothing: VirtualSmoothing,
+    /// Virtual temperatures as computed by the firmware.
+    firmware_virtual_temperatures: VirtualTemperatures,
+    /// Host-side core sensor override.
+    core_sensor_override: Option<PhysicalSensor>,
+    /// Session high and low virtual temperatures.
+    extremes: VirtualExtremes,
     /// Prediction info.
     prediction: Option<PredictionInfo>,
     /// Battery status.
     battery_status: BatteryStatus,
     /// Probe mode.
     mode: ProbeMode,
+    /// When the current mode was first reported, once any mode has been.
+    mode_since: Option<(Instant, chrono::DateTime<chrono::Utc>)>,
+    /// Mode changes not yet published.
+    pending_mode_changes: Vec<ModeChange>,
+    /// Instant read reading not yet published.
+    pending_instant_read: Option<InstantReading>,
+    /// When the last instant read reading arrived, while in instant read.
+    last_instant_read: Option<Instant>,
+    /// Diagnostics while the probe is in error mode.
+    fault: Option<FaultInfo>,
+    /// Faults not yet published.
+    pending_faults: Vec<ProbeFaulted>,
     /// Overheating info.
     overheating: Overheating,
     /// Min sequence number.
@@ -95,18 +129,99 @@ struct ProbeState {
     max_sequence: u32,
     /// Temperature log.
     temperature_log: TemperatureLog,
+    /// Maps log sequence numbers to host time.
+    sequence_clock: SequenceClock,
     /// Food safety data.
     food_safe_data: Option<FoodSafeData>,
     /// Session info.
     session_info: Option<SessionInfo>,
     /// RSSI value.
     rssi: Option<i16>,
+    /// When the RSSI was last taken from an advertisement or read over the
+    /// connection.
+    rssi_checked: tokio::time::Instant,
+    /// Whether reading the RSSI over the connection is unsupported.
+    rssi_unsupported: bool,
     /// Last update time.
     last_update: Instant,
     /// Thermometer preferences (power mode).
     thermometer_preferences: Option<ThermometerPreferences>,
     /// Alarm configuration.
     alarm_config: Option<AlarmConfig>,
+    /// Cook started with `start_cook`.
+    active_cook: Option<CookSpec>,
+    /// Overheat policy being applied, if any.
+    overheat_monitor: Option<OverheatMonitor>,
+    /// Overheat events raised this session.
+    overheat_events: Vec<OverheatEvent>,
+    /// Cooling profile applied once cooked and chilled food is safe.
+    cooling_profile: CoolingProfile,
+    /// Cooling-phase monitor, once cooling monitoring has started.
+    cooling: Option<CoolingMonitor>,
+    /// Whether cooling monitoring was cancelled for the current cook.
+    cooling_cancelled: bool,
+    /// Searing detector, if enabled.
+    searing: Option<SearingMonitor>,
+    /// Detector for the core reaching the prediction set point.
+    setpoint: SetpointTracker,
+    /// Sequence number of the food safe start marked in the log.
+    food_safe_start_marked: Option<u32>,
+    /// Whether HACCP instant-read logging is enabled.
+    haccp_enabled: bool,
+    /// Item label attached to new HACCP records.
+    haccp_label: Option<String>,
+    /// Stability detector for HACCP instant-read records.
+    haccp_recorder: HaccpRecorder,
+    /// Captured HACCP records.
+    haccp_log: HaccpLog,
+    /// Connection state observed at the last update.
+    connection_state: ConnectionState,
+    /// Temperatures (Celsius) checked for crossings in state diffs.
+    change_thresholds: Vec<f64>,
+    /// GATT discovery results from the last BLE connection.
+    discovery_report: Option<DiscoveryReport>,
+    /// What the probe's firmware supports.
+    capabilities: ProbeCapabilities,
+    /// Hardware revision read when the probe last connected.
+    hardware_revision: Option<String>,
+    /// Battery models to pick from.
+    battery_profiles: BatteryProfiles,
+    /// Running time since the last charge.
+    battery: BatteryMonitor,
+    /// Configuration commands sent to the probe.
+    command_history: CommandHistory,
+    /// Tells configuration written here from changes by other clients.
+    config_tracker: ConfigTracker,
+    /// External configuration changes not yet published.
+    pending_config_changes: Vec<ConfigChangedExternally>,
+    /// Temperature alarms that started sounding, not yet published.
+    pending_alarms: Vec<AlarmEvent>,
+    /// Decodes status notifications against the previous one. Reset when
+    /// state reported in status is written locally, so the next
+    /// notification is applied in full.
+    status_decoder: StatusDecoder,
+    /// Heartbeat applied to the connection, if any.
+    heartbeat: Option<HeartbeatPolicy>,
+    /// When the last status update arrived.
+    last_status_at: Option<Instant>,
+    /// When the last status notification arrived, whether or not it could
+    /// be applied.
+    last_notification_at: Option<Instant>,
+    /// When the probe last connected.
+    connected_at: Option<Instant>,
+    /// Whether the connection was last closed by [`Probe::disconnect`]
+    /// rather than lost.
+    disconnected_by_user: bool,
+    /// Whether the current silence has been reported.
+    unhealthy_reported: bool,
+    /// Consecutive status notifications that failed to parse.
+    status_failures: u32,
+    /// Protocol mismatch suspending status parsing until reconnect.
+    protocol_mismatch: Option<ProtocolMismatch>,
+    /// Progress of the log sync.
+    log_sync: SyncTracker,
+    /// UART notification bytes not yet forming a complete response.
+    uart_buffer: Vec<u8>,
 }
 
 impl ProbeState {
@@ -119,883 +234,5097 @@ impl ProbeState {
             color_set_at: None,
             temperatures: ProbeTemperatures::new(),
             virtual_temperatures: VirtualTemperatures::default(),
+            unfiltered_virtual_temperatures: VirtualTemperatures::default(),
+            sanity: SanityFilter::default(),
+            smoothing: VirtualSmoothing::default(),
+            firmware_virtual_temperatures: VirtualTemperatures::default(),
+            core_sensor_override: None,
+            extremes: VirtualExtremes::default(),
             prediction: None,
             battery_status: BatteryStatus::default(),
             mode: ProbeMode::default(),
+            mode_since: None,
+            pending_mode_changes: Vec::new(),
+            pending_instant_read: None,
+            last_instant_read: None,
+            fault: None,
+            pending_faults: Vec::new(),
             overheating: Overheating::default(),
             min_sequence: 0,
             max_sequence: 0,
             temperature_log: TemperatureLog::default(),
+            sequence_clock: SequenceClock::new(),
             food_safe_data: None,
             session_info: None,
             rssi: None,
+            rssi_checked: tokio::time::Instant::now(),
+            rssi_unsupported: false,
             last_update: Instant::now(),
             thermometer_preferences: None,
             alarm_config: None,
+            active_cook: None,
+            overheat_monitor: None,
+            overheat_events: Vec::new(),
+            cooling_profile: CoolingProfile::default(),
+            cooling: None,
+            cooling_cancelled: false,
+            searing: None,
+            setpoint: SetpointTracker::default(),
+            food_safe_start_marked: None,
+            haccp_enabled: false,
+            haccp_label: None,
+            haccp_recorder: HaccpRecorder::default(),
+            haccp_log: HaccpLog::new(),
+            connection_state: ConnectionState::default(),
+            change_thresholds: Vec::new(),
+            discovery_report: None,
+            capabilities: ProbeCapabilities::default(),
+            hardware_revision: None,
+            battery_profiles: BatteryProfiles::default(),
+            battery: BatteryMonitor::default(),
+            command_history: CommandHistory::default(),
+            config_tracker: ConfigTracker::default(),
+            pending_config_changes: Vec::new(),
+            pending_alarms: Vec::new(),
+            status_decoder: StatusDecoder::new(),
+            heartbeat: None,
+            last_status_at: None,
+            last_notification_at: None,
+            connected_at: None,
+            disconnected_by_user: false,
+            unhealthy_reported: false,
+            status_failures: 0,
+            protocol_mismatch: None,
+            log_sync: SyncTracker::default(),
+            uart_buffer: Vec::new(),
         }
     }
-}
-
-/// Temperature update event.
-#[derive(Debug, Clone)]
-pub struct TemperatureUpdate {
-    /// Raw temperatures.
-    pub temperatures: ProbeTemperatures,
-    /// Virtual temperatures.
-    pub virtual_temperatures: VirtualTemperatures,
-}
-
-/// Represents a single Combustion Predictive Thermometer probe.
-pub struct Probe {
-    /// BLE identifier.
-    identifier: String,
-    /// Internal state.
-    state: Arc<RwLock<ProbeState>>,
-    /// Connection manager.
-    connection: Arc<ConnectionManager>,
-    /// Characteristic handler.
-    characteristics: Arc<RwLock<Option<CharacteristicHandler>>>,
-    /// Whether the probe is stale.
-    is_stale: Arc<AtomicBool>,
-    /// Temperature update channel.
-    temperature_tx: broadcast::Sender<TemperatureUpdate>,
-    /// Prediction update channel.
-    prediction_tx: broadcast::Sender<PredictionInfo>,
-    /// Log sync progress channel.
-    log_sync_tx: broadcast::Sender<f64>,
-    /// Stale timeout.
-    stale_timeout: Duration,
-    /// Callback ID counter.
-    callback_counter: Arc<std::sync::atomic::AtomicU64>,
-}
 
-impl Probe {
-    /// Default stale timeout (15 seconds).
-    pub const DEFAULT_STALE_TIMEOUT: Duration = Duration::from_secs(15);
+    /// Capture a snapshot of the state.
+    fn snapshot(
+        &self,
+        connection_state: ConnectionState,
+        captured_at: chrono::DateTime<chrono::Utc>,
+    ) -> ProbeSnapshot {
+        ProbeSnapshot {
+            schema_version: ProbeSnapshot::SCHEMA_VERSION,
+            serial_number: self.serial_number,
+            probe_id: self.probe_id,
+            color: self.color,
+            mode: self.mode,
+            battery_status: self.battery_status,
+            connection_state,
+            rssi: self.rssi,
+            temperatures: self.temperatures.clone(),
+            virtual_temperatures: self.virtual_temperatures.clone(),
+            prediction: self.prediction.clone(),
+            food_safe_data: self.food_safe_data.clone(),
+            overheating: self.overheating,
+            min_sequence: self.min_sequence,
+            max_sequence: self.max_sequence,
+            captured_at,
+        }
+    }
 
-    /// Create a new probe instance.
-    pub(crate) fn new(identifier: String, peripheral: Peripheral, serial_number: u32) -> Self {
-        let (temperature_tx, _) = broadcast::channel(64);
-        let (prediction_tx, _) = broadcast::channel(16);
-        let (log_sync_tx, _) = broadcast::channel(16);
+    /// Record the connection state for an update and capture the prior state
+    /// if anyone is listening for changes.
+    fn begin_update(
+        &mut self,
+        connection_state: ConnectionState,
+        change_tx: &broadcast::Sender<StateDiff>,
+    ) -> Option<ProbeSnapshot> {
+        let previous = (change_tx.receiver_count() > 0)
+            .then(|| self.snapshot(self.connection_state, chrono::Utc::now()));
+        self.connection_state = connection_state;
+        previous
+    }
 
-        Self {
-            identifier,
-            state: Arc::new(RwLock::new(ProbeState::new(serial_number))),
-            connection: Arc::new(ConnectionManager::new(peripheral)),
-            characteristics: Arc::new(RwLock::new(None)),
-            is_stale: Arc::new(AtomicBool::new(false)),
-            temperature_tx,
-            prediction_tx,
-            log_sync_tx,
-            stale_timeout: Self::DEFAULT_STALE_TIMEOUT,
-            callback_counter: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+    /// Publish the differences between `previous` and the current state.
+    fn publish_changes(
+        &self,
+        previous: Option<ProbeSnapshot>,
+        change_tx: &broadcast::Sender<StateDiff>,
+    ) {
+        let Some(previous) = previous else {
+            return;
+        };
+        let diff = StateDiff::between(
+            previous,
+            self.snapshot(self.connection_state, chrono::Utc::now()),
+            &self.change_thresholds,
+        );
+        if !diff.is_empty() {
+            let _ = change_tx.send(diff);
         }
     }
 
-    /// Update from advertising data.
-    pub(crate) fn update_from_advertising(&self, adv_data: &AdvertisingData, rssi: Option<i16>) {
-        let mut state = self.state.write();
-        let now = Instant::now();
+    /// Include the current virtual temperatures in the session extremes.
+    ///
+    /// Must be called after `mode` and `virtual_temperatures` have been
+    /// updated. Instant read readings are spot checks, and only T1 is
+    /// meaningful, so they are left out.
+    fn record_extremes(&mut self) {
+        if self.mode == ProbeMode::InstantRead {
+            return;
+        }
+        self.extremes
+            .update(&self.virtual_temperatures, chrono::Utc::now());
+    }
 
-        state.temperatures = adv_data.temperatures.clone();
-        state.virtual_temperatures = adv_data.virtual_temperatures.clone();
+    /// Feed the current instant-read temperature into the HACCP recorder.
+    ///
+    /// Must be called after `mode` and `temperatures` have been updated.
+    fn record_haccp_sample(&mut self) {
+        if !self.haccp_enabled {
+            return;
+        }
 
-        // Only update probe_id from advertising if we haven't recently set it explicitly.
-        // This prevents stale advertising packets from overwriting a pending ID change.
-        let id_in_grace_period = state
-            .probe_id_set_at
-            .map(|t| now.duration_since(t) < ID_COLOR_GRACE_PERIOD)
-            .unwrap_or(false);
-        if !id_in_grace_period {
-            state.probe_id = adv_data.probe_id;
+        if self.mode != ProbeMode::InstantRead {
+            self.haccp_recorder.reset();
+            return;
         }
 
-        // Only update color from advertising if we haven't recently set it explicitly.
-        let color_in_grace_period = state
-            .color_set_at
-            .map(|t| now.duration_since(t) < ID_COLOR_GRACE_PERIOD)
-            .unwrap_or(false);
-        if !color_in_grace_period {
-            state.color = adv_data.color;
+        // In instant read mode the reading is reported on T1
+        let Some(celsius) = self.temperatures.values[0].to_celsius() else {
+            return;
+        };
+
+        if let Some(stable) = self.haccp_recorder.feed(celsius) {
+            let record = HaccpRecord {
+                timestamp: chrono::Utc::now(),
+                temperature_celsius: stable,
+                probe_serial: format!("{:08X}", self.serial_number),
+                label: self.haccp_label.clone(),
+            };
+            info!(
+                "HACCP record: {} {:.1}°C {:?}",
+                record.probe_serial, record.temperature_celsius, record.label
+            );
+            self.haccp_log.push(record);
         }
+    }
 
-        state.battery_status = adv_data.battery_status;
-        state.mode = adv_data.mode;
-        state.overheating = Overheating::new(adv_data.overheating_sensors);
-        state.rssi = rssi;
-        state.last_update = now;
+    /// Store new readings, after checking them against the sanity limits.
+    fn set_readings(
+        &mut self,
+        mut temperatures: ProbeTemperatures,
+        mut virtual_temperatures: VirtualTemperatures,
+        now: Instant,
+    ) {
+        self.sanity
+            .apply(&mut temperatures, &mut virtual_temperatures, now);
+        self.temperatures = temperatures;
+        self.set_virtual_temperatures(virtual_temperatures);
+    }
 
-        // Reset stale flag
-        self.is_stale.store(false, Ordering::SeqCst);
+    /// Set the firmware's virtual temperatures, applying any core sensor
+    /// override and then smoothing.
+    ///
+    /// Must be called after `temperatures` has been updated, once per new
+    /// reading. An overridden core reads the chosen sensor, or `None` if
+    /// that sensor is inactive or invalid; the firmware's sensor selection
+    /// is kept for comparison.
+    fn set_virtual_temperatures(&mut self, firmware: VirtualTemperatures) {
+        let mut unfiltered = firmware.clone();
+        if let Some(sensor) = self.core_sensor_override {
+            unfiltered.core = self.temperatures.active_celsius()[sensor.index()];
+        }
+        self.virtual_temperatures = self.smoothing.apply(&unfiltered);
+        self.unfiltered_virtual_temperatures = unfiltered;
+        self.firmware_virtual_temperatures = firmware;
+    }
 
-        // Send temperature update
-        let _ = self.temperature_tx.send(TemperatureUpdate {
-            temperatures: state.temperatures.clone(),
-            virtual_temperatures: state.virtual_temperatures.clone(),
-        });
+    /// Apply a parsed advertising packet to the state.
+    fn apply_advertising(&mut self, adv_data: &AdvertisingData, rssi: Option<i16>, now: Instant) {
+        self.set_readings(
+            adv_data.temperatures.clone(),
+            adv_data.virtual_temperatures.clone(),
+            now,
+        );
+        self.apply_id_and_color(adv_data.probe_id, adv_data.color, now);
+        self.battery_status = adv_data.battery_status;
+        self.set_mode(adv_data.mode, now);
+        self.overheating = Overheating::new(adv_data.overheating_sensors);
+        self.update_fault();
+        self.rssi = rssi;
+        self.rssi_checked = tokio::time::Instant::now();
+        self.last_update = now;
+        self.record_extremes();
+        self.record_haccp_sample();
+        if !self.status_preferred(now) {
+            self.queue_instant_read(ReadingSource::Advertising, now);
+        }
     }
 
-    /// Update from status notification.
-    #[allow(dead_code)]
-    pub(crate) fn update_from_status(&self, status: &ProbeStatus) {
-        let mut state = self.state.write();
-        let now = Instant::now();
+    /// Apply readings pushed in by an external sensor source.
+    fn apply_readings(
+        &mut self,
+        temperatures: ProbeTemperatures,
+        virtual_temperatures: VirtualTemperatures,
+        now: Instant,
+    ) {
+        self.set_readings(temperatures, virtual_temperatures, now);
+        self.last_update = now;
+        self.record_extremes();
+        self.record_haccp_sample();
+        self.evaluate_alarms();
+    }
 
-        state.temperatures = status.temperatures.clone();
-        state.virtual_temperatures = status.virtual_temperatures.clone();
+    /// Trip alarms on the current readings, for pseudo-probes, which have
+    /// no firmware to do it.
+    fn evaluate_alarms(&mut self) {
+        let Some(mut alarms) = self.alarm_config.clone() else {
+            return;
+        };
+        let mut readings = [None; ALARM_COUNT];
+        readings[..8].copy_from_slice(&self.temperatures.to_celsius());
+        readings[8] = self.virtual_temperatures.core;
+        readings[9] = self.virtual_temperatures.surface;
+        readings[10] = self.virtual_temperatures.ambient;
+        alarms.evaluate(&readings);
+        self.queue_sounding_alarms(Some(&alarms));
+        self.alarm_config = Some(alarms);
+    }
 
-        // Only update probe_id from status if we haven't recently set it explicitly.
-        let id_in_grace_period = state
-            .probe_id_set_at
-            .map(|t| now.duration_since(t) < ID_COLOR_GRACE_PERIOD)
-            .unwrap_or(false);
-        if !id_in_grace_period {
-            state.probe_id = status.probe_id;
+    /// Apply a parsed status notification to the state.
+    fn apply_status(&mut self, status: &ProbeStatus, now: Instant) {
+        self.set_readings(
+            status.temperatures.clone(),
+            status.virtual_temperatures.clone(),
+            now,
+        );
+        self.apply_id_and_color(status.probe_id, status.color, now);
+        self.battery_status = status.battery_status;
+        self.set_mode(status.mode, now);
+        self.overheating = status.overheating;
+        self.update_fault();
+        self.min_sequence = status.min_sequence_number;
+        self.anchor_sequence(status.max_sequence_number);
+        self.max_sequence = status.max_sequence_number;
+        self.reconcile_config(status);
+        self.prediction = status.prediction.clone();
+        if let (Some(prediction), Some(cook)) = (&mut self.prediction, &self.active_cook) {
+            prediction.carryover_temperature = cook.carryover;
         }
 
-        // Only update color from status if we haven't recently set it explicitly.
-        let color_in_grace_period = state
-            .color_set_at
-            .map(|t| now.duration_since(t) < ID_COLOR_GRACE_PERIOD)
-            .unwrap_or(false);
-        if !color_in_grace_period {
-            state.color = status.color;
+        // Update thermometer preferences and alarm config from status
+        self.thermometer_preferences = status.thermometer_preferences;
+        self.queue_sounding_alarms(status.alarm_config.as_ref());
+        self.alarm_config = status.alarm_config.clone();
+
+        // Update food safe data from status
+        // Handle both local and external (e.g., iOS app) food safe configuration
+        match (&status.food_safe_config, &status.food_safe_status) {
+            (Some(config), Some(fs_status)) => {
+                if let Some(ref mut food_safe_data) = self.food_safe_data {
+                    // Update existing data with new status
+                    food_safe_data.update_from_status(fs_status.clone());
+                    // Also update config in case it changed externally
+                    food_safe_data.update_config(config.clone());
+                } else {
+                    // Create new food safe data from external config/status
+                    self.food_safe_data = Some(FoodSafeData::from_config_and_status(
+                        config.clone(),
+                        fs_status.clone(),
+                    ));
+                }
+            }
+            (Some(config), None) => {
+                // Config but no status yet - create data with config only
+                if let Some(ref mut food_safe_data) = self.food_safe_data {
+                    food_safe_data.update_config(config.clone());
+                } else {
+                    self.food_safe_data = Some(FoodSafeData::with_config(config.clone()));
+                }
+            }
+            (None, Some(fs_status)) => {
+                // Status but no config - update if we have existing data
+                if let Some(ref mut food_safe_data) = self.food_safe_data {
+                    food_safe_data.update_from_status(fs_status.clone());
+                }
+            }
+            (None, None) => {
+                // No food safe data - don't clear here as it might have been set locally
+            }
         }
+        self.mark_food_safe_start();
+        self.finish_status(now);
+    }
 
-        state.battery_status = status.battery_status;
-        state.mode = status.mode;
-        state.overheating = status.overheating;
-        state.min_sequence = status.min_sequence_number;
-        state.max_sequence = status.max_sequence_number;
-        state.prediction = status.prediction.clone();
-        state.last_update = now;
+    /// Decode and apply raw status notification bytes.
+    ///
+    /// Only the sections that changed since the previous notification are
+    /// reparsed, and when just the sequence numbers and temperatures changed
+    /// the rest of the state is left as it is.
+    fn apply_status_bytes(&mut self, data: &[u8], now: Instant) -> Result<()> {
+        let mut decoder = std::mem::take(&mut self.status_decoder);
+        let result = decoder.decode(data).map(|(status, sections)| {
+            if sections.only_readings() {
+                self.apply_status_readings(status, now);
+            } else {
+                self.apply_status(status, now);
+            }
+        });
+        self.status_decoder = decoder;
+        result
+    }
 
-        // Reset stale flag
-        self.is_stale.store(false, Ordering::SeqCst);
+    /// Get the records of the log synced and the records the probe holds.
+    fn log_sync_counts(&self) -> (u32, u32) {
+        let Some(span) = self.max_sequence.checked_sub(self.min_sequence) else {
+            return (0, 0);
+        };
+        let missing = self
+            .temperature_log
+            .missing_sequences(self.min_sequence, self.max_sequence)
+            .len() as u32;
+        (span + 1 - missing, span + 1)
+    }
 
-        // Send updates
-        let _ = self.temperature_tx.send(TemperatureUpdate {
-            temperatures: state.temperatures.clone(),
-            virtual_temperatures: state.virtual_temperatures.clone(),
-        });
+    /// Add a downloaded log record not already in the log, after checking
+    /// it against the sanity limits.
+    fn add_log_point(&mut self, mut point: LoggedDataPoint) {
+        if self
+            .temperature_log
+            .data_point(point.sequence_number)
+            .is_some()
+        {
+            return;
+        }
+        self.sanity.apply_logged(&mut point);
+        self.temperature_log.add_data_point(point);
+    }
 
-        if let Some(ref prediction) = state.prediction {
-            let _ = self.prediction_tx.send(prediction.clone());
+    /// Apply a UART response that reports probe state.
+    fn apply_uart_response(&mut self, response: &UartResponse) {
+        if !response.success {
+            return;
+        }
+        match response.message_type {
+            UartMessageType::ReadSessionInfoResponse => {
+                if let Some(info) = SessionInfo::from_bytes(&response.payload) {
+                    self.session_info = Some(info);
+                }
+            }
+            UartMessageType::ReadLogsResponse => {
+                match LoggedDataPoint::from_bytes(&response.payload) {
+                    Some(point) => self.add_log_point(point),
+                    None => debug!("Log record too short: {:02X?}", response.payload),
+                }
+            }
+            _ => {}
         }
     }
 
-    // === Identification ===
+    /// Count a status notification that failed to parse, suspending status
+    /// parsing once failures reach [`ProtocolMismatch::FAILURE_THRESHOLD`].
+    fn note_status_failure(&mut self, error: &Error) -> Option<ProtocolMismatch> {
+        self.status_failures += 1;
+        if self.status_failures < ProtocolMismatch::FAILURE_THRESHOLD {
+            return None;
+        }
 
-    /// Get the unique serial number.
-    pub fn serial_number(&self) -> u32 {
-        self.state.read().serial_number
+        let mismatch = ProtocolMismatch {
+            serial_number: self.serial_number,
+            firmware_version: self.capabilities.firmware_version,
+            consecutive_failures: self.status_failures,
+            last_error: error.to_string(),
+            detected_at: chrono::Utc::now(),
+        };
+        self.protocol_mismatch = Some(mismatch.clone());
+        Some(mismatch)
     }
 
-    /// Get the serial number as a formatted string.
-    pub fn serial_number_string(&self) -> String {
-        format!("{:08X}", self.state.read().serial_number)
+    /// Apply a status notification in which only the sequence numbers and
+    /// temperatures changed since the previous one.
+    fn apply_status_readings(&mut self, status: &ProbeStatus, now: Instant) {
+        // A local ID or color change may not have reached the probe, so the
+        // reported ones apply once the grace period is over
+        self.apply_id_and_color(status.probe_id, status.color, now);
+        self.set_readings(
+            status.temperatures.clone(),
+            status.virtual_temperatures.clone(),
+            now,
+        );
+        self.update_fault();
+        self.min_sequence = status.min_sequence_number;
+        self.anchor_sequence(status.max_sequence_number);
+        self.max_sequence = status.max_sequence_number;
+        self.finish_status(now);
     }
 
-    /// Get the BLE identifier.
-    pub fn identifier(&self) -> &str {
-        &self.identifier
+    /// Record the arrival of a status notification or UART response, which
+    /// shows the connection is alive even if it cannot be applied.
+    fn note_notification(&mut self, now: Instant) {
+        self.last_notification_at = Some(now);
+        self.unhealthy_reported = false;
     }
 
-    /// Get the probe ID (1-8).
-    pub fn id(&self) -> ProbeId {
-        self.state.read().probe_id
+    /// Record the arrival of a status notification once it is applied.
+    fn finish_status(&mut self, now: Instant) {
+        self.last_update = now;
+        self.last_status_at = Some(now);
+        self.note_notification(now);
+        self.record_extremes();
+        self.record_haccp_sample();
+        self.queue_instant_read(ReadingSource::Status, now);
     }
 
-    /// Get the silicone ring color.
-    pub fn color(&self) -> ProbeColor {
-        self.state.read().color
+    /// Queue events for temperature alarms in `alarms` that were not
+    /// sounding before.
+    ///
+    /// Must be called before `alarm_config` is updated.
+    fn queue_sounding_alarms(&mut self, alarms: Option<&AlarmConfig>) {
+        let Some(alarms) = alarms else {
+            return;
+        };
+        let previous = self.alarm_config.as_ref();
+        let at = chrono::Utc::now();
+        for (bound, statuses, before) in [
+            (
+                AlarmBound::High,
+                &alarms.high_alarms,
+                previous.map(|p| &p.high_alarms),
+            ),
+            (
+                AlarmBound::Low,
+                &alarms.low_alarms,
+                previous.map(|p| &p.low_alarms),
+            ),
+        ] {
+            for (index, status) in statuses.iter().enumerate() {
+                let was_alarming = before.is_some_and(|before| before[index].alarming);
+                if !status.alarming || was_alarming {
+                    continue;
+                }
+                let Some(sensor) = AlarmSensor::from_index(index) else {
+                    continue;
+                };
+                self.pending_alarms.push(AlarmEvent::Temperature {
+                    serial_number: self.serial_number,
+                    sensor,
+                    bound,
+                    threshold: status.temperature,
+                    at,
+                });
+            }
+        }
     }
 
-    // === Connection ===
+    /// Check if status notifications are arriving over a connection, in
+    /// which case they carry instant read readings rather than the slower
+    /// advertisements.
+    fn status_preferred(&self, now: Instant) -> bool {
+        self.connection_state.is_connected()
+            && self
+                .last_status_at
+                .is_some_and(|at| now.duration_since(at) < STATUS_READING_WINDOW)
+    }
 
-    /// Get the current connection state.
-    pub fn connection_state(&self) -> ConnectionState {
-        self.connection.state()
+    /// Queue the current instant read reading for publishing.
+    ///
+    /// Must be called after `mode` and `temperatures` have been updated.
+    fn queue_instant_read(&mut self, source: ReadingSource, now: Instant) {
+        if self.mode != ProbeMode::InstantRead {
+            self.last_instant_read = None;
+            return;
+        }
+        // In instant read mode the reading is reported on T1
+        let Some(temperature) = self.temperatures.values[0].to_celsius() else {
+            return;
+        };
+        let since_previous = self.last_instant_read.map(|at| now.duration_since(at));
+        self.last_instant_read = Some(now);
+        self.pending_instant_read = Some(InstantReading {
+            temperature,
+            source,
+            received_at: chrono::Utc::now(),
+            since_previous,
+        });
     }
 
-    /// Get the signal strength (RSSI).
-    pub fn rssi(&self) -> Option<i16> {
-        self.state.read().rssi
+    /// Annotate the log entry where the probe started food safe monitoring.
+    ///
+    /// Marks each start once; a new configuration on the probe starts at a
+    /// new sequence number and is marked again.
+    fn mark_food_safe_start(&mut self) {
+        let Some(sequence) = self
+            .food_safe_data
+            .as_ref()
+            .and_then(|data| data.start_sequence())
+        else {
+            return;
+        };
+        if self.food_safe_start_marked == Some(sequence) {
+            return;
+        }
+
+        let recorded_at = self
+            .sequence_clock
+            .timestamp(sequence)
+            .unwrap_or_else(chrono::Utc::now);
+        self.temperature_log.add_annotation(
+            Annotation::new("Food safe started", recorded_at).at_sequence(sequence),
+        );
+        self.food_safe_start_marked = Some(sequence);
     }
 
-    /// Attempt to connect to the probe.
-    pub async fn connect(&self) -> Result<()> {
-        info!("Connecting to probe {}", self.serial_number_string());
+    /// Check the heartbeat, returning how long the connection has been
+    /// silent if that is newly past the timeout.
+    fn check_heartbeat(&mut self, now: Instant) -> Option<Duration> {
+        let policy = self.heartbeat?;
+        if self.unhealthy_reported {
+            return None;
+        }
+        let since = self.last_notification_at.max(self.connected_at)?;
+        let silent_for = now.saturating_duration_since(since);
+        if silent_for < policy.timeout {
+            return None;
+        }
+        self.unhealthy_reported = true;
+        Some(silent_for)
+    }
 
-        self.connection.connect(true).await?;
+    /// Check a status notification for configuration changed by other
+    /// clients, and bring the active cook in line with them so it is not
+    /// undone by later writes.
+    fn reconcile_config(&mut self, status: &ProbeStatus) {
+        let changes = self.config_tracker.observe(self.serial_number, status);
+        for change in &changes {
+            info!(
+                "Probe {:08X} {:?} changed by another client: {:?}",
+                self.serial_number, change.kind, change.current
+            );
+            let Some(cook) = &mut self.active_cook else {
+                continue;
+            };
+            match &change.current {
+                ConfigCommand::SetPrediction {
+                    mode,
+                    set_point_celsius,
+                } => {
+                    cook.prediction_mode = *mode;
+                    cook.target = set_point_celsius + cook.carryover;
+                }
+                ConfigCommand::CancelPrediction => cook.prediction_mode = PredictionMode::None,
+                ConfigCommand::SetAlarms(config) => {
+                    cook.alarms = Some((**config).clone());
+                    cook.target_alarm = false;
+                }
+                _ => {}
+            }
+        }
+        self.pending_config_changes.extend(changes);
+    }
 
-        info!("Connected to probe {}", self.serial_number_string());
+    /// Run the host-side monitors on the current readings.
+    fn raise_events(&mut self) -> RaisedEvents {
+        RaisedEvents {
+            alarms: std::mem::take(&mut self.pending_alarms),
+            config_changes: std::mem::take(&mut self.pending_config_changes),
+            mode: std::mem::take(&mut self.pending_mode_changes),
+            instant_read: self.pending_instant_read.take(),
+            faults: std::mem::take(&mut self.pending_faults),
+            overheat: self.check_overheat(),
+            cooling: self.check_cooling(),
+            cooling_deadline: self.cooling.as_ref().and_then(CoolingMonitor::deadline),
+            searing: self.check_searing(),
+            setpoint_reached: self.check_setpoint(),
+            battery: self.check_battery(),
+        }
+    }
 
-        // Set up characteristics handler
-        let handler = CharacteristicHandler::new(self.connection.peripheral().clone());
-        handler.discover_characteristics().await?;
+    /// Get the battery model for the probe's revisions.
+    fn battery_model(&self) -> BatteryModel {
+        *self.battery_profiles.select(
+            self.hardware_revision.as_deref(),
+            self.capabilities.firmware_version,
+        )
+    }
 
-        // Subscribe to UART notifications
-        if handler.has_characteristic(&UART_TX_UUID) {
-            handler.subscribe(&UART_TX_UUID).await?;
+    /// Count running time and check if the battery is expected to run out
+    /// soon.
+    fn check_battery(&mut self) -> Option<BatteryWarning> {
+        let model = self.battery_model();
+        let (on_time, estimated_remaining) =
+            self.battery
+                .update(self.battery_status, &model, tokio::time::Instant::now())?;
+        warn!(
+            "Probe {:08X} battery expected to run out in {} min",
+            self.serial_number,
+            estimated_remaining.as_secs() / 60
+        );
+        Some(BatteryWarning {
+            serial_number: self.serial_number,
+            on_time,
+            estimated_remaining,
+            battery_low: self.battery_status.is_low(),
+            at: chrono::Utc::now(),
+        })
+    }
+
+    /// Track error mode, capturing diagnostics and clearing the virtual
+    /// temperatures, which are not valid while the probe is faulted.
+    ///
+    /// Must be called after `mode`, `temperatures`, `virtual_temperatures`,
+    /// `battery_status`, and `overheating` have been updated. Queues a
+    /// [`ProbeFaulted`] when the probe enters error mode.
+    fn update_fault(&mut self) {
+        if self.mode != ProbeMode::Error {
+            if self.fault.take().is_some() {
+                info!("Probe {:08X} recovered from error mode", self.serial_number);
+            }
+            return;
         }
 
-        // Subscribe to Probe Status notifications for prediction data
-        info!(
-            "Checking for Probe Status characteristic: {}",
-            PROBE_STATUS_CHARACTERISTIC_UUID
+        let selection = self.virtual_temperatures.sensor_selection;
+        self.virtual_temperatures =
+            VirtualTemperatures::with_selection(None, None, None, selection);
+        self.unfiltered_virtual_temperatures = self.virtual_temperatures.clone();
+
+        let faulted_at = self
+            .fault
+            .map_or_else(chrono::Utc::now, |fault| fault.faulted_at);
+        let fault = FaultInfo::capture(
+            &self.temperatures,
+            self.overheating,
+            self.battery_status,
+            faulted_at,
         );
-        if handler.has_characteristic(&PROBE_STATUS_CHARACTERISTIC_UUID) {
-            handler.subscribe(&PROBE_STATUS_CHARACTERISTIC_UUID).await?;
-            info!("Subscribed to Probe Status characteristic - prediction data will be available");
+        if self.fault.is_none() {
+            self.pending_faults.push(ProbeFaulted {
+                serial_number: self.serial_number,
+                fault,
+            });
+        }
+        self.fault = Some(fault);
+    }
+
+    /// Record the reported mode, queueing a [`ModeChange`] if it changed.
+    ///
+    /// The first reported mode starts the clock without raising a change.
+    fn set_mode(&mut self, mode: ProbeMode, now: Instant) {
+        let Some((since, _)) = self.mode_since else {
+            self.mode = mode;
+            self.mode_since = Some((now, chrono::Utc::now()));
+            return;
+        };
+        if mode == self.mode {
+            return;
+        }
+
+        let change = ModeChange {
+            from: self.mode,
+            to: mode,
+            time_in_previous_mode: now.saturating_duration_since(since),
+            changed_at: chrono::Utc::now(),
+        };
+        if mode == ProbeMode::Error {
+            warn!(
+                "Probe {:08X} entered error mode after {:?} in {:?}",
+                self.serial_number, change.time_in_previous_mode, change.from
+            );
         } else {
-            info!("Probe Status characteristic NOT found - prediction data will not be available");
+            debug!(
+                "Probe {:08X} mode changed from {:?} to {:?}",
+                self.serial_number, change.from, change.to
+            );
         }
 
-        handler.start_notifications().await?;
+        self.mode = mode;
+        self.mode_since = Some((now, change.changed_at));
+        self.pending_mode_changes.push(change);
+    }
 
-        // Start processing status notifications
-        self.start_status_notification_handler(&handler);
+    /// Track cooling of cooked and chilled food.
+    ///
+    /// Monitoring starts with the cooling profile once food safety reports
+    /// the food safe for a cooked and chilled serving.
+    fn check_cooling(&mut self) -> Vec<CoolingEvent> {
+        if self.cooling.is_none() {
+            let chilled_and_safe = self.food_safe_data.as_ref().is_some_and(|data| {
+                data.is_safe()
+                    && data
+                        .config
+                        .as_ref()
+                        .is_some_and(|config| config.serving == Serving::CookedAndChilled)
+            });
+            if !chilled_and_safe {
+                // A cancellation lasts until the next cook
+                self.cooling_cancelled = false;
+            } else if !self.cooling_cancelled {
+                self.cooling = Some(CoolingMonitor::new(self.cooling_profile.clone()));
+            }
+        }
 
-        *self.characteristics.write() = Some(handler);
+        match (&mut self.cooling, self.virtual_temperatures.core) {
+            (Some(monitor), Some(core)) => monitor.update(core, chrono::Utc::now()),
+            _ => Vec::new(),
+        }
+    }
 
-        Ok(())
+    /// Feed the surface temperature to the searing detector, annotating the
+    /// log when searing starts or ends.
+    ///
+    /// Instant read readings are spot checks and are left out.
+    fn check_searing(&mut self) -> Option<SearingEvent> {
+        if self.mode == ProbeMode::InstantRead {
+            return None;
+        }
+        let monitor = self.searing.as_mut()?;
+        let event = monitor.update(self.virtual_temperatures.surface?, chrono::Utc::now())?;
+
+        let (text, at) = match &event {
+            SearingEvent::Started { at, .. } => ("Searing started", *at),
+            SearingEvent::Ended { at, .. } => ("Searing ended", *at),
+        };
+        self.temperature_log
+            .add_annotation(Annotation::new(text, at).at_sequence(self.max_sequence));
+        Some(event)
     }
 
-    /// Start a background task to process status notifications.
-    fn start_status_notification_handler(&self, handler: &CharacteristicHandler) {
-        use tracing::debug;
+    /// Check if the core crossed the prediction set point, annotating the
+    /// log at the interpolated time of the crossing.
+    ///
+    /// Uses the unsmoothed core so filtering does not delay the crossing.
+    fn check_setpoint(&mut self) -> Option<SetpointReached> {
+        if self.mode == ProbeMode::InstantRead {
+            return None;
+        }
+        let set_point = self
+            .prediction
+            .as_ref()
+            .map(|prediction| prediction.set_point_temperature)
+            .filter(|set_point| *set_point > 0.0);
+        let core = self.unfiltered_virtual_temperatures.core?;
+        let event = self.setpoint.update(set_point, core, chrono::Utc::now())?;
+
+        self.temperature_log.add_annotation(
+            Annotation::new("Setpoint reached", event.interpolated_at)
+                .at_sequence(self.max_sequence),
+        );
+        Some(event)
+    }
 
-        let mut rx = handler.subscribe_notifications();
-        let state = self.state.clone();
-        let temperature_tx = self.temperature_tx.clone();
-        let prediction_tx = self.prediction_tx.clone();
-        let is_stale = self.is_stale.clone();
+    /// Apply the overheat policy to the current readings, recording and
+    /// returning any new events.
+    fn check_overheat(&mut self) -> Vec<OverheatEvent> {
+        let Some(monitor) = &mut self.overheat_monitor else {
+            return Vec::new();
+        };
+        let events = monitor.check(&self.temperatures, self.overheating, chrono::Utc::now());
+        for event in &events {
+            let text = format!("{} sensors overheating", event.group.name());
+            self.temperature_log.add_annotation(
+                Annotation::new(text, event.detected_at).at_sequence(self.max_sequence),
+            );
+        }
+        self.overheat_events.extend(events.iter().cloned());
+        events
+    }
 
-        let expected_status_uuid = PROBE_STATUS_CHARACTERISTIC_UUID;
-        debug!(
-            "Status notification handler: Looking for UUID {}",
-            expected_status_uuid
+    /// Anchor a newly reported sequence number to the host clock.
+    ///
+    /// Sequence numbers only go backwards when the probe starts a new
+    /// session, so the clock starts over.
+    fn anchor_sequence(&mut self, max_sequence: u32) {
+        if max_sequence < self.max_sequence {
+            self.sequence_clock.reset();
+        }
+        if max_sequence != self.max_sequence {
+            self.sequence_clock
+                .set_nominal_period(self.temperature_log.sample_period_ms);
+            self.sequence_clock.anchor(max_sequence, chrono::Utc::now());
+        }
+    }
+
+    /// Apply a snapshot relayed from another host.
+    #[cfg(feature = "gateway")]
+    fn apply_snapshot(&mut self, snapshot: &ProbeSnapshot, now: Instant) {
+        self.set_readings(
+            snapshot.temperatures.clone(),
+            snapshot.virtual_temperatures.clone(),
+            now,
         );
+        self.apply_id_and_color(snapshot.probe_id, snapshot.color, now);
+        self.status_decoder.reset();
+        self.battery_status = snapshot.battery_status;
+        self.set_mode(snapshot.mode, now);
+        self.overheating = snapshot.overheating;
+        self.update_fault();
+        self.min_sequence = snapshot.min_sequence;
+        self.max_sequence = snapshot.max_sequence;
+        self.prediction = snapshot.prediction.clone();
+        self.food_safe_data = snapshot.food_safe_data.clone();
+        self.rssi = snapshot.rssi;
+        self.last_update = now;
+        self.last_status_at = Some(now);
+        self.note_notification(now);
+        self.record_extremes();
+        self.record_haccp_sample();
+    }
 
-        tokio::spawn(async move {
-            debug!("Status notification handler started");
-            while let Ok(event) = rx.recv().await {
-                let is_status = event.characteristic_uuid == expected_status_uuid;
-                debug!(
-                    "Received notification: UUID={}, expected={}, match={}, data_len={}",
-                    event.characteristic_uuid,
+    /// Update probe ID and color unless they were recently set explicitly.
+    ///
+    /// This prevents stale packets from overwriting a pending ID/color change.
+    fn apply_id_and_color(&mut self, probe_id: ProbeId, color: ProbeColor, now: Instant) {
+        let id_in_grace_period = self
+            .probe_id_set_at
+            .map(|t| now.duration_since(t) < ID_COLOR_GRACE_PERIOD)
+            .unwrap_or(false);
+        if !id_in_grace_period {
+            self.probe_id = probe_id;
+        }
+
+        let color_in_grace_period = self
+            .color_set_at
+            .map(|t| now.duration_since(t) < ID_COLOR_GRACE_PERIOD)
+            .unwrap_or(false);
+        if !color_in_grace_period {
+            self.color = color;
+        }
+    }
+}
+
+/// Apply a status notification and publish the resulting updates.
+///
+/// Shared by [`Probe::ingest_status_bytes`] and the notification task, which
+/// only holds clones of the probe's shared handles. Returns the events
+/// raised by host-side monitors, for the caller to publish.
+///
+/// After a [`ProtocolMismatch`] notifications are ignored, raising nothing,
+/// until the probe reconnects. They still count as signs of life for the
+/// heartbeat, so the connection is not recycled while the probe is talking.
+fn apply_status_update(
+    state: &RwLock<ProbeState>,
+    channels: &StatusChannels,
+    connection_state: ConnectionState,
+    data: &[u8],
+) -> Result<RaisedEvents> {
+    let mut state = state.write();
+    let now = Instant::now();
+    state.note_notification(now);
+    if state.protocol_mismatch.is_some() {
+        return Ok(RaisedEvents::default());
+    }
+    let previous = state.begin_update(connection_state, &channels.change_tx);
+    if let Err(e) = state.apply_status_bytes(data, now) {
+        if let Some(mismatch) = state.note_status_failure(&e) {
+            warn!("{}", mismatch);
+            let _ = channels.protocol_mismatch_tx.send(mismatch);
+        }
+        return Err(e);
+    }
+    state.status_failures = 0;
+    state.publish_changes(previous, &channels.change_tx);
+
+    // Reset stale flag
+    channels.is_stale.store(false, Ordering::SeqCst);
+
+    // Send temperature update
+    let _ = channels.temperature_tx.send(TemperatureUpdate {
+        temperatures: state.temperatures.clone(),
+        virtual_temperatures: state.virtual_temperatures.clone(),
+    });
+
+    // Send prediction update if available
+    if let Some(ref prediction) = state.prediction {
+        let _ = channels.prediction_tx.send(prediction.clone());
+    }
+
+    Ok(state.raise_events())
+}
+
+/// Wait up to [`UART_RESPONSE_TIMEOUT`] for a response of `message_type`.
+async fn await_uart_response(
+    responses: &mut broadcast::Receiver<UartResponse>,
+    message_type: UartMessageType,
+) -> Result<UartResponse> {
+    tokio::time::timeout(UART_RESPONSE_TIMEOUT, async {
+        loop {
+            match responses.recv().await {
+                Ok(response) if response.message_type == message_type => return Ok(response),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Err(Error::ConnectionLost),
+            }
+        }
+    })
+    .await
+    .map_err(|_| Error::Timeout)?
+}
+
+/// Reassemble UART notifications into responses, applying those that report
+/// probe state and publishing each on `response_tx`.
+///
+/// Shared by [`Probe::ingest_uart_bytes`] and the UART notification task.
+/// Frames that fail to parse are skipped.
+fn apply_uart_bytes(
+    state: &RwLock<ProbeState>,
+    response_tx: &broadcast::Sender<UartResponse>,
+    data: &[u8],
+) {
+    let mut responses = Vec::new();
+    {
+        let mut state = state.write();
+        let mut buffer = std::mem::take(&mut state.uart_buffer);
+        buffer.extend_from_slice(data);
+        let mut rest = buffer.as_slice();
+        while let Some(start) = find_sync(rest) {
+            rest = &rest[start..];
+            let Some((frame, after)) = split_frame(rest) else {
+                break;
+            };
+            match UartResponse::parse(frame) {
+                Ok(response) => {
+                    state.note_notification(Instant::now());
+                    state.apply_uart_response(&response);
+                    responses.push(response);
+                    rest = after;
+                }
+                Err(e) => {
+                    // The sync bytes may have been noise; scan again after them
+                    debug!("Skipping UART frame: {}", e);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        if find_sync(rest).is_none() || rest.len() > UART_BUFFER_LIMIT {
+            rest = &[];
+        }
+        state.uart_buffer = rest.to_vec();
+    }
+    for response in responses {
+        let _ = response_tx.send(response);
+    }
+}
+
+/// Where [`apply_status_update`] publishes a status notification.
+#[derive(Clone)]
+struct StatusChannels {
+    /// Stale flag, cleared by each applied notification.
+    is_stale: Arc<AtomicBool>,
+    /// Temperature update channel.
+    temperature_tx: broadcast::Sender<TemperatureUpdate>,
+    /// Prediction update channel.
+    prediction_tx: broadcast::Sender<PredictionInfo>,
+    /// State change channel.
+    change_tx: broadcast::Sender<StateDiff>,
+    /// Protocol mismatch channel.
+    protocol_mismatch_tx: broadcast::Sender<ProtocolMismatch>,
+}
+
+/// Events raised by host-side monitors during a state update.
+#[derive(Debug, Default)]
+struct RaisedEvents {
+    /// Mode changes.
+    mode: Vec<ModeChange>,
+    /// Instant read reading.
+    instant_read: Option<InstantReading>,
+    /// Configuration changed by other clients.
+    config_changes: Vec<ConfigChangedExternally>,
+    /// Temperature alarms that started sounding.
+    alarms: Vec<AlarmEvent>,
+    /// Faults.
+    faults: Vec<ProbeFaulted>,
+    /// Overheat events.
+    overheat: Vec<OverheatEvent>,
+    /// Cooling-phase events.
+    cooling: Vec<CoolingEvent>,
+    /// When the current cooling stage runs out of time.
+    cooling_deadline: Option<chrono::DateTime<chrono::Utc>>,
+    /// Searing event.
+    searing: Option<SearingEvent>,
+    /// Set point crossing.
+    setpoint_reached: Option<SetpointReached>,
+    /// Battery warning.
+    battery: Option<BatteryWarning>,
+}
+
+/// Channels for events raised by host-side monitors.
+#[derive(Clone)]
+struct EventChannels {
+    /// Mode change channel.
+    mode_tx: broadcast::Sender<ModeChange>,
+    /// Fault channel.
+    fault_tx: broadcast::Sender<ProbeFaulted>,
+    /// Overheat event channel.
+    overheat_tx: broadcast::Sender<OverheatEvent>,
+    /// Cooling-phase event channel.
+    cooling_tx: broadcast::Sender<CoolingEvent>,
+    /// Searing event channel.
+    searing_tx: broadcast::Sender<SearingEvent>,
+    /// Set point crossing channel.
+    setpoint_tx: broadcast::Sender<SetpointReached>,
+    /// Unhealthy connection channel.
+    unhealthy_tx: broadcast::Sender<ConnectionUnhealthy>,
+    /// Battery warning channel.
+    battery_tx: broadcast::Sender<BatteryWarning>,
+    /// Instant read channel.
+    instant_read_tx: broadcast::Sender<InstantReading>,
+    /// External configuration change channel.
+    config_changed_tx: broadcast::Sender<ConfigChangedExternally>,
+    /// Temperature alarm and timer channel.
+    alarm_tx: broadcast::Sender<AlarmEvent>,
+    /// Applied default cook channel.
+    defaults_tx: broadcast::Sender<DefaultsApplied>,
+    /// Missing status characteristic channel.
+    status_unavailable_tx: broadcast::Sender<StatusUnavailable>,
+    /// Protocol mismatch channel.
+    protocol_mismatch_tx: broadcast::Sender<ProtocolMismatch>,
+    /// Probe state, for failing a cooling stage at its deadline.
+    state: std::sync::Weak<RwLock<ProbeState>>,
+    /// Current cooling stage deadline and the task waiting for it.
+    cooling_timer: Arc<parking_lot::Mutex<Option<CoolingTimer>>>,
+}
+
+/// Task waiting for a cooling stage deadline.
+struct CoolingTimer {
+    /// Deadline being waited for.
+    deadline: chrono::DateTime<chrono::Utc>,
+    /// Sleeps until the deadline, then fails the stage.
+    task: crate::runtime::TaskHandle<()>,
+}
+
+impl EventChannels {
+    /// Create the channels.
+    fn new(state: std::sync::Weak<RwLock<ProbeState>>) -> Self {
+        let (mode_tx, _) = broadcast::channel(16);
+        let (fault_tx, _) = broadcast::channel(16);
+        let (overheat_tx, _) = broadcast::channel(16);
+        let (cooling_tx, _) = broadcast::channel(16);
+        let (searing_tx, _) = broadcast::channel(16);
+        let (setpoint_tx, _) = broadcast::channel(16);
+        let (unhealthy_tx, _) = broadcast::channel(16);
+        let (battery_tx, _) = broadcast::channel(16);
+        let (instant_read_tx, _) = broadcast::channel(64);
+        let (config_changed_tx, _) = broadcast::channel(16);
+        let (alarm_tx, _) = broadcast::channel(16);
+        let (defaults_tx, _) = broadcast::channel(16);
+        let (status_unavailable_tx, _) = broadcast::channel(16);
+        let (protocol_mismatch_tx, _) = broadcast::channel(16);
+        Self {
+            mode_tx,
+            fault_tx,
+            overheat_tx,
+            cooling_tx,
+            searing_tx,
+            setpoint_tx,
+            unhealthy_tx,
+            battery_tx,
+            instant_read_tx,
+            config_changed_tx,
+            alarm_tx,
+            defaults_tx,
+            status_unavailable_tx,
+            protocol_mismatch_tx,
+            state,
+            cooling_timer: Arc::new(parking_lot::Mutex::new(None)),
+        }
+    }
+
+    /// Wait for a cooling stage deadline, replacing any previous one.
+    ///
+    /// Readings may stop before the deadline, so a stage that has not
+    /// finished is failed by a timer rather than by the next reading.
+    fn arm_cooling_timer(&self, deadline: Option<chrono::DateTime<chrono::Utc>>) {
+        let mut timer = self.cooling_timer.lock();
+        if timer.as_ref().map(|timer| timer.deadline) == deadline {
+            return;
+        }
+        if let Some(previous) = timer.take() {
+            previous.task.abort();
+        }
+        let Some(deadline) = deadline else {
+            return;
+        };
+
+        let state = self.state.clone();
+        let cooling_tx = self.cooling_tx.clone();
+        let task = crate::runtime::spawn(async move {
+            // The wall clock may be set or stop while the host sleeps, so
+            // check the deadline on each wake
+            while let Ok(remaining) = (deadline - chrono::Utc::now()).to_std() {
+                if remaining.is_zero() {
+                    break;
+                }
+                tokio::time::sleep(remaining.min(TIMER_RECHECK_INTERVAL)).await;
+            }
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let event = state
+                .write()
+                .cooling
+                .as_mut()
+                .and_then(|monitor| monitor.expire(chrono::Utc::now()));
+            if let Some(event) = event {
+                let _ = cooling_tx.send(event);
+            }
+        });
+        *timer = Some(CoolingTimer { deadline, task });
+    }
+
+    /// Publish raised events.
+    fn publish(&self, events: RaisedEvents) {
+        for change in events.mode {
+            let _ = self.mode_tx.send(change);
+        }
+        if let Some(reading) = events.instant_read {
+            let _ = self.instant_read_tx.send(reading);
+        }
+        for change in events.config_changes {
+            let _ = self.config_changed_tx.send(change);
+        }
+        for alarm in events.alarms {
+            let _ = self.alarm_tx.send(alarm);
+        }
+        for fault in events.faults {
+            let _ = self.fault_tx.send(fault);
+        }
+        for event in events.overheat {
+            let _ = self.overheat_tx.send(event);
+        }
+        for event in events.cooling {
+            let _ = self.cooling_tx.send(event);
+        }
+        self.arm_cooling_timer(events.cooling_deadline);
+        if let Some(event) = events.searing {
+            let _ = self.searing_tx.send(event);
+        }
+        if let Some(event) = events.setpoint_reached {
+            let _ = self.setpoint_tx.send(event);
+        }
+        if let Some(warning) = events.battery {
+            let _ = self.battery_tx.send(warning);
+        }
+    }
+}
+
+/// Where a reading came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadingSource {
+    /// An advertisement.
+    Advertising,
+    /// A status notification over a connection.
+    Status,
+}
+
+/// An instant read reading.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstantReading {
+    /// Temperature in Celsius.
+    pub temperature: f64,
+    /// Where the reading came from.
+    pub source: ReadingSource,
+    /// When the reading arrived.
+    pub received_at: chrono::DateTime<chrono::Utc>,
+    /// Time since the previous reading, or `None` for the first reading
+    /// since entering instant read.
+    pub since_previous: Option<Duration>,
+}
+
+/// Temperature update event.
+#[derive(Debug, Clone)]
+pub struct TemperatureUpdate {
+    /// Raw temperatures.
+    pub temperatures: ProbeTemperatures,
+    /// Virtual temperatures.
+    pub virtual_temperatures: VirtualTemperatures,
+}
+
+/// The probe switched operating mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeChange {
+    /// Mode before the change.
+    pub from: ProbeMode,
+    /// Mode after the change.
+    pub to: ProbeMode,
+    /// How long the probe was in `from`, as observed by the host.
+    pub time_in_previous_mode: Duration,
+    /// When the change was observed.
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// How a probe is reached.
+#[derive(Clone)]
+enum Link {
+    /// Direct BLE connection to a local peripheral.
+    Ble {
+        /// Connection manager.
+        connection: Arc<ConnectionManager>,
+        /// Characteristic handler.
+        characteristics: Arc<RwLock<Option<Arc<CharacteristicHandler>>>>,
+    },
+    /// Caller-supplied transport (e.g. a network gateway).
+    External(Arc<dyn ProbeTransport>),
+}
+
+impl Link {
+    /// Get the state of the connection.
+    fn connection_state(&self) -> ConnectionState {
+        match self {
+            Self::Ble { connection, .. } => connection.state(),
+            Self::External(transport) => transport.connection_state(),
+        }
+    }
+
+    /// Refer to the link without keeping it alive.
+    fn downgrade(&self) -> WeakLink {
+        match self {
+            Self::Ble { connection, .. } => WeakLink::Ble(Arc::downgrade(connection)),
+            Self::External(transport) => WeakLink::External(Arc::downgrade(transport)),
+        }
+    }
+}
+
+/// A [`Link`] that does not keep the connection alive.
+enum WeakLink {
+    /// Direct BLE connection.
+    Ble(std::sync::Weak<ConnectionManager>),
+    /// Caller-supplied transport.
+    External(std::sync::Weak<dyn ProbeTransport>),
+}
+
+impl WeakLink {
+    /// Get the state of the connection, or `None` if the link is gone.
+    fn connection_state(&self) -> Option<ConnectionState> {
+        match self {
+            Self::Ble(connection) => Some(connection.upgrade()?.state()),
+            Self::External(transport) => Some(transport.upgrade()?.connection_state()),
+        }
+    }
+}
+
+/// A configuration step of [`Probe::start_cook`].
+#[derive(Debug, Clone, Copy)]
+enum CookStep {
+    /// Prediction set point and mode.
+    Prediction,
+    /// Food safety configuration.
+    FoodSafe,
+    /// Alarm configuration.
+    Alarms,
+}
+
+/// Settings replaced by [`Probe::start_cook`], kept for rollback.
+struct PreviousCookSettings {
+    /// Active prediction mode and set point (Celsius).
+    prediction: Option<(PredictionMode, f64)>,
+    /// Food safety configuration.
+    food_safe: Option<FoodSafeConfig>,
+    /// Alarm configuration.
+    alarms: Option<AlarmConfig>,
+}
+
+/// Represents a single Combustion Predictive Thermometer probe.
+pub struct Probe {
+    /// Current platform identifier.
+    identifier: RwLock<String>,
+    /// Internal state.
+    state: Arc<RwLock<ProbeState>>,
+    /// Connection link.
+    link: Link,
+    /// Whether the probe is stale.
+    is_stale: Arc<AtomicBool>,
+    /// Temperature update channel.
+    temperature_tx: broadcast::Sender<TemperatureUpdate>,
+    /// Prediction update channel.
+    prediction_tx: broadcast::Sender<PredictionInfo>,
+    /// Log sync progress channel.
+    log_sync_tx: broadcast::Sender<SyncProgress>,
+    /// Log sync completion channel.
+    log_sync_complete_tx: broadcast::Sender<SyncComplete>,
+    /// State change channel.
+    change_tx: broadcast::Sender<StateDiff>,
+    /// UART response channel, for requests awaiting their response.
+    uart_response_tx: broadcast::Sender<UartResponse>,
+    /// Channels for events raised by host-side monitors.
+    events: EventChannels,
+    /// Task carrying out the overheat policy's protective actions.
+    overheat_responder: parking_lot::Mutex<Option<crate::runtime::TaskHandle<()>>>,
+    /// Task setting the core alarm when a removal prediction completes.
+    removal_alarm_responder: parking_lot::Mutex<Option<crate::runtime::TaskHandle<()>>>,
+    /// Task polling over UART while connected without status notifications.
+    status_poller: parking_lot::Mutex<Option<crate::runtime::TaskHandle<()>>>,
+    /// Task feeding an external transport's UART notifications into the probe.
+    uart_forwarder: parking_lot::Mutex<Option<crate::runtime::TaskHandle<()>>>,
+    /// Time-based alarms.
+    timers: Arc<TimerSchedule>,
+    /// Default cooks to start on connecting.
+    cook_defaults: RwLock<Option<Arc<ProbeDefaults>>>,
+    /// Stale timeout.
+    stale_timeout: Duration,
+    /// Registered callbacks.
+    callbacks: CallbackRegistry,
+    /// Whether this is a pseudo-probe fed by an external sensor source.
+    external: bool,
+    /// Buffer of payloads that failed to parse.
+    quarantine: RwLock<PayloadQuarantine>,
+}
+
+impl Probe {
+    /// Default stale timeout (15 seconds).
+    pub const DEFAULT_STALE_TIMEOUT: Duration = Duration::from_secs(15);
+
+    /// How long a connected probe may go without advertising before its RSSI
+    /// is read over the connection instead.
+    pub const RSSI_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// How long connecting waits for the probe's status before deciding
+    /// whether to start a default cook.
+    pub const DEFAULTS_STATUS_WAIT: Duration = Duration::from_secs(2);
+
+    /// Create a new probe instance.
+    pub(crate) fn new(identifier: String, peripheral: Peripheral, serial_number: u32) -> Self {
+        Self::with_link(
+            identifier,
+            serial_number,
+            Link::Ble {
+                connection: Arc::new(ConnectionManager::new(peripheral)),
+                characteristics: Arc::new(RwLock::new(None)),
+            },
+        )
+    }
+
+    /// Create a probe reached through a non-BLE transport.
+    ///
+    /// State updates must be fed in by the caller, e.g. with
+    /// [`ingest_status_bytes`](Self::ingest_status_bytes); commands are
+    /// written through `transport`.
+    pub fn with_transport(
+        identifier: impl Into<String>,
+        serial_number: u32,
+        transport: Arc<dyn ProbeTransport>,
+    ) -> Self {
+        Self::with_link(identifier.into(), serial_number, Link::External(transport))
+    }
+
+    fn with_link(identifier: String, serial_number: u32, link: Link) -> Self {
+        let (temperature_tx, _) = broadcast::channel(64);
+        let (prediction_tx, _) = broadcast::channel(16);
+        let (log_sync_tx, _) = broadcast::channel(16);
+        let (log_sync_complete_tx, _) = broadcast::channel(16);
+        let (change_tx, _) = broadcast::channel(64);
+        let (uart_response_tx, _) = broadcast::channel(64);
+        let state = Arc::new(RwLock::new(ProbeState::new(serial_number)));
+        let events = EventChannels::new(Arc::downgrade(&state));
+        let session_state = Arc::downgrade(&state);
+        let timers = TimerSchedule::new(serial_number, events.alarm_tx.clone(), move || {
+            let state = session_state.upgrade()?;
+            let session_id = state.read().session_info.as_ref()?.session_id;
+            Some(session_id)
+        });
+
+        Self {
+            identifier: RwLock::new(identifier),
+            state,
+            link,
+            is_stale: Arc::new(AtomicBool::new(false)),
+            temperature_tx,
+            prediction_tx,
+            log_sync_tx,
+            log_sync_complete_tx,
+            change_tx,
+            uart_response_tx,
+            events,
+            overheat_responder: parking_lot::Mutex::new(None),
+            removal_alarm_responder: parking_lot::Mutex::new(None),
+            status_poller: parking_lot::Mutex::new(None),
+            uart_forwarder: parking_lot::Mutex::new(None),
+            timers,
+            cook_defaults: RwLock::new(None),
+            stale_timeout: Self::DEFAULT_STALE_TIMEOUT,
+            callbacks: CallbackRegistry::new(),
+            external: false,
+            quarantine: RwLock::new(PayloadQuarantine::default()),
+        }
+    }
+
+    /// Mark this probe as a pseudo-probe fed by an external sensor source.
+    pub(crate) fn into_external(mut self) -> Self {
+        self.external = true;
+        self
+    }
+
+    /// Check if this is a pseudo-probe fed by an
+    /// [`ExternalSensorSource`](crate::external::ExternalSensorSource)
+    /// rather than a Combustion probe.
+    pub fn is_external(&self) -> bool {
+        self.external
+    }
+
+    /// Get the buffer of this probe's payloads that failed to parse.
+    ///
+    /// Disabled until given a capacity. Probes discovered by a
+    /// [`DeviceManager`](crate::DeviceManager) share its
+    /// [`quarantine`](crate::DeviceManager::quarantine).
+    pub fn quarantine(&self) -> PayloadQuarantine {
+        self.quarantine.read().clone()
+    }
+
+    /// Share a quarantine buffer, e.g. the device manager's.
+    ///
+    /// Takes effect for notifications from the next connection.
+    pub(crate) fn set_quarantine(&self, quarantine: PayloadQuarantine) {
+        *self.quarantine.write() = quarantine;
+    }
+
+    /// Keep a payload that failed to parse.
+    fn quarantine_payload(&self, source: PayloadSource, data: &[u8], error: &Error) {
+        self.quarantine
+            .read()
+            .record(source, Some(self.serial_number()), data, error);
+    }
+
+    /// Update from advertising data.
+    pub(crate) fn update_from_advertising(&self, adv_data: &AdvertisingData, rssi: Option<i16>) {
+        let connection_state = self.connection_state();
+        let mut state = self.state.write();
+        let previous = state.begin_update(connection_state, &self.change_tx);
+        state.apply_advertising(adv_data, rssi, Instant::now());
+        state.publish_changes(previous, &self.change_tx);
+        self.events.publish(state.raise_events());
+
+        // Reset stale flag
+        self.is_stale.store(false, Ordering::SeqCst);
+
+        // Send temperature update
+        let _ = self.temperature_tx.send(TemperatureUpdate {
+            temperatures: state.temperatures.clone(),
+            virtual_temperatures: state.virtual_temperatures.clone(),
+        });
+    }
+
+    /// Update from readings pushed by an external sensor source.
+    pub(crate) fn update_from_readings(
+        &self,
+        temperatures: ProbeTemperatures,
+        virtual_temperatures: VirtualTemperatures,
+    ) {
+        let connection_state = self.connection_state();
+        let mut state = self.state.write();
+        let previous = state.begin_update(connection_state, &self.change_tx);
+        state.apply_readings(temperatures, virtual_temperatures, Instant::now());
+        state.publish_changes(previous, &self.change_tx);
+
+        // Reset stale flag
+        self.is_stale.store(false, Ordering::SeqCst);
+
+        let _ = self.temperature_tx.send(TemperatureUpdate {
+            temperatures: state.temperatures.clone(),
+            virtual_temperatures: state.virtual_temperatures.clone(),
+        });
+        self.events.publish(state.raise_events());
+    }
+
+    /// Get the channels status notifications are published through.
+    fn status_channels(&self) -> StatusChannels {
+        StatusChannels {
+            is_stale: self.is_stale.clone(),
+            temperature_tx: self.temperature_tx.clone(),
+            prediction_tx: self.prediction_tx.clone(),
+            change_tx: self.change_tx.clone(),
+            protocol_mismatch_tx: self.events.protocol_mismatch_tx.clone(),
+        }
+    }
+
+    /// Update from raw status notification bytes, quarantining them if they
+    /// can't be parsed.
+    fn update_from_status_bytes(&self, data: &[u8]) -> Result<()> {
+        let events = apply_status_update(
+            &self.state,
+            &self.status_channels(),
+            self.connection_state(),
+            data,
+        )
+        .map_err(|e| {
+            self.quarantine_payload(PayloadSource::Status, data, &e);
+            e
+        })?;
+        self.events.publish(events);
+        Ok(())
+    }
+
+    /// Update from a snapshot relayed from another host.
+    #[cfg(feature = "gateway")]
+    pub(crate) fn update_from_snapshot(&self, snapshot: &ProbeSnapshot) {
+        let connection_state = self.connection_state();
+        let mut state = self.state.write();
+        let previous = state.begin_update(connection_state, &self.change_tx);
+        state.apply_snapshot(snapshot, Instant::now());
+        state.publish_changes(previous, &self.change_tx);
+        self.events.publish(state.raise_events());
+
+        // Reset stale flag
+        self.is_stale.store(false, Ordering::SeqCst);
+
+        // Send updates
+        let _ = self.temperature_tx.send(TemperatureUpdate {
+            temperatures: state.temperatures.clone(),
+            virtual_temperatures: state.virtual_temperatures.clone(),
+        });
+
+        if let Some(ref prediction) = state.prediction {
+            let _ = self.prediction_tx.send(prediction.clone());
+        }
+    }
+
+    /// Feed raw probe status bytes received via an external transport.
+    ///
+    /// The bytes must be in the Probe Status characteristic format (see
+    /// [`ProbeStatus::parse`]). This drives the same state machine as BLE
+    /// notifications, so callbacks and subscriptions fire as usual,
+    /// including ignoring the bytes after a [`ProtocolMismatch`] until the
+    /// probe reconnects.
+    pub fn ingest_status_bytes(&self, data: &[u8]) -> Result<()> {
+        self.update_from_status_bytes(data)
+    }
+
+    /// Feed raw UART notification bytes received via an external transport.
+    ///
+    /// Responses may arrive split across several calls or several to a
+    /// call; they are reassembled, and bytes that don't form a valid
+    /// response are skipped. Responses reporting probe state, such as Read
+    /// Session Information, update it as they would over BLE.
+    pub fn ingest_uart_bytes(&self, data: &[u8]) {
+        apply_uart_bytes(&self.state, &self.uart_response_tx, data);
+    }
+
+    /// Feed raw advertising manufacturer data received via an external transport.
+    ///
+    /// The bytes must be the manufacturer-specific payload (see
+    /// [`AdvertisingData::parse`]) and belong to this probe.
+    pub fn ingest_advertising_bytes(&self, data: &[u8], rssi: Option<i16>) -> Result<()> {
+        let adv_data = AdvertisingData::parse(data).map_err(|e| {
+            self.quarantine_payload(PayloadSource::Advertising, data, &e);
+            e
+        })?;
+
+        let serial_number = self.serial_number();
+        if adv_data.serial_number != serial_number {
+            return Err(Error::InvalidData {
+                context: format!(
+                    "Advertising data is for probe {:08X}, not {:08X}",
+                    adv_data.serial_number, serial_number
+                ),
+                payload: None,
+            });
+        }
+
+        self.update_from_advertising(&adv_data, rssi);
+        Ok(())
+    }
+
+    // === Identification ===
+
+    /// Get the unique serial number.
+    pub fn serial_number(&self) -> u32 {
+        self.state.read().serial_number
+    }
+
+    /// Get the serial number as a formatted string.
+    pub fn serial_number_string(&self) -> String {
+        format!("{:08X}", self.state.read().serial_number)
+    }
+
+    /// Get the probe's current platform identifier.
+    ///
+    /// This is the BLE address on Linux and Windows, but a per-host UUID on
+    /// macOS that can change between app launches, and occasionally while
+    /// running. It is updated when the probe is rediscovered under a new
+    /// identifier. Use [`serial_number`](Self::serial_number) to identify a
+    /// probe persistently.
+    pub fn identifier(&self) -> String {
+        self.identifier.read().clone()
+    }
+
+    /// Point the probe at a rediscovered peripheral with a new identifier.
+    ///
+    /// Skipped while connected, since the connection belongs to the old
+    /// peripheral.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the probe now uses the new identifier.
+    pub(crate) fn rebind(&self, identifier: String, peripheral: Peripheral) -> bool {
+        let Link::Ble { connection, .. } = &self.link else {
+            return false;
+        };
+        if !connection.rebind(peripheral) {
+            return false;
+        }
+        *self.identifier.write() = identifier;
+        true
+    }
+
+    /// Get the probe ID (1-8).
+    pub fn id(&self) -> ProbeId {
+        self.state.read().probe_id
+    }
+
+    /// Get the silicone ring color.
+    pub fn color(&self) -> ProbeColor {
+        self.state.read().color
+    }
+
+    /// Capture a snapshot of the probe's current state.
+    pub fn snapshot(&self) -> ProbeSnapshot {
+        self.snapshot_at(chrono::Utc::now())
+    }
+
+    /// Capture a snapshot stamped with the given time, so snapshots taken
+    /// together can share one.
+    pub(crate) fn snapshot_at(&self, captured_at: chrono::DateTime<chrono::Utc>) -> ProbeSnapshot {
+        let connection_state = self.connection_state();
+        self.state.read().snapshot(connection_state, captured_at)
+    }
+
+    /// Subscribe to state changes.
+    ///
+    /// A [`StateDiff`] is sent after each update that changed at least one
+    /// field or crossed one of the [change thresholds](Self::set_change_thresholds).
+    /// Connection state changes are reported with the next data update.
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<StateDiff> {
+        self.change_tx.subscribe()
+    }
+
+    /// Publish snapshots of the probe at a fixed rate, e.g. for a GUI.
+    ///
+    /// Updates are coalesced into at most one snapshot per `interval`, and
+    /// nothing is published for intervals in which the probe did not change;
+    /// see [`SnapshotFrames`]. Must be called within a Tokio runtime.
+    pub fn snapshot_frames(&self, interval: Duration) -> SnapshotFrames {
+        // Hold the probe weakly so frames end when it is dropped
+        let state = Arc::downgrade(&self.state);
+        let link = self.link.downgrade();
+        let changes = self.change_tx.subscribe();
+        SnapshotFrames::spawn(interval, self.snapshot(), changes, move || {
+            let connection_state = link.connection_state()?;
+            Some(
+                state
+                    .upgrade()?
+                    .read()
+                    .snapshot(connection_state, chrono::Utc::now()),
+            )
+        })
+    }
+
+    /// Set the temperatures (Celsius) reported as threshold crossings in
+    /// [`StateDiff`]s.
+    pub fn set_change_thresholds(&self, thresholds: impl IntoIterator<Item = f64>) {
+        self.state.write().change_thresholds = thresholds.into_iter().collect();
+    }
+
+    /// Get the temperatures (Celsius) reported as threshold crossings.
+    pub fn change_thresholds(&self) -> Vec<f64> {
+        self.state.read().change_thresholds.clone()
+    }
+
+    // === Connection ===
+
+    /// Get the current connection state.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.link.connection_state()
+    }
+
+    /// Get the GATT discovery report from the last BLE connection.
+    ///
+    /// Lists which expected characteristics were found or missing. `None`
+    /// until the probe has connected, and always `None` for probes reached
+    /// through an external transport.
+    pub fn discovery_report(&self) -> Option<DiscoveryReport> {
+        self.state.read().discovery_report.clone()
+    }
+
+    /// Get the BLE connection attempt history.
+    ///
+    /// Counts attempts since the probe was discovered, including retries.
+    /// Always `None` for probes reached through an external transport.
+    pub fn connection_metrics(&self) -> Option<ConnectionMetrics> {
+        match &self.link {
+            Link::Ble { connection, .. } => Some(connection.metrics()),
+            Link::External(_) => None,
+        }
+    }
+
+    /// Get the signal strength (RSSI).
+    pub fn rssi(&self) -> Option<i16> {
+        self.state.read().rssi
+    }
+
+    /// Read the signal strength over the active connection.
+    ///
+    /// Some platforms stop delivering advertisements while a probe is
+    /// connected, which would leave [`rssi`](Self::rssi) at its value from
+    /// before the connection. The [`DeviceManager`](crate::DeviceManager)
+    /// calls this for connected probes that have not advertised for
+    /// [`RSSI_REFRESH_INTERVAL`](Self::RSSI_REFRESH_INTERVAL).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotConnected`] if the probe is not connected, or
+    /// [`Error::NotSupported`] if the platform or transport cannot read the
+    /// RSSI over a connection, in which case `rssi` keeps its last value and
+    /// the `DeviceManager` stops asking.
+    pub async fn refresh_rssi(&self) -> Result<i16> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+        // Marked before reading so a slow read isn't started again meanwhile
+        self.state.write().rssi_checked = tokio::time::Instant::now();
+
+        let result = match &self.link {
+            Link::Ble { connection, .. } => connection.read_rssi().await,
+            Link::External(transport) => transport.read_rssi().await,
+        };
+        let rssi = match result {
+            Ok(rssi) => rssi,
+            Err(e) => {
+                if matches!(e, Error::NotSupported { .. }) {
+                    self.state.write().rssi_unsupported = true;
+                }
+                return Err(e);
+            }
+        };
+        let connection_state = self.connection_state();
+        let mut state = self.state.write();
+        let previous = state.begin_update(connection_state, &self.change_tx);
+        state.rssi = Some(rssi);
+        state.publish_changes(previous, &self.change_tx);
+        Ok(rssi)
+    }
+
+    /// Check if the probe is connected, can read its RSSI over the
+    /// connection, and has not had an RSSI for
+    /// [`RSSI_REFRESH_INTERVAL`](Self::RSSI_REFRESH_INTERVAL).
+    pub(crate) fn needs_rssi_refresh(&self) -> bool {
+        let state = self.state.read();
+        self.is_connected()
+            && !state.rssi_unsupported
+            && state.rssi_checked.elapsed() >= Self::RSSI_REFRESH_INTERVAL
+    }
+
+    /// Attempt to connect to the probe.
+    ///
+    /// Over BLE, the probe's current status is read once connected, so the
+    /// prediction, food safety, alarm, and power mode state is available
+    /// when this returns rather than after the next status notification.
+    /// A default cook set with [`set_cook_defaults`](Self::set_cook_defaults)
+    /// is then started if no cook is running, here or on the probe.
+    pub async fn connect(&self) -> Result<()> {
+        self.state.write().disconnected_by_user = false;
+        self.connect_link().await?;
+        self.apply_cook_defaults().await;
+        Ok(())
+    }
+
+    /// Connect the link and start handling its notifications.
+    async fn connect_link(&self) -> Result<()> {
+        info!("Connecting to probe {}", self.serial_number_string());
+
+        let (connection, characteristics) = match &self.link {
+            Link::Ble {
+                connection,
+                characteristics,
+            } => (connection, characteristics),
+            Link::External(transport) => {
+                transport.connect().await?;
+                if let Some(revision) = transport.firmware_revision() {
+                    self.set_capabilities(ProbeCapabilities::from_firmware_revision(&revision));
+                }
+                let has_status = transport.has_status_characteristic();
+                self.note_status_characteristic(has_status);
+                self.forward_uart(transport.subscribe_uart());
+                self.mark_connected();
+                info!("Connected to probe {}", self.serial_number_string());
+                self.poll_without_status(!has_status);
+                return Ok(());
+            }
+        };
+
+        connection.connect(true).await?;
+        self.mark_connected();
+
+        info!("Connected to probe {}", self.serial_number_string());
+
+        // Set up characteristics handler
+        let handler = CharacteristicHandler::new(connection.peripheral());
+        handler.discover_characteristics().await?;
+
+        let report = handler.discovery_report();
+        if !report.is_complete() {
+            warn!(
+                "Probe {} is missing required characteristics: {:?}",
+                self.serial_number_string(),
+                report.missing_required()
+            );
+        }
+        self.state.write().discovery_report = Some(report);
+
+        if handler.has_characteristic(&FIRMWARE_REVISION_UUID) {
+            match handler.read_firmware_revision().await {
+                Ok(revision) => {
+                    self.set_capabilities(ProbeCapabilities::from_firmware_revision(&revision))
+                }
+                Err(e) => debug!(
+                    "Failed to read firmware revision from {}: {}",
+                    self.serial_number_string(),
+                    e
+                ),
+            }
+        }
+        if handler.has_characteristic(&HARDWARE_REVISION_UUID) {
+            match handler.read_hardware_revision().await {
+                Ok(revision) => self.state.write().hardware_revision = Some(revision),
+                Err(e) => debug!(
+                    "Failed to read hardware revision from {}: {}",
+                    self.serial_number_string(),
+                    e
+                ),
+            }
+        }
+
+        // Subscribe to UART notifications
+        if handler.has_characteristic(&UART_TX_UUID) {
+            handler.subscribe(&UART_TX_UUID).await?;
+        }
+
+        // Subscribe to Probe Status notifications for prediction data
+        info!(
+            "Checking for Probe Status characteristic: {}",
+            PROBE_STATUS_CHARACTERISTIC_UUID
+        );
+        let has_status = handler.has_characteristic(&PROBE_STATUS_CHARACTERISTIC_UUID);
+        if has_status {
+            handler.subscribe(&PROBE_STATUS_CHARACTERISTIC_UUID).await?;
+            info!("Subscribed to Probe Status characteristic - prediction data will be available");
+        } else {
+            info!("Probe Status characteristic NOT found - prediction data will not be available");
+        }
+        self.note_status_characteristic(has_status);
+
+        handler.start_notifications().await?;
+
+        // Start processing status notifications
+        self.start_status_notification_handler(&handler);
+        self.start_uart_notification_handler(&handler);
+        self.read_back_status(&handler).await;
+        if handler.has_characteristic(&UART_TX_UUID) {
+            self.request_session_info(&handler).await;
+        }
+
+        *characteristics.write() = Some(Arc::new(handler));
+        self.poll_without_status(!has_status);
+
+        Ok(())
+    }
+
+    /// Start or stop feeding UART notification bytes from an external
+    /// transport into the probe.
+    ///
+    /// The bytes are handled as UART notifications over BLE are.
+    fn forward_uart(&self, rx: Option<broadcast::Receiver<Vec<u8>>>) {
+        let mut forwarder = self.uart_forwarder.lock();
+        if let Some(previous) = forwarder.take() {
+            previous.abort();
+        }
+        let Some(mut rx) = rx else {
+            return;
+        };
+
+        self.state.write().uart_buffer.clear();
+        // Stops once the probe is dropped
+        let state = Arc::downgrade(&self.state);
+        let response_tx = self.uart_response_tx.clone();
+        *forwarder = Some(crate::runtime::spawn(async move {
+            loop {
+                let data = match rx.recv().await {
+                    Ok(data) => data,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Missed {} UART notifications", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+                apply_uart_bytes(&state, &response_tx, &data);
+            }
+        }));
+    }
+
+    /// Start or stop polling over UART for the data status notifications
+    /// would otherwise carry.
+    ///
+    /// Without the Probe Status characteristic, session information and
+    /// new log records are requested every [`STATUS_POLL_INTERVAL`]; the
+    /// responses are applied as they arrive. The probe offers no request
+    /// for prediction, food safety, alarm or power mode state.
+    fn poll_without_status(&self, poll: bool) {
+        let mut poller = self.status_poller.lock();
+        if let Some(previous) = poller.take() {
+            previous.abort();
+        }
+        if !poll {
+            return;
+        }
+
+        info!(
+            "Polling probe {} over UART for session information and logs",
+            self.serial_number_string()
+        );
+        // Stops once the probe is dropped
+        let state = Arc::downgrade(&self.state);
+        let link = self.link.clone();
+        let serial_number = self.serial_number_string();
+        *poller = Some(crate::runtime::spawn(async move {
+            let mut interval = tokio::time::interval(STATUS_POLL_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+                let next = state
+                    .read()
+                    .temperature_log
+                    .max_sequence()
+                    .map_or(0, |sequence| sequence.saturating_add(1));
+                for request in [
+                    build_read_session_info_request(),
+                    build_read_logs_request(next, u32::MAX),
+                ] {
+                    if let Err(e) = write_link_uart(&link, &request.to_bytes()).await {
+                        debug!("Failed to poll probe {}: {}", serial_number, e);
+                        break;
+                    }
+                }
+            }
+        }));
+    }
+
+    /// Read the Probe Status characteristic once after connecting.
+    ///
+    /// Populates the prediction, food safety, alarm, and power mode state
+    /// immediately, instead of waiting for the first notification carrying
+    /// those blocks. Failures are logged and otherwise ignored; the
+    /// notifications fill the state in later.
+    async fn read_back_status(&self, handler: &CharacteristicHandler) {
+        if !handler.has_characteristic(&PROBE_STATUS_CHARACTERISTIC_UUID) {
+            return;
+        }
+
+        let data = match handler.read(&PROBE_STATUS_CHARACTERISTIC_UUID).await {
+            Ok(data) => data,
+            Err(e) => {
+                debug!(
+                    "Failed to read Probe Status from {}: {}",
+                    self.serial_number_string(),
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Err(e) = self.update_from_status_bytes(&data) {
+            debug!("Failed to parse Probe Status read back: {:?}", e);
+        }
+    }
+
+    /// Ask the probe for its session information after connecting.
+    ///
+    /// The response is applied when it arrives, so connecting does not wait
+    /// for it. Failures are logged and otherwise ignored.
+    async fn request_session_info(&self, handler: &CharacteristicHandler) {
+        let request = build_read_session_info_request().to_bytes();
+        if let Err(e) = handler.write_uart(&request).await {
+            debug!(
+                "Failed to request session info from {}: {}",
+                self.serial_number_string(),
+                e
+            );
+        }
+    }
+
+    /// Start a background task to process UART responses.
+    fn start_uart_notification_handler(&self, handler: &CharacteristicHandler) {
+        let mut rx = handler.subscribe_notifications();
+        let state = self.state.clone();
+        let response_tx = self.uart_response_tx.clone();
+        state.write().uart_buffer.clear();
+
+        crate::runtime::spawn(async move {
+            while let Ok(event) = rx.recv().await {
+                if event.characteristic_uuid == UART_TX_UUID {
+                    apply_uart_bytes(&state, &response_tx, &event.data);
+                }
+            }
+        });
+    }
+
+    /// Restart the heartbeat silence timer and status parsing for a new
+    /// connection.
+    fn mark_connected(&self) {
+        let mut state = self.state.write();
+        state.connected_at = Some(Instant::now());
+        state.unhealthy_reported = false;
+        state.status_failures = 0;
+        state.protocol_mismatch = None;
+    }
+
+    /// Start a background task to process status notifications.
+    fn start_status_notification_handler(&self, handler: &CharacteristicHandler) {
+        let mut rx = handler.subscribe_notifications();
+        let state = self.state.clone();
+        let channels = self.status_channels();
+        let events = self.events.clone();
+        let quarantine = self.quarantine();
+        let serial_number = self.serial_number();
+
+        let expected_status_uuid = PROBE_STATUS_CHARACTERISTIC_UUID;
+        debug!(
+            "Status notification handler: Looking for UUID {}",
+            expected_status_uuid
+        );
+
+        crate::runtime::spawn(async move {
+            debug!("Status notification handler started");
+            while let Ok(event) = rx.recv().await {
+                let is_status = event.characteristic_uuid == expected_status_uuid;
+                debug!(
+                    "Received notification: UUID={}, expected={}, match={}, data_len={}",
+                    event.characteristic_uuid,
                     expected_status_uuid,
                     is_status,
                     event.data.len()
                 );
 
-                // Only process probe status notifications
-                if is_status {
-                    debug!(
-                        "Processing Probe Status notification: {} bytes, data: {:02X?}",
-                        event.data.len(),
-                        &event.data[..std::cmp::min(event.data.len(), 40)]
+                // Only process probe status notifications
+                if is_status {
+                    debug!(
+                        "Processing Probe Status notification: {} bytes, data: {:02X?}",
+                        event.data.len(),
+                        &event.data[..std::cmp::min(event.data.len(), 40)]
+                    );
+
+                    let raised = apply_status_update(
+                        &state,
+                        &channels,
+                        ConnectionState::Connected,
+                        &event.data,
+                    );
+                    match raised {
+                        Ok(raised) => events.publish(raised),
+                        Err(e) => {
+                            debug!("Failed to parse status notification: {:?}", e);
+                            quarantine.record(
+                                PayloadSource::Status,
+                                Some(serial_number),
+                                &event.data,
+                                &e,
+                            );
+                        }
+                    }
+                }
+            }
+            debug!("Status notification handler stopped");
+        });
+    }
+
+    /// Disconnect from the probe.
+    ///
+    /// The probe is not connected again automatically, e.g. as a known
+    /// probe marked for auto-connect, until [`connect`](Self::connect) is
+    /// called.
+    pub async fn disconnect(&self) -> Result<()> {
+        info!("Disconnecting from probe {}", self.serial_number_string());
+        self.state.write().disconnected_by_user = true;
+        self.poll_without_status(false);
+        self.forward_uart(None);
+
+        let (connection, characteristics) = match &self.link {
+            Link::Ble {
+                connection,
+                characteristics,
+            } => (connection, characteristics),
+            Link::External(transport) => return transport.disconnect().await,
+        };
+
+        // Stop notifications
+        let handler = characteristics.read().clone();
+        if let Some(handler) = handler {
+            handler.stop_notifications().await;
+        }
+
+        connection.disconnect().await?;
+        *characteristics.write() = None;
+
+        Ok(())
+    }
+
+    /// Check if the connection was last closed by
+    /// [`disconnect`](Self::disconnect) rather than lost.
+    pub fn is_disconnected_by_user(&self) -> bool {
+        self.state.read().disconnected_by_user
+    }
+
+    /// Check if we're maintaining a connection.
+    pub fn is_maintaining_connection(&self) -> bool {
+        match &self.link {
+            Link::Ble { connection, .. } => connection.is_maintaining_connection(),
+            Link::External(transport) => transport.is_maintaining_connection(),
+        }
+    }
+
+    /// Apply a heartbeat to the connection.
+    ///
+    /// While connected, a status silence longer than the policy's timeout
+    /// publishes a [`ConnectionUnhealthy`] event to
+    /// [`subscribe_connection_unhealthy`](Self::subscribe_connection_unhealthy)
+    /// once, and the connection is recycled if the policy asks for it. A
+    /// [`DeviceManager`](crate::DeviceManager) checks its probes every
+    /// second; call [`check_heartbeat`](Self::check_heartbeat) periodically
+    /// for probes used on their own.
+    pub fn set_heartbeat(&self, policy: HeartbeatPolicy) {
+        let mut state = self.state.write();
+        state.heartbeat = Some(policy);
+        state.unhealthy_reported = false;
+    }
+
+    /// Stop applying the heartbeat.
+    pub fn clear_heartbeat(&self) {
+        self.state.write().heartbeat = None;
+    }
+
+    /// Get the heartbeat being applied, if any.
+    pub fn heartbeat(&self) -> Option<HeartbeatPolicy> {
+        self.state.read().heartbeat
+    }
+
+    /// Check the heartbeat, publishing an event if the connection has newly
+    /// become unhealthy.
+    ///
+    /// Returns the event, whose `recycling` field says whether the caller
+    /// should call [`recycle_connection`](Self::recycle_connection).
+    pub fn check_heartbeat(&self) -> Option<ConnectionUnhealthy> {
+        self.check_heartbeat_at(Instant::now())
+    }
+
+    fn check_heartbeat_at(&self, now: Instant) -> Option<ConnectionUnhealthy> {
+        if !self.is_connected() {
+            return None;
+        }
+        let (silent_for, recycle) = {
+            let mut state = self.state.write();
+            let silent_for = state.check_heartbeat(now)?;
+            (silent_for, state.heartbeat.is_some_and(|p| p.recycle))
+        };
+
+        warn!(
+            "Probe {} sent no status for {:?}, connection unhealthy",
+            self.serial_number_string(),
+            silent_for
+        );
+        let event = ConnectionUnhealthy {
+            serial_number: self.serial_number(),
+            silent_for,
+            detected_at: chrono::Utc::now(),
+            recycling: recycle && matches!(self.link, Link::Ble { .. }),
+        };
+        let _ = self.events.unhealthy_tx.send(event.clone());
+        Some(event)
+    }
+
+    /// Subscribe to unhealthy connection events.
+    pub fn subscribe_connection_unhealthy(&self) -> broadcast::Receiver<ConnectionUnhealthy> {
+        self.events.unhealthy_tx.subscribe()
+    }
+
+    /// Register a callback for unhealthy connection events.
+    pub fn on_connection_unhealthy<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&ConnectionUnhealthy) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "connection_unhealthy",
+            self.events.unhealthy_tx.subscribe(),
+            move |event| callback(&event),
+        )
+    }
+
+    /// Drop the BLE connection and reconnect if it was maintained.
+    ///
+    /// Use when the connection is known to be dead even though the platform
+    /// still reports it, e.g. after the host slept or when it fails the
+    /// heartbeat. Does nothing for probes reached through an external
+    /// transport.
+    pub async fn recycle_connection(&self) -> Result<()> {
+        let Link::Ble {
+            connection,
+            characteristics,
+        } = &self.link
+        else {
+            return Ok(());
+        };
+        if connection.state() == ConnectionState::Disconnected {
+            return Ok(());
+        }
+
+        warn!(
+            "Recycling connection to probe {}",
+            self.serial_number_string()
+        );
+        let handler = characteristics.write().take();
+        if let Some(handler) = handler {
+            handler.stop_notifications().await;
+        }
+        connection.mark_lost().await;
+
+        if connection.is_maintaining_connection() && !connection.is_reconnection_suspended() {
+            self.connect().await?;
+        }
+        Ok(())
+    }
+
+    /// Suspend or resume automatic reconnection over BLE.
+    pub(crate) fn set_reconnection_suspended(&self, suspended: bool) {
+        if let Link::Ble { connection, .. } = &self.link {
+            connection.set_reconnection_suspended(suspended);
+        }
+    }
+
+    /// Pretend the last update arrived at `at`.
+    #[cfg(test)]
+    pub(crate) fn mark_updated_at(&self, at: Instant) {
+        self.state.write().last_update = at;
+    }
+
+    /// Check if the probe is stale (no data received recently).
+    pub fn is_stale(&self) -> bool {
+        let elapsed = self.state.read().last_update.elapsed();
+        let is_stale = elapsed > self.stale_timeout;
+        self.is_stale.store(is_stale, Ordering::SeqCst);
+        is_stale
+    }
+
+    // === Temperature Data ===
+
+    /// Get current temperatures from all 8 sensors.
+    pub fn current_temperatures(&self) -> ProbeTemperatures {
+        self.state.read().temperatures.clone()
+    }
+
+    /// Get virtual temperatures (core, surface, ambient).
+    ///
+    /// Smoothed where a [smoothing filter](Self::set_smoothing) is set.
+    pub fn virtual_temperatures(&self) -> VirtualTemperatures {
+        self.state.read().virtual_temperatures.clone()
+    }
+
+    /// Get virtual temperatures before any smoothing.
+    pub fn unfiltered_virtual_temperatures(&self) -> VirtualTemperatures {
+        self.state.read().unfiltered_virtual_temperatures.clone()
+    }
+
+    /// Smooth a virtual sensor's readings with `filter`, or `None` to stop
+    /// smoothing it.
+    ///
+    /// Applies to [`virtual_temperatures`](Self::virtual_temperatures),
+    /// temperature updates, and everything the host derives from them, such
+    /// as threshold watches, so they don't chatter when e.g. ambient
+    /// oscillates with a grill's fan. Takes effect from the next reading,
+    /// with no earlier readings in the filter.
+    pub fn set_smoothing(&self, sensor: VirtualSensor, filter: Option<SmoothingFilter>) {
+        self.state.write().smoothing.set_filter(sensor, filter);
+    }
+
+    /// Get the smoothing filter applied to a virtual sensor, if any.
+    pub fn smoothing(&self, sensor: VirtualSensor) -> Option<SmoothingFilter> {
+        self.state.read().smoothing.filter(sensor)
+    }
+
+    /// Check incoming readings against `limits`, or `None` to accept all
+    /// readings.
+    ///
+    /// Readings outside a sensor's plausible range, or changing faster than
+    /// the limits allow, are dropped or flagged before they reach the
+    /// probe's state, so a corrupted packet cannot trip alarms or land in
+    /// exports. Outliers are counted in [`sanity_stats`](Self::sanity_stats).
+    pub fn set_sanity_limits(&self, limits: Option<SanityLimits>) {
+        self.state.write().sanity.set_limits(limits);
+    }
+
+    /// Get the limits incoming readings are checked against, if any.
+    pub fn sanity_limits(&self) -> Option<SanityLimits> {
+        self.state.read().sanity.limits().cloned()
+    }
+
+    /// Get counts of readings checked against the sanity limits and of
+    /// outliers found.
+    pub fn sanity_stats(&self) -> SanityStats {
+        self.state.read().sanity.stats().clone()
+    }
+
+    /// Get virtual temperatures as computed by the firmware, ignoring any
+    /// [core sensor override](Self::override_core_sensor).
+    pub fn firmware_virtual_temperatures(&self) -> VirtualTemperatures {
+        self.state.read().firmware_virtual_temperatures.clone()
+    }
+
+    /// Override the firmware's choice of core sensor, or `None` to follow
+    /// the firmware again.
+    ///
+    /// The firmware picks the core sensor automatically, which can be wrong
+    /// for thin cuts. While overridden,
+    /// [`virtual_temperatures`](Self::virtual_temperatures) reports the core
+    /// from the chosen sensor; its `sensor_selection` and
+    /// [`firmware_virtual_temperatures`](Self::firmware_virtual_temperatures)
+    /// still show the firmware's choice for comparison. The override is
+    /// host-side only and takes effect immediately.
+    pub fn override_core_sensor(&self, sensor: Option<PhysicalSensor>) {
+        let mut state = self.state.write();
+        state.core_sensor_override = sensor;
+        state.smoothing.reset(VirtualSensor::Core);
+        let firmware = state.firmware_virtual_temperatures.clone();
+        state.set_virtual_temperatures(firmware);
+        state.update_fault();
+    }
+
+    /// Get the core sensor override, if any.
+    pub fn core_sensor_override(&self) -> Option<PhysicalSensor> {
+        self.state.read().core_sensor_override
+    }
+
+    /// Get the lowest and highest virtual temperatures seen this session.
+    ///
+    /// Updated from both advertising and status updates, so UIs can show
+    /// e.g. the day's highest ambient temperature without downloading the
+    /// log. Instant read readings are not included.
+    pub fn extremes(&self) -> VirtualExtremes {
+        self.state.read().extremes
+    }
+
+    /// Start tracking extremes afresh, e.g. for a new cook.
+    pub fn reset_extremes(&self) {
+        self.state.write().extremes = VirtualExtremes::default();
+    }
+
+    /// Subscribe to temperature updates.
+    pub fn subscribe_temperatures(&self) -> broadcast::Receiver<TemperatureUpdate> {
+        self.temperature_tx.subscribe()
+    }
+
+    /// Get the callbacks registered on this probe, for debugging.
+    pub fn callbacks(&self) -> Vec<CallbackInfo> {
+        self.callbacks.list()
+    }
+
+    /// Subscribe to reports of panicking callbacks.
+    ///
+    /// A callback that panics is reported here and keeps receiving events.
+    pub fn subscribe_callback_errors(&self) -> broadcast::Receiver<CallbackError> {
+        self.callbacks.subscribe_errors()
+    }
+
+    /// Register a callback for temperature updates.
+    pub fn on_temperatures_updated<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&ProbeTemperatures, &VirtualTemperatures) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "temperatures_updated",
+            self.temperature_tx.subscribe(),
+            move |update| callback(&update.temperatures, &update.virtual_temperatures),
+        )
+    }
+
+    /// Watch a virtual temperature until it reaches `above` (Celsius).
+    ///
+    /// The returned [`ThresholdWatch`] can be awaited directly, configured with
+    /// a sustain duration, or turned into a stream of repeated triggers.
+    pub fn watch_threshold(&self, sensor: VirtualSensor, above: f64) -> ThresholdWatch {
+        self.watch_condition(sensor, ThresholdCondition::Above(above))
+    }
+
+    /// Watch a virtual temperature until it drops below `below` (Celsius).
+    pub fn watch_threshold_below(&self, sensor: VirtualSensor, below: f64) -> ThresholdWatch {
+        self.watch_condition(sensor, ThresholdCondition::Below(below))
+    }
+
+    /// Watch a virtual temperature for an arbitrary condition.
+    pub fn watch_condition(
+        &self,
+        sensor: VirtualSensor,
+        condition: ThresholdCondition,
+    ) -> ThresholdWatch {
+        // Subscribe before reading the current value so no update is missed
+        let rx = self.temperature_tx.subscribe();
+        let value = self.state.read().virtual_temperatures.get(sensor);
+        ThresholdWatch::new(sensor, condition, rx, value)
+    }
+
+    /// Wait until `check` finds what it is looking for in the probe's state.
+    ///
+    /// `check` is called with a snapshot of the current state, then again
+    /// each time the state changes, until it returns `Some`.
+    ///
+    /// ```rust,no_run
+    /// # use combustion_rust_ble::Probe;
+    /// # use std::time::Duration;
+    /// # async fn example(probe: &Probe) -> combustion_rust_ble::Result<()> {
+    /// let rssi = probe
+    ///     .wait_for_condition(Duration::from_secs(60), |snapshot| {
+    ///         snapshot.rssi.filter(|&rssi| rssi > -70)
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if `check` has not succeeded within
+    /// `timeout`.
+    pub async fn wait_for_condition<T, F>(&self, timeout: Duration, mut check: F) -> Result<T>
+    where
+        F: FnMut(&ProbeSnapshot) -> Option<T> + Send,
+    {
+        // Subscribe before the first check so no change is missed
+        let mut changes = self.change_tx.subscribe();
+        let wait = async {
+            loop {
+                if let Some(value) = check(&self.snapshot()) {
+                    return value;
+                }
+                // A lagged receiver just checks again, and `self` keeps the
+                // sender alive, so the channel never closes here
+                let _ = changes.recv().await;
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| Error::Timeout)
+    }
+
+    /// Wait until the virtual core reaches `celsius`.
+    ///
+    /// # Returns
+    ///
+    /// The core temperature (Celsius) when it was reached.
+    ///
+    /// # Errors
+    ///
+    /// See [`wait_for_condition`](Self::wait_for_condition).
+    pub async fn wait_until_core_at_least(&self, celsius: f64, timeout: Duration) -> Result<f64> {
+        self.wait_for_condition(timeout, |snapshot| {
+            snapshot
+                .virtual_temperatures
+                .core
+                .filter(|&core| core >= celsius)
+        })
+        .await
+    }
+
+    /// Wait until the virtual core drops below `celsius`, e.g. while chilling.
+    ///
+    /// # Returns
+    ///
+    /// The core temperature (Celsius) when it dropped below.
+    ///
+    /// # Errors
+    ///
+    /// See [`wait_for_condition`](Self::wait_for_condition).
+    pub async fn wait_until_core_below(&self, celsius: f64, timeout: Duration) -> Result<f64> {
+        self.wait_for_condition(timeout, |snapshot| {
+            snapshot
+                .virtual_temperatures
+                .core
+                .filter(|&core| core < celsius)
+        })
+        .await
+    }
+
+    /// Wait until the prediction is in `state`, e.g.
+    /// [`PredictionState::RemovalPredictionDone`].
+    ///
+    /// # Errors
+    ///
+    /// See [`wait_for_condition`](Self::wait_for_condition).
+    pub async fn wait_until_prediction_state(
+        &self,
+        state: PredictionState,
+        timeout: Duration,
+    ) -> Result<PredictionInfo> {
+        self.wait_for_condition(timeout, |snapshot| {
+            snapshot
+                .prediction
+                .clone()
+                .filter(|prediction| prediction.state == state)
+        })
+        .await
+    }
+
+    /// Wait until the probe reports the food safe to serve.
+    ///
+    /// # Errors
+    ///
+    /// See [`wait_for_condition`](Self::wait_for_condition).
+    pub async fn wait_until_food_safe(&self, timeout: Duration) -> Result<FoodSafeData> {
+        self.wait_for_condition(timeout, |snapshot| {
+            snapshot
+                .food_safe_data
+                .clone()
+                .filter(|food_safe| food_safe.is_safe())
+        })
+        .await
+    }
+
+    // === Logging ===
+
+    /// Get the minimum sequence number of logs on probe.
+    pub fn min_sequence_number(&self) -> u32 {
+        self.state.read().min_sequence
+    }
+
+    /// Get the maximum sequence number of logs on probe.
+    pub fn max_sequence_number(&self) -> u32 {
+        self.state.read().max_sequence
+    }
+
+    /// Get the percentage of logs synced.
+    pub fn percent_of_logs_synced(&self) -> f64 {
+        let state = self.state.read();
+        state
+            .temperature_log
+            .percent_synced(state.min_sequence, state.max_sequence)
+    }
+
+    /// Access the temperature log.
+    ///
+    /// Data points carry drift-corrected timestamps once the probe has
+    /// reported a new sequence number.
+    pub fn temperature_log(&self) -> TemperatureLog {
+        let state = self.state.read();
+        let mut log = state.temperature_log.clone();
+        log.apply_timestamps(&state.sequence_clock);
+        log
+    }
+
+    /// Attach a note to the current session, e.g. "wrapped in foil".
+    ///
+    /// The note is timestamped now and tied to the latest log sequence
+    /// number, and is kept with the [`temperature_log`](Self::temperature_log)
+    /// so it appears in its exports.
+    pub fn annotate(&self, text: impl Into<String>) -> Annotation {
+        let mut state = self.state.write();
+        let annotation = Annotation::new(text, chrono::Utc::now()).at_sequence(state.max_sequence);
+        state.temperature_log.add_annotation(annotation.clone());
+        annotation
+    }
+
+    /// Attach a prepared annotation to the current session.
+    pub fn add_annotation(&self, annotation: Annotation) {
+        self.state
+            .write()
+            .temperature_log
+            .add_annotation(annotation);
+    }
+
+    /// Get the current session's annotations, oldest first.
+    pub fn annotations(&self) -> Vec<Annotation> {
+        self.state.read().temperature_log.annotations.clone()
+    }
+
+    /// Get the clock mapping this probe's sequence numbers to host time.
+    pub fn sequence_clock(&self) -> SequenceClock {
+        self.state.read().sequence_clock.clone()
+    }
+
+    /// Get the drift-corrected wall-clock time a log sequence number was
+    /// sampled, or `None` until the probe has reported a new sequence number.
+    pub fn timestamp_for_sequence(&self, sequence: u32) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.state.read().sequence_clock.timestamp(sequence)
+    }
+
+    /// Get the log entry where the probe started food safe monitoring.
+    ///
+    /// `None` until food safe is running and the entry has been downloaded.
+    /// The entry is also marked with a "Food safe started" annotation in the
+    /// [`temperature_log`](Self::temperature_log) and its exports.
+    pub fn food_safe_start(&self) -> Option<LoggedDataPoint> {
+        let state = self.state.read();
+        let sequence = state.food_safe_data.as_ref()?.start_sequence()?;
+        let mut point = state.temperature_log.data_point(sequence)?.clone();
+        if let Some(timestamp) = state.sequence_clock.timestamp(sequence) {
+            point.timestamp = Some(timestamp);
+        }
+        Some(point)
+    }
+
+    /// Get when the probe started food safe monitoring, from the log
+    /// sequence number it reported.
+    pub fn food_safe_started_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let state = self.state.read();
+        let sequence = state.food_safe_data.as_ref()?.start_sequence()?;
+        state.sequence_clock.timestamp(sequence)
+    }
+
+    /// Add log records downloaded through an external transport.
+    ///
+    /// Each call is one chunk of the sync: records are checked against the
+    /// [sanity limits](Self::set_sanity_limits) and added to the
+    /// [`temperature_log`](Self::temperature_log), skipping any already
+    /// there, and progress is published to
+    /// [`subscribe_log_sync`](Self::subscribe_log_sync) if it moved by at
+    /// least [`SyncProgress::MIN_STEP_PERCENT`]. Once every record between
+    /// the probe's minimum and maximum sequence numbers is present, a
+    /// [`SyncComplete`] is published to
+    /// [`subscribe_log_sync_complete`](Self::subscribe_log_sync_complete).
+    pub fn ingest_log_points(&self, points: impl IntoIterator<Item = LoggedDataPoint>) {
+        let (progress, complete) = {
+            let mut state = self.state.write();
+            let (before, _) = state.log_sync_counts();
+            for point in points {
+                state.add_log_point(point);
+            }
+            let (records, total) = state.log_sync_counts();
+            state.log_sync.chunk(before, records, total, Instant::now())
+        };
+
+        if let Some(progress) = progress {
+            let _ = self.log_sync_tx.send(progress);
+        }
+        if let Some(complete) = complete {
+            debug!(
+                "Log sync of {} complete: {} records in {:?}",
+                self.serial_number_string(),
+                complete.total,
+                complete.duration
+            );
+            let _ = self.log_sync_complete_tx.send(complete);
+        }
+    }
+
+    /// Subscribe to log sync progress updates.
+    ///
+    /// Sent at most once per chunk of records, when the percentage synced
+    /// has moved by at least [`SyncProgress::MIN_STEP_PERCENT`], and when
+    /// the sync completes.
+    pub fn subscribe_log_sync(&self) -> broadcast::Receiver<SyncProgress> {
+        self.log_sync_tx.subscribe()
+    }
+
+    /// Register a callback for log sync progress.
+    pub fn on_log_sync_progress<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&SyncProgress) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "log_sync_progress",
+            self.log_sync_tx.subscribe(),
+            move |progress| callback(&progress),
+        )
+    }
+
+    /// Subscribe to log sync completions, with the totals of each sync.
+    pub fn subscribe_log_sync_complete(&self) -> broadcast::Receiver<SyncComplete> {
+        self.log_sync_complete_tx.subscribe()
+    }
+
+    /// Register a callback for log sync completions.
+    pub fn on_log_sync_complete<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&SyncComplete) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "log_sync_complete",
+            self.log_sync_complete_tx.subscribe(),
+            move |complete| callback(&complete),
+        )
+    }
+
+    // === Prediction ===
+
+    /// Get current prediction information.
+    pub fn prediction_info(&self) -> Option<PredictionInfo> {
+        self.state.read().prediction.clone()
+    }
+
+    /// Set prediction target temperature and mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TemperatureOutOfRange`] if the set point is below 0°C
+    /// or above the probe's
+    /// [`max_set_point`](ProbeCapabilities::max_set_point), rather than
+    /// sending a set point the probe would truncate.
+    pub async fn set_prediction(&self, mode: PredictionMode, set_point_celsius: f64) -> Result<()> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        // Per spec: Prediction Set Point = raw * 0.1°C, 10 bits wide
+        let set_point_raw = PredictionInfo::encode_set_point(set_point_celsius)?;
+        let message = build_set_prediction_request(mode.to_raw(), set_point_raw);
+
+        let command = ConfigCommand::SetPrediction {
+            mode,
+            set_point_celsius,
+        };
+        self.send_config(command, &message).await
+    }
+
+    /// Set prediction target temperature and mode, with the set point in
+    /// Fahrenheit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TemperatureOutOfRange`] if the set point is below
+    /// 32°F or above the probe's
+    /// [`max_set_point`](ProbeCapabilities::max_set_point).
+    pub async fn set_prediction_fahrenheit(
+        &self,
+        mode: PredictionMode,
+        set_point_fahrenheit: f64,
+    ) -> Result<()> {
+        self.set_prediction_temp(mode, Temperature::fahrenheit(set_point_fahrenheit))
+            .await
+    }
+
+    /// Set prediction target temperature and mode, with the set point in
+    /// either unit.
+    ///
+    /// ```rust,no_run
+    /// # use combustion_rust_ble::{PredictionMode, Probe, Temperature};
+    /// # async fn example(probe: &Probe) -> combustion_rust_ble::Result<()> {
+    /// probe
+    ///     .set_prediction_temp(PredictionMode::TimeToRemoval, Temperature::fahrenheit(203.0))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TemperatureOutOfRange`] if the set point is below
+    /// 0°C or above the probe's
+    /// [`max_set_point`](ProbeCapabilities::max_set_point).
+    pub async fn set_prediction_temp(
+        &self,
+        mode: PredictionMode,
+        set_point: Temperature,
+    ) -> Result<()> {
+        self.set_prediction(mode, set_point.as_celsius()).await
+    }
+
+    /// Cancel active prediction.
+    pub async fn cancel_prediction(&self) -> Result<()> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = build_cancel_prediction_request();
+        self.send_config(ConfigCommand::CancelPrediction, &message)
+            .await
+    }
+
+    /// Subscribe to prediction updates.
+    pub fn subscribe_predictions(&self) -> broadcast::Receiver<PredictionInfo> {
+        self.prediction_tx.subscribe()
+    }
+
+    /// Register a callback for prediction updates.
+    pub fn on_prediction_updated<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&PredictionInfo) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "prediction_updated",
+            self.prediction_tx.subscribe(),
+            move |prediction| callback(&prediction),
+        )
+    }
+
+    /// Subscribe to the core reaching the prediction set point.
+    ///
+    /// Each [`SetpointReached`] carries the crossing time interpolated
+    /// between status updates, for timers and notifications more precise
+    /// than the ~1 Hz status rate. The crossing is also annotated in the
+    /// temperature log.
+    pub fn subscribe_setpoint_reached(&self) -> broadcast::Receiver<SetpointReached> {
+        self.events.setpoint_tx.subscribe()
+    }
+
+    /// Register a callback for the core reaching the prediction set point.
+    pub fn on_setpoint_reached<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&SetpointReached) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "setpoint_reached",
+            self.events.setpoint_tx.subscribe(),
+            move |event| callback(&event),
+        )
+    }
+
+    // === Food Safety ===
+
+    /// Configure food safety monitoring with a product type (simplified mode).
+    ///
+    /// This uses simplified mode which applies predefined USDA temperature thresholds
+    /// based on the product type.
+    pub async fn configure_food_safe(&self, product: FoodSafeProduct) -> Result<()> {
+        let config = product.to_config(Serving::ServedImmediately);
+        self.configure_food_safe_with_config(config).await
+    }
+
+    /// Configure food safety monitoring with a product type and serving mode (simplified mode).
+    pub async fn configure_food_safe_with_serving(
+        &self,
+        product: FoodSafeProduct,
+        serving: Serving,
+    ) -> Result<()> {
+        let config = product.to_config(serving);
+        self.configure_food_safe_with_config(config).await
+    }
+
+    /// Configure food safety monitoring with full configuration (integrated mode).
+    ///
+    /// This allows specifying custom parameters for time-temperature integration
+    /// including Z-value, D-value, reference temperature, and target log reduction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFoodSafeConfig`] if the configuration fails
+    /// [`FoodSafeConfig::validate`].
+    pub async fn configure_food_safe_with_config(&self, config: FoodSafeConfig) -> Result<()> {
+        let config_bytes = config.try_to_bytes()?;
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = build_configure_food_safe_request(&config_bytes);
+        self.send_config(ConfigCommand::ConfigureFoodSafe(config.clone()), &message)
+            .await?;
+
+        let mut state = self.state.write();
+        state.food_safe_data = Some(FoodSafeData::with_config(config));
+        state.status_decoder.reset();
+        state.cooling = None;
+        state.food_safe_start_marked = None;
+
+        Ok(())
+    }
+
+    /// Configure food safety monitoring with integrated mode for a product.
+    ///
+    /// Integrated mode calculates log reduction based on time-temperature integration.
+    pub async fn configure_food_safe_integrated(
+        &self,
+        product: FoodSafeProduct,
+        serving: Serving,
+    ) -> Result<()> {
+        let config = product.to_integrated_config(serving);
+        self.configure_food_safe_with_config(config).await
+    }
+
+    /// Reset food safety calculations.
+    pub async fn reset_food_safe(&self) -> Result<()> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = build_reset_food_safe_request();
+        self.send_config(ConfigCommand::ResetFoodSafe, &message)
+            .await?;
+
+        let mut state = self.state.write();
+        state.food_safe_data = None;
+        state.status_decoder.reset();
+        state.cooling = None;
+        state.food_safe_start_marked = None;
+
+        Ok(())
+    }
+
+    /// Get current food safety data.
+    pub fn food_safe_data(&self) -> Option<FoodSafeData> {
+        self.state.read().food_safe_data.clone()
+    }
+
+    /// Estimate how long until the food is safe, from the core temperature
+    /// and the prediction engine's trajectory.
+    ///
+    /// See [`FoodSafeData::estimated_time_to_safe`]. Returns `None` without
+    /// food safety data or a core reading.
+    pub fn estimated_time_to_safe(&self) -> Option<Duration> {
+        let state = self.state.read();
+        let core = state.virtual_temperatures.core?;
+        state
+            .food_safe_data
+            .as_ref()?
+            .estimated_time_to_safe(core, state.prediction.as_ref())
+    }
+
+    // === HACCP Logging ===
+
+    /// Enable HACCP instant-read logging.
+    ///
+    /// While enabled, each stabilized reading taken in instant-read mode is
+    /// captured as a [`HaccpRecord`] in the probe's HACCP log.
+    pub fn enable_haccp_logging(&self) {
+        let mut state = self.state.write();
+        state.haccp_enabled = true;
+        state.haccp_recorder.reset();
+    }
+
+    /// Disable HACCP instant-read logging. Existing records are kept.
+    pub fn disable_haccp_logging(&self) {
+        self.state.write().haccp_enabled = false;
+    }
+
+    /// Check if HACCP instant-read logging is enabled.
+    pub fn is_haccp_logging_enabled(&self) -> bool {
+        self.state.read().haccp_enabled
+    }
+
+    /// Set the item label attached to subsequent HACCP records.
+    pub fn set_haccp_label(&self, label: Option<String>) {
+        self.state.write().haccp_label = label;
+    }
+
+    /// Get the current HACCP item label.
+    pub fn haccp_label(&self) -> Option<String> {
+        self.state.read().haccp_label.clone()
+    }
+
+    /// Get a copy of the HACCP log.
+    pub fn haccp_log(&self) -> HaccpLog {
+        self.state.read().haccp_log.clone()
+    }
+
+    /// Clear all captured HACCP records.
+    pub fn clear_haccp_log(&self) {
+        self.state.write().haccp_log.clear();
+    }
+
+    // === Battery & Status ===
+
+    /// Get current battery status.
+    pub fn battery_status(&self) -> BatteryStatus {
+        self.state.read().battery_status
+    }
+
+    /// Get overheating information.
+    pub fn overheating(&self) -> Overheating {
+        self.state.read().overheating
+    }
+
+    /// Get current operational mode.
+    pub fn mode(&self) -> ProbeMode {
+        self.state.read().mode
+    }
+
+    /// Get how long the probe has been in its current mode, or `None` if no
+    /// mode has been reported yet.
+    pub fn time_in_mode(&self) -> Option<Duration> {
+        self.state
+            .read()
+            .mode_since
+            .map(|(since, _)| since.elapsed())
+    }
+
+    /// Get when the current mode was first reported.
+    pub fn mode_since(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.state.read().mode_since.map(|(_, at)| at)
+    }
+
+    /// Subscribe to mode changes, e.g. the probe entering
+    /// [`ProbeMode::Error`].
+    pub fn subscribe_mode_changes(&self) -> broadcast::Receiver<ModeChange> {
+        self.events.mode_tx.subscribe()
+    }
+
+    /// Register a callback for mode changes.
+    pub fn on_mode_changed<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&ModeChange) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "mode_changed",
+            self.events.mode_tx.subscribe(),
+            move |change| callback(&change),
+        )
+    }
+
+    /// Subscribe to instant read readings.
+    ///
+    /// Sent for every reading while the probe is in
+    /// [`ProbeMode::InstantRead`], for UIs that show the reading live as the
+    /// probe goes into the food. While connected, readings come from status
+    /// notifications, which arrive more often than advertisements; otherwise
+    /// from advertisements. Each reading carries its source and the time
+    /// since the previous one.
+    pub fn subscribe_instant_reads(&self) -> broadcast::Receiver<InstantReading> {
+        self.events.instant_read_tx.subscribe()
+    }
+
+    /// Register a callback for instant read readings.
+    pub fn on_instant_read<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&InstantReading) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "instant_read",
+            self.events.instant_read_tx.subscribe(),
+            move |reading| callback(&reading),
+        )
+    }
+
+    /// Get diagnostics for a probe in [`ProbeMode::Error`], or `None` if
+    /// the probe is not faulted.
+    ///
+    /// While faulted the probe's readings are not valid: every sensor is
+    /// inactive and the virtual temperatures are `None`. See
+    /// [`FaultInfo::guidance`] for advice to show the user.
+    pub fn fault_info(&self) -> Option<FaultInfo> {
+        self.state.read().fault
+    }
+
+    /// Subscribe to the probe entering error mode.
+    pub fn subscribe_faults(&self) -> broadcast::Receiver<ProbeFaulted> {
+        self.events.fault_tx.subscribe()
+    }
+
+    /// Register a callback for the probe entering error mode.
+    pub fn on_faulted<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&ProbeFaulted) + Send + Sync + 'static,
+    {
+        self.callbacks
+            .spawn("faulted", self.events.fault_tx.subscribe(), move |fault| {
+                callback(&fault)
+            })
+    }
+
+    /// Use `profiles` to pick the model for estimating battery life.
+    ///
+    /// The model is picked by the hardware and firmware revisions read when
+    /// the probe connects.
+    pub fn set_battery_profiles(&self, profiles: BatteryProfiles) {
+        self.state.write().battery_profiles = profiles;
+    }
+
+    /// Get the battery model in effect for this probe.
+    pub fn battery_model(&self) -> BatteryModel {
+        self.state.read().battery_model()
+    }
+
+    /// Get how long the probe has been seen running since its last charge.
+    ///
+    /// Counted while the probe sends updates, and reset when its low battery
+    /// flag clears after charging.
+    pub fn battery_on_time(&self) -> Duration {
+        self.state.read().battery.on_time()
+    }
+
+    /// Set how long the probe has been running since its last charge, e.g.
+    /// restored from an earlier run, or zero after charging it.
+    pub fn set_battery_on_time(&self, on_time: Duration) {
+        self.state.write().battery.set_on_time(on_time);
+    }
+
+    /// Estimate the probe's remaining running time from its
+    /// [battery model](Self::battery_model).
+    pub fn estimated_battery_remaining(&self) -> Duration {
+        let state = self.state.read();
+        state.battery.estimate_remaining(&state.battery_model())
+    }
+
+    /// Subscribe to warnings that the battery will run out soon.
+    ///
+    /// Raised once per charge, when the estimated running time left drops
+    /// to the model's [`warning_lead`](BatteryModel::warning_lead) or the
+    /// probe reports a low battery, whichever comes first.
+    pub fn subscribe_battery_warnings(&self) -> broadcast::Receiver<BatteryWarning> {
+        self.events.battery_tx.subscribe()
+    }
+
+    /// Register a callback for battery warnings.
+    pub fn on_battery_warning<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&BatteryWarning) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "battery_warning",
+            self.events.battery_tx.subscribe(),
+            move |warning| callback(&warning),
+        )
+    }
+
+    /// Subscribe to configuration changed by other clients.
+    ///
+    /// When another client, such as the official app connected through
+    /// MeatNet, changes the prediction, alarms or power mode, the change is
+    /// noticed in the next status notification. The cached configuration and
+    /// any [`active_cook`](Self::active_cook) follow the probe, so later
+    /// writes from this client build on the change instead of undoing it.
+    pub fn subscribe_config_changes(&self) -> broadcast::Receiver<ConfigChangedExternally> {
+        self.events.config_changed_tx.subscribe()
+    }
+
+    /// Register a callback for configuration changed by other clients.
+    pub fn on_config_changed_externally<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&ConfigChangedExternally) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "config_changed_externally",
+            self.events.config_changed_tx.subscribe(),
+            move |change| callback(&change),
+        )
+    }
+
+    // === Power Mode & Preferences ===
+
+    /// Get current power mode.
+    ///
+    /// Returns `None` if the probe hasn't sent thermometer preferences yet.
+    pub fn power_mode(&self) -> Option<PowerMode> {
+        self.state
+            .read()
+            .thermometer_preferences
+            .map(|p| p.power_mode)
+    }
+
+    /// Check if the probe is in always-on mode.
+    pub fn is_always_on(&self) -> bool {
+        self.state
+            .read()
+            .thermometer_preferences
+            .map(|p| p.is_always_on())
+            .unwrap_or(false)
+    }
+
+    /// Get thermometer preferences.
+    pub fn thermometer_preferences(&self) -> Option<ThermometerPreferences> {
+        self.state.read().thermometer_preferences
+    }
+
+    /// Set the power mode.
+    ///
+    /// - `PowerMode::Normal`: Probe will auto power-off when placed in charger.
+    /// - `PowerMode::AlwaysOn`: Probe stays powered even in charger.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] for a
+    /// [`PowerMode::Unknown`] value that does not fit in the 2-bit field.
+    pub async fn set_power_mode(&self, mode: PowerMode) -> Result<()> {
+        if mode.to_raw() > ThermometerPreferences::POWER_MODE_MASK {
+            return Err(Error::InvalidParameter {
+                name: "mode".to_string(),
+                value: mode.to_raw().to_string(),
+            });
+        }
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = build_set_power_mode_request(mode.to_raw());
+        self.send_config(ConfigCommand::SetPowerMode(mode), &message)
+            .await?;
+
+        // Update local state, keeping bits this library does not interpret
+        let mut state = self.state.write();
+        let mut preferences = state.thermometer_preferences.unwrap_or_default();
+        preferences.set_power_mode(mode);
+        state.thermometer_preferences = Some(preferences);
+        state.status_decoder.reset();
+
+        Ok(())
+    }
+
+    /// Write thermometer preferences.
+    ///
+    /// Every field the specification allows writing is sent to the probe;
+    /// currently that is only the power mode. Unknown bits have no write
+    /// command and keep the values last reported by the probe.
+    ///
+    /// The probe reports its preferences in every status notification, so
+    /// [`thermometer_preferences`](Self::thermometer_preferences) reflects
+    /// the device's values rather than only what was written.
+    pub async fn set_thermometer_preferences(
+        &self,
+        preferences: ThermometerPreferences,
+    ) -> Result<()> {
+        self.set_power_mode(preferences.power_mode).await
+    }
+
+    /// Reset the thermometer to factory defaults.
+    ///
+    /// This will reset all settings including probe ID, color, alarms, etc.
+    pub async fn reset_thermometer(&self) -> Result<()> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = build_reset_thermometer_request();
+        self.send_config(ConfigCommand::ResetThermometer, &message)
+            .await
+    }
+
+    // === Temperature Alarms ===
+
+    /// Get current alarm configuration.
+    ///
+    /// Returns `None` if the probe hasn't sent alarm status yet.
+    pub fn alarm_config(&self) -> Option<AlarmConfig> {
+        self.state.read().alarm_config.clone()
+    }
+
+    /// Check if any alarm is currently triggered.
+    pub fn any_alarm_tripped(&self) -> bool {
+        self.state
+            .read()
+            .alarm_config
+            .as_ref()
+            .map(|c| c.any_tripped())
+            .unwrap_or(false)
+    }
+
+    /// Check if any alarm is currently sounding.
+    pub fn any_alarm_alarming(&self) -> bool {
+        self.state
+            .read()
+            .alarm_config
+            .as_ref()
+            .map(|c| c.any_alarming())
+            .unwrap_or(false)
+    }
+
+    /// Check if any alarm is enabled.
+    pub fn any_alarm_enabled(&self) -> bool {
+        self.state
+            .read()
+            .alarm_config
+            .as_ref()
+            .map(|c| c.any_enabled())
+            .unwrap_or(false)
+    }
+
+    /// Set temperature alarms.
+    ///
+    /// Configures high and low temperature alarms for all sensors (T1-T8) and
+    /// virtual sensors (Core, Surface, Ambient).
+    ///
+    /// See `AlarmConfig` for how to configure individual alarms.
+    ///
+    /// Pseudo-probes from an
+    /// [`ExternalSensorSource`](crate::external::ExternalSensorSource) keep
+    /// the alarms in the library and evaluate them on each reading, at any
+    /// temperature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if an alarm temperature cannot be
+    /// encoded; see [`AlarmConfig::try_to_bytes`]. For pseudo-probes, only
+    /// temperatures that are not a number are rejected.
+    pub async fn set_alarms(&self, config: &AlarmConfig) -> Result<()> {
+        if self.external {
+            return self.set_local_alarms(config);
+        }
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let config_bytes = config.try_to_bytes()?;
+        let message = build_set_high_low_alarms_request(&config_bytes);
+        self.send_config(ConfigCommand::SetAlarms(Box::new(config.clone())), &message)
+            .await?;
+
+        // Update local state
+        let mut state = self.state.write();
+        state.alarm_config = Some(config.clone());
+        state.status_decoder.reset();
+
+        Ok(())
+    }
+
+    /// Keep alarms for a pseudo-probe, which evaluates them itself.
+    fn set_local_alarms(&self, config: &AlarmConfig) -> Result<()> {
+        let mut config = config.clone();
+        for alarm in config
+            .high_alarms
+            .iter_mut()
+            .chain(config.low_alarms.iter_mut())
+        {
+            if !alarm.temperature.is_finite() {
+                return Err(Error::InvalidParameter {
+                    name: "temperature".to_string(),
+                    value: alarm.temperature.to_string(),
+                });
+            }
+            alarm.tripped = false;
+            alarm.alarming = false;
+        }
+
+        let mut state = self.state.write();
+        state.alarm_config = Some(config);
+        state.evaluate_alarms();
+        self.events.publish(state.raise_events());
+        Ok(())
+    }
+
+    /// Silence any currently sounding alarms.
+    pub async fn silence_alarms(&self) -> Result<()> {
+        if self.external {
+            if let Some(alarms) = &mut self.state.write().alarm_config {
+                alarms.silence();
+            }
+            return Ok(());
+        }
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = build_silence_alarms_request();
+        self.send_config(ConfigCommand::SilenceAlarms, &message)
+            .await
+    }
+
+    /// Set a high temperature alarm for the core (virtual) sensor.
+    ///
+    /// This is a convenience method that creates an alarm config with just
+    /// the core high alarm set.
+    pub async fn set_core_high_alarm(&self, temperature_celsius: f64) -> Result<()> {
+        let mut config = self.alarm_config().unwrap_or_default();
+        config.set_core_high_alarm(temperature_celsius, true);
+        self.set_alarms(&config).await
+    }
+
+    /// Set a low temperature alarm for the core (virtual) sensor.
+    ///
+    /// This is a convenience method that creates an alarm config with just
+    /// the core low alarm set.
+    pub async fn set_core_low_alarm(&self, temperature_celsius: f64) -> Result<()> {
+        let mut config = self.alarm_config().unwrap_or_default();
+        config.set_core_low_alarm(temperature_celsius, true);
+        self.set_alarms(&config).await
+    }
+
+    /// Disable all alarms.
+    pub async fn disable_all_alarms(&self) -> Result<()> {
+        let config = AlarmConfig::new();
+        self.set_alarms(&config).await
+    }
+
+    /// Set a core high alarm at the set point when a removal prediction
+    /// completes.
+    ///
+    /// While armed, each time the prediction enters
+    /// [`PredictionState::RemovalPredictionDone`] the probe's core high alarm
+    /// is enabled at the prediction set point, keeping the other alarms. The
+    /// probe then sounds the alarm itself even if the BLE connection drops
+    /// when the food is ready to come off the heat. The alarm is not set
+    /// until the probe has reported its alarm settings, so they aren't
+    /// overwritten, and each write is recorded in the
+    /// [`command_history`](Self::command_history). Must be called within a
+    /// Tokio runtime when arming.
+    pub fn arm_removal_alarm(&self, armed: bool) {
+        let mut responder = self.removal_alarm_responder.lock();
+        if let Some(previous) = responder.take() {
+            previous.abort();
+        }
+        if !armed {
+            return;
+        }
+
+        let mut rx = self.prediction_tx.subscribe();
+        let state = self.state.clone();
+        let link = self.link.clone();
+        let serial_number = self.serial_number_string();
+        *responder = Some(crate::runtime::spawn(async move {
+            let mut was_done = false;
+            loop {
+                let prediction = match rx.recv().await {
+                    Ok(prediction) => prediction,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let done = prediction.state == PredictionState::RemovalPredictionDone;
+                let entered = done && !was_done;
+                was_done = done;
+                if !entered {
+                    continue;
+                }
+
+                let set_point = prediction.set_point_temperature;
+                // Writing defaults would clear the user's other alarms
+                let Some(mut config) = state.read().alarm_config.clone() else {
+                    warn!(
+                        "Not setting removal alarm on {}: its alarm settings are not known yet",
+                        serial_number
                     );
+                    continue;
+                };
+                let core_high = config.core_high_alarm();
+                if core_high.set && (core_high.temperature - set_point).abs() < 0.05 {
+                    continue;
+                }
+                config.set_core_high_alarm(set_point, true);
+
+                info!(
+                    "Probe {} removal prediction done, setting core alarm at {:.1}°C",
+                    serial_number, set_point
+                );
+                let result = match config.try_to_bytes() {
+                    Ok(bytes) => {
+                        let message = build_set_high_low_alarms_request(&bytes);
+                        let command = ConfigCommand::SetAlarms(Box::new(config.clone()));
+                        send_link_config(&link, &state, command, &message).await
+                    }
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => {
+                        let mut state = state.write();
+                        state.alarm_config = Some(config);
+                        state.status_decoder.reset();
+                    }
+                    Err(e) => warn!("Failed to set removal alarm on {}: {}", serial_number, e),
+                }
+            }
+        }));
+    }
+
+    /// Check if the removal alarm is armed; see
+    /// [`arm_removal_alarm`](Self::arm_removal_alarm).
+    pub fn is_removal_alarm_armed(&self) -> bool {
+        self.removal_alarm_responder.lock().is_some()
+    }
+
+    /// Subscribe to alarms: temperature alarms starting to sound and timers
+    /// firing.
+    pub fn subscribe_alarms(&self) -> broadcast::Receiver<AlarmEvent> {
+        self.events.alarm_tx.subscribe()
+    }
+
+    /// Register a callback for alarms.
+    pub fn on_alarm<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&AlarmEvent) + Send + Sync + 'static,
+    {
+        self.callbacks
+            .spawn("alarm", self.events.alarm_tx.subscribe(), move |alarm| {
+                callback(&alarm)
+            })
+    }
+
+    // === Timers ===
+
+    /// Schedule a timer firing after `delay`, e.g. "alert me in 45 minutes".
+    ///
+    /// The timer belongs to the probe's current cook session and is
+    /// published on [`subscribe_alarms`](Self::subscribe_alarms) when it
+    /// fires. Must be called from within a Tokio runtime.
+    pub fn schedule_timer_after(&self, label: impl Into<String>, delay: Duration) -> TimerAlarm {
+        let fires_at =
+            chrono::Utc::now() + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX);
+        self.timers.schedule_at(label.into(), fires_at)
+    }
 
-                    match ProbeStatus::parse(&event.data) {
-                        Ok(status) => {
-                            debug!(
-                                "Parsed status: prediction={:?}, food_safe_status={:?}",
-                                status.prediction.as_ref().map(|p| format!(
-                                    "state={:?}, mode={:?}, setpoint={:.1}",
-                                    p.state, p.mode, p.set_point_temperature
-                                )),
-                                status.food_safe_status.as_ref().map(|fs| format!(
-                                    "state={:?}, log_red={:.1}, secs={}",
-                                    fs.state, fs.log_reduction, fs.seconds_above_threshold
-                                ))
-                            );
+    /// Schedule a timer firing at `at`, e.g. "alert at 6:30 AM to wrap"
+    /// with [`next_local_time`](crate::timers::next_local_time).
+    ///
+    /// A time in the past fires straight away. The timer goes by the wall
+    /// clock, so it still fires on time, within 30 seconds, if the host
+    /// sleeps or its clock is set while waiting. Must be called from within
+    /// a Tokio runtime.
+    pub fn schedule_timer_at(
+        &self,
+        label: impl Into<String>,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> TimerAlarm {
+        self.timers.schedule_at(label.into(), at)
+    }
 
-                            let mut state = state.write();
-                            let now = Instant::now();
-
-                            state.temperatures = status.temperatures.clone();
-                            state.virtual_temperatures = status.virtual_temperatures.clone();
-                            state.battery_status = status.battery_status;
-                            state.mode = status.mode;
-                            state.overheating = status.overheating;
-                            state.min_sequence = status.min_sequence_number;
-                            state.max_sequence = status.max_sequence_number;
-                            state.prediction = status.prediction.clone();
-
-                            // Update thermometer preferences and alarm config from status
-                            state.thermometer_preferences = status.thermometer_preferences;
-                            state.alarm_config = status.alarm_config.clone();
-
-                            // Update food safe data from status
-                            // Handle both local and external (e.g., iOS app) food safe configuration
-                            match (&status.food_safe_config, &status.food_safe_status) {
-                                (Some(config), Some(fs_status)) => {
-                                    if let Some(ref mut food_safe_data) = state.food_safe_data {
-                                        // Update existing data with new status
-                                        food_safe_data.update_from_status(fs_status.clone());
-                                        // Also update config in case it changed externally
-                                        food_safe_data.update_config(config.clone());
-                                    } else {
-                                        // Create new food safe data from external config/status
-                                        state.food_safe_data = Some(FoodSafeData::from_config_and_status(
-                                            config.clone(),
-                                            fs_status.clone(),
-                                        ));
-                                    }
-                                }
-                                (Some(config), None) => {
-                                    // Config but no status yet - create data with config only
-                                    if state.food_safe_data.is_none() {
-                                        state.food_safe_data = Some(FoodSafeData::with_config(config.clone()));
-                                    } else if let Some(ref mut food_safe_data) = state.food_safe_data {
-                                        food_safe_data.update_config(config.clone());
-                                    }
-                                }
-                                (None, Some(fs_status)) => {
-                                    // Status but no config - update if we have existing data
-                                    if let Some(ref mut food_safe_data) = state.food_safe_data {
-                                        food_safe_data.update_from_status(fs_status.clone());
-                                    }
-                                }
-                                (None, None) => {
-                                    // No food safe data - clear if not configured locally
-                                    // Don't clear here as it might have been set locally
-                                }
-                            }
+    /// Cancel a timer, returning whether it was scheduled.
+    pub fn cancel_timer(&self, id: uuid::Uuid) -> bool {
+        self.timers.cancel(id)
+    }
+
+    /// List the scheduled timers, soonest first.
+    pub fn timers(&self) -> Vec<TimerAlarm> {
+        self.timers.list()
+    }
 
-                            state.last_update = now;
+    /// Keep this probe's timers in `store`, so they survive a restart.
+    ///
+    /// Timers the store holds for this probe are scheduled, firing straight
+    /// away if they came due while the application was not running. Must be
+    /// called from within a Tokio runtime.
+    #[cfg(feature = "persistence")]
+    pub fn set_timer_store(&self, store: Arc<crate::persistence::TimerStore>) {
+        self.timers.set_store(store);
+    }
 
-                            // Reset stale flag
-                            is_stale.store(false, Ordering::SeqCst);
+    // === Overheat Protection ===
 
-                            // Send temperature update
-                            let _ = temperature_tx.send(TemperatureUpdate {
-                                temperatures: state.temperatures.clone(),
-                                virtual_temperatures: state.virtual_temperatures.clone(),
-                            });
+    /// Apply an overheat policy to this probe's readings.
+    ///
+    /// Each time a sensor group starts overheating, an [`OverheatEvent`] is
+    /// published to [`subscribe_overheat`](Self::subscribe_overheat),
+    /// recorded in [`overheat_events`](Self::overheat_events), and, if the
+    /// policy asks for it, the probe's alarms are silenced, which is recorded
+    /// in the [`command_history`](Self::command_history). Replaces any
+    /// previous policy. Must be called within a Tokio runtime.
+    pub fn set_overheat_policy(&self, policy: OverheatPolicy) {
+        let silence_alarms = policy.silence_alarms;
+        self.state.write().overheat_monitor = Some(OverheatMonitor::new(policy));
+
+        let mut responder = self.overheat_responder.lock();
+        if let Some(previous) = responder.take() {
+            previous.abort();
+        }
+        if !silence_alarms {
+            return;
+        }
 
-                            // Send prediction update if available
-                            if let Some(ref prediction) = state.prediction {
-                                let _ = prediction_tx.send(prediction.clone());
+        let mut rx = self.events.overheat_tx.subscribe();
+        let state = self.state.clone();
+        let link = self.link.clone();
+        let external = self.external;
+        let serial_number = self.serial_number_string();
+        *responder = Some(crate::runtime::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        warn!(
+                            "Probe {} {} sensors overheating, silencing alarms",
+                            serial_number,
+                            event.group.name()
+                        );
+                        // External sources have no firmware alarms to write to
+                        if external {
+                            if let Some(alarms) = &mut state.write().alarm_config {
+                                alarms.silence();
                             }
+                            continue;
                         }
-                        Err(e) => {
-                            debug!("Failed to parse status notification: {:?}", e);
+                        let message = build_silence_alarms_request();
+                        let command = ConfigCommand::SilenceAlarms;
+                        if let Err(e) = send_link_config(&link, &state, command, &message).await {
+                            warn!("Failed to silence alarms on {}: {}", serial_number, e);
                         }
                     }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
                 }
             }
-            debug!("Status notification handler stopped");
-        });
+        }));
     }
 
-    /// Disconnect from the probe.
-    pub async fn disconnect(&self) -> Result<()> {
-        info!("Disconnecting from probe {}", self.serial_number_string());
-
-        // Stop notifications
-        if let Some(ref handler) = *self.characteristics.read() {
-            handler.stop_notifications().await;
+    /// Stop applying the overheat policy.
+    pub fn clear_overheat_policy(&self) {
+        self.state.write().overheat_monitor = None;
+        if let Some(responder) = self.overheat_responder.lock().take() {
+            responder.abort();
         }
+    }
 
-        self.connection.disconnect().await?;
-        *self.characteristics.write() = None;
+    /// Get the overheat policy being applied, if any.
+    pub fn overheat_policy(&self) -> Option<OverheatPolicy> {
+        self.state
+            .read()
+            .overheat_monitor
+            .as_ref()
+            .map(|monitor| monitor.policy().clone())
+    }
 
-        Ok(())
+    /// Get the overheat events raised so far, oldest first.
+    pub fn overheat_events(&self) -> Vec<OverheatEvent> {
+        self.state.read().overheat_events.clone()
     }
 
-    /// Check if we're maintaining a connection.
-    pub fn is_maintaining_connection(&self) -> bool {
-        self.connection.is_maintaining_connection()
+    /// Subscribe to overheat events.
+    pub fn subscribe_overheat(&self) -> broadcast::Receiver<OverheatEvent> {
+        self.events.overheat_tx.subscribe()
     }
 
-    /// Check if the probe is stale (no data received recently).
-    pub fn is_stale(&self) -> bool {
-        let elapsed = self.state.read().last_update.elapsed();
-        let is_stale = elapsed > self.stale_timeout;
-        self.is_stale.store(is_stale, Ordering::SeqCst);
-        is_stale
+    /// Register a callback for overheat events.
+    pub fn on_overheat<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&OverheatEvent) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "overheat",
+            self.events.overheat_tx.subscribe(),
+            move |event| callback(&event),
+        )
     }
 
-    // === Temperature Data ===
+    // === Cooling ===
 
-    /// Get current temperatures from all 8 sensors.
-    pub fn current_temperatures(&self) -> ProbeTemperatures {
-        self.state.read().temperatures.clone()
+    /// Set the cooling guideline applied to cooked and chilled food.
+    ///
+    /// When food safety is configured with [`Serving::CookedAndChilled`] and
+    /// reports the food safe, the host starts tracking the core temperature
+    /// against this profile and publishes [`CoolingEvent`]s. Defaults to
+    /// [`CoolingProfile::food_code`]. Takes effect for the next cooling
+    /// phase; use [`monitor_cooling`](Self::monitor_cooling) to restart one
+    /// in progress.
+    pub fn set_cooling_profile(&self, profile: CoolingProfile) {
+        self.state.write().cooling_profile = profile;
     }
 
-    /// Get virtual temperatures (core, surface, ambient).
-    pub fn virtual_temperatures(&self) -> VirtualTemperatures {
-        self.state.read().virtual_temperatures.clone()
+    /// Start cooling-phase monitoring now with `profile`, whatever the food
+    /// safety state.
+    ///
+    /// A stage still running at its deadline fails then, even if no reading
+    /// arrives.
+    pub fn monitor_cooling(&self, profile: CoolingProfile) {
+        let mut state = self.state.write();
+        state.cooling = Some(CoolingMonitor::new(profile));
+        state.cooling_cancelled = false;
+        self.events.arm_cooling_timer(None);
     }
 
-    /// Subscribe to temperature updates.
-    pub fn subscribe_temperatures(&self) -> broadcast::Receiver<TemperatureUpdate> {
-        self.temperature_tx.subscribe()
+    /// Stop cooling-phase monitoring.
+    ///
+    /// Monitoring stays off for the current cook; it starts again
+    /// automatically once food safety is reset or reconfigured and cooked
+    /// and chilled food is reported safe, or with
+    /// [`monitor_cooling`](Self::monitor_cooling).
+    pub fn cancel_cooling(&self) {
+        let mut state = self.state.write();
+        state.cooling = None;
+        state.cooling_cancelled = true;
+        self.events.arm_cooling_timer(None);
     }
 
-    /// Register a callback for temperature updates.
-    pub fn on_temperatures_updated<F>(&self, callback: F) -> CallbackHandle
+    /// Get the cooling-phase monitor, if cooling monitoring has started.
+    pub fn cooling_monitor(&self) -> Option<CoolingMonitor> {
+        self.state.read().cooling.clone()
+    }
+
+    // === Searing ===
+
+    /// Detect searing from the surface temperature with `thresholds`.
+    ///
+    /// Publishes a [`SearingEvent`] when the surface starts or stops rising
+    /// like a sear, e.g. to time the flip of a reverse sear, and annotates
+    /// the temperature log. Replaces any previous detector.
+    pub fn detect_searing(&self, thresholds: SearingThresholds) {
+        self.state.write().searing = Some(SearingMonitor::new(thresholds));
+    }
+
+    /// Stop detecting searing.
+    pub fn stop_searing_detection(&self) {
+        self.state.write().searing = None;
+    }
+
+    /// Get the searing detector, if detection is enabled.
+    pub fn searing_monitor(&self) -> Option<SearingMonitor> {
+        self.state.read().searing.clone()
+    }
+
+    /// Check if the surface is currently searing.
+    pub fn is_searing(&self) -> bool {
+        self.state
+            .read()
+            .searing
+            .as_ref()
+            .is_some_and(SearingMonitor::is_searing)
+    }
+
+    /// Subscribe to searing events.
+    pub fn subscribe_searing(&self) -> broadcast::Receiver<SearingEvent> {
+        self.events.searing_tx.subscribe()
+    }
+
+    /// Register a callback for searing events.
+    pub fn on_searing_event<F>(&self, callback: F) -> CallbackHandle
     where
-        F: Fn(&ProbeTemperatures, &VirtualTemperatures) + Send + Sync + 'static,
+        F: Fn(&SearingEvent) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "searing_event",
+            self.events.searing_tx.subscribe(),
+            move |event| callback(&event),
+        )
+    }
+
+    /// Subscribe to cooling-phase events.
+    pub fn subscribe_cooling(&self) -> broadcast::Receiver<CoolingEvent> {
+        self.events.cooling_tx.subscribe()
+    }
+
+    /// Register a callback for cooling-phase events.
+    pub fn on_cooling_event<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&CoolingEvent) + Send + Sync + 'static,
     {
-        let callback_id = self.callback_counter.fetch_add(1, Ordering::SeqCst);
-        let mut rx = self.temperature_tx.subscribe();
+        self.callbacks.spawn(
+            "cooling_event",
+            self.events.cooling_tx.subscribe(),
+            move |event| callback(&event),
+        )
+    }
+
+    // === Guided Cook ===
+
+    /// Start a guided cook.
+    ///
+    /// Validates `spec`, then sets the prediction, food safety configuration,
+    /// and alarms it asks for, in that order. The prediction set point is the
+    /// spec's [`removal_target`](CookSpec::removal_target), and later
+    /// [`PredictionInfo`]s report the carryover so both the remove-at and
+    /// serve-at temperatures are available. If any step fails, the steps
+    /// already attempted (including the failing one) are restored to their
+    /// previous settings, or cleared if there were none, and the original
+    /// error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] or [`Error::InvalidFoodSafeConfig`]
+    /// if `spec` is invalid, [`Error::NotConnected`] if the probe is not
+    /// connected, or the error of the step that failed.
+    pub async fn start_cook(&self, spec: CookSpec) -> Result<()> {
+        spec.validate()?;
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
 
-        let handle = tokio::spawn(async move {
-            while let Ok(update) = rx.recv().await {
-                callback(&update.temperatures, &update.virtual_temperatures);
+        let previous = PreviousCookSettings {
+            prediction: self
+                .prediction_info()
+                .filter(|p| p.mode != PredictionMode::None)
+                .map(|p| (p.mode, p.set_point_temperature)),
+            food_safe: self.food_safe_data().and_then(|d| d.config),
+            alarms: self.alarm_config(),
+        };
+
+        let mut attempted = Vec::new();
+        for step in [CookStep::Prediction, CookStep::FoodSafe, CookStep::Alarms] {
+            let result = match step {
+                CookStep::Prediction if spec.prediction_mode != PredictionMode::None => {
+                    self.set_prediction(spec.prediction_mode, spec.removal_target())
+                        .await
+                }
+                CookStep::FoodSafe => match &spec.food_safe {
+                    Some(config) => self.configure_food_safe_with_config(config.clone()).await,
+                    None => continue,
+                },
+                CookStep::Alarms => match &spec.resolved_alarms() {
+                    Some(alarms) => self.set_alarms(alarms).await,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            attempted.push(step);
+
+            if let Err(e) = result {
+                warn!(
+                    "Starting cook on {} failed at {:?}, rolling back: {}",
+                    self.serial_number_string(),
+                    step,
+                    e
+                );
+                for step in attempted.into_iter().rev() {
+                    if let Err(undo) = self.restore_cook_step(step, &previous).await {
+                        warn!("Failed to roll back {:?}: {}", step, undo);
+                    }
+                }
+                return Err(e);
             }
-        });
+        }
 
-        CallbackHandle::new(callback_id, move || {
-            handle.abort();
-        })
+        self.state.write().active_cook = Some(spec);
+        Ok(())
     }
 
-    // === Logging ===
+    /// End the current cook.
+    ///
+    /// Cancels the prediction, resets food safety, and disables all alarms.
+    /// Every step is attempted even if an earlier one fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotConnected`] if the probe is not connected, or the
+    /// first error from the cleanup steps.
+    pub async fn end_cook(&self) -> Result<()> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
 
-    /// Get the minimum sequence number of logs on probe.
-    pub fn min_sequence_number(&self) -> u32 {
-        self.state.read().min_sequence
+        let results = [
+            self.cancel_prediction().await,
+            self.reset_food_safe().await,
+            self.disable_all_alarms().await,
+        ];
+        self.state.write().active_cook = None;
+
+        results.into_iter().collect()
     }
 
-    /// Get the maximum sequence number of logs on probe.
-    pub fn max_sequence_number(&self) -> u32 {
-        self.state.read().max_sequence
+    /// Get the cook started with [`start_cook`](Self::start_cook), if it has
+    /// not been ended.
+    pub fn active_cook(&self) -> Option<CookSpec> {
+        self.state.read().active_cook.clone()
     }
 
-    /// Get the percentage of logs synced.
-    pub fn percent_of_logs_synced(&self) -> f64 {
-        let state = self.state.read();
-        state
-            .temperature_log
-            .percent_synced(state.min_sequence, state.max_sequence)
+    /// Set the default cooks to start when the probe connects, or `None` to
+    /// stop applying defaults.
+    ///
+    /// Each time the probe connects without a cook running, the default
+    /// matching its color or ID is started with
+    /// [`start_cook`](Self::start_cook) and announced on
+    /// [`subscribe_defaults_applied`](Self::subscribe_defaults_applied). A
+    /// default that fails to start is logged and does not fail the
+    /// connection.
+    pub fn set_cook_defaults(&self, defaults: Option<Arc<ProbeDefaults>>) {
+        *self.cook_defaults.write() = defaults;
     }
 
-    /// Access the temperature log.
-    pub fn temperature_log(&self) -> TemperatureLog {
-        self.state.read().temperature_log.clone()
+    /// Get the default cooks started when the probe connects, if set.
+    pub fn cook_defaults(&self) -> Option<Arc<ProbeDefaults>> {
+        self.cook_defaults.read().clone()
     }
 
-    /// Subscribe to log sync progress updates.
-    pub fn subscribe_log_sync(&self) -> broadcast::Receiver<f64> {
-        self.log_sync_tx.subscribe()
+    /// Subscribe to default cooks being started on connecting.
+    pub fn subscribe_defaults_applied(&self) -> broadcast::Receiver<DefaultsApplied> {
+        self.events.defaults_tx.subscribe()
     }
 
-    /// Register a callback for log sync progress.
-    pub fn on_log_sync_progress<F>(&self, callback: F) -> CallbackHandle
+    /// Register a callback for default cooks being started on connecting.
+    pub fn on_defaults_applied<F>(&self, callback: F) -> CallbackHandle
     where
-        F: Fn(f64) + Send + Sync + 'static,
+        F: Fn(&DefaultsApplied) + Send + Sync + 'static,
     {
-        let callback_id = self.callback_counter.fetch_add(1, Ordering::SeqCst);
-        let mut rx = self.log_sync_tx.subscribe();
+        self.callbacks.spawn(
+            "defaults_applied",
+            self.events.defaults_tx.subscribe(),
+            move |applied| callback(&applied),
+        )
+    }
+
+    /// Start the default cook matching the probe, if any and no cook is
+    /// running.
+    ///
+    /// A cook started before this `Probe` existed, e.g. before the app
+    /// restarted, only shows in the probe's status, so the first status
+    /// since connecting is awaited and the defaults are skipped if it
+    /// reports a prediction or food-safe configuration.
+    async fn apply_cook_defaults(&self) {
+        let Some(defaults) = self.cook_defaults() else {
+            return;
+        };
+        if self.active_cook().is_some() {
+            return;
+        }
+        let Some(cook) = defaults.for_probe(self.color(), self.id()).cloned() else {
+            return;
+        };
+        if !self.wait_for_status_since_connect().await {
+            debug!(
+                "No status from {} yet; applying defaults without it",
+                self.serial_number_string()
+            );
+        }
+        if self.has_probe_cook() {
+            info!(
+                "Probe {} already has a cook configured; not applying defaults",
+                self.serial_number_string()
+            );
+            return;
+        }
 
-        let handle = tokio::spawn(async move {
-            while let Ok(progress) = rx.recv().await {
-                callback(progress);
+        match self.start_cook(cook.spec.clone()).await {
+            Ok(()) => {
+                info!(
+                    "Started default cook for {} on {}",
+                    cook.probe,
+                    self.serial_number_string()
+                );
+                let _ = self.events.defaults_tx.send(DefaultsApplied {
+                    serial_number: self.serial_number(),
+                    probe: cook.probe,
+                    spec: cook.spec,
+                    applied_at: chrono::Utc::now(),
+                });
             }
-        });
+            Err(e) => warn!(
+                "Failed to start default cook for {} on {}: {}",
+                cook.probe,
+                self.serial_number_string(),
+                e
+            ),
+        }
+    }
 
-        CallbackHandle::new(callback_id, move || {
-            handle.abort();
+    /// Wait up to [`DEFAULTS_STATUS_WAIT`](Self::DEFAULTS_STATUS_WAIT) for
+    /// a status update since the probe last connected.
+    ///
+    /// Returns `false` if none arrived, or the probe has no Probe Status
+    /// characteristic.
+    async fn wait_for_status_since_connect(&self) -> bool {
+        let received = || {
+            let state = self.state.read();
+            matches!(
+                (state.last_status_at, state.connected_at),
+                (Some(status), Some(connected)) if status >= connected
+            )
+        };
+        if !self.capabilities().status_characteristic {
+            return false;
+        }
+        tokio::time::timeout(Self::DEFAULTS_STATUS_WAIT, async {
+            while !received() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
         })
+        .await
+        .is_ok()
     }
 
-    // === Prediction ===
+    /// Check if the probe's status reports a prediction or food-safe
+    /// configuration.
+    fn has_probe_cook(&self) -> bool {
+        let state = self.state.read();
+        let predicting = state
+            .prediction
+            .as_ref()
+            .is_some_and(|p| p.mode != PredictionMode::None);
+        // Unconfigured probes report an all-zero food-safe configuration
+        let food_safe = state
+            .food_safe_data
+            .as_ref()
+            .and_then(|data| data.config.as_ref())
+            .is_some_and(|config| config.to_bytes() != [0; 10]);
+        predicting || food_safe
+    }
 
-    /// Get current prediction information.
-    pub fn prediction_info(&self) -> Option<PredictionInfo> {
-        self.state.read().prediction.clone()
+    /// Restore one cook step to the settings it had before `start_cook`.
+    async fn restore_cook_step(
+        &self,
+        step: CookStep,
+        previous: &PreviousCookSettings,
+    ) -> Result<()> {
+        match step {
+            CookStep::Prediction => match previous.prediction {
+                Some((mode, set_point)) => self.set_prediction(mode, set_point).await,
+                None => self.cancel_prediction().await,
+            },
+            CookStep::FoodSafe => match &previous.food_safe {
+                Some(config) => self.configure_food_safe_with_config(config.clone()).await,
+                None => self.reset_food_safe().await,
+            },
+            CookStep::Alarms => match &previous.alarms {
+                Some(alarms) => self.set_alarms(alarms).await,
+                None => self.disable_all_alarms().await,
+            },
+        }
     }
 
-    /// Set prediction target temperature and mode.
-    pub async fn set_prediction(&self, mode: PredictionMode, set_point_celsius: f64) -> Result<()> {
-        if !self.connection.is_connected() {
+    // === Configuration ===
+
+    /// Get the configuration commands sent to the probe, oldest first.
+    ///
+    /// Prediction, alarm, food-safe, power mode, ID and color commands are
+    /// recorded with when they were sent and whether the write succeeded, up
+    /// to [`COMMAND_HISTORY_CAPACITY`](crate::audit::COMMAND_HISTORY_CAPACITY)
+    /// of them. Commands rejected before sending, e.g. while disconnected,
+    /// are not recorded.
+    pub fn command_history(&self) -> Vec<CommandRecord> {
+        self.state.read().command_history.records()
+    }
+
+    /// Set probe ID (1-8).
+    pub async fn set_id(&self, id: ProbeId) -> Result<()> {
+        if !self.is_connected() {
             return Err(Error::NotConnected);
         }
 
-        if !(0.0..=300.0).contains(&set_point_celsius) {
-            return Err(Error::InvalidParameter {
-                name: "set_point_celsius".to_string(),
-                value: set_point_celsius.to_string(),
-            });
+        let message = build_set_probe_id_request(id.as_u8());
+        self.send_config(ConfigCommand::SetId(id), &message).await?;
+
+        let mut state = self.state.write();
+        state.probe_id = id;
+        state.probe_id_set_at = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Set probe color.
+    pub async fn set_color(&self, color: ProbeColor) -> Result<()> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
         }
 
-        // Per spec: Prediction Set Point = raw * 0.1°C, so raw = celsius * 10
-        let set_point_raw = (set_point_celsius * 10.0) as u16;
-        let message = build_set_prediction_request(mode.to_raw(), set_point_raw);
+        let message = build_set_probe_color_request(color.to_raw());
+        self.send_config(ConfigCommand::SetColor(color), &message)
+            .await?;
+
+        let mut state = self.state.write();
+        state.color = color;
+        state.color_set_at = Some(Instant::now());
 
-        self.send_uart_message(&message).await
+        Ok(())
     }
 
-    /// Cancel active prediction.
-    pub async fn cancel_prediction(&self) -> Result<()> {
-        if !self.connection.is_connected() {
+    /// Get the session information last reported by the probe.
+    ///
+    /// Read from the probe when it connects over BLE.
+    pub fn session_info(&self) -> Option<SessionInfo> {
+        self.state.read().session_info.clone()
+    }
+
+    /// Read session information from the probe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotConnected`] if not connected, [`Error::Timeout`]
+    /// if the probe does not respond, [`Error::ProbeError`] if it reports
+    /// failure, or [`Error::InvalidData`] if the response is malformed.
+    pub async fn read_session_info(&self) -> Result<SessionInfo> {
+        if !self.is_connected() {
             return Err(Error::NotConnected);
         }
 
-        let message = build_cancel_prediction_request();
-        self.send_uart_message(&message).await
+        let mut responses = self.uart_response_tx.subscribe();
+        self.send_uart_message(&build_read_session_info_request())
+            .await?;
+        let response =
+            await_uart_response(&mut responses, UartMessageType::ReadSessionInfoResponse).await?;
+        if !response.success {
+            return Err(Error::ProbeError {
+                message: "Read Session Information failed".to_string(),
+            });
+        }
+        SessionInfo::from_bytes(&response.payload).ok_or_else(|| {
+            Error::invalid_payload("Session information response too short", &response.payload)
+        })
     }
 
-    /// Subscribe to prediction updates.
-    pub fn subscribe_predictions(&self) -> broadcast::Receiver<PredictionInfo> {
-        self.prediction_tx.subscribe()
+    // === Firmware ===
+
+    /// Read firmware version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotConnected`] if the probe is not connected over
+    /// BLE, or [`Error::NotSupported`] if an external transport does not
+    /// know the revision.
+    pub async fn read_firmware_version(&self) -> Result<String> {
+        let characteristics = match &self.link {
+            Link::Ble {
+                characteristics, ..
+            } => characteristics,
+            Link::External(transport) => {
+                return transport.firmware_revision().ok_or(Error::NotSupported {
+                    operation: "read_firmware_version over this transport".to_string(),
+                });
+            }
+        };
+        let handler = characteristics.read().clone().ok_or(Error::NotConnected)?;
+        handler.read_firmware_revision().await
     }
 
-    /// Register a callback for prediction updates.
-    pub fn on_prediction_updated<F>(&self, callback: F) -> CallbackHandle
+    /// Get what the probe's firmware supports.
+    ///
+    /// Derived from the firmware revision read when the probe connects.
+    /// Until then, or if the revision cannot be read, only the original
+    /// message layouts are assumed.
+    pub fn capabilities(&self) -> ProbeCapabilities {
+        self.state.read().capabilities.clone()
+    }
+
+    /// Override what the probe's firmware is assumed to support.
+    ///
+    /// Replaced again the next time the probe connects and its firmware
+    /// revision is read.
+    pub fn set_capabilities(&self, capabilities: ProbeCapabilities) {
+        debug!(
+            "Probe {} capabilities: {:?}",
+            self.serial_number_string(),
+            capabilities
+        );
+        self.state.write().capabilities = capabilities;
+    }
+
+    /// Record whether the connection found the Probe Status characteristic,
+    /// publishing a diagnostic if it is missing.
+    fn note_status_characteristic(&self, present: bool) {
+        let firmware_version = {
+            let mut state = self.state.write();
+            state.capabilities.status_characteristic = present;
+            state.capabilities.firmware_version
+        };
+        if present {
+            return;
+        }
+
+        let diagnostic = StatusUnavailable {
+            serial_number: self.serial_number(),
+            firmware_version,
+            detected_at: chrono::Utc::now(),
+        };
+        warn!("{}", diagnostic);
+        let _ = self.events.status_unavailable_tx.send(diagnostic);
+    }
+
+    /// Subscribe to diagnostics for connections finding no Probe Status
+    /// characteristic.
+    pub fn subscribe_status_unavailable(&self) -> broadcast::Receiver<StatusUnavailable> {
+        self.events.status_unavailable_tx.subscribe()
+    }
+
+    /// Register a callback for connections finding no Probe Status
+    /// characteristic.
+    pub fn on_status_unavailable<F>(&self, callback: F) -> CallbackHandle
     where
-        F: Fn(&PredictionInfo) + Send + Sync + 'static,
+        F: Fn(&StatusUnavailable) + Send + Sync + 'static,
     {
-        let callback_id = self.callback_counter.fetch_add(1, Ordering::SeqCst);
-        let mut rx = self.prediction_tx.subscribe();
+        self.callbacks.spawn(
+            "status_unavailable",
+            self.events.status_unavailable_tx.subscribe(),
+            move |diagnostic| callback(&diagnostic),
+        )
+    }
 
-        let handle = tokio::spawn(async move {
-            while let Ok(prediction) = rx.recv().await {
-                callback(&prediction);
-            }
-        });
+    /// Get the protocol mismatch suspending status parsing on this
+    /// connection, if any.
+    pub fn protocol_mismatch(&self) -> Option<ProtocolMismatch> {
+        self.state.read().protocol_mismatch.clone()
+    }
 
-        CallbackHandle::new(callback_id, move || {
-            handle.abort();
-        })
+    /// Subscribe to diagnostics for status notifications that keep failing
+    /// to parse.
+    ///
+    /// Raised once per connection, after
+    /// [`ProtocolMismatch::FAILURE_THRESHOLD`] consecutive failures; status
+    /// notifications are then ignored until the probe reconnects.
+    pub fn subscribe_protocol_mismatch(&self) -> broadcast::Receiver<ProtocolMismatch> {
+        self.events.protocol_mismatch_tx.subscribe()
     }
 
-    // === Food Safety ===
+    /// Register a callback for status notifications that keep failing to
+    /// parse.
+    pub fn on_protocol_mismatch<F>(&self, callback: F) -> CallbackHandle
+    where
+        F: Fn(&ProtocolMismatch) + Send + Sync + 'static,
+    {
+        self.callbacks.spawn(
+            "protocol_mismatch",
+            self.events.protocol_mismatch_tx.subscribe(),
+            move |mismatch| callback(&mismatch),
+        )
+    }
 
-    /// Configure food safety monitoring with a product type (simplified mode).
+    /// Read hardware revision.
+    pub async fn read_hardware_revision(&self) -> Result<String> {
+        let Link::Ble {
+            characteristics, ..
+        } = &self.link
+        else {
+            return Err(Error::NotSupported {
+                operation: "read_hardware_revision over this transport".to_string(),
+            });
+        };
+        let handler = characteristics.read().clone().ok_or(Error::NotConnected)?;
+        handler.read_hardware_revision().await
+    }
+
+    /// Get the details written in the header of an
+    /// [app-compatible export](TemperatureLog::to_app_csv) of this probe's
+    /// log.
+    pub fn app_export_metadata(&self) -> AppExportMetadata {
+        let state = self.state.read();
+        let mut metadata = AppExportMetadata::new(state.serial_number);
+        metadata.firmware_version = state.capabilities.firmware_version;
+        metadata.hardware_revision = state.hardware_revision.clone();
+        metadata
+    }
+
+    /// Write the current session's log, events, configuration audit trail,
+    /// diagnostics and a cook report to `dir`, for attaching to a bug
+    /// report, with the log, report and chart in `unit`.
     ///
-    /// This uses simplified mode which applies predefined USDA temperature thresholds
-    /// based on the product type.
-    pub async fn configure_food_safe(&self, product: FoodSafeProduct) -> Result<()> {
-        let config = product.to_config(Serving::ServedImmediately);
-        self.configure_food_safe_with_config(config).await
+    /// See [`bundle`](crate::persistence::bundle) for the files written.
+    /// Requires the `persistence` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if `dir` can't be created or written.
+    #[cfg(feature = "persistence")]
+    pub fn export_session_bundle(
+        &self,
+        dir: impl AsRef<std::path::Path>,
+        unit: crate::utils::TemperatureUnit,
+    ) -> Result<crate::persistence::SessionBundle> {
+        crate::persistence::bundle::write_bundle(self, dir.as_ref(), unit)
     }
 
-    /// Configure food safety monitoring with a product type and serving mode (simplified mode).
-    pub async fn configure_food_safe_with_serving(
+    /// Write a [session bundle](Self::export_session_bundle) with each file
+    /// encrypted with `key`, for keeping as a record.
+    ///
+    /// Requires the `persistence` and `encryption` features.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Storage`] if `dir` can't be created or written.
+    #[cfg(all(feature = "persistence", feature = "encryption"))]
+    pub fn export_session_bundle_encrypted(
         &self,
-        product: FoodSafeProduct,
-        serving: Serving,
-    ) -> Result<()> {
-        let config = product.to_config(serving);
-        self.configure_food_safe_with_config(config).await
+        dir: impl AsRef<std::path::Path>,
+        unit: crate::utils::TemperatureUnit,
+        key: &crate::encryption::EncryptionKey,
+    ) -> Result<crate::persistence::SessionBundle> {
+        crate::persistence::bundle::write_encrypted_bundle(self, dir.as_ref(), unit, key)
+    }
+
+    /// Get the hardware revision read when the probe last connected.
+    pub fn hardware_revision(&self) -> Option<String> {
+        self.state.read().hardware_revision.clone()
     }
 
-    /// Configure food safety monitoring with full configuration (integrated mode).
+    // === Raw GATT (unstable) ===
+
+    /// Get raw access to the probe's GATT characteristics.
     ///
-    /// This allows specifying custom parameters for time-temperature integration
-    /// including Z-value, D-value, reference temperature, and target log reduction.
-    pub async fn configure_food_safe_with_config(&self, config: FoodSafeConfig) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
-        }
+    /// **Unstable**: see [`RawGatt`](crate::raw_gatt::RawGatt). Requires the
+    /// `unstable` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotConnected`] if the probe is not connected over BLE,
+    /// or [`Error::NotSupported`] for probes reached through an external
+    /// transport.
+    #[cfg(feature = "unstable")]
+    pub fn raw_gatt(&self) -> Result<crate::raw_gatt::RawGatt> {
+        let Link::Ble {
+            characteristics, ..
+        } = &self.link
+        else {
+            return Err(Error::NotSupported {
+                operation: "raw_gatt over an external transport".to_string(),
+            });
+        };
+        let handler = characteristics.read().clone().ok_or(Error::NotConnected)?;
+        Ok(crate::raw_gatt::RawGatt::new(handler))
+    }
 
-        let config_bytes = config.to_bytes();
-        let message = build_configure_food_safe_request(&config_bytes);
-        self.send_uart_message(&message).await?;
+    // === Internal ===
 
-        self.state.write().food_safe_data = Some(FoodSafeData::with_config(config));
+    /// Check if the probe link is connected.
+    fn is_connected(&self) -> bool {
+        self.connection_state().is_connected()
+    }
 
-        Ok(())
+    /// Send a UART message.
+    async fn send_uart_message(&self, message: &UartMessage) -> Result<()> {
+        self.write_uart(&message.to_bytes()).await
     }
 
-    /// Configure food safety monitoring with integrated mode for a product.
-    ///
-    /// Integrated mode calculates log reduction based on time-temperature integration.
-    pub async fn configure_food_safe_integrated(
-        &self,
-        product: FoodSafeProduct,
-        serving: Serving,
-    ) -> Result<()> {
-        let config = product.to_integrated_config(serving);
-        self.configure_food_safe_with_config(config).await
+    /// Send a configuration command, recording it in the command history.
+    async fn send_config(&self, command: ConfigCommand, message: &UartMessage) -> Result<()> {
+        send_link_config(&self.link, &self.state, command, message).await
     }
 
-    /// Reset food safety calculations.
-    pub async fn reset_food_safe(&self) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
+    /// Run a configuration command received as a typed value, e.g. from a
+    /// gateway client, through the same method an app would call.
+    #[cfg(feature = "gateway")]
+    pub(crate) async fn run_config_command(&self, command: ConfigCommand) -> Result<()> {
+        match command {
+            ConfigCommand::SetPrediction {
+                mode,
+                set_point_celsius,
+            } => self.set_prediction(mode, set_point_celsius).await,
+            ConfigCommand::CancelPrediction => self.cancel_prediction().await,
+            ConfigCommand::SetAlarms(config) => self.set_alarms(&config).await,
+            ConfigCommand::SilenceAlarms => self.silence_alarms().await,
+            ConfigCommand::ConfigureFoodSafe(config) => {
+                self.configure_food_safe_with_config(config).await
+            }
+            ConfigCommand::ResetFoodSafe => self.reset_food_safe().await,
+            ConfigCommand::SetPowerMode(mode) => self.set_power_mode(mode).await,
+            ConfigCommand::ResetThermometer => self.reset_thermometer().await,
+            ConfigCommand::SetId(id) => self.set_id(id).await,
+            ConfigCommand::SetColor(color) => self.set_color(color).await,
         }
+    }
 
-        let message = build_reset_food_safe_request();
-        self.send_uart_message(&message).await?;
+    /// Write encoded UART bytes to the probe.
+    pub(crate) async fn write_uart(&self, data: &[u8]) -> Result<()> {
+        write_link_uart(&self.link, data).await
+    }
+}
 
-        self.state.write().food_safe_data = None;
+/// Send a configuration command over a link, recording it in the probe's
+/// command history.
+async fn send_link_config(
+    link: &Link,
+    state: &RwLock<ProbeState>,
+    command: ConfigCommand,
+    message: &UartMessage,
+) -> Result<()> {
+    let data = message.to_bytes();
+    // Stamped before writing, so the record orders before the probe's
+    // response to it
+    let sent_at = chrono::Utc::now();
+    let result = match link {
+        Link::External(transport) => transport.send_config(&command, &data).await,
+        Link::Ble { .. } => write_link_uart(link, &data).await,
+    };
+    let mut state = state.write();
+    match &result {
+        Ok(()) => state.config_tracker.wrote(&command),
+        Err(e) => debug!("Failed to send {:?} to probe: {}", command, e),
+    }
+    state.command_history.record(CommandRecord {
+        command,
+        sent_at,
+        outcome: ConfigOutcome::from(&result),
+    });
+    result
+}
 
-        Ok(())
+/// Write encoded UART bytes over a link.
+async fn write_link_uart(link: &Link, data: &[u8]) -> Result<()> {
+    match link {
+        Link::Ble {
+            characteristics, ..
+        } => {
+            let handler = characteristics.read().clone().ok_or(Error::NotConnected)?;
+            handler.write(&UART_RX_UUID, data, false).await
+        }
+        Link::External(transport) => transport.write_uart(data).await,
     }
+}
 
-    /// Get current food safety data.
-    pub fn food_safe_data(&self) -> Option<FoodSafeData> {
-        self.state.read().food_safe_data.clone()
+impl std::fmt::Debug for Probe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Probe")
+            .field("identifier", &*self.identifier.read())
+            .field("serial_number", &self.serial_number_string())
+            .field("connection_state", &self.connection_state())
+            .finish()
     }
+}
 
-    // === Battery & Status ===
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RawTemperature;
+
+    fn status_bytes(probe_id_raw: u8, color_raw: u8) -> Vec<u8> {
+        let mut data = vec![0u8; 30];
+        data[0..4].copy_from_slice(&5u32.to_le_bytes());
+        data[4..8].copy_from_slice(&42u32.to_le_bytes());
+        data[21] = (probe_id_raw << 5) | (color_raw << 2);
+        data
+    }
 
-    /// Get current battery status.
-    pub fn battery_status(&self) -> BatteryStatus {
-        self.state.read().battery_status
+    #[test]
+    fn test_apply_status_updates_state() {
+        let mut state = ProbeState::new(0x1000_0001);
+        let status = ProbeStatus::parse(&status_bytes(2, 3)).unwrap();
+
+        state.apply_status(&status, Instant::now());
+
+        assert_eq!(state.min_sequence, 5);
+        assert_eq!(state.max_sequence, 42);
+        assert_eq!(state.probe_id, ProbeId::new(3));
+        assert_eq!(state.color, ProbeColor::from_raw(3));
     }
 
-    /// Get overheating information.
-    pub fn overheating(&self) -> Overheating {
-        self.state.read().overheating
+    #[test]
+    fn test_cooling_starts_once_chilled_food_is_safe() {
+        use crate::data::{CoolingPhase, FoodSafeState, FoodSafeStatus};
+
+        let mut state = ProbeState::new(0x1000_0001);
+        state.virtual_temperatures = VirtualTemperatures::new(Some(80.0), None, None);
+        let config = FoodSafeProduct::BeefRoast.to_config(Serving::CookedAndChilled);
+        state.food_safe_data = Some(FoodSafeData::with_config(config));
+        assert!(state.check_cooling().is_empty());
+        assert!(state.cooling.is_none());
+
+        let status = FoodSafeStatus {
+            state: FoodSafeState::Safe,
+            ..FoodSafeStatus::default()
+        };
+        state
+            .food_safe_data
+            .as_mut()
+            .unwrap()
+            .update_from_status(status);
+        assert!(state.check_cooling().is_empty());
+        assert_eq!(state.cooling.as_ref().unwrap().phase(), CoolingPhase::Armed);
+
+        state.virtual_temperatures.core = Some(55.0);
+        assert!(matches!(
+            state.check_cooling().as_slice(),
+            [CoolingEvent::Started { .. }]
+        ));
     }
 
-    /// Get current operational mode.
-    pub fn mode(&self) -> ProbeMode {
-        self.state.read().mode
+    #[test]
+    fn test_cancelled_cooling_stays_off_until_next_cook() {
+        use crate::data::{FoodSafeState, FoodSafeStatus};
+
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let safe = || {
+            let config = FoodSafeProduct::BeefRoast.to_config(Serving::CookedAndChilled);
+            let status = FoodSafeStatus {
+                state: FoodSafeState::Safe,
+                ..FoodSafeStatus::default()
+            };
+            Some(FoodSafeData::from_config_and_status(config, status))
+        };
+        probe.state.write().food_safe_data = safe();
+        probe.state.write().check_cooling();
+        assert!(probe.cooling_monitor().is_some());
+
+        probe.cancel_cooling();
+        probe.state.write().check_cooling();
+        assert!(probe.cooling_monitor().is_none());
+
+        // A new cook arms monitoring again
+        probe.state.write().food_safe_data = None;
+        probe.state.write().check_cooling();
+        probe.state.write().food_safe_data = safe();
+        probe.state.write().check_cooling();
+        assert!(probe.cooling_monitor().is_some());
     }
 
-    // === Power Mode & Preferences ===
+    #[tokio::test]
+    async fn test_cooling_stage_fails_at_deadline() {
+        use crate::data::{CoolingPhase, CoolingProfile, CoolingStage};
+
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let mut events = probe.subscribe_cooling();
+        probe.monitor_cooling(CoolingProfile {
+            stages: vec![CoolingStage {
+                from: 57.0,
+                to: 21.0,
+                within: Duration::from_millis(50),
+            }],
+        });
+        let raised = {
+            let mut state = probe.state.write();
+            state.virtual_temperatures = VirtualTemperatures::new(Some(50.0), None, None);
+            state.raise_events()
+        };
+        probe.events.publish(raised);
+        assert!(matches!(
+            events.recv().await,
+            Ok(CoolingEvent::Started { .. })
+        ));
+
+        // No further readings arrive
+        let failed = tokio::time::timeout(Duration::from_secs(1), events.recv()).await;
+        assert_eq!(
+            failed.unwrap().unwrap(),
+            CoolingEvent::Failed {
+                stage: 0,
+                temperature: 50.0,
+            }
+        );
+        assert_eq!(
+            probe.cooling_monitor().unwrap().phase(),
+            CoolingPhase::Failed(0)
+        );
+    }
 
-    /// Get current power mode.
-    ///
-    /// Returns `None` if the probe hasn't sent thermometer preferences yet.
-    pub fn power_mode(&self) -> Option<PowerMode> {
-        self.state
-            .read()
-            .thermometer_preferences
-            .map(|p| p.power_mode)
+    #[test]
+    fn test_food_safe_start_is_marked_once() {
+        use crate::data::FoodSafeStatus;
+
+        let mut state = ProbeState::new(0x1000_0001);
+        let config = FoodSafeProduct::ChickenBreast.to_config(Serving::ServedImmediately);
+        let status = FoodSafeStatus {
+            sequence_number: 7,
+            ..FoodSafeStatus::default()
+        };
+        state.food_safe_data = Some(FoodSafeData::from_config_and_status(config, status));
+
+        state.mark_food_safe_start();
+        state.mark_food_safe_start();
+        let marks: Vec<_> = state.temperature_log.annotations_at(7).collect();
+        assert_eq!(marks.len(), 1);
+        assert_eq!(marks[0].text, "Food safe started");
     }
 
-    /// Check if the probe is in always-on mode.
-    pub fn is_always_on(&self) -> bool {
-        self.state
-            .read()
-            .thermometer_preferences
-            .map(|p| p.is_always_on())
-            .unwrap_or(false)
+    #[test]
+    fn test_apply_status_anchors_sequence_clock() {
+        let mut state = ProbeState::new(0x1000_0001);
+        let status = ProbeStatus::parse(&status_bytes(0, 0)).unwrap();
+        state.apply_status(&status, Instant::now());
+        state.apply_status(&status, Instant::now());
+        assert_eq!(state.sequence_clock.anchors().len(), 1);
+        assert!(state.sequence_clock.timestamp(42).is_some());
+
+        // A lower max sequence means a new session
+        let mut data = status_bytes(0, 0);
+        data[4..8].copy_from_slice(&3u32.to_le_bytes());
+        state.apply_status(&ProbeStatus::parse(&data).unwrap(), Instant::now());
+        assert_eq!(state.sequence_clock.anchors().len(), 1);
+        assert_eq!(state.sequence_clock.anchors()[0].sequence, 3);
     }
 
-    /// Get thermometer preferences.
-    pub fn thermometer_preferences(&self) -> Option<ThermometerPreferences> {
-        self.state.read().thermometer_preferences
+    #[test]
+    fn test_apply_status_bytes_reparses_changed_sections() {
+        let mut state = ProbeState::new(0x1000_0001);
+        let mut data = status_bytes(2, 3);
+        state.apply_status_bytes(&data, Instant::now()).unwrap();
+        assert_eq!(state.probe_id, ProbeId::new(3));
+
+        // A readings-only update leaves local state alone
+        state.thermometer_preferences = Some(ThermometerPreferences::default());
+        data[4..8].copy_from_slice(&43u32.to_le_bytes());
+        data[8] = 0x10;
+        state.apply_status_bytes(&data, Instant::now()).unwrap();
+        assert_eq!(state.max_sequence, 43);
+        assert_eq!(
+            state.temperatures,
+            ProbeStatus::parse(&data).unwrap().temperatures
+        );
+        assert!(state.thermometer_preferences.is_some());
+
+        // Any other change is applied in full
+        data[23] = 0x03;
+        state.apply_status_bytes(&data, Instant::now()).unwrap();
+        assert_eq!(
+            state.prediction.as_ref().map(|p| p.state),
+            Some(PredictionState::Predicting)
+        );
+        assert!(state.thermometer_preferences.is_none());
+
+        assert!(state
+            .apply_status_bytes(&[0u8; 10], Instant::now())
+            .is_err());
+        assert_eq!(state.max_sequence, 43);
     }
 
-    /// Set the power mode.
-    ///
-    /// - `PowerMode::Normal`: Probe will auto power-off when placed in charger.
-    /// - `PowerMode::AlwaysOn`: Probe stays powered even in charger.
-    pub async fn set_power_mode(&self, mode: PowerMode) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
-        }
+    #[test]
+    fn test_apply_status_respects_id_grace_period() {
+        let mut state = ProbeState::new(0x1000_0001);
+        let now = Instant::now();
+        state.probe_id = ProbeId::new(5);
+        state.probe_id_set_at = Some(now);
 
-        let message = build_set_power_mode_request(mode.to_raw());
-        self.send_uart_message(&message).await?;
+        let status = ProbeStatus::parse(&status_bytes(0, 0)).unwrap();
+        state.apply_status(&status, now);
 
-        // Update local state
-        let mut state = self.state.write();
-        state.thermometer_preferences = Some(ThermometerPreferences::with_power_mode(mode));
+        // ID was just set explicitly, color was not
+        assert_eq!(state.probe_id, ProbeId::new(5));
+        assert_eq!(state.color, ProbeColor::from_raw(0));
+    }
 
-        Ok(())
+    #[test]
+    fn test_unchanged_id_reapplied_after_grace_period() {
+        let mut state = ProbeState::new(0x1000_0001);
+        let now = Instant::now();
+        state.apply_status_bytes(&status_bytes(0, 0), now).unwrap();
+        state.probe_id = ProbeId::new(5);
+        state.probe_id_set_at = Some(now);
+
+        // The probe never took the new ID; later notifications change only
+        // the readings
+        let mut data = status_bytes(0, 0);
+        data[4..8].copy_from_slice(&43u32.to_le_bytes());
+        let later = now + ID_COLOR_GRACE_PERIOD + Duration::from_secs(1);
+        state.apply_status_bytes(&data, later).unwrap();
+        assert_eq!(state.probe_id, ProbeId::from_raw(0));
     }
 
-    /// Reset the thermometer to factory defaults.
-    ///
-    /// This will reset all settings including probe ID, color, alarms, etc.
-    pub async fn reset_thermometer(&self) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
+    struct NullTransport;
+
+    #[async_trait::async_trait]
+    impl ProbeTransport for NullTransport {
+        fn connection_state(&self) -> ConnectionState {
+            ConnectionState::Connected
         }
 
-        let message = build_reset_thermometer_request();
-        self.send_uart_message(&message).await
-    }
+        async fn connect(&self) -> Result<()> {
+            Ok(())
+        }
 
-    // === Temperature Alarms ===
+        async fn disconnect(&self) -> Result<()> {
+            Ok(())
+        }
 
-    /// Get current alarm configuration.
-    ///
-    /// Returns `None` if the probe hasn't sent alarm status yet.
-    pub fn alarm_config(&self) -> Option<AlarmConfig> {
-        self.state.read().alarm_config.clone()
+        async fn write_uart(&self, _data: &[u8]) -> Result<()> {
+            Ok(())
+        }
     }
 
-    /// Check if any alarm is currently triggered.
-    pub fn any_alarm_tripped(&self) -> bool {
-        self.state
-            .read()
-            .alarm_config
-            .as_ref()
-            .map(|c| c.any_tripped())
-            .unwrap_or(false)
+    /// Transport for a probe without the Probe Status characteristic,
+    /// recording UART writes.
+    #[derive(Default)]
+    struct NoStatusTransport {
+        writes: parking_lot::Mutex<Vec<UartMessage>>,
     }
 
-    /// Check if any alarm is currently sounding.
-    pub fn any_alarm_alarming(&self) -> bool {
-        self.state
-            .read()
-            .alarm_config
-            .as_ref()
-            .map(|c| c.any_alarming())
-            .unwrap_or(false)
-    }
+    #[async_trait::async_trait]
+    impl ProbeTransport for NoStatusTransport {
+        fn connection_state(&self) -> ConnectionState {
+            ConnectionState::Connected
+        }
 
-    /// Check if any alarm is enabled.
-    pub fn any_alarm_enabled(&self) -> bool {
-        self.state
-            .read()
-            .alarm_config
-            .as_ref()
-            .map(|c| c.any_enabled())
-            .unwrap_or(false)
-    }
+        fn has_status_characteristic(&self) -> bool {
+            false
+        }
 
-    /// Set temperature alarms.
-    ///
-    /// Configures high and low temperature alarms for all sensors (T1-T8) and
-    /// virtual sensors (Core, Surface, Ambient).
-    ///
-    /// See `AlarmConfig` for how to configure individual alarms.
-    pub async fn set_alarms(&self, config: &AlarmConfig) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
+        async fn connect(&self) -> Result<()> {
+            Ok(())
         }
 
-        let config_bytes = config.to_bytes();
-        let message = build_set_high_low_alarms_request(&config_bytes);
-        self.send_uart_message(&message).await?;
+        async fn disconnect(&self) -> Result<()> {
+            Ok(())
+        }
 
-        // Update local state
-        self.state.write().alarm_config = Some(config.clone());
+        async fn write_uart(&self, data: &[u8]) -> Result<()> {
+            self.writes.lock().push(UartMessage::parse(data)?);
+            Ok(())
+        }
+    }
 
-        Ok(())
+    /// Transport holding each UART write until the test releases it.
+    #[derive(Default)]
+    struct GatedTransport {
+        writing: tokio::sync::Notify,
+        release: tokio::sync::Notify,
     }
 
-    /// Silence any currently sounding alarms.
-    pub async fn silence_alarms(&self) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
+    #[async_trait::async_trait]
+    impl ProbeTransport for GatedTransport {
+        fn connection_state(&self) -> ConnectionState {
+            ConnectionState::Connected
         }
 
-        let message = build_silence_alarms_request();
-        self.send_uart_message(&message).await
+        async fn connect(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn write_uart(&self, _data: &[u8]) -> Result<()> {
+            self.writing.notify_one();
+            self.release.notified().await;
+            Ok(())
+        }
     }
 
-    /// Set a high temperature alarm for the core (virtual) sensor.
-    ///
-    /// This is a convenience method that creates an alarm config with just
-    /// the core high alarm set.
-    pub async fn set_core_high_alarm(&self, temperature_celsius: f64) -> Result<()> {
-        let mut config = self.alarm_config().unwrap_or_default();
-        config.set_core_high_alarm(temperature_celsius, true);
-        self.set_alarms(&config).await
+    #[tokio::test]
+    async fn test_command_history_stamped_before_sending() {
+        let transport = Arc::new(GatedTransport::default());
+        let probe = Probe::with_transport("test", 0x1000_0001, transport.clone());
+
+        // Note the time while the write is held in the transport
+        let (result, while_writing) = tokio::join!(probe.silence_alarms(), async {
+            transport.writing.notified().await;
+            let now = chrono::Utc::now();
+            transport.release.notify_one();
+            now
+        });
+        result.unwrap();
+        assert!(probe.command_history()[0].sent_at <= while_writing);
     }
 
-    /// Set a low temperature alarm for the core (virtual) sensor.
-    ///
-    /// This is a convenience method that creates an alarm config with just
-    /// the core low alarm set.
-    pub async fn set_core_low_alarm(&self, temperature_celsius: f64) -> Result<()> {
-        let mut config = self.alarm_config().unwrap_or_default();
-        config.set_core_low_alarm(temperature_celsius, true);
-        self.set_alarms(&config).await
+    #[tokio::test(start_paused = true)]
+    async fn test_uart_polling_without_status_characteristic() {
+        let transport = Arc::new(NoStatusTransport::default());
+        let probe = Probe::with_transport("test", 0x1000_0001, transport.clone());
+        probe.connect().await.unwrap();
+        tokio::task::yield_now().await;
+        let requested = |transport: &NoStatusTransport| {
+            transport
+                .writes
+                .lock()
+                .iter()
+                .map(|m| (m.message_type(), m.payload.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            requested(&transport),
+            vec![
+                (UartMessageType::ReadSessionInfo, vec![]),
+                (
+                    UartMessageType::ReadLogs,
+                    [0u32.to_le_bytes(), [0xFF; 4]].concat()
+                ),
+            ]
+        );
+
+        // Log records arrive as UART responses
+        let mut record = 7u32.to_le_bytes().to_vec();
+        record.extend_from_slice(&ProbeTemperatures::from_raw([1200; 8]).to_packed_bytes());
+        record.extend_from_slice(&[0; 7]);
+        let response = UartResponse::new(UartMessageType::ReadLogsResponse, true, record);
+        probe.ingest_uart_bytes(&response.to_bytes());
+        assert!(probe.temperature_log().data_point(7).is_some());
+
+        // The next poll asks for newer records only
+        transport.writes.lock().clear();
+        tokio::time::sleep(STATUS_POLL_INTERVAL + Duration::from_millis(1)).await;
+        assert_eq!(
+            requested(&transport)[1],
+            (
+                UartMessageType::ReadLogs,
+                [8u32.to_le_bytes(), [0xFF; 4]].concat()
+            )
+        );
+
+        // Disconnecting stops the polling
+        probe.disconnect().await.unwrap();
+        transport.writes.lock().clear();
+        tokio::time::sleep(STATUS_POLL_INTERVAL * 2).await;
+        assert!(transport.writes.lock().is_empty());
     }
 
-    /// Disable all alarms.
-    pub async fn disable_all_alarms(&self) -> Result<()> {
-        let config = AlarmConfig::new();
-        self.set_alarms(&config).await
+    #[test]
+    fn test_mode_changes_are_published() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let mut changes = probe.subscribe_mode_changes();
+        assert!(probe.time_in_mode().is_none());
+
+        // The first report sets the mode without a change
+        probe.ingest_status_bytes(&status_bytes(0, 0)).unwrap();
+        assert!(changes.try_recv().is_err());
+        assert!(probe.time_in_mode().is_some());
+
+        let mut data = status_bytes(0, 0);
+        data[21] |= ProbeMode::Error.to_raw();
+        probe.ingest_status_bytes(&data).unwrap();
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.from, ProbeMode::Normal);
+        assert_eq!(change.to, ProbeMode::Error);
+        assert_eq!(probe.mode(), ProbeMode::Error);
+
+        probe.ingest_status_bytes(&data).unwrap();
+        assert!(changes.try_recv().is_err());
     }
 
-    // === Configuration ===
+    #[test]
+    fn test_session_info_from_uart_response() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let mut responses = probe.uart_response_tx.subscribe();
+        let info = SessionInfo {
+            session_id: 0x1234_5678,
+            sample_period_ms: 1000,
+        };
+        let frame = UartResponse::new(
+            UartMessageType::ReadSessionInfoResponse,
+            true,
+            info.to_bytes().to_vec(),
+        )
+        .to_bytes();
+
+        // Noise, then the response split across notifications
+        let mut first = vec![0x00, UART_SYNC_BYTES[0], 0x42];
+        first.extend_from_slice(&frame[..4]);
+        probe.ingest_uart_bytes(&first);
+        assert!(probe.session_info().is_none());
+        probe.ingest_uart_bytes(&frame[4..]);
+        assert_eq!(probe.session_info(), Some(info.clone()));
+        assert_eq!(
+            responses.try_recv().unwrap().message_type,
+            UartMessageType::ReadSessionInfoResponse
+        );
 
-    /// Set probe ID (1-8).
-    pub async fn set_id(&self, id: ProbeId) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
-        }
+        // Failed or corrupt responses are ignored
+        let failed = UartResponse::new(
+            UartMessageType::ReadSessionInfoResponse,
+            false,
+            SessionInfo::default().to_bytes().to_vec(),
+        );
+        probe.ingest_uart_bytes(&failed.to_bytes());
+        let mut corrupt = frame.clone();
+        corrupt[8] ^= 0xFF;
+        probe.ingest_uart_bytes(&corrupt);
+        assert_eq!(probe.session_info(), Some(info));
+        assert!(probe.state.read().uart_buffer.is_empty());
+    }
 
-        let message = build_set_probe_id_request(id.as_u8());
-        self.send_uart_message(&message).await?;
+    #[test]
+    fn test_error_mode_faults_probe() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let mut faults = probe.subscribe_faults();
+
+        let mut data = status_bytes(0, 0);
+        data[8..21].copy_from_slice(&ProbeTemperatures::from_raw([900; 8]).to_packed_bytes());
+        probe.ingest_status_bytes(&data).unwrap();
+        assert!(probe.fault_info().is_none());
+        assert!(probe.virtual_temperatures().core.is_some());
+
+        data[21] |= ProbeMode::Error.to_raw();
+        probe.ingest_status_bytes(&data).unwrap();
+        let faulted = faults.try_recv().unwrap();
+        assert_eq!(faulted.serial_number, 0x1000_0001);
+        assert_eq!(probe.fault_info(), Some(faulted.fault));
+        assert!(probe.virtual_temperatures().core.is_none());
+        assert!(!probe.current_temperatures().is_active(0));
+
+        // Staying faulted keeps the original fault time
+        probe.ingest_status_bytes(&data).unwrap();
+        assert!(faults.try_recv().is_err());
+        assert_eq!(
+            probe.fault_info().unwrap().faulted_at,
+            faulted.fault.faulted_at
+        );
 
-        let mut state = self.state.write();
-        state.probe_id = id;
-        state.probe_id_set_at = Some(Instant::now());
+        data[21] &= !0x03;
+        probe.ingest_status_bytes(&data).unwrap();
+        assert!(probe.fault_info().is_none());
+    }
 
-        Ok(())
+    #[test]
+    fn test_log_sync_progress() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.ingest_status_bytes(&status_bytes(0, 0)).unwrap();
+        let mut progress = probe.subscribe_log_sync();
+        let mut complete = probe.subscribe_log_sync_complete();
+        let points = |sequences: std::ops::RangeInclusive<u32>| {
+            sequences.map(|sequence| LoggedDataPoint::new(sequence, ProbeTemperatures::new()))
+        };
+
+        // Records 5-42 are on the probe
+        probe.ingest_log_points(points(5..=10));
+        let first = progress.try_recv().unwrap();
+        assert_eq!((first.records, first.total), (6, 38));
+
+        // Records already synced don't move the progress
+        probe.ingest_log_points(points(8..=10));
+        assert!(progress.try_recv().is_err());
+
+        probe.ingest_log_points(points(11..=42));
+        assert!(progress.try_recv().unwrap().is_complete());
+        let totals = complete.try_recv().unwrap();
+        assert_eq!((totals.total, totals.received), (38, 38));
+        assert_eq!(probe.percent_of_logs_synced(), 100.0);
+        assert!(complete.try_recv().is_err());
     }
 
-    /// Set probe color.
-    pub async fn set_color(&self, color: ProbeColor) -> Result<()> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
-        }
+    #[test]
+    fn test_core_sensor_override() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let mut data = status_bytes(0, 0);
+        let raw = [600, 700, 800, 900, 1000, 1100, 1200, 1300];
+        data[8..21].copy_from_slice(&ProbeTemperatures::from_raw(raw).to_packed_bytes());
+        probe.ingest_status_bytes(&data).unwrap();
+        assert_eq!(probe.virtual_temperatures().core, Some(10.0));
+
+        probe.override_core_sensor(Some(PhysicalSensor::T2));
+        assert_eq!(probe.core_sensor_override(), Some(PhysicalSensor::T2));
+        let overridden = probe.virtual_temperatures();
+        assert_eq!(overridden.core, Some(15.0));
+        assert_eq!(overridden.sensor_selection.core_sensor, 0);
+        assert_eq!(probe.firmware_virtual_temperatures().core, Some(10.0));
+
+        // Later readings keep the override
+        probe.ingest_status_bytes(&data).unwrap();
+        assert_eq!(probe.virtual_temperatures().core, Some(15.0));
+
+        probe.override_core_sensor(None);
+        assert_eq!(probe.virtual_temperatures().core, Some(10.0));
+    }
 
-        let message = build_set_probe_color_request(color.to_raw());
-        self.send_uart_message(&message).await?;
+    #[test]
+    fn test_smoothing_keeps_unfiltered_readings() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.set_smoothing(VirtualSensor::Core, Some(SmoothingFilter::ema(0.5)));
 
-        let mut state = self.state.write();
-        state.color = color;
-        state.color_set_at = Some(Instant::now());
+        let mut data = status_bytes(0, 0);
+        for raw in [600, 800] {
+            let temperatures = ProbeTemperatures::from_raw([raw; 8]);
+            data[8..21].copy_from_slice(&temperatures.to_packed_bytes());
+            probe.ingest_status_bytes(&data).unwrap();
+        }
 
-        Ok(())
+        assert_eq!(probe.unfiltered_virtual_temperatures().core, Some(20.0));
+        assert_eq!(probe.virtual_temperatures().core, Some(15.0));
+        assert_eq!(
+            probe.virtual_temperatures().surface,
+            probe.unfiltered_virtual_temperatures().surface
+        );
     }
 
-    /// Read session information.
-    pub async fn read_session_info(&self) -> Result<SessionInfo> {
-        if !self.connection.is_connected() {
-            return Err(Error::NotConnected);
+    #[test]
+    fn test_sanity_limits_drop_corrupted_readings() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.set_sanity_limits(Some(SanityLimits::new()));
+
+        let mut data = status_bytes(0, 0);
+        for raw in [1600, 8000] {
+            let temperatures = ProbeTemperatures::from_raw([raw; 8]);
+            data[8..21].copy_from_slice(&temperatures.to_packed_bytes());
+            probe.ingest_status_bytes(&data).unwrap();
         }
 
-        let message = build_read_session_info_request();
-        self.send_uart_message(&message).await?;
+        assert_eq!(probe.current_temperatures().values[0].to_celsius(), None);
+        assert_eq!(probe.virtual_temperatures().core, None);
+        let stats = probe.sanity_stats();
+        assert!(stats.out_of_range > 0);
+        assert_eq!(stats.dropped, stats.out_of_range);
+    }
+
+    #[test]
+    fn test_sanity_limits_check_downloaded_logs() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.set_sanity_limits(Some(SanityLimits::new()));
+        let mut temperatures = ProbeTemperatures::from_raw([1600; 8]);
+        temperatures.values[0] = RawTemperature::from_celsius(380.0);
+        let point = LoggedDataPoint::new(7, temperatures);
+
+        probe.ingest_log_points([point.clone()]);
+        let logged = probe.temperature_log().data_point(7).unwrap().clone();
+        assert_eq!(logged.temperatures.values[0], RawTemperature::INVALID);
+        assert!(logged.temperatures.values[1].to_celsius().is_some());
+        let stats = probe.sanity_stats();
+        assert_eq!(
+            (stats.checked, stats.out_of_range, stats.dropped),
+            (8, 1, 1)
+        );
 
-        // In a real implementation, we'd wait for the response
-        // For now, return cached or default
-        Ok(self.state.read().session_info.clone().unwrap_or_default())
+        // Records already synced aren't checked again
+        probe.ingest_log_points([point]);
+        assert_eq!(probe.sanity_stats(), stats);
     }
 
-    // === Firmware ===
+    #[test]
+    fn test_subscribe_changes() {
+        use crate::snapshot::ChangedField;
 
-    /// Read firmware version.
-    pub async fn read_firmware_version(&self) -> Result<String> {
-        let _handler = self
-            .characteristics
-            .read()
-            .as_ref()
-            .ok_or(Error::NotConnected)?;
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        let mut changes = probe.subscribe_changes();
 
-        // This won't work because we can't clone CharacteristicHandler
-        // We need a different approach
-        Err(Error::NotSupported {
-            operation: "read_firmware_version requires connected state".to_string(),
-        })
+        probe.ingest_status_bytes(&status_bytes(2, 3)).unwrap();
+        let diff = changes.try_recv().unwrap();
+        assert!(diff.has_changed(ChangedField::ConnectionState));
+        assert!(diff.has_changed(ChangedField::ProbeId));
+        assert!(diff.has_changed(ChangedField::LogRange));
+
+        // Identical update produces no diff
+        probe.ingest_status_bytes(&status_bytes(2, 3)).unwrap();
+        assert!(changes.try_recv().is_err());
     }
 
-    /// Read hardware revision.
-    pub async fn read_hardware_revision(&self) -> Result<String> {
-        Err(Error::NotSupported {
-            operation: "read_hardware_revision requires connected state".to_string(),
-        })
+    #[test]
+    fn test_extremes_skip_instant_read() {
+        let mut state = ProbeState::new(0x1000_0001);
+        state.virtual_temperatures = VirtualTemperatures::new(Some(30.0), None, Some(160.0));
+        state.record_extremes();
+        state.virtual_temperatures = VirtualTemperatures::new(Some(40.0), None, Some(150.0));
+        state.record_extremes();
+
+        state.mode = ProbeMode::InstantRead;
+        state.virtual_temperatures = VirtualTemperatures::new(Some(70.0), None, Some(-20.0));
+        state.record_extremes();
+
+        let core = state.extremes.core.unwrap();
+        assert_eq!((core.min, core.max), (30.0, 40.0));
+        let ambient = state.extremes.ambient.unwrap();
+        assert_eq!((ambient.min, ambient.max), (150.0, 160.0));
     }
 
-    // === Internal ===
+    #[test]
+    fn test_heartbeat_reports_silence_once() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.ingest_status_bytes(&status_bytes(0, 0)).unwrap();
+        let mut events = probe.subscribe_connection_unhealthy();
+        let later = Instant::now() + Duration::from_secs(6);
+
+        // Off by default
+        assert!(probe.check_heartbeat_at(later).is_none());
+
+        probe.set_heartbeat(HeartbeatPolicy::new(Duration::from_secs(5)).with_recycle());
+        assert!(probe.check_heartbeat_at(Instant::now()).is_none());
+        let event = probe.check_heartbeat_at(later).unwrap();
+        assert!(event.silent_for >= Duration::from_secs(5));
+        // Only BLE connections are recycled
+        assert!(!event.recycling);
+        assert_eq!(events.try_recv().unwrap(), event);
+        assert!(probe.check_heartbeat_at(later).is_none());
+
+        // A new status ends the silence
+        probe.ingest_status_bytes(&status_bytes(0, 0)).unwrap();
+        assert!(probe
+            .check_heartbeat_at(Instant::now() + Duration::from_secs(6))
+            .is_some());
+    }
 
-    /// Send a UART message.
-    async fn send_uart_message(&self, message: &UartMessage) -> Result<()> {
-        let handler_guard = self.characteristics.read();
-        let handler = handler_guard.as_ref().ok_or(Error::NotConnected)?;
+    #[test]
+    fn test_ignored_notifications_keep_the_heartbeat_alive() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.set_heartbeat(HeartbeatPolicy::new(Duration::from_secs(5)).with_recycle());
+        probe.ingest_status_bytes(&status_bytes(0, 0)).unwrap();
+        for _ in 0..ProtocolMismatch::FAILURE_THRESHOLD {
+            assert!(probe.ingest_status_bytes(&[0u8; 8]).is_err());
+        }
+        assert!(probe.protocol_mismatch().is_some());
 
-        let data = message.to_bytes();
-        handler.write(&UART_RX_UUID, &data, false).await
+        // Long silent but for the ignored notification
+        let now = Instant::now();
+        probe.state.write().last_notification_at = now.checked_sub(Duration::from_secs(10));
+        probe.ingest_status_bytes(&status_bytes(0, 0)).unwrap();
+        assert!(probe.check_heartbeat_at(now).is_none());
+        assert!(probe
+            .check_heartbeat_at(now + Duration::from_secs(6))
+            .is_some());
     }
-}
 
-impl std::fmt::Debug for Probe {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Probe")
-            .field("identifier", &self.identifier)
-            .field("serial_number", &self.serial_number_string())
-            .field("connection_state", &self.connection_state())
-            .finish()
+    #[tokio::test]
+    async fn test_removal_alarm_is_set_when_prediction_done() {
+        let probe = Probe::with_transport("test", 0x1000_0001, Arc::new(NullTransport));
+        probe.arm_removal_alarm(true);
+        assert!(probe.is_removal_alarm_armed());
+
+        // Removal prediction done at a 57.0°C set point
+        let mut data = status_bytes(0, 0);
+        data[23] = PredictionState::RemovalPredictionDone as u8
+            | (PredictionMode::TimeToRemoval.to_raw() << 4);
+        data[24..26].copy_from_slice(&570u16.to_le_bytes());
+
+        // Nothing is written until the probe reports its alarms
+        probe.ingest_status_bytes(&data).unwrap();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert!(probe.alarm_config().is_none());
+        assert!(probe.command_history().is_empty());
+
+        let mut alarms = AlarmConfig::new();
+        alarms.set_core_low_alarm(40.0, true);
+        data.resize(94, 0);
+        data[50..94].copy_from_slice(&alarms.to_bytes());
+        data[23] = PredictionState::Predicting as u8;
+        probe.ingest_status_bytes(&data).unwrap();
+        data[23] = PredictionState::RemovalPredictionDone as u8
+            | (PredictionMode::TimeToRemoval.to_raw() << 4);
+        probe.ingest_status_bytes(&data).unwrap();
+
+        while probe.command_history().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let alarms = probe.alarm_config().unwrap();
+        assert!(alarms.core_high_alarm().set);
+        assert!((alarms.core_high_alarm().temperature - 57.0).abs() < 0.01);
+        // The other alarms are kept
+        assert!(alarms.core_low_alarm().set);
+        assert!(matches!(
+            probe.command_history()[0].command,
+            ConfigCommand::SetAlarms(_)
+        ));
+
+        probe.arm_removal_alarm(false);
+        assert!(!probe.is_removal_alarm_armed());
     }
 }
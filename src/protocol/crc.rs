@@ -1,13 +1,38 @@
 //! CRC calculation for UART messages.
 //!
-//! Uses CRC-16/CCITT-FALSE polynomial (0x1021) as specified in the
-//! Combustion probe BLE specification.
+//! UART messages are checked with CRC-16/CCITT-FALSE as specified in the
+//! Combustion probe BLE specification. Its parameters, in the usual
+//! catalogue notation, are:
+//!
+//! | Parameter | Value |
+//! |-----------|-------|
+//! | Width     | 16 bits |
+//! | Polynomial | `0x1021` ([`CRC_POLYNOMIAL`]) |
+//! | Initial value | `0xFFFF` ([`CRC_INITIAL`]) |
+//! | Reflect input / output | no |
+//! | Final XOR | `0x0000` |
+//! | Check (CRC of ASCII `"123456789"`) | `0x29B1` ([`CRC_CHECK`]) |
+//!
+//! In a UART message the CRC covers every byte after the CRC field, from
+//! the message type to the end of the payload, and is sent little-endian
+//! after the sync bytes; see
+//! [`uart_messages`](crate::protocol::uart_messages) for the framing.
+//!
+//! ```
+//! use combustion_rust_ble::protocol::crc::{calculate_crc, CRC_CHECK};
+//!
+//! assert_eq!(calculate_crc(b"123456789"), CRC_CHECK);
+//! ```
+
+/// CRC-16/CCITT-FALSE polynomial, without the implicit top bit.
+pub const CRC_POLYNOMIAL: u16 = 0x1021;
 
-/// CRC-16/CCITT-FALSE polynomial
-const CRC_POLYNOMIAL: u16 = 0x1021;
+/// Initial CRC value.
+pub const CRC_INITIAL: u16 = 0xFFFF;
 
-/// Initial CRC value
-const CRC_INITIAL: u16 = 0xFFFF;
+/// CRC of the ASCII bytes `"123456789"`, the standard check value for
+/// comparing implementations.
+pub const CRC_CHECK: u16 = 0x29B1;
 
 /// Calculate CRC-16 for UART message data.
 ///
@@ -27,11 +52,28 @@ const CRC_INITIAL: u16 = 0xFFFF;
 /// ```
 /// use combustion_rust_ble::protocol::calculate_crc;
 ///
-/// let data = [0xCA, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00];
-/// let crc = calculate_crc(&data);
+/// // Message type and payload length of a Read Session Info request
+/// assert_eq!(calculate_crc(&[0x03, 0x00]), 0x485C);
 /// ```
 pub fn calculate_crc(data: &[u8]) -> u16 {
-    let mut crc = CRC_INITIAL;
+    update_crc(CRC_INITIAL, data)
+}
+
+/// Continue a CRC over more data.
+///
+/// Lets a CRC be calculated over data arriving in pieces: start from
+/// [`CRC_INITIAL`] and feed each piece in order.
+///
+/// # Example
+///
+/// ```
+/// use combustion_rust_ble::protocol::crc::{update_crc, CRC_CHECK, CRC_INITIAL};
+///
+/// let crc = update_crc(CRC_INITIAL, b"12345");
+/// assert_eq!(update_crc(crc, b"6789"), CRC_CHECK);
+/// ```
+pub fn update_crc(crc: u16, data: &[u8]) -> u16 {
+    let mut crc = crc;
 
     for &byte in data {
         crc ^= (byte as u16) << 8;
@@ -50,6 +92,9 @@ pub fn calculate_crc(data: &[u8]) -> u16 {
 /// Verify that data with appended CRC is valid.
 ///
 /// The last two bytes of the data are treated as the CRC (little-endian).
+/// This is a trailing-CRC layout, not UART framing, where the CRC follows
+/// the sync bytes; use [`frame_crc`](crate::protocol::uart_messages::frame_crc)
+/// for UART messages.
 ///
 /// # Arguments
 ///
@@ -101,11 +146,19 @@ mod tests {
 
     #[test]
     fn test_crc_known_value() {
-        // Test with known data - sync byte and simple payload
-        let data = [0xCA, 0x01, 0x00];
-        let crc = calculate_crc(&data);
-        // The exact value depends on the algorithm implementation
-        assert_ne!(crc, 0); // Basic sanity check
+        assert_eq!(calculate_crc(b"123456789"), CRC_CHECK);
+        assert_eq!(calculate_crc(&[0xCA, 0x01, 0x00]), 0x1E9B);
+        // Set Probe ID 3: message type, payload length and payload
+        assert_eq!(calculate_crc(&[0x01, 0x01, 0x02]), 0xE8DF);
+    }
+
+    #[test]
+    fn test_crc_incremental() {
+        let data = b"123456789";
+        for split in 0..=data.len() {
+            let (head, tail) = data.split_at(split);
+            assert_eq!(update_crc(update_crc(CRC_INITIAL, head), tail), CRC_CHECK);
+        }
     }
 
     #[test]
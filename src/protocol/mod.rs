@@ -3,12 +3,15 @@
 //! This module contains the implementations for:
 //! - UART message parsing and construction
 //! - Probe status parsing
-//! - CRC calculation
+//! - CRC calculation and UART message framing
 
 pub mod crc;
 pub mod status;
 pub mod uart_messages;
 
-pub use crc::calculate_crc;
-pub use status::ProbeStatus;
-pub use uart_messages::{UartMessage, UartMessageHeader, UartMessageType};
+pub use crc::{calculate_crc, update_crc};
+pub use status::{ProbeStatus, StatusDecoder, StatusSection, StatusSections};
+pub use uart_messages::{
+    find_sync, frame_crc, frame_length, split_frame, UartMessage, UartMessageHeader,
+    UartMessageType, UartResponse, UART_SYNC_BYTES,
+};
@@ -71,179 +71,173 @@ impl ProbeStatus {
             data
         );
 
+        Self::check_size(data)?;
+        let mut status = Self {
+            min_sequence_number: 0,
+            max_sequence_number: 0,
+            temperatures: ProbeTemperatures::default(),
+            mode: ProbeMode::default(),
+            probe_id: ProbeId::default(),
+            color: ProbeColor::default(),
+            battery_status: BatteryStatus::default(),
+            virtual_temperatures: VirtualTemperatures::default(),
+            prediction: None,
+            food_safe_config: None,
+            food_safe_status: None,
+            overheating: Overheating::default(),
+            thermometer_preferences: None,
+            alarm_config: None,
+        };
+        status.reparse(data, StatusSections::ALL)?;
+        Ok(status)
+    }
+
+    /// Check that status data holds at least the mandatory sections.
+    fn check_size(data: &[u8]) -> Result<()> {
         if data.len() < Self::MIN_SIZE {
-            return Err(Error::InvalidData {
-                context: format!(
+            return Err(Error::invalid_payload(
+                format!(
                     "Status data too short: {} bytes (need at least {})",
                     data.len(),
                     Self::MIN_SIZE
                 ),
-            });
+                data,
+            ));
         }
+        Ok(())
+    }
 
-        // Bytes 0-3: Min sequence number (little-endian)
-        let min_sequence_number = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-
-        // Bytes 4-7: Max sequence number (little-endian)
-        let max_sequence_number = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    /// Reparse only the given sections of status data in place.
+    ///
+    /// Fields derived from several sections are refreshed when any of them
+    /// is reparsed, so the result matches [`parse`](Self::parse) as long as
+    /// the other sections are unchanged since this status was parsed.
+    fn reparse(&mut self, data: &[u8], sections: StatusSections) -> Result<()> {
+        use tracing::debug;
 
-        // Bytes 8-20: Packed temperatures (13 bytes for 8 x 13-bit values)
-        let temperatures = ProbeTemperatures::from_packed_bytes(&data[8..21]).ok_or_else(|| {
-            Error::InvalidData {
-                context: "Failed to parse packed temperatures".to_string(),
-            }
-        })?;
-
-        // Byte 21: Mode and ID (packed 8-bit field)
-        // Per Combustion Probe BLE spec:
-        // - Bits 0-1: Mode (0-3)
-        // - Bits 2-4: Color ID (0-7)
-        // - Bits 5-7: Probe ID (0-7, representing IDs 1-8)
-        let mode_id_byte = data[21];
-        let mode = ProbeMode::from_raw(mode_id_byte & 0x03);
-        let color = ProbeColor::from_raw((mode_id_byte >> 2) & 0x07);
-        let probe_id = ProbeId::from_raw((mode_id_byte >> 5) & 0x07);
-
-        // Byte 22: Battery and Virtual Sensors
-        // - Bit 0: Battery status (0=OK, 1=Low)
-        // - Bits 1-7: Virtual sensor configuration
-        let battery_status = BatteryStatus::from_raw(data[22] & 0x01);
+        Self::check_size(data)?;
 
-        // Virtual sensors are encoded in byte 22 bits 1-7 and use temperature data
-        let virtual_temperatures = Self::parse_virtual_temps_from_config(data[22], &temperatures);
+        if sections.contains(StatusSection::LogRange) {
+            // Bytes 0-3: Min sequence number (little-endian)
+            self.min_sequence_number = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
 
-        // Bytes 23-29: Prediction Status (7 bytes)
-        debug!(
-            "Parsing prediction from bytes 23-29: {:02X?}",
-            &data[23..30]
-        );
-        let prediction = Self::parse_prediction_status(&data[23..30]);
-        debug!("Parsed prediction: {:?}", prediction);
+            // Bytes 4-7: Max sequence number (little-endian)
+            self.max_sequence_number = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        }
 
-        // Bytes 30-39: Food Safe Data (10 bytes) - optional
-        let food_safe_config = if data.len() >= 40 {
-            debug!(
-                "Parsing food safe config from bytes 30-39: {:02X?}",
-                &data[30..40]
-            );
-            FoodSafeConfig::from_bytes(&data[30..40])
-        } else {
-            None
-        };
+        if sections.contains(StatusSection::ModeAndId) {
+            // Byte 21: Mode and ID (packed 8-bit field)
+            // Per Combustion Probe BLE spec:
+            // - Bits 0-1: Mode (0-3)
+            // - Bits 2-4: Color ID (0-7)
+            // - Bits 5-7: Probe ID (0-7, representing IDs 1-8)
+            let mode_id_byte = data[21];
+            self.mode = ProbeMode::from_raw(mode_id_byte & 0x03);
+            self.color = ProbeColor::from_raw((mode_id_byte >> 2) & 0x07);
+            self.probe_id = ProbeId::from_raw((mode_id_byte >> 5) & 0x07);
+        }
 
-        // Bytes 40-47: Food Safe Status (8 bytes) - optional
-        let food_safe_status = if data.len() >= 48 {
-            debug!(
-                "Parsing food safe status from bytes 40-47: {:02X?}",
-                &data[40..48]
-            );
-            FoodSafeStatus::from_bytes(&data[40..48])
-        } else {
-            None
-        };
+        // The active sensors depend on the mode
+        if sections.contains(StatusSection::Temperatures)
+            || sections.contains(StatusSection::ModeAndId)
+        {
+            // Bytes 8-20: Packed temperatures (13 bytes for 8 x 13-bit values)
+            self.temperatures = ProbeTemperatures::from_packed_bytes(&data[8..21])
+                .ok_or_else(|| Error::invalid_payload("Failed to parse packed temperatures", data))?
+                .with_active_mask(self.mode.active_sensor_mask());
+        }
 
-        // Byte 48: Overheating Sensors (if available)
-        let overheating = if data.len() > 48 {
-            Overheating::new(data[48])
-        } else {
-            Overheating::default()
-        };
+        if sections.contains(StatusSection::BatteryAndVirtualSensors) {
+            // Byte 22: Battery and Virtual Sensors
+            // - Bit 0: Battery status (0=OK, 1=Low)
+            // - Bits 1-7: Virtual sensor configuration
+            self.battery_status = BatteryStatus::from_raw(data[22] & 0x01);
+        }
 
-        // Byte 49: Thermometer Preferences (if available)
-        // - Bits 0-1: Power mode (0=Normal, 1=Always On)
-        // - Bits 2-7: Reserved
-        let thermometer_preferences = if data.len() > 49 {
-            debug!(
-                "Parsing thermometer preferences from byte 49: {:02X}",
-                data[49]
-            );
-            Some(ThermometerPreferences::from_byte(data[49]))
-        } else {
-            None
-        };
+        // Virtual sensors are encoded in byte 22 bits 1-7 and use temperature data
+        if sections.contains(StatusSection::Temperatures)
+            || sections.contains(StatusSection::ModeAndId)
+            || sections.contains(StatusSection::BatteryAndVirtualSensors)
+        {
+            let sensor_selection = VirtualSensorSelection::from_byte(data[22] >> 1);
+            self.virtual_temperatures =
+                VirtualTemperatures::from_selection(&self.temperatures, sensor_selection);
+        }
 
-        // Bytes 50-93: Alarm Status Arrays (if available)
-        // - Bytes 50-71: High Alarm Status array (22 bytes, 11 alarms × 2 bytes each)
-        // - Bytes 72-93: Low Alarm Status array (22 bytes, 11 alarms × 2 bytes each)
-        let alarm_config = if data.len() >= 94 {
+        if sections.contains(StatusSection::Prediction) {
+            // Bytes 23-29: Prediction Status (7 bytes)
             debug!(
-                "Parsing alarm config from bytes 50-93: {:02X?}",
-                &data[50..94]
+                "Parsing prediction from bytes 23-29: {:02X?}",
+                &data[23..30]
             );
-            AlarmConfig::from_bytes(&data[50..94])
-        } else {
-            None
-        };
-
-        Ok(Self {
-            min_sequence_number,
-            max_sequence_number,
-            temperatures,
-            mode,
-            probe_id,
-            color,
-            battery_status,
-            virtual_temperatures,
-            prediction,
-            food_safe_config,
-            food_safe_status,
-            overheating,
-            thermometer_preferences,
-            alarm_config,
-        })
-    }
+            self.prediction = Self::parse_prediction_status(&data[23..30]);
+            debug!("Parsed prediction: {:?}", self.prediction);
+        }
 
-    /// Parse virtual temperatures from configuration byte and raw temperatures.
-    ///
-    /// The virtual sensor configuration is in byte 22 bits 1-7:
-    /// - Bits 1-3: Virtual Core sensor index (0=T1 through 5=T6)
-    /// - Bits 4-5: Virtual Surface sensor offset (0=T4, 1=T5, 2=T6, 3=T7)
-    /// - Bits 6-7: Virtual Ambient sensor offset (0=T5, 1=T6, 2=T7, 3=T8)
-    fn parse_virtual_temps_from_config(
-        config_byte: u8,
-        temperatures: &ProbeTemperatures,
-    ) -> VirtualTemperatures {
-        // Extract virtual sensor indices
-        let core_index = ((config_byte >> 1) & 0x07) as usize;
-        let surface_offset = ((config_byte >> 4) & 0x03) as usize;
-        let ambient_offset = ((config_byte >> 6) & 0x03) as usize;
-
-        // Convert offsets to actual sensor indices
-        let surface_index = 3 + surface_offset; // T4=3, T5=4, T6=5, T7=6
-        let ambient_index = 4 + ambient_offset; // T5=4, T6=5, T7=6, T8=7
-
-        // Get temperatures (indices are 0-based)
-        let core = if core_index < 8 {
-            temperatures.to_celsius().get(core_index).copied().flatten()
-        } else {
-            None
-        };
+        if sections.contains(StatusSection::FoodSafe) {
+            // Bytes 30-39: Food Safe Data (10 bytes) - optional
+            self.food_safe_config = if data.len() >= 40 {
+                debug!(
+                    "Parsing food safe config from bytes 30-39: {:02X?}",
+                    &data[30..40]
+                );
+                FoodSafeConfig::from_bytes(&data[30..40])
+            } else {
+                None
+            };
+
+            // Bytes 40-47: Food Safe Status (8 bytes) - optional
+            self.food_safe_status = if data.len() >= 48 {
+                debug!(
+                    "Parsing food safe status from bytes 40-47: {:02X?}",
+                    &data[40..48]
+                );
+                FoodSafeStatus::from_bytes(&data[40..48])
+            } else {
+                None
+            };
+        }
 
-        let surface = if surface_index < 8 {
-            temperatures
-                .to_celsius()
-                .get(surface_index)
-                .copied()
-                .flatten()
-        } else {
-            None
-        };
+        if sections.contains(StatusSection::Overheating) {
+            // Byte 48: Overheating Sensors (if available)
+            self.overheating = if data.len() > 48 {
+                Overheating::new(data[48])
+            } else {
+                Overheating::default()
+            };
+        }
 
-        let ambient = if ambient_index < 8 {
-            temperatures
-                .to_celsius()
-                .get(ambient_index)
-                .copied()
-                .flatten()
-        } else {
-            None
-        };
+        if sections.contains(StatusSection::Preferences) {
+            // Byte 49: Thermometer Preferences (if available)
+            // - Bits 0-1: Power mode (0=Normal, 1=Always On)
+            // - Bits 2-7: Reserved
+            self.thermometer_preferences = if data.len() > 49 {
+                debug!(
+                    "Parsing thermometer preferences from byte 49: {:02X}",
+                    data[49]
+                );
+                Some(ThermometerPreferences::from_byte(data[49]))
+            } else {
+                None
+            };
+        }
 
-        // Create sensor selection with the actual indices used
-        let sensor_selection =
-            VirtualSensorSelection::new(core_index as u8, surface_index as u8, ambient_index as u8);
+        if sections.contains(StatusSection::Alarms) {
+            // Bytes 50-93: Alarm Status Arrays (if available)
+            // - Bytes 50-71: High Alarm Status array (22 bytes, 11 alarms × 2 bytes each)
+            // - Bytes 72-93: Low Alarm Status array (22 bytes, 11 alarms × 2 bytes each)
+            self.alarm_config = if data.len() >= 94 {
+                debug!(
+                    "Parsing alarm config from bytes 50-93: {:02X?}",
+                    &data[50..94]
+                );
+                AlarmConfig::from_bytes(&data[50..94])
+            } else {
+                None
+            };
+        }
 
-        VirtualTemperatures::with_selection(core, surface, ambient, sensor_selection)
+        Ok(())
     }
 
     /// Parse prediction status from 7-byte packed structure.
@@ -310,6 +304,7 @@ impl ProbeStatus {
             estimated_core_temperature,
             seconds_since_prediction_start: 0, // Not in status notification
             core_sensor_index,
+            carryover_temperature: 0.0,
         })
     }
 
@@ -362,10 +357,193 @@ impl ProbeStatus {
     }
 }
 
+/// A section of the status notification layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusSection {
+    /// Bytes 0-7: Min and max sequence numbers.
+    LogRange,
+    /// Bytes 8-20: Raw temperature data.
+    Temperatures,
+    /// Byte 21: Mode, color and probe ID.
+    ModeAndId,
+    /// Byte 22: Battery status and virtual sensor selection.
+    BatteryAndVirtualSensors,
+    /// Bytes 23-29: Prediction status.
+    Prediction,
+    /// Bytes 30-47: Food safe data and status.
+    FoodSafe,
+    /// Byte 48: Overheating sensors.
+    Overheating,
+    /// Byte 49: Thermometer preferences.
+    Preferences,
+    /// Bytes 50-93: High and low alarm status arrays.
+    Alarms,
+}
+
+impl StatusSection {
+    /// All sections, in layout order.
+    pub const ALL: [StatusSection; 9] = [
+        StatusSection::LogRange,
+        StatusSection::Temperatures,
+        StatusSection::ModeAndId,
+        StatusSection::BatteryAndVirtualSensors,
+        StatusSection::Prediction,
+        StatusSection::FoodSafe,
+        StatusSection::Overheating,
+        StatusSection::Preferences,
+        StatusSection::Alarms,
+    ];
+
+    /// Get the byte range of this section in the status data.
+    pub fn range(self) -> std::ops::Range<usize> {
+        match self {
+            StatusSection::LogRange => 0..8,
+            StatusSection::Temperatures => 8..21,
+            StatusSection::ModeAndId => 21..22,
+            StatusSection::BatteryAndVirtualSensors => 22..23,
+            StatusSection::Prediction => 23..30,
+            StatusSection::FoodSafe => 30..48,
+            StatusSection::Overheating => 48..49,
+            StatusSection::Preferences => 49..50,
+            StatusSection::Alarms => 50..94,
+        }
+    }
+
+    /// Get the bit of this section in [`StatusSections`].
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// A set of status notification sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StatusSections(u16);
+
+impl StatusSections {
+    /// No sections.
+    pub const NONE: StatusSections = StatusSections(0);
+
+    /// Every section.
+    pub const ALL: StatusSections = StatusSections((1 << StatusSection::ALL.len()) - 1);
+
+    /// Get the sections whose bytes differ between two payloads.
+    ///
+    /// A section present in only one of the payloads counts as changed.
+    pub fn between(previous: &[u8], current: &[u8]) -> Self {
+        fn bytes(data: &[u8], range: std::ops::Range<usize>) -> Option<&[u8]> {
+            data.get(range.start.min(data.len())..range.end.min(data.len()))
+        }
+
+        let mut sections = Self::NONE;
+        for section in StatusSection::ALL {
+            let range = section.range();
+            if bytes(previous, range.clone()) != bytes(current, range) {
+                sections.insert(section);
+            }
+        }
+        sections
+    }
+
+    /// Check if a section is in the set.
+    pub fn contains(self, section: StatusSection) -> bool {
+        self.0 & section.bit() != 0
+    }
+
+    /// Add a section to the set.
+    pub fn insert(&mut self, section: StatusSection) {
+        self.0 |= section.bit();
+    }
+
+    /// Check if the set is empty.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check if only the log range and temperatures are in the set.
+    ///
+    /// This is the common case between notifications, where the probe has
+    /// taken a new sample and nothing was configured.
+    pub fn only_readings(self) -> bool {
+        let readings = StatusSection::LogRange.bit() | StatusSection::Temperatures.bit();
+        self.0 & !readings == 0
+    }
+}
+
+/// Decoder for a stream of status notifications from one probe.
+///
+/// Consecutive notifications usually differ only in the sequence numbers and
+/// temperatures. The decoder compares each payload against the previous one
+/// and reparses only the sections that changed, updating its status in
+/// place instead of building a new one.
+#[derive(Debug, Clone, Default)]
+pub struct StatusDecoder {
+    /// Previous payload.
+    previous: Vec<u8>,
+    /// Status decoded from the previous payload.
+    status: Option<ProbeStatus>,
+}
+
+impl StatusDecoder {
+    /// Create a decoder with no previous payload.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode a status notification.
+    ///
+    /// Returns the decoded status and the sections that changed since the
+    /// previous notification. The first payload, or one of a different
+    /// length, is parsed in full and reports every section as changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidData`] if the payload can't be parsed. The
+    /// decoder is then reset so the next payload is parsed in full.
+    pub fn decode(&mut self, data: &[u8]) -> Result<(&ProbeStatus, StatusSections)> {
+        let sections = match &mut self.status {
+            Some(status) if self.previous.len() == data.len() => {
+                let sections = StatusSections::between(&self.previous, data);
+                if let Err(e) = status.reparse(data, sections) {
+                    self.reset();
+                    return Err(e);
+                }
+                sections
+            }
+            _ => match ProbeStatus::parse(data) {
+                Ok(status) => {
+                    self.status = Some(status);
+                    StatusSections::ALL
+                }
+                Err(e) => {
+                    self.reset();
+                    return Err(e);
+                }
+            },
+        };
+        self.previous.clear();
+        self.previous.extend_from_slice(data);
+        let status = self.status.as_ref().expect("status decoded above");
+        Ok((status, sections))
+    }
+
+    /// Get the status decoded from the previous payload.
+    pub fn status(&self) -> Option<&ProbeStatus> {
+        self.status.as_ref()
+    }
+
+    /// Forget the previous payload, so the next one is parsed in full.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+        self.status = None;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // The fixture spells out every bit field, including the empty ones
+    #[allow(clippy::identity_op, clippy::erasing_op)]
     fn create_test_status_data() -> Vec<u8> {
         // Minimum size is 30 bytes (through prediction status)
         let mut data = vec![0u8; 50];
@@ -441,4 +619,62 @@ mod tests {
         assert_eq!(status.available_log_count(), 91);
         assert!(status.has_logs());
     }
+
+    #[test]
+    fn test_status_sections_between() {
+        let data = create_test_status_data();
+        let mut next = data.clone();
+        assert!(StatusSections::between(&data, &next).is_empty());
+
+        next[4..8].copy_from_slice(&101u32.to_le_bytes());
+        next[8] = 0x55;
+        let sections = StatusSections::between(&data, &next);
+        assert!(sections.contains(StatusSection::LogRange));
+        assert!(sections.contains(StatusSection::Temperatures));
+        assert!(!sections.contains(StatusSection::Prediction));
+        assert!(sections.only_readings());
+
+        next[49] = 0x01;
+        let sections = StatusSections::between(&data, &next);
+        assert!(sections.contains(StatusSection::Preferences));
+        assert!(!sections.only_readings());
+
+        // Sections missing from the shorter payload count as changed
+        let sections = StatusSections::between(&data[..30], &data);
+        assert!(!sections.contains(StatusSection::Prediction));
+        assert!(sections.contains(StatusSection::FoodSafe));
+        assert!(sections.contains(StatusSection::Preferences));
+    }
+
+    #[test]
+    fn test_status_decoder_matches_full_parse() {
+        let mut decoder = StatusDecoder::new();
+        let data = create_test_status_data();
+        let (_, sections) = decoder.decode(&data).unwrap();
+        assert_eq!(sections, StatusSections::ALL);
+
+        // New sample only
+        let mut next = data.clone();
+        next[4..8].copy_from_slice(&101u32.to_le_bytes());
+        next[8..21].copy_from_slice(&[0x44; 13]);
+        let (status, sections) = decoder.decode(&next).unwrap();
+        assert!(sections.only_readings());
+        assert_eq!(*status, ProbeStatus::parse(&next).unwrap());
+
+        // Mode change re-derives the active sensors and virtual temperatures
+        next[21] = 0b0000_0101;
+        next[22] = 0b0000_0110;
+        let (status, sections) = decoder.decode(&next).unwrap();
+        assert!(sections.contains(StatusSection::ModeAndId));
+        assert!(!sections.contains(StatusSection::Temperatures));
+        assert_eq!(*status, ProbeStatus::parse(&next).unwrap());
+
+        // A shorter payload is parsed in full
+        let (status, sections) = decoder.decode(&next[..40]).unwrap();
+        assert_eq!(sections, StatusSections::ALL);
+        assert_eq!(*status, ProbeStatus::parse(&next[..40]).unwrap());
+
+        assert!(decoder.decode(&[0u8; 10]).is_err());
+        assert!(decoder.status().is_none());
+    }
 }
@@ -5,6 +5,34 @@
 //! Message format per the Predictive Probe BLE Specification:
 //! - Request: Sync(2) + CRC(2) + MsgType(1) + PayloadLen(1) + Payload
 //! - Response: Sync(2) + CRC(2) + MsgType(1) + Success(1) + PayloadLen(1) + Payload
+//!
+//! The CRC, described in [`crc`](crate::protocol::crc), covers the bytes
+//! from the message type to the end of the payload and is sent
+//! little-endian. Responses are told apart from requests by the high bit of
+//! the message type.
+//!
+//! [`find_sync`], [`frame_length`], [`frame_crc`] and [`split_frame`] pick
+//! messages out of a byte stream, for transports that don't deliver one
+//! message per write:
+//!
+//! ```
+//! use combustion_rust_ble::protocol::uart_messages::{
+//!     build_set_probe_id_request, frame_crc, split_frame, UartMessage,
+//! };
+//!
+//! let mut stream = vec![0x00, 0x17];
+//! stream.extend(build_set_probe_id_request(3).to_bytes());
+//! stream.extend([0xCA, 0xFE]);
+//!
+//! let (frame, rest) = split_frame(&stream).unwrap();
+//! assert_eq!(frame, [0xCA, 0xFE, 0xDF, 0xE8, 0x01, 0x01, 0x02]);
+//! assert_eq!(frame_crc(frame), Some(0xE8DF));
+//! assert!(UartMessage::parse(frame).is_ok());
+//!
+//! // The next message has not fully arrived
+//! assert_eq!(rest, [0xCA, 0xFE]);
+//! assert!(split_frame(rest).is_none());
+//! ```
 
 use crate::error::{Error, Result};
 use crate::protocol::crc::calculate_crc;
@@ -16,71 +44,75 @@ pub const UART_SYNC_BYTES: [u8; 2] = [0xCA, 0xFE];
 ///
 /// Message type values per the Predictive Probe BLE Specification:
 /// <https://github.com/combustion-inc/combustion-documentation/blob/main/probe_ble_specification.rst>
+///
+/// Message types this library does not recognize, for example ones added by
+/// newer firmware, are carried as [`Unknown`](Self::Unknown) with the value
+/// received.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(u8)]
+#[non_exhaustive]
 pub enum UartMessageType {
     /// Set probe ID request (0x01).
-    SetProbeId = 0x01,
-    /// Set probe ID response.
-    SetProbeIdResponse = 0x81,
+    SetProbeId,
+    /// Set probe ID response (0x81).
+    SetProbeIdResponse,
 
     /// Set probe color request (0x02).
-    SetProbeColor = 0x02,
-    /// Set probe color response.
-    SetProbeColorResponse = 0x82,
+    SetProbeColor,
+    /// Set probe color response (0x82).
+    SetProbeColorResponse,
 
     /// Read session information request (0x03).
-    ReadSessionInfo = 0x03,
-    /// Read session information response.
-    ReadSessionInfoResponse = 0x83,
+    ReadSessionInfo,
+    /// Read session information response (0x83).
+    ReadSessionInfoResponse,
 
     /// Read temperature logs request (0x04).
-    ReadLogs = 0x04,
-    /// Read temperature logs response.
-    ReadLogsResponse = 0x84,
+    ReadLogs,
+    /// Read temperature logs response (0x84).
+    ReadLogsResponse,
 
     /// Set prediction request (0x05).
-    SetPrediction = 0x05,
-    /// Set prediction response.
-    SetPredictionResponse = 0x85,
+    SetPrediction,
+    /// Set prediction response (0x85).
+    SetPredictionResponse,
 
     /// Read over-temperature request (0x06).
-    ReadOverTemperature = 0x06,
-    /// Read over-temperature response.
-    ReadOverTemperatureResponse = 0x86,
+    ReadOverTemperature,
+    /// Read over-temperature response (0x86).
+    ReadOverTemperatureResponse,
 
     /// Configure food safety request (0x07).
-    ConfigureFoodSafe = 0x07,
-    /// Configure food safety response.
-    ConfigureFoodSafeResponse = 0x87,
+    ConfigureFoodSafe,
+    /// Configure food safety response (0x87).
+    ConfigureFoodSafeResponse,
 
     /// Reset food safety request (0x08).
-    ResetFoodSafe = 0x08,
-    /// Reset food safety response.
-    ResetFoodSafeResponse = 0x88,
+    ResetFoodSafe,
+    /// Reset food safety response (0x88).
+    ResetFoodSafeResponse,
 
     /// Set power mode request (0x09).
-    SetPowerMode = 0x09,
-    /// Set power mode response.
-    SetPowerModeResponse = 0x89,
+    SetPowerMode,
+    /// Set power mode response (0x89).
+    SetPowerModeResponse,
 
     /// Reset thermometer request (0x0A).
-    ResetThermometer = 0x0A,
-    /// Reset thermometer response.
-    ResetThermometerResponse = 0x8A,
+    ResetThermometer,
+    /// Reset thermometer response (0x8A).
+    ResetThermometerResponse,
 
     /// Set high/low alarms request (0x0B).
-    SetHighLowAlarms = 0x0B,
-    /// Set high/low alarms response.
-    SetHighLowAlarmsResponse = 0x8B,
+    SetHighLowAlarms,
+    /// Set high/low alarms response (0x8B).
+    SetHighLowAlarmsResponse,
 
     /// Silence alarms request (0x0C).
-    SilenceAlarms = 0x0C,
-    /// Silence alarms response.
-    SilenceAlarmsResponse = 0x8C,
+    SilenceAlarms,
+    /// Silence alarms response (0x8C).
+    SilenceAlarmsResponse,
 
-    /// Unknown message type.
-    Unknown = 0xFF,
+    /// Unrecognized message type, as received.
+    Unknown(u8),
 }
 
 // Note: Cancel Prediction uses SetPrediction (0x05) with mode=0.
@@ -114,18 +146,44 @@ impl UartMessageType {
             0x8B => Self::SetHighLowAlarmsResponse,
             0x0C => Self::SilenceAlarms,
             0x8C => Self::SilenceAlarmsResponse,
-            _ => Self::Unknown,
+            _ => Self::Unknown(value),
         }
     }
 
     /// Convert to raw byte value.
     pub fn to_raw(&self) -> u8 {
-        *self as u8
+        match self {
+            Self::SetProbeId => 0x01,
+            Self::SetProbeIdResponse => 0x81,
+            Self::SetProbeColor => 0x02,
+            Self::SetProbeColorResponse => 0x82,
+            Self::ReadSessionInfo => 0x03,
+            Self::ReadSessionInfoResponse => 0x83,
+            Self::ReadLogs => 0x04,
+            Self::ReadLogsResponse => 0x84,
+            Self::SetPrediction => 0x05,
+            Self::SetPredictionResponse => 0x85,
+            Self::ReadOverTemperature => 0x06,
+            Self::ReadOverTemperatureResponse => 0x86,
+            Self::ConfigureFoodSafe => 0x07,
+            Self::ConfigureFoodSafeResponse => 0x87,
+            Self::ResetFoodSafe => 0x08,
+            Self::ResetFoodSafeResponse => 0x88,
+            Self::SetPowerMode => 0x09,
+            Self::SetPowerModeResponse => 0x89,
+            Self::ResetThermometer => 0x0A,
+            Self::ResetThermometerResponse => 0x8A,
+            Self::SetHighLowAlarms => 0x0B,
+            Self::SetHighLowAlarmsResponse => 0x8B,
+            Self::SilenceAlarms => 0x0C,
+            Self::SilenceAlarmsResponse => 0x8C,
+            Self::Unknown(value) => *value,
+        }
     }
 
     /// Check if this is a response message.
     pub fn is_response(&self) -> bool {
-        (*self as u8) & 0x80 != 0
+        self.to_raw() & 0x80 != 0
     }
 
     /// Check if this is a request message.
@@ -139,7 +197,7 @@ impl UartMessageType {
             return None;
         }
 
-        Some(Self::from_raw((*self as u8) | 0x80))
+        Some(Self::from_raw(self.to_raw() | 0x80))
     }
 }
 
@@ -157,6 +215,13 @@ pub struct UartMessageHeader {
 impl UartMessageHeader {
     /// Header size in bytes (sync + CRC + msg_type + payload_len).
     pub const SIZE: usize = 6;
+    /// Response header size in bytes (sync + CRC + msg_type + success +
+    /// payload_len).
+    pub const RESPONSE_SIZE: usize = 7;
+    /// Offset of the CRC, after the sync bytes.
+    pub const CRC_OFFSET: usize = 2;
+    /// Offset of the first byte covered by the CRC, the message type.
+    pub const CRC_START: usize = 4;
     /// Size of message type + payload length (for CRC calculation).
     pub const CRC_DATA_SIZE: usize = 2;
 
@@ -171,16 +236,18 @@ impl UartMessageHeader {
     /// Parse a header from bytes (request format).
     pub fn parse(data: &[u8]) -> Result<Self> {
         if data.len() < Self::SIZE {
-            return Err(Error::InvalidData {
-                context: format!("Header too short: {} bytes", data.len()),
-            });
+            return Err(Error::invalid_payload(
+                format!("Header too short: {} bytes", data.len()),
+                data,
+            ));
         }
 
         // Check sync bytes
         if data[0] != UART_SYNC_BYTES[0] || data[1] != UART_SYNC_BYTES[1] {
-            return Err(Error::InvalidData {
-                context: format!("Invalid sync bytes: {:#04x} {:#04x}", data[0], data[1]),
-            });
+            return Err(Error::invalid_payload(
+                format!("Invalid sync bytes: {:#04x} {:#04x}", data[0], data[1]),
+                data,
+            ));
         }
 
         // CRC is at bytes 2-3 (will be verified separately)
@@ -216,36 +283,31 @@ impl UartMessage {
     pub fn parse(data: &[u8]) -> Result<Self> {
         // Need at least header (6 bytes)
         if data.len() < UartMessageHeader::SIZE {
-            return Err(Error::InvalidData {
-                context: format!("Message too short: {} bytes", data.len()),
-            });
+            return Err(Error::invalid_payload(
+                format!("Message too short: {} bytes", data.len()),
+                data,
+            ));
         }
 
         let header = UartMessageHeader::parse(data)?;
 
         let expected_len = UartMessageHeader::SIZE + header.payload_length as usize;
         if data.len() < expected_len {
-            return Err(Error::InvalidData {
-                context: format!(
+            return Err(Error::invalid_payload(
+                format!(
                     "Message incomplete: have {} bytes, need {}",
                     data.len(),
                     expected_len
                 ),
-            });
+                data,
+            ));
         }
 
         // Extract CRC from bytes 2-3
         let received_crc = u16::from_le_bytes([data[2], data[3]]);
 
-        // Build data for CRC verification: msg_type + payload_len + payload
-        let mut crc_data = Vec::with_capacity(2 + header.payload_length as usize);
-        crc_data.push(data[4]); // msg_type
-        crc_data.push(data[5]); // payload_len
-        if header.payload_length > 0 {
-            crc_data.extend_from_slice(&data[6..6 + header.payload_length as usize]);
-        }
-
-        let calculated_crc = calculate_crc(&crc_data);
+        // CRC covers msg_type + payload_len + payload
+        let calculated_crc = calculate_crc(&data[UartMessageHeader::CRC_START..expected_len]);
         if received_crc != calculated_crc {
             return Err(Error::CrcMismatch {
                 expected: calculated_crc,
@@ -303,6 +365,137 @@ impl UartMessage {
     }
 }
 
+/// A UART response from the probe.
+///
+/// Format: Sync(2) + CRC(2) + MsgType(1) + Success(1) + PayloadLen(1) + Payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UartResponse {
+    /// Response message type.
+    pub message_type: UartMessageType,
+    /// Whether the probe carried out the request.
+    pub success: bool,
+    /// Response payload.
+    pub payload: Vec<u8>,
+}
+
+impl UartResponse {
+    /// Create a response.
+    pub fn new(message_type: UartMessageType, success: bool, payload: Vec<u8>) -> Self {
+        Self {
+            message_type,
+            success,
+            payload,
+        }
+    }
+
+    /// Parse a complete response from bytes, such as a frame from
+    /// [`split_frame`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidData`] if the bytes are not a complete
+    /// response, or [`Error::CrcMismatch`] if the CRC doesn't match.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let header = UartMessageHeader::parse(data)?;
+        if !header.message_type.is_response() {
+            return Err(Error::invalid_payload(
+                format!("{:?} is not a response", header.message_type),
+                data,
+            ));
+        }
+        let length = frame_length(data)
+            .filter(|length| data.len() >= *length)
+            .ok_or_else(|| {
+                Error::invalid_payload(format!("Response incomplete: {} bytes", data.len()), data)
+            })?;
+
+        let received_crc = u16::from_le_bytes([data[2], data[3]]);
+        let calculated_crc = calculate_crc(&data[UartMessageHeader::CRC_START..length]);
+        if received_crc != calculated_crc {
+            return Err(Error::CrcMismatch {
+                expected: calculated_crc,
+                actual: received_crc,
+            });
+        }
+
+        Ok(Self {
+            message_type: header.message_type,
+            success: data[5] == 1,
+            payload: data[UartMessageHeader::RESPONSE_SIZE..length].to_vec(),
+        })
+    }
+
+    /// Serialize the response to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut covered = Vec::with_capacity(3 + self.payload.len());
+        covered.push(self.message_type.to_raw());
+        covered.push(u8::from(self.success));
+        covered.push(self.payload.len() as u8);
+        covered.extend_from_slice(&self.payload);
+
+        let mut data = Vec::with_capacity(UartMessageHeader::CRC_START + covered.len());
+        data.extend_from_slice(&UART_SYNC_BYTES);
+        data.extend_from_slice(&calculate_crc(&covered).to_le_bytes());
+        data.extend_from_slice(&covered);
+        data
+    }
+}
+
+// Framing helpers
+
+/// Find where the next UART message may start in a byte stream.
+///
+/// Returns the offset of the first [`UART_SYNC_BYTES`], or of a first sync
+/// byte ending `data`, whose second byte may arrive with the next chunk.
+/// Bytes before the offset can't start a message and can be discarded.
+pub fn find_sync(data: &[u8]) -> Option<usize> {
+    data.windows(2)
+        .position(|pair| pair == UART_SYNC_BYTES)
+        .or_else(|| (data.last() == Some(&UART_SYNC_BYTES[0])).then(|| data.len() - 1))
+}
+
+/// Get the length of the UART message at the start of `data`, request or
+/// response.
+///
+/// Returns `None` if `data` doesn't start with the sync bytes or its
+/// header hasn't fully arrived.
+pub fn frame_length(data: &[u8]) -> Option<usize> {
+    if !data.starts_with(&UART_SYNC_BYTES) {
+        return None;
+    }
+    let message_type = UartMessageType::from_raw(*data.get(UartMessageHeader::CRC_START)?);
+    let header_size = if message_type.is_response() {
+        UartMessageHeader::RESPONSE_SIZE
+    } else {
+        UartMessageHeader::SIZE
+    };
+    let payload_length = *data.get(header_size - 1)?;
+    Some(header_size + payload_length as usize)
+}
+
+/// Calculate the CRC of the UART message at the start of `data`.
+///
+/// This is the CRC the message should carry at
+/// [`UartMessageHeader::CRC_OFFSET`]. Returns `None` if the message hasn't
+/// fully arrived.
+pub fn frame_crc(data: &[u8]) -> Option<u16> {
+    let length = frame_length(data)?;
+    let covered = data.get(UartMessageHeader::CRC_START..length)?;
+    Some(calculate_crc(covered))
+}
+
+/// Split the first complete UART message off a byte stream.
+///
+/// Skips bytes before the first sync bytes and returns the message and the
+/// bytes after it, or `None` until a complete message has arrived. The CRC
+/// is not checked; if it doesn't match, the sync bytes may have been noise,
+/// so scan again from just after them.
+pub fn split_frame(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let data = &data[find_sync(data)?..];
+    let length = frame_length(data)?;
+    (data.len() >= length).then(|| data.split_at(length))
+}
+
 // Request builders
 
 /// Build a Read Session Info request.
@@ -367,9 +560,9 @@ pub fn build_configure_food_safe_simplified_request(product_type: u8) -> UartMes
     // Rest of the fields are zeroed as they're ignored in simplified mode
     let mut payload = [0u8; 10];
     // Byte 0: Mode (bits 0-2) = 0, Product low bits (bits 3-7)
-    payload[0] = (product_type as u8 & 0x1F) << 3;
+    payload[0] = (product_type & 0x1F) << 3;
     // Byte 1: Product high bits (bits 0-4), Serving (bits 5-7) = 0
-    payload[1] = (product_type >> 5) as u8 & 0x1F;
+    payload[1] = (product_type >> 5) & 0x1F;
     UartMessage::new(UartMessageType::ConfigureFoodSafe, payload.to_vec())
 }
 
@@ -434,7 +627,17 @@ mod tests {
             UartMessageType::from_raw(0x83),
             UartMessageType::ReadSessionInfoResponse
         );
-        assert_eq!(UartMessageType::from_raw(0xFF), UartMessageType::Unknown);
+        assert_eq!(
+            UartMessageType::from_raw(0xFF),
+            UartMessageType::Unknown(0xFF)
+        );
+        assert_eq!(
+            UartMessageType::from_raw(0x0D),
+            UartMessageType::Unknown(0x0D)
+        );
+        for raw in 0..=u8::MAX {
+            assert_eq!(UartMessageType::from_raw(raw).to_raw(), raw);
+        }
     }
 
     #[test]
@@ -515,6 +718,39 @@ mod tests {
         assert_eq!(bytes.len(), 7);
     }
 
+    #[test]
+    fn test_framing_helpers() {
+        let request = build_set_probe_id_request(3).to_bytes();
+        assert_eq!(request, [0xCA, 0xFE, 0xDF, 0xE8, 0x01, 0x01, 0x02]);
+        assert_eq!(frame_length(&request), Some(7));
+        assert_eq!(frame_crc(&request), Some(0xE8DF));
+        assert_eq!(frame_length(&request[..5]), None);
+        assert_eq!(frame_crc(&request[..6]), None);
+        assert_eq!(frame_length(&request[1..]), None);
+
+        // Response: success byte before the payload length
+        let mut response = vec![0xCA, 0xFE, 0x00, 0x00, 0x81, 0x00, 0x00];
+        let crc = calculate_crc(&response[4..]);
+        response[2..4].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(frame_length(&response), Some(7));
+        assert_eq!(frame_crc(&response), Some(crc));
+
+        assert_eq!(find_sync(&[0x00, 0xCA, 0xCA, 0xFE]), Some(2));
+        assert_eq!(find_sync(&[0x00, 0xFE, 0xCA]), Some(2));
+        assert_eq!(find_sync(&[0x00, 0xFE]), None);
+        assert_eq!(find_sync(&[]), None);
+
+        let mut stream = vec![0xFE, 0xCA];
+        stream.extend(&request);
+        stream.extend(&response);
+        let (frame, rest) = split_frame(&stream).unwrap();
+        assert_eq!(frame, request);
+        let (frame, rest) = split_frame(rest).unwrap();
+        assert_eq!(frame, response);
+        assert!(rest.is_empty());
+        assert!(split_frame(&request[..6]).is_none());
+    }
+
     #[test]
     fn test_build_requests() {
         let msg = build_read_session_info_request();
@@ -593,4 +829,25 @@ mod tests {
         assert_eq!(msg.message_type(), UartMessageType::SilenceAlarms);
         assert!(msg.payload.is_empty());
     }
+
+    #[test]
+    fn test_response_roundtrip() {
+        let response = UartResponse::new(
+            UartMessageType::ReadSessionInfoResponse,
+            true,
+            vec![0x78, 0x56, 0x34, 0x12, 0xE8, 0x03],
+        );
+        let bytes = response.to_bytes();
+        assert_eq!(frame_length(&bytes), Some(bytes.len()));
+        assert_eq!(UartResponse::parse(&bytes).unwrap(), response);
+
+        let mut corrupt = bytes.clone();
+        corrupt[7] ^= 0xFF;
+        assert!(matches!(
+            UartResponse::parse(&corrupt),
+            Err(Error::CrcMismatch { .. })
+        ));
+        assert!(UartResponse::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(UartResponse::parse(&build_read_session_info_request().to_bytes()).is_err());
+    }
 }
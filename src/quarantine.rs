@@ -0,0 +1,200 @@
+//! Capture of unparseable probe payloads.
+//!
+//! Payloads that fail to parse are normally logged and dropped. A
+//! [`PayloadQuarantine`] keeps the most recent ones along with the parse
+//! error, so they can be attached to bug reports. Quarantine is opt-in: it
+//! keeps nothing until given a capacity with
+//! [`set_capacity`](PayloadQuarantine::set_capacity).
+//!
+//! ```rust,no_run
+//! # async fn example() -> combustion_rust_ble::Result<()> {
+//! use combustion_rust_ble::DeviceManager;
+//!
+//! let manager = DeviceManager::new().await?;
+//! manager.quarantine().set_capacity(32);
+//! manager.start_scanning().await?;
+//!
+//! // Later, when something looks wrong
+//! for payload in manager.quarantine().payloads() {
+//!     eprintln!("{:?} {}: {}", payload.source, payload.hex(), payload.error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::error::Error;
+
+/// Where a quarantined payload was received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PayloadSource {
+    /// Manufacturer-specific advertising data.
+    Advertising,
+    /// A Probe Status notification.
+    Status,
+}
+
+/// A payload that failed to parse.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuarantinedPayload {
+    /// Where the payload was received.
+    pub source: PayloadSource,
+    /// Serial number of the probe it was received from, if known.
+    pub serial_number: Option<u32>,
+    /// The payload, as received.
+    pub data: Vec<u8>,
+    /// The parse error.
+    pub error: String,
+    /// When the payload was received.
+    pub received_at: DateTime<Utc>,
+}
+
+impl QuarantinedPayload {
+    /// Format the payload as space-separated hex.
+    pub fn hex(&self) -> String {
+        crate::error::hex_dump(&self.data, self.data.len())
+    }
+}
+
+/// Bounded buffer of recent unparseable payloads.
+///
+/// Clones share the same buffer.
+#[derive(Clone, Default)]
+pub struct PayloadQuarantine {
+    inner: Arc<Mutex<QuarantineInner>>,
+}
+
+#[derive(Default)]
+struct QuarantineInner {
+    /// Most payloads kept; zero disables quarantine.
+    capacity: usize,
+    /// Kept payloads, oldest first.
+    payloads: VecDeque<QuarantinedPayload>,
+}
+
+impl PayloadQuarantine {
+    /// Create a quarantine keeping up to `capacity` payloads.
+    pub fn new(capacity: usize) -> Self {
+        let quarantine = Self::default();
+        quarantine.set_capacity(capacity);
+        quarantine
+    }
+
+    /// Get the most payloads kept.
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().capacity
+    }
+
+    /// Set the most payloads kept, dropping the oldest ones beyond it.
+    ///
+    /// Zero disables quarantine and clears it.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock();
+        inner.capacity = capacity;
+        let excess = inner.payloads.len().saturating_sub(capacity);
+        inner.payloads.drain(..excess);
+    }
+
+    /// Check if payloads are being kept.
+    pub fn is_enabled(&self) -> bool {
+        self.capacity() > 0
+    }
+
+    /// Get the kept payloads, oldest first.
+    pub fn payloads(&self) -> Vec<QuarantinedPayload> {
+        self.inner.lock().payloads.iter().cloned().collect()
+    }
+
+    /// Get the number of kept payloads.
+    pub fn len(&self) -> usize {
+        self.inner.lock().payloads.len()
+    }
+
+    /// Check if no payloads are kept.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all kept payloads.
+    pub fn clear(&self) {
+        self.inner.lock().payloads.clear();
+    }
+
+    /// Keep a payload that failed to parse, if quarantine is enabled.
+    pub(crate) fn record(
+        &self,
+        source: PayloadSource,
+        serial_number: Option<u32>,
+        data: &[u8],
+        error: &Error,
+    ) {
+        let mut inner = self.inner.lock();
+        if inner.capacity == 0 {
+            return;
+        }
+        if inner.payloads.len() == inner.capacity {
+            inner.payloads.pop_front();
+        }
+        inner.payloads.push_back(QuarantinedPayload {
+            source,
+            serial_number,
+            data: data.to_vec(),
+            error: error.to_string(),
+            received_at: Utc::now(),
+        });
+    }
+}
+
+impl std::fmt::Debug for PayloadQuarantine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("PayloadQuarantine")
+            .field("capacity", &inner.capacity)
+            .field("len", &inner.payloads.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ProbeStatus;
+
+    fn record(quarantine: &PayloadQuarantine, data: &[u8]) {
+        let error = ProbeStatus::parse(data).unwrap_err();
+        quarantine.record(PayloadSource::Status, Some(1), data, &error);
+    }
+
+    #[test]
+    fn test_quarantine_is_opt_in() {
+        let quarantine = PayloadQuarantine::default();
+        record(&quarantine, &[1, 2, 3]);
+        assert!(!quarantine.is_enabled());
+        assert!(quarantine.is_empty());
+    }
+
+    #[test]
+    fn test_quarantine_keeps_most_recent() {
+        let quarantine = PayloadQuarantine::new(2);
+        for byte in 1..=3 {
+            record(&quarantine, &[byte]);
+        }
+
+        let payloads = quarantine.payloads();
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].data, vec![2]);
+        assert_eq!(payloads[1].hex(), "03");
+        assert!(payloads[1].error.contains("too short"));
+
+        quarantine.set_capacity(1);
+        assert_eq!(quarantine.payloads()[0].data, vec![3]);
+        quarantine.set_capacity(0);
+        assert!(quarantine.is_empty());
+    }
+}
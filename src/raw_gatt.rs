@@ -0,0 +1,139 @@
+//! Raw GATT characteristic access.
+//!
+//! **Unstable.** This is an escape hatch for characteristics the crate does
+//! not model yet (e.g. features of newer firmware). Its API may change or be
+//! removed in any release without a semver bump. Requires the `unstable`
+//! feature.
+//!
+//! Access is guarded so it can't break the crate's own use of the probe:
+//! writing to the UART RX characteristic and unsubscribing from
+//! characteristics the crate listens to are refused. Use the typed [`Probe`]
+//! methods for those instead.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::Probe;
+//! use futures::StreamExt;
+//! use uuid::Uuid;
+//!
+//! # async fn example(probe: &Probe, uuid: Uuid) -> combustion_rust_ble::Result<()> {
+//! let gatt = probe.raw_gatt()?;
+//! let value = gatt.read(&uuid).await?;
+//!
+//! let mut notifications = Box::pin(gatt.subscribe(&uuid).await?);
+//! while let Some(data) = notifications.next().await {
+//!     println!("{:02X?}", data);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Probe`]: crate::Probe
+
+use futures::stream::Stream;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::ble::characteristics::CharacteristicHandler;
+use crate::ble::uuids::{PROBE_STATUS_CHARACTERISTIC_UUID, UART_RX_UUID, UART_TX_UUID};
+use crate::error::{Error, Result};
+
+/// Characteristics the crate writes to itself.
+const RESERVED_WRITE: [Uuid; 1] = [UART_RX_UUID];
+
+/// Characteristics whose notifications the crate relies on.
+const RESERVED_NOTIFY: [Uuid; 2] = [UART_TX_UUID, PROBE_STATUS_CHARACTERISTIC_UUID];
+
+/// Guarded raw access to a connected probe's GATT characteristics.
+///
+/// Obtained from [`Probe::raw_gatt`](crate::Probe::raw_gatt). Valid for the
+/// connection it was created on; reconnecting requires a new handle.
+#[derive(Clone)]
+pub struct RawGatt {
+    /// Characteristic handler of the current connection.
+    handler: Arc<CharacteristicHandler>,
+}
+
+impl RawGatt {
+    /// Wrap a connection's characteristic handler.
+    pub(crate) fn new(handler: Arc<CharacteristicHandler>) -> Self {
+        Self { handler }
+    }
+
+    /// Get the UUIDs of all characteristics discovered on the probe.
+    pub fn characteristics(&self) -> Vec<Uuid> {
+        self.handler.characteristic_uuids()
+    }
+
+    /// Read a characteristic value.
+    pub async fn read(&self, uuid: &Uuid) -> Result<Vec<u8>> {
+        self.handler.read(uuid).await
+    }
+
+    /// Write a characteristic value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] for characteristics the crate writes
+    /// to itself.
+    pub async fn write(&self, uuid: &Uuid, data: &[u8], with_response: bool) -> Result<()> {
+        if RESERVED_WRITE.contains(uuid) {
+            return Err(Error::NotSupported {
+                operation: format!("raw write to reserved characteristic {}", uuid),
+            });
+        }
+        self.handler.write(uuid, data, with_response).await
+    }
+
+    /// Subscribe to notifications from a characteristic.
+    ///
+    /// The stream ends when the connection's notification listener stops.
+    /// Notifications missed because the consumer fell behind are dropped.
+    pub async fn subscribe(&self, uuid: &Uuid) -> Result<impl Stream<Item = Vec<u8>> + Send> {
+        // Listen before subscribing so the first notification isn't missed
+        let rx = self.handler.subscribe_notifications();
+        if !RESERVED_NOTIFY.contains(uuid) {
+            self.handler.subscribe(uuid).await?;
+        }
+
+        let uuid = *uuid;
+        Ok(futures::stream::unfold(rx, move |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) if event.characteristic_uuid == uuid => {
+                        return Some((event.data, rx));
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(
+                            "Raw GATT subscriber for {} dropped {} notifications",
+                            uuid, n
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    /// Unsubscribe from notifications from a characteristic.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] for characteristics the crate relies on.
+    pub async fn unsubscribe(&self, uuid: &Uuid) -> Result<()> {
+        if RESERVED_NOTIFY.contains(uuid) {
+            return Err(Error::NotSupported {
+                operation: format!("raw unsubscribe from reserved characteristic {}", uuid),
+            });
+        }
+        self.handler.unsubscribe(uuid).await
+    }
+}
+
+impl std::fmt::Debug for RawGatt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawGatt").finish_non_exhaustive()
+    }
+}
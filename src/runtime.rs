@@ -0,0 +1,282 @@
+//! Spawning the library's background tasks.
+//!
+//! The library runs background tasks for scanning, notifications, callbacks
+//! and the like. By default they are spawned with `tokio::spawn`, so they run
+//! on whichever tokio runtime the library is called from, multi-thread or
+//! current-thread alike. Applications that drive their futures some other
+//! way, e.g. a single-threaded service polling a `LocalSet` or its own
+//! executor, can route the tasks elsewhere with [`set_spawner`].
+//!
+//! Timers and I/O still come from tokio, so a tokio runtime must be running
+//! for them; only where the tasks are spawned is configurable.
+//!
+//! [`live_tasks`] counts the tasks that have not stopped yet, for spotting
+//! tasks leaked by long-running applications.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::runtime;
+//!
+//! let rt = tokio::runtime::Builder::new_current_thread()
+//!     .enable_all()
+//!     .build()
+//!     .unwrap();
+//! let handle = rt.handle().clone();
+//! runtime::set_spawner(move |task| {
+//!     handle.spawn(task);
+//! });
+//! ```
+
+use futures::future::{AbortHandle, Abortable, BoxFuture};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// Runs the library's background tasks.
+///
+/// Implemented for closures taking the task, so a spawner can be set with
+/// `set_spawner(|task| { ... })`.
+pub trait Spawn: Send + Sync {
+    /// Run `task` to completion in the background.
+    ///
+    /// The task must be polled from a context where tokio timers work.
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+}
+
+impl<F> Spawn for F
+where
+    F: Fn(BoxFuture<'static, ()>) + Send + Sync,
+{
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        self(task)
+    }
+}
+
+/// Spawns tasks on the current tokio runtime, the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSpawner;
+
+impl Spawn for TokioSpawner {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        tokio::spawn(task);
+    }
+}
+
+/// Spawner set with [`set_spawner`], if any.
+static SPAWNER: RwLock<Option<Arc<dyn Spawn>>> = RwLock::new(None);
+
+/// Number of spawned tasks that have not stopped.
+static LIVE_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Count the library's background tasks that have been spawned and have not
+/// stopped yet.
+///
+/// Tasks stop when they finish, are aborted, panic, or are dropped by the
+/// spawner. A count that keeps growing while the set of probes and
+/// subscriptions stays the same points to a leaked task.
+pub fn live_tasks() -> usize {
+    LIVE_TASKS.load(Ordering::SeqCst)
+}
+
+/// Route the library's background tasks through `spawner`.
+///
+/// Applies to tasks spawned from then on; tasks already running are left
+/// where they are, so set the spawner before creating a
+/// [`DeviceManager`](crate::DeviceManager).
+pub fn set_spawner(spawner: impl Spawn + 'static) {
+    *SPAWNER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(spawner));
+}
+
+/// Go back to spawning tasks with [`TokioSpawner`].
+pub fn reset_spawner() {
+    *SPAWNER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Why a task did not produce its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TaskError {
+    /// The task was stopped with [`TaskHandle::abort`].
+    #[error("task aborted")]
+    Aborted,
+    /// The task panicked, or the spawner dropped it before it finished.
+    #[error("task panicked or was dropped")]
+    Lost,
+}
+
+/// Handle to a spawned background task.
+///
+/// Awaiting the handle gives the task's output. Dropping it leaves the task
+/// running.
+#[derive(Debug)]
+pub struct TaskHandle<T> {
+    /// Stops the task.
+    abort: AbortHandle,
+    /// Receives the task's output.
+    output: oneshot::Receiver<T>,
+    /// Set once the task has stopped, however it stopped.
+    finished: Arc<AtomicBool>,
+}
+
+impl<T> TaskHandle<T> {
+    /// Stop the task at its next await point.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+
+    /// Check if the task has stopped.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Future for TaskHandle<T> {
+    type Output = Result<T, TaskError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.output).poll(cx).map(|output| {
+            output.map_err(|_| {
+                if self.abort.is_aborted() {
+                    TaskError::Aborted
+                } else {
+                    TaskError::Lost
+                }
+            })
+        })
+    }
+}
+
+/// Marks a task finished when dropped, including by a panic, and counts it
+/// in [`live_tasks`] until then.
+struct FinishGuard(Arc<AtomicBool>);
+
+impl FinishGuard {
+    /// Start counting a task.
+    fn new(finished: Arc<AtomicBool>) -> Self {
+        LIVE_TASKS.fetch_add(1, Ordering::SeqCst);
+        Self(finished)
+    }
+}
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+        LIVE_TASKS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Spawn a background task through the configured [`Spawn`].
+pub(crate) fn spawn<F>(future: F) -> TaskHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (abort, registration) = AbortHandle::new_pair();
+    let (tx, output) = oneshot::channel();
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let guard = FinishGuard::new(finished.clone());
+    let task = Abortable::new(future, registration);
+    let task: BoxFuture<'static, ()> = Box::pin(async move {
+        let _guard = guard;
+        if let Ok(value) = task.await {
+            let _ = tx.send(value);
+        }
+    });
+
+    match custom_spawner() {
+        Some(spawner) => spawner.spawn(task),
+        None => TokioSpawner.spawn(task),
+    }
+
+    TaskHandle {
+        abort,
+        output,
+        finished,
+    }
+}
+
+/// Spawn a background task if there is anywhere to run it: a spawner set
+/// with [`set_spawner`], or else a running tokio runtime.
+///
+/// For cleanup from `Drop`, which may run outside any runtime. Returns
+/// whether the task was spawned.
+pub(crate) fn try_spawn<F>(future: F) -> bool
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    if custom_spawner().is_none() && tokio::runtime::Handle::try_current().is_err() {
+        return false;
+    }
+    spawn(future);
+    true
+}
+
+/// Get the spawner set with [`set_spawner`], if any.
+fn custom_spawner() -> Option<Arc<dyn Spawn>> {
+    SPAWNER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_task_handle_output_and_abort() {
+        let handle = spawn(async { 42 });
+        assert_eq!(handle.await, Ok(42));
+
+        let handle = spawn(std::future::pending::<()>());
+        tokio::task::yield_now().await;
+        assert!(!handle.is_finished());
+        handle.abort();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(handle.is_finished());
+        assert_eq!(handle.await, Err(TaskError::Aborted));
+    }
+
+    /// Serializes tests that replace the global spawner.
+    static SPAWNER_TESTS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Custom spawner set for the life of a test, restored even if it panics.
+    struct ScopedSpawner {
+        _lock: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl ScopedSpawner {
+        fn set(spawner: impl Spawn + 'static) -> Self {
+            let lock = SPAWNER_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+            set_spawner(spawner);
+            Self { _lock: lock }
+        }
+    }
+
+    impl Drop for ScopedSpawner {
+        fn drop(&mut self) {
+            reset_spawner();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_spawner() {
+        // Other tests running meanwhile spawn through this spawner too, so
+        // count only the tasks spawned from this test's thread
+        let test_thread = std::thread::current().id();
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let counter = spawned.clone();
+        let scoped = ScopedSpawner::set(move |task| {
+            if std::thread::current().id() == test_thread {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+            tokio::spawn(task);
+        });
+        let handle = spawn(async { "done" });
+        drop(scoped);
+
+        assert_eq!(handle.await, Ok("done"));
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,913 @@
+//! Simulated probes with scripted fault injection.
+//!
+//! A [`SimulatedProbe`] stands in for a real probe so connection handling,
+//! command round-trips and error paths can be tested without hardware. It
+//! drives a [`Probe`] through the same ingest path as external transports:
+//! advertisements and status notifications at a fixed interval, plus UART
+//! command handling with response frames.
+//!
+//! Faults are scheduled with a [`FaultScript`] at offsets from
+//! [`start`](SimulatedProbe::start), or injected directly with
+//! [`inject`](SimulatedProbe::inject). All timing uses tokio's clock, so tests
+//! run with `#[tokio::test(start_paused = true)]` are deterministic.
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::simulator::{Fault, FaultScript, SimulatedProbe};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> combustion_rust_ble::Result<()> {
+//! let sim = SimulatedProbe::new(0x1000_0001).with_script(
+//!     FaultScript::new()
+//!         .at(Duration::from_secs(5), Fault::DropNotifications(3))
+//!         .at(Duration::from_secs(10), Fault::Disconnect),
+//! );
+//! let probe = sim.probe();
+//! sim.start();
+//!
+//! probe.connect().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requires the `simulator` feature.
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::ble::advertising::{ProbeColor, ProbeId, ProbeMode, ProductType};
+use crate::ble::connection::ConnectionState;
+use crate::data::{
+    AlarmConfig, LoggedDataPoint, PowerMode, PredictionMode, PredictionState, ProbeTemperatures,
+    RawTemperature, SessionInfo, ThermometerPreferences, VirtualSensor, VirtualSensorSelection,
+};
+use crate::error::{Error, Result};
+use crate::probe::Probe;
+use crate::protocol::uart_messages::{UartMessage, UartMessageType, UartResponse};
+use crate::transport::ProbeTransport;
+
+/// A fault the simulator can inject.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Drop the next `n` status notifications.
+    DropNotifications(u32),
+    /// Corrupt the next `n` frames: status notifications are truncated and
+    /// UART responses get a bad CRC.
+    CorruptFrames(u32),
+    /// Delay responses to UART commands by this long (zero to clear).
+    ResponseDelay(Duration),
+    /// Deliver UART responses in chunks of at most this many bytes (zero to
+    /// clear).
+    FragmentResponses(usize),
+    /// Fade the advertised RSSI linearly to `to` dBm over `over`.
+    RssiFade {
+        /// Target RSSI (dBm).
+        to: i16,
+        /// Fade duration.
+        over: Duration,
+    },
+    /// Drop the connection.
+    Disconnect,
+    /// Refuse the next `n` connection attempts.
+    RefuseConnections(u32),
+    /// Fail the next UART command of this type without applying it.
+    RejectCommand(UartMessageType),
+}
+
+/// Faults scheduled at offsets from the start of a simulation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultScript {
+    /// Scheduled faults, ordered by offset.
+    steps: Vec<(Duration, Fault)>,
+}
+
+impl FaultScript {
+    /// Create an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule `fault` at `offset` from the start of the simulation.
+    ///
+    /// Faults at the same offset are injected in the order they were added.
+    pub fn at(mut self, offset: Duration, fault: Fault) -> Self {
+        let index = self.steps.partition_point(|(at, _)| *at <= offset);
+        self.steps.insert(index, (offset, fault));
+        self
+    }
+
+    /// Get the scheduled faults, ordered by offset.
+    pub fn steps(&self) -> &[(Duration, Fault)] {
+        &self.steps
+    }
+
+    /// Check if no faults are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// An RSSI fade in progress.
+#[derive(Debug, Clone, Copy)]
+struct RssiFade {
+    /// RSSI when the fade started.
+    from: i16,
+    /// Target RSSI.
+    to: i16,
+    /// When the fade started.
+    start: Instant,
+    /// Fade duration.
+    over: Duration,
+}
+
+/// Mutable simulator state.
+#[derive(Debug)]
+struct SimState {
+    /// Connection state reported to the probe.
+    connection_state: ConnectionState,
+    /// Sensor temperatures (Celsius).
+    temperatures: [f64; 8],
+    /// Probe ID.
+    probe_id: ProbeId,
+    /// Ring color.
+    color: ProbeColor,
+    /// Operating mode.
+    mode: ProbeMode,
+    /// Whether the battery is low.
+    battery_low: bool,
+    /// Oldest log sequence number.
+    min_sequence: u32,
+    /// Newest log sequence number.
+    max_sequence: u32,
+    /// Packed temperatures of each log record, by sequence number.
+    log: Vec<[u8; 13]>,
+    /// Session information reported over UART.
+    session_info: SessionInfo,
+    /// Prediction mode requested by the last `SetPrediction` command.
+    prediction_mode: PredictionMode,
+    /// Prediction set point (raw, 0.1 °C).
+    prediction_set_point_raw: u16,
+    /// Alarm configuration, with trip state maintained by the simulator.
+    alarms: AlarmConfig,
+    /// Thermometer preferences.
+    preferences: ThermometerPreferences,
+    /// Advertised RSSI (dBm).
+    rssi: i16,
+    /// RSSI fade in progress.
+    rssi_fade: Option<RssiFade>,
+    /// Status notifications still to drop.
+    drop_notifications: u32,
+    /// Frames still to corrupt.
+    corrupt_frames: u32,
+    /// Delay before responding to UART commands.
+    response_delay: Duration,
+    /// Maximum UART response chunk size (0 for whole frames).
+    fragment_size: usize,
+    /// Connection attempts still to refuse.
+    refuse_connections: u32,
+    /// Command types whose next command fails.
+    rejected_commands: Vec<UartMessageType>,
+    /// Firmware revision reported to the probe.
+    firmware_revision: Option<String>,
+    /// Whether advertisements continue while connected.
+    advertises_while_connected: bool,
+    /// Whether the probe has the Probe Status characteristic.
+    status_characteristic: bool,
+}
+
+impl SimState {
+    /// Apply a fault.
+    fn inject(&mut self, fault: Fault, now: Instant) {
+        debug!("Injecting simulated fault: {:?}", fault);
+        match fault {
+            Fault::DropNotifications(n) => self.drop_notifications += n,
+            Fault::CorruptFrames(n) => self.corrupt_frames += n,
+            Fault::ResponseDelay(delay) => self.response_delay = delay,
+            Fault::FragmentResponses(size) => self.fragment_size = size,
+            Fault::RssiFade { to, over } => {
+                self.rssi_fade = Some(RssiFade {
+                    from: self.current_rssi(now),
+                    to,
+                    start: now,
+                    over,
+                })
+            }
+            Fault::Disconnect => self.connection_state = ConnectionState::Disconnected,
+            Fault::RefuseConnections(n) => self.refuse_connections += n,
+            Fault::RejectCommand(message_type) => self.rejected_commands.push(message_type),
+        }
+    }
+
+    /// Get the RSSI at `now`, following any fade.
+    fn current_rssi(&self, now: Instant) -> i16 {
+        let Some(fade) = self.rssi_fade else {
+            return self.rssi;
+        };
+        let progress = if fade.over.is_zero() {
+            1.0
+        } else {
+            (now.duration_since(fade.start).as_secs_f64() / fade.over.as_secs_f64()).min(1.0)
+        };
+        let delta = f64::from(fade.to) - f64::from(fade.from);
+        (f64::from(fade.from) + delta * progress).round() as i16
+    }
+
+    /// Consume one pending corruption, if any.
+    fn take_corruption(&mut self) -> bool {
+        if self.corrupt_frames == 0 {
+            return false;
+        }
+        self.corrupt_frames -= 1;
+        true
+    }
+
+    /// Encode the packed temperatures.
+    fn packed_temperatures(&self) -> [u8; 13] {
+        let mut values = [0u16; 8];
+        for (value, celsius) in values.iter_mut().zip(self.temperatures) {
+            *value = RawTemperature::from_celsius(celsius).raw_value();
+        }
+        ProbeTemperatures::from_raw(values).to_packed_bytes()
+    }
+
+    /// Encode the mode/ID/color byte.
+    fn mode_id_byte(&self) -> u8 {
+        (self.probe_id.to_raw() << 5) | (self.color.to_raw() << 2) | self.mode.to_raw()
+    }
+
+    /// Apply a UART command the simulator models.
+    fn apply_command(&mut self, message: &UartMessage) {
+        let payload = &message.payload;
+        match message.message_type() {
+            UartMessageType::SetProbeId => {
+                if let Some(&raw) = payload.first() {
+                    self.probe_id = ProbeId::from_raw(raw);
+                }
+            }
+            UartMessageType::SetProbeColor => {
+                if let Some(&raw) = payload.first() {
+                    self.color = ProbeColor::from_raw(raw);
+                }
+            }
+            UartMessageType::SetPrediction if payload.len() >= 2 => {
+                let packed = u16::from_le_bytes([payload[0], payload[1]]);
+                self.prediction_mode = PredictionMode::from_raw(((packed >> 10) & 0x03) as u8);
+                self.prediction_set_point_raw = packed & 0x03FF;
+            }
+            UartMessageType::SetHighLowAlarms => {
+                if let Some(alarms) = AlarmConfig::from_bytes(payload) {
+                    self.alarms = alarms;
+                }
+            }
+            UartMessageType::SetPowerMode => {
+                if let Some(&raw) = payload.first() {
+                    self.preferences.set_power_mode(PowerMode::from_raw(raw));
+                }
+            }
+            UartMessageType::SilenceAlarms => {
+                for alarm in self
+                    .alarms
+                    .high_alarms
+                    .iter_mut()
+                    .chain(self.alarms.low_alarms.iter_mut())
+                {
+                    alarm.alarming = false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add a log record of the current temperatures.
+    fn record_log(&mut self) {
+        self.max_sequence += 1;
+        let packed = self.packed_temperatures();
+        while self.log.len() <= self.max_sequence as usize {
+            self.log.push(packed);
+        }
+    }
+
+    /// Build the responses to a UART command.
+    ///
+    /// Read Session Information is answered with the session information
+    /// and Read Logs with one response per record in the requested range;
+    /// other commands get an empty success response.
+    fn responses(&self, message: &UartMessage) -> Vec<UartResponse> {
+        let Some(response_type) = message.message_type().response_type() else {
+            return Vec::new();
+        };
+        let payload = &message.payload;
+        match message.message_type() {
+            UartMessageType::ReadSessionInfo => vec![UartResponse::new(
+                response_type,
+                true,
+                self.session_info.to_bytes().to_vec(),
+            )],
+            UartMessageType::ReadLogs if payload.len() >= 8 => {
+                let from = u32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]);
+                let to = u32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]);
+                (from.max(self.min_sequence)..=to.min(self.max_sequence))
+                    .filter_map(|sequence| {
+                        let packed = self.log.get(sequence as usize)?;
+                        let mut record = vec![0u8; LoggedDataPoint::SIZE];
+                        record[0..4].copy_from_slice(&sequence.to_le_bytes());
+                        record[4..17].copy_from_slice(packed);
+                        Some(UartResponse::new(response_type, true, record))
+                    })
+                    .collect()
+            }
+            _ => vec![UartResponse::new(response_type, true, Vec::new())],
+        }
+    }
+
+    /// Trip alarms whose thresholds the current temperatures have crossed.
+    fn update_alarms(&mut self) {
+        // Default virtual sensor selection: core T1, surface T4, ambient T5
+        let selection = VirtualSensorSelection::from_byte(0);
+        let mut readings = self.temperatures.to_vec();
+        readings.extend(VirtualSensor::ALL.map(|sensor| {
+            selection
+                .get(sensor)
+                .map_or(f64::NAN, |physical| self.temperatures[physical.index()])
+        }));
+
+        for (index, &celsius) in readings.iter().enumerate() {
+            let high = &mut self.alarms.high_alarms[index];
+            if high.set && !high.tripped && celsius >= high.temperature {
+                high.tripped = true;
+                high.alarming = true;
+            }
+            let low = &mut self.alarms.low_alarms[index];
+            if low.set && !low.tripped && celsius <= low.temperature {
+                low.tripped = true;
+                low.alarming = true;
+            }
+        }
+    }
+
+    /// Encode the prediction status.
+    fn prediction_status(&self) -> [u8; 7] {
+        let set_point = self.prediction_set_point_raw & 0x03FF;
+        // The default virtual core is T1
+        let state = if self.prediction_mode == PredictionMode::None {
+            PredictionState::ProbeInserted
+        } else if self.temperatures[0] >= f64::from(set_point) * 0.1 {
+            PredictionState::RemovalPredictionDone
+        } else {
+            PredictionState::Predicting
+        };
+
+        let mut data = [0u8; 7];
+        data[0] = (state as u8) | (self.prediction_mode.to_raw() << 4);
+        data[1] = set_point as u8;
+        data[2] = (set_point >> 8) as u8;
+        data
+    }
+
+    /// Encode a Probe Status notification.
+    fn status_frame(&self) -> Vec<u8> {
+        let mut data = vec![0u8; 94];
+        data[0..4].copy_from_slice(&self.min_sequence.to_le_bytes());
+        data[4..8].copy_from_slice(&self.max_sequence.to_le_bytes());
+        data[8..21].copy_from_slice(&self.packed_temperatures());
+        data[21] = self.mode_id_byte();
+        data[22] = u8::from(self.battery_low);
+        data[23..30].copy_from_slice(&self.prediction_status());
+        data[49] = self.preferences.to_byte();
+        data[50..94].copy_from_slice(&self.alarms.to_bytes());
+        data
+    }
+
+    /// Encode advertising manufacturer data.
+    fn advertising_frame(&self, serial_number: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 22];
+        data[0] = ProductType::PredictiveProbe.to_raw();
+        data[1..5].copy_from_slice(&serial_number.to_le_bytes());
+        data[5..18].copy_from_slice(&self.packed_temperatures());
+        data[18] = self.mode_id_byte();
+        data[19] = u8::from(self.battery_low);
+        data
+    }
+}
+
+/// State shared between the simulator, its driver task and the probe transport.
+struct Inner {
+    /// Mutable state.
+    state: Mutex<SimState>,
+    /// UART commands received, in order.
+    commands: Mutex<Vec<UartMessage>>,
+    /// UART response frames.
+    uart_tx: broadcast::Sender<Vec<u8>>,
+}
+
+#[async_trait]
+impl ProbeTransport for Inner {
+    fn connection_state(&self) -> ConnectionState {
+        self.state.lock().connection_state
+    }
+
+    fn firmware_revision(&self) -> Option<String> {
+        self.state.lock().firmware_revision.clone()
+    }
+
+    fn has_status_characteristic(&self) -> bool {
+        self.state.lock().status_characteristic
+    }
+
+    fn subscribe_uart(&self) -> Option<broadcast::Receiver<Vec<u8>>> {
+        Some(self.uart_tx.subscribe())
+    }
+
+    async fn read_rssi(&self) -> Result<i16> {
+        let state = self.state.lock();
+        Ok(state.current_rssi(Instant::now()))
+    }
+
+    async fn connect(&self) -> Result<()> {
+        let mut state = self.state.lock();
+        if state.refuse_connections > 0 {
+            state.refuse_connections -= 1;
+            return Err(Error::ConnectionFailed {
+                reason: "Simulated connection refusal".to_string(),
+            });
+        }
+        state.connection_state = ConnectionState::Connected;
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        self.state.lock().connection_state = ConnectionState::Disconnected;
+        Ok(())
+    }
+
+    async fn write_uart(&self, data: &[u8]) -> Result<()> {
+        if !self.connection_state().is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let message = UartMessage::parse(data)?;
+        let (delay, fragment_size, frames) = {
+            let mut state = self.state.lock();
+            if let Some(index) = state
+                .rejected_commands
+                .iter()
+                .position(|t| *t == message.message_type())
+            {
+                state.rejected_commands.remove(index);
+                return Err(Error::ProbeError {
+                    message: format!("simulated {:?} failure", message.message_type()),
+                });
+            }
+            state.apply_command(&message);
+            let mut frames: Vec<_> = state
+                .responses(&message)
+                .iter()
+                .map(UartResponse::to_bytes)
+                .collect();
+            if let Some(frame) = frames.first_mut().filter(|_| state.take_corruption()) {
+                frame[2] ^= 0xFF;
+            }
+            (state.response_delay, state.fragment_size, frames)
+        };
+        self.commands.lock().push(message);
+
+        let uart_tx = self.uart_tx.clone();
+        crate::runtime::spawn(async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            for frame in frames {
+                let chunk_size = if fragment_size == 0 {
+                    frame.len()
+                } else {
+                    fragment_size
+                };
+                for chunk in frame.chunks(chunk_size) {
+                    let _ = uart_tx.send(chunk.to_vec());
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// A simulated probe.
+///
+/// Advertises continuously and, while connected, sends a status notification
+/// every [`interval`](Self::with_interval), adding one log record each time.
+/// Probe ID, color, prediction and alarm commands change what it reports, and
+/// enabled alarms trip when the simulated temperatures cross them. Every
+/// command is recorded and answered over the probe's UART notifications:
+/// Read Session Information with the session information, Read Logs with the
+/// requested records, and other commands with a success response frame.
+///
+/// The driver task stops when the simulator is dropped.
+pub struct SimulatedProbe {
+    /// Serial number.
+    serial_number: u32,
+    /// Time between notifications.
+    interval: Duration,
+    /// Scheduled faults.
+    script: FaultScript,
+    /// Shared state.
+    inner: Arc<Inner>,
+    /// The probe driven by the simulator.
+    probe: Arc<Probe>,
+    /// Driver task.
+    handle: Mutex<Option<crate::runtime::TaskHandle<()>>>,
+}
+
+impl SimulatedProbe {
+    /// Default time between notifications.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Create a simulated probe at room temperature.
+    pub fn new(serial_number: u32) -> Self {
+        let (uart_tx, _) = broadcast::channel(64);
+        let inner = Arc::new(Inner {
+            state: Mutex::new(SimState {
+                connection_state: ConnectionState::Disconnected,
+                temperatures: [20.0; 8],
+                probe_id: ProbeId::default(),
+                color: ProbeColor::default(),
+                mode: ProbeMode::default(),
+                battery_low: false,
+                min_sequence: 0,
+                max_sequence: 0,
+                log: Vec::new(),
+                // Derived from the serial number so runs are repeatable
+                session_info: SessionInfo::new(
+                    serial_number,
+                    Self::DEFAULT_INTERVAL.as_millis() as u32,
+                ),
+                prediction_mode: PredictionMode::None,
+                prediction_set_point_raw: 0,
+                alarms: AlarmConfig::new(),
+                preferences: ThermometerPreferences::new(),
+                rssi: -60,
+                rssi_fade: None,
+                drop_notifications: 0,
+                corrupt_frames: 0,
+                response_delay: Duration::ZERO,
+                fragment_size: 0,
+                refuse_connections: 0,
+                rejected_commands: Vec::new(),
+                firmware_revision: None,
+                advertises_while_connected: true,
+                status_characteristic: true,
+            }),
+            commands: Mutex::new(Vec::new()),
+            uart_tx,
+        });
+        let probe = Arc::new(Probe::with_transport(
+            format!("sim:{:08X}", serial_number),
+            serial_number,
+            inner.clone(),
+        ));
+
+        Self {
+            serial_number,
+            interval: Self::DEFAULT_INTERVAL,
+            script: FaultScript::new(),
+            inner,
+            probe,
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// Set the time between notifications.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self.inner.state.lock().session_info.sample_period_ms =
+            u32::try_from(interval.as_millis()).unwrap_or(u32::MAX);
+        self
+    }
+
+    /// Set the firmware revision reported to the probe, e.g. `"1.4.0"`.
+    ///
+    /// Simulated probes report no revision by default, so they are assumed
+    /// to support only the original message layouts.
+    pub fn with_firmware_revision(self, revision: impl Into<String>) -> Self {
+        self.inner.state.lock().firmware_revision = Some(revision.into());
+        self
+    }
+
+    /// Set the ring color the probe reports.
+    pub fn with_color(self, color: ProbeColor) -> Self {
+        self.inner.state.lock().color = color;
+        self
+    }
+
+    /// Stop delivering advertisements while connected, as some platforms do.
+    ///
+    /// The probe's RSSI then only updates when read over the connection.
+    pub fn without_advertising_while_connected(self) -> Self {
+        self.inner.state.lock().advertises_while_connected = false;
+        self
+    }
+
+    /// Leave out the Probe Status characteristic, as older firmware does.
+    ///
+    /// The probe then only advertises; no status notifications are sent
+    /// while connected.
+    pub fn without_status_characteristic(self) -> Self {
+        self.inner.state.lock().status_characteristic = false;
+        self
+    }
+
+    /// Set the faults to inject once started.
+    pub fn with_script(mut self, script: FaultScript) -> Self {
+        self.script = script;
+        self
+    }
+
+    /// Get the simulated serial number.
+    pub fn serial_number(&self) -> u32 {
+        self.serial_number
+    }
+
+    /// Get the probe driven by the simulator.
+    pub fn probe(&self) -> Arc<Probe> {
+        self.probe.clone()
+    }
+
+    /// Start advertising, notifying and running the fault script.
+    ///
+    /// The first advertisement is sent immediately. Calling this again
+    /// restarts the script.
+    pub fn start(&self) {
+        let inner = self.inner.clone();
+        let probe = self.probe.clone();
+        let serial_number = self.serial_number;
+        let interval = self.interval;
+        let script = self.script.clone();
+
+        let handle = crate::runtime::spawn(async move {
+            let start = Instant::now();
+            let mut steps = script.steps.into_iter().peekable();
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                let next_fault = steps.peek().map(|(offset, _)| start + *offset);
+
+                tokio::select! {
+                    _ = ticker.tick() => tick(&inner, &probe, serial_number),
+                    _ = sleep_until_some(next_fault) => {
+                        let now = Instant::now();
+                        let mut state = inner.state.lock();
+                        while let Some((_, fault)) =
+                            steps.next_if(|(offset, _)| start + *offset <= now)
+                        {
+                            state.inject(fault, now);
+                        }
+                    }
+                }
+            }
+        });
+
+        if let Some(previous) = self.handle.lock().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    /// Inject a fault immediately.
+    pub fn inject(&self, fault: Fault) {
+        self.inner.state.lock().inject(fault, Instant::now());
+    }
+
+    /// Set the sensor temperatures (Celsius, T1 to T8).
+    pub fn set_temperatures(&self, celsius: [f64; 8]) {
+        self.inner.state.lock().temperatures = celsius;
+    }
+
+    /// Set whether the battery is low.
+    pub fn set_battery_low(&self, low: bool) {
+        self.inner.state.lock().battery_low = low;
+    }
+
+    /// Set the operating mode.
+    pub fn set_mode(&self, mode: ProbeMode) {
+        self.inner.state.lock().mode = mode;
+    }
+
+    /// Get the RSSI currently advertised (dBm).
+    pub fn rssi(&self) -> i16 {
+        self.inner.state.lock().current_rssi(Instant::now())
+    }
+
+    /// Apply a UART command as if another client, such as the official app
+    /// connected through MeatNet, had sent it.
+    ///
+    /// The change shows up in later status notifications but not in
+    /// [`commands`](Self::commands).
+    pub fn apply_external_command(&self, message: &UartMessage) {
+        self.inner.state.lock().apply_command(message);
+    }
+
+    /// Get the UART commands received so far, in order.
+    pub fn commands(&self) -> Vec<UartMessage> {
+        self.inner.commands.lock().clone()
+    }
+
+    /// Subscribe to UART response frames as the probe would notify them,
+    /// including any injected corruption or fragmentation.
+    pub fn subscribe_uart_responses(&self) -> broadcast::Receiver<Vec<u8>> {
+        self.inner.uart_tx.subscribe()
+    }
+}
+
+impl Drop for SimulatedProbe {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.lock().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl std::fmt::Debug for SimulatedProbe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimulatedProbe")
+            .field("serial_number", &format!("{:08X}", self.serial_number))
+            .field("interval", &self.interval)
+            .field("script", &self.script)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Send one round of advertising and status data to the probe.
+fn tick(inner: &Inner, probe: &Probe, serial_number: u32) {
+    let now = Instant::now();
+    let (advertising, rssi, status) = {
+        let mut state = inner.state.lock();
+        let paused = state.connection_state.is_connected() && !state.advertises_while_connected;
+        let advertising = (!paused).then(|| state.advertising_frame(serial_number));
+        let rssi = state.current_rssi(now);
+
+        state.update_alarms();
+
+        if state.connection_state.is_connected() {
+            state.record_log();
+        }
+        let status = if state.connection_state.is_connected() && state.status_characteristic {
+            if state.drop_notifications > 0 {
+                state.drop_notifications -= 1;
+                None
+            } else {
+                let mut frame = state.status_frame();
+                if state.take_corruption() {
+                    // Cut short so it fails to parse, as a garbled notification would
+                    frame.truncate(8);
+                }
+                Some(frame)
+            }
+        } else {
+            None
+        };
+
+        (advertising, rssi, status)
+    };
+
+    if let Some(advertising) = advertising {
+        if let Err(e) = probe.ingest_advertising_bytes(&advertising, Some(rssi)) {
+            debug!("Simulated advertisement rejected: {}", e);
+        }
+    }
+    if let Some(status) = status {
+        if let Err(e) = probe.ingest_status_bytes(&status) {
+            debug!("Simulated status notification rejected: {}", e);
+        }
+    }
+}
+
+/// Sleep until `deadline`, or forever if there is none.
+async fn sleep_until_some(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::uart_messages::{build_read_logs_request, build_set_probe_color_request};
+
+    #[test]
+    fn test_script_orders_by_offset() {
+        let script = FaultScript::new()
+            .at(Duration::from_secs(5), Fault::Disconnect)
+            .at(Duration::from_secs(1), Fault::DropNotifications(1))
+            .at(Duration::from_secs(5), Fault::RefuseConnections(1));
+
+        let faults: Vec<_> = script.steps().iter().map(|(_, f)| f.clone()).collect();
+        assert_eq!(
+            faults,
+            vec![
+                Fault::DropNotifications(1),
+                Fault::Disconnect,
+                Fault::RefuseConnections(1)
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dropped_notifications_and_disconnect() {
+        let sim = SimulatedProbe::new(0x1000_0001).with_script(
+            FaultScript::new()
+                .at(Duration::from_millis(1500), Fault::DropNotifications(2))
+                .at(Duration::from_millis(5500), Fault::Disconnect),
+        );
+        let probe = sim.probe();
+        probe.connect().await.unwrap();
+        sim.start();
+
+        tokio::time::sleep(Duration::from_millis(3600)).await;
+        // Ticks at 0, 1, 2, 3 s; the ones at 2 and 3 s are dropped
+        assert_eq!(probe.max_sequence_number(), 2);
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert_eq!(probe.max_sequence_number(), 6);
+        assert_eq!(probe.connection_state(), ConnectionState::Disconnected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refused_connections_and_rssi_fade() {
+        let sim = SimulatedProbe::new(0x1000_0001);
+        sim.inject(Fault::RefuseConnections(2));
+        sim.inject(Fault::RssiFade {
+            to: -90,
+            over: Duration::from_secs(10),
+        });
+        let probe = sim.probe();
+
+        assert!(probe.connect().await.is_err());
+        assert!(probe.connect().await.is_err());
+        assert!(probe.connect().await.is_ok());
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(sim.rssi(), -75);
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(sim.rssi(), -90);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_info_and_logs_over_uart() {
+        let sim = SimulatedProbe::new(0x1000_0001).with_interval(Duration::from_millis(500));
+        let probe = sim.probe();
+        probe.connect().await.unwrap();
+        sim.start();
+
+        let info = probe.read_session_info().await.unwrap();
+        assert_eq!(info, SessionInfo::new(0x1000_0001, 500));
+
+        sim.set_temperatures([55.0; 8]);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let mut responses = sim.subscribe_uart_responses();
+        let request = build_read_logs_request(1, 2).to_bytes();
+        sim.inner.write_uart(&request).await.unwrap();
+        for sequence in 1..=2 {
+            let response = UartResponse::parse(&responses.recv().await.unwrap()).unwrap();
+            assert_eq!(response.message_type, UartMessageType::ReadLogsResponse);
+            let point = LoggedDataPoint::from_bytes(&response.payload).unwrap();
+            assert_eq!(point.sequence_number, sequence);
+        }
+        tokio::task::yield_now().await;
+        assert!(probe.temperature_log().len() >= 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_commands_round_trip_with_faulty_responses() {
+        let sim = SimulatedProbe::new(0x1000_0001);
+        let probe = sim.probe();
+        probe.connect().await.unwrap();
+        sim.start();
+
+        let mut responses = sim.subscribe_uart_responses();
+        sim.inject(Fault::FragmentResponses(4));
+        sim.inject(Fault::CorruptFrames(1));
+        sim.inject(Fault::ResponseDelay(Duration::from_millis(500)));
+
+        probe.set_color(ProbeColor::from_raw(5)).await.unwrap();
+        assert_eq!(sim.commands(), vec![build_set_probe_color_request(5)]);
+
+        let mut frame = Vec::new();
+        while frame.len() < 7 {
+            let chunk = responses.recv().await.unwrap();
+            assert!(chunk.len() <= 4);
+            frame.extend(chunk);
+        }
+        assert!(matches!(
+            UartMessage::parse(&frame),
+            Err(Error::CrcMismatch { .. })
+        ));
+
+        // Subsequent notifications carry the new color
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(probe.color(), ProbeColor::from_raw(5));
+    }
+}
@@ -0,0 +1,369 @@
+//! Point-in-time probe snapshots.
+//!
+//! A [`ProbeSnapshot`] captures the observable state of a probe at a single
+//! moment, for exporting, relaying, or comparing over time. A [`StateDiff`]
+//! describes what changed between two consecutive snapshots.
+
+use chrono::{DateTime, Utc};
+
+use crate::ble::advertising::{BatteryStatus, Overheating, ProbeColor, ProbeId, ProbeMode};
+use crate::ble::connection::ConnectionState;
+use crate::data::{
+    FoodSafeData, PredictionInfo, ProbeTemperatures, VirtualSensor, VirtualTemperatures,
+};
+
+/// Snapshot of a probe's state.
+///
+/// Serialized snapshots carry a [`schema_version`](Self::schema_version), so
+/// consumers such as gateway clients can tell when a snapshot was produced
+/// by a newer release with fields they do not know about.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct ProbeSnapshot {
+    /// Version of the snapshot layout, [`ProbeSnapshot::SCHEMA_VERSION`]
+    /// when captured by this release. Snapshots serialized before versioning
+    /// was introduced read as version 1.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "ProbeSnapshot::first_schema_version")
+    )]
+    pub schema_version: u16,
+
+    /// Probe serial number.
+    pub serial_number: u32,
+
+    /// Probe ID (1-8).
+    pub probe_id: ProbeId,
+
+    /// Silicone ring color.
+    pub color: ProbeColor,
+
+    /// Operational mode.
+    pub mode: ProbeMode,
+
+    /// Battery status.
+    pub battery_status: BatteryStatus,
+
+    /// Connection state at capture time.
+    pub connection_state: ConnectionState,
+
+    /// Signal strength (RSSI).
+    pub rssi: Option<i16>,
+
+    /// Raw temperatures from all 8 sensors.
+    pub temperatures: ProbeTemperatures,
+
+    /// Virtual temperatures (core, surface, ambient).
+    pub virtual_temperatures: VirtualTemperatures,
+
+    /// Prediction information, if available.
+    pub prediction: Option<PredictionInfo>,
+
+    /// Food safety data, if configured.
+    pub food_safe_data: Option<FoodSafeData>,
+
+    /// Overheating information.
+    pub overheating: Overheating,
+
+    /// Minimum sequence number of logs on the probe.
+    pub min_sequence: u32,
+
+    /// Maximum sequence number of logs on the probe.
+    pub max_sequence: u32,
+
+    /// Time the snapshot was captured.
+    pub captured_at: DateTime<Utc>,
+}
+
+impl ProbeSnapshot {
+    /// Current snapshot layout version.
+    ///
+    /// Incremented whenever fields are added or their meaning changes.
+    pub const SCHEMA_VERSION: u16 = 1;
+
+    /// Check if the snapshot was produced by a newer release than this one,
+    /// in which case fields this release does not know about were dropped.
+    pub fn is_newer_schema(&self) -> bool {
+        self.schema_version > Self::SCHEMA_VERSION
+    }
+
+    /// Version assumed for snapshots without a version.
+    #[cfg(feature = "serde")]
+    fn first_schema_version() -> u16 {
+        1
+    }
+
+    /// Get the serial number as a formatted string.
+    pub fn serial_number_string(&self) -> String {
+        format!("{:08X}", self.serial_number)
+    }
+
+    /// Snapshot of probe 1 with no readings, for tests to override fields of.
+    #[cfg(test)]
+    pub(crate) fn test_default() -> Self {
+        Self {
+            schema_version: Self::SCHEMA_VERSION,
+            serial_number: 1,
+            probe_id: ProbeId::default(),
+            color: ProbeColor::default(),
+            mode: ProbeMode::default(),
+            battery_status: BatteryStatus::default(),
+            connection_state: ConnectionState::default(),
+            rssi: None,
+            temperatures: ProbeTemperatures::new(),
+            virtual_temperatures: VirtualTemperatures::default(),
+            prediction: None,
+            food_safe_data: None,
+            overheating: Overheating::default(),
+            min_sequence: 0,
+            max_sequence: 0,
+            captured_at: Utc::now(),
+        }
+    }
+
+    /// Serialize as a single line of JSON, for newline-delimited JSON
+    /// streams such as `--json` output.
+    #[cfg(feature = "serde")]
+    pub fn to_json_line(&self) -> crate::error::Result<String> {
+        serde_json::to_string(self).map_err(|e| crate::error::Error::InvalidData {
+            context: format!("failed to serialize probe snapshot: {}", e),
+            payload: None,
+        })
+    }
+}
+
+/// A snapshot field that can change between updates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangedField {
+    /// Probe ID.
+    ProbeId,
+    /// Silicone ring color.
+    Color,
+    /// Operational mode.
+    Mode,
+    /// Battery status.
+    BatteryStatus,
+    /// Connection state.
+    ConnectionState,
+    /// Signal strength.
+    Rssi,
+    /// Raw sensor temperatures.
+    Temperatures,
+    /// Virtual temperatures.
+    VirtualTemperatures,
+    /// Prediction information.
+    Prediction,
+    /// Food safety data.
+    FoodSafe,
+    /// Overheating sensors.
+    Overheating,
+    /// Range of log sequence numbers on the probe.
+    LogRange,
+}
+
+/// Direction of a threshold crossing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CrossingDirection {
+    /// The temperature rose to or above the threshold.
+    Rising,
+    /// The temperature fell below the threshold.
+    Falling,
+}
+
+/// A virtual temperature crossing a configured threshold.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThresholdCrossing {
+    /// Sensor that crossed the threshold.
+    pub sensor: VirtualSensor,
+    /// Threshold in Celsius.
+    pub threshold: f64,
+    /// Direction of the crossing.
+    pub direction: CrossingDirection,
+    /// Previous temperature in Celsius.
+    pub previous: f64,
+    /// Current temperature in Celsius.
+    pub current: f64,
+}
+
+/// Differences between two consecutive snapshots of a probe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDiff {
+    /// State before the update.
+    pub previous: ProbeSnapshot,
+    /// State after the update.
+    pub current: ProbeSnapshot,
+    /// Fields whose value changed.
+    pub changed: Vec<ChangedField>,
+    /// Virtual temperatures that crossed one of the thresholds.
+    pub crossings: Vec<ThresholdCrossing>,
+}
+
+impl StateDiff {
+    /// Compute the differences between two snapshots.
+    ///
+    /// `thresholds` are temperatures in Celsius checked for crossings by each
+    /// virtual sensor. The capture time is not considered a change.
+    pub fn between(previous: ProbeSnapshot, current: ProbeSnapshot, thresholds: &[f64]) -> Self {
+        let (p, c) = (&previous, &current);
+        let checks = [
+            (ChangedField::ProbeId, p.probe_id != c.probe_id),
+            (ChangedField::Color, p.color != c.color),
+            (ChangedField::Mode, p.mode != c.mode),
+            (
+                ChangedField::BatteryStatus,
+                p.battery_status != c.battery_status,
+            ),
+            (
+                ChangedField::ConnectionState,
+                p.connection_state != c.connection_state,
+            ),
+            (ChangedField::Rssi, p.rssi != c.rssi),
+            (ChangedField::Temperatures, p.temperatures != c.temperatures),
+            (
+                ChangedField::VirtualTemperatures,
+                p.virtual_temperatures != c.virtual_temperatures,
+            ),
+            (ChangedField::Prediction, p.prediction != c.prediction),
+            (ChangedField::FoodSafe, p.food_safe_data != c.food_safe_data),
+            (ChangedField::Overheating, p.overheating != c.overheating),
+            (
+                ChangedField::LogRange,
+                p.min_sequence != c.min_sequence || p.max_sequence != c.max_sequence,
+            ),
+        ];
+        let changed = checks
+            .into_iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(field, _)| field)
+            .collect();
+
+        let mut crossings = Vec::new();
+        for sensor in VirtualSensor::ALL {
+            let (Some(before), Some(after)) = (
+                p.virtual_temperatures.get(sensor),
+                c.virtual_temperatures.get(sensor),
+            ) else {
+                continue;
+            };
+            for &threshold in thresholds {
+                let direction = if before < threshold && after >= threshold {
+                    CrossingDirection::Rising
+                } else if before >= threshold && after < threshold {
+                    CrossingDirection::Falling
+                } else {
+                    continue;
+                };
+                crossings.push(ThresholdCrossing {
+                    sensor,
+                    threshold,
+                    direction,
+                    previous: before,
+                    current: after,
+                });
+            }
+        }
+
+        Self {
+            previous,
+            current,
+            changed,
+            crossings,
+        }
+    }
+
+    /// Check if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.crossings.is_empty()
+    }
+
+    /// Check if a field changed.
+    pub fn has_changed(&self, field: ChangedField) -> bool {
+        self.changed.contains(&field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(core: Option<f64>) -> ProbeSnapshot {
+        ProbeSnapshot {
+            rssi: Some(-60),
+            virtual_temperatures: VirtualTemperatures::new(core, None, None),
+            ..ProbeSnapshot::test_default()
+        }
+    }
+
+    #[test]
+    fn test_diff_ignores_capture_time() {
+        let diff = StateDiff::between(snapshot(Some(20.0)), snapshot(Some(20.0)), &[]);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields() {
+        let mut current = snapshot(Some(21.0));
+        current.rssi = Some(-70);
+        current.max_sequence = 5;
+
+        let diff = StateDiff::between(snapshot(Some(20.0)), current, &[]);
+        assert_eq!(
+            diff.changed,
+            vec![
+                ChangedField::Rssi,
+                ChangedField::VirtualTemperatures,
+                ChangedField::LogRange
+            ]
+        );
+        assert!(diff.crossings.is_empty());
+    }
+
+    #[test]
+    fn test_diff_threshold_crossings() {
+        let rising = StateDiff::between(snapshot(Some(59.5)), snapshot(Some(60.0)), &[60.0, 70.0]);
+        assert_eq!(rising.crossings.len(), 1);
+        assert_eq!(rising.crossings[0].sensor, VirtualSensor::Core);
+        assert_eq!(rising.crossings[0].direction, CrossingDirection::Rising);
+
+        let falling = StateDiff::between(snapshot(Some(60.0)), snapshot(Some(59.9)), &[60.0]);
+        assert_eq!(falling.crossings[0].direction, CrossingDirection::Falling);
+
+        // Unknown readings never cross
+        let unknown = StateDiff::between(snapshot(None), snapshot(Some(80.0)), &[60.0]);
+        assert!(unknown.crossings.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_line_round_trip() {
+        let original = snapshot(Some(55.5));
+        let line = original.to_json_line().unwrap();
+        assert!(!line.contains('\n'));
+        let parsed: ProbeSnapshot = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed, original);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_schema_version() {
+        let mut value = serde_json::to_value(snapshot(None)).unwrap();
+        assert_eq!(value["schema_version"], ProbeSnapshot::SCHEMA_VERSION);
+
+        // Snapshots from before versioning read as version 1
+        value.as_object_mut().unwrap().remove("schema_version");
+        let parsed: ProbeSnapshot = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(parsed.schema_version, 1);
+        assert!(!parsed.is_newer_schema());
+
+        // Unknown fields from a newer release are skipped
+        value["schema_version"] = (ProbeSnapshot::SCHEMA_VERSION + 1).into();
+        value["added_later"] = true.into();
+        let parsed: ProbeSnapshot = serde_json::from_value(value).unwrap();
+        assert!(parsed.is_newer_schema());
+    }
+}
@@ -0,0 +1,368 @@
+//! Host-side time-based alarms.
+//!
+//! Besides the temperature alarms the probe runs itself, a probe can carry
+//! timers kept by this library: "alert me in 45 minutes" with
+//! [`Probe::schedule_timer_after`](crate::Probe::schedule_timer_after), or
+//! "alert at 6:30 AM to wrap" with
+//! [`Probe::schedule_timer_at`](crate::Probe::schedule_timer_at) and
+//! [`next_local_time`]. Timers belong to the probe's current cook session and
+//! are dropped without firing if the probe has started a new one by then.
+//!
+//! Timers firing and temperature alarms starting to sound are both published
+//! as [`AlarmEvent`]s on
+//! [`Probe::subscribe_alarms`](crate::Probe::subscribe_alarms). With the
+//! `persistence` feature, a `persistence::TimerStore` keeps timers across
+//! restarts.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use chrono::NaiveTime;
+//! use combustion_rust_ble::timers::next_local_time;
+//!
+//! # fn example(probe: &combustion_rust_ble::Probe) {
+//! probe.schedule_timer_after("Baste", Duration::from_secs(45 * 60));
+//! probe.schedule_timer_at("Wrap", next_local_time(NaiveTime::from_hms_opt(6, 30, 0).unwrap()));
+//! # }
+//! ```
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone, Utc};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tokio::sync::broadcast;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::command::AlarmBound;
+use crate::data::AlarmSensor;
+#[cfg(feature = "persistence")]
+use crate::persistence::TimerStore;
+use crate::runtime::TaskHandle;
+
+/// A time-based alarm for a probe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimerAlarm {
+    /// Unique ID of the timer.
+    pub id: Uuid,
+    /// Serial number of the probe the timer belongs to.
+    pub serial_number: u32,
+    /// What the timer is for, e.g. "Wrap".
+    pub label: String,
+    /// When the timer fires.
+    pub fires_at: DateTime<Utc>,
+    /// Cook session the timer belongs to, if the probe reported one.
+    pub session_id: Option<u32>,
+    /// When the timer was scheduled.
+    pub created_at: DateTime<Utc>,
+}
+
+impl TimerAlarm {
+    /// Create a timer firing at `fires_at`.
+    pub(crate) fn new(
+        serial_number: u32,
+        label: String,
+        fires_at: DateTime<Utc>,
+        session_id: Option<u32>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            serial_number,
+            label,
+            fires_at,
+            session_id,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Get the time left until the timer fires, zero if it is due.
+    pub fn remaining(&self, now: DateTime<Utc>) -> std::time::Duration {
+        (self.fires_at - now).to_std().unwrap_or_default()
+    }
+}
+
+/// An alarm raised for a probe.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlarmEvent {
+    /// A temperature alarm on the probe started sounding.
+    Temperature {
+        /// Probe serial number.
+        serial_number: u32,
+        /// Sensor the alarm watches.
+        sensor: AlarmSensor,
+        /// Whether the alarm is a high or low alarm.
+        bound: AlarmBound,
+        /// Alarm threshold in Celsius.
+        threshold: f64,
+        /// When the alarm was reported.
+        at: DateTime<Utc>,
+    },
+    /// A timer fired.
+    Timer {
+        /// The timer.
+        timer: TimerAlarm,
+        /// When it fired, later than scheduled if it was due while the
+        /// application was not running.
+        fired_at: DateTime<Utc>,
+    },
+}
+
+impl AlarmEvent {
+    /// Get the serial number of the probe the alarm is for.
+    pub fn serial_number(&self) -> u32 {
+        match self {
+            Self::Temperature { serial_number, .. } => *serial_number,
+            Self::Timer { timer, .. } => timer.serial_number,
+        }
+    }
+}
+
+/// Get the next time the local clock shows `time`, for timers like "at 6:30
+/// AM".
+///
+/// Today if that time is still ahead, otherwise tomorrow. Timers scheduled
+/// for it fire by the wall clock, within 30 seconds, even if the host slept
+/// or its clock was set in the meantime.
+pub fn next_local_time(time: NaiveTime) -> DateTime<Utc> {
+    next_occurrence(Local::now(), time)
+}
+
+/// Get the first time after `now` that a clock in its time zone shows `time`.
+fn next_occurrence<Tz: TimeZone>(now: DateTime<Tz>, time: NaiveTime) -> DateTime<Utc> {
+    let zone = now.timezone();
+    let mut date = now.date_naive();
+    loop {
+        // Times skipped by a daylight saving change don't occur that day
+        if let Some(at) = zone.from_local_datetime(&date.and_time(time)).earliest() {
+            if at > now {
+                return at.with_timezone(&Utc);
+            }
+        }
+        date += ChronoDuration::days(1);
+    }
+}
+
+/// How often a waiting timer checks the wall clock for its due time.
+pub(crate) const TIMER_RECHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A scheduled timer and the task waiting for it.
+struct Scheduled {
+    /// The timer.
+    timer: TimerAlarm,
+    /// Sleeps until the timer is due, then fires it.
+    task: TaskHandle<()>,
+}
+
+/// Timers scheduled for one probe.
+pub(crate) struct TimerSchedule {
+    /// Probe serial number.
+    serial_number: u32,
+    /// Channel the probe publishes alarms on.
+    alarm_tx: broadcast::Sender<AlarmEvent>,
+    /// Get the probe's current session ID.
+    session: Box<dyn Fn() -> Option<u32> + Send + Sync>,
+    /// Scheduled timers by ID.
+    timers: Mutex<HashMap<Uuid, Scheduled>>,
+    /// Store keeping timers across restarts, if any.
+    #[cfg(feature = "persistence")]
+    store: Mutex<Option<Arc<TimerStore>>>,
+}
+
+impl TimerSchedule {
+    /// Create an empty schedule.
+    pub(crate) fn new(
+        serial_number: u32,
+        alarm_tx: broadcast::Sender<AlarmEvent>,
+        session: impl Fn() -> Option<u32> + Send + Sync + 'static,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            serial_number,
+            alarm_tx,
+            session: Box::new(session),
+            timers: Mutex::new(HashMap::new()),
+            #[cfg(feature = "persistence")]
+            store: Mutex::new(None),
+        })
+    }
+
+    /// Schedule a timer firing at `fires_at` for the current session.
+    pub(crate) fn schedule_at(
+        self: &Arc<Self>,
+        label: String,
+        fires_at: DateTime<Utc>,
+    ) -> TimerAlarm {
+        let timer = TimerAlarm::new(self.serial_number, label, fires_at, (self.session)());
+        #[cfg(feature = "persistence")]
+        self.persist(|store| store.save(&timer));
+        self.schedule(timer.clone());
+        timer
+    }
+
+    /// Start waiting for a timer, replacing any with the same ID.
+    fn schedule(self: &Arc<Self>, timer: TimerAlarm) {
+        let schedule = Arc::downgrade(self);
+        let id = timer.id;
+        let fires_at = timer.fires_at;
+        let deadline = tokio::time::Instant::now() + timer.remaining(Utc::now());
+        // Held until the timer is listed, in case it is already due
+        let mut timers = self.timers.lock();
+        let task = crate::runtime::spawn(async move {
+            // The monotonic clock may stop while the host sleeps, and the
+            // wall clock may be set, so check the due time on each wake
+            loop {
+                let now = tokio::time::Instant::now();
+                if now >= deadline || fires_at <= Utc::now() {
+                    break;
+                }
+                tokio::time::sleep((deadline - now).min(TIMER_RECHECK_INTERVAL)).await;
+            }
+            if let Some(schedule) = Weak::upgrade(&schedule) {
+                schedule.fire(id);
+            }
+        });
+        if let Some(previous) = timers.insert(id, Scheduled { timer, task }) {
+            previous.task.abort();
+        }
+    }
+
+    /// Fire a due timer, unless the probe has moved on to another session.
+    fn fire(&self, id: Uuid) {
+        let Some(Scheduled { timer, .. }) = self.timers.lock().remove(&id) else {
+            return;
+        };
+        #[cfg(feature = "persistence")]
+        self.persist(|store| store.remove(id).map(|_| ()));
+
+        let current = (self.session)();
+        if timer.session_id.is_some() && current.is_some() && timer.session_id != current {
+            debug!(
+                "Dropping timer {:?} for {:08X}: its cook session has ended",
+                timer.label, self.serial_number
+            );
+            return;
+        }
+        let _ = self.alarm_tx.send(AlarmEvent::Timer {
+            timer,
+            fired_at: Utc::now(),
+        });
+    }
+
+    /// Cancel a timer, returning whether it was scheduled.
+    pub(crate) fn cancel(&self, id: Uuid) -> bool {
+        let Some(scheduled) = self.timers.lock().remove(&id) else {
+            return false;
+        };
+        scheduled.task.abort();
+        #[cfg(feature = "persistence")]
+        self.persist(|store| store.remove(id).map(|_| ()));
+        true
+    }
+
+    /// List the scheduled timers, soonest first.
+    pub(crate) fn list(&self) -> Vec<TimerAlarm> {
+        let mut timers: Vec<_> = self
+            .timers
+            .lock()
+            .values()
+            .map(|scheduled| scheduled.timer.clone())
+            .collect();
+        timers.sort_by_key(|timer| timer.fires_at);
+        timers
+    }
+
+    /// Keep timers in `store`, scheduling those it holds for this probe.
+    ///
+    /// Timers that came due while the application was not running fire
+    /// straight away.
+    #[cfg(feature = "persistence")]
+    pub(crate) fn set_store(self: &Arc<Self>, store: Arc<TimerStore>) {
+        for timer in store.list_for(self.serial_number) {
+            if !self.timers.lock().contains_key(&timer.id) {
+                self.schedule(timer);
+            }
+        }
+        for timer in self.list() {
+            if let Err(e) = store.save(&timer) {
+                tracing::warn!("Failed to store timer {:?}: {}", timer.label, e);
+            }
+        }
+        *self.store.lock() = Some(store);
+    }
+
+    /// Update the store, if any, logging failures.
+    #[cfg(feature = "persistence")]
+    fn persist(&self, update: impl FnOnce(&TimerStore) -> crate::error::Result<()>) {
+        let store = self.store.lock().clone();
+        if let Some(store) = store {
+            if let Err(e) = update(&store) {
+                tracing::warn!(
+                    "Failed to update stored timers for {:08X}: {}",
+                    self.serial_number,
+                    e
+                );
+            }
+        }
+    }
+}
+
+impl Drop for TimerSchedule {
+    fn drop(&mut self) {
+        for scheduled in self.timers.get_mut().values() {
+            scheduled.task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_next_occurrence() {
+        let zone = FixedOffset::east_opt(2 * 3600).unwrap();
+        let now = zone.with_ymd_and_hms(2024, 3, 9, 5, 0, 0).unwrap();
+        let wrap = NaiveTime::from_hms_opt(6, 30, 0).unwrap();
+
+        // Later today, in UTC
+        let at = next_occurrence(now, wrap);
+        assert_eq!(at, Utc.with_ymd_and_hms(2024, 3, 9, 4, 30, 0).unwrap());
+
+        // Already past, so tomorrow
+        let now = zone.with_ymd_and_hms(2024, 3, 9, 6, 30, 0).unwrap();
+        let at = next_occurrence(now, wrap);
+        assert_eq!(at, Utc.with_ymd_and_hms(2024, 3, 10, 4, 30, 0).unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timer_schedule() {
+        let (alarm_tx, mut rx) = broadcast::channel(16);
+        let session = Arc::new(std::sync::atomic::AtomicU32::new(7));
+        let current = session.clone();
+        let schedule = TimerSchedule::new(0x1000_0001, alarm_tx, move || {
+            Some(current.load(std::sync::atomic::Ordering::SeqCst))
+        });
+
+        let soon = schedule.schedule_at("Baste".into(), Utc::now() + ChronoDuration::seconds(60));
+        let later = schedule.schedule_at("Wrap".into(), Utc::now() + ChronoDuration::seconds(30));
+        assert_eq!(soon.session_id, Some(7));
+        assert_eq!(schedule.list(), vec![later.clone(), soon.clone()]);
+
+        assert!(schedule.cancel(later.id));
+        assert!(!schedule.cancel(later.id));
+
+        tokio::time::sleep(std::time::Duration::from_secs(61)).await;
+        match rx.try_recv().unwrap() {
+            AlarmEvent::Timer { timer, .. } => assert_eq!(timer, soon),
+            other => panic!("unexpected alarm {:?}", other),
+        }
+        assert!(schedule.list().is_empty());
+
+        // A new cook session drops the timer
+        schedule.schedule_at("Rest".into(), Utc::now() + ChronoDuration::seconds(10));
+        session.store(8, std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_secs(11)).await;
+        assert!(rx.try_recv().is_err());
+        assert!(schedule.list().is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+//! Non-BLE probe transports.
+//!
+//! A [`ProbeTransport`] lets a [`Probe`](crate::Probe) be driven by something
+//! other than a local Bluetooth peripheral, such as a network gateway. State
+//! updates are fed into the probe separately (e.g. via
+//! [`Probe::ingest_status_bytes`](crate::Probe::ingest_status_bytes)); the
+//! transport carries connection control and outgoing UART commands, and may
+//! deliver the UART responses.
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use crate::audit::ConfigCommand;
+use crate::ble::connection::ConnectionState;
+use crate::error::{Error, Result};
+
+/// Connection control and command channel for a probe.
+#[async_trait]
+pub trait ProbeTransport: Send + Sync {
+    /// Get the current connection state.
+    fn connection_state(&self) -> ConnectionState;
+
+    /// Check if the transport is maintaining (auto-reconnecting) the connection.
+    fn is_maintaining_connection(&self) -> bool {
+        false
+    }
+
+    /// Get the probe's firmware revision string, if the transport knows it.
+    ///
+    /// Read when the probe connects to derive its
+    /// [`ProbeCapabilities`](crate::ProbeCapabilities).
+    fn firmware_revision(&self) -> Option<String> {
+        None
+    }
+
+    /// Check if the probe has the Probe Status characteristic.
+    ///
+    /// Read when the probe connects. Transports relaying status updates
+    /// should keep the default.
+    fn has_status_characteristic(&self) -> bool {
+        true
+    }
+
+    /// Subscribe to the probe's UART notifications, as raw bytes.
+    ///
+    /// Called when the probe connects; what arrives is handled as UART
+    /// notifications over BLE are, so command responses and Read Session
+    /// Information and Read Logs responses reach the probe. The default
+    /// returns `None`, for transports that feed the bytes in with
+    /// [`Probe::ingest_uart_bytes`](crate::Probe::ingest_uart_bytes) or
+    /// don't carry them.
+    fn subscribe_uart(&self) -> Option<broadcast::Receiver<Vec<u8>>> {
+        None
+    }
+
+    /// Read the signal strength over the active connection.
+    ///
+    /// The default returns [`Error::NotSupported`].
+    async fn read_rssi(&self) -> Result<i16> {
+        Err(Error::NotSupported {
+            operation: "reading RSSI over this transport".to_string(),
+        })
+    }
+
+    /// Connect to the probe.
+    async fn connect(&self) -> Result<()>;
+
+    /// Disconnect from the probe.
+    async fn disconnect(&self) -> Result<()>;
+
+    /// Write an encoded UART request to the probe.
+    async fn write_uart(&self, data: &[u8]) -> Result<()>;
+
+    /// Send a configuration command, given with its encoded UART request.
+    ///
+    /// Transports that forward typed commands rather than raw UART bytes,
+    /// such as a gateway client, override this. The default writes `data`.
+    async fn send_config(&self, command: &ConfigCommand, data: &[u8]) -> Result<()> {
+        let _ = command;
+        self.write_uart(data).await
+    }
+}
@@ -1,5 +1,7 @@
 //! Utility functions for the combustion-rust-ble crate.
 
+use crate::error::{Error, Result};
+
 /// Convert Celsius to Fahrenheit.
 ///
 /// # Arguments
@@ -46,6 +48,341 @@ pub fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
     (fahrenheit - 32.0) * 5.0 / 9.0
 }
 
+/// Temperature display unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TemperatureUnit {
+    /// Degrees Celsius.
+    #[default]
+    Celsius,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Get the unit symbol (e.g. "°C").
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    /// Get the unit name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "Celsius",
+            TemperatureUnit::Fahrenheit => "Fahrenheit",
+        }
+    }
+
+    /// Get the other unit.
+    pub fn toggled(&self) -> Self {
+        match self {
+            TemperatureUnit::Celsius => TemperatureUnit::Fahrenheit,
+            TemperatureUnit::Fahrenheit => TemperatureUnit::Celsius,
+        }
+    }
+
+    /// Get the unit conventionally used in a locale's region.
+    ///
+    /// Accepts the same tags as [`TemperatureFormat::for_locale`]. Regions
+    /// using Fahrenheit, such as `"en-US"`, give Fahrenheit; anything else,
+    /// including tags without a region, gives Celsius.
+    pub fn for_locale(locale: &str) -> Self {
+        let region = locale
+            .split(['-', '_', '.'])
+            .skip(1)
+            .find(|subtag| subtag.len() == 2)
+            .unwrap_or("")
+            .to_ascii_uppercase();
+
+        match region.as_str() {
+            "US" | "LR" | "BS" | "BZ" | "KY" | "PW" | "FM" | "MH" => TemperatureUnit::Fahrenheit,
+            _ => TemperatureUnit::Celsius,
+        }
+    }
+
+    /// Convert a Celsius value into this unit.
+    pub fn from_celsius(&self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius_to_fahrenheit(celsius),
+        }
+    }
+
+    /// Convert a value in this unit into Celsius.
+    pub fn to_celsius(&self, value: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => value,
+            TemperatureUnit::Fahrenheit => fahrenheit_to_celsius(value),
+        }
+    }
+}
+
+/// A temperature that carries its unit, for APIs that would otherwise take
+/// a bare Celsius `f64`.
+///
+/// # Example
+///
+/// ```
+/// use combustion_rust_ble::Temperature;
+///
+/// let done = Temperature::fahrenheit(203.0);
+/// assert!((done.as_celsius() - 95.0).abs() < 0.001);
+/// assert_eq!(done.to_string(), "95.0°C");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Temperature {
+    /// Degrees Celsius.
+    celsius: f64,
+}
+
+impl Temperature {
+    /// Create a temperature from a value in `unit`.
+    pub fn new(value: f64, unit: TemperatureUnit) -> Self {
+        Self::celsius(unit.to_celsius(value))
+    }
+
+    /// Create a temperature in degrees Celsius.
+    pub const fn celsius(celsius: f64) -> Self {
+        Self { celsius }
+    }
+
+    /// Create a temperature in degrees Fahrenheit.
+    pub fn fahrenheit(fahrenheit: f64) -> Self {
+        Self::celsius(fahrenheit_to_celsius(fahrenheit))
+    }
+
+    /// Get the temperature in degrees Celsius.
+    pub fn as_celsius(&self) -> f64 {
+        self.celsius
+    }
+
+    /// Get the temperature in degrees Fahrenheit.
+    pub fn as_fahrenheit(&self) -> f64 {
+        celsius_to_fahrenheit(self.celsius)
+    }
+
+    /// Get the temperature in `unit`.
+    pub fn in_unit(&self, unit: TemperatureUnit) -> f64 {
+        unit.from_celsius(self.celsius)
+    }
+}
+
+impl std::fmt::Display for Temperature {
+    /// Format in Celsius with one decimal place, e.g. `95.0°C`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format_temperature(self.celsius, TemperatureUnit::Celsius))
+    }
+}
+
+impl std::str::FromStr for Temperature {
+    type Err = Error;
+
+    /// Parse as [`parse_temperature`] does, reading values without a unit
+    /// as Celsius.
+    fn from_str(s: &str) -> Result<Self> {
+        parse_temperature(s, TemperatureUnit::Celsius).map(Self::celsius)
+    }
+}
+
+/// Options for rendering temperatures as text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureFormat {
+    /// Unit to render in.
+    pub unit: TemperatureUnit,
+    /// Number of digits after the decimal separator.
+    pub precision: usize,
+    /// Decimal separator character ('.' or ',').
+    pub decimal_separator: char,
+    /// Whether to append the unit symbol.
+    pub show_unit: bool,
+    /// Whether to follow the value with the other unit in parentheses.
+    pub dual: bool,
+}
+
+impl TemperatureFormat {
+    /// Create a format for the given unit with one decimal place and a '.' separator.
+    pub fn new(unit: TemperatureUnit) -> Self {
+        Self {
+            unit,
+            precision: 1,
+            decimal_separator: '.',
+            show_unit: true,
+            dual: false,
+        }
+    }
+
+    /// Create a format using the decimal separator conventional for a locale.
+    ///
+    /// Accepts BCP 47 / POSIX style tags such as `"en-US"`, `"de_DE"` or `"fr"`.
+    /// Unknown locales fall back to '.'.
+    pub fn for_locale(unit: TemperatureUnit, locale: &str) -> Self {
+        Self::new(unit).with_decimal_separator(decimal_separator_for_locale(locale))
+    }
+
+    /// Create a format for a locale, in the unit its region uses (see
+    /// [`TemperatureUnit::for_locale`]).
+    pub fn localized(locale: &str) -> Self {
+        Self::for_locale(TemperatureUnit::for_locale(locale), locale)
+    }
+
+    /// Set the number of decimal places.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Set the decimal separator.
+    pub fn with_decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Set whether the unit symbol is appended.
+    pub fn with_unit_symbol(mut self, show_unit: bool) -> Self {
+        self.show_unit = show_unit;
+        self
+    }
+
+    /// Set whether the other unit follows in parentheses, e.g.
+    /// `165.0°F (73.9°C)`.
+    ///
+    /// Both values carry their unit symbol, whatever
+    /// [`show_unit`](Self::show_unit) says.
+    pub fn with_dual(mut self, dual: bool) -> Self {
+        self.dual = dual;
+        self
+    }
+
+    /// Render a Celsius temperature.
+    pub fn format(&self, celsius: f64) -> String {
+        if self.dual {
+            let single = Self {
+                dual: false,
+                show_unit: true,
+                ..*self
+            };
+            let other = Self {
+                unit: self.unit.toggled(),
+                ..single
+            };
+            return format!("{} ({})", single.format(celsius), other.format(celsius));
+        }
+        let value = self.unit.from_celsius(celsius);
+        let mut text = format!("{:.*}", self.precision, value);
+        if self.decimal_separator != '.' {
+            text = text.replace('.', &self.decimal_separator.to_string());
+        }
+        if self.show_unit {
+            text.push_str(self.unit.symbol());
+        }
+        text
+    }
+}
+
+impl Default for TemperatureFormat {
+    fn default() -> Self {
+        Self::new(TemperatureUnit::Celsius)
+    }
+}
+
+/// Get the decimal separator conventionally used by a locale.
+fn decimal_separator_for_locale(locale: &str) -> char {
+    let language = locale
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    match language.as_str() {
+        "de" | "fr" | "es" | "it" | "pt" | "nl" | "sv" | "da" | "nb" | "nn" | "no" | "fi"
+        | "pl" | "cs" | "sk" | "ru" | "uk" | "tr" | "el" | "hu" | "ro" | "id" => ',',
+        _ => '.',
+    }
+}
+
+/// Format a Celsius temperature in the given unit with one decimal place.
+///
+/// # Example
+///
+/// ```
+/// use combustion_rust_ble::{format_temperature, TemperatureUnit};
+///
+/// assert_eq!(format_temperature(100.0, TemperatureUnit::Fahrenheit), "212.0°F");
+/// ```
+pub fn format_temperature(celsius: f64, unit: TemperatureUnit) -> String {
+    TemperatureFormat::new(unit).format(celsius)
+}
+
+/// Format a Celsius temperature in the given unit, followed by the other unit in parentheses.
+///
+/// # Example
+///
+/// ```
+/// use combustion_rust_ble::{format_temperature_dual, TemperatureUnit};
+///
+/// assert_eq!(format_temperature_dual(100.0, TemperatureUnit::Celsius), "100.0°C (212.0°F)");
+/// ```
+pub fn format_temperature_dual(celsius: f64, unit: TemperatureUnit) -> String {
+    format!(
+        "{} ({})",
+        format_temperature(celsius, unit),
+        format_temperature(celsius, unit.toggled())
+    )
+}
+
+/// Parse a user-entered temperature string into Celsius.
+///
+/// Accepts either '.' or ',' as the decimal separator and an optional unit
+/// suffix (`C`, `°C`, `F`, `°F`, case-insensitive, with or without spaces).
+/// Values without a unit are interpreted in `default_unit`.
+///
+/// # Example
+///
+/// ```
+/// use combustion_rust_ble::{parse_temperature, TemperatureUnit};
+///
+/// let c = parse_temperature("165 °F", TemperatureUnit::Celsius).unwrap();
+/// assert!((c - 73.89).abs() < 0.01);
+///
+/// let c = parse_temperature("74,5C", TemperatureUnit::Fahrenheit).unwrap();
+/// assert!((c - 74.5).abs() < 0.001);
+/// ```
+pub fn parse_temperature(input: &str, default_unit: TemperatureUnit) -> Result<f64> {
+    let invalid = || Error::InvalidParameter {
+        name: "temperature".to_string(),
+        value: input.to_string(),
+    };
+
+    let trimmed = input.trim();
+    let lower = trimmed.to_ascii_lowercase();
+
+    let (number, unit) = if let Some(rest) = lower.strip_suffix('c') {
+        (rest, TemperatureUnit::Celsius)
+    } else if let Some(rest) = lower.strip_suffix('f') {
+        (rest, TemperatureUnit::Fahrenheit)
+    } else {
+        (lower.as_str(), default_unit)
+    };
+
+    let number = number.trim_end();
+    let number = number.strip_suffix('°').unwrap_or(number).trim_end();
+
+    if number.is_empty() {
+        return Err(invalid());
+    }
+
+    let value: f64 = number.replace(',', ".").parse().map_err(|_| invalid())?;
+    if !value.is_finite() {
+        return Err(invalid());
+    }
+
+    Ok(unit.to_celsius(value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,4 +408,90 @@ mod tests {
         let converted = fahrenheit_to_celsius(celsius_to_fahrenheit(original));
         assert!((converted - original).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_temperature_format() {
+        assert_eq!(
+            format_temperature(74.54, TemperatureUnit::Celsius),
+            "74.5°C"
+        );
+        assert_eq!(
+            TemperatureFormat::for_locale(TemperatureUnit::Celsius, "de-DE").format(74.5),
+            "74,5°C"
+        );
+        assert_eq!(
+            TemperatureFormat::for_locale(TemperatureUnit::Fahrenheit, "en_US.UTF-8")
+                .with_precision(0)
+                .format(100.0),
+            "212°F"
+        );
+        assert_eq!(
+            TemperatureFormat::new(TemperatureUnit::Celsius)
+                .with_precision(2)
+                .with_unit_symbol(false)
+                .format(-1.0),
+            "-1.00"
+        );
+    }
+
+    #[test]
+    fn test_temperature_format_localized() {
+        assert_eq!(
+            TemperatureUnit::for_locale("en-US"),
+            TemperatureUnit::Fahrenheit
+        );
+        assert_eq!(
+            TemperatureUnit::for_locale("es_us.UTF-8"),
+            TemperatureUnit::Fahrenheit
+        );
+        assert_eq!(
+            TemperatureUnit::for_locale("zh-Hant-TW"),
+            TemperatureUnit::Celsius
+        );
+        assert_eq!(TemperatureUnit::for_locale("en"), TemperatureUnit::Celsius);
+
+        let us = TemperatureFormat::localized("en-US").with_dual(true);
+        assert_eq!(us.format(73.9), "165.0°F (73.9°C)");
+        assert_eq!(
+            TemperatureFormat::localized("de-DE")
+                .with_dual(true)
+                .with_unit_symbol(false)
+                .format(73.9),
+            "73,9°C (165,0°F)"
+        );
+    }
+
+    #[test]
+    fn test_temperature_units() {
+        let t = Temperature::fahrenheit(212.0);
+        assert!((t.as_celsius() - 100.0).abs() < 0.001);
+        assert!((t.in_unit(TemperatureUnit::Fahrenheit) - 212.0).abs() < 0.001);
+        assert_eq!(
+            Temperature::new(100.0, TemperatureUnit::Celsius),
+            Temperature::celsius(100.0)
+        );
+        assert!(Temperature::celsius(60.0) < Temperature::fahrenheit(145.0));
+        assert_eq!(
+            "63C".parse::<Temperature>().unwrap(),
+            Temperature::celsius(63.0)
+        );
+        assert!("hot".parse::<Temperature>().is_err());
+    }
+
+    #[test]
+    fn test_parse_temperature() {
+        let c = TemperatureUnit::Celsius;
+        let f = TemperatureUnit::Fahrenheit;
+
+        assert!((parse_temperature("165 °F", c).unwrap() - 73.888).abs() < 0.01);
+        assert!((parse_temperature("74,5C", f).unwrap() - 74.5).abs() < 0.001);
+        assert!((parse_temperature(" 63.5 ", c).unwrap() - 63.5).abs() < 0.001);
+        assert!((parse_temperature("212", f).unwrap() - 100.0).abs() < 0.001);
+        assert!((parse_temperature("-4°f", c).unwrap() - (-20.0)).abs() < 0.001);
+
+        assert!(parse_temperature("", c).is_err());
+        assert!(parse_temperature("°C", c).is_err());
+        assert!(parse_temperature("hot", c).is_err());
+        assert!(parse_temperature("12K", c).is_err());
+    }
 }
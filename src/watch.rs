@@ -0,0 +1,270 @@
+//! Temperature threshold watchers.
+//!
+//! A [`ThresholdWatch`] waits for a virtual temperature to cross a threshold,
+//! optionally requiring the condition to hold for a sustain duration before
+//! firing. Created with [`Probe::watch_threshold`](crate::Probe::watch_threshold)
+//! and awaited directly:
+//!
+//! ```rust,no_run
+//! use combustion_rust_ble::{Probe, VirtualSensor};
+//! use std::time::Duration;
+//!
+//! # async fn example(probe: &Probe) -> combustion_rust_ble::Result<()> {
+//! let core = probe
+//!     .watch_threshold(VirtualSensor::Core, 60.0)
+//!     .sustained_for(Duration::from_secs(30))
+//!     .await?;
+//! println!("Core reached {:.1}°C", core);
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::stream::Stream;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+use crate::data::VirtualSensor;
+use crate::error::{Error, Result};
+use crate::probe::TemperatureUpdate;
+
+/// Condition checked by a [`ThresholdWatch`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThresholdCondition {
+    /// Temperature at or above the threshold (Celsius).
+    Above(f64),
+    /// Temperature below the threshold (Celsius).
+    Below(f64),
+}
+
+impl ThresholdCondition {
+    /// Check if a temperature satisfies the condition.
+    pub fn is_met(&self, celsius: f64) -> bool {
+        match *self {
+            Self::Above(threshold) => celsius >= threshold,
+            Self::Below(threshold) => celsius < threshold,
+        }
+    }
+}
+
+/// Watches a probe's virtual temperature for a threshold condition.
+///
+/// Await the watch to wait for the first time the condition holds, or
+/// convert it with [`into_stream`](Self::into_stream) to be notified each time
+/// the condition becomes true again after having been false.
+pub struct ThresholdWatch {
+    /// Sensor being watched.
+    sensor: VirtualSensor,
+    /// Condition to wait for.
+    condition: ThresholdCondition,
+    /// How long the condition must hold before firing.
+    sustain: Duration,
+    /// Temperature updates from the probe.
+    rx: broadcast::Receiver<TemperatureUpdate>,
+    /// Latest temperature of the watched sensor.
+    value: Option<f64>,
+    /// Whether the condition must become false before firing again.
+    armed: bool,
+    /// When the condition started holding.
+    since: Option<Instant>,
+}
+
+impl ThresholdWatch {
+    /// Create a watch starting from the sensor's current value.
+    pub(crate) fn new(
+        sensor: VirtualSensor,
+        condition: ThresholdCondition,
+        rx: broadcast::Receiver<TemperatureUpdate>,
+        value: Option<f64>,
+    ) -> Self {
+        Self {
+            sensor,
+            condition,
+            sustain: Duration::ZERO,
+            rx,
+            value,
+            armed: true,
+            since: None,
+        }
+    }
+
+    /// Require the condition to hold continuously for `duration` before firing.
+    pub fn sustained_for(mut self, duration: Duration) -> Self {
+        self.sustain = duration;
+        self
+    }
+
+    /// Get the watched sensor.
+    pub fn sensor(&self) -> VirtualSensor {
+        self.sensor
+    }
+
+    /// Get the watched condition.
+    pub fn condition(&self) -> ThresholdCondition {
+        self.condition
+    }
+
+    /// Wait for the condition to become true.
+    ///
+    /// # Returns
+    ///
+    /// The sensor temperature (Celsius) at the time the watch fired.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConnectionLost`] if the probe is dropped.
+    pub async fn wait(&mut self) -> Result<f64> {
+        loop {
+            let met = self.value.filter(|&v| self.condition.is_met(v));
+
+            match met {
+                None => {
+                    self.armed = true;
+                    self.since = None;
+                }
+                Some(value) if self.armed => {
+                    let deadline = *self.since.get_or_insert_with(Instant::now) + self.sustain;
+                    if Instant::now() >= deadline {
+                        self.armed = false;
+                        self.since = None;
+                        return Ok(value);
+                    }
+
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(deadline) => {}
+                        update = self.rx.recv() => self.handle_update(update)?,
+                    }
+                    continue;
+                }
+                Some(_) => {}
+            }
+
+            let update = self.rx.recv().await;
+            self.handle_update(update)?;
+        }
+    }
+
+    /// Convert the watch into a stream that yields each time the condition
+    /// becomes true.
+    pub fn into_stream(self) -> impl Stream<Item = Result<f64>> + Send {
+        futures::stream::unfold(Some(self), |watch| async move {
+            let mut watch = watch?;
+            match watch.wait().await {
+                Ok(value) => Some((Ok(value), Some(watch))),
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+
+    /// Record a temperature update.
+    fn handle_update(
+        &mut self,
+        update: std::result::Result<TemperatureUpdate, broadcast::error::RecvError>,
+    ) -> Result<()> {
+        match update {
+            Ok(update) => {
+                self.value = update.virtual_temperatures.get(self.sensor);
+                Ok(())
+            }
+            // Missed updates don't matter; the next one carries the latest value
+            Err(broadcast::error::RecvError::Lagged(_)) => Ok(()),
+            Err(broadcast::error::RecvError::Closed) => Err(Error::ConnectionLost),
+        }
+    }
+}
+
+impl IntoFuture for ThresholdWatch {
+    type Output = Result<f64>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Result<f64>> + Send>>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        Box::pin(async move { self.wait().await })
+    }
+}
+
+impl std::fmt::Debug for ThresholdWatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThresholdWatch")
+            .field("sensor", &self.sensor)
+            .field("condition", &self.condition)
+            .field("sustain", &self.sustain)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ProbeTemperatures, VirtualTemperatures};
+    use futures::StreamExt;
+
+    fn update(core: f64) -> TemperatureUpdate {
+        TemperatureUpdate {
+            temperatures: ProbeTemperatures::new(),
+            virtual_temperatures: VirtualTemperatures::new(Some(core), None, None),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fires_immediately_when_already_met() {
+        let (_tx, rx) = broadcast::channel(4);
+        let watch = ThresholdWatch::new(
+            VirtualSensor::Core,
+            ThresholdCondition::Above(60.0),
+            rx,
+            Some(61.0),
+        );
+        assert_eq!(watch.await.unwrap(), 61.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sustain_resets_when_condition_breaks() {
+        let (tx, rx) = broadcast::channel(4);
+        let mut watch = ThresholdWatch::new(
+            VirtualSensor::Core,
+            ThresholdCondition::Above(60.0),
+            rx,
+            Some(50.0),
+        )
+        .sustained_for(Duration::from_secs(10));
+
+        let task = tokio::spawn(async move { (watch.wait().await, Instant::now()) });
+        let start = Instant::now();
+
+        tx.send(update(61.0)).unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        tx.send(update(59.0)).unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        tx.send(update(62.0)).unwrap();
+
+        let (value, fired_at) = task.await.unwrap();
+        assert_eq!(value.unwrap(), 62.0);
+        assert!(fired_at - start >= Duration::from_secs(16));
+    }
+
+    #[tokio::test]
+    async fn test_stream_rearms_after_condition_clears() {
+        let (tx, rx) = broadcast::channel(8);
+        let watch = ThresholdWatch::new(
+            VirtualSensor::Core,
+            ThresholdCondition::Below(5.0),
+            rx,
+            None,
+        );
+
+        for core in [4.0, 3.0, 6.0, 4.5] {
+            tx.send(update(core)).unwrap();
+        }
+        drop(tx);
+
+        let results: Vec<_> = watch.into_stream().collect().await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(*results[0].as_ref().unwrap(), 4.0);
+        assert_eq!(*results[1].as_ref().unwrap(), 4.5);
+        assert!(matches!(results[2], Err(Error::ConnectionLost)));
+    }
+}